@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The gRPC stubs are only needed (and protoc only required) with the `grpc` feature.
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/engine.proto");
+        tonic_build::compile_protos("proto/engine.proto")?;
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
 /// Query parameters for Gamma API market endpoints
 #[derive(Debug, Clone, Default)]
 pub struct GammaMarketParams {
@@ -9,6 +11,8 @@ pub struct GammaMarketParams {
     pub tag_id: Option<String>,
     pub order: Option<String>,
     pub ascending: Option<bool>,
+    pub end_date_min: Option<DateTime<Utc>>,
+    pub end_date_max: Option<DateTime<Utc>>,
 }
 
 impl GammaMarketParams {
@@ -60,6 +64,18 @@ impl GammaMarketParams {
         self
     }
 
+    /// Only markets ending at or after this time
+    pub fn with_end_date_min(mut self, end_date_min: DateTime<Utc>) -> Self {
+        self.end_date_min = Some(end_date_min);
+        self
+    }
+
+    /// Only markets ending at or before this time
+    pub fn with_end_date_max(mut self, end_date_max: DateTime<Utc>) -> Self {
+        self.end_date_max = Some(end_date_max);
+        self
+    }
+
     /// Convert parameters to query string
     pub fn to_query_string(&self) -> String {
         let mut params = Vec::new();
@@ -88,6 +104,18 @@ impl GammaMarketParams {
         if let Some(ascending) = self.ascending {
             params.push(format!("ascending={}", ascending));
         }
+        if let Some(end_date_min) = self.end_date_min {
+            params.push(format!(
+                "end_date_min={}",
+                end_date_min.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ));
+        }
+        if let Some(end_date_max) = self.end_date_max {
+            params.push(format!(
+                "end_date_max={}",
+                end_date_max.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ));
+        }
 
         if params.is_empty() {
             String::new()
@@ -151,4 +179,18 @@ mod tests {
         assert!(query.contains("closed=false"));
         assert!(query.contains("tag_id=politics"));
     }
+
+    #[test]
+    fn test_end_date_range() {
+        let min = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let params = GammaMarketParams::new()
+            .with_end_date_min(min)
+            .with_end_date_max(min + chrono::Duration::days(30));
+
+        let query = params.to_query_string();
+        assert!(query.contains("end_date_min=2026-01-01T00:00:00Z"));
+        assert!(query.contains("end_date_max=2026-01-31T00:00:00Z"));
+    }
 }
@@ -36,6 +36,13 @@ pub struct GammaMarket {
     pub liquidity_num: Option<f64>,
     pub volume24hr: Option<f64>,
 
+    // Dates
+    #[serde(
+        default,
+        deserialize_with = "super::serde_helpers::deserialize_optional_datetime"
+    )]
+    pub end_date: Option<DateTime<Utc>>,
+
     // Price data
     pub last_trade_price: Option<f64>,
     pub best_bid: Option<f64>,
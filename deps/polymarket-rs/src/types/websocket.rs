@@ -261,6 +261,15 @@ pub struct MarketSubscription {
     pub assets_ids: Vec<String>,
 }
 
+/// Change to the assets of an open market websocket
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MarketSubscriptionUpdate {
+    /// List of asset/token IDs to add or remove
+    pub assets_ids: Vec<String>,
+    /// "subscribe" or "unsubscribe"
+    pub operation: String,
+}
+
 /// Authentication message for user websocket
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UserAuthentication {
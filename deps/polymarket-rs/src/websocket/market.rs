@@ -1,20 +1,22 @@
+use futures_util::stream::SplitSink;
 use futures_util::{SinkExt, Stream, StreamExt};
 use std::pin::Pin;
 use std::sync::Arc;
+use tokio::net::TcpStream;
 use tokio::sync::RwLock;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 
 use crate::error::{Error, Result};
-use crate::types::{MarketSubscription, WsEvent};
+use crate::types::{MarketSubscription, MarketSubscriptionUpdate, WsEvent};
 
 /// Handle for querying WebSocket subscription state
 ///
 /// This handle provides read-only access to the current token IDs
 /// being subscribed to.
 ///
-/// **Note**: Polymarket does not support updating subscriptions on an
-/// existing WebSocket connection. To change subscriptions, you must
-/// close the connection and create a new one with the updated token list.
+/// **Note**: This handle can't change the subscription. To add or remove
+/// assets on an open connection, use
+/// [`MarketWsClient::subscribe_frames_with_control`].
 #[derive(Clone)]
 pub struct SubscriptionHandle {
     /// Shared state containing current token IDs
@@ -28,6 +30,47 @@ impl SubscriptionHandle {
     }
 }
 
+/// Write half of a market WebSocket, for changing its assets while it is open
+///
+/// Returned by [`MarketWsClient::subscribe_frames_with_control`]. Newly
+/// subscribed assets get a book snapshot on the connection like the
+/// initial ones did.
+pub struct MarketSubscriptionControl {
+    write: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+}
+
+impl MarketSubscriptionControl {
+    /// Start streaming `token_ids` on this connection
+    pub async fn subscribe(&mut self, token_ids: Vec<String>) -> Result<()> {
+        self.send(token_ids, "subscribe").await
+    }
+
+    /// Stop streaming `token_ids` on this connection
+    pub async fn unsubscribe(&mut self, token_ids: Vec<String>) -> Result<()> {
+        self.send(token_ids, "unsubscribe").await
+    }
+
+    /// Send the market channel's application-level `PING`; the server
+    /// answers with a `PONG` text frame on the stream
+    pub async fn ping(&mut self) -> Result<()> {
+        self.write
+            .send(Message::Text("PING".to_string()))
+            .await
+            .map_err(|e| Error::WebSocket(e.to_string()))
+    }
+
+    async fn send(&mut self, token_ids: Vec<String>, operation: &str) -> Result<()> {
+        let update = MarketSubscriptionUpdate {
+            assets_ids: token_ids,
+            operation: operation.to_string(),
+        };
+        self.write
+            .send(Message::Text(serde_json::to_string(&update)?))
+            .await
+            .map_err(|e| Error::WebSocket(e.to_string()))
+    }
+}
+
 /// WebSocket client for streaming market data (order book updates)
 ///
 /// This client connects to the Polymarket CLOB WebSocket endpoint and streams
@@ -53,47 +96,21 @@ pub struct MarketWsClient {
 fn parse_ws_message(
     msg: std::result::Result<Message, tokio_tungstenite::tungstenite::Error>,
 ) -> Option<Result<WsEvent>> {
-    match msg {
-        Ok(Message::Text(text)) => {
-            // Skip empty or whitespace-only messages
-            let trimmed = text.trim();
-            if trimmed.is_empty() {
-                return None;
-            }
-
-            // Skip PING/PONG messages sent as text (some servers do this)
-            if trimmed.eq_ignore_ascii_case("ping") || trimmed.eq_ignore_ascii_case("pong") {
-                return None;
-            }
-
-            // The server can send either a single object or an array
-            // Try to parse as array first
-            if let Ok(events) = serde_json::from_str::<Vec<serde_json::Value>>(&text) {
-                // Got an array, take the first event
-                if let Some(first) = events.first() {
-                    match serde_json::from_value::<WsEvent>(first.clone()) {
-                        Ok(event) => return Some(Ok(event)),
-                        Err(e) => return Some(Err(Error::Json(e))),
-                    }
-                } else {
-                    // Empty array, ignore
-                    return None;
-                }
-            }
+    match frame_text(msg)? {
+        Ok(text) => parse_text(&text),
+        Err(e) => Some(Err(e)),
+    }
+}
 
-            // Try parsing as single object
-            match serde_json::from_str::<WsEvent>(&text) {
-                Ok(event) => Some(Ok(event)),
-                Err(e) => {
-                    // Log unexpected message format for debugging
-                    log::warn!(
-                        "Unexpected WebSocket message (first 200 chars): {}",
-                        &text.chars().take(200).collect::<String>()
-                    );
-                    Some(Err(Error::Json(e)))
-                }
-            }
-        }
+/// Extract the text payload of a WebSocket message
+///
+/// Control frames are skipped (`None`); close frames, binary frames and
+/// transport errors become errors.
+fn frame_text(
+    msg: std::result::Result<Message, tokio_tungstenite::tungstenite::Error>,
+) -> Option<Result<String>> {
+    match msg {
+        Ok(Message::Text(text)) => Some(Ok(text)),
         Ok(Message::Close(_)) => {
             // Connection closed gracefully
             Some(Err(Error::ConnectionClosed))
@@ -119,6 +136,73 @@ fn parse_ws_message(
     }
 }
 
+/// Parse the text of a market channel message into every WsEvent it holds
+///
+/// Like [`parse_text`], but an array message yields all of its events, in
+/// order, rather than only the first. Events are appended to `out`, so one
+/// buffer can be reused across messages.
+pub fn parse_text_into(text: &str, out: &mut Vec<Result<WsEvent>>) {
+    let trimmed = text.trim();
+    if !trimmed.starts_with('[') {
+        out.extend(parse_text(text));
+        return;
+    }
+    match serde_json::from_str::<Vec<serde_json::Value>>(trimmed) {
+        Ok(events) => out.extend(
+            events
+                .into_iter()
+                .map(|event| serde_json::from_value::<WsEvent>(event).map_err(Error::Json)),
+        ),
+        Err(e) => out.push(Err(Error::Json(e))),
+    }
+}
+
+/// Parse the text of a market channel message into a WsEvent
+///
+/// Returns `None` for messages that carry no event (empty, text
+/// PING/PONG, empty arrays). Use with [`MarketWsClient::subscribe_frames`]
+/// to parse on a different task than the one reading the socket.
+pub fn parse_text(text: &str) -> Option<Result<WsEvent>> {
+    // Skip empty or whitespace-only messages
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    // Skip PING/PONG messages sent as text (some servers do this)
+    if trimmed.eq_ignore_ascii_case("ping") || trimmed.eq_ignore_ascii_case("pong") {
+        return None;
+    }
+
+    // The server can send either a single object or an array
+    // Try to parse as array first
+    if let Ok(events) = serde_json::from_str::<Vec<serde_json::Value>>(text) {
+        // Got an array, take the first event
+        if let Some(first) = events.first() {
+            match serde_json::from_value::<WsEvent>(first.clone()) {
+                Ok(event) => return Some(Ok(event)),
+                Err(e) => return Some(Err(Error::Json(e))),
+            }
+        } else {
+            // Empty array, ignore
+            return None;
+        }
+    }
+
+    // Try parsing as single object
+    match serde_json::from_str::<WsEvent>(text) {
+        Ok(event) => Some(Ok(event)),
+        Err(e) => {
+            // Log unexpected message format for debugging
+            log::warn!(
+                "Unexpected WebSocket message (first 200 chars): {}",
+                &text.chars().take(200).collect::<String>()
+            );
+            Some(Err(Error::Json(e)))
+        }
+    }
+}
+
 impl MarketWsClient {
     /// Default WebSocket URL for market data
     const DEFAULT_WS_URL: &'static str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
@@ -264,6 +348,75 @@ impl MarketWsClient {
 
         Ok(Box::pin(stream))
     }
+
+    /// Subscribe to market updates and receive the raw text of each message
+    ///
+    /// Like [`subscribe`](Self::subscribe), but nothing is parsed: each item
+    /// is a message's text, for [`parse_text`]. This lets a caller read the
+    /// socket on one task and parse on another.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The WebSocket connection fails
+    /// - The subscription message cannot be sent
+    pub async fn subscribe_frames(
+        &self,
+        token_ids: Vec<String>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String>> + Send>>> {
+        // Connect to the WebSocket endpoint
+        let (ws_stream, _) = connect_async(&self.ws_url).await?;
+
+        let (mut write, read) = ws_stream.split();
+
+        let subscription = MarketSubscription {
+            assets_ids: token_ids,
+        };
+        let subscription_msg = serde_json::to_string(&subscription)?;
+        write
+            .send(Message::Text(subscription_msg))
+            .await
+            .map_err(|e| Error::WebSocket(e.to_string()))?;
+        drop(write);
+
+        let stream = read.filter_map(|msg| async move { frame_text(msg) });
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Like [`subscribe_frames`](Self::subscribe_frames), but keeps the
+    /// write half of the socket as a [`MarketSubscriptionControl`], through
+    /// which assets can be added and removed without reconnecting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The WebSocket connection fails
+    /// - The subscription message cannot be sent
+    pub async fn subscribe_frames_with_control(
+        &self,
+        token_ids: Vec<String>,
+    ) -> Result<(
+        Pin<Box<dyn Stream<Item = Result<String>> + Send>>,
+        MarketSubscriptionControl,
+    )> {
+        let (ws_stream, _) = connect_async(&self.ws_url).await?;
+
+        let (mut write, read) = ws_stream.split();
+
+        let subscription = MarketSubscription {
+            assets_ids: token_ids,
+        };
+        let subscription_msg = serde_json::to_string(&subscription)?;
+        write
+            .send(Message::Text(subscription_msg))
+            .await
+            .map_err(|e| Error::WebSocket(e.to_string()))?;
+
+        let stream = read.filter_map(|msg| async move { frame_text(msg) });
+
+        Ok((Box::pin(stream), MarketSubscriptionControl { write }))
+    }
 }
 
 impl Default for MarketWsClient {
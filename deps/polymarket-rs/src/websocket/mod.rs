@@ -14,12 +14,12 @@ mod market;
 mod stream;
 mod user;
 
-pub use market::{MarketWsClient, SubscriptionHandle};
+pub use market::{parse_text, parse_text_into, MarketSubscriptionControl, MarketWsClient, SubscriptionHandle};
 pub use stream::{ReconnectConfig, ReconnectingStream};
 pub use user::UserWsClient;
 
 // Re-export commonly used types for convenience
 pub use crate::types::{
-    BookEvent, LastTradePriceEvent, MarketSubscription, OrderEvent, PriceChange, PriceChangeEvent,
+    BookEvent, LastTradePriceEvent, MarketSubscription, MarketSubscriptionUpdate, OrderEvent, PriceChange, PriceChangeEvent,
     PriceLevel, TradeEvent, UserAuthentication, UserWsEvent, WsEvent,
 };
@@ -0,0 +1,362 @@
+use axum::extract::{Path, Request, State};
+use axum::http::{header, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{delete, get, post, put};
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use tracing::{info, warn};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::execution::operator::{FlattenReport, ManualOrder, OperatorActions};
+use crate::flags::{parse_rollout, FeatureFlags, FlagStatus};
+use crate::health::{HealthRegistry, HealthReport};
+use crate::logging::LogFilter;
+use crate::market_data::subscriptions::{Subscribed, SubscriptionControl};
+use crate::state::equity::{DailyReturn, Drawdown, EquityCurve, EquitySample};
+use crate::state::pnl::LedgerEntry;
+use crate::state::portfolio::{Portfolio, PortfolioSnapshot};
+use crate::strategy::audit::{Decision, DecisionAudit};
+use crate::strategy::{PauseSwitch, StrategySet};
+use crate::webhook::constant_time_eq;
+
+/// Shared state handed to every admin route.
+#[derive(Clone)]
+pub struct AdminState {
+    pub portfolio: Portfolio,
+    pub equity: EquityCurve,
+    pub health: HealthRegistry,
+    pub audit: DecisionAudit,
+    pub log_filter: LogFilter,
+    pub pause: PauseSwitch,
+    pub strategies: StrategySet,
+    pub flags: FeatureFlags,
+    pub operator: OperatorActions,
+    /// One per running market data adapter.
+    pub subscriptions: Vec<SubscriptionControl>,
+    /// Bearer token every route but the `GET`s requires. Without one they're
+    /// all refused, so the engine can't be steered over the network.
+    pub token: Option<String>,
+}
+
+/// Serve the admin HTTP API until the listener fails.
+///
+/// `GET` routes are open. Every other route changes what the engine does,
+/// so it needs `Authorization: Bearer <token>` with the state's `token`:
+/// 401 for a missing or wrong token, 403 when no token is configured.
+///
+/// Routes:
+/// - `GET /portfolio` — current [`PortfolioSnapshot`] as JSON.
+/// - `GET /ledger`    — every closed lot with its cost basis and realized PnL.
+/// - `GET /equity`    — sampled equity curve, max drawdown, and daily returns.
+/// - `GET /healthz`   — 200 while the server is up, with the component report.
+/// - `GET /readyz`    — 200 when every adapter, channel, and the executor are up
+///   and the cache is fresh; 503 otherwise.
+/// - `GET /audit`     — markets whose strategy decisions are being recorded.
+/// - `GET /audit/:market_id` — recent decisions for a watched market, oldest
+///   first, with every value the strategies computed; 404 if not watched.
+/// - `PUT` / `DELETE /audit/:market_id` — start / stop recording a market.
+/// - `GET /log-level` — active tracing filter.
+/// - `PUT /log-level` — replace it; the body is `RUST_LOG`-style directives,
+///   e.g. `info,prediction_engine::execution=debug`. 400 if they don't parse.
+/// - `GET /strategies` — running strategies and which are paused.
+/// - `PUT` / `DELETE /pause` — pause / resume all strategy evaluation.
+/// - `PUT` / `DELETE /strategies/:name/pause` — pause / resume one strategy;
+///   404 if no running strategy has that name.
+/// - `GET /flags` — feature flags with configured and effective rollout.
+/// - `PUT /flags/:name` — override a flag; the body is `on`, `off`, a
+///   fraction, or a percentage. 400 if it doesn't parse.
+/// - `DELETE /flags/:name` — drop the override, back to the configured value.
+/// - `POST /flatten/:market_id` — close every position in the market at the
+///   touch, bypassing the risk breaker; 503 if the execution bridge is down.
+/// - `POST /orders` — submit a manual order (JSON: `market_id`, `token_id`,
+///   `side`, `price`, `size`, optional `venue`) as strategy `manual`, through
+///   the usual risk checks. 202 once queued; 400 if invalid; 503 if the
+///   execution bridge is down.
+/// - `POST /orders/cancel-all` — cancel every resting order at the venue
+///   (live mode only); 502 if the venue call fails.
+/// - `GET /subscriptions` — tokens each adapter streams, by venue.
+/// - `POST /subscriptions/:venue` — start streaming a market (JSON:
+///   `market_id`, optional `token_ids`, defaulting to the market id as on
+///   Kalshi) on the open connection. Returns how many tokens were new; 404
+///   if no adapter runs for the venue.
+/// - `DELETE /subscriptions/:venue/:token_id` — stop streaming a token; 404
+///   if it isn't subscribed.
+pub async fn run_admin_server(addr: SocketAddr, state: AdminState) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/portfolio", get(get_portfolio))
+        .route("/ledger", get(get_ledger))
+        .route("/equity", get(get_equity))
+        .route("/healthz", get(get_healthz))
+        .route("/readyz", get(get_readyz))
+        .route("/audit", get(get_audit_markets))
+        .route("/audit/:market_id", get(get_audit).put(put_audit).delete(delete_audit))
+        .route("/log-level", get(get_log_level).put(put_log_level))
+        .route("/strategies", get(get_strategies))
+        .route("/pause", put(put_pause).delete(delete_pause))
+        .route("/strategies/:name/pause", put(put_strategy_pause).delete(delete_strategy_pause))
+        .route("/flags", get(get_flags))
+        .route("/flags/:name", put(put_flag).delete(delete_flag))
+        .route("/flatten/:market_id", post(post_flatten))
+        .route("/orders", post(post_order))
+        .route("/orders/cancel-all", post(post_cancel_all))
+        .route("/subscriptions", get(get_subscriptions))
+        .route("/subscriptions/:venue", post(post_subscription))
+        .route("/subscriptions/:venue/:token_id", delete(delete_subscription))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(%addr, "admin API listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Let `GET`s through; anything else needs the configured bearer token.
+async fn require_token(State(state): State<AdminState>, request: Request, next: Next) -> Result<Response, (StatusCode, String)> {
+    if matches!(*request.method(), Method::GET | Method::HEAD) {
+        return Ok(next.run(request).await);
+    }
+    let Some(token) = &state.token else {
+        return Err((StatusCode::FORBIDDEN, "operator routes are disabled; set ADMIN_TOKEN to enable them".to_string()));
+    };
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !presented.is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes())) {
+        warn!(method = %request.method(), path = request.uri().path(), "admin request refused: missing or invalid token");
+        return Err((StatusCode::UNAUTHORIZED, "missing or invalid token".to_string()));
+    }
+    Ok(next.run(request).await)
+}
+
+async fn get_portfolio(State(state): State<AdminState>) -> Json<PortfolioSnapshot> {
+    Json(state.portfolio.snapshot())
+}
+
+async fn get_ledger(State(state): State<AdminState>) -> Json<Vec<LedgerEntry>> {
+    Json(state.portfolio.positions().ledger().entries())
+}
+
+#[derive(Serialize)]
+struct EquityReport {
+    samples: Vec<EquitySample>,
+    max_drawdown: Drawdown,
+    current_drawdown: Drawdown,
+    daily_returns: Vec<DailyReturn>,
+}
+
+async fn get_equity(State(state): State<AdminState>) -> Json<EquityReport> {
+    Json(EquityReport {
+        samples: state.equity.samples(),
+        max_drawdown: state.equity.max_drawdown(),
+        current_drawdown: state.equity.current_drawdown(),
+        daily_returns: state.equity.daily_returns(),
+    })
+}
+
+async fn get_healthz(State(state): State<AdminState>) -> Json<HealthReport> {
+    Json(state.health.report())
+}
+
+async fn get_readyz(State(state): State<AdminState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.health.report();
+    let status = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+async fn get_audit_markets(State(state): State<AdminState>) -> Json<Vec<String>> {
+    Json(state.audit.watched())
+}
+
+async fn get_audit(
+    State(state): State<AdminState>,
+    Path(market_id): Path<String>,
+) -> Result<Json<Vec<Decision>>, StatusCode> {
+    state.audit.decisions(&market_id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn put_audit(State(state): State<AdminState>, Path(market_id): Path<String>) -> StatusCode {
+    if state.audit.watch(&market_id) {
+        info!(%market_id, "decision audit enabled");
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn delete_audit(State(state): State<AdminState>, Path(market_id): Path<String>) -> StatusCode {
+    state.audit.unwatch(&market_id);
+    info!(%market_id, "decision audit disabled");
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Serialize)]
+struct LogLevel {
+    filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous: Option<String>,
+}
+
+async fn get_log_level(State(state): State<AdminState>) -> Json<LogLevel> {
+    Json(LogLevel { filter: state.log_filter.current(), previous: None })
+}
+
+async fn put_log_level(State(state): State<AdminState>, body: String) -> Result<Json<LogLevel>, (StatusCode, String)> {
+    let previous = state.log_filter.set(&body).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let filter = state.log_filter.current();
+    info!(%previous, %filter, "log filter changed via admin API");
+    Ok(Json(LogLevel { filter, previous: Some(previous) }))
+}
+
+#[derive(Serialize)]
+struct StrategyStatus {
+    name: &'static str,
+    paused: bool,
+}
+
+#[derive(Serialize)]
+struct Strategies {
+    /// All evaluation paused, whatever each strategy's own flag says.
+    paused: bool,
+    strategies: Vec<StrategyStatus>,
+}
+
+async fn get_strategies(State(state): State<AdminState>) -> Json<Strategies> {
+    Json(Strategies {
+        paused: state.pause.is_paused(),
+        strategies: state
+            .strategies
+            .statuses()
+            .into_iter()
+            .map(|(name, paused)| StrategyStatus { name, paused })
+            .collect(),
+    })
+}
+
+async fn put_pause(State(state): State<AdminState>) -> StatusCode {
+    state.pause.set_paused(true);
+    StatusCode::NO_CONTENT
+}
+
+async fn delete_pause(State(state): State<AdminState>) -> StatusCode {
+    state.pause.set_paused(false);
+    StatusCode::NO_CONTENT
+}
+
+async fn put_strategy_pause(State(state): State<AdminState>, Path(name): Path<String>) -> StatusCode {
+    if state.strategies.set_paused(&name, true) { StatusCode::NO_CONTENT } else { StatusCode::NOT_FOUND }
+}
+
+async fn delete_strategy_pause(State(state): State<AdminState>, Path(name): Path<String>) -> StatusCode {
+    if state.strategies.set_paused(&name, false) { StatusCode::NO_CONTENT } else { StatusCode::NOT_FOUND }
+}
+
+async fn get_flags(State(state): State<AdminState>) -> Json<Vec<FlagStatus>> {
+    Json(state.flags.statuses())
+}
+
+async fn put_flag(
+    State(state): State<AdminState>,
+    Path(name): Path<String>,
+    body: String,
+) -> Result<Json<Vec<FlagStatus>>, (StatusCode, String)> {
+    let rollout = parse_rollout(&body).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    state.flags.set_override(&name, Some(rollout));
+    Ok(Json(state.flags.statuses()))
+}
+
+async fn delete_flag(State(state): State<AdminState>, Path(name): Path<String>) -> StatusCode {
+    state.flags.set_override(&name, None);
+    StatusCode::NO_CONTENT
+}
+
+async fn post_flatten(
+    State(state): State<AdminState>,
+    Path(market_id): Path<String>,
+) -> Result<Json<FlattenReport>, (StatusCode, String)> {
+    info!(%market_id, "flatten requested via admin API");
+    state
+        .operator
+        .flatten(&market_id)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))
+}
+
+async fn post_order(
+    State(state): State<AdminState>,
+    Json(order): Json<ManualOrder>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    state.operator.check(&order).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    state.operator.submit(order).await.map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Serialize)]
+struct Canceled {
+    canceled: usize,
+}
+
+async fn post_cancel_all(State(state): State<AdminState>) -> Result<Json<Canceled>, (StatusCode, String)> {
+    info!("cancel-all requested via admin API");
+    let canceled = state.operator.cancel_all().await.map_err(|e| (StatusCode::BAD_GATEWAY, format!("{e:#}")))?;
+    Ok(Json(Canceled { canceled }))
+}
+
+async fn get_subscriptions(State(state): State<AdminState>) -> Json<BTreeMap<&'static str, Vec<Subscribed>>> {
+    Json(state.subscriptions.iter().map(|control| (control.venue().name(), control.tokens())).collect())
+}
+
+/// The adapter streaming `venue`, matched by [`Venue::name`](crate::market_data::types::Venue::name)
+/// ignoring case.
+fn subscription_control<'a>(state: &'a AdminState, venue: &str) -> Result<&'a SubscriptionControl, (StatusCode, String)> {
+    state
+        .subscriptions
+        .iter()
+        .find(|control| control.venue().name().eq_ignore_ascii_case(venue))
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no adapter runs for venue {venue}")))
+}
+
+#[derive(Deserialize)]
+struct SubscribeRequest {
+    market_id: String,
+    #[serde(default)]
+    token_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Added {
+    added: usize,
+}
+
+async fn post_subscription(
+    State(state): State<AdminState>,
+    Path(venue): Path<String>,
+    Json(request): Json<SubscribeRequest>,
+) -> Result<Json<Added>, (StatusCode, String)> {
+    let control = subscription_control(&state, &venue)?;
+    let token_ids = match request.token_ids.is_empty() {
+        true => vec![request.market_id.clone()],
+        false => request.token_ids,
+    };
+    info!(venue = control.venue().name(), market_id = %request.market_id, ?token_ids, "subscribe requested via admin API");
+    let added = control.subscribe(&request.market_id, &token_ids);
+    Ok(Json(Added { added }))
+}
+
+async fn delete_subscription(
+    State(state): State<AdminState>,
+    Path((venue, token_id)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let control = subscription_control(&state, &venue)?;
+    info!(venue = control.venue().name(), %token_id, "unsubscribe requested via admin API");
+    match control.unsubscribe(&[token_id]) {
+        0 => Err((StatusCode::NOT_FOUND, "token not subscribed".to_string())),
+        _ => Ok(StatusCode::NO_CONTENT),
+    }
+}
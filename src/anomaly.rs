@@ -0,0 +1,168 @@
+//! Per-venue event-rate anomaly detection.
+//!
+//! Learns each venue's normal event rate as an EWMA of mean and variance over
+//! fixed buckets, and flags sustained deviations: a feed that goes (nearly)
+//! silent, or a burst far above normal that usually means a subscription
+//! error. Baselines stop learning while a venue is anomalous so the anomaly
+//! never becomes the new normal.
+
+use metrics::{counter, gauge};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::market_data::bus::Subscriber;
+use crate::market_data::types::Venue;
+use crate::notify::{Notifier, NotifyEvent};
+
+#[derive(Debug, Clone)]
+pub struct RateAnomalyConfig {
+    pub bucket: Duration,
+    /// EWMA smoothing factor per bucket.
+    pub alpha: f64,
+    /// Buckets observed before a venue's baseline is trusted.
+    pub warmup_buckets: u32,
+    /// Minimum |z-score| for a bucket to count as deviating.
+    pub z_threshold: f64,
+    /// A deviating bucket must also be above `burst_ratio × mean` ...
+    pub burst_ratio: f64,
+    /// ... or below `drop_ratio × mean`.
+    pub drop_ratio: f64,
+    /// Consecutive deviating buckets before raising (and normal ones before clearing).
+    pub sustain_buckets: u32,
+}
+
+impl Default for RateAnomalyConfig {
+    fn default() -> Self {
+        Self {
+            bucket: Duration::from_secs(10),
+            alpha: 0.05,
+            warmup_buckets: 30,
+            z_threshold: 4.0,
+            burst_ratio: 50.0,
+            drop_ratio: 0.1,
+            sustain_buckets: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Burst,
+    Drop,
+}
+
+impl Direction {
+    fn name(self) -> &'static str {
+        match self {
+            Direction::Burst => "burst",
+            Direction::Drop => "drop",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct VenueRate {
+    count: u64,
+    buckets: u32,
+    mean: f64,
+    var: f64,
+    /// Consecutive buckets disagreeing with the current state.
+    streak: u32,
+    anomaly: Option<Direction>,
+}
+
+impl VenueRate {
+    /// Classify a finished bucket's rate against the baseline.
+    fn deviation(&self, rate: f64, config: &RateAnomalyConfig) -> Option<Direction> {
+        if self.buckets < config.warmup_buckets {
+            return None;
+        }
+        let z = (rate - self.mean) / self.var.sqrt().max(f64::EPSILON);
+        if z >= config.z_threshold && rate > self.mean * config.burst_ratio {
+            Some(Direction::Burst)
+        } else if z <= -config.z_threshold && rate < self.mean * config.drop_ratio {
+            Some(Direction::Drop)
+        } else if rate < self.mean * config.drop_ratio && self.var.sqrt() < self.mean * config.drop_ratio {
+            // A very steady feed has a tiny variance; don't let that hide a silent feed.
+            Some(Direction::Drop)
+        } else {
+            None
+        }
+    }
+
+    fn learn(&mut self, rate: f64, alpha: f64) {
+        if self.buckets == 0 {
+            self.mean = rate;
+        } else {
+            let diff = rate - self.mean;
+            self.mean += alpha * diff;
+            self.var = (1.0 - alpha) * (self.var + alpha * diff * diff);
+        }
+        self.buckets += 1;
+    }
+}
+
+/// Consume events from `rx` (an event-bus subscription) and evaluate each venue's rate
+/// every `config.bucket`. Venues are tracked from their first event.
+pub async fn run_rate_anomaly_detector(mut rx: Subscriber, config: RateAnomalyConfig, notifier: Notifier) {
+    let mut venues: HashMap<Venue, VenueRate> = HashMap::new();
+    let mut ticker = tokio::time::interval(config.bucket);
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(event) = event else { return };
+                venues.entry(event.venue.clone()).or_default().count += 1;
+            }
+            _ = ticker.tick() => {
+                for (venue, state) in venues.iter_mut() {
+                    evaluate(venue, state, &config, &notifier);
+                }
+            }
+        }
+    }
+}
+
+fn evaluate(venue: &Venue, state: &mut VenueRate, config: &RateAnomalyConfig, notifier: &Notifier) {
+    let venue_label = format!("{venue:?}").to_lowercase();
+    let rate = std::mem::take(&mut state.count) as f64 / config.bucket.as_secs_f64();
+    let deviation = state.deviation(rate, config);
+
+    gauge!("event_rate", "venue" => venue_label.clone()).set(rate);
+    gauge!("event_rate_baseline", "venue" => venue_label.clone()).set(state.mean);
+
+    state.streak = if deviation != state.anomaly { state.streak + 1 } else { 0 };
+    if state.streak >= config.sustain_buckets {
+        state.streak = 0;
+        let previous = std::mem::replace(&mut state.anomaly, deviation);
+        let baseline = state.mean;
+        match deviation {
+            Some(direction) => {
+                warn!(venue = %venue_label, direction = direction.name(), rate, baseline, "event rate anomaly");
+                counter!("event_rate_anomalies_total", "venue" => venue_label.clone(), "direction" => direction.name())
+                    .increment(1);
+                notifier.notify(NotifyEvent::Watchdog {
+                    check: format!("event_rate.{venue_label}"),
+                    firing: true,
+                    detail: format!("{} — {rate:.1} events/s vs normal {baseline:.1}", direction.name()),
+                });
+            }
+            None => {
+                info!(venue = %venue_label, rate, baseline, "event rate back to normal");
+                notifier.notify(NotifyEvent::Watchdog {
+                    check: format!("event_rate.{venue_label}"),
+                    firing: false,
+                    detail: format!("{rate:.1} events/s after {}", previous.map_or("anomaly", Direction::name)),
+                });
+            }
+        }
+    }
+    gauge!("event_rate_anomaly", "venue" => venue_label).set(if state.anomaly.is_some() { 1.0 } else { 0.0 });
+
+    // Only learn from buckets that look normal, and not while anomalous.
+    if deviation.is_none() && state.anomaly.is_none() {
+        state.learn(rate, config.alpha);
+    }
+}
@@ -0,0 +1,249 @@
+//! One isolated virtual account inside a replay: its own strategies,
+//! positions, cash, fill model, and results. Several accounts can share a
+//! single pass over the event stream and the market cache.
+
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::backtest::checkpoint::{self, AccountCheckpoint};
+use crate::backtest::fill_model::{ModelFill, QueueFillModel};
+use crate::backtest::sim::SimExecutor;
+use crate::backtest::{BacktestConfig, SimFill, SimSignal};
+use crate::clock::Clock;
+use crate::execution::traits::{ExecutionEngine, ExecutionIntent, LegFillStatus};
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::market_data::types::MarketEvent;
+use crate::state::equity::{EquityCurve, EquitySample};
+use crate::state::fees::Liquidity;
+use crate::state::market::MarketState;
+use crate::state::ids::TokenId;
+use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::portfolio::Portfolio;
+use crate::state::position::PositionTracker;
+use crate::strategy::audit::DecisionAudit;
+use crate::strategy::evaluate_all;
+use crate::strategy::traits::{EvalContext, Strategy};
+
+pub(crate) struct Account {
+    strategies: Vec<Box<dyn Strategy>>,
+    positions: PositionTracker,
+    pub(crate) portfolio: Portfolio,
+    queue: Option<QueueFillModel>,
+    equity: EquityCurve,
+    pub(crate) signals: usize,
+    pub(crate) signal_log: Vec<SimSignal>,
+    pub(crate) fills: Vec<SimFill>,
+    next_sample_ms: u64,
+}
+
+impl Account {
+    pub(crate) fn new(
+        config: &BacktestConfig,
+        strategies: Vec<Box<dyn Strategy>>,
+        cache: &MarketCache,
+        token_to_market: &Arc<TokenToMarket>,
+    ) -> Self {
+        let positions = PositionTracker::new();
+        let portfolio = Portfolio::new(positions.clone(), cache.clone(), Arc::clone(token_to_market), config.starting_cash)
+            .with_fee_schedules(config.fee_schedules.clone());
+        Self {
+            strategies,
+            positions,
+            portfolio,
+            queue: config.queue_model.map(QueueFillModel::new),
+            equity: EquityCurve::new(usize::MAX),
+            signals: 0,
+            signal_log: Vec::new(),
+            fills: Vec::new(),
+            next_sample_ms: 0,
+        }
+    }
+
+    /// Update the fill model's books from an event; resting orders fill on
+    /// the event before strategies react to it.
+    pub(crate) fn on_market_event(&mut self, event: &MarketEvent, ts_ms: u64) {
+        if let Some(queue) = self.queue.as_mut() {
+            for fill in queue.on_event(event) {
+                record_model_fill(&self.portfolio, &mut self.fills, fill, ts_ms);
+            }
+        }
+    }
+
+    /// Run the account's strategies against the update to `key` and execute
+    /// what they signal.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn evaluate(
+        &mut self,
+        key: &MarketKey,
+        state: &MarketState,
+        cache: &MarketCache,
+        market_map: &MarketMap,
+        token_to_market: &TokenToMarket,
+        clock: &dyn Clock,
+        executor: &SimExecutor,
+        ts_ms: u64,
+    ) {
+        let audit = DecisionAudit::disabled();
+        let ctx = EvalContext {
+            updated_key: key,
+            updated_state: state,
+            cache,
+            market_map,
+            token_to_market,
+            positions: &self.positions,
+            ws_received_at: None,
+            clock,
+            audit: &audit,
+        };
+
+        for signal in evaluate_all(&self.strategies, &ctx) {
+            self.signals += 1;
+            let signaled_edge = signal.edge;
+            self.signal_log.push(SimSignal {
+                ts_ms,
+                strategy: signal.strategy_name,
+                market_id: signal.market_id.to_string(),
+                edge: signal.edge,
+            });
+
+            if let Some(queue) = self.queue.as_mut() {
+                let intent = ExecutionIntent::from_signal(signal, clock.now());
+                if intent.liquidity == Liquidity::Maker {
+                    // A new quote replaces the strategy's previous one.
+                    queue.cancel(intent.strategy_name, intent.market_id.as_str());
+                }
+                for leg in &intent.legs {
+                    let fill = match intent.liquidity {
+                        Liquidity::Maker => queue.place(intent.strategy_name, &intent.venue, intent.market_id.as_str(), leg, signaled_edge),
+                        Liquidity::Taker => queue.take(intent.strategy_name, &intent.venue, intent.market_id.as_str(), leg, signaled_edge),
+                    };
+                    if let Some(fill) = fill {
+                        record_model_fill(&self.portfolio, &mut self.fills, fill, ts_ms);
+                    }
+                }
+                continue;
+            }
+
+            let intent = ExecutionIntent::from_signal(signal, clock.now());
+            let (venue, liquidity, legs) = (intent.venue.clone(), intent.liquidity, intent.legs.clone());
+            let report = executor.execute(intent).await;
+
+            for (leg, result) in legs.iter().zip(&report.leg_results) {
+                if let LegFillStatus::Filled { avg_price, filled_size, .. } = result {
+                    self.portfolio.apply_fill_at(
+                        MarketKey(venue.clone(), leg.token_id),
+                        report.strategy_name,
+                        liquidity,
+                        &leg.side,
+                        *avg_price,
+                        *filled_size,
+                        ts_ms,
+                    );
+                    self.fills.push(SimFill {
+                        ts_ms,
+                        strategy: report.strategy_name,
+                        venue: venue.clone(),
+                        market_id: report.market_id.to_string(),
+                        token_id: leg.token_id.to_string(),
+                        side: leg.side.clone(),
+                        price: *avg_price,
+                        size: *filled_size,
+                        liquidity,
+                        signaled_edge,
+                    });
+                }
+            }
+            debug!(market_id = %report.market_id, fully_filled = report.fully_filled(), "backtest execution");
+        }
+    }
+
+    pub(crate) fn sample_equity(&mut self, ts_ms: u64, interval_ms: u64) {
+        if ts_ms >= self.next_sample_ms {
+            let balances = self.portfolio.snapshot().balances;
+            self.equity.push(EquitySample { ts_ms, equity: balances.equity, cash: balances.cash });
+            self.next_sample_ms = ts_ms + interval_ms;
+        }
+    }
+
+    pub(crate) fn equity_samples(&self) -> Vec<EquitySample> {
+        self.equity.samples()
+    }
+
+    pub(crate) fn checkpoint(&self) -> AccountCheckpoint {
+        AccountCheckpoint {
+            signals: self.signals,
+            signal_log: self.signal_log.iter().map(Into::into).collect(),
+            fills: self.fills.iter().map(Into::into).collect(),
+            equity: self.equity.samples(),
+            next_sample_ms: self.next_sample_ms,
+        }
+    }
+
+    /// Rebuild from a checkpoint: fills are re-applied to reconstruct cash,
+    /// positions, and fee accruals.
+    pub(crate) fn restore(&mut self, saved: &AccountCheckpoint) -> anyhow::Result<()> {
+        let names: Vec<&'static str> = self.strategies.iter().map(|s| s.name()).collect();
+        for fill in &saved.fills {
+            let fill = SimFill {
+                ts_ms: fill.ts_ms,
+                strategy: checkpoint::resolve_strategy(&names, &fill.strategy)?,
+                venue: fill.venue.clone(),
+                market_id: fill.market_id.clone(),
+                token_id: fill.token_id.clone(),
+                side: fill.side.clone(),
+                price: fill.price,
+                size: fill.size,
+                liquidity: fill.liquidity,
+                signaled_edge: fill.signaled_edge,
+            };
+            self.portfolio.apply_fill_at(
+                MarketKey(fill.venue.clone(), TokenId::intern(&fill.token_id)),
+                fill.strategy,
+                fill.liquidity,
+                &fill.side,
+                fill.price,
+                fill.size,
+                fill.ts_ms,
+            );
+            self.fills.push(fill);
+        }
+        for signal in &saved.signal_log {
+            self.signal_log.push(SimSignal {
+                ts_ms: signal.ts_ms,
+                strategy: checkpoint::resolve_strategy(&names, &signal.strategy)?,
+                market_id: signal.market_id.clone(),
+                edge: signal.edge,
+            });
+        }
+        for sample in &saved.equity {
+            self.equity.push(*sample);
+        }
+        self.signals = saved.signals;
+        self.next_sample_ms = saved.next_sample_ms;
+        Ok(())
+    }
+}
+
+fn record_model_fill(portfolio: &Portfolio, fills: &mut Vec<SimFill>, fill: ModelFill, ts_ms: u64) {
+    portfolio.apply_fill_at(
+        MarketKey(fill.venue.clone(), TokenId::intern(&fill.token_id)),
+        fill.strategy,
+        fill.liquidity,
+        &fill.side,
+        fill.price,
+        fill.size,
+        ts_ms,
+    );
+    fills.push(SimFill {
+        ts_ms,
+        strategy: fill.strategy,
+        venue: fill.venue,
+        market_id: fill.market_id,
+        token_id: fill.token_id,
+        side: fill.side,
+        price: fill.price,
+        size: fill.size,
+        liquidity: fill.liquidity,
+        signaled_edge: fill.signaled_edge,
+    });
+}
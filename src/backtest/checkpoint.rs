@@ -0,0 +1,127 @@
+//! Periodic replay checkpoints, so a long backtest can resume after an
+//! interruption.
+//!
+//! A checkpoint stores the replay cursor and everything the run has produced
+//! so far (fills, signals, equity) per account. Resuming fast-forwards the market cache
+//! through the already-processed events without evaluating strategies, then
+//! re-applies the fills to rebuild cash, positions, and fee accruals. Strategy
+//! internal state and resting queue-model orders are not saved; strategies
+//! re-quote on the next update.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::backtest::{SimFill, SimSignal};
+use crate::market_data::types::{Side, Venue};
+use crate::state::equity::EquitySample;
+use crate::state::fees::Liquidity;
+
+#[derive(Debug, Clone)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    /// Event-time spacing between checkpoints.
+    pub interval_ms: u64,
+    /// Resume from `path` if it holds a checkpoint for the same range.
+    pub resume: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointFill {
+    pub ts_ms: u64,
+    pub strategy: String,
+    pub venue: Venue,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub liquidity: Liquidity,
+    pub signaled_edge: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointSignal {
+    pub ts_ms: u64,
+    pub strategy: String,
+    pub market_id: String,
+    pub edge: f64,
+}
+
+/// Results so far of one account in the replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountCheckpoint {
+    pub signals: usize,
+    pub signal_log: Vec<CheckpointSignal>,
+    pub fills: Vec<CheckpointFill>,
+    pub equity: Vec<EquitySample>,
+    pub next_sample_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Number of events fully processed; the replay resumes at this index.
+    pub events_processed: usize,
+    pub ts_ms: u64,
+    /// One per account, in the order the replay was given them.
+    pub accounts: Vec<AccountCheckpoint>,
+}
+
+impl Checkpoint {
+    /// Write atomically (temp file, then rename).
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load the checkpoint at `path`, or `None` if there isn't one.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("failed to read checkpoint {}", path.display())),
+        };
+        let checkpoint = serde_json::from_slice(&bytes)
+            .with_context(|| format!("failed to parse checkpoint {}", path.display()))?;
+        Ok(Some(checkpoint))
+    }
+}
+
+impl From<&SimFill> for CheckpointFill {
+    fn from(f: &SimFill) -> Self {
+        Self {
+            ts_ms: f.ts_ms,
+            strategy: f.strategy.to_string(),
+            venue: f.venue.clone(),
+            market_id: f.market_id.clone(),
+            token_id: f.token_id.clone(),
+            side: f.side.clone(),
+            price: f.price,
+            size: f.size,
+            liquidity: f.liquidity,
+            signaled_edge: f.signaled_edge,
+        }
+    }
+}
+
+impl From<&SimSignal> for CheckpointSignal {
+    fn from(s: &SimSignal) -> Self {
+        Self { ts_ms: s.ts_ms, strategy: s.strategy.to_string(), market_id: s.market_id.clone(), edge: s.edge }
+    }
+}
+
+/// Map a saved strategy name back to the running strategy's `&'static str`.
+pub(crate) fn resolve_strategy(names: &[&'static str], name: &str) -> anyhow::Result<&'static str> {
+    names
+        .iter()
+        .copied()
+        .find(|n| *n == name)
+        .with_context(|| format!("checkpoint references strategy {name:?} which is not in this run"))
+}
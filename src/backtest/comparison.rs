@@ -0,0 +1,99 @@
+//! Side-by-side backtests: several strategy configurations replayed in one
+//! pass over the same events, each with its own isolated account.
+
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::backtest::report::BacktestReport;
+use crate::backtest::{data, replay_accounts, BacktestConfig, BacktestResult};
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::strategy::traits::Strategy;
+
+/// A labelled strategy configuration to compare.
+pub struct Variant {
+    pub label: String,
+    pub strategies: Vec<Box<dyn Strategy>>,
+}
+
+pub struct ComparisonRun {
+    pub label: String,
+    pub result: BacktestResult,
+    pub report: BacktestReport,
+}
+
+pub struct Comparison {
+    pub runs: Vec<ComparisonRun>,
+}
+
+#[derive(Serialize)]
+struct LabelledReport<'a> {
+    label: &'a str,
+    report: &'a BacktestReport,
+}
+
+pub async fn run_comparison(
+    config: &BacktestConfig,
+    variants: Vec<Variant>,
+    universe: Option<(MarketMap, TokenToMarket)>,
+) -> anyhow::Result<Comparison> {
+    let (data_dir, start_ms, end_ms) = (config.data_dir.clone(), config.start_ms, config.end_ms);
+    let events = tokio::task::spawn_blocking(move || data::load_events(&data_dir, start_ms, end_ms)).await??;
+    let (market_map, token_to_market) = universe.unwrap_or_else(|| data::infer_universe(&events));
+
+    let (labels, strategy_sets): (Vec<String>, Vec<_>) = variants.into_iter().map(|v| (v.label, v.strategies)).unzip();
+    let results = replay_accounts(config, &events, strategy_sets, &market_map, Arc::new(token_to_market)).await?;
+
+    let runs = labels
+        .into_iter()
+        .zip(results)
+        .map(|(label, result)| {
+            let report = BacktestReport::new(config, &result);
+            ComparisonRun { label, result, report }
+        })
+        .collect();
+    Ok(Comparison { runs })
+}
+
+impl Comparison {
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let reports: Vec<LabelledReport> = self
+            .runs
+            .iter()
+            .map(|run| LabelledReport { label: &run.label, report: &run.report })
+            .collect();
+        Ok(serde_json::to_string_pretty(&reports)?)
+    }
+}
+
+fn cell(value: Option<f64>, scale: f64, precision: usize) -> String {
+    value.map(|v| format!("{:.*}", precision, v * scale)).unwrap_or_else(|| "n/a".to_string())
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>8} {:>7} {:>11} {:>9} {:>8} {:>8} {:>8} {:>7} {:>9}",
+            "variant", "signals", "trades", "net pnl", "return%", "sharpe", "sortino", "max dd%", "hit%", "captured"
+        )?;
+        for run in &self.runs {
+            let r = &run.report;
+            writeln!(
+                f,
+                "{:<20} {:>8} {:>7} {:>11.2} {:>9} {:>8} {:>8} {:>8} {:>7} {:>9}",
+                run.label,
+                r.signals,
+                r.trades,
+                r.net_pnl,
+                cell(Some(r.total_return), 100.0, 2),
+                cell(r.sharpe, 1.0, 2),
+                cell(r.sortino, 1.0, 2),
+                cell(Some(r.max_drawdown.fraction), 100.0, 2),
+                cell(r.hit_rate, 100.0, 1),
+                cell(r.avg_captured_edge, 1.0, 4),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// How fast event time advances relative to wall time.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReplaySpeed {
+    /// No pacing — process events as fast as possible.
+    #[default]
+    Max,
+    /// `n` seconds of event time per wall-clock second (1.0 = real time).
+    Multiplier(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunState {
+    Running,
+    /// Paused, with `steps` events allowed through before blocking again.
+    Paused { steps: u64 },
+}
+
+/// Pause / resume / single-step handle for a running replay.
+/// Cheap to clone; every clone controls the same replay.
+#[derive(Debug, Clone)]
+pub struct ReplayControl {
+    state: Arc<watch::Sender<RunState>>,
+}
+
+impl Default for ReplayControl {
+    fn default() -> Self {
+        Self { state: Arc::new(watch::channel(RunState::Running).0) }
+    }
+}
+
+impl ReplayControl {
+    /// A control that starts paused, for stepping from the first event.
+    pub fn paused() -> Self {
+        Self { state: Arc::new(watch::channel(RunState::Paused { steps: 0 }).0) }
+    }
+
+    pub fn pause(&self) {
+        self.state.send_replace(RunState::Paused { steps: 0 });
+    }
+
+    pub fn resume(&self) {
+        self.state.send_replace(RunState::Running);
+    }
+
+    /// While paused, let `n` more events through.
+    pub fn step(&self, n: u64) {
+        self.state.send_if_modified(|state| match state {
+            RunState::Paused { steps } => {
+                *steps += n;
+                true
+            }
+            RunState::Running => false,
+        });
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(*self.state.borrow(), RunState::Paused { .. })
+    }
+
+    /// Wait until the next event may be processed. Returns `true` if it had
+    /// to block, so pacing can re-anchor instead of racing to catch up.
+    pub(crate) async fn gate(&self) -> bool {
+        let mut rx = self.state.subscribe();
+        let mut blocked = false;
+        loop {
+            let mut proceed = false;
+            self.state.send_if_modified(|state| match state {
+                RunState::Running => {
+                    proceed = true;
+                    false
+                }
+                RunState::Paused { steps } if *steps > 0 => {
+                    *steps -= 1;
+                    proceed = true;
+                    true
+                }
+                RunState::Paused { .. } => false,
+            });
+            if proceed {
+                return blocked;
+            }
+            blocked = true;
+            // The sender lives in `self`, so this can't fail.
+            let _ = rx.changed().await;
+        }
+    }
+}
+
+/// Sleeps between events so event time tracks wall time at the configured speed.
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    speed: ReplaySpeed,
+    anchor: Option<(u64, Instant)>,
+}
+
+impl Pacer {
+    pub(crate) fn new(speed: ReplaySpeed) -> Self {
+        Self { speed, anchor: None }
+    }
+
+    /// Restart pacing from the next event (after a pause or resume).
+    pub(crate) fn reset(&mut self) {
+        self.anchor = None;
+    }
+
+    pub(crate) async fn wait(&mut self, ts_ms: u64) {
+        let ReplaySpeed::Multiplier(multiplier) = self.speed else { return };
+        if multiplier <= 0.0 {
+            return;
+        }
+        let (anchor_ts, anchor_at) = *self.anchor.get_or_insert((ts_ms, Instant::now()));
+        let offset = Duration::from_secs_f64(ts_ms.saturating_sub(anchor_ts) as f64 / 1_000.0 / multiplier);
+        tokio::time::sleep_until(anchor_at + offset).await;
+    }
+}
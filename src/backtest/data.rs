@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use anyhow::Context;
+use arrow::array::{Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use tracing::{debug, info};
+
+use crate::market_data::history::event_ts_ms;
+use crate::market_data::adapters::polymarket::{MarketInfo, MarketMap, TokenToMarket};
+use crate::market_data::types::{MarketEvent, MarketEventKind, Side, Venue};
+use crate::state::ids::TokenId;
+
+fn parquet_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            parquet_files(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "parquet") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> anyhow::Result<&'a T> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<T>())
+        .with_context(|| format!("archive column '{name}' missing or mistyped"))
+}
+
+/// Column added in a later archive version — `None` when reading older files.
+fn optional_column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Option<&'a T> {
+    batch.column_by_name(name).and_then(|c| c.as_any().downcast_ref::<T>())
+}
+
+fn opt_f64(col: &Float64Array, i: usize) -> Option<f64> {
+    (!col.is_null(i)).then(|| col.value(i))
+}
+
+fn opt_col_f64(col: Option<&Float64Array>, i: usize) -> Option<f64> {
+    col.and_then(|c| opt_f64(c, i))
+}
+
+/// Sizes and trade direction, when the archive recorded them.
+#[derive(Default)]
+struct RowSizes {
+    bid_size: Option<f64>,
+    ask_size: Option<f64>,
+    trade_size: Option<f64>,
+    trade_side: Option<Side>,
+}
+
+fn parse_venue(raw: &str) -> Option<Venue> {
+    match raw {
+        "Polymarket" => Some(Venue::Polymarket),
+        "Kalshi" => Some(Venue::Kalshi),
+        "Betfair" => Some(Venue::Betfair),
+        "Rest" => Some(Venue::Rest),
+        "Amm" => Some(Venue::Amm),
+        _ => None,
+    }
+}
+
+/// Rebuild the event kind from an archived row. Archives written before
+/// sizes were recorded come back with zero sizes and `Buy` trade direction.
+/// Depth isn't archived: a book snapshot comes back as its top of book, and
+/// a delta as a delta at the touch when it changed the size there (which
+/// the replayed cache, having no book, applies as a price update).
+fn parse_kind(raw: &str, bid: Option<f64>, ask: Option<f64>, last: Option<f64>, sizes: RowSizes) -> Option<MarketEventKind> {
+    match raw {
+        "trade" => Some(MarketEventKind::Trade {
+            price: last?,
+            size: sizes.trade_size.unwrap_or(0.0),
+            side: sizes.trade_side.unwrap_or(Side::Buy),
+        }),
+        "top_of_book" | "book_snapshot" => Some(MarketEventKind::TopOfBook {
+            bid_price: bid.unwrap_or(0.0),
+            bid_size: sizes.bid_size.unwrap_or(0.0),
+            ask_price: ask.unwrap_or(0.0),
+            ask_size: sizes.ask_size.unwrap_or(0.0),
+        }),
+        "book_delta" => {
+            let touch = |side, price: Option<f64>, size: Option<f64>| {
+                Some(MarketEventKind::BookDelta { side, price: Decimal::from_f64(price?)?, size: Decimal::from_f64(size?)? })
+            };
+            touch(Side::Buy, bid, sizes.bid_size)
+                .or_else(|| touch(Side::Sell, ask, sizes.ask_size))
+                .or(Some(MarketEventKind::PriceChange))
+        }
+        "book_stale" => Some(MarketEventKind::BookStale),
+        "resync" => Some(MarketEventKind::Resync),
+        "unsubscribed" => Some(MarketEventKind::Unsubscribed),
+        "feed_stale" => Some(MarketEventKind::FeedStale),
+        "heartbeat" => Some(MarketEventKind::Heartbeat),
+        "price_change" => Some(MarketEventKind::PriceChange),
+        // Cache snapshots are periodic copies of state, not events.
+        _ => None,
+    }
+}
+
+fn read_file(path: &Path, start_ms: u64, end_ms: u64, out: &mut Vec<MarketEvent>) -> anyhow::Result<()> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+    for batch in reader {
+        let batch = batch?;
+        let ts = column::<Int64Array>(&batch, "ts_ms")?;
+        let venue = column::<StringArray>(&batch, "venue")?;
+        let kind = column::<StringArray>(&batch, "kind")?;
+        let market_id = column::<StringArray>(&batch, "market_id")?;
+        let token_id = column::<StringArray>(&batch, "token_id")?;
+        let best_bid = column::<Float64Array>(&batch, "best_bid")?;
+        let best_ask = column::<Float64Array>(&batch, "best_ask")?;
+        let volume24h = column::<Float64Array>(&batch, "volume24h")?;
+        let last_trade = column::<Float64Array>(&batch, "last_trade_price")?;
+        let liquidity = column::<Float64Array>(&batch, "liquidity")?;
+        let bid_size = optional_column::<Float64Array>(&batch, "bid_size");
+        let ask_size = optional_column::<Float64Array>(&batch, "ask_size");
+        let trade_size = optional_column::<Float64Array>(&batch, "trade_size");
+        let trade_side = optional_column::<StringArray>(&batch, "trade_side");
+
+        for i in 0..batch.num_rows() {
+            let ts_ms = ts.value(i) as u64;
+            if ts_ms < start_ms || ts_ms >= end_ms || market_id.is_null(i) {
+                continue;
+            }
+            let (bid, ask, last) = (opt_f64(best_bid, i), opt_f64(best_ask, i), opt_f64(last_trade, i));
+            let sizes = RowSizes {
+                bid_size: opt_col_f64(bid_size, i),
+                ask_size: opt_col_f64(ask_size, i),
+                trade_size: opt_col_f64(trade_size, i),
+                trade_side: trade_side.filter(|c| !c.is_null(i)).map(|c| match c.value(i) {
+                    "sell" => Side::Sell,
+                    _ => Side::Buy,
+                }),
+            };
+            let (Some(venue), Some(kind)) =
+                (parse_venue(venue.value(i)), parse_kind(kind.value(i), bid, ask, last, sizes))
+            else {
+                continue;
+            };
+            let at = UNIX_EPOCH + Duration::from_millis(ts_ms);
+            out.push(MarketEvent {
+                venue,
+                kind,
+                market_id: market_id.value(i).to_string(),
+                token_id: token_id.value(i).to_string(),
+                ts_exchange_ms: Some(at),
+                ts_receive_ms: Some(at),
+                received_at: Instant::now(),
+                parsed_at: None,
+                volume24h: opt_f64(volume24h, i),
+                last_trade_price: last,
+                liquidity: opt_f64(liquidity, i),
+                // Archived as f64; the shortest decimal form recovers the tick.
+                best_bid: bid.and_then(Decimal::from_f64),
+                best_ask: ask.and_then(Decimal::from_f64),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Load every archived event in `[start_ms, end_ms)` under `root`, sorted by
+/// receive time (stable, so same-millisecond events keep file order).
+pub fn load_events(root: &Path, start_ms: u64, end_ms: u64) -> anyhow::Result<Vec<MarketEvent>> {
+    let mut files = Vec::new();
+    parquet_files(root, &mut files).with_context(|| format!("failed to scan {}", root.display()))?;
+    files.sort();
+
+    let mut events = Vec::new();
+    for path in &files {
+        read_file(path, start_ms, end_ms, &mut events)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        debug!(path = %path.display(), total = events.len(), "archive file loaded");
+    }
+    events.sort_by_key(event_ts_ms);
+
+    info!(files = files.len(), events = events.len(), "archive loaded");
+    Ok(events)
+}
+
+/// Best-effort market metadata for archives recorded without a universe
+/// export: tokens are paired per market in first-seen order. YES/NO order
+/// may be swapped, which is harmless for strategies that treat the pair
+/// symmetrically (e.g. arbitrage) but not for directional ones.
+pub fn infer_universe(events: &[MarketEvent]) -> (MarketMap, TokenToMarket) {
+    let mut tokens_by_market: HashMap<&str, Vec<&str>> = HashMap::new();
+    for event in events {
+        let tokens = tokens_by_market.entry(&event.market_id).or_default();
+        if !tokens.contains(&event.token_id.as_str()) {
+            tokens.push(&event.token_id);
+        }
+    }
+
+    let mut market_map = MarketMap::new();
+    let mut token_to_market = TokenToMarket::new();
+    for (market_id, tokens) in tokens_by_market {
+        if tokens.len() != 2 {
+            continue;
+        }
+        for token in &tokens {
+            token_to_market.insert(token.to_string(), market_id.to_string());
+        }
+        market_map.insert(market_id.to_string(), MarketInfo {
+            market_id: market_id.to_string(),
+            question: String::new(),
+            yes_token_id: TokenId::intern(tokens[0]),
+            no_token_id: TokenId::intern(tokens[1]),
+            neg_risk: false,
+        });
+    }
+    (market_map, token_to_market)
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use crate::execution::traits::OrderLeg;
+use crate::market_data::types::{MarketEvent, MarketEventKind, Side, Venue};
+use crate::state::fees::Liquidity;
+use crate::state::market::as_f64;
+
+const PRICE_EPS: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy)]
+pub struct QueueModelConfig {
+    /// Size assumed at a price level when the archive didn't record one.
+    pub default_level_size: f64,
+}
+
+impl Default for QueueModelConfig {
+    fn default() -> Self {
+        Self { default_level_size: 100.0 }
+    }
+}
+
+/// Best price and (if known) size on one side of a token's book.
+#[derive(Debug, Clone, Copy, Default)]
+struct Level {
+    price: Option<f64>,
+    size: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Book {
+    bid: Level,
+    ask: Level,
+}
+
+/// A passive order waiting in the simulated queue.
+#[derive(Debug, Clone)]
+pub struct RestingOrder {
+    pub id: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub remaining: f64,
+    /// Size that must trade at this price before this order starts filling.
+    /// Infinite when the order sits behind the best level: it then only fills
+    /// if the market trades or quotes through its price.
+    pub queue_ahead: f64,
+    pub signaled_edge: f64,
+}
+
+/// A fill produced by the model, either immediately (taker) or later (resting).
+#[derive(Debug, Clone)]
+pub struct ModelFill {
+    pub order_id: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub liquidity: Liquidity,
+    pub signaled_edge: f64,
+}
+
+/// Fill simulator that tracks passive queue position.
+///
+/// - Taker orders fill against the recorded best opposite level, capped at
+///   its size (the archive only has top of book, so the rest is unfilled).
+/// - Passive orders join the back of the queue at their price: the recorded
+///   level size is ahead of them when joining the best price, nothing when
+///   improving it. Observed trades at the price consume the queue first,
+///   then the order. A trade or quote through the price fills it outright.
+#[derive(Debug, Default)]
+pub struct QueueFillModel {
+    config: QueueModelConfig,
+    books: HashMap<(Venue, String), Book>,
+    resting: Vec<RestingOrder>,
+    next_id: u64,
+}
+
+impl QueueFillModel {
+    pub fn new(config: QueueModelConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn resting(&self) -> &[RestingOrder] {
+        &self.resting
+    }
+
+    /// Update the book from an event and return fills of resting orders it triggers.
+    pub fn on_event(&mut self, event: &MarketEvent) -> Vec<ModelFill> {
+        let book_key = (event.venue.clone(), event.token_id.clone());
+        let book = self.books.entry(book_key).or_default();
+
+        if let MarketEventKind::TopOfBook { bid_price, bid_size, ask_price, ask_size } = event.kind {
+            book.bid = Level { price: Some(bid_price), size: (bid_size > 0.0).then_some(bid_size) };
+            book.ask = Level { price: Some(ask_price), size: (ask_size > 0.0).then_some(ask_size) };
+        } else {
+            // Keep a size the event doesn't give only if its price didn't move.
+            let (bid_size, ask_size) = event.touch_sizes();
+            if let Some(bid) = event.best_bid.map(as_f64) && (bid_size.is_some() || book.bid.price != Some(bid)) {
+                book.bid = Level { price: Some(bid), size: bid_size.filter(|s| *s > 0.0) };
+            }
+            if let Some(ask) = event.best_ask.map(as_f64) && (ask_size.is_some() || book.ask.price != Some(ask)) {
+                book.ask = Level { price: Some(ask), size: ask_size.filter(|s| *s > 0.0) };
+            }
+        }
+        let book = *book;
+
+        let mut fills = Vec::new();
+        if let MarketEventKind::Trade { price, size, side } = &event.kind {
+            self.match_trade(&event.venue, &event.token_id, *price, *size, side, &mut fills);
+        }
+        self.match_crossed(&event.venue, &event.token_id, book, &mut fills);
+        fills
+    }
+
+    /// A trade by an aggressive `side` at `price` consumes resting orders on
+    /// the other side, in queue order.
+    fn match_trade(&mut self, venue: &Venue, token_id: &str, price: f64, size: f64, side: &Side, fills: &mut Vec<ModelFill>) {
+        let mut volume = size;
+        for order in self.resting.iter_mut() {
+            if order.venue != *venue || order.token_id != token_id || order.side == *side {
+                continue;
+            }
+            let through = match order.side {
+                Side::Buy => price < order.price - PRICE_EPS,
+                Side::Sell => price > order.price + PRICE_EPS,
+            };
+            let at_price = (price - order.price).abs() <= PRICE_EPS;
+
+            let filled = if through {
+                order.remaining
+            } else if at_price && volume > 0.0 {
+                let consumed_queue = volume.min(order.queue_ahead);
+                order.queue_ahead -= consumed_queue;
+                volume -= consumed_queue;
+                let filled = volume.min(order.remaining);
+                volume -= filled;
+                filled
+            } else {
+                0.0
+            };
+            if filled > 0.0 {
+                order.remaining -= filled;
+                fills.push(resting_fill(order, order.price, filled));
+            }
+        }
+        self.resting.retain(|o| o.remaining > PRICE_EPS);
+    }
+
+    /// The opposite quote moved through a resting order's price: it would
+    /// have been hit, so fill the remainder at the order price.
+    fn match_crossed(&mut self, venue: &Venue, token_id: &str, book: Book, fills: &mut Vec<ModelFill>) {
+        for order in self.resting.iter_mut() {
+            if order.venue != *venue || order.token_id != token_id {
+                continue;
+            }
+            let crossed = match order.side {
+                Side::Buy => book.ask.price.is_some_and(|ask| ask <= order.price - PRICE_EPS),
+                Side::Sell => book.bid.price.is_some_and(|bid| bid >= order.price + PRICE_EPS),
+            };
+            if crossed {
+                fills.push(resting_fill(order, order.price, order.remaining));
+                order.remaining = 0.0;
+            }
+        }
+        self.resting.retain(|o| o.remaining > PRICE_EPS);
+    }
+
+    /// Immediately fill a marketable leg against the best opposite level.
+    pub fn take(
+        &mut self,
+        strategy: &'static str,
+        venue: &Venue,
+        market_id: &str,
+        leg: &OrderLeg,
+        signaled_edge: f64,
+    ) -> Option<ModelFill> {
+        let book = self.books.get(&(venue.clone(), leg.token_id.to_string())).copied().unwrap_or_default();
+        let limit = as_f64(leg.price);
+        let level = match leg.side {
+            Side::Buy => book.ask,
+            Side::Sell => book.bid,
+        };
+        let price = level.price?;
+        let marketable = match leg.side {
+            Side::Buy => price <= limit + PRICE_EPS,
+            Side::Sell => price >= limit - PRICE_EPS,
+        };
+        if !marketable {
+            return None;
+        }
+
+        let size = as_f64(leg.size).min(level.size.unwrap_or(self.config.default_level_size));
+        self.next_id += 1;
+        Some(ModelFill {
+            order_id: self.next_id,
+            strategy,
+            venue: venue.clone(),
+            market_id: market_id.to_string(),
+            token_id: leg.token_id.to_string(),
+            side: leg.side.clone(),
+            price,
+            size,
+            liquidity: Liquidity::Taker,
+            signaled_edge,
+        })
+    }
+
+    /// Rest a passive leg in the queue. A leg that would cross the book is
+    /// taken instead, as the venue would.
+    pub fn place(
+        &mut self,
+        strategy: &'static str,
+        venue: &Venue,
+        market_id: &str,
+        leg: &OrderLeg,
+        signaled_edge: f64,
+    ) -> Option<ModelFill> {
+        if let Some(fill) = self.take(strategy, venue, market_id, leg, signaled_edge) {
+            return Some(fill);
+        }
+
+        let book = self.books.get(&(venue.clone(), leg.token_id.to_string())).copied().unwrap_or_default();
+        let limit = as_f64(leg.price);
+        let same_side = match leg.side {
+            Side::Buy => book.bid,
+            Side::Sell => book.ask,
+        };
+        let queue_ahead = match same_side.price {
+            None => 0.0,
+            Some(best) if (best - limit).abs() <= PRICE_EPS => {
+                same_side.size.unwrap_or(self.config.default_level_size)
+            }
+            Some(best) => {
+                let improves = match leg.side {
+                    Side::Buy => limit > best,
+                    Side::Sell => limit < best,
+                };
+                if improves { 0.0 } else { f64::INFINITY }
+            }
+        };
+
+        self.next_id += 1;
+        self.resting.push(RestingOrder {
+            id: self.next_id,
+            strategy,
+            venue: venue.clone(),
+            market_id: market_id.to_string(),
+            token_id: leg.token_id.to_string(),
+            side: leg.side.clone(),
+            price: limit,
+            remaining: as_f64(leg.size),
+            queue_ahead,
+            signaled_edge,
+        });
+        None
+    }
+
+    /// Cancel every resting order of `strategy` in `market_id` (used when a
+    /// strategy requotes — a new quote replaces the old one).
+    pub fn cancel(&mut self, strategy: &'static str, market_id: &str) {
+        self.resting.retain(|o| !(o.strategy == strategy && o.market_id == market_id));
+    }
+}
+
+fn resting_fill(order: &RestingOrder, price: f64, size: f64) -> ModelFill {
+    ModelFill {
+        order_id: order.id,
+        strategy: order.strategy,
+        venue: order.venue.clone(),
+        market_id: order.market_id.clone(),
+        token_id: order.token_id.clone(),
+        side: order.side.clone(),
+        price,
+        size,
+        liquidity: Liquidity::Maker,
+        signaled_edge: order.signaled_edge,
+    }
+}
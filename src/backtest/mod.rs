@@ -0,0 +1,266 @@
+//! Offline backtesting: archived market events are replayed through the same
+//! cache update and strategy evaluation code the live engine uses, with a
+//! simulated executor standing in for the venue. Time comes from a
+//! [`SimClock`] advanced by event timestamps, so runs are deterministic.
+
+mod account;
+pub mod checkpoint;
+pub mod comparison;
+pub mod control;
+pub mod data;
+pub mod fill_model;
+pub mod monte_carlo;
+pub mod parity;
+pub mod report;
+pub mod sim;
+pub mod walk_forward;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use crate::clock::{Clock, SimClock};
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::market_data::history::event_ts_ms;
+use crate::market_data::market_worker::apply_event;
+use crate::market_data::types::{MarketEvent, Side, Venue};
+use crate::state::equity::EquitySample;
+use crate::state::fees::{FeeSchedule, Liquidity};
+use crate::state::market::as_f64;
+use crate::state::market_cache::MarketCache;
+use crate::state::portfolio::PortfolioSnapshot;
+use crate::strategy::traits::Strategy;
+use account::Account;
+use checkpoint::{Checkpoint, CheckpointConfig};
+use control::{Pacer, ReplayControl, ReplaySpeed};
+use fill_model::QueueModelConfig;
+use sim::SimExecutor;
+
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    /// Archive root written by the archiver or the history downloader.
+    pub data_dir: PathBuf,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub starting_cash: f64,
+    pub fee_schedules: HashMap<Venue, FeeSchedule>,
+    /// Event-time spacing of equity samples.
+    pub equity_interval_ms: u64,
+    /// Simulate queue position for passive orders and depth for takers.
+    /// `None` fills every marketable leg in full at the top of book and
+    /// rejects the rest.
+    pub queue_model: Option<QueueModelConfig>,
+    pub replay: ReplayOptions,
+}
+
+/// Pacing, interactive control, and checkpointing of the replay loop.
+/// The default runs flat out with no checkpoints.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    pub speed: ReplaySpeed,
+    pub control: ReplayControl,
+    pub checkpoint: Option<CheckpointConfig>,
+}
+
+/// A fill produced by the simulated executor.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimFill {
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub liquidity: Liquidity,
+    /// Edge the signal claimed when it was generated.
+    pub signaled_edge: f64,
+}
+
+/// A signal generated during the replay, for comparison against live logs.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimSignal {
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub market_id: String,
+    pub edge: f64,
+}
+
+#[derive(Debug)]
+pub struct BacktestResult {
+    pub events: usize,
+    pub signals: usize,
+    pub signal_log: Vec<SimSignal>,
+    pub fills: Vec<SimFill>,
+    pub equity: Vec<EquitySample>,
+    pub final_snapshot: PortfolioSnapshot,
+    /// token_id → mid (or the one-sided quote) at the end of the run.
+    pub final_marks: HashMap<String, f64>,
+}
+
+/// Replay the archive in `config` through `strategies`.
+///
+/// `universe` supplies market metadata; when `None` it is inferred from the
+/// events (see [`data::infer_universe`]).
+pub async fn run_backtest(
+    config: &BacktestConfig,
+    strategies: Vec<Box<dyn Strategy>>,
+    universe: Option<(MarketMap, TokenToMarket)>,
+) -> anyhow::Result<BacktestResult> {
+    let (data_dir, start_ms, end_ms) = (config.data_dir.clone(), config.start_ms, config.end_ms);
+    let events = tokio::task::spawn_blocking(move || data::load_events(&data_dir, start_ms, end_ms)).await??;
+    let (market_map, token_to_market) = universe.unwrap_or_else(|| data::infer_universe(&events));
+    replay(config, &events, strategies, &market_map, Arc::new(token_to_market)).await
+}
+
+/// Replay already-loaded `events` (sorted by event time) through `strategies`.
+/// Lets callers that run many backtests over one archive load it once.
+pub async fn replay(
+    config: &BacktestConfig,
+    events: &[MarketEvent],
+    strategies: Vec<Box<dyn Strategy>>,
+    market_map: &MarketMap,
+    token_to_market: Arc<TokenToMarket>,
+) -> anyhow::Result<BacktestResult> {
+    let mut results = replay_accounts(config, events, vec![strategies], market_map, token_to_market).await?;
+    Ok(results.remove(0))
+}
+
+/// Replay `events` once, driving one isolated account per strategy set.
+///
+/// Accounts share the market cache and clock but nothing else: each has its
+/// own positions, cash, fill model, and results, returned in input order.
+pub async fn replay_accounts(
+    config: &BacktestConfig,
+    events: &[MarketEvent],
+    strategy_sets: Vec<Vec<Box<dyn Strategy>>>,
+    market_map: &MarketMap,
+    token_to_market: Arc<TokenToMarket>,
+) -> anyhow::Result<Vec<BacktestResult>> {
+    anyhow::ensure!(!strategy_sets.is_empty(), "backtest needs at least one account");
+    info!(
+        events = events.len(),
+        markets = market_map.len(),
+        accounts = strategy_sets.len(),
+        strategies = strategy_sets.iter().map(Vec::len).sum::<usize>(),
+        "backtest starting"
+    );
+
+    let cache = MarketCache::new();
+    let clock = Arc::new(SimClock::new(config.start_ms));
+    let executor = SimExecutor::new(cache.clone(), clock.clone());
+    let mut accounts: Vec<Account> = strategy_sets
+        .into_iter()
+        .map(|strategies| Account::new(config, strategies, &cache, &token_to_market))
+        .collect();
+
+    let mut pacer = Pacer::new(config.replay.speed);
+    let checkpoint = config.replay.checkpoint.as_ref();
+    let mut resume_at = 0;
+
+    if let Some(saved) = checkpoint.filter(|c| c.resume).map(|c| Checkpoint::load(&c.path)).transpose()?.flatten() {
+        anyhow::ensure!(
+            saved.start_ms == config.start_ms && saved.end_ms == config.end_ms && saved.events_processed <= events.len(),
+            "checkpoint was taken for a different replay range"
+        );
+        anyhow::ensure!(saved.accounts.len() == accounts.len(), "checkpoint was taken with a different number of accounts");
+
+        // Rebuild the cache (and fill-model books) without evaluating strategies.
+        for event in &events[..saved.events_processed] {
+            apply_event(&cache, event);
+            for account in accounts.iter_mut() {
+                account.on_market_event(event, event_ts_ms(event));
+            }
+        }
+        for (account, saved) in accounts.iter_mut().zip(&saved.accounts) {
+            account.restore(saved)?;
+        }
+        clock.advance_to(saved.ts_ms);
+        resume_at = saved.events_processed;
+        info!(events_processed = resume_at, "resumed backtest from checkpoint");
+    }
+    let mut next_checkpoint_ms = checkpoint.map_or(u64::MAX, |c| clock.unix_ms() + c.interval_ms);
+
+    for (index, event) in events.iter().enumerate().skip(resume_at) {
+        let ts_ms = event_ts_ms(event);
+        if config.replay.control.gate().await {
+            pacer.reset();
+        }
+        pacer.wait(ts_ms).await;
+        clock.advance_to(ts_ms);
+        let key = apply_event(&cache, event);
+
+        for account in accounts.iter_mut() {
+            account.on_market_event(event, ts_ms);
+        }
+
+        if let Some(state) = cache.get_shared(&key) {
+            for account in accounts.iter_mut() {
+                account
+                    .evaluate(&key, &state, &cache, market_map, &token_to_market, clock.as_ref(), &executor, ts_ms)
+                    .await;
+                account.sample_equity(ts_ms, config.equity_interval_ms);
+            }
+        }
+
+        if let Some(checkpoint) = checkpoint.filter(|_| ts_ms >= next_checkpoint_ms) {
+            Checkpoint {
+                start_ms: config.start_ms,
+                end_ms: config.end_ms,
+                events_processed: index + 1,
+                ts_ms,
+                accounts: accounts.iter().map(Account::checkpoint).collect(),
+            }
+            .save(&checkpoint.path)?;
+            debug!(events_processed = index + 1, ts_ms, "backtest checkpoint written");
+            next_checkpoint_ms = ts_ms + checkpoint.interval_ms;
+        }
+    }
+
+    // A finished run must not be resumed from its last checkpoint.
+    if let Some(checkpoint) = checkpoint
+        && let Err(e) = std::fs::remove_file(&checkpoint.path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        return Err(e.into());
+    }
+
+    let final_marks: HashMap<String, f64> = cache
+        .all()
+        .into_iter()
+        .filter_map(|(key, state)| {
+            let mark = match (state.best_bid, state.best_ask) {
+                (Some(bid), Some(ask)) => Some((bid + ask) / Decimal::TWO),
+                (bid, ask) => bid.or(ask),
+            };
+            mark.map(|m| (key.1.to_string(), as_f64(m)))
+        })
+        .collect();
+
+    Ok(accounts
+        .into_iter()
+        .map(|account| {
+            let final_snapshot = account.portfolio.snapshot();
+            info!(
+                events = events.len(),
+                signals = account.signals,
+                fills = account.fills.len(),
+                equity = final_snapshot.balances.equity,
+                "backtest complete"
+            );
+            BacktestResult {
+                events: events.len(),
+                signals: account.signals,
+                equity: account.equity_samples(),
+                signal_log: account.signal_log,
+                fills: account.fills,
+                final_snapshot,
+                final_marks: final_marks.clone(),
+            }
+        })
+        .collect())
+}
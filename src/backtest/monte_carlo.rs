@@ -0,0 +1,100 @@
+//! Bootstrap resampling of a backtest's trade sequence.
+//!
+//! A single backtest is one ordering of one sample of trades. Resampling the
+//! trades with replacement (and charging random extra slippage) gives a
+//! distribution of outcomes, so a strategy that only looks good because of
+//! a few lucky trades or a benign ordering shows a wide interval.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::backtest::report::group_trades;
+use crate::backtest::BacktestResult;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloConfig {
+    pub iterations: usize,
+    /// Extra adverse slippage per trade, drawn uniformly from
+    /// `[0, max_slippage_bps]` of its notional.
+    pub max_slippage_bps: f64,
+    pub seed: u64,
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self { iterations: 1_000, max_slippage_bps: 0.0, seed: 0 }
+    }
+}
+
+/// 5th / 50th / 95th percentiles of a simulated quantity.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Percentiles {
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonteCarloSummary {
+    pub iterations: usize,
+    pub trades: usize,
+    pub max_slippage_bps: f64,
+    pub pnl: Percentiles,
+    /// Absolute max drawdown of each path's cumulative PnL.
+    pub max_drawdown: Percentiles,
+    /// Fraction of paths that end below zero.
+    pub prob_loss: f64,
+}
+
+/// Bootstrap the trades of `result`. Returns `None` when no trade could be
+/// valued at the final marks.
+pub fn simulate(result: &BacktestResult, config: &MonteCarloConfig) -> Option<MonteCarloSummary> {
+    let trades: Vec<(f64, f64)> = group_trades(&result.fills, &result.final_marks)
+        .into_iter()
+        .filter_map(|t| t.pnl.map(|pnl| (pnl, t.notional)))
+        .collect();
+    if trades.is_empty() || config.iterations == 0 {
+        return None;
+    }
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut pnls = Vec::with_capacity(config.iterations);
+    let mut drawdowns = Vec::with_capacity(config.iterations);
+    let mut losses = 0usize;
+
+    for _ in 0..config.iterations {
+        let (mut cumulative, mut peak, mut worst) = (0.0f64, 0.0f64, 0.0f64);
+        for _ in 0..trades.len() {
+            let (pnl, notional) = trades[rng.gen_range(0..trades.len())];
+            let slippage = if config.max_slippage_bps > 0.0 {
+                notional * rng.gen_range(0.0..config.max_slippage_bps) / 10_000.0
+            } else {
+                0.0
+            };
+            cumulative += pnl - slippage;
+            peak = peak.max(cumulative);
+            worst = worst.max(peak - cumulative);
+        }
+        if cumulative < 0.0 {
+            losses += 1;
+        }
+        pnls.push(cumulative);
+        drawdowns.push(worst);
+    }
+
+    Some(MonteCarloSummary {
+        iterations: config.iterations,
+        trades: trades.len(),
+        max_slippage_bps: config.max_slippage_bps,
+        pnl: percentiles(&mut pnls),
+        max_drawdown: percentiles(&mut drawdowns),
+        prob_loss: losses as f64 / config.iterations as f64,
+    })
+}
+
+fn percentiles(values: &mut [f64]) -> Percentiles {
+    values.sort_by(f64::total_cmp);
+    let at = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+    Percentiles { p5: at(0.05), p50: at(0.50), p95: at(0.95) }
+}
@@ -0,0 +1,187 @@
+//! Live-vs-backtest parity: replay the events archived during a live or
+//! paper session and diff the signals the backtest generates against the
+//! signals the session recorded in its SQLite database. Strategies are pure functions of the
+//! market state, so divergences point at nondeterminism, hidden state, or
+//! lookahead in the backtest path.
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use tracing::info;
+
+use crate::backtest::{run_backtest, BacktestConfig, SimSignal};
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::strategy::traits::Strategy;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParityConfig {
+    /// Max gap between a live signal's recorded time and the matching
+    /// backtest signal's event time. Recorded times are taken at the
+    /// execution bridge, so this covers pipeline latency and clock skew.
+    pub time_tolerance_ms: u64,
+    /// Max absolute edge difference before a matched pair is flagged.
+    pub edge_tolerance: f64,
+}
+
+impl Default for ParityConfig {
+    fn default() -> Self {
+        Self { time_tolerance_ms: 2_000, edge_tolerance: 1e-6 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoggedSignal {
+    pub ts_ms: u64,
+    pub strategy: String,
+    pub market_id: String,
+    pub edge: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EdgeMismatch {
+    pub live: LoggedSignal,
+    pub backtest: LoggedSignal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParityReport {
+    pub live_signals: usize,
+    pub backtest_signals: usize,
+    pub matched: usize,
+    pub edge_mismatches: Vec<EdgeMismatch>,
+    /// Signals the live session logged but the backtest never produced.
+    pub live_only: Vec<LoggedSignal>,
+    /// Signals the backtest produced that the live session never logged.
+    pub backtest_only: Vec<LoggedSignal>,
+}
+
+impl ParityReport {
+    pub fn is_clean(&self) -> bool {
+        self.edge_mismatches.is_empty() && self.live_only.is_empty() && self.backtest_only.is_empty()
+    }
+}
+
+/// Replay `[config.start_ms, config.end_ms)` and diff against the signals
+/// recorded in the SQLite database at `db_path` (`SQLITE_PATH`).
+pub async fn run_parity(
+    config: &BacktestConfig,
+    db_path: &Path,
+    strategies: Vec<Box<dyn Strategy>>,
+    universe: Option<(MarketMap, TokenToMarket)>,
+    parity: ParityConfig,
+) -> anyhow::Result<ParityReport> {
+    let live = recorded_signals(db_path, config.start_ms, config.end_ms)?;
+
+    let result = run_backtest(config, strategies, universe).await?;
+    let backtest: Vec<LoggedSignal> = result.signal_log.iter().map(LoggedSignal::from).collect();
+
+    let report = diff(live, backtest, parity);
+    info!(
+        live = report.live_signals,
+        backtest = report.backtest_signals,
+        matched = report.matched,
+        edge_mismatches = report.edge_mismatches.len(),
+        live_only = report.live_only.len(),
+        backtest_only = report.backtest_only.len(),
+        "parity check complete"
+    );
+    Ok(report)
+}
+
+/// Signals recorded in `[start_ms, end_ms)`, oldest first.
+fn recorded_signals(db_path: &Path, start_ms: u64, end_ms: u64) -> anyhow::Result<Vec<LoggedSignal>> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("failed to open {}", db_path.display()))?;
+    let mut stmt = conn.prepare(
+        "SELECT ts_ms, strategy, market_id, edge FROM signals WHERE ts_ms >= ?1 AND ts_ms < ?2 ORDER BY ts_ms",
+    )?;
+    let bound = |ms: u64| i64::try_from(ms).unwrap_or(i64::MAX);
+    let rows = stmt.query_map(params![bound(start_ms), bound(end_ms)], |row| {
+        Ok(LoggedSignal {
+            ts_ms: row.get::<_, i64>(0)? as u64,
+            strategy: row.get(1)?,
+            market_id: row.get(2)?,
+            edge: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Pair each live signal with the closest-in-time unmatched backtest signal
+/// for the same strategy and market.
+pub fn diff(live: Vec<LoggedSignal>, backtest: Vec<LoggedSignal>, parity: ParityConfig) -> ParityReport {
+    let (live_signals, backtest_signals) = (live.len(), backtest.len());
+
+    let mut pending: HashMap<(String, String), Vec<Option<LoggedSignal>>> = HashMap::new();
+    for signal in backtest {
+        pending.entry((signal.strategy.clone(), signal.market_id.clone())).or_default().push(Some(signal));
+    }
+
+    let mut matched = 0;
+    let mut edge_mismatches = Vec::new();
+    let mut live_only = Vec::new();
+    for signal in live {
+        let candidates = pending.get_mut(&(signal.strategy.clone(), signal.market_id.clone()));
+        let best = candidates.and_then(|candidates| {
+            let closest = candidates
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| c.as_ref().map(|c| (i, c.ts_ms.abs_diff(signal.ts_ms))))
+                .filter(|(_, gap)| *gap <= parity.time_tolerance_ms)
+                .min_by_key(|(_, gap)| *gap)?;
+            candidates[closest.0].take()
+        });
+        match best {
+            Some(backtest) => {
+                matched += 1;
+                if (backtest.edge - signal.edge).abs() > parity.edge_tolerance {
+                    edge_mismatches.push(EdgeMismatch { live: signal, backtest });
+                }
+            }
+            None => live_only.push(signal),
+        }
+    }
+
+    let mut backtest_only: Vec<LoggedSignal> = pending.into_values().flatten().flatten().collect();
+    backtest_only.sort_by_key(|s| s.ts_ms);
+
+    ParityReport { live_signals, backtest_signals, matched, edge_mismatches, live_only, backtest_only }
+}
+
+impl From<&SimSignal> for LoggedSignal {
+    fn from(s: &SimSignal) -> Self {
+        Self { ts_ms: s.ts_ms, strategy: s.strategy.to_string(), market_id: s.market_id.clone(), edge: s.edge }
+    }
+}
+
+impl fmt::Display for ParityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Parity: live {}  backtest {}  matched {}  edge mismatches {}  live-only {}  backtest-only {}",
+            self.live_signals,
+            self.backtest_signals,
+            self.matched,
+            self.edge_mismatches.len(),
+            self.live_only.len(),
+            self.backtest_only.len(),
+        )?;
+        for m in &self.edge_mismatches {
+            writeln!(
+                f,
+                "  edge   {} {} @ {}: live {:.6} vs backtest {:.6}",
+                m.live.strategy, m.live.market_id, m.live.ts_ms, m.live.edge, m.backtest.edge
+            )?;
+        }
+        for s in &self.live_only {
+            writeln!(f, "  live   {} {} @ {} edge {:.6}", s.strategy, s.market_id, s.ts_ms, s.edge)?;
+        }
+        for s in &self.backtest_only {
+            writeln!(f, "  replay {} {} @ {} edge {:.6}", s.strategy, s.market_id, s.ts_ms, s.edge)?;
+        }
+        Ok(())
+    }
+}
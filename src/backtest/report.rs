@@ -0,0 +1,298 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use crate::backtest::monte_carlo::{self, MonteCarloConfig, MonteCarloSummary};
+use crate::backtest::{BacktestConfig, BacktestResult, SimFill};
+use crate::market_data::types::Side;
+use crate::state::equity::{Drawdown, EquityCurve};
+
+const MS_PER_YEAR: f64 = 365.0 * 86_400_000.0;
+/// Prediction markets trade every day, so returns annualize over 365 days.
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Performance summary of one backtest run.
+///
+/// Edge figures are per unit of size. A trade is the set of fills one signal
+/// produced (same strategy, market, and timestamp); its captured edge is the
+/// value of those fills at the end-of-run mid, so it is gross of fees and
+/// only meaningful for markets that were still quoted at the end.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub events: usize,
+    pub signals: usize,
+    pub trades: usize,
+    pub fills: usize,
+    pub starting_equity: f64,
+    pub final_equity: f64,
+    pub total_return: f64,
+    /// `None` when the run covers no time.
+    pub annualized_return: Option<f64>,
+    /// Annualized from daily returns. `None` with fewer than two days.
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub max_drawdown: Drawdown,
+    /// Fraction of trades with positive captured value.
+    pub hit_rate: Option<f64>,
+    pub avg_signaled_edge: Option<f64>,
+    pub avg_captured_edge: Option<f64>,
+    pub taker_fees: f64,
+    pub maker_rebates: f64,
+    pub net_pnl: f64,
+    pub markets: Vec<MarketReport>,
+    /// Bootstrapped PnL / drawdown intervals, if requested.
+    pub monte_carlo: Option<MonteCarloSummary>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketReport {
+    pub market_id: String,
+    pub trades: usize,
+    pub fills: usize,
+    pub volume: f64,
+    /// Realized + unrealized at the final mark, gross of fees.
+    pub pnl: f64,
+    pub hit_rate: Option<f64>,
+    pub avg_signaled_edge: Option<f64>,
+    pub avg_captured_edge: Option<f64>,
+}
+
+/// One signal's worth of fills, valued at the final marks.
+pub(crate) struct Trade<'a> {
+    pub market_id: &'a str,
+    pub signaled_edge: f64,
+    pub captured_edge: Option<f64>,
+    /// Marked value of the fills, gross of fees. `None` if a leg is unpriced.
+    pub pnl: Option<f64>,
+    pub notional: f64,
+}
+
+impl BacktestReport {
+    pub fn new(config: &BacktestConfig, result: &BacktestResult) -> Self {
+        let curve = EquityCurve::new(usize::MAX);
+        for sample in &result.equity {
+            curve.push(*sample);
+        }
+        let starting_equity = config.starting_cash;
+        let final_equity = result.final_snapshot.balances.equity;
+        let total_return = if starting_equity != 0.0 { final_equity / starting_equity - 1.0 } else { 0.0 };
+
+        let span_ms = match (result.equity.first(), result.equity.last()) {
+            (Some(first), Some(last)) => last.ts_ms.saturating_sub(first.ts_ms),
+            _ => 0,
+        };
+        let annualized_return = (span_ms > 0 && total_return > -1.0)
+            .then(|| (1.0 + total_return).powf(MS_PER_YEAR / span_ms as f64) - 1.0);
+
+        let daily: Vec<f64> = curve.daily_returns().iter().skip(1).map(|d| d.return_pct / 100.0).collect();
+        let (sharpe, sortino) = risk_ratios(&daily);
+
+        let trades = group_trades(&result.fills, &result.final_marks);
+        let (hit_rate, avg_signaled_edge, avg_captured_edge) = trade_stats(trades.iter());
+
+        let mut markets: BTreeMap<&str, MarketReport> = BTreeMap::new();
+        for fill in &result.fills {
+            let line = markets.entry(&fill.market_id).or_insert_with(|| MarketReport {
+                market_id: fill.market_id.clone(),
+                ..MarketReport::default()
+            });
+            line.fills += 1;
+            line.volume += fill.price * fill.size;
+        }
+        for position in &result.final_snapshot.positions {
+            if let Some(line) = position.market_id.as_deref().and_then(|m| markets.get_mut(m)) {
+                line.pnl += position.realized_pnl + position.unrealized_pnl;
+            }
+        }
+        for (market_id, line) in markets.iter_mut() {
+            let market_trades: Vec<&Trade> = trades.iter().filter(|t| t.market_id == *market_id).collect();
+            line.trades = market_trades.len();
+            (line.hit_rate, line.avg_signaled_edge, line.avg_captured_edge) = trade_stats(market_trades.into_iter());
+        }
+        let mut markets: Vec<MarketReport> = markets.into_values().collect();
+        markets.sort_by(|a, b| b.pnl.total_cmp(&a.pnl));
+
+        let pnl = &result.final_snapshot.pnl;
+        Self {
+            start_ms: config.start_ms,
+            end_ms: config.end_ms,
+            events: result.events,
+            signals: result.signals,
+            trades: trades.len(),
+            fills: result.fills.len(),
+            starting_equity,
+            final_equity,
+            total_return,
+            annualized_return,
+            sharpe,
+            sortino,
+            max_drawdown: curve.max_drawdown(),
+            hit_rate,
+            avg_signaled_edge,
+            avg_captured_edge,
+            taker_fees: pnl.taker_fees,
+            maker_rebates: pnl.maker_rebates,
+            net_pnl: final_equity - starting_equity,
+            markets,
+            monte_carlo: None,
+        }
+    }
+
+    /// Attach bootstrapped confidence intervals for the run's trades.
+    pub fn with_monte_carlo(mut self, result: &BacktestResult, config: &MonteCarloConfig) -> Self {
+        self.monte_carlo = monte_carlo::simulate(result, config);
+        self
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn save_json(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Group fills into trades: a signal's fills share strategy, market, and timestamp.
+pub(crate) fn group_trades<'a>(fills: &'a [SimFill], final_marks: &HashMap<String, f64>) -> Vec<Trade<'a>> {
+    let mut groups: BTreeMap<(u64, &str, &str), Vec<&SimFill>> = BTreeMap::new();
+    for fill in fills {
+        groups.entry((fill.ts_ms, fill.strategy, &fill.market_id)).or_default().push(fill);
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, _, market_id), fills)| {
+            // Unit edge = marked value / average leg size, so a two-leg arb
+            // captures (mark_yes + mark_no) − (price_yes + price_no).
+            let mut value = Some(0.0);
+            for fill in &fills {
+                let leg = final_marks.get(&fill.token_id).map(|mark| match fill.side {
+                    Side::Buy => (mark - fill.price) * fill.size,
+                    Side::Sell => (fill.price - mark) * fill.size,
+                });
+                value = value.zip(leg).map(|(v, l)| v + l);
+            }
+            let avg_size = fills.iter().map(|f| f.size).sum::<f64>() / fills.len() as f64;
+            Trade {
+                market_id,
+                signaled_edge: fills[0].signaled_edge,
+                captured_edge: value.filter(|_| avg_size > 0.0).map(|v| v / avg_size),
+                pnl: value,
+                notional: fills.iter().map(|f| f.price * f.size).sum(),
+            }
+        })
+        .collect()
+}
+
+/// (hit rate, mean signaled edge, mean captured edge) over `trades`.
+fn trade_stats<'a, 'b: 'a>(trades: impl Iterator<Item = &'a Trade<'b>>) -> (Option<f64>, Option<f64>, Option<f64>) {
+    let (mut n, mut signaled) = (0usize, 0.0);
+    let (mut valued, mut hits, mut captured) = (0usize, 0usize, 0.0);
+    for trade in trades {
+        n += 1;
+        signaled += trade.signaled_edge;
+        if let Some(edge) = trade.captured_edge {
+            valued += 1;
+            captured += edge;
+            if edge > 0.0 {
+                hits += 1;
+            }
+        }
+    }
+    let mean = |sum: f64, count: usize| (count > 0).then(|| sum / count as f64);
+    (mean(hits as f64, valued), mean(signaled, n), mean(captured, valued))
+}
+
+/// Annualized (Sharpe, Sortino) of daily returns, risk-free rate zero.
+fn risk_ratios(daily: &[f64]) -> (Option<f64>, Option<f64>) {
+    if daily.len() < 2 {
+        return (None, None);
+    }
+    let n = daily.len() as f64;
+    let mean = daily.iter().sum::<f64>() / n;
+    let std = (daily.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    let downside = (daily.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+    let annualize = DAYS_PER_YEAR.sqrt();
+    (
+        (std > 0.0).then(|| mean / std * annualize),
+        (downside > 0.0).then(|| mean / downside * annualize),
+    )
+}
+
+// ── Human-readable summary ───────────────────────────────────────
+
+fn pct(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}%", v * 100.0)).unwrap_or_else(|| "n/a".to_string())
+}
+
+fn num(value: Option<f64>) -> String {
+    value.map(|v| format!("{v:.4}")).unwrap_or_else(|| "n/a".to_string())
+}
+
+impl fmt::Display for BacktestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Backtest {} → {}", fmt_ms(self.start_ms), fmt_ms(self.end_ms))?;
+        writeln!(f, "  events {}  signals {}  trades {}  fills {}", self.events, self.signals, self.trades, self.fills)?;
+        writeln!(f, "  equity        {:.2} → {:.2}  (net {:+.2})", self.starting_equity, self.final_equity, self.net_pnl)?;
+        writeln!(f, "  return        {}  annualized {}", pct(Some(self.total_return)), pct(self.annualized_return))?;
+        writeln!(f, "  sharpe        {}  sortino {}", num(self.sharpe), num(self.sortino))?;
+        writeln!(f, "  max drawdown  {:.2} ({})", self.max_drawdown.absolute, pct(Some(self.max_drawdown.fraction)))?;
+        writeln!(f, "  hit rate      {}", pct(self.hit_rate))?;
+        writeln!(f, "  edge          signaled {}  captured {}", num(self.avg_signaled_edge), num(self.avg_captured_edge))?;
+        writeln!(f, "  fees          taker {:.2}  rebates {:.2}", self.taker_fees, self.maker_rebates)?;
+        if let Some(mc) = &self.monte_carlo {
+            writeln!(
+                f,
+                "  monte carlo   {} paths × {} trades, slippage ≤ {} bps",
+                mc.iterations, mc.trades, mc.max_slippage_bps
+            )?;
+            writeln!(f, "    pnl         p5 {:.2}  p50 {:.2}  p95 {:.2}", mc.pnl.p5, mc.pnl.p50, mc.pnl.p95)?;
+            writeln!(
+                f,
+                "    drawdown    p5 {:.2}  p50 {:.2}  p95 {:.2}",
+                mc.max_drawdown.p5, mc.max_drawdown.p50, mc.max_drawdown.p95
+            )?;
+            writeln!(f, "    P(loss)     {}", pct(Some(mc.prob_loss)))?;
+        }
+
+        if !self.markets.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "  {:<24} {:>6} {:>10} {:>10} {:>8} {:>9} {:>9}", "market", "trades", "volume", "pnl", "hit", "signaled", "captured")?;
+            for m in &self.markets {
+                writeln!(
+                    f,
+                    "  {:<24} {:>6} {:>10.2} {:>10.2} {:>8} {:>9} {:>9}",
+                    truncate(&m.market_id, 24),
+                    m.trades,
+                    m.volume,
+                    m.pnl,
+                    pct(m.hit_rate),
+                    num(m.avg_signaled_edge),
+                    num(m.avg_captured_edge),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn fmt_ms(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ms.to_string())
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => &s[..idx],
+        None => s,
+    }
+}
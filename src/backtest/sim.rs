@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::clock::SharedClock;
+use crate::execution::traits::{ExecutionEngine, ExecutionIntent, ExecutionReport, LegFillStatus};
+use crate::market_data::types::Side;
+use crate::state::market::as_f64;
+use crate::state::market_cache::{MarketCache, MarketKey};
+
+/// Fills orders against the top of book in the backtest's market cache.
+///
+/// A buy fills at the recorded best ask if that is at or below the limit,
+/// a sell at the best bid if at or above it; otherwise the leg is rejected.
+/// Recorded quotes carry no depth, so a fill is always for the full size.
+pub struct SimExecutor {
+    cache: MarketCache,
+    clock: SharedClock,
+    next_order_id: AtomicU64,
+}
+
+impl SimExecutor {
+    pub fn new(cache: MarketCache, clock: SharedClock) -> Self {
+        Self { cache, clock, next_order_id: AtomicU64::new(1) }
+    }
+}
+
+#[async_trait]
+impl ExecutionEngine for SimExecutor {
+    async fn execute(&self, intent: ExecutionIntent) -> ExecutionReport {
+        let leg_results = intent
+            .legs
+            .iter()
+            .map(|leg| {
+                let state = self.cache.get_market_state(&MarketKey(intent.venue.clone(), leg.token_id));
+                let fill_price = match leg.side {
+                    Side::Buy => state.and_then(|s| s.best_ask).filter(|ask| *ask <= leg.price),
+                    Side::Sell => state.and_then(|s| s.best_bid).filter(|bid| *bid >= leg.price),
+                };
+                match fill_price {
+                    Some(price) => LegFillStatus::Filled {
+                        order_id: format!("sim-{}", self.next_order_id.fetch_add(1, Ordering::Relaxed)),
+                        avg_price: as_f64(price),
+                        filled_size: as_f64(leg.size),
+                    },
+                    None => LegFillStatus::Rejected { reason: "no liquidity at limit".to_string() },
+                }
+            })
+            .collect();
+
+        ExecutionReport {
+            market_id: intent.market_id,
+            strategy_name: intent.strategy_name,
+            leg_results,
+            completed_at: self.clock.now(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Walk-forward analysis: choose parameters on a rolling training window,
+//! then score them on the following, unseen test window. The out-of-sample
+//! numbers are what a parameter sweep would actually have earned.
+
+use serde::Serialize;
+use std::fmt::Debug;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::backtest::report::BacktestReport;
+use crate::backtest::{data, replay, replay_accounts, BacktestConfig, ReplayOptions};
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::market_data::history::event_ts_ms;
+use crate::market_data::types::MarketEvent;
+use crate::strategy::traits::Strategy;
+
+#[derive(Debug, Clone, Copy)]
+pub struct WalkForwardConfig {
+    pub train_ms: u64,
+    pub test_ms: u64,
+    /// How far each window advances. Equal to `test_ms` gives back-to-back,
+    /// non-overlapping test windows.
+    pub step_ms: u64,
+}
+
+/// Metric a candidate is ranked by on its training window. Higher is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    NetPnl,
+    Sharpe,
+    Sortino,
+    /// Return divided by max drawdown fraction.
+    ReturnOverDrawdown,
+}
+
+impl Objective {
+    pub fn score(&self, report: &BacktestReport) -> f64 {
+        match self {
+            Objective::NetPnl => report.net_pnl,
+            Objective::Sharpe => report.sharpe.unwrap_or(f64::NEG_INFINITY),
+            Objective::Sortino => report.sortino.unwrap_or(f64::NEG_INFINITY),
+            Objective::ReturnOverDrawdown => {
+                if report.max_drawdown.fraction > 0.0 {
+                    report.total_return / report.max_drawdown.fraction
+                } else {
+                    report.total_return
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowResult<P> {
+    pub train_start_ms: u64,
+    pub train_end_ms: u64,
+    pub test_start_ms: u64,
+    pub test_end_ms: u64,
+    pub params: P,
+    pub train_score: f64,
+    pub test_score: f64,
+    pub test_report: BacktestReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalkForwardReport<P> {
+    pub windows: Vec<WindowResult<P>>,
+    /// Sum of test-window net PnL — the out-of-sample result.
+    pub oos_net_pnl: f64,
+    pub mean_train_score: f64,
+    pub mean_test_score: f64,
+    /// mean test score / mean train score. Well below 1 suggests the
+    /// training scores came from fitting noise.
+    pub efficiency: Option<f64>,
+}
+
+/// Run walk-forward analysis over `[base.start_ms, base.end_ms)`.
+///
+/// `build` turns a parameter set into fresh strategy instances; it is called
+/// once per candidate per window so no state leaks between runs. All
+/// candidates of a training window are replayed in a single pass.
+pub async fn run_walk_forward<P, F>(
+    base: &BacktestConfig,
+    wf: WalkForwardConfig,
+    candidates: &[P],
+    objective: Objective,
+    build: F,
+    universe: Option<(MarketMap, TokenToMarket)>,
+) -> anyhow::Result<WalkForwardReport<P>>
+where
+    P: Clone + Debug,
+    F: Fn(&P) -> Vec<Box<dyn Strategy>>,
+{
+    anyhow::ensure!(!candidates.is_empty(), "walk-forward needs at least one parameter set");
+    anyhow::ensure!(wf.train_ms > 0 && wf.test_ms > 0 && wf.step_ms > 0, "walk-forward windows must be non-empty");
+
+    let (data_dir, start_ms, end_ms) = (base.data_dir.clone(), base.start_ms, base.end_ms);
+    let events = tokio::task::spawn_blocking(move || data::load_events(&data_dir, start_ms, end_ms)).await??;
+    let (market_map, token_to_market) = universe.unwrap_or_else(|| data::infer_universe(&events));
+    let token_to_market = Arc::new(token_to_market);
+
+    let mut windows = Vec::new();
+    let mut train_start = base.start_ms;
+    while train_start + wf.train_ms + wf.test_ms <= base.end_ms {
+        let train_end = train_start + wf.train_ms;
+        let test_end = train_end + wf.test_ms;
+
+        let train_config = window_config(base, train_start, train_end);
+        let train_events = slice(&events, train_start, train_end);
+        // Every candidate trains in the same pass, each in its own account.
+        let strategy_sets = candidates.iter().map(&build).collect();
+        let results =
+            replay_accounts(&train_config, train_events, strategy_sets, &market_map, Arc::clone(&token_to_market)).await?;
+        let mut best: Option<(&P, f64)> = None;
+        for (params, result) in candidates.iter().zip(&results) {
+            let score = objective.score(&BacktestReport::new(&train_config, result));
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((params, score));
+            }
+        }
+        let (params, train_score) = best.expect("candidates is non-empty");
+
+        let test_config = window_config(base, train_end, test_end);
+        let test_events = slice(&events, train_end, test_end);
+        let result = replay(&test_config, test_events, build(params), &market_map, Arc::clone(&token_to_market)).await?;
+        let test_report = BacktestReport::new(&test_config, &result);
+        let test_score = objective.score(&test_report);
+
+        info!(
+            train_start_ms = train_start,
+            test_start_ms = train_end,
+            params = ?params,
+            train_score,
+            test_score,
+            "walk-forward window complete"
+        );
+        windows.push(WindowResult {
+            train_start_ms: train_start,
+            train_end_ms: train_end,
+            test_start_ms: train_end,
+            test_end_ms: test_end,
+            params: params.clone(),
+            train_score,
+            test_score,
+            test_report,
+        });
+        train_start += wf.step_ms;
+    }
+    anyhow::ensure!(!windows.is_empty(), "range is shorter than one train + test window");
+
+    let n = windows.len() as f64;
+    let mean_train_score = windows.iter().map(|w| w.train_score).sum::<f64>() / n;
+    let mean_test_score = windows.iter().map(|w| w.test_score).sum::<f64>() / n;
+    Ok(WalkForwardReport {
+        oos_net_pnl: windows.iter().map(|w| w.test_report.net_pnl).sum(),
+        mean_train_score,
+        mean_test_score,
+        efficiency: (mean_train_score.is_finite() && mean_train_score > 0.0 && mean_test_score.is_finite())
+            .then(|| mean_test_score / mean_train_score),
+        windows,
+    })
+}
+
+fn window_config(base: &BacktestConfig, start_ms: u64, end_ms: u64) -> BacktestConfig {
+    // Windows run unpaced and without checkpoints whatever the base says.
+    BacktestConfig { start_ms, end_ms, replay: ReplayOptions::default(), ..base.clone() }
+}
+
+/// Events with `start_ms <= ts < end_ms`. `events` is sorted by event time.
+fn slice(events: &[MarketEvent], start_ms: u64, end_ms: u64) -> &[MarketEvent] {
+    let lo = events.partition_point(|e| event_ts_ms(e) < start_ms);
+    let hi = events.partition_point(|e| event_ts_ms(e) < end_ms);
+    &events[lo..hi]
+}
@@ -0,0 +1,452 @@
+//! Bounded channel with a configurable overflow policy.
+//!
+//! `tokio::sync::mpsc` can only wait or fail when full, so a market-data
+//! burst either stalls the adapter's socket reader or loses the freshest
+//! update. This channel adds drop-oldest, which keeps the producer moving and
+//! the newest prices queued. The market-data stages use it; the rest of the
+//! pipeline keeps mpsc with a fixed policy per stage (signals wait, record
+//! sinks drop the newest). Event observers read from a broadcast bus instead;
+//! see `market_data::bus`.
+//!
+//! [`coalescing`] is a keyed variant for notifications that only say "this
+//! key changed": a key already queued is not queued again.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use tokio::sync::{Notify, mpsc};
+
+use crate::metrics::channels::{record_channel_coalesced, record_channel_overflow};
+
+/// What a send does when the channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for space. Lossless, but a slow consumer stalls the producer.
+    Block,
+    /// Evict the oldest queued message to make room.
+    DropOldest,
+    /// Discard the message being sent.
+    DropNewest,
+}
+
+impl Overflow {
+    pub fn name(self) -> &'static str {
+        match self {
+            Overflow::Block => "block",
+            Overflow::DropOldest => "drop_oldest",
+            Overflow::DropNewest => "drop_newest",
+        }
+    }
+}
+
+impl FromStr for Overflow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "block" => Ok(Overflow::Block),
+            "drop_oldest" => Ok(Overflow::DropOldest),
+            "drop_newest" => Ok(Overflow::DropNewest),
+            _ => anyhow::bail!("'{s}' is not an overflow policy (expected block, drop_oldest, or drop_newest)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelConfig {
+    pub capacity: usize,
+    pub overflow: Overflow,
+}
+
+/// Create a channel. `name` labels `channel_overflow_total` when messages are dropped.
+pub fn bounded<T>(name: &'static str, config: ChannelConfig) -> (Sender<T>, Receiver<T>) {
+    assert!(config.capacity > 0, "channel {name} needs a capacity of at least 1");
+    let shared = Arc::new(Shared {
+        name,
+        config,
+        queue: Mutex::new(VecDeque::with_capacity(config.capacity)),
+        items: Notify::new(),
+        space: Notify::new(),
+        senders: AtomicUsize::new(1),
+        receiver_gone: AtomicBool::new(false),
+    });
+    (Sender { shared: Arc::clone(&shared) }, Receiver { shared })
+}
+
+struct Shared<T> {
+    name: &'static str,
+    config: ChannelConfig,
+    queue: Mutex<VecDeque<T>>,
+    /// Signalled when a message is queued or the last sender goes.
+    items: Notify,
+    /// Signalled when a message is taken or the receiver goes.
+    space: Notify,
+    senders: AtomicUsize,
+    receiver_gone: AtomicBool,
+}
+
+impl<T> Shared<T> {
+    fn queue(&self) -> MutexGuard<'_, VecDeque<T>> {
+        self.queue.lock().expect("channel queue poisoned")
+    }
+}
+
+// ── Sender ────────────────────────────────────────────────────────────────────
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The receiver is gone; the message is handed back.
+#[derive(Debug)]
+pub struct Closed<T>(pub T);
+
+impl<T> Sender<T> {
+    /// Queue `value`, applying the overflow policy if the channel is full.
+    /// `Ok` means the channel is open, not that this message was kept.
+    pub async fn send(&self, value: T) -> Result<(), Closed<T>> {
+        loop {
+            // Registered before the capacity check so a receive in between
+            // still wakes this send.
+            let space = self.shared.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+
+            if self.is_closed() {
+                return Err(Closed(value));
+            }
+            {
+                let mut queue = self.shared.queue();
+                if queue.len() < self.shared.config.capacity {
+                    queue.push_back(value);
+                    drop(queue);
+                    self.shared.items.notify_one();
+                    return Ok(());
+                }
+                match self.shared.config.overflow {
+                    Overflow::Block => {}
+                    Overflow::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(value);
+                        drop(queue);
+                        record_channel_overflow(self.shared.name, Overflow::DropOldest, 1);
+                        return Ok(());
+                    }
+                    Overflow::DropNewest => {
+                        drop(queue);
+                        record_channel_overflow(self.shared.name, Overflow::DropNewest, 1);
+                        return Ok(());
+                    }
+                }
+            }
+            space.await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.queue().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn max_capacity(&self) -> usize {
+        self.shared.config.capacity
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.receiver_gone.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.items.notify_one();
+        }
+    }
+}
+
+// ── Receiver ──────────────────────────────────────────────────────────────────
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Next message, or `None` once every sender is gone and the queue is drained.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            let items = self.shared.items.notified();
+            tokio::pin!(items);
+            items.as_mut().enable();
+
+            let next = self.shared.queue().pop_front();
+            if let Some(value) = next {
+                self.shared.space.notify_one();
+                return Some(value);
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+            items.await;
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_gone.store(true, Ordering::Release);
+        self.shared.space.notify_waiters();
+    }
+}
+
+// ── Coalescing ────────────────────────────────────────────────────────────────
+
+/// Create a keyed channel holding at most one pending value per key. Sending
+/// a key that is already queued replaces its value but keeps its place, so a
+/// consumer that falls behind handles each key once, with its latest value,
+/// instead of working through every intermediate update. At most `capacity`
+/// keys are pending; a new key sent while full is dropped.
+pub fn coalescing<K: Hash + Eq + Clone, V>(
+    name: &'static str,
+    capacity: usize,
+) -> (CoalescingSender<K, V>, CoalescingReceiver<K, V>) {
+    assert!(capacity > 0, "channel {name} needs a capacity of at least 1");
+    let shared = Arc::new(KeyedShared {
+        name,
+        capacity,
+        pending: Mutex::new(Pending { order: VecDeque::new(), values: HashMap::new() }),
+        items: Notify::new(),
+        senders: AtomicUsize::new(1),
+        receiver_gone: AtomicBool::new(false),
+    });
+    (CoalescingSender { shared: Arc::clone(&shared) }, CoalescingReceiver { shared })
+}
+
+/// Keys in the order they first became pending, and each one's latest value.
+struct Pending<K, V> {
+    order: VecDeque<K>,
+    values: HashMap<K, V>,
+}
+
+struct KeyedShared<K, V> {
+    name: &'static str,
+    capacity: usize,
+    pending: Mutex<Pending<K, V>>,
+    /// Signalled when a key becomes pending or the last sender goes.
+    items: Notify,
+    senders: AtomicUsize,
+    receiver_gone: AtomicBool,
+}
+
+impl<K, V> KeyedShared<K, V> {
+    fn pending(&self) -> MutexGuard<'_, Pending<K, V>> {
+        self.pending.lock().expect("coalescing channel poisoned")
+    }
+}
+
+impl<K: Hash + Eq, V> KeyedShared<K, V> {
+    fn pop(&self) -> Option<(K, V)> {
+        let mut pending = self.pending();
+        pending.order.pop_front().map(|key| {
+            let value = pending.values.remove(&key).expect("pending key without a value");
+            (key, value)
+        })
+    }
+}
+
+pub struct CoalescingSender<K, V> {
+    shared: Arc<KeyedShared<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V> CoalescingSender<K, V> {
+    /// Mark `key` pending with `value`. Never waits.
+    pub fn send(&self, key: K, value: V) -> Result<(), Closed<V>> {
+        if self.is_closed() {
+            return Err(Closed(value));
+        }
+        let mut pending = self.shared.pending();
+        if let Some(slot) = pending.values.get_mut(&key) {
+            *slot = value;
+            drop(pending);
+            record_channel_coalesced(self.shared.name, 1);
+            return Ok(());
+        }
+        if pending.order.len() >= self.shared.capacity {
+            drop(pending);
+            record_channel_overflow(self.shared.name, Overflow::DropNewest, 1);
+            return Ok(());
+        }
+        pending.order.push_back(key.clone());
+        pending.values.insert(key, value);
+        drop(pending);
+        self.shared.items.notify_one();
+        Ok(())
+    }
+
+    /// Mark every key in `entries` pending, as [`send`](Self::send) does for
+    /// one, under a single lock and with a single wakeup. Never waits.
+    pub fn send_batch(&self, entries: impl IntoIterator<Item = (K, V)>) -> Result<(), Closed<()>> {
+        if self.is_closed() {
+            return Err(Closed(()));
+        }
+        let (mut queued, mut coalesced, mut dropped) = (0u64, 0u64, 0u64);
+        {
+            let mut pending = self.shared.pending();
+            for (key, value) in entries {
+                if let Some(slot) = pending.values.get_mut(&key) {
+                    *slot = value;
+                    coalesced += 1;
+                } else if pending.order.len() >= self.shared.capacity {
+                    dropped += 1;
+                } else {
+                    pending.order.push_back(key.clone());
+                    pending.values.insert(key, value);
+                    queued += 1;
+                }
+            }
+        }
+        if coalesced > 0 {
+            record_channel_coalesced(self.shared.name, coalesced);
+        }
+        if dropped > 0 {
+            record_channel_overflow(self.shared.name, Overflow::DropNewest, dropped);
+        }
+        if queued > 0 {
+            self.shared.items.notify_one();
+        }
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.receiver_gone.load(Ordering::Acquire)
+    }
+}
+
+impl<K, V> Clone for CoalescingSender<K, V> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl<K, V> Drop for CoalescingSender<K, V> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.items.notify_one();
+        }
+    }
+}
+
+pub struct CoalescingReceiver<K, V> {
+    shared: Arc<KeyedShared<K, V>>,
+}
+
+impl<K: Hash + Eq, V> CoalescingReceiver<K, V> {
+    /// The longest-pending key and its latest value, if any, without waiting.
+    pub fn try_recv(&mut self) -> Option<(K, V)> {
+        self.shared.pop()
+    }
+
+    /// The longest-pending key and its latest value, or `None` once every
+    /// sender is gone and nothing is pending.
+    pub async fn recv(&mut self) -> Option<(K, V)> {
+        loop {
+            let items = self.shared.items.notified();
+            tokio::pin!(items);
+            items.as_mut().enable();
+
+            let next = self.shared.pop();
+            if next.is_some() {
+                return next;
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                return None;
+            }
+            items.await;
+        }
+    }
+}
+
+impl<K, V> Drop for CoalescingReceiver<K, V> {
+    fn drop(&mut self) {
+        self.shared.receiver_gone.store(true, Ordering::Release);
+    }
+}
+
+// ── Monitoring ────────────────────────────────────────────────────────────────
+
+/// `(depth, capacity)`, or `None` once every sender is gone.
+pub type DepthProbe = Box<dyn Fn() -> Option<(usize, usize)> + Send + Sync>;
+/// Whether the receiver and at least one sender are alive.
+pub type OpenProbe = Box<dyn Fn() -> bool + Send + Sync>;
+
+/// A channel the health registry and channel monitor can sample. Probes hold
+/// no sender, so watching a channel never keeps it open.
+pub trait Watch {
+    fn depth_probe(&self) -> DepthProbe;
+    fn open_probe(&self) -> OpenProbe;
+}
+
+impl<T: Send + 'static> Watch for mpsc::Sender<T> {
+    fn depth_probe(&self) -> DepthProbe {
+        let weak = self.downgrade();
+        Box::new(move || weak.upgrade().map(|tx| (tx.max_capacity() - tx.capacity(), tx.max_capacity())))
+    }
+
+    fn open_probe(&self) -> OpenProbe {
+        let weak = self.downgrade();
+        Box::new(move || weak.upgrade().is_some_and(|tx| !tx.is_closed()))
+    }
+}
+
+impl<T: Send + 'static> Watch for Sender<T> {
+    fn depth_probe(&self) -> DepthProbe {
+        let weak = Arc::downgrade(&self.shared);
+        Box::new(move || {
+            let shared = live(&weak)?;
+            Some((shared.queue().len(), shared.config.capacity))
+        })
+    }
+
+    fn open_probe(&self) -> OpenProbe {
+        let weak = Arc::downgrade(&self.shared);
+        Box::new(move || live(&weak).is_some_and(|shared| !shared.receiver_gone.load(Ordering::Acquire)))
+    }
+}
+
+impl<K: Send + 'static, V: Send + 'static> Watch for CoalescingSender<K, V> {
+    fn depth_probe(&self) -> DepthProbe {
+        let weak = Arc::downgrade(&self.shared);
+        Box::new(move || {
+            let shared = weak.upgrade().filter(|shared| shared.senders.load(Ordering::Acquire) > 0)?;
+            let depth = shared.pending().order.len();
+            Some((depth, shared.capacity))
+        })
+    }
+
+    fn open_probe(&self) -> OpenProbe {
+        let weak = Arc::downgrade(&self.shared);
+        Box::new(move || {
+            weak.upgrade().is_some_and(|shared| {
+                shared.senders.load(Ordering::Acquire) > 0 && !shared.receiver_gone.load(Ordering::Acquire)
+            })
+        })
+    }
+}
+
+fn live<T>(weak: &Weak<Shared<T>>) -> Option<Arc<Shared<T>>> {
+    weak.upgrade().filter(|shared| shared.senders.load(Ordering::Acquire) > 0)
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+use tracing::info;
+
+use prediction_engine::backtest::checkpoint::CheckpointConfig;
+use prediction_engine::backtest::control::ReplaySpeed;
+use prediction_engine::backtest::fill_model::QueueModelConfig;
+use prediction_engine::backtest::monte_carlo::MonteCarloConfig;
+use prediction_engine::backtest::report::BacktestReport;
+use prediction_engine::backtest::{run_backtest, BacktestConfig, ReplayOptions};
+use prediction_engine::market_data::types::Venue;
+use prediction_engine::market_data::universe::Universe;
+use prediction_engine::state::fees::FeeSchedule;
+use prediction_engine::strategy::arbitrage::ArbitrageStrategy;
+use prediction_engine::strategy::market_maker::{MarketMakerConfig, MarketMakerStrategy};
+use prediction_engine::strategy::traits::Strategy;
+
+use super::{parse_day_ms, BacktestArgs, StrategyKind};
+use crate::config::Config;
+
+/// `backtest` subcommand: replay the archive, print the summary, and
+/// optionally write the JSON report.
+pub async fn run(args: BacktestArgs, config: &Config) -> anyhow::Result<()> {
+    let start_ms = parse_day_ms(&args.from)?;
+    let end_ms = parse_day_ms(&args.to)?;
+    anyhow::ensure!(start_ms < end_ms, "--from must be before --to");
+
+    let data_dir = args
+        .data
+        .clone()
+        .or_else(|| config.archive.as_ref().map(|a| a.dir.clone()))
+        .unwrap_or_else(|| "data/archive".into());
+
+    let mut fees = FeeSchedule::polymarket_default();
+    if let Some(bps) = args.taker_fee_bps {
+        fees.taker_fee_bps = bps;
+    }
+    if let Some(bps) = args.maker_rebate_bps {
+        fees.maker_rebate_bps = bps;
+    }
+
+    let backtest = BacktestConfig {
+        data_dir,
+        start_ms,
+        end_ms,
+        starting_cash: args.starting_cash,
+        fee_schedules: [(Venue::Polymarket, fees)].into(),
+        equity_interval_ms: args.equity_interval_secs * 1_000,
+        queue_model: args.queue_model.then(QueueModelConfig::default),
+        replay: ReplayOptions {
+            speed: args.speed.map_or(ReplaySpeed::Max, ReplaySpeed::Multiplier),
+            checkpoint: args.checkpoint.clone().map(|path| CheckpointConfig {
+                path,
+                interval_ms: Duration::from_secs(args.checkpoint_interval_secs).as_millis() as u64,
+                resume: args.resume,
+            }),
+            ..ReplayOptions::default()
+        },
+    };
+
+    let universe = match &args.universe {
+        Some(path) => {
+            let universe = Universe::load(path)?;
+            Some((universe.market_map(), universe.token_to_market.into_iter().collect()))
+        }
+        None => None,
+    };
+
+    info!(
+        data = %backtest.data_dir.display(),
+        from = %args.from,
+        to = %args.to,
+        strategies = ?args.strategy,
+        "backtest starting"
+    );
+    let result = run_backtest(&backtest, build_strategies(&args)?, universe).await?;
+
+    let mut report = BacktestReport::new(&backtest, &result);
+    if args.monte_carlo > 0 {
+        report = report.with_monte_carlo(&result, &MonteCarloConfig {
+            iterations: args.monte_carlo,
+            max_slippage_bps: args.slippage_bps,
+            ..MonteCarloConfig::default()
+        });
+    }
+
+    println!("{report}");
+    if let Some(path) = &args.report {
+        report.save_json(path)?;
+        info!(path = %path.display(), "backtest report written");
+    }
+    Ok(())
+}
+
+fn build_strategies(args: &BacktestArgs) -> anyhow::Result<Vec<Box<dyn Strategy>>> {
+    let mut kinds = args.strategy.clone();
+    kinds.dedup();
+    kinds
+        .into_iter()
+        .map(|kind| -> anyhow::Result<Box<dyn Strategy>> {
+            Ok(match kind {
+                StrategyKind::Arbitrage => Box::new(ArbitrageStrategy::new(args.min_edge, args.size)?),
+                StrategyKind::MarketMaker => {
+                    let mut mm = MarketMakerConfig::default();
+                    if let Some(v) = args.half_spread {
+                        mm.half_spread = v;
+                    }
+                    if let Some(v) = args.quote_size {
+                        mm.quote_size = v;
+                    }
+                    if let Some(v) = args.max_inventory {
+                        mm.max_inventory = v;
+                    }
+                    Box::new(MarketMakerStrategy::new(mm)?)
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use prediction_engine::engine::EngineBuilder;
+use prediction_engine::execution::paper::PaperExecutor;
+use prediction_engine::execution::traits::{ExecutionEngine, ExecutionIntent, ExecutionReport};
+use prediction_engine::market_data::adapters::polymarket::{MarketInfo, MarketMap, TokenToMarket};
+use prediction_engine::market_data::pool;
+use prediction_engine::market_data::types::{MarketEvent, MarketEventKind, Venue};
+use prediction_engine::risk::RiskConfig;
+use prediction_engine::state::ids::TokenId;
+use prediction_engine::state::market_cache::MarketCache;
+use prediction_engine::state::portfolio::Portfolio;
+use prediction_engine::state::position::PositionTracker;
+use prediction_engine::strategy::arbitrage::ArbitrageStrategy;
+
+use super::BenchArgs;
+use crate::config::Config;
+
+/// The last trade counts as the end of the run once none follow for this long.
+const DRAIN_QUIET: Duration = Duration::from_millis(250);
+const DRAIN_POLL: Duration = Duration::from_millis(10);
+/// Price steps per market before YES asks repeat. Each step is 1e-6, so asks
+/// stay between 0.40 and 0.45 and every tick is a distinct buy arb the
+/// bridge's duplicate filter lets through.
+const PRICE_STEPS: u64 = 50_000;
+
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    markets: usize,
+    events: u64,
+    events_per_sec: f64,
+    trades: usize,
+    trades_per_sec: f64,
+    p50_us: u64,
+    p90_us: u64,
+    p99_us: u64,
+    max_us: u64,
+}
+
+/// `bench` subcommand: feed synthetic top-of-book updates through the real
+/// router, market cache, arbitrage strategy and execution bridge into the
+/// paper executor, and report tick-to-trade latency and throughput.
+/// Nothing touches the network, the database or the journal.
+pub async fn run(args: BenchArgs, config: &Config) -> anyhow::Result<()> {
+    anyhow::ensure!(args.markets > 0, "--markets must be at least 1");
+
+    let (market_map, token_to_market) = universe(args.markets);
+    let cache = MarketCache::new();
+    let executor = Arc::new(TimedExecutor::new());
+
+    // Same channels and placement as `run`, but no persistence or alerting.
+    let builder = EngineBuilder::new(config.channels.pipeline());
+    let event_tx = builder.event_sender();
+    let engine = builder
+        .markets(Arc::clone(&market_map), Arc::clone(&token_to_market))
+        .strategy(Box::new(ArbitrageStrategy::new(0.025, 5.0)?))
+        .executor(executor.clone(), "paper")
+        .risk(RiskConfig { max_drawdown: 1.0, max_daily_notional: f64::INFINITY })
+        .portfolio(Portfolio::new(PositionTracker::new(), cache.clone(), Arc::clone(&token_to_market), f64::MAX))
+        .cache(cache)
+        .priority_edge(config.priority_edge)
+        .signal_filter(config.signal_filter)
+        .hot_path(config.hot_path.clone())
+        .busy_poll(config.busy_poll)
+        .build();
+    let mut running = engine.start()?;
+
+    // Seed both books with no edge so only the timed ticks trade.
+    for info in market_map.values() {
+        let mut batch = pool::batch();
+        batch.push(tick(info, info.no_token_id, Decimal::new(49, 2), Decimal::new(50, 2)));
+        batch.push(tick(info, info.yes_token_id, Decimal::new(59, 2), Decimal::new(60, 2)));
+        event_tx.send(batch).await.ok();
+    }
+    tokio::time::sleep(DRAIN_QUIET).await;
+    executor.reset();
+
+    let markets: Vec<&MarketInfo> = market_map.values().collect();
+    let start = Instant::now();
+    for seq in 0..args.events {
+        if args.rate > 0 {
+            let due = start + Duration::from_secs_f64(seq as f64 / args.rate as f64);
+            if due > Instant::now() {
+                tokio::time::sleep_until(due.into()).await;
+            }
+        }
+        let info = markets[(seq % markets.len() as u64) as usize];
+        let step = (seq / markets.len() as u64 % PRICE_STEPS) as i64;
+        let ask = Decimal::new(400_000 + step, 6);
+        let bid = ask - Decimal::new(1, 2);
+        if event_tx.send(pool::single(tick(info, info.yes_token_id, bid, ask))).await.is_err() {
+            anyhow::bail!("pipeline stopped during the run");
+        }
+    }
+
+    let mut settled = (executor.count(), Instant::now());
+    loop {
+        tokio::time::sleep(DRAIN_POLL).await;
+        let count = executor.count();
+        if count != settled.0 {
+            settled = (count, Instant::now());
+        } else if settled.1.elapsed() >= DRAIN_QUIET {
+            break;
+        }
+    }
+    running.shutdown().await;
+    drop(running);
+
+    let elapsed = executor.last_completed().unwrap_or_else(Instant::now).saturating_duration_since(start);
+    let report = executor.report(args.markets, args.events, elapsed);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("markets        {}", report.markets);
+        println!("events         {} ({:.0}/s)", report.events, report.events_per_sec);
+        println!("trades         {} ({:.0}/s)", report.trades, report.trades_per_sec);
+        println!(
+            "tick-to-trade  p50 {}µs  p90 {}µs  p99 {}µs  max {}µs",
+            report.p50_us, report.p90_us, report.p99_us, report.max_us
+        );
+    }
+    Ok(())
+}
+
+/// `markets` YES/NO pairs with made-up ids.
+fn universe(markets: usize) -> (Arc<MarketMap>, Arc<TokenToMarket>) {
+    let mut market_map = MarketMap::new();
+    let mut token_to_market = HashMap::new();
+    for m in 0..markets {
+        let market_id = format!("bench-{m}");
+        let (yes, no) = (format!("bench-{m}-yes"), format!("bench-{m}-no"));
+        token_to_market.insert(yes.clone(), market_id.clone());
+        token_to_market.insert(no.clone(), market_id.clone());
+        market_map.insert(
+            market_id.clone(),
+            MarketInfo {
+                market_id,
+                question: format!("Benchmark market {m}"),
+                yes_token_id: TokenId::intern(&yes),
+                no_token_id: TokenId::intern(&no),
+                neg_risk: false,
+            },
+        );
+    }
+    (Arc::new(market_map), Arc::new(token_to_market))
+}
+
+fn tick(info: &MarketInfo, token: TokenId, bid: Decimal, ask: Decimal) -> MarketEvent {
+    let now = Instant::now();
+    let kind = MarketEventKind::TopOfBook { bid_price: 0.0, bid_size: 100.0, ask_price: 0.0, ask_size: 100.0 };
+    let mut event = pool::event(Venue::Polymarket, kind, &info.market_id, token.as_str(), now);
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(now);
+    event.best_bid = Some(bid);
+    event.best_ask = Some(ask);
+    event
+}
+
+/// Paper executor that records how long each trade took from the tick behind it.
+struct TimedExecutor {
+    inner: PaperExecutor,
+    samples: Mutex<Samples>,
+}
+
+#[derive(Default)]
+struct Samples {
+    tick_to_trade: Vec<Duration>,
+    last_completed: Option<Instant>,
+}
+
+impl TimedExecutor {
+    fn new() -> Self {
+        Self { inner: PaperExecutor::new(), samples: Mutex::default() }
+    }
+
+    fn count(&self) -> usize {
+        self.samples.lock().unwrap().tick_to_trade.len()
+    }
+
+    fn last_completed(&self) -> Option<Instant> {
+        self.samples.lock().unwrap().last_completed
+    }
+
+    fn reset(&self) {
+        *self.samples.lock().unwrap() = Samples::default();
+    }
+
+    fn report(&self, markets: usize, events: u64, elapsed: Duration) -> BenchReport {
+        let mut samples = std::mem::take(&mut self.samples.lock().unwrap().tick_to_trade);
+        samples.sort_unstable();
+        let quantile = |q: f64| {
+            let Some(last) = samples.len().checked_sub(1) else { return 0 };
+            samples[(last as f64 * q).round() as usize].as_micros() as u64
+        };
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        BenchReport {
+            markets,
+            events,
+            events_per_sec: events as f64 / secs,
+            trades: samples.len(),
+            trades_per_sec: samples.len() as f64 / secs,
+            p50_us: quantile(0.50),
+            p90_us: quantile(0.90),
+            p99_us: quantile(0.99),
+            max_us: quantile(1.0),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionEngine for TimedExecutor {
+    async fn execute(&self, intent: ExecutionIntent) -> ExecutionReport {
+        let received = intent.ws_received_at;
+        let report = self.inner.execute(intent).await;
+        if let Some(received) = received {
+            let mut samples = self.samples.lock().unwrap();
+            samples.tick_to_trade.push(report.completed_at.saturating_duration_since(received));
+            samples.last_completed = Some(report.completed_at);
+        }
+        report
+    }
+}
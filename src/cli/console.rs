@@ -0,0 +1,348 @@
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Context;
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+
+use super::ConsoleArgs;
+
+const HELP: &str = "\
+commands:
+  status                    readiness and per-component health
+  positions                 open positions with mark and PnL
+  orders                    orders handed to the executor and not yet reported
+  pnl                       PnL totals and per-strategy breakdown
+  strategies                running strategies and which are paused
+  pause [STRATEGY]          pause one strategy, or all evaluation
+  resume [STRATEGY]         resume one strategy, or all evaluation
+  order MARKET TOKEN buy|sell PRICE SIZE
+                            submit a manual order (strategy `manual`, same risk checks)
+  flags                     feature flags and their rollout
+  flag NAME on|off|FRACTION|PERCENT|clear
+                            override a feature flag, or drop the override
+  flatten MARKET            close every position in MARKET at the touch
+  cancel-all                cancel every resting order at the venue
+  log-level [DIRECTIVES]    show or replace the tracing filter
+  audit [MARKET]            audited markets, or recent decisions for one
+  help                      this list
+  quit                      leave the console (the engine keeps running)";
+
+/// `console` subcommand: an interactive prompt over a running engine's
+/// admin API, for hands-on operation during incidents. Holds no state of
+/// its own, so any number can attach to one engine.
+pub async fn run(args: ConsoleArgs) -> anyhow::Result<()> {
+    let console = Console {
+        http: reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?,
+        base: args.url.trim_end_matches('/').to_string(),
+        token: args.token.or_else(|| std::env::var("ADMIN_TOKEN").ok()),
+    };
+    let health = console.call(Method::GET, "/healthz", None).await.with_context(|| {
+        format!("no engine answering at {} (is it running? is --url right?)", console.base)
+    })?;
+    println!(
+        "connected to {} ({}); type `help` for commands",
+        console.base,
+        if health["ready"].as_bool() == Some(true) { "ready" } else { "not ready" }
+    );
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("engine> ");
+        std::io::stdout().flush()?;
+        let Some(line) = lines.next_line().await? else { break };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, rest)) = words.split_first() else { continue };
+        if matches!(command, "quit" | "exit") {
+            break;
+        }
+        let result = match (command, rest) {
+            ("help", _) => console.help(),
+            ("status", []) => console.status().await,
+            ("positions", []) => console.positions().await,
+            ("orders", []) => console.orders().await,
+            ("pnl", []) => console.pnl().await,
+            ("strategies", []) => console.strategies().await,
+            ("pause", []) => console.pause("/pause", true).await,
+            ("resume", []) => console.pause("/pause", false).await,
+            ("pause", [name]) => console.pause(&format!("/strategies/{name}/pause"), true).await,
+            ("resume", [name]) => console.pause(&format!("/strategies/{name}/pause"), false).await,
+            ("order", [market, token, side, price, size]) => {
+                match manual_order(market, token, side, price, size) {
+                    Ok(order) => {
+                        if confirm(&mut lines, &format!("{side} {size} @ {price} of {token} in {market}?")).await? {
+                            console.submit(order).await
+                        } else {
+                            console.note("not submitted")
+                        }
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            ("flags", []) => console.flags().await,
+            ("flag", [name, "clear"]) => console.set_flag(name, None).await,
+            ("flag", [name, rollout]) => console.set_flag(name, Some(rollout)).await,
+            ("flatten", [market]) => {
+                if confirm(&mut lines, &format!("flatten every position in {market}?")).await? {
+                    console.flatten(market).await
+                } else {
+                    console.note("not flattened")
+                }
+            }
+            ("cancel-all", []) => {
+                if confirm(&mut lines, "cancel every resting order?").await? {
+                    console.cancel_all().await
+                } else {
+                    console.note("nothing cancelled")
+                }
+            }
+            ("log-level", []) => console.show("/log-level").await,
+            ("log-level", directives) => console.set_log_level(&directives.join(",")).await,
+            ("audit", []) => console.show("/audit").await,
+            ("audit", [market]) => console.show(&format!("/audit/{market}")).await,
+            _ => console.note(&format!("unknown command `{}`; type `help`", line.trim())),
+        };
+        if let Err(e) = result {
+            println!("error: {e:#}");
+        }
+    }
+    Ok(())
+}
+
+/// The admin API's JSON body for an `order` command.
+fn manual_order(market: &str, token: &str, side: &str, price: &str, size: &str) -> anyhow::Result<Value> {
+    let side = match side.to_ascii_lowercase().as_str() {
+        "buy" => "Buy",
+        "sell" => "Sell",
+        _ => anyhow::bail!("side must be buy or sell, not `{side}`"),
+    };
+    let price: f64 = price.parse().with_context(|| format!("invalid price `{price}`"))?;
+    let size: f64 = size.parse().with_context(|| format!("invalid size `{size}`"))?;
+    Ok(serde_json::json!({ "market_id": market, "token_id": token, "side": side, "price": price, "size": size }))
+}
+
+/// Ask a yes/no question on the prompt; anything but "y" or "yes" is no.
+async fn confirm(lines: &mut Lines<BufReader<Stdin>>, prompt: &str) -> anyhow::Result<bool> {
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+    let answer = lines.next_line().await?.unwrap_or_default();
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+struct Console {
+    http: reqwest::Client,
+    base: String,
+    token: Option<String>,
+}
+
+impl Console {
+    /// Send one admin request; non-2xx statuses become errors carrying the body.
+    async fn call(&self, method: Method, path: &str, body: Option<String>) -> anyhow::Result<Value> {
+        let mut request = self.http.request(method, format!("{}{path}", self.base));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        match status {
+            StatusCode::NOT_FOUND if text.is_empty() => anyhow::bail!("not found"),
+            s if !s.is_success() => anyhow::bail!("{status}: {text}"),
+            _ if text.is_empty() => Ok(Value::Null),
+            _ => serde_json::from_str(&text).context("admin API returned invalid JSON"),
+        }
+    }
+
+    fn help(&self) -> anyhow::Result<()> {
+        self.note(HELP)
+    }
+
+    fn note(&self, text: &str) -> anyhow::Result<()> {
+        println!("{text}");
+        Ok(())
+    }
+
+    async fn show(&self, path: &str) -> anyhow::Result<()> {
+        let value = self.call(Method::GET, path, None).await?;
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        Ok(())
+    }
+
+    async fn status(&self) -> anyhow::Result<()> {
+        let report = self.call(Method::GET, "/healthz", None).await?;
+        println!("ready: {}", report["ready"]);
+        if let Some(components) = report["components"].as_object() {
+            for (name, c) in components {
+                let detail = c["detail"].as_str().unwrap_or("");
+                println!("  {name:<20} {:<5} {detail}", c["status"].as_str().unwrap_or("?"));
+            }
+        }
+        Ok(())
+    }
+
+    async fn positions(&self) -> anyhow::Result<()> {
+        let portfolio = self.call(Method::GET, "/portfolio", None).await?;
+        let positions = portfolio["positions"].as_array().cloned().unwrap_or_default();
+        if positions.is_empty() {
+            println!("no open positions");
+            return Ok(());
+        }
+        println!("{:<12} {:<20} {:>10} {:>8} {:>8} {:>10}", "market_id", "token_id", "size", "avg", "mark", "upnl");
+        for p in positions {
+            println!(
+                "{:<12} {:<20} {:>10.2} {:>8.3} {:>8} {:>10.2}",
+                p["market_id"].as_str().unwrap_or("-"),
+                short(p["token_id"].as_str().unwrap_or("")),
+                p["size"].as_f64().unwrap_or(0.0),
+                p["avg_price"].as_f64().unwrap_or(0.0),
+                p["mark_price"].as_f64().map_or("-".to_string(), |m| format!("{m:.3}")),
+                p["unrealized_pnl"].as_f64().unwrap_or(0.0),
+            );
+        }
+        Ok(())
+    }
+
+    async fn orders(&self) -> anyhow::Result<()> {
+        let portfolio = self.call(Method::GET, "/portfolio", None).await?;
+        let orders = portfolio["open_orders"].as_array().cloned().unwrap_or_default();
+        if orders.is_empty() {
+            println!("no open orders");
+            return Ok(());
+        }
+        for o in orders {
+            println!("#{} {} {} ({})", o["id"], o["market_id"].as_str().unwrap_or(""), o["strategy"].as_str().unwrap_or(""), o["venue"]);
+            for leg in o["legs"].as_array().into_iter().flatten() {
+                println!(
+                    "    {:<4} {:>8.2} @ {:.3}  {}",
+                    leg["side"].as_str().unwrap_or("?"),
+                    leg["size"].as_f64().unwrap_or(0.0),
+                    leg["price"].as_f64().unwrap_or(0.0),
+                    short(leg["token_id"].as_str().unwrap_or("")),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn pnl(&self) -> anyhow::Result<()> {
+        let portfolio = self.call(Method::GET, "/portfolio", None).await?;
+        let pnl = &portfolio["pnl"];
+        let balances = &portfolio["balances"];
+        println!(
+            "total {:.2}  realized {:.2}  unrealized {:.2}  cash {:.2}  equity {:.2}",
+            pnl["total"].as_f64().unwrap_or(0.0),
+            pnl["realized"].as_f64().unwrap_or(0.0),
+            pnl["unrealized"].as_f64().unwrap_or(0.0),
+            balances["cash"].as_f64().unwrap_or(0.0),
+            balances["equity"].as_f64().unwrap_or(0.0),
+        );
+        if let Some(by_strategy) = portfolio["pnl_by_strategy"].as_object() {
+            for (name, s) in by_strategy {
+                println!(
+                    "  {name:<16} net {:>10.2}  volume {:>10.2}",
+                    s["realized_net"].as_f64().unwrap_or(0.0),
+                    s["volume"].as_f64().unwrap_or(0.0),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn strategies(&self) -> anyhow::Result<()> {
+        let report = self.call(Method::GET, "/strategies", None).await?;
+        if report["paused"].as_bool() == Some(true) {
+            println!("ALL EVALUATION PAUSED");
+        }
+        for s in report["strategies"].as_array().into_iter().flatten() {
+            let state = if s["paused"].as_bool() == Some(true) { "paused" } else { "running" };
+            println!("  {:<16} {state}", s["name"].as_str().unwrap_or("?"));
+        }
+        Ok(())
+    }
+
+    async fn pause(&self, path: &str, paused: bool) -> anyhow::Result<()> {
+        let method = if paused { Method::PUT } else { Method::DELETE };
+        self.call(method, path, None).await?;
+        self.strategies().await
+    }
+
+    async fn flags(&self) -> anyhow::Result<()> {
+        let flags = self.call(Method::GET, "/flags", None).await?;
+        let flags = flags.as_array().cloned().unwrap_or_default();
+        if flags.is_empty() {
+            println!("no feature flags set; everything is enabled");
+        }
+        for f in flags {
+            let overridden = if f["overridden"].as_bool() == Some(true) {
+                format!(" (overridden; configured {})", f["configured"].as_f64().map_or("-".to_string(), |c| format!("{c}")))
+            } else {
+                String::new()
+            };
+            println!("  {:<28} {:>6.2}{overridden}", f["name"].as_str().unwrap_or("?"), f["rollout"].as_f64().unwrap_or(1.0));
+        }
+        Ok(())
+    }
+
+    async fn set_flag(&self, name: &str, rollout: Option<&str>) -> anyhow::Result<()> {
+        let path = format!("/flags/{name}");
+        match rollout {
+            Some(rollout) => self.call(Method::PUT, &path, Some(rollout.to_string())).await?,
+            None => self.call(Method::DELETE, &path, None).await?,
+        };
+        self.flags().await
+    }
+
+    async fn flatten(&self, market: &str) -> anyhow::Result<()> {
+        let report = self.call(Method::POST, &format!("/flatten/{market}"), None).await?;
+        let legs = report["legs"].as_array().cloned().unwrap_or_default();
+        if legs.is_empty() {
+            println!("nothing to flatten in {market}");
+        }
+        for leg in legs {
+            println!(
+                "submitted {} {:.2} @ {:.3}  {}",
+                leg["side"].as_str().unwrap_or("?"),
+                leg["size"].as_f64().unwrap_or(0.0),
+                leg["price"].as_f64().unwrap_or(0.0),
+                short(leg["token_id"].as_str().unwrap_or("")),
+            );
+        }
+        for token in report["unpriced"].as_array().into_iter().flatten() {
+            println!("NOT closed (no quote): {}", token.as_str().unwrap_or("?"));
+        }
+        Ok(())
+    }
+
+    async fn submit(&self, order: Value) -> anyhow::Result<()> {
+        let mut request = self.http.post(format!("{}/orders", self.base)).json(&order);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        anyhow::ensure!(status.is_success(), "{status}: {}", response.text().await?);
+        println!("queued; check `orders` and `positions` for the fill");
+        Ok(())
+    }
+
+    async fn cancel_all(&self) -> anyhow::Result<()> {
+        let report = self.call(Method::POST, "/orders/cancel-all", None).await?;
+        println!("cancelled {} orders", report["canceled"]);
+        Ok(())
+    }
+
+    async fn set_log_level(&self, directives: &str) -> anyhow::Result<()> {
+        let level = self.call(Method::PUT, "/log-level", Some(directives.to_string())).await?;
+        println!("{} (was {})", level["filter"].as_str().unwrap_or(""), level["previous"].as_str().unwrap_or(""));
+        Ok(())
+    }
+}
+
+/// Token ids are 70+ digit numbers; the tail is enough to tell them apart.
+fn short(token_id: &str) -> &str {
+    &token_id[token_id.len().saturating_sub(18)..]
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use tracing::info;
+
+use prediction_engine::market_data::adapters::kalshi;
+use prediction_engine::market_data::adapters::polymarket::{self, UniverseFilter};
+use prediction_engine::market_data::universe::Universe;
+
+use super::{DiscoverArgs, HistoryVenue};
+use crate::config::Config;
+
+/// `discover-markets` subcommand: run Polymarket discovery with the configured
+/// thresholds and `[universe]` selection, or Kalshi discovery with the
+/// `[venues.kalshi]` filters (or the flag overrides), and list what the
+/// engine would subscribe to.
+pub async fn run(args: DiscoverArgs, config: &Config) -> anyhow::Result<()> {
+    let mut markets = match args.venue {
+        HistoryVenue::Polymarket => {
+            let filter = UniverseFilter {
+                min_volume_24h: args.min_volume.unwrap_or(config.polymarket.min_volume_24h),
+                min_liquidity: args.min_liquidity.unwrap_or(config.polymarket.min_liquidity),
+            };
+            polymarket::discover(&filter, &config.universe).await?
+        }
+        HistoryVenue::Kalshi => {
+            anyhow::ensure!(args.min_liquidity.is_none(), "--min-liquidity only applies to Polymarket");
+            // Universe files are imported as Polymarket markets.
+            anyhow::ensure!(args.out.is_none(), "--out only applies to Polymarket");
+            let mut selection = config.kalshi.discovery.clone().unwrap_or_default();
+            if let Some(min_volume) = args.min_volume {
+                selection.min_volume_24h = min_volume as u64;
+            }
+            let rest_url = config.kalshi.rest_url.as_deref().unwrap_or(kalshi::KALSHI_REST_URL);
+            kalshi::discover(rest_url, &selection).await?
+        }
+    };
+    if let Some(limit) = args.limit {
+        markets.truncate(limit);
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&markets)?);
+    } else {
+        println!("{:<12} {:>14} {:>12} {:>10}  question", "market_id", "volume", "liquidity", "ends");
+        for m in &markets {
+            let liquidity = m.liquidity.map_or_else(|| "-".to_string(), |l| format!("{l:.0}"));
+            let ends = m.end_date.map_or_else(|| "-".to_string(), |d| d.format("%Y-%m-%d").to_string());
+            println!("{:<12} {:>14.0} {:>12} {:>10}  {}", m.info.market_id, m.volume, liquidity, ends, m.info.question);
+        }
+    }
+
+    if let Some(path) = &args.out {
+        let (market_map, token_to_market) = polymarket::lookup_tables(markets.iter().map(|m| &m.info));
+        Universe::new(&market_map, &token_to_market, &HashMap::new()).save(path)?;
+        info!(markets = markets.len(), path = %path.display(), "universe written");
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anyhow::Context;
+use tracing::{info, warn};
+
+use prediction_engine::market_data::history::{self, HistoryRange};
+use prediction_engine::persist::archive::{write_history, ArchiveConfig};
+
+use super::{parse_day_ms, DownloadArgs, HistoryVenue};
+use crate::config::Config;
+
+const MS_PER_DAY: u64 = 86_400_000;
+
+/// `download` subcommand: fetch each market's history and write it to the archive.
+/// A market that fails is logged and skipped so one bad id doesn't abort the batch.
+pub async fn run(args: DownloadArgs, config: &Config) -> anyhow::Result<()> {
+    let range = HistoryRange {
+        start_ms: parse_day_ms(&args.start)?,
+        end_ms: parse_day_ms(&args.end)? + MS_PER_DAY,
+    };
+    anyhow::ensure!(range.start_ms < range.end_ms, "--start must not be after --end");
+
+    let mut markets = args.markets.clone();
+    if let Some(path) = &args.markets_file {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        markets.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(str::to_string),
+        );
+    }
+
+    let mut archive = ArchiveConfig::default();
+    if let Some(settings) = &config.archive {
+        archive.root = settings.dir.clone();
+        if let Some(layout) = &settings.layout {
+            archive.layout = layout.clone();
+        }
+    }
+    if let Some(out) = args.out {
+        archive.root = out;
+    }
+
+    info!(
+        venue = ?args.venue,
+        markets = markets.len(),
+        start = %args.start,
+        end = %args.end,
+        root = %archive.root.display(),
+        "history download starting"
+    );
+
+    let client = history::http_client()?;
+    let mut total = 0;
+    let mut failed = 0;
+
+    for market in &markets {
+        let result = match args.venue {
+            HistoryVenue::Polymarket => {
+                history::polymarket::download_market(&client, market, range, args.fidelity, args.trades).await
+            }
+            HistoryVenue::Kalshi => history::kalshi::download_market(&client, market, range).await,
+        };
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                warn!(market = %market, error = %e, "history download failed — skipping");
+                failed += 1;
+                continue;
+            }
+        };
+
+        let config = archive.clone();
+        total += tokio::task::spawn_blocking(move || write_history(&config, events)).await??;
+    }
+
+    info!(events = total, markets = markets.len(), failed, "history download complete");
+    Ok(())
+}
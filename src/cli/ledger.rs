@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use anyhow::Context;
+use tracing::info;
+
+use prediction_engine::persist::journal::{scan_journal, JournalEntry};
+use prediction_engine::state::market_cache::MarketKey;
+use prediction_engine::state::position::{CostBasisMethod, PositionTracker};
+
+use super::ExportLedgerArgs;
+use crate::config::Config;
+
+/// `export-ledger` subcommand: replay the journal's fills and write every lot
+/// closure as CSV. Read-only, so it can run next to a live engine.
+pub async fn run(args: ExportLedgerArgs, config: &Config) -> anyhow::Result<()> {
+    let journal = args.journal.as_deref().unwrap_or(&config.journal_path);
+    let (positions, fills) = replay_positions(journal)?;
+    positions
+        .ledger()
+        .export_csv(&args.out)
+        .with_context(|| format!("failed to write {}", args.out.display()))?;
+    info!(
+        journal = %journal.display(),
+        fills,
+        closures = positions.ledger().entries().len(),
+        out = %args.out.display(),
+        "ledger exported"
+    );
+    Ok(())
+}
+
+/// Positions (and their ledger) rebuilt from the journal's fills, with the fill count.
+/// Uses the same cost-basis method as the engine.
+pub(crate) fn replay_positions(journal: &Path) -> anyhow::Result<(PositionTracker, usize)> {
+    let positions = PositionTracker::with_method(CostBasisMethod::Fifo);
+    let mut fills = 0;
+    for entry in scan_journal(journal)? {
+        if let JournalEntry::Fill { ts_ms, venue, token_id, side, price, size, .. } = entry {
+            positions.apply_fill_at(MarketKey(venue, token_id), &side, price, size, ts_ms);
+            fills += 1;
+        }
+    }
+    Ok((positions, fills))
+}
@@ -0,0 +1,27 @@
+use tracing::info;
+
+use prediction_engine::persist::{postgres, sqlite};
+
+use crate::config::{Config, StorageConfig};
+
+/// `--check-migrations`: list migrations the configured database still needs
+/// without applying them. Fails when any are pending so deploy scripts can gate on it.
+pub async fn check(config: &Config) -> anyhow::Result<()> {
+    let pending = match &config.storage {
+        Some(StorageConfig::Sqlite(path)) => sqlite::pending_migrations(path)?,
+        Some(StorageConfig::Postgres(url)) => postgres::pending_migrations(url).await?,
+        None => {
+            info!("no storage backend configured — nothing to check");
+            return Ok(());
+        }
+    };
+
+    if pending.is_empty() {
+        info!("database schema is up to date");
+        return Ok(());
+    }
+    for migration in &pending {
+        info!(version = migration.version, name = migration.name, "pending migration");
+    }
+    anyhow::bail!("{} pending migration(s)", pending.len())
+}
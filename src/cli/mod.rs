@@ -0,0 +1,274 @@
+pub mod backtest;
+pub mod bench;
+pub mod console;
+pub mod discover;
+pub mod download;
+pub mod ledger;
+pub mod migrations;
+pub mod preflight;
+pub mod print_config;
+pub mod reconcile;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "prediction-engine", version, about = "Prediction market trading engine")]
+pub struct Cli {
+    /// Config file. Defaults to CONFIG_FILE, then `config.toml` if present.
+    #[arg(short, long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Override a config key, e.g. `--set risk.max_drawdown=0.05`. Beats env vars and the file.
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
+    /// Print the effective configuration and where each value came from, then exit.
+    #[arg(long, global = true)]
+    pub print_config: bool,
+
+    /// Required to run with `execution.mode = "live"`: orders go to the venue with real funds.
+    #[arg(long, global = true)]
+    pub i_understand_live_trading: bool,
+
+    /// Report pending database migrations and exit (non-zero if any are pending).
+    #[arg(long, global = true)]
+    pub check_migrations: bool,
+
+    /// Run the startup preflight checks, print a summary, and exit (non-zero if any fail).
+    #[arg(long, global = true)]
+    pub check: bool,
+
+    /// Defaults to `run` when omitted.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the engine in the configured execution mode.
+    Run(RunArgs),
+    /// Run the engine against live data with the paper executor, whatever the config says.
+    Paper(RunArgs),
+    /// Run with the dry-run executor: orders are signed with the trading key but never posted.
+    DryRun(RunArgs),
+    /// Download historical prices/trades into the Parquet archive.
+    Download(DownloadArgs),
+    /// Replay the archive through strategies and print a performance report.
+    Backtest(BacktestArgs),
+    /// List the markets discovery would trade, optionally saving them as a universe file.
+    DiscoverMarkets(DiscoverArgs),
+    /// Compare journal positions with the venue's and exit non-zero on any mismatch.
+    Reconcile(ReconcileArgs),
+    /// Write the realized-PnL ledger rebuilt from the journal as CSV.
+    ExportLedger(ExportLedgerArgs),
+    /// Interactive console over a running engine's admin API (pause, flatten, cancel-all, ...).
+    Console(ConsoleArgs),
+    /// Measure tick-to-trade latency and throughput on synthetic data with the paper executor.
+    Bench(BenchArgs),
+}
+
+#[derive(Debug, Default, clap::Args)]
+pub struct RunArgs {
+    /// Show the terminal dashboard instead of logging to stdout
+    /// (logs still go to LOG_DIR if set).
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    pub tui: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DiscoverArgs {
+    /// Venue to list: Polymarket (`[universe]` selection) or Kalshi
+    /// (`[venues.kalshi]` filters).
+    #[arg(long, value_enum, default_value = "polymarket")]
+    pub venue: HistoryVenue,
+    /// Minimum 24h volume in USD, or contracts on Kalshi (overrides
+    /// POLYMARKET_MIN_VOLUME_24H / KALSHI_MIN_VOLUME_24H).
+    #[arg(long)]
+    pub min_volume: Option<f64>,
+    /// Minimum liquidity in USD (overrides POLYMARKET_MIN_LIQUIDITY; Polymarket only).
+    #[arg(long)]
+    pub min_liquidity: Option<f64>,
+    /// Only list the top N markets by volume.
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Print JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+    /// Save the listed markets as a universe file (for UNIVERSE_IMPORT or `backtest --universe`).
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ReconcileArgs {
+    /// Wallet address holding the positions. Derived from the trading key if omitted.
+    #[arg(long)]
+    pub wallet: Option<String>,
+    /// Journal to rebuild local positions from. Defaults to JOURNAL_PATH.
+    #[arg(long)]
+    pub journal: Option<PathBuf>,
+    /// Size difference (shares) tolerated before a token counts as mismatched.
+    #[arg(long, default_value_t = 0.01)]
+    pub tolerance: f64,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ExportLedgerArgs {
+    /// Journal to rebuild the ledger from. Defaults to JOURNAL_PATH.
+    #[arg(long)]
+    pub journal: Option<PathBuf>,
+    /// CSV output path.
+    #[arg(long, short)]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ConsoleArgs {
+    /// Admin API of the engine to operate.
+    #[arg(long, default_value = "http://localhost:9001")]
+    pub url: String,
+    /// The engine's `ADMIN_TOKEN`, which every command that changes
+    /// something needs; read from `ADMIN_TOKEN` when not given.
+    #[arg(long)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct BenchArgs {
+    /// Synthetic markets (YES/NO token pairs) the updates rotate through.
+    #[arg(long, default_value_t = 50)]
+    pub markets: usize,
+    /// Market updates to send.
+    #[arg(long, default_value_t = 100_000)]
+    pub events: u64,
+    /// Updates per second; 0 sends as fast as the pipeline accepts them.
+    #[arg(long, default_value_t = 10_000)]
+    pub rate: u64,
+    /// Print JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HistoryVenue {
+    Polymarket,
+    Kalshi,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DownloadArgs {
+    #[arg(long, value_enum)]
+    pub venue: HistoryVenue,
+    /// Market ids to download: Gamma market ids for Polymarket, tickers for Kalshi.
+    #[arg(long, value_delimiter = ',', required_unless_present = "markets_file")]
+    pub markets: Vec<String>,
+    /// File with one market id per line (`#` comments allowed).
+    #[arg(long)]
+    pub markets_file: Option<PathBuf>,
+    /// First UTC day to download (YYYY-MM-DD).
+    #[arg(long)]
+    pub start: String,
+    /// Last UTC day to download, inclusive (YYYY-MM-DD).
+    #[arg(long)]
+    pub end: String,
+    /// Polymarket price-history resolution in minutes.
+    #[arg(long, default_value_t = 1)]
+    pub fidelity: u32,
+    /// Also download individual trades (Polymarket; Kalshi always downloads trades).
+    #[arg(long)]
+    pub trades: bool,
+    /// Archive root. Defaults to ARCHIVE_DIR, then `data/archive`.
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StrategyKind {
+    Arbitrage,
+    MarketMaker,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct BacktestArgs {
+    /// Archive root. Defaults to ARCHIVE_DIR, then `data/archive`.
+    #[arg(long)]
+    pub data: Option<PathBuf>,
+    /// Strategies to run together in one account (comma-separated or repeated).
+    #[arg(long, value_enum, value_delimiter = ',', required = true)]
+    pub strategy: Vec<StrategyKind>,
+    /// First UTC day to replay (YYYY-MM-DD).
+    #[arg(long)]
+    pub from: String,
+    /// UTC day to stop at, exclusive (YYYY-MM-DD).
+    #[arg(long)]
+    pub to: String,
+    /// Market universe exported by a live run (UNIVERSE_EXPORT). Inferred from the archive if omitted.
+    #[arg(long)]
+    pub universe: Option<PathBuf>,
+
+    // ── Overrides ────────────────────────────────────────────────
+    #[arg(long, default_value_t = 1_000.0)]
+    pub starting_cash: f64,
+    /// Arbitrage minimum edge.
+    #[arg(long, default_value_t = 0.025)]
+    pub min_edge: f64,
+    /// Arbitrage order size.
+    #[arg(long, default_value_t = 5.0)]
+    pub size: f64,
+    /// Market-maker half-spread.
+    #[arg(long)]
+    pub half_spread: Option<f64>,
+    /// Market-maker quote size.
+    #[arg(long)]
+    pub quote_size: Option<f64>,
+    /// Market-maker inventory limit.
+    #[arg(long)]
+    pub max_inventory: Option<f64>,
+    /// Taker fee in bps (defaults to the Polymarket schedule).
+    #[arg(long)]
+    pub taker_fee_bps: Option<f64>,
+    /// Maker rebate in bps.
+    #[arg(long)]
+    pub maker_rebate_bps: Option<f64>,
+    /// Simulate queue position and top-of-book depth instead of full fills at the touch.
+    #[arg(long)]
+    pub queue_model: bool,
+    #[arg(long, default_value_t = 60)]
+    pub equity_interval_secs: u64,
+
+    // ── Replay ───────────────────────────────────────────────────
+    /// Event-time speed multiplier (1 = real time). Unpaced when omitted.
+    #[arg(long)]
+    pub speed: Option<f64>,
+    /// Write periodic checkpoints to this file.
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+    #[arg(long, default_value_t = 3_600)]
+    pub checkpoint_interval_secs: u64,
+    /// Resume from `--checkpoint` if it exists.
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    // ── Output ───────────────────────────────────────────────────
+    /// Bootstrap iterations for Monte Carlo intervals (0 disables).
+    #[arg(long, default_value_t = 1_000)]
+    pub monte_carlo: usize,
+    /// Max extra slippage per trade in the Monte Carlo runs, in bps.
+    #[arg(long, default_value_t = 0.0)]
+    pub slippage_bps: f64,
+    /// Write the JSON report here.
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+}
+
+/// Midnight UTC of a `YYYY-MM-DD` day, in unix ms.
+pub(crate) fn parse_day_ms(day: &str) -> anyhow::Result<u64> {
+    let date = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .with_context(|| format!("invalid date '{day}' (expected YYYY-MM-DD)"))?;
+    let ts = date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc().timestamp_millis();
+    Ok(ts as u64)
+}
@@ -0,0 +1,249 @@
+use std::fs::OpenOptions;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use polymarket_rs::{ClobClient, PrivateKeySigner};
+use tracing::{error, info, warn};
+
+use prediction_engine::execution::keys::load_signer;
+use prediction_engine::execution::live::fetch_collateral;
+use prediction_engine::market_data::adapters::kalshi;
+use prediction_engine::persist::{postgres, sqlite};
+use prediction_engine::publish::{self, PublishTarget};
+
+use crate::config::{Config, ExecutionMode, StorageConfig};
+
+const CLOB_HOST: &str = "https://clob.polymarket.com";
+const GAMMA_HOST: &str = "https://gamma-api.polymarket.com";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Degraded but safe to trade (e.g. an optional exporter is down).
+    Warn,
+    /// Starting would fail or trade blind.
+    Fail,
+    Skip,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Pass => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+            Status::Skip => "skip",
+        }
+    }
+}
+
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+/// Results of every preflight check, in the order they ran.
+pub struct Preflight {
+    pub checks: Vec<Check>,
+}
+
+impl Preflight {
+    fn push(&mut self, name: &'static str, status: Status, detail: impl Into<String>) {
+        self.checks.push(Check { name, status, detail: detail.into() });
+    }
+
+    fn push_result(&mut self, name: &'static str, on_error: Status, result: anyhow::Result<String>) {
+        match result {
+            Ok(detail) => self.push(name, Status::Pass, detail),
+            Err(e) => self.push(name, on_error, format!("{e:#}")),
+        }
+    }
+
+    /// Authenticate `signer` against the CLOB and check its collateral,
+    /// under the check `names`, with `prefix` on each detail.
+    async fn wallet(
+        &mut self,
+        config: &Config,
+        (credentials, collateral): (&'static str, &'static str),
+        prefix: &str,
+        signer: &anyhow::Result<PrivateKeySigner>,
+    ) {
+        let signer = match signer {
+            Ok(signer) => signer,
+            Err(e) => return self.push(credentials, Status::Fail, format!("{prefix}{e:#}")),
+        };
+        match fetch_collateral(signer).await {
+            Ok(funds) => {
+                self.push(credentials, Status::Pass, format!("{prefix}wallet {}", signer.address()));
+                // Dry-run never spends, so an unfunded wallet is only worth a warning there.
+                let short = if config.execution_mode == ExecutionMode::Live { Status::Fail } else { Status::Warn };
+                let detail = format!("{prefix}balance ${:.2}, allowance ${:.2}", funds.balance, funds.allowance);
+                let status = if funds.balance <= 0.0 || funds.allowance <= 0.0 { short } else { Status::Pass };
+                self.push(collateral, status, detail);
+            }
+            Err(e) => self.push(credentials, Status::Fail, format!("{prefix}CLOB authentication failed: {e:#}")),
+        }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == Status::Fail).count()
+    }
+
+    /// One log line per check, at a level matching its status.
+    pub fn log(&self) {
+        for c in &self.checks {
+            match c.status {
+                Status::Pass | Status::Skip => info!(check = c.name, status = c.status.name(), detail = %c.detail, "preflight"),
+                Status::Warn => warn!(check = c.name, detail = %c.detail, "preflight warning"),
+                Status::Fail => error!(check = c.name, detail = %c.detail, "preflight failed"),
+            }
+        }
+    }
+
+    pub fn print(&self) {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        for c in &self.checks {
+            println!("{:<width$}  {:<4}  {}", c.name, c.status.name(), c.detail);
+        }
+        let warnings = self.checks.iter().filter(|c| c.status == Status::Warn).count();
+        println!("\n{} checks, {} failed, {warnings} warnings", self.checks.len(), self.failures());
+    }
+}
+
+/// `--check`: run preflight, print the summary, and exit non-zero on any failure.
+pub async fn check(config: &Config, admin_addr: SocketAddr) -> anyhow::Result<()> {
+    let signers = load_signers(config).await;
+    let preflight = run(config, admin_addr, &signers).await;
+    preflight.print();
+    match preflight.failures() {
+        0 => Ok(()),
+        n => anyhow::bail!("{n} preflight check(s) failed"),
+    }
+}
+
+/// The trading keys live and dry-run modes sign with: the default wallet's,
+/// then each `[[wallets]]` entry's by name. `None` in paper mode.
+pub struct Signers {
+    pub default: Option<anyhow::Result<PrivateKeySigner>>,
+    pub wallets: Vec<(String, anyhow::Result<PrivateKeySigner>)>,
+}
+
+pub async fn load_signers(config: &Config) -> Signers {
+    if config.execution_mode == ExecutionMode::Paper {
+        return Signers { default: None, wallets: Vec::new() };
+    }
+    let mut wallets = Vec::new();
+    for wallet in &config.wallets {
+        let signer = match wallet.key_source() {
+            Ok(source) => load_signer(&source).await,
+            Err(e) => Err(e),
+        };
+        wallets.push((wallet.name.clone(), signer));
+    }
+    Signers { default: Some(load_signer(&config.key_source).await), wallets }
+}
+
+/// Check everything the engine depends on before it starts: venue APIs,
+/// credentials and collateral, persistence and telemetry endpoints, and the
+/// ports it will listen on. Read-only apart from creating the journal file.
+/// `signers` are the already-loaded trading keys.
+pub async fn run(config: &Config, admin_addr: SocketAddr, signers: &Signers) -> Preflight {
+    let mut p = Preflight { checks: Vec::new() };
+    let http = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build().expect("static reqwest config");
+
+    // ── Config ───────────────────────────────────────────────────
+    // Load-time validation already passed; this records what was loaded.
+    let kinds: Vec<&str> = config.strategies.iter().map(|s| s.kind()).collect();
+    p.push("config", Status::Pass, format!("mode={}, strategies={kinds:?}", config.execution_mode.name()));
+    if config.strategies.is_empty() {
+        p.push("strategies", Status::Warn, "none configured — the engine will only record market data");
+    }
+
+    // ── Venues ───────────────────────────────────────────────────
+    let clob = ClobClient::new(CLOB_HOST).get_ok().await;
+    p.push_result("polymarket_clob", Status::Fail, clob.map(|_| CLOB_HOST.to_string()).map_err(Into::into));
+    p.push_result("polymarket_gamma", Status::Fail, get_ok(&http, &format!("{GAMMA_HOST}/markets?limit=1")).await);
+    if config.kalshi.discovery.is_some() {
+        let rest_url = config.kalshi.rest_url.as_deref().unwrap_or(kalshi::KALSHI_REST_URL).trim_end_matches('/');
+        p.push_result("kalshi_rest", Status::Fail, get_ok(&http, &format!("{rest_url}/events?limit=1")).await);
+    }
+
+    // ── Credentials ──────────────────────────────────────────────
+    match &signers.default {
+        None => p.push("credentials", Status::Skip, "paper mode"),
+        Some(signer) => p.wallet(config, ("credentials", "collateral"), "", signer).await,
+    }
+    for (name, signer) in &signers.wallets {
+        p.wallet(config, ("wallet_credentials", "wallet_collateral"), &format!("{name}: "), signer).await;
+    }
+
+    // ── Persistence ──────────────────────────────────────────────
+    let journal = OpenOptions::new().create(true).append(true).open(&config.journal_path);
+    p.push_result(
+        "journal",
+        Status::Fail,
+        journal.map(|_| config.journal_path.display().to_string()).map_err(Into::into),
+    );
+    match &config.storage {
+        None => p.push("database", Status::Skip, "not configured"),
+        Some(StorageConfig::Sqlite(path)) => p.push_result("database", Status::Fail, sqlite::pending_migrations(path).map(pending)),
+        Some(StorageConfig::Postgres(url)) => {
+            p.push_result("database", Status::Fail, postgres::pending_migrations(url).await.map(pending))
+        }
+    }
+    if let Some(ch) = &config.clickhouse {
+        p.push_result("clickhouse", Status::Warn, get_ok(&http, &format!("{}/ping", ch.url.trim_end_matches('/'))).await);
+    }
+    if let Some(influx) = &config.influx {
+        p.push_result("influx", Status::Warn, get_ok(&http, &format!("{}/health", influx.url.trim_end_matches('/'))).await);
+    }
+    match config.publish.as_ref().map(|publish| &publish.target) {
+        None => {}
+        Some(PublishTarget::WebSocket { addr }) => p.push_result("publish_listen", Status::Fail, bindable(*addr).await),
+        Some(target) => p.push_result("publish", Status::Warn, publish::probe(target).await),
+    }
+
+    // ── Telemetry and listeners ──────────────────────────────────
+    match &config.metrics.push {
+        Some(push) => p.push_result("metrics_push", Status::Warn, reachable(&http, &push.url).await),
+        None => p.push_result("metrics_listen", Status::Fail, bindable(config.metrics.listen).await),
+    }
+    p.push_result("admin_listen", Status::Fail, bindable(admin_addr).await);
+    if let Some(addr) = config.grpc_addr {
+        p.push_result("grpc_listen", Status::Fail, bindable(addr).await);
+    }
+    if let Some(fix) = &config.fix {
+        p.push_result("fix_listen", Status::Fail, bindable(fix.addr).await);
+    }
+    if let Some(webhook) = &config.webhook {
+        p.push_result("webhook_listen", Status::Fail, bindable(webhook.addr).await);
+    }
+
+    p
+}
+
+fn pending(migrations: Vec<&'static prediction_engine::persist::migrations::Migration>) -> String {
+    match migrations.len() {
+        0 => "schema up to date".to_string(),
+        n => format!("{n} migration(s) pending, applied at start"),
+    }
+}
+
+async fn get_ok(http: &reqwest::Client, url: &str) -> anyhow::Result<String> {
+    let status = http.get(url).send().await?.status();
+    anyhow::ensure!(status.is_success(), "{url} returned {status}");
+    Ok(url.to_string())
+}
+
+/// Any HTTP answer counts: push endpoints often reject a bare GET.
+async fn reachable(http: &reqwest::Client, url: &str) -> anyhow::Result<String> {
+    let status = http.get(url).send().await?.status();
+    Ok(format!("{url} ({status})"))
+}
+
+async fn bindable(addr: SocketAddr) -> anyhow::Result<String> {
+    tokio::net::TcpListener::bind(addr).await.map_err(|e| anyhow::anyhow!("cannot listen on {addr}: {e}"))?;
+    Ok(addr.to_string())
+}
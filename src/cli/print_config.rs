@@ -0,0 +1,72 @@
+use crate::config::{Config, SettingSource};
+
+/// Substrings of setting names whose values are never printed.
+const SECRET_MARKERS: [&str; 6] = ["PASSWORD", "TOKEN", "WEBHOOK_URL", "SECRET", "POSTGRES_URL", "PUBLISH_URL"];
+
+/// `--print-config`: every setting the engine read, its effective raw value,
+/// and which layer it came from (`--set` > environment > file > default).
+pub fn print(config: &Config) {
+    match &config.source {
+        Some(path) => println!("# config file: {}", path.display()),
+        None => println!("# config file: none"),
+    }
+    println!("# precedence: --set > environment (.env included) > file > default\n");
+
+    let name_width = config.provenance.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let values: Vec<String> = config
+        .provenance
+        .iter()
+        .map(|p| match &p.value {
+            Some(_) if SECRET_MARKERS.iter().any(|m| p.name.contains(m)) => "<redacted>".to_string(),
+            Some(value) => value.clone(),
+            None => "-".to_string(),
+        })
+        .collect();
+    let value_width = values.iter().map(String::len).max().unwrap_or(0).min(48);
+
+    for (p, value) in config.provenance.iter().zip(&values) {
+        let origin = match (p.source, &p.key) {
+            (SettingSource::Override, Some(key)) => format!("--set {key}"),
+            (SettingSource::File, Some(key)) => format!("file: {key}"),
+            (SettingSource::Env, _) => "env".to_string(),
+            _ => "default".to_string(),
+        };
+        println!("{:<name_width$}  {:<value_width$}  {origin}", p.name, value);
+    }
+
+    println!("\n# strategies ({})", source_name(config.strategies_source));
+    for (i, strategy) in config.strategies.iter().enumerate() {
+        println!("strategies[{i}] = {strategy:?}");
+    }
+    for wallet in &config.wallets {
+        println!(
+            "wallets.{} = strategies {:?}, key_source {}, max_daily_notional {}",
+            wallet.name,
+            wallet.strategies,
+            wallet.key_source.as_deref().unwrap_or("env"),
+            wallet.max_daily_notional.unwrap_or(config.risk.max_daily_notional),
+        );
+    }
+
+    println!("\n# effective");
+    println!("execution_mode = {:?}", config.execution_mode);
+    println!(
+        "risk = max_drawdown {}, max_daily_notional {}",
+        config.risk.max_drawdown, config.risk.max_daily_notional
+    );
+    println!(
+        "polymarket = min_volume_24h {}, min_liquidity {}",
+        config.polymarket.min_volume_24h, config.polymarket.min_liquidity
+    );
+    println!("kalshi = markets {:?}, credentials {:?}", config.kalshi.markets, config.kalshi.credentials);
+    println!("universe = {:?}", config.universe);
+}
+
+fn source_name(source: SettingSource) -> &'static str {
+    match source {
+        SettingSource::Default => "default",
+        SettingSource::File => "file",
+        SettingSource::Env => "env",
+        SettingSource::Override => "--set",
+    }
+}
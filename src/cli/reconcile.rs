@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Context;
+use polymarket_rs::DataClient;
+use rust_decimal::prelude::ToPrimitive;
+use tracing::{info, warn};
+
+use prediction_engine::execution::keys::load_signer;
+use prediction_engine::market_data::types::Venue;
+
+use super::ledger::replay_positions;
+use super::ReconcileArgs;
+use crate::config::Config;
+
+const DATA_API_HOST: &str = "https://data-api.polymarket.com";
+
+/// `reconcile` subcommand: rebuild positions from the journal and compare them
+/// token by token with what Polymarket reports for the wallet. Fails when any
+/// token differs by more than the tolerance so it can gate a restart.
+pub async fn run(args: ReconcileArgs, config: &Config) -> anyhow::Result<()> {
+    let wallet = match &args.wallet {
+        Some(wallet) => wallet.clone(),
+        None => load_signer(&config.key_source)
+            .await
+            .context("reconcile needs --wallet or a loadable trading key")?
+            .address()
+            .to_string(),
+    };
+
+    let journal = args.journal.as_deref().unwrap_or(&config.journal_path);
+    let (positions, fills) = replay_positions(journal)?;
+    let local: BTreeMap<String, f64> = positions
+        .all()
+        .into_iter()
+        .filter(|(key, _)| key.0 == Venue::Polymarket)
+        .map(|(key, position)| (key.1.to_string(), position.size))
+        .collect();
+
+    let venue: BTreeMap<String, f64> = DataClient::new(DATA_API_HOST)
+        .get_positions(&wallet)
+        .await
+        .with_context(|| format!("failed to fetch Polymarket positions for {wallet}"))?
+        .into_iter()
+        .map(|p| (p.asset, p.size.to_f64().unwrap_or(0.0)))
+        .collect();
+
+    let tokens: BTreeSet<&String> = local.keys().chain(venue.keys()).collect();
+    let mut mismatches = 0;
+    for token_id in tokens {
+        let ours = local.get(token_id).copied().unwrap_or(0.0);
+        let theirs = venue.get(token_id).copied().unwrap_or(0.0);
+        if (ours - theirs).abs() > args.tolerance {
+            warn!(token_id = %token_id, journal = ours, venue = theirs, diff = ours - theirs, "position mismatch");
+            mismatches += 1;
+        }
+    }
+
+    info!(
+        wallet = %wallet,
+        fills,
+        local_positions = local.len(),
+        venue_positions = venue.len(),
+        mismatches,
+        "reconciliation complete"
+    );
+    anyhow::ensure!(mismatches == 0, "{mismatches} position(s) differ between the journal and Polymarket");
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Time source abstraction. Live components use [`SystemClock`]; the
+//! backtester drives a [`SimClock`] from event timestamps so replays are
+//! deterministic and TTL/latency logic behaves as it would have live.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub trait Clock: Send + Sync + Debug {
+    /// Monotonic now, for measuring intervals.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock now in unix milliseconds, for timestamps.
+    fn unix_ms(&self) -> u64;
+
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn unix_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Simulated clock that only moves when told to.
+/// `now()` is an `Instant` offset from a fixed origin by simulated elapsed time.
+#[derive(Debug)]
+pub struct SimClock {
+    origin: Instant,
+    start_ms: u64,
+    now_ms: AtomicU64,
+}
+
+impl SimClock {
+    pub fn new(start_ms: u64) -> Self {
+        Self { origin: Instant::now(), start_ms, now_ms: AtomicU64::new(start_ms) }
+    }
+
+    /// Move time forward to `ts_ms`. Never moves backwards.
+    pub fn advance_to(&self, ts_ms: u64) {
+        self.now_ms.fetch_max(ts_ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Instant {
+        let elapsed = self.now_ms.load(Ordering::SeqCst).saturating_sub(self.start_ms);
+        self.origin + Duration::from_millis(elapsed)
+    }
+
+    fn unix_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,740 @@
+//! `config.toml` schema.
+//!
+//! Every scalar setting has an environment-variable twin (documented in the
+//! README); the file is flattened onto those names so [`super::Config`] has a
+//! single lookup path, and env vars set in the process or `.env` win over the
+//! file. Strategies exist only in the file since they don't flatten to scalars:
+//! one `[strategy.<kind>]` table per kind, or a `[[strategies]]` list when the
+//! same kind runs more than once. Extra signing wallets (`[[wallets]]`),
+//! polled REST feeds (`[[pollers]]`) and AMM pools (`[[amm.pools]]`) are
+//! file-only for the same reason.
+
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use prediction_engine::market_data::adapters::amm::AmmPool;
+use prediction_engine::market_data::adapters::rest_poller::PollFeed;
+
+use super::{ArbitrageSettings, MarketMakerSettings, StrategySettings, WalletSettings};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    #[serde(default)]
+    pub logging: LoggingSection,
+    #[serde(default)]
+    pub venues: VenuesSection,
+    #[serde(default)]
+    pub universe: UniverseSection,
+    /// Omitted (along with `strategy`) = the built-in arbitrage strategy.
+    pub strategies: Option<Vec<StrategySettings>>,
+    pub strategy: Option<StrategySections>,
+    /// Signing wallets besides the default one from `[execution]`.
+    #[serde(default)]
+    pub wallets: Vec<WalletSettings>,
+    /// REST endpoints polled for quotes.
+    #[serde(default)]
+    pub pollers: Vec<PollFeed>,
+    #[serde(default)]
+    pub amm: AmmSection,
+    #[serde(default)]
+    pub execution: ExecutionSection,
+    #[serde(default)]
+    pub risk: RiskSection,
+    #[serde(default)]
+    pub portfolio: PortfolioSection,
+    #[serde(default)]
+    pub metrics: MetricsSection,
+    #[serde(default)]
+    pub admin: AdminSection,
+    #[serde(default)]
+    pub persistence: PersistenceSection,
+    #[serde(default)]
+    pub health: HealthSection,
+    #[serde(default)]
+    pub notify: NotifySection,
+    #[serde(default)]
+    pub watchdog: WatchdogSection,
+    #[serde(default)]
+    pub channels: ChannelsSection,
+    #[serde(default)]
+    pub runtime: RuntimeSection,
+    /// Venue → endpoint class → REST requests per second, e.g.
+    /// `polymarket = { trading = 20 }`.
+    #[serde(default)]
+    pub rate_limits: BTreeMap<String, BTreeMap<String, u64>>,
+    #[serde(default)]
+    pub audit: AuditSection,
+    #[serde(default)]
+    pub grpc: GrpcSection,
+    #[serde(default)]
+    pub fix: FixSection,
+    #[serde(default)]
+    pub webhook: WebhookSection,
+    #[serde(default)]
+    pub publish: PublishSection,
+    /// Feature flag name → `true` / `false`, a fraction, or `"25%"`.
+    #[serde(default)]
+    pub flags: BTreeMap<String, FlagValue>,
+}
+
+// ── Sections ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingSection {
+    pub level: Option<String>,
+    pub format: Option<String>,
+    pub dir: Option<PathBuf>,
+    pub file_prefix: Option<String>,
+    pub rotation: Option<String>,
+    pub max_files: Option<u64>,
+}
+
+/// `[strategy.arbitrage]`, `[strategy.market_maker]`: each table present runs
+/// one instance of that kind.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategySections {
+    pub arbitrage: Option<ArbitrageSettings>,
+    pub market_maker: Option<MarketMakerSettings>,
+}
+
+impl StrategySections {
+    fn into_list(self) -> Vec<StrategySettings> {
+        self.arbitrage
+            .map(StrategySettings::Arbitrage)
+            .into_iter()
+            .chain(self.market_maker.map(StrategySettings::MarketMaker))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VenuesSection {
+    #[serde(default)]
+    pub polymarket: PolymarketSection,
+    #[serde(default)]
+    pub kalshi: KalshiSection,
+    #[serde(default)]
+    pub betfair: BetfairSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolymarketSection {
+    pub min_volume_24h: Option<f64>,
+    pub min_liquidity: Option<f64>,
+    /// WebSockets to spread the streamed markets over.
+    pub ws_connections: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KalshiSection {
+    /// Market tickers to stream; the adapter runs when this is non-empty or
+    /// `discover` is on.
+    pub markets: Option<Vec<String>>,
+    /// List open markets over REST and stream those passing the filters below.
+    pub discover: Option<bool>,
+    /// Series tickers to list events of; all open events when unset.
+    pub series: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    /// Minimum 24h volume, in contracts.
+    pub min_volume_24h: Option<u64>,
+    pub max_days_to_close: Option<u64>,
+    pub top_n: Option<usize>,
+    /// API key id, with its PEM private key in `private_key_file`.
+    pub api_key_id: Option<String>,
+    pub private_key_file: Option<PathBuf>,
+    /// `vault` or `aws`: read both from one JSON secret instead.
+    pub secret_store: Option<String>,
+    pub secret: Option<String>,
+    pub ws_url: Option<String>,
+    pub rest_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BetfairSection {
+    /// Market ids to stream; the adapter runs when this is non-empty.
+    pub markets: Option<Vec<String>>,
+    /// Application key, with the session token in `session_token_file`.
+    pub app_key: Option<String>,
+    pub session_token_file: Option<PathBuf>,
+    pub stream_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UniverseSection {
+    pub import: Option<PathBuf>,
+    pub export: Option<PathBuf>,
+    /// Always-subscribed market slugs, condition IDs, or Gamma ids.
+    pub markets: Option<Vec<String>>,
+    /// Gamma tag ids to search instead of all active markets.
+    pub tags: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub top_n: Option<usize>,
+    /// Skip markets ending within this many hours.
+    pub min_hours_to_end: Option<u64>,
+    /// Skip markets ending more than this many days out.
+    pub max_days_to_end: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecutionSection {
+    /// `paper`, `dry_run`, or `live`.
+    pub mode: Option<String>,
+    /// Required for `live`; the equivalent of `--i-understand-live-trading`.
+    pub confirm_live: Option<bool>,
+    /// Signals with at least this edge go on the priority lane.
+    pub priority_edge: Option<f64>,
+    /// Strategy signals older than this are dropped; 0 disables.
+    pub signal_ttl_ms: Option<u64>,
+    /// Repeats of a signal executed this recently are dropped; 0 disables.
+    pub dedup_window_ms: Option<u64>,
+    /// `env`, `keyring`, `age`, `gpg`, `vault`, or `aws`. The key itself never goes in this file.
+    pub key_source: Option<String>,
+    /// Encrypted key file for the `age` and `gpg` sources.
+    pub key_file: Option<PathBuf>,
+    pub keyring_service: Option<String>,
+    pub keyring_user: Option<String>,
+    /// Vault path or Secrets Manager secret id for the `vault` and `aws` sources.
+    pub key_secret: Option<String>,
+    pub key_secret_field: Option<String>,
+    /// Re-fetch interval for remote key sources; 0 fetches once.
+    pub secret_refresh_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskSection {
+    pub max_drawdown: Option<f64>,
+    pub max_daily_notional: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PortfolioSection {
+    /// Market id → group its exposure is summed under, e.g.
+    /// `{ "0xabc..." = "election" }`.
+    #[serde(default)]
+    pub correlation_groups: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsSection {
+    pub addr: Option<String>,
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    pub latency_us_buckets: Option<Buckets>,
+    pub latency_ms_buckets: Option<Buckets>,
+    pub edge_buckets_bps: Option<Buckets>,
+    pub quantiles: Option<Vec<f64>>,
+    pub market_gauges_top_k: Option<u64>,
+    pub push: Option<MetricsPushSection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsPushSection {
+    pub url: String,
+    pub mode: Option<String>,
+    pub interval_secs: Option<u64>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+/// A bucket list, or `"summary"`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Buckets {
+    List(Vec<f64>),
+    Named(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceSection {
+    pub backend: Option<String>,
+    pub sqlite_path: Option<PathBuf>,
+    pub postgres_url: Option<String>,
+    pub journal_path: Option<PathBuf>,
+    pub instance_lock_path: Option<PathBuf>,
+    pub db_max_age_days: Option<u64>,
+    pub journal_max_age_days: Option<u64>,
+    pub archive: Option<ArchiveSection>,
+    pub clickhouse: Option<ClickHouseSection>,
+    pub influx: Option<InfluxSection>,
+    pub s3: Option<S3Section>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveSection {
+    pub dir: PathBuf,
+    pub layout: Option<String>,
+    pub max_age_days: Option<u64>,
+    pub max_gb: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClickHouseSection {
+    pub url: String,
+    pub database: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InfluxSection {
+    pub url: String,
+    pub org: Option<String>,
+    pub bucket: Option<String>,
+    pub token: Option<String>,
+    pub interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Section {
+    pub bucket: String,
+    pub prefix: Option<String>,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    pub delete_local: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthSection {
+    pub stale_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifySection {
+    pub telegram_bot_token: Option<String>,
+    pub telegram_chat_id: Option<String>,
+    pub discord_webhook_url: Option<String>,
+    pub slack_webhook_url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub max_per_minute: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogSection {
+    pub event_timeout_secs: Option<u64>,
+    pub signal_full_secs: Option<u64>,
+    pub no_fill_mins: Option<u64>,
+    /// UTC hours, e.g. `"13-21"`.
+    pub active_hours: Option<String>,
+    pub event_rate_burst_ratio: Option<f64>,
+    pub event_rate_drop_ratio: Option<f64>,
+    pub feed_stale_secs: Option<u64>,
+    /// Per-venue deadlines in seconds, e.g. `{ rest = 300 }`.
+    pub feed_stale_venues: Option<BTreeMap<String, u64>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelsSection {
+    pub market_events: Option<u64>,
+    /// `block`, `drop_oldest`, or `drop_newest`.
+    pub market_events_overflow: Option<String>,
+    pub venue_lane: Option<u64>,
+    pub venue_lane_overflow: Option<String>,
+    pub notifications: Option<u64>,
+    pub signals: Option<u64>,
+    pub records: Option<u64>,
+    pub taps: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeSection {
+    /// Worker threads for the hot-path runtime; 0 or unset shares the main runtime.
+    pub hot_path_threads: Option<u64>,
+    pub hot_path_cores: Option<Vec<u64>>,
+    /// Venues whose WebSocket reader gets its own thread, as `venue` or `venue:core`.
+    pub venue_threads: Option<Vec<String>>,
+    /// Seconds between keepalive pings on each venue WebSocket.
+    pub ws_ping_interval_secs: Option<u64>,
+    /// Unanswered pings in a row after which a venue WebSocket reconnects.
+    pub ws_max_missed_pongs: Option<u64>,
+    /// Microseconds the strategy engine and execution bridge spin before parking; 0 or unset never spins.
+    pub busy_poll_us: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditSection {
+    /// Market ids, or `["*"]` for every market.
+    pub markets: Option<Vec<String>>,
+    pub depth: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminSection {
+    /// Listen address of the admin API.
+    pub addr: Option<String>,
+    /// Bearer token the operator routes require.
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcSection {
+    /// Listen address; the server is off when unset.
+    pub addr: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixSection {
+    /// Listen address; the gateway is off when unset.
+    pub addr: Option<String>,
+    pub comp_id: Option<String>,
+    /// SenderCompIDs allowed to log on.
+    pub clients: Option<Vec<String>>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSection {
+    /// Listen address; the signal webhook is off when unset.
+    pub addr: Option<String>,
+    pub token: Option<String>,
+    /// Names allowed in a signal's `source` field.
+    pub sources: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AmmSection {
+    /// JSON-RPC WebSocket the pools are read over.
+    pub rpc_url: Option<String>,
+    /// Outcome tokens each synthetic quote is priced for.
+    pub quote_size: Option<f64>,
+    /// File-only; taken out before flattening.
+    #[serde(default)]
+    pub pools: Vec<AmmPool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishSection {
+    /// `redis://`, `nats://` or `ws://`; the feed is off when unset.
+    pub url: Option<String>,
+    /// Stream key, subject, or WebSocket path.
+    pub topic: Option<String>,
+    /// Approximate Redis stream length cap.
+    pub maxlen: Option<u64>,
+}
+
+/// A feature flag's value as written in the file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum FlagValue {
+    Switch(bool),
+    Rollout(f64),
+    Named(String),
+}
+
+impl FlagValue {
+    fn render(&self) -> String {
+        match self {
+            FlagValue::Switch(on) => if *on { "on" } else { "off" }.to_string(),
+            FlagValue::Rollout(rollout) => rollout.to_string(),
+            FlagValue::Named(value) => value.clone(),
+        }
+    }
+}
+
+// ── Loading ───────────────────────────────────────────────────────────────────
+
+impl FileConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("reading config file {}", path.display()))?;
+        let file: Self = toml::from_str(&raw).with_context(|| format!("invalid config file {}", path.display()))?;
+        file.check_strategies().with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    fn check_strategies(self) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            self.strategies.is_none() || self.strategy.is_none(),
+            "both [[strategies]] and [strategy.<kind>] are set; use one or the other"
+        );
+        Ok(self)
+    }
+
+    /// A partial config from `--set section.key=value` overrides. Values are
+    /// parsed as TOML (`0.05`, `true`, `["a", "b"]`), falling back to a plain string.
+    pub fn from_overrides(overrides: &[String]) -> anyhow::Result<Self> {
+        let mut root = toml::Table::new();
+        for raw in overrides {
+            let (key, value) = raw
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("--set {raw}: expected section.key=value"))?;
+            let value = toml::from_str::<toml::Table>(&format!("v = {value}"))
+                .ok()
+                .and_then(|mut t| t.remove("v"))
+                .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+            let mut path: Vec<&str> = key.trim().split('.').collect();
+            let field = path.pop().filter(|f| !f.is_empty()).ok_or_else(|| anyhow::anyhow!("--set {raw}: empty key"))?;
+            let mut table = &mut root;
+            for section in path {
+                table = table
+                    .entry(section)
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                    .as_table_mut()
+                    .ok_or_else(|| anyhow::anyhow!("--set {raw}: '{section}' is not a section"))?;
+            }
+            table.insert(field.to_string(), value);
+        }
+        let file: Self = toml::Value::Table(root).try_into().context("invalid --set override")?;
+        file.check_strategies()
+    }
+
+    /// Flatten into env-var name → (value, dotted file key).
+    pub fn into_vars(self) -> (HashMap<String, (String, String)>, Option<Vec<StrategySettings>>) {
+        let mut out = Flat::default();
+
+        let l = self.logging;
+        out.put("RUST_LOG", "logging.level", l.level);
+        out.put("LOG_FORMAT", "logging.format", l.format);
+        out.put("LOG_DIR", "logging.dir", l.dir.map(|p| p.display().to_string()));
+        out.put("LOG_FILE_PREFIX", "logging.file_prefix", l.file_prefix);
+        out.put("LOG_ROTATION", "logging.rotation", l.rotation);
+        out.put("LOG_MAX_FILES", "logging.max_files", l.max_files);
+
+        let pm = self.venues.polymarket;
+        out.put("POLYMARKET_MIN_VOLUME_24H", "venues.polymarket.min_volume_24h", pm.min_volume_24h);
+        out.put("POLYMARKET_MIN_LIQUIDITY", "venues.polymarket.min_liquidity", pm.min_liquidity);
+        out.put("POLYMARKET_WS_CONNECTIONS", "venues.polymarket.ws_connections", pm.ws_connections);
+        let k = self.venues.kalshi;
+        out.put("KALSHI_MARKETS", "venues.kalshi.markets", k.markets.map(|m| m.join(",")));
+        out.put("KALSHI_API_KEY_ID", "venues.kalshi.api_key_id", k.api_key_id);
+        out.put("KALSHI_PRIVATE_KEY_FILE", "venues.kalshi.private_key_file", k.private_key_file.map(|p| p.display().to_string()));
+        out.put("KALSHI_SECRET_STORE", "venues.kalshi.secret_store", k.secret_store);
+        out.put("KALSHI_SECRET", "venues.kalshi.secret", k.secret);
+        out.put("KALSHI_WS_URL", "venues.kalshi.ws_url", k.ws_url);
+        out.put("KALSHI_REST_URL", "venues.kalshi.rest_url", k.rest_url);
+        out.put("KALSHI_DISCOVER", "venues.kalshi.discover", k.discover);
+        out.put("KALSHI_SERIES", "venues.kalshi.series", k.series.map(|s| s.join(",")));
+        out.put("KALSHI_CATEGORIES", "venues.kalshi.categories", k.categories.map(|c| c.join(",")));
+        out.put("KALSHI_MIN_VOLUME_24H", "venues.kalshi.min_volume_24h", k.min_volume_24h);
+        out.put("KALSHI_MAX_DAYS_TO_CLOSE", "venues.kalshi.max_days_to_close", k.max_days_to_close);
+        out.put("KALSHI_TOP_N", "venues.kalshi.top_n", k.top_n);
+        out.put("AMM_RPC_URL", "amm.rpc_url", self.amm.rpc_url);
+        out.put("AMM_QUOTE_SIZE", "amm.quote_size", self.amm.quote_size);
+        let b = self.venues.betfair;
+        out.put("BETFAIR_MARKETS", "venues.betfair.markets", b.markets.map(|m| m.join(",")));
+        out.put("BETFAIR_APP_KEY", "venues.betfair.app_key", b.app_key);
+        out.put("BETFAIR_SESSION_TOKEN_FILE", "venues.betfair.session_token_file", b.session_token_file.map(|p| p.display().to_string()));
+        out.put("BETFAIR_STREAM_URL", "venues.betfair.stream_url", b.stream_url);
+
+        out.put("UNIVERSE_IMPORT", "universe.import", self.universe.import.map(|p| p.display().to_string()));
+        out.put("UNIVERSE_EXPORT", "universe.export", self.universe.export.map(|p| p.display().to_string()));
+        out.put("UNIVERSE_MARKETS", "universe.markets", self.universe.markets.map(|m| m.join(",")));
+        out.put("UNIVERSE_TAGS", "universe.tags", self.universe.tags.map(|t| t.join(",")));
+        out.put("UNIVERSE_CATEGORIES", "universe.categories", self.universe.categories.map(|c| c.join(",")));
+        out.put("UNIVERSE_TOP_N", "universe.top_n", self.universe.top_n);
+        out.put("UNIVERSE_MIN_HOURS_TO_END", "universe.min_hours_to_end", self.universe.min_hours_to_end);
+        out.put("UNIVERSE_MAX_DAYS_TO_END", "universe.max_days_to_end", self.universe.max_days_to_end);
+
+        let e = self.execution;
+        out.put("EXECUTION_MODE", "execution.mode", e.mode);
+        out.put("EXECUTION_CONFIRM_LIVE", "execution.confirm_live", e.confirm_live);
+        out.put("PRIORITY_EDGE", "execution.priority_edge", e.priority_edge);
+        out.put("SIGNAL_TTL_MS", "execution.signal_ttl_ms", e.signal_ttl_ms);
+        out.put("SIGNAL_DEDUP_MS", "execution.dedup_window_ms", e.dedup_window_ms);
+        out.put("PRIVATE_KEY_SOURCE", "execution.key_source", e.key_source);
+        out.put("PRIVATE_KEY_FILE", "execution.key_file", e.key_file.map(|p| p.display().to_string()));
+        out.put("PRIVATE_KEY_KEYRING_SERVICE", "execution.keyring_service", e.keyring_service);
+        out.put("PRIVATE_KEY_KEYRING_USER", "execution.keyring_user", e.keyring_user);
+        out.put("PRIVATE_KEY_SECRET", "execution.key_secret", e.key_secret);
+        out.put("PRIVATE_KEY_SECRET_FIELD", "execution.key_secret_field", e.key_secret_field);
+        out.put("SECRET_REFRESH_SECS", "execution.secret_refresh_secs", e.secret_refresh_secs);
+        out.put("RISK_MAX_DRAWDOWN", "risk.max_drawdown", self.risk.max_drawdown);
+        out.put("RISK_MAX_DAILY_NOTIONAL", "risk.max_daily_notional", self.risk.max_daily_notional);
+        if !self.portfolio.correlation_groups.is_empty() {
+            let groups = self
+                .portfolio
+                .correlation_groups
+                .iter()
+                .map(|(market, group)| format!("{market}={group}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            out.put("CORRELATION_GROUPS", "portfolio.correlation_groups", Some(groups));
+        }
+
+        let m = self.metrics;
+        out.put("METRICS_ADDR", "metrics.addr", m.addr);
+        out.put("METRICS_PREFIX", "metrics.prefix", m.prefix);
+        if !m.labels.is_empty() {
+            let labels = m.labels.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(",");
+            out.put("METRICS_LABELS", "metrics.labels", Some(labels));
+        }
+        out.put("METRICS_LATENCY_US_BUCKETS", "metrics.latency_us_buckets", m.latency_us_buckets.map(Buckets::render));
+        out.put("METRICS_LATENCY_MS_BUCKETS", "metrics.latency_ms_buckets", m.latency_ms_buckets.map(Buckets::render));
+        out.put("METRICS_EDGE_BUCKETS_BPS", "metrics.edge_buckets_bps", m.edge_buckets_bps.map(Buckets::render));
+        out.put("METRICS_QUANTILES", "metrics.quantiles", m.quantiles.map(|q| join(&q)));
+        out.put("MARKET_GAUGES_TOP_K", "metrics.market_gauges_top_k", m.market_gauges_top_k);
+        if let Some(push) = m.push {
+            out.put("METRICS_PUSH_URL", "metrics.push.url", Some(push.url));
+            out.put("METRICS_PUSH_MODE", "metrics.push.mode", push.mode);
+            out.put("METRICS_PUSH_INTERVAL_SECS", "metrics.push.interval_secs", push.interval_secs);
+            out.put("METRICS_PUSH_USER", "metrics.push.user", push.user);
+            out.put("METRICS_PUSH_PASSWORD", "metrics.push.password", push.password);
+        }
+
+        let p = self.persistence;
+        out.put("STORAGE_BACKEND", "persistence.backend", p.backend);
+        out.put("SQLITE_PATH", "persistence.sqlite_path", p.sqlite_path.map(|p| p.display().to_string()));
+        out.put("POSTGRES_URL", "persistence.postgres_url", p.postgres_url);
+        out.put("JOURNAL_PATH", "persistence.journal_path", p.journal_path.map(|p| p.display().to_string()));
+        out.put("INSTANCE_LOCK_PATH", "persistence.instance_lock_path", p.instance_lock_path.map(|p| p.display().to_string()));
+        out.put("DB_MAX_AGE_DAYS", "persistence.db_max_age_days", p.db_max_age_days);
+        out.put("JOURNAL_MAX_AGE_DAYS", "persistence.journal_max_age_days", p.journal_max_age_days);
+        if let Some(a) = p.archive {
+            out.put("ARCHIVE_DIR", "persistence.archive.dir", Some(a.dir.display().to_string()));
+            out.put("ARCHIVE_LAYOUT", "persistence.archive.layout", a.layout);
+            out.put("ARCHIVE_MAX_AGE_DAYS", "persistence.archive.max_age_days", a.max_age_days);
+            out.put("ARCHIVE_MAX_GB", "persistence.archive.max_gb", a.max_gb);
+        }
+        if let Some(c) = p.clickhouse {
+            out.put("CLICKHOUSE_URL", "persistence.clickhouse.url", Some(c.url));
+            out.put("CLICKHOUSE_DATABASE", "persistence.clickhouse.database", c.database);
+            out.put("CLICKHOUSE_USER", "persistence.clickhouse.user", c.user);
+            out.put("CLICKHOUSE_PASSWORD", "persistence.clickhouse.password", c.password);
+        }
+        if let Some(i) = p.influx {
+            out.put("INFLUX_URL", "persistence.influx.url", Some(i.url));
+            out.put("INFLUX_ORG", "persistence.influx.org", i.org);
+            out.put("INFLUX_BUCKET", "persistence.influx.bucket", i.bucket);
+            out.put("INFLUX_TOKEN", "persistence.influx.token", i.token);
+            out.put("INFLUX_INTERVAL_SECS", "persistence.influx.interval_secs", i.interval_secs);
+        }
+        if let Some(s) = p.s3 {
+            out.put("S3_BUCKET", "persistence.s3.bucket", Some(s.bucket));
+            out.put("S3_PREFIX", "persistence.s3.prefix", s.prefix);
+            out.put("S3_ENDPOINT", "persistence.s3.endpoint", s.endpoint);
+            out.put("S3_REGION", "persistence.s3.region", s.region);
+            out.put("S3_DELETE_LOCAL", "persistence.s3.delete_local", s.delete_local);
+        }
+
+        out.put("HEALTH_STALE_SECS", "health.stale_secs", self.health.stale_secs);
+
+        let n = self.notify;
+        out.put("TELEGRAM_BOT_TOKEN", "notify.telegram_bot_token", n.telegram_bot_token);
+        out.put("TELEGRAM_CHAT_ID", "notify.telegram_chat_id", n.telegram_chat_id);
+        out.put("DISCORD_WEBHOOK_URL", "notify.discord_webhook_url", n.discord_webhook_url);
+        out.put("SLACK_WEBHOOK_URL", "notify.slack_webhook_url", n.slack_webhook_url);
+        out.put("NOTIFY_EVENTS", "notify.events", n.events.map(|e| e.join(",")));
+        out.put("NOTIFY_MAX_PER_MINUTE", "notify.max_per_minute", n.max_per_minute);
+
+        let w = self.watchdog;
+        out.put("WATCHDOG_EVENT_TIMEOUT_SECS", "watchdog.event_timeout_secs", w.event_timeout_secs);
+        out.put("WATCHDOG_SIGNAL_FULL_SECS", "watchdog.signal_full_secs", w.signal_full_secs);
+        out.put("WATCHDOG_NO_FILL_MINS", "watchdog.no_fill_mins", w.no_fill_mins);
+        out.put("WATCHDOG_ACTIVE_HOURS", "watchdog.active_hours", w.active_hours);
+        out.put("EVENT_RATE_BURST_RATIO", "watchdog.event_rate_burst_ratio", w.event_rate_burst_ratio);
+        out.put("EVENT_RATE_DROP_RATIO", "watchdog.event_rate_drop_ratio", w.event_rate_drop_ratio);
+        out.put("WATCHDOG_FEED_STALE_SECS", "watchdog.feed_stale_secs", w.feed_stale_secs);
+        out.put(
+            "WATCHDOG_FEED_STALE_VENUES",
+            "watchdog.feed_stale_venues",
+            w.feed_stale_venues.map(|venues| venues.iter().map(|(venue, secs)| format!("{venue}:{secs}")).collect::<Vec<_>>().join(",")),
+        );
+
+        let c = self.channels;
+        out.put("CHANNEL_MARKET_EVENTS", "channels.market_events", c.market_events);
+        out.put("CHANNEL_MARKET_EVENTS_OVERFLOW", "channels.market_events_overflow", c.market_events_overflow);
+        out.put("CHANNEL_VENUE_LANE", "channels.venue_lane", c.venue_lane);
+        out.put("CHANNEL_VENUE_LANE_OVERFLOW", "channels.venue_lane_overflow", c.venue_lane_overflow);
+        out.put("CHANNEL_NOTIFICATIONS", "channels.notifications", c.notifications);
+        out.put("CHANNEL_SIGNALS", "channels.signals", c.signals);
+        out.put("CHANNEL_RECORDS", "channels.records", c.records);
+        out.put("CHANNEL_TAPS", "channels.taps", c.taps);
+
+        out.put("HOT_PATH_THREADS", "runtime.hot_path_threads", self.runtime.hot_path_threads);
+        out.put(
+            "HOT_PATH_CORES",
+            "runtime.hot_path_cores",
+            self.runtime.hot_path_cores.map(|c| c.iter().map(u64::to_string).collect::<Vec<_>>().join(",")),
+        );
+        out.put("VENUE_THREADS", "runtime.venue_threads", self.runtime.venue_threads.map(|v| v.join(",")));
+        out.put("WS_PING_INTERVAL_SECS", "runtime.ws_ping_interval_secs", self.runtime.ws_ping_interval_secs);
+        out.put("WS_MAX_MISSED_PONGS", "runtime.ws_max_missed_pongs", self.runtime.ws_max_missed_pongs);
+        out.put("BUSY_POLL_US", "runtime.busy_poll_us", self.runtime.busy_poll_us);
+        if !self.rate_limits.is_empty() {
+            let limits = self
+                .rate_limits
+                .iter()
+                .flat_map(|(venue, classes)| classes.iter().map(move |(class, limit)| format!("{venue}.{class}={limit}")))
+                .collect::<Vec<_>>()
+                .join(",");
+            out.put("RATE_LIMITS", "rate_limits", Some(limits));
+        }
+
+        out.put("AUDIT_MARKETS", "audit.markets", self.audit.markets.map(|m| m.join(",")));
+        out.put("AUDIT_DEPTH", "audit.depth", self.audit.depth);
+        out.put("ADMIN_ADDR", "admin.addr", self.admin.addr);
+        out.put("ADMIN_TOKEN", "admin.token", self.admin.token);
+        out.put("GRPC_ADDR", "grpc.addr", self.grpc.addr);
+        out.put("FIX_ADDR", "fix.addr", self.fix.addr);
+        out.put("FIX_COMP_ID", "fix.comp_id", self.fix.comp_id);
+        out.put("FIX_CLIENTS", "fix.clients", self.fix.clients.map(|c| c.join(",")));
+        out.put("FIX_PASSWORD", "fix.password", self.fix.password);
+        out.put("SIGNAL_WEBHOOK_ADDR", "webhook.addr", self.webhook.addr);
+        out.put("SIGNAL_WEBHOOK_TOKEN", "webhook.token", self.webhook.token);
+        out.put("SIGNAL_WEBHOOK_SOURCES", "webhook.sources", self.webhook.sources.map(|s| s.join(",")));
+        out.put("SIGNAL_PUBLISH_URL", "publish.url", self.publish.url);
+        out.put("SIGNAL_PUBLISH_TOPIC", "publish.topic", self.publish.topic);
+        out.put("SIGNAL_PUBLISH_MAXLEN", "publish.maxlen", self.publish.maxlen);
+        if !self.flags.is_empty() {
+            let flags = self.flags.iter().map(|(k, v)| format!("{k}={}", v.render())).collect::<Vec<_>>().join(",");
+            out.put("FEATURE_FLAGS", "flags", Some(flags));
+        }
+
+        (out.0, self.strategies.or_else(|| self.strategy.map(StrategySections::into_list)))
+    }
+}
+
+impl Buckets {
+    fn render(self) -> String {
+        match self {
+            Buckets::List(buckets) => join(&buckets),
+            Buckets::Named(name) => name,
+        }
+    }
+}
+
+fn join(values: &[f64]) -> String {
+    values.iter().map(f64::to_string).collect::<Vec<_>>().join(",")
+}
+
+#[derive(Default)]
+struct Flat(HashMap<String, (String, String)>);
+
+impl Flat {
+    fn put(&mut self, env: &str, key: &str, value: Option<impl ToString>) {
+        if let Some(value) = value {
+            self.0.insert(env.to_string(), (value.to_string(), key.to_string()));
+        }
+    }
+}
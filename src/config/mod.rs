@@ -1,17 +1,1529 @@
 #![allow(dead_code)]
 
+mod file;
+pub mod watch;
+
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use file::FileConfig;
+use prediction_engine::channel::{ChannelConfig, Overflow};
+use prediction_engine::engine::PipelineChannels;
+use prediction_engine::execution::keys::KeySource;
+use prediction_engine::execution::SignalFilter;
+use prediction_engine::fix::FixConfig;
+use prediction_engine::flags::parse_rollout;
+use prediction_engine::market_data::adapters::amm::AmmPool;
+use prediction_engine::market_data::adapters::betfair::BetfairCredentials;
+use prediction_engine::market_data::adapters::kalshi::KalshiSelection;
+use prediction_engine::market_data::adapters::keepalive::KeepaliveConfig;
+use prediction_engine::market_data::adapters::polymarket::{MarketMap, UniverseSelection};
+use prediction_engine::market_data::adapters::rest_poller::PollFeed;
+use prediction_engine::market_data::types::Venue;
+use prediction_engine::publish::{PublishConfig, PublishTarget, DEFAULT_REDIS_MAXLEN, DEFAULT_TOPIC};
+use prediction_engine::rate_limit::{EndpointClass, RateLimit};
+use prediction_engine::runtime::{BusyPoll, HotPathConfig, VenueThread};
+use prediction_engine::secrets::{SecretRef, SecretStore};
+use prediction_engine::strategy::arbitrage::ArbitrageStrategy;
+use prediction_engine::state::rewards::RewardsBook;
+use prediction_engine::strategy::market_maker::{MarketMakerConfig, MarketMakerStrategy, QuoteMode};
+use prediction_engine::strategy::scoped::MarketScoped;
+use prediction_engine::strategy::traits::Strategy;
+use prediction_engine::webhook::WebhookConfig;
+
+/// Read when `CONFIG_FILE` is unset and it exists in the working directory.
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+const DEFAULT_FIX_COMP_ID: &str = "PREDENGINE";
+
+// min_edge = 0.025 (2.5%): Polymarket charges ~1% taker fee per leg (2 legs = 2%
+// total). A 2.5% edge threshold ensures we're profitable net of fees, with a small
+// buffer for slippage. Real edges from the CLOB are typically 0.002–0.004 (below
+// fees), so signals should be rare and only fire on genuine dislocations.
+const DEFAULT_ARB_MIN_EDGE: f64 = 0.025;
+const DEFAULT_ARB_SIZE: f64 = 5.0;
+
+/// Where signals, intents, reports, and fills are persisted.
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Sqlite(PathBuf),
+    Postgres(String),
+}
+
+/// Parquet market-data archive location.
+#[derive(Debug, Clone)]
+pub struct ArchiveSettings {
+    pub dir: PathBuf,
+    pub layout: Option<String>,
+}
+
+/// ClickHouse tick/fill sink connection.
+#[derive(Debug, Clone)]
+pub struct ClickHouseSettings {
+    pub url: String,
+    pub database: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+/// InfluxDB exporter connection.
+#[derive(Debug, Clone)]
+pub struct InfluxSettings {
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    pub token: Option<String>,
+    pub interval: Duration,
+}
+
+/// Object-storage upload of finished archive files.
+#[derive(Debug, Clone)]
+pub struct UploadSettings {
+    pub bucket: String,
+    pub prefix: String,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    pub delete_local: bool,
+}
+
+/// Retention limits. Every field is optional; unset = keep forever.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionSettings {
+    pub archive_max_age: Option<Duration>,
+    pub archive_max_bytes: Option<u64>,
+    /// Age past which database rows are deleted.
+    pub db_max_age: Option<Duration>,
+    /// Age past which journal entries not needed for recovery are compacted away.
+    pub journal_max_age: Option<Duration>,
+}
+
+/// Prometheus histogram rendering. Each family is a comma-separated bucket
+/// list, or `summary` to render it as a summary with `quantiles`.
+#[derive(Debug, Clone)]
+pub struct MetricsSettings {
+    /// Scrape listener; unused when `push` is set.
+    pub listen: SocketAddr,
+    /// Push instead of serving `/metrics`.
+    pub push: Option<MetricsPushSettings>,
+    /// Prepended to every metric name as `<prefix>_<name>`.
+    pub prefix: Option<String>,
+    /// Added to every series, e.g. `instance`, `environment`, `strategy_set`.
+    pub labels: Vec<(String, String)>,
+    pub latency_us_buckets: Option<Vec<f64>>,
+    pub latency_ms_buckets: Option<Vec<f64>>,
+    /// In basis points, as configured.
+    pub edge_buckets_bps: Option<Vec<f64>>,
+    pub quantiles: Vec<f64>,
+}
+
+/// What `METRICS_PUSH_URL` points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsPushMode {
+    /// A Pushgateway job URL.
+    PushGateway,
+    /// An endpoint ingesting the text exposition format (e.g. VictoriaMetrics import).
+    Import,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsPushSettings {
+    pub url: String,
+    pub mode: MetricsPushMode,
+    pub interval: Duration,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// One strategy instance, as configured under `[[strategies]]` or `[strategy.<kind>]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategySettings {
+    Arbitrage(ArbitrageSettings),
+    MarketMaker(MarketMakerSettings),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArbitrageSettings {
+    #[serde(default = "default_arb_min_edge")]
+    pub min_edge: f64,
+    #[serde(default = "default_arb_size")]
+    pub size: f64,
+    /// Gamma market ids to trade; empty = every subscribed market.
+    #[serde(default)]
+    pub markets: Vec<String>,
+}
+
+/// Unset fields keep `MarketMakerConfig::default()`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketMakerSettings {
+    pub half_spread: Option<f64>,
+    pub quote_size: Option<f64>,
+    pub max_inventory: Option<f64>,
+    pub skew_factor: Option<f64>,
+    pub widen_factor: Option<f64>,
+    pub tick_size: Option<f64>,
+    /// `spread` (default) or `rewards`.
+    pub mode: Option<QuoteMode>,
+    pub reward_spread_fraction: Option<f64>,
+    pub reward_share: Option<f64>,
+    /// Gamma market ids to quote; empty = every subscribed market.
+    #[serde(default)]
+    pub markets: Vec<String>,
+}
+
+impl MarketMakerSettings {
+    fn config(&self) -> MarketMakerConfig {
+        let defaults = MarketMakerConfig::default();
+        MarketMakerConfig {
+            half_spread: self.half_spread.unwrap_or(defaults.half_spread),
+            quote_size: self.quote_size.unwrap_or(defaults.quote_size),
+            max_inventory: self.max_inventory.unwrap_or(defaults.max_inventory),
+            skew_factor: self.skew_factor.unwrap_or(defaults.skew_factor),
+            widen_factor: self.widen_factor.unwrap_or(defaults.widen_factor),
+            tick_size: self.tick_size.unwrap_or(defaults.tick_size),
+            mode: self.mode.unwrap_or(defaults.mode),
+            reward_spread_fraction: self.reward_spread_fraction.unwrap_or(defaults.reward_spread_fraction),
+            reward_share: self.reward_share.unwrap_or(defaults.reward_share),
+        }
+    }
+}
+
+impl StrategySettings {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StrategySettings::Arbitrage(_) => "arbitrage",
+            StrategySettings::MarketMaker(_) => "market_maker",
+        }
+    }
+
+    /// Markets this strategy is limited to; empty = all.
+    pub fn markets(&self) -> &[String] {
+        match self {
+            StrategySettings::Arbitrage(s) => &s.markets,
+            StrategySettings::MarketMaker(s) => &s.markets,
+        }
+    }
+
+    /// Market makers read reward programs from, and accrue estimates into, `rewards`.
+    pub fn build(&self, rewards: &RewardsBook) -> anyhow::Result<Box<dyn Strategy>> {
+        let strategy: Box<dyn Strategy> = match self {
+            StrategySettings::Arbitrage(s) => Box::new(ArbitrageStrategy::new(s.min_edge, s.size)?),
+            StrategySettings::MarketMaker(s) => {
+                Box::new(MarketMakerStrategy::new(s.config())?.with_rewards(rewards.clone()))
+            }
+        };
+        Ok(match self.markets() {
+            [] => strategy,
+            markets => Box::new(MarketScoped::new(strategy, markets.iter().cloned())),
+        })
+    }
+}
+
+fn default_arb_min_edge() -> f64 {
+    DEFAULT_ARB_MIN_EDGE
+}
+
+fn default_arb_size() -> f64 {
+    DEFAULT_ARB_SIZE
+}
+
+/// The raw settings behind a [`KeySource`], from `[execution]` or a wallet.
+struct KeySettings {
+    source: Option<String>,
+    /// Variable read by the `env` source.
+    env: String,
+    file: Option<PathBuf>,
+    keyring_service: Option<String>,
+    keyring_user: Option<String>,
+    secret: Option<String>,
+    secret_field: Option<String>,
+}
+
+impl KeySettings {
+    /// `describe` names a setting (by its environment-variable name) in errors.
+    fn resolve(self, describe: impl Fn(&'static str) -> String) -> anyhow::Result<KeySource> {
+        let source = self.source.as_deref().unwrap_or("env");
+        let requires = |setting: &'static str| {
+            anyhow::anyhow!("{}={source} requires {}", describe("PRIVATE_KEY_SOURCE"), describe(setting))
+        };
+        Ok(match source {
+            "env" => KeySource::Env(self.env),
+            "keyring" => KeySource::Keyring {
+                service: self.keyring_service.unwrap_or_else(|| "prediction-engine".to_string()),
+                user: self.keyring_user.unwrap_or_else(|| "polymarket".to_string()),
+            },
+            "age" => KeySource::Age(self.file.ok_or_else(|| requires("PRIVATE_KEY_FILE"))?),
+            "gpg" => KeySource::Gpg(self.file.ok_or_else(|| requires("PRIVATE_KEY_FILE"))?),
+            "vault" | "aws" => {
+                let store = if source == "vault" { SecretStore::Vault } else { SecretStore::AwsSecretsManager };
+                let id = self.secret.ok_or_else(|| requires("PRIVATE_KEY_SECRET"))?;
+                // Vault secrets are always key/value; a Secrets Manager secret may be the bare key.
+                let field = self.secret_field.or_else(|| (store == SecretStore::Vault).then(|| "private_key".to_string()));
+                KeySource::Secret(SecretRef { store, id, field })
+            }
+            other => anyhow::bail!(
+                "unknown {} '{other}' (expected env, keyring, age, gpg, vault, or aws)",
+                describe("PRIVATE_KEY_SOURCE")
+            ),
+        })
+    }
+}
+
+/// A signing wallet of its own for some strategies, as configured under
+/// `[[wallets]]`. Strategies not assigned to one trade through the default
+/// wallet from `[execution]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WalletSettings {
+    pub name: String,
+    /// Names of the strategies whose signals this wallet signs.
+    pub strategies: Vec<String>,
+    /// As `[execution] key_source`; `env` by default.
+    pub key_source: Option<String>,
+    /// Variable the `env` source reads; `PRIVATE_KEY_<NAME>` by default.
+    pub key_env: Option<String>,
+    pub key_file: Option<PathBuf>,
+    pub keyring_service: Option<String>,
+    pub keyring_user: Option<String>,
+    pub key_secret: Option<String>,
+    pub key_secret_field: Option<String>,
+    /// This wallet's own daily cap; `risk.max_daily_notional` when unset.
+    pub max_daily_notional: Option<f64>,
+}
+
+impl WalletSettings {
+    pub fn key_source(&self) -> anyhow::Result<KeySource> {
+        KeySettings {
+            source: self.key_source.clone(),
+            env: self.key_env.clone().unwrap_or_else(|| format!("PRIVATE_KEY_{}", self.name.to_ascii_uppercase().replace('-', "_"))),
+            file: self.key_file.clone(),
+            keyring_service: self.keyring_service.clone(),
+            keyring_user: self.keyring_user.clone(),
+            secret: self.key_secret.clone(),
+            secret_field: self.key_secret_field.clone(),
+        }
+        .resolve(|name| self.describe(name))
+    }
+
+    /// This wallet's twin of an `[execution]` key setting, for errors.
+    fn describe(&self, name: &'static str) -> String {
+        let field = match name {
+            "PRIVATE_KEY_SOURCE" => "key_source",
+            "PRIVATE_KEY_FILE" => "key_file",
+            "PRIVATE_KEY_SECRET" => "key_secret",
+            other => other,
+        };
+        format!("wallets.{}.{field}", self.name)
+    }
+}
+
+/// Which executor the execution bridge drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// Simulated fills; no key needed.
+    Paper,
+    /// Orders signed with the trading key but never posted; fills are simulated.
+    DryRun,
+    /// Real orders on the Polymarket CLOB; needs the trading key (see `key_source`)
+    /// and `confirm_live`.
+    Live,
+}
+
+impl ExecutionMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            ExecutionMode::Paper => "paper",
+            ExecutionMode::DryRun => "dry_run",
+            ExecutionMode::Live => "live",
+        }
+    }
+
+    pub fn needs_key(self) -> bool {
+        matches!(self, ExecutionMode::DryRun | ExecutionMode::Live)
+    }
+}
+
+/// Pre-trade risk limits.
+#[derive(Debug, Clone)]
+pub struct RiskSettings {
+    pub max_drawdown: f64,
+    pub max_daily_notional: f64,
+}
+
+/// Polymarket discovery thresholds. Ignored when the universe is imported.
+#[derive(Debug, Clone)]
+pub struct PolymarketSettings {
+    pub min_volume_24h: f64,
+    pub min_liquidity: f64,
+}
+
+/// Kalshi market data. The adapter runs only when `markets` is non-empty.
+#[derive(Debug, Clone)]
+pub struct KalshiSettings {
+    /// Market tickers to stream, on top of any discovery finds.
+    pub markets: Vec<String>,
+    /// Discover open markets over REST and stream those it keeps as well.
+    pub discovery: Option<KalshiSelection>,
+    /// Required when `markets` or `discovery` is set; checked by `validate`.
+    pub credentials: Option<KalshiCredentialSource>,
+    /// Overrides Kalshi's production WebSocket, e.g. for the demo environment.
+    pub ws_url: Option<String>,
+    /// Overrides Kalshi's production REST API, which discovery lists from.
+    pub rest_url: Option<String>,
+}
+
+/// Where the Kalshi API key comes from.
+#[derive(Debug, Clone)]
+pub enum KalshiCredentialSource {
+    /// The key id, with the PEM private key in a file.
+    File { api_key_id: String, private_key: PathBuf },
+    /// One JSON secret with `api_key_id` and `private_key` fields.
+    Secret { store: SecretStore, id: String },
+}
+
+/// Betfair Exchange market data. The adapter runs only when `markets` is
+/// non-empty.
+#[derive(Debug, Clone)]
+pub struct BetfairSettings {
+    /// Betfair market ids to stream, e.g. `1.234567890`.
+    pub markets: Vec<String>,
+    /// Required when `markets` is set; checked by `validate`.
+    pub credentials: Option<BetfairCredentials>,
+    /// Overrides Betfair's production stream, e.g. for the integration environment.
+    pub stream_url: Option<String>,
+}
+
+/// On-chain AMM pools. The adapter runs only when `pools` is non-empty.
+#[derive(Debug, Clone)]
+pub struct AmmSettings {
+    /// Required when `pools` is set; checked by `validate`.
+    pub rpc_url: Option<String>,
+    /// Outcome tokens each quote is priced for; the adapter's default when unset.
+    pub quote_size: Option<f64>,
+    /// From `[[amm.pools]]`.
+    pub pools: Vec<AmmPool>,
+}
+
+/// Which layer a setting's effective value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingSource {
+    Default,
+    File,
+    /// The process environment, including `.env`.
+    Env,
+    /// `--set` on the command line.
+    Override,
+}
+
+/// One setting as resolved at load time, for `--print-config`.
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    /// Environment variable name.
+    pub name: String,
+    /// Dotted config-file key, when the value came from the file or `--set`.
+    pub key: Option<String>,
+    /// Raw value; `None` when the built-in default applies.
+    pub value: Option<String>,
+    pub source: SettingSource,
+}
+
+/// Log line format on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per line, event fields flattened, span fields
+    /// (`correlation_id`, `strategy`, `venue`, `market_id`) under `span`.
+    Json,
+}
+
+/// How often the log file rolls over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+/// Rolling log file written alongside stdout, in the same format.
+#[derive(Debug, Clone)]
+pub struct LogFileSettings {
+    pub dir: PathBuf,
+    /// File name prefix; rotated files get a date (and hour) suffix.
+    pub prefix: String,
+    pub rotation: LogRotation,
+    /// Oldest rotated files beyond this count are deleted. `None` keeps all.
+    pub max_files: Option<usize>,
+}
+
+/// Strategy decision audit. More markets can be watched at runtime via the admin API.
+#[derive(Debug, Clone)]
+pub struct AuditSettings {
+    /// Markets watched from startup.
+    pub markets: Vec<String>,
+    /// Watch every market (`AUDIT_MARKETS=*`).
+    pub all: bool,
+    /// Decisions kept per market.
+    pub depth: usize,
+}
+
+/// Operator notifications. Enabled when at least one channel is configured.
+#[derive(Debug, Clone)]
+pub struct NotifySettings {
+    pub telegram: Option<(String, String)>,
+    pub discord_webhook: Option<String>,
+    pub slack_webhook: Option<String>,
+    /// Event kind names (`fill`, `rejection`, `risk`, `disconnect`, `watchdog`, `daily`).
+    pub events: Vec<String>,
+    pub max_per_minute: usize,
+}
+
+/// Data-flow watchdog thresholds.
+#[derive(Debug, Clone)]
+pub struct WatchdogSettings {
+    pub event_timeout: Duration,
+    pub signal_full_after: Duration,
+    /// No-fill alert; disabled when `None`.
+    pub fill_timeout: Option<Duration>,
+    /// UTC hours `[start, end)` in which fills are expected.
+    pub active_hours: Option<(u32, u32)>,
+    /// Event-rate anomaly: burst above this multiple of the normal rate.
+    pub event_rate_burst_ratio: f64,
+    /// Event-rate anomaly: drop below this fraction of the normal rate.
+    pub event_rate_drop_ratio: f64,
+    /// Feed watchdog: a token quiet this long is marked stale. Venues
+    /// without a deadline of their own aren't watched when `None`.
+    pub feed_stale_after: Option<Duration>,
+    /// Feed watchdog deadlines for particular venues, in the order given.
+    pub feed_stale_venues: Vec<(Venue, Duration)>,
+}
+
+impl WatchdogSettings {
+    /// Whether any venue's feeds are watched for staleness.
+    pub fn watches_feeds(&self) -> bool {
+        self.feed_stale_after.is_some() || !self.feed_stale_venues.is_empty()
+    }
+}
+
+/// Capacities of the pipeline's bounded channels. Market events and venue
+/// lanes also take an overflow policy; the other stages' policies are fixed:
+/// notifications coalesce per market, signals wait for space, record sinks
+/// drop the newest message (counted in `channel_send_failures_total`), and
+/// event-bus subscribers that fall behind miss the oldest.
+#[derive(Debug, Clone)]
+pub struct ChannelSettings {
+    /// Adapters → router, in batches of one venue message each.
+    pub market_events: ChannelConfig,
+    /// Router → each venue's market worker, in the same batches.
+    pub venue_lane: ChannelConfig,
+    /// Market workers → strategy engine: markets pending evaluation.
+    pub notifications: usize,
+    /// Strategy engine → execution bridge.
+    pub signals: usize,
+    /// Recorder → each record sink (database, ClickHouse, ...).
+    pub records: usize,
+    /// Router → event bus subscribers (archiver, ClickHouse, anomaly
+    /// detector, ...): how far each may fall behind.
+    pub taps: usize,
+}
+
+impl ChannelSettings {
+    /// The capacities the engine builder takes; the rest are the binary's own.
+    pub fn pipeline(&self) -> PipelineChannels {
+        PipelineChannels {
+            market_events: self.market_events,
+            venue_lane: self.venue_lane,
+            notifications: self.notifications,
+            signals: self.signals,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// Config file this was loaded from, if any.
+    pub source: Option<PathBuf>,
+    /// `--set` overrides it was loaded with; re-applied on reload.
+    pub overrides: Vec<String>,
     pub log_level: String,
+    pub log_format: LogFormat,
+    /// File log sink. Stdout only when `None`.
+    pub log_file: Option<LogFileSettings>,
+    /// Persistence backend. Persistence is disabled when `None`.
+    pub storage: Option<StorageConfig>,
+    /// Parquet archiver. Disabled when `None`.
+    pub archive: Option<ArchiveSettings>,
+    /// ClickHouse sink. Disabled when `None`.
+    pub clickhouse: Option<ClickHouseSettings>,
+    /// InfluxDB exporter. Disabled when `None`.
+    pub influx: Option<InfluxSettings>,
+    /// Archive uploader. Disabled when `None`; requires the archiver.
+    pub upload: Option<UploadSettings>,
+    /// Crash-recovery journal, replayed on startup.
+    pub journal_path: PathBuf,
+    /// Exclusive lock held while the engine runs; see `instance_lock`.
+    pub instance_lock_path: PathBuf,
+    /// Load the market universe from this file instead of running discovery.
+    pub universe_import: Option<PathBuf>,
+    /// Write the session's market universe to this file after startup.
+    pub universe_export: Option<PathBuf>,
+    /// Pinned markets, tag and category filters, and top-N for discovery.
+    /// Ignored when the universe is imported.
+    pub universe: UniverseSelection,
+    pub retention: RetentionSettings,
+    pub metrics: MetricsSettings,
+    /// `/readyz` fails once the market cache has gone this long without an update.
+    pub health_stale_after: Duration,
+    /// Notifier. Disabled when `None`.
+    pub notify: Option<NotifySettings>,
+    pub watchdog: WatchdogSettings,
+    pub channels: ChannelSettings,
+    /// Separate runtime for the market-data → execution pipeline. Shares
+    /// the main runtime when `None`.
+    pub hot_path: Option<HotPathConfig>,
+    /// Venues whose WebSocket reader gets a thread of its own.
+    pub venue_threads: Vec<VenueThread>,
+    /// Ping schedule of the venue WebSockets.
+    pub ws_keepalive: KeepaliveConfig,
+    /// REST requests per second by venue and endpoint class, over
+    /// `rate_limit::DEFAULT_LIMITS`; 0 lifts a limit.
+    pub rate_limits: Vec<RateLimit>,
+    /// Spin budget for the strategy engine and execution bridge receives.
+    pub busy_poll: BusyPoll,
+    /// Per-market gauge cardinality cap; 0 disables the per-market gauges.
+    pub market_gauges_top_k: usize,
+    pub audit: AuditSettings,
+    /// Admin HTTP API listener.
+    pub admin_addr: SocketAddr,
+    /// Bearer token the admin API's operator routes require; they're
+    /// refused when `None`.
+    pub admin_token: Option<String>,
+    /// gRPC control/streaming API listener. Disabled when `None`; needs the `grpc` feature.
+    pub grpc_addr: Option<SocketAddr>,
+    /// FIX order-routing gateway. Disabled when `None`.
+    pub fix: Option<FixConfig>,
+    /// HTTP endpoint for externally generated signals. Disabled when `None`.
+    pub webhook: Option<WebhookConfig>,
+    /// Outbound feed of signals and their outcomes. Disabled when `None`.
+    pub publish: Option<PublishConfig>,
+    /// Feature flag name → rollout fraction; see `prediction_engine::flags`.
+    pub flags: BTreeMap<String, f64>,
+    /// Market id → correlation group for the portfolio's `exposure_by_group`.
+    /// Markets not listed form their own group.
+    pub correlation_groups: BTreeMap<String, String>,
+    pub polymarket: PolymarketSettings,
+    /// WebSockets the Polymarket adapter spreads its markets over.
+    pub polymarket_ws_connections: usize,
+    pub kalshi: KalshiSettings,
+    pub betfair: BetfairSettings,
+    pub amm: AmmSettings,
+    pub strategies: Vec<StrategySettings>,
+    pub execution_mode: ExecutionMode,
+    /// Signals with at least this edge skip ahead of the rest; see
+    /// `execution::lanes`. Off when `None`.
+    pub priority_edge: Option<f64>,
+    /// How old, and how soon repeated, a strategy signal may be before the
+    /// bridge drops it.
+    pub signal_filter: SignalFilter,
+    /// Explicit opt-in required for `ExecutionMode::Live`.
+    pub confirm_live: bool,
+    /// Where the trading key is loaded from in live mode.
+    pub key_source: KeySource,
+    /// How often a Vault or AWS key source is re-fetched to pick up rotation;
+    /// `None` fetches once at startup.
+    pub secret_refresh: Option<Duration>,
+    /// Extra signing wallets; strategies not assigned to one use `key_source`.
+    pub wallets: Vec<WalletSettings>,
+    /// REST endpoints to poll for quotes, from `[[pollers]]`.
+    pub pollers: Vec<PollFeed>,
+    pub risk: RiskSettings,
+    /// Every setting read during load, in read order.
+    pub provenance: Vec<Provenance>,
+    pub strategies_source: SettingSource,
 }
 
 impl Config {
-    pub fn from_env() -> anyhow::Result<Self> {
+    /// Load settings, highest precedence first: `overrides` (`section.key=value`,
+    /// from `--set`), the environment (and `.env`), then the config file. The file
+    /// is `path`, else `CONFIG_FILE`, else `./config.toml` if present.
+    pub fn load(path: Option<&Path>, overrides: &[String]) -> anyhow::Result<Self> {
         // dotenvy loads .env, but doesn't override already-set env vars
         dotenvy::dotenv().ok();
 
-        let log_level = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+        let path = match (path, std::env::var("CONFIG_FILE")) {
+            (Some(path), _) => Some(path.to_path_buf()),
+            (None, Ok(path)) => Some(PathBuf::from(path)),
+            (None, Err(_)) => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|p| p.exists()),
+        };
+        let (file, file_strategies, wallets, pollers, amm_pools) = match &path {
+            Some(path) => {
+                let mut file = FileConfig::load(path)?;
+                let wallets = std::mem::take(&mut file.wallets);
+                let pollers = std::mem::take(&mut file.pollers);
+                let amm_pools = std::mem::take(&mut file.amm.pools);
+                let (vars, strategies) = file.into_vars();
+                (vars, strategies, wallets, pollers, amm_pools)
+            }
+            None => Default::default(),
+        };
+        let (flat_overrides, override_strategies) = FileConfig::from_overrides(overrides)?.into_vars();
+        let strategies_source = match (&override_strategies, &file_strategies) {
+            (Some(_), _) => SettingSource::Override,
+            (None, Some(_)) => SettingSource::File,
+            (None, None) => SettingSource::Default,
+        };
+        let vars = Vars { overrides: flat_overrides, file, path, seen: RefCell::default() };
+        let mut config = Self::from_vars(&vars, override_strategies.or(file_strategies))?;
+        config.wallets = wallets;
+        config.pollers = pollers;
+        config.amm.pools = amm_pools;
+        config.validate(&vars)?;
+        config.overrides = overrides.to_vec();
+        config.strategies_source = strategies_source;
+        config.provenance = vars.seen.into_inner();
+        Ok(config)
+    }
+
+    fn from_vars(vars: &Vars, strategies: Option<Vec<StrategySettings>>) -> anyhow::Result<Self> {
+
+        let log_level = vars.var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+        let log_format = match vars.var("LOG_FORMAT").ok().as_deref() {
+            Some("json") => LogFormat::Json,
+            Some("text") | None => LogFormat::Text,
+            Some(other) => anyhow::bail!("unknown {} '{other}' (expected text or json)", vars.describe("LOG_FORMAT")),
+        };
+        let log_file = match vars.var("LOG_DIR").ok() {
+            Some(dir) => Some(LogFileSettings {
+                dir: PathBuf::from(dir),
+                prefix: vars.var("LOG_FILE_PREFIX").unwrap_or_else(|_| "prediction-engine.log".to_string()),
+                rotation: match vars.var("LOG_ROTATION").ok().as_deref() {
+                    Some("hourly") => LogRotation::Hourly,
+                    Some("daily") | None => LogRotation::Daily,
+                    Some("never") => LogRotation::Never,
+                    Some(other) => anyhow::bail!("unknown {} '{other}' (expected hourly, daily, or never)", vars.describe("LOG_ROTATION")),
+                },
+                max_files: env_parse::<usize>(vars, "LOG_MAX_FILES")?,
+            }),
+            None => None,
+        };
+        let storage = match vars.var("STORAGE_BACKEND").ok().as_deref() {
+            Some("postgres") => Some(StorageConfig::Postgres(
+                vars.var("POSTGRES_URL")
+                    .map_err(|_| anyhow::anyhow!("STORAGE_BACKEND=postgres requires POSTGRES_URL"))?,
+            )),
+            Some("sqlite") | None => vars.var("SQLITE_PATH")
+                .ok()
+                .map(|p| StorageConfig::Sqlite(PathBuf::from(p))),
+            Some(other) => anyhow::bail!("unknown {} '{other}' (expected sqlite or postgres)", vars.describe("STORAGE_BACKEND")),
+        };
+
+        let archive = vars.var("ARCHIVE_DIR").ok().map(|dir| ArchiveSettings {
+            dir: PathBuf::from(dir),
+            layout: vars.var("ARCHIVE_LAYOUT").ok(),
+        });
+
+        let clickhouse = vars.var("CLICKHOUSE_URL").ok().map(|url| ClickHouseSettings {
+            url,
+            database: vars.var("CLICKHOUSE_DATABASE").unwrap_or_else(|_| "default".to_string()),
+            user: vars.var("CLICKHOUSE_USER").ok(),
+            password: vars.var("CLICKHOUSE_PASSWORD").ok(),
+        });
+
+        let influx = match vars.var("INFLUX_URL").ok() {
+            Some(url) => Some(InfluxSettings {
+                url,
+                org: vars.var("INFLUX_ORG").unwrap_or_default(),
+                bucket: vars.var("INFLUX_BUCKET").unwrap_or_else(|_| "prediction-engine".to_string()),
+                token: vars.var("INFLUX_TOKEN").ok(),
+                interval: Duration::from_secs(env_parse::<u64>(vars, "INFLUX_INTERVAL_SECS")?.unwrap_or(10)),
+            }),
+            None => None,
+        };
+
+        let upload = vars.var("S3_BUCKET").ok().map(|bucket| UploadSettings {
+            bucket,
+            prefix: vars.var("S3_PREFIX").unwrap_or_default(),
+            endpoint: vars.var("S3_ENDPOINT").ok(),
+            region: vars.var("S3_REGION").ok(),
+            delete_local: vars.var("S3_DELETE_LOCAL").is_ok_and(|v| v == "true" || v == "1"),
+        });
+
+        let journal_path = vars.var("JOURNAL_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("data/journal.bin"));
+        let instance_lock_path = vars.var("INSTANCE_LOCK_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("data/engine.lock"));
+
+        let universe_import = vars.var("UNIVERSE_IMPORT").ok().map(PathBuf::from);
+        let universe_export = vars.var("UNIVERSE_EXPORT").ok().map(PathBuf::from);
+        let universe = UniverseSelection {
+            markets: env_strings(vars, "UNIVERSE_MARKETS"),
+            tags: env_strings(vars, "UNIVERSE_TAGS"),
+            categories: env_strings(vars, "UNIVERSE_CATEGORIES"),
+            top_n: env_parse::<usize>(vars, "UNIVERSE_TOP_N")?,
+            min_time_to_end: env_parse::<u64>(vars, "UNIVERSE_MIN_HOURS_TO_END")?.map(|hours| Duration::from_secs(hours * 3_600)),
+            max_time_to_end: env_days(vars, "UNIVERSE_MAX_DAYS_TO_END")?,
+        };
+
+        let retention = RetentionSettings {
+            archive_max_age: env_days(vars, "ARCHIVE_MAX_AGE_DAYS")?,
+            archive_max_bytes: env_parse::<u64>(vars, "ARCHIVE_MAX_GB")?.map(|gb| gb * 1_000_000_000),
+            db_max_age: env_days(vars, "DB_MAX_AGE_DAYS")?,
+            journal_max_age: env_days(vars, "JOURNAL_MAX_AGE_DAYS")?,
+        };
+
+        let defaults = prediction_engine::metrics::HistogramConfig::default();
+        let metrics = MetricsSettings {
+            listen: match vars.var("METRICS_ADDR") {
+                Ok(raw) => raw.parse().map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid host:port", vars.describe("METRICS_ADDR")))?,
+                Err(_) => SocketAddr::from(([0, 0, 0, 0], 9000)),
+            },
+            push: match vars.var("METRICS_PUSH_URL").ok() {
+                Some(url) => Some(MetricsPushSettings {
+                    url,
+                    mode: match vars.var("METRICS_PUSH_MODE").ok().as_deref() {
+                        Some("pushgateway") | None => MetricsPushMode::PushGateway,
+                        Some("import") => MetricsPushMode::Import,
+                        Some(other) => anyhow::bail!("unknown {} '{other}' (expected pushgateway or import)", vars.describe("METRICS_PUSH_MODE")),
+                    },
+                    interval: Duration::from_secs(env_parse::<u64>(vars, "METRICS_PUSH_INTERVAL_SECS")?.unwrap_or(15)),
+                    username: vars.var("METRICS_PUSH_USER").ok(),
+                    password: vars.var("METRICS_PUSH_PASSWORD").ok(),
+                }),
+                None => None,
+            },
+            prefix: vars.var("METRICS_PREFIX").ok().filter(|p| !p.is_empty()),
+            labels: env_labels(vars, "METRICS_LABELS")?,
+            latency_us_buckets: env_buckets(vars, "METRICS_LATENCY_US_BUCKETS", defaults.latency_us_buckets)?,
+            latency_ms_buckets: env_buckets(vars, "METRICS_LATENCY_MS_BUCKETS", defaults.latency_ms_buckets)?,
+            edge_buckets_bps: env_buckets(vars, 
+                "METRICS_EDGE_BUCKETS_BPS",
+                defaults.edge_buckets.map(|b| b.iter().map(|e| e * 10_000.0).collect()),
+            )?,
+            quantiles: env_list(vars, "METRICS_QUANTILES")?.unwrap_or(defaults.quantiles),
+        };
+
+        let health_stale_after = Duration::from_secs(env_parse::<u64>(vars, "HEALTH_STALE_SECS")?.unwrap_or(60));
+
+        let telegram = match (vars.var("TELEGRAM_BOT_TOKEN").ok(), vars.var("TELEGRAM_CHAT_ID").ok()) {
+            (Some(token), Some(chat)) => Some((token, chat)),
+            (None, None) => None,
+            _ => anyhow::bail!("TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID must be set together"),
+        };
+        let discord_webhook = vars.var("DISCORD_WEBHOOK_URL").ok();
+        let slack_webhook = vars.var("SLACK_WEBHOOK_URL").ok();
+        let notify = if telegram.is_some() || discord_webhook.is_some() || slack_webhook.is_some() {
+            Some(NotifySettings {
+                telegram,
+                discord_webhook,
+                slack_webhook,
+                events: vars.var("NOTIFY_EVENTS")
+                    .map(|v| v.split(',').map(|e| e.trim().to_string()).filter(|e| !e.is_empty()).collect())
+                    .unwrap_or_else(|_| ["fill", "rejection", "risk", "disconnect", "watchdog", "daily", "restart"].map(String::from).to_vec()),
+                max_per_minute: env_parse::<usize>(vars, "NOTIFY_MAX_PER_MINUTE")?.unwrap_or(20),
+            })
+        } else {
+            None
+        };
+
+        let active_hours = match vars.var("WATCHDOG_ACTIVE_HOURS").ok() {
+            Some(raw) => {
+                let parsed = raw
+                    .split_once('-')
+                    .and_then(|(start, end)| Some((start.trim().parse::<u32>().ok()?, end.trim().parse::<u32>().ok()?)))
+                    .filter(|&(start, end)| start < 24 && end <= 24);
+                Some(parsed.ok_or_else(|| anyhow::anyhow!("{}='{raw}' (expected UTC hours like 13-21)", vars.describe("WATCHDOG_ACTIVE_HOURS")))?)
+            }
+            None => None,
+        };
+        let mut feed_stale_venues: Vec<(Venue, Duration)> = Vec::new();
+        if let Ok(raw) = vars.var("WATCHDOG_FEED_STALE_VENUES") {
+            for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (venue, secs) = entry.split_once(':').ok_or_else(|| {
+                    anyhow::anyhow!("{}: '{entry}' (expected venue:seconds, e.g. rest:300)", vars.describe("WATCHDOG_FEED_STALE_VENUES"))
+                })?;
+                let venue: Venue = venue.trim().parse().map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe("WATCHDOG_FEED_STALE_VENUES")))?;
+                let secs = secs
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or_else(|| anyhow::anyhow!("{}: '{secs}' is not a positive number of seconds", vars.describe("WATCHDOG_FEED_STALE_VENUES")))?;
+                anyhow::ensure!(
+                    !feed_stale_venues.iter().any(|(v, _)| *v == venue),
+                    "{}: {} is listed twice",
+                    vars.describe("WATCHDOG_FEED_STALE_VENUES"),
+                    venue.name()
+                );
+                feed_stale_venues.push((venue, Duration::from_secs(secs)));
+            }
+        }
+        let feed_stale_after = match env_parse::<u64>(vars, "WATCHDOG_FEED_STALE_SECS")? {
+            Some(0) => anyhow::bail!("{} must be positive", vars.describe("WATCHDOG_FEED_STALE_SECS")),
+            secs => secs.map(Duration::from_secs),
+        };
+        let watchdog = WatchdogSettings {
+            event_timeout: Duration::from_secs(env_parse::<u64>(vars, "WATCHDOG_EVENT_TIMEOUT_SECS")?.unwrap_or(60)),
+            signal_full_after: Duration::from_secs(env_parse::<u64>(vars, "WATCHDOG_SIGNAL_FULL_SECS")?.unwrap_or(30)),
+            fill_timeout: env_parse::<u64>(vars, "WATCHDOG_NO_FILL_MINS")?.map(|m| Duration::from_secs(m * 60)),
+            active_hours,
+            event_rate_burst_ratio: env_parse::<f64>(vars, "EVENT_RATE_BURST_RATIO")?.unwrap_or(50.0),
+            event_rate_drop_ratio: env_parse::<f64>(vars, "EVENT_RATE_DROP_RATIO")?.unwrap_or(0.1),
+            feed_stale_after,
+            feed_stale_venues,
+        };
+
+        let market_gauges_top_k = env_parse::<usize>(vars, "MARKET_GAUGES_TOP_K")?.unwrap_or(50);
+
+        let channels = ChannelSettings {
+            market_events: ChannelConfig {
+                capacity: env_capacity(vars, "CHANNEL_MARKET_EVENTS")?.unwrap_or(4_096),
+                overflow: env_overflow(vars, "CHANNEL_MARKET_EVENTS_OVERFLOW")?.unwrap_or(Overflow::Block),
+            },
+            venue_lane: ChannelConfig {
+                capacity: env_capacity(vars, "CHANNEL_VENUE_LANE")?.unwrap_or(1_024),
+                overflow: env_overflow(vars, "CHANNEL_VENUE_LANE_OVERFLOW")?.unwrap_or(Overflow::Block),
+            },
+            notifications: env_capacity(vars, "CHANNEL_NOTIFICATIONS")?.unwrap_or(4_096),
+            signals: env_capacity(vars, "CHANNEL_SIGNALS")?.unwrap_or(64),
+            records: env_capacity(vars, "CHANNEL_RECORDS")?.unwrap_or(4_096),
+            taps: env_capacity(vars, "CHANNEL_TAPS")?.unwrap_or(16_384),
+        };
+
+        let hot_path_cores = match vars.var("HOT_PATH_CORES") {
+            Ok(raw) => raw
+                .split(',')
+                .map(|c| c.trim())
+                .filter(|c| !c.is_empty())
+                .map(|c| c.parse::<usize>().map_err(|_| anyhow::anyhow!("{}: '{c}' is not a core number", vars.describe("HOT_PATH_CORES"))))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            Err(_) => Vec::new(),
+        };
+        // Pinning without a thread count runs one thread per listed core.
+        let hot_path = match env_parse::<usize>(vars, "HOT_PATH_THREADS")? {
+            Some(0) if !hot_path_cores.is_empty() => {
+                anyhow::bail!("{} is set but {} is 0", vars.describe("HOT_PATH_CORES"), vars.describe("HOT_PATH_THREADS"))
+            }
+            Some(0) => None,
+            Some(threads) => Some(HotPathConfig { threads, cores: hot_path_cores }),
+            None if hot_path_cores.is_empty() => None,
+            None => Some(HotPathConfig { threads: hot_path_cores.len(), cores: hot_path_cores }),
+        };
+
+        let venue_threads = match vars.var("VENUE_THREADS") {
+            Ok(raw) => {
+                let mut threads: Vec<VenueThread> = Vec::new();
+                for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    let (venue, core) = match entry.split_once(':') {
+                        Some((venue, core)) => (venue, Some(core.trim())),
+                        None => (entry, None),
+                    };
+                    let venue: Venue = venue.parse().map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe("VENUE_THREADS")))?;
+                    let core = core
+                        .map(|c| c.parse::<usize>().map_err(|_| anyhow::anyhow!("{}: '{c}' is not a core number", vars.describe("VENUE_THREADS"))))
+                        .transpose()?;
+                    anyhow::ensure!(
+                        !threads.iter().any(|t| t.venue == venue),
+                        "{}: {} is listed twice",
+                        vars.describe("VENUE_THREADS"),
+                        venue.name()
+                    );
+                    threads.push(VenueThread { venue, core });
+                }
+                threads
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let defaults = KeepaliveConfig::default();
+        let ws_keepalive = KeepaliveConfig {
+            ping_interval: match env_parse::<u64>(vars, "WS_PING_INTERVAL_SECS")? {
+                Some(0) => anyhow::bail!("{} must be positive", vars.describe("WS_PING_INTERVAL_SECS")),
+                secs => secs.map_or(defaults.ping_interval, Duration::from_secs),
+            },
+            max_missed_pongs: match env_parse::<u32>(vars, "WS_MAX_MISSED_PONGS")? {
+                Some(0) => anyhow::bail!("{} must be positive", vars.describe("WS_MAX_MISSED_PONGS")),
+                missed => missed.unwrap_or(defaults.max_missed_pongs),
+            },
+        };
+
+        let mut rate_limits: Vec<RateLimit> = Vec::new();
+        for (key, value) in env_labels(vars, "RATE_LIMITS")? {
+            let (venue, class) = key.split_once('.').ok_or_else(|| {
+                anyhow::anyhow!("{}: '{key}' (expected venue.class, e.g. polymarket.trading)", vars.describe("RATE_LIMITS"))
+            })?;
+            let venue: Venue = venue.parse().map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe("RATE_LIMITS")))?;
+            let class: EndpointClass = class.parse().map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe("RATE_LIMITS")))?;
+            let limit = value
+                .parse::<u32>()
+                .map_err(|_| anyhow::anyhow!("{}: '{value}' is not a number of requests per second", vars.describe("RATE_LIMITS")))?;
+            anyhow::ensure!(
+                !rate_limits.iter().any(|(v, c, _)| *v == venue && *c == class),
+                "{}: {key} is listed twice",
+                vars.describe("RATE_LIMITS")
+            );
+            rate_limits.push((venue, class, limit));
+        }
+
+        let busy_poll = BusyPoll { spin: Duration::from_micros(env_parse::<u64>(vars, "BUSY_POLL_US")?.unwrap_or(0)) };
+
+        let admin_addr = match vars.var("ADMIN_ADDR") {
+            Ok(raw) => raw.parse().map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid host:port", vars.describe("ADMIN_ADDR")))?,
+            Err(_) => SocketAddr::from(([127, 0, 0, 1], 9001)),
+        };
+        let admin_token = vars.var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+        let grpc_addr = match vars.var("GRPC_ADDR") {
+            Ok(raw) => Some(raw.parse().map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid host:port", vars.describe("GRPC_ADDR")))?),
+            Err(_) => None,
+        };
+
+        let fix = match vars.var("FIX_ADDR") {
+            Ok(raw) => {
+                let addr = raw.parse().map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid host:port", vars.describe("FIX_ADDR")))?;
+                let clients = env_strings(vars, "FIX_CLIENTS");
+                anyhow::ensure!(
+                    !clients.is_empty(),
+                    "{} is set but FIX_CLIENTS is empty; list the SenderCompIDs allowed to log on",
+                    vars.describe("FIX_ADDR")
+                );
+                Some(FixConfig {
+                    addr,
+                    comp_id: vars.var("FIX_COMP_ID").unwrap_or_else(|_| DEFAULT_FIX_COMP_ID.to_string()),
+                    clients,
+                    password: vars.var("FIX_PASSWORD").ok(),
+                })
+            }
+            Err(_) => None,
+        };
+
+        let webhook = match vars.var("SIGNAL_WEBHOOK_ADDR") {
+            Ok(raw) => {
+                let addr = raw
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid host:port", vars.describe("SIGNAL_WEBHOOK_ADDR")))?;
+                let token = vars.var("SIGNAL_WEBHOOK_TOKEN").ok().filter(|t| !t.is_empty()).ok_or_else(|| {
+                    anyhow::anyhow!("{} is set but SIGNAL_WEBHOOK_TOKEN is not", vars.describe("SIGNAL_WEBHOOK_ADDR"))
+                })?;
+                Some(WebhookConfig { addr, token, sources: env_strings(vars, "SIGNAL_WEBHOOK_SOURCES") })
+            }
+            Err(_) => None,
+        };
+
+        let publish = match vars.var("SIGNAL_PUBLISH_URL") {
+            Ok(url) => {
+                let maxlen = env_parse::<u64>(vars, "SIGNAL_PUBLISH_MAXLEN")?.unwrap_or(DEFAULT_REDIS_MAXLEN);
+                let target = PublishTarget::parse(&url, maxlen).map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe("SIGNAL_PUBLISH_URL")))?;
+                let topic = vars.var("SIGNAL_PUBLISH_TOPIC").unwrap_or_else(|_| DEFAULT_TOPIC.to_string());
+                anyhow::ensure!(
+                    !topic.is_empty() && !topic.contains(char::is_whitespace),
+                    "{}='{topic}' must be non-empty with no whitespace",
+                    vars.describe("SIGNAL_PUBLISH_TOPIC")
+                );
+                Some(PublishConfig { target, topic })
+            }
+            Err(_) => None,
+        };
+
+        let flags = env_labels(vars, "FEATURE_FLAGS")?
+            .into_iter()
+            .map(|(name, value)| {
+                let rollout = parse_rollout(&value).map_err(|e| anyhow::anyhow!("{} flag '{name}': {e}", vars.describe("FEATURE_FLAGS")))?;
+                Ok((name, rollout))
+            })
+            .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+
+        let correlation_groups = env_labels(vars, "CORRELATION_GROUPS")?
+            .into_iter()
+            .map(|(market, group)| {
+                anyhow::ensure!(!group.is_empty(), "{}: market '{market}' has no group", vars.describe("CORRELATION_GROUPS"));
+                Ok((market, group))
+            })
+            .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+
+        let polymarket = PolymarketSettings {
+            min_volume_24h: env_parse::<f64>(vars, "POLYMARKET_MIN_VOLUME_24H")?.unwrap_or(100_000.0),
+            min_liquidity: env_parse::<f64>(vars, "POLYMARKET_MIN_LIQUIDITY")?.unwrap_or(10_000.0),
+        };
+        let polymarket_ws_connections = match env_parse::<usize>(vars, "POLYMARKET_WS_CONNECTIONS")? {
+            Some(0) => anyhow::bail!("{} must be positive", vars.describe("POLYMARKET_WS_CONNECTIONS")),
+            connections => connections.unwrap_or(1),
+        };
+
+        let kalshi_credentials = match (vars.var("KALSHI_SECRET").ok(), vars.var("KALSHI_API_KEY_ID").ok()) {
+            (Some(id), _) => {
+                let store = match vars.var("KALSHI_SECRET_STORE").as_deref().unwrap_or("vault") {
+                    "vault" => SecretStore::Vault,
+                    "aws" => SecretStore::AwsSecretsManager,
+                    other => anyhow::bail!("unknown {} '{other}' (expected vault or aws)", vars.describe("KALSHI_SECRET_STORE")),
+                };
+                Some(KalshiCredentialSource::Secret { store, id })
+            }
+            (None, Some(api_key_id)) => {
+                let private_key = vars.var("KALSHI_PRIVATE_KEY_FILE").map(PathBuf::from).map_err(|_| {
+                    anyhow::anyhow!("{} requires {}", vars.describe("KALSHI_API_KEY_ID"), vars.describe("KALSHI_PRIVATE_KEY_FILE"))
+                })?;
+                Some(KalshiCredentialSource::File { api_key_id, private_key })
+            }
+            (None, None) => None,
+        };
+        let kalshi_discovery = match env_parse::<bool>(vars, "KALSHI_DISCOVER")?.unwrap_or(false) {
+            true => Some(KalshiSelection {
+                series: env_strings(vars, "KALSHI_SERIES"),
+                categories: env_strings(vars, "KALSHI_CATEGORIES"),
+                min_volume_24h: env_parse::<u64>(vars, "KALSHI_MIN_VOLUME_24H")?.unwrap_or(0),
+                max_time_to_close: env_days(vars, "KALSHI_MAX_DAYS_TO_CLOSE")?,
+                top_n: env_parse::<usize>(vars, "KALSHI_TOP_N")?,
+            }),
+            false => None,
+        };
+        let kalshi = KalshiSettings {
+            markets: env_strings(vars, "KALSHI_MARKETS"),
+            discovery: kalshi_discovery,
+            credentials: kalshi_credentials,
+            ws_url: vars.var("KALSHI_WS_URL").ok(),
+            rest_url: vars.var("KALSHI_REST_URL").ok(),
+        };
+
+        let betfair_credentials = match vars.var("BETFAIR_APP_KEY").ok() {
+            Some(app_key) => {
+                let session_token_file = vars.var("BETFAIR_SESSION_TOKEN_FILE").map(PathBuf::from).map_err(|_| {
+                    anyhow::anyhow!("{} requires {}", vars.describe("BETFAIR_APP_KEY"), vars.describe("BETFAIR_SESSION_TOKEN_FILE"))
+                })?;
+                Some(BetfairCredentials { app_key, session_token_file })
+            }
+            None => None,
+        };
+        let betfair = BetfairSettings {
+            markets: env_strings(vars, "BETFAIR_MARKETS"),
+            credentials: betfair_credentials,
+            stream_url: vars.var("BETFAIR_STREAM_URL").ok(),
+        };
+
+        let amm = AmmSettings {
+            rpc_url: vars.var("AMM_RPC_URL").ok(),
+            quote_size: env_parse::<f64>(vars, "AMM_QUOTE_SIZE")?,
+            pools: Vec::new(),
+        };
+
+        let strategies = strategies.unwrap_or_else(|| vec![StrategySettings::Arbitrage(ArbitrageSettings {
+            min_edge: DEFAULT_ARB_MIN_EDGE,
+            size: DEFAULT_ARB_SIZE,
+            markets: Vec::new(),
+        })]);
+
+        let execution_mode = match vars.var("EXECUTION_MODE").ok().as_deref() {
+            Some("paper") | None => ExecutionMode::Paper,
+            Some("dry_run") | Some("dry-run") => ExecutionMode::DryRun,
+            Some("live") => ExecutionMode::Live,
+            Some(other) => anyhow::bail!(
+                "unknown {} '{other}' (expected paper, dry_run, or live)",
+                vars.describe("EXECUTION_MODE")
+            ),
+        };
+        let confirm_live = env_parse::<bool>(vars, "EXECUTION_CONFIRM_LIVE")?.unwrap_or(false);
+        let priority_edge = env_parse::<f64>(vars, "PRIORITY_EDGE")?;
+        if let Some(edge) = priority_edge {
+            anyhow::ensure!(edge > 0.0, "{} must be positive", vars.describe("PRIORITY_EDGE"));
+        }
+        let filter_defaults = SignalFilter::default();
+        let filter_ms = |var: &str, default: Option<Duration>| -> anyhow::Result<Option<Duration>> {
+            Ok(match env_parse::<u64>(vars, var)? {
+                Some(0) => None,
+                Some(ms) => Some(Duration::from_millis(ms)),
+                None => default,
+            })
+        };
+        let signal_filter = SignalFilter {
+            ttl: filter_ms("SIGNAL_TTL_MS", filter_defaults.ttl)?,
+            dedup_window: filter_ms("SIGNAL_DEDUP_MS", filter_defaults.dedup_window)?,
+        };
+
+        let key_source = KeySettings {
+            source: vars.var("PRIVATE_KEY_SOURCE").ok(),
+            env: "PRIVATE_KEY".to_string(),
+            file: vars.var("PRIVATE_KEY_FILE").ok().map(PathBuf::from),
+            keyring_service: vars.var("PRIVATE_KEY_KEYRING_SERVICE").ok(),
+            keyring_user: vars.var("PRIVATE_KEY_KEYRING_USER").ok(),
+            secret: vars.var("PRIVATE_KEY_SECRET").ok(),
+            secret_field: vars.var("PRIVATE_KEY_SECRET_FIELD").ok(),
+        }
+        .resolve(|name| match name {
+            "PRIVATE_KEY_SOURCE" => vars.describe(name),
+            other => other.to_string(),
+        })?;
+
+        let secret_refresh = match env_parse::<u64>(vars, "SECRET_REFRESH_SECS")? {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(prediction_engine::secrets::DEFAULT_REFRESH),
+        };
+
+        let risk_defaults = prediction_engine::risk::RiskConfig::default();
+        let risk = RiskSettings {
+            max_drawdown: env_parse::<f64>(vars, "RISK_MAX_DRAWDOWN")?.unwrap_or(risk_defaults.max_drawdown),
+            max_daily_notional: env_parse::<f64>(vars, "RISK_MAX_DAILY_NOTIONAL")?
+                .unwrap_or(risk_defaults.max_daily_notional),
+        };
+
+        let audit_markets = env_strings(vars, "AUDIT_MARKETS");
+        let audit = AuditSettings {
+            all: audit_markets.iter().any(|m| m == "*"),
+            markets: audit_markets.into_iter().filter(|m| m != "*").collect(),
+            depth: env_parse::<usize>(vars, "AUDIT_DEPTH")?
+                .unwrap_or(prediction_engine::strategy::audit::DEFAULT_AUDIT_DEPTH),
+        };
+
+        Ok(Self {
+            source: vars.path.clone(),
+            overrides: Vec::new(),
+            log_level,
+            log_format,
+            log_file,
+            storage,
+            archive,
+            clickhouse,
+            influx,
+            upload,
+            journal_path,
+            instance_lock_path,
+            universe_import,
+            universe_export,
+            universe,
+            retention,
+            metrics,
+            health_stale_after,
+            notify,
+            watchdog,
+            channels,
+            hot_path,
+            venue_threads,
+            ws_keepalive,
+            rate_limits,
+            busy_poll,
+            market_gauges_top_k,
+            audit,
+            admin_addr,
+            admin_token,
+            grpc_addr,
+            fix,
+            webhook,
+            publish,
+            flags,
+            correlation_groups,
+            polymarket,
+            polymarket_ws_connections,
+            kalshi,
+            betfair,
+            amm,
+            strategies,
+            execution_mode,
+            priority_edge,
+            signal_filter,
+            confirm_live,
+            key_source,
+            secret_refresh,
+            wallets: Vec::new(),
+            pollers: Vec::new(),
+            risk,
+            provenance: Vec::new(),
+            strategies_source: SettingSource::Default,
+        })
+    }
+
+    /// Strategies may only name markets that discovery (or the imported
+    /// universe) actually subscribed to. Checked once the universe is known.
+    pub fn check_strategy_markets(&self, market_map: &MarketMap) -> anyhow::Result<()> {
+        for strategy in &self.strategies {
+            let missing: Vec<&str> = strategy
+                .markets()
+                .iter()
+                .filter(|m| !market_map.contains_key(*m))
+                .map(String::as_str)
+                .collect();
+            anyhow::ensure!(
+                missing.is_empty(),
+                "strategy.{}.markets names markets that aren't in the universe: {} \
+                 (use Gamma market ids, and pin them under [universe] markets)",
+                strategy.kind(),
+                missing.join(", "),
+            );
+        }
+        Ok(())
+    }
+
+    /// Range checks that parsing alone can't catch.
+    fn validate(&self, vars: &Vars) -> anyhow::Result<()> {
+        let ensure_positive = |name: &str, value: f64| {
+            anyhow::ensure!(value > 0.0 && value.is_finite(), "{} must be positive (got {value})", vars.describe(name));
+            Ok(())
+        };
+        anyhow::ensure!(
+            self.risk.max_drawdown > 0.0 && self.risk.max_drawdown <= 1.0,
+            "{} must be in (0, 1] (got {})", vars.describe("RISK_MAX_DRAWDOWN"), self.risk.max_drawdown,
+        );
+        ensure_positive("RISK_MAX_DAILY_NOTIONAL", self.risk.max_daily_notional)?;
+        anyhow::ensure!(
+            self.polymarket.min_volume_24h >= 0.0 && self.polymarket.min_liquidity >= 0.0,
+            "{} and {} must not be negative",
+            vars.describe("POLYMARKET_MIN_VOLUME_24H"), vars.describe("POLYMARKET_MIN_LIQUIDITY"),
+        );
+        anyhow::ensure!(
+            (self.kalshi.markets.is_empty() && self.kalshi.discovery.is_none()) || self.kalshi.credentials.is_some(),
+            "{} and {} require Kalshi API credentials: {} and {}, or {}",
+            vars.describe("KALSHI_MARKETS"), vars.describe("KALSHI_DISCOVER"), vars.describe("KALSHI_API_KEY_ID"),
+            vars.describe("KALSHI_PRIVATE_KEY_FILE"), vars.describe("KALSHI_SECRET"),
+        );
+        anyhow::ensure!(
+            self.betfair.markets.is_empty() || self.betfair.credentials.is_some(),
+            "{} requires Betfair credentials: {} and {}",
+            vars.describe("BETFAIR_MARKETS"), vars.describe("BETFAIR_APP_KEY"), vars.describe("BETFAIR_SESSION_TOKEN_FILE"),
+        );
+        anyhow::ensure!(self.audit.depth > 0, "{} must be at least 1", vars.describe("AUDIT_DEPTH"));
+        if let Some(q) = self.metrics.quantiles.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            anyhow::bail!("{}: quantile {q} is outside [0, 1]", vars.describe("METRICS_QUANTILES"));
+        }
+
+        anyhow::ensure!(!self.strategies.is_empty(), "no strategies configured; add a [strategy.<kind>] section");
+        for (i, strategy) in self.strategies.iter().enumerate() {
+            let kind = strategy.kind();
+            let label = if self.strategies.iter().filter(|s| s.kind() == kind).count() > 1 {
+                format!("strategies[{i}] ({kind})")
+            } else {
+                format!("strategy.{kind}")
+            };
+            let check = |field: &str, value: f64, min_exclusive: f64, max: Option<(f64, &str)>| {
+                anyhow::ensure!(
+                    value.is_finite() && value > min_exclusive,
+                    "{label}.{field} must be greater than {min_exclusive} (got {value})"
+                );
+                if let Some((max, why)) = max {
+                    anyhow::ensure!(value <= max, "{label}.{field} is {value}, above {max} ({why})");
+                }
+                Ok(())
+            };
+            match strategy {
+                StrategySettings::Arbitrage(s) => {
+                    check("min_edge", s.min_edge, 0.0, Some((1.0, "an edge is a fraction of $1 per share")))?;
+                    check(
+                        "size",
+                        s.size,
+                        0.0,
+                        Some((self.risk.max_daily_notional, "one signal would exceed risk.max_daily_notional")),
+                    )?;
+                }
+                StrategySettings::MarketMaker(s) => {
+                    let c = s.config();
+                    check("half_spread", c.half_spread, 0.0, Some((0.5, "quotes would cross 0 or 1")))?;
+                    check("max_inventory", c.max_inventory, 0.0, None)?;
+                    check("quote_size", c.quote_size, 0.0, Some((c.max_inventory, "one fill would exceed max_inventory")))?;
+                    check("tick_size", c.tick_size, 0.0, Some((0.1, "Polymarket ticks are 0.01 or 0.001")))?;
+                    anyhow::ensure!(
+                        c.skew_factor.is_finite() && c.skew_factor >= 0.0,
+                        "{label}.skew_factor must not be negative (got {})", c.skew_factor
+                    );
+                    anyhow::ensure!(
+                        c.widen_factor.is_finite() && c.widen_factor >= 0.0,
+                        "{label}.widen_factor must not be negative (got {})", c.widen_factor
+                    );
+                    check("reward_spread_fraction", c.reward_spread_fraction, 0.0, Some((1.0, "quotes would sit outside the reward band")))?;
+                    check("reward_share", c.reward_share, 0.0, Some((1.0, "a share of the pool is at most all of it")))?;
+                }
+            }
+            if let Some(blank) = strategy.markets().iter().position(|m| m.trim().is_empty()) {
+                anyhow::bail!("{label}.markets[{blank}] is empty");
+            }
+        }
+
+        if self.execution_mode == ExecutionMode::Live {
+            anyhow::ensure!(
+                self.confirm_live,
+                "{}=live places real orders with real funds; confirm with --i-understand-live-trading, \
+                 [execution] confirm_live = true, or EXECUTION_CONFIRM_LIVE=true \
+                 (or use dry_run to sign orders without posting them)",
+                vars.describe("EXECUTION_MODE"),
+            );
+        }
+        let mut assigned = HashMap::new();
+        for (i, wallet) in self.wallets.iter().enumerate() {
+            anyhow::ensure!(!wallet.name.trim().is_empty(), "wallets[{i}].name is empty");
+            anyhow::ensure!(
+                self.wallets[..i].iter().all(|w| w.name != wallet.name),
+                "wallets.{0}: two wallets are named {0}", wallet.name,
+            );
+            anyhow::ensure!(!wallet.strategies.is_empty(), "wallets.{}.strategies is empty", wallet.name);
+            for strategy in &wallet.strategies {
+                if let Some(other) = assigned.insert(strategy.as_str(), wallet.name.as_str()) {
+                    anyhow::bail!("strategy {strategy} is assigned to both wallets.{other} and wallets.{}", wallet.name);
+                }
+            }
+            if let Some(cap) = wallet.max_daily_notional {
+                anyhow::ensure!(
+                    cap > 0.0 && cap.is_finite(),
+                    "wallets.{}.max_daily_notional must be positive (got {cap})", wallet.name,
+                );
+            }
+            let key_source = wallet.key_source()?;
+            if self.execution_mode.needs_key() {
+                self.check_key_source(&key_source, vars, |name| wallet.describe(name))?;
+            }
+        }
+        if self.execution_mode.needs_key() {
+            self.check_key_source(&self.key_source, vars, |name| vars.describe(name))?;
+        }
+        for (i, feed) in self.pollers.iter().enumerate() {
+            feed.validate().map_err(|e| anyhow::anyhow!("pollers.{}: {e}", feed.name))?;
+            anyhow::ensure!(
+                self.pollers[..i].iter().all(|f| f.name != feed.name),
+                "pollers.{0}: two feeds are named {0}", feed.name,
+            );
+        }
+        anyhow::ensure!(
+            self.amm.pools.is_empty() || self.amm.rpc_url.is_some(),
+            "[[amm.pools]] requires {}", vars.describe("AMM_RPC_URL"),
+        );
+        if let Some(size) = self.amm.quote_size {
+            anyhow::ensure!(size > 0.0 && size.is_finite(), "{} must be positive (got {size})", vars.describe("AMM_QUOTE_SIZE"));
+        }
+        for (i, pool) in self.amm.pools.iter().enumerate() {
+            pool.validate().map_err(|e| anyhow::anyhow!("amm.pools[{i}]: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Fail early when the key behind `source` plainly isn't there. `describe`
+    /// names a key setting (by its environment-variable name) in errors.
+    fn check_key_source(&self, source: &KeySource, vars: &Vars, describe: impl Fn(&'static str) -> String) -> anyhow::Result<()> {
+        match source {
+            KeySource::Env(var) => anyhow::ensure!(
+                std::env::var(var).is_ok(),
+                "{}={} requires {var}, or another {}",
+                vars.describe("EXECUTION_MODE"),
+                self.execution_mode.name(),
+                describe("PRIVATE_KEY_SOURCE"),
+            ),
+            KeySource::Age(path) | KeySource::Gpg(path) => anyhow::ensure!(
+                path.is_file(),
+                "{}: key file {} does not exist",
+                describe("PRIVATE_KEY_FILE"),
+                path.display(),
+            ),
+            KeySource::Secret(SecretRef { store: SecretStore::Vault, .. }) => anyhow::ensure!(
+                std::env::var("VAULT_ADDR").is_ok() && std::env::var("VAULT_TOKEN").is_ok(),
+                "{}=vault requires VAULT_ADDR and VAULT_TOKEN",
+                describe("PRIVATE_KEY_SOURCE"),
+            ),
+            KeySource::Keyring { .. } | KeySource::Secret(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Setting lookup: command-line overrides, then the process environment
+/// (including `.env`), then the config file.
+struct Vars {
+    /// Env-var name → (value, dotted key), from `--set`.
+    overrides: HashMap<String, (String, String)>,
+    /// Env-var name → (value, dotted key in the file).
+    file: HashMap<String, (String, String)>,
+    path: Option<PathBuf>,
+    /// Every lookup, for [`Config::provenance`].
+    seen: RefCell<Vec<Provenance>>,
+}
+
+impl Vars {
+    fn var(&self, name: &str) -> Result<String, std::env::VarError> {
+        let (result, key, source) = if let Some((value, key)) = self.overrides.get(name) {
+            (Ok(value.clone()), Some(key.clone()), SettingSource::Override)
+        } else {
+            match (std::env::var(name), self.file.get(name)) {
+                (Ok(value), _) => (Ok(value), None, SettingSource::Env),
+                (Err(_), Some((value, key))) => (Ok(value.clone()), Some(key.clone()), SettingSource::File),
+                (Err(e), None) => (Err(e), None, SettingSource::Default),
+            }
+        };
+        let mut seen = self.seen.borrow_mut();
+        if !seen.iter().any(|p| p.name == name) {
+            seen.push(Provenance { name: name.to_string(), key, value: result.as_ref().ok().cloned(), source });
+        }
+        result
+    }
+
+    /// How to name the setting in errors: where its value came from.
+    fn describe(&self, name: &str) -> String {
+        if let Some((_, key)) = self.overrides.get(name) {
+            return format!("--set {key}");
+        }
+        match (std::env::var_os(name), self.file.get(name), &self.path) {
+            (None, Some((_, key)), Some(path)) => format!("{key} (in {})", path.display()),
+            _ => name.to_string(),
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(vars: &Vars, name: &str) -> anyhow::Result<Option<T>> {
+    match vars.var(name) {
+        Ok(raw) => raw
+            .parse()
+            .map(Some)
+            .map_err(|_| anyhow::anyhow!("{}='{raw}' is not a valid number", vars.describe(name))),
+        Err(_) => Ok(None),
+    }
+}
+
+fn env_capacity(vars: &Vars, name: &str) -> anyhow::Result<Option<usize>> {
+    let capacity = env_parse::<usize>(vars, name)?;
+    anyhow::ensure!(capacity != Some(0), "{} must be at least 1", vars.describe(name));
+    Ok(capacity)
+}
+
+fn env_overflow(vars: &Vars, name: &str) -> anyhow::Result<Option<Overflow>> {
+    match vars.var(name) {
+        Ok(raw) => raw.parse().map(Some).map_err(|e| anyhow::anyhow!("{}: {e}", vars.describe(name))),
+        Err(_) => Ok(None),
+    }
+}
+
+fn env_days(vars: &Vars, name: &str) -> anyhow::Result<Option<Duration>> {
+    Ok(env_parse::<u64>(vars, name)?.map(|days| Duration::from_secs(days * 86_400)))
+}
+
+fn env_list(vars: &Vars, name: &str) -> anyhow::Result<Option<Vec<f64>>> {
+    let Ok(raw) = vars.var(name) else { return Ok(None) };
+    raw.split(',')
+        .map(|v| v.trim().parse::<f64>().map_err(|_| anyhow::anyhow!("{}: '{v}' is not a valid number", vars.describe(name))))
+        .collect::<anyhow::Result<Vec<f64>>>()
+        .map(Some)
+}
+
+/// Comma-separated strings from `name`, blanks dropped; empty when unset.
+fn env_strings(vars: &Vars, name: &str) -> Vec<String> {
+    vars.var(name)
+        .map(|raw| raw.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// `key=value,key=value` pairs from `name`; empty when unset.
+fn env_labels(vars: &Vars, name: &str) -> anyhow::Result<Vec<(String, String)>> {
+    let Ok(raw) = vars.var(name) else { return Ok(Vec::new()) };
+    raw.split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((k, v)) if !k.trim().is_empty() => Ok((k.trim().to_string(), v.trim().to_string())),
+            _ => Err(anyhow::anyhow!("{}: '{pair}' is not key=value", vars.describe(name))),
+        })
+        .collect()
+}
 
-        Ok(Self { log_level })
+/// Bucket list from `name`, `None` for `summary`, or `default` when unset.
+fn env_buckets(vars: &Vars, name: &str, default: Option<Vec<f64>>) -> anyhow::Result<Option<Vec<f64>>> {
+    match vars.var(name).as_deref() {
+        Ok("summary") => Ok(None),
+        Ok(_) => env_list(vars, name),
+        Err(_) => Ok(default),
     }
 }
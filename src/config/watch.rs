@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use prediction_engine::flags::FeatureFlags;
+use prediction_engine::logging::LogFilter;
+use prediction_engine::market_data::adapters::polymarket::{MarketActivity, UniverseFilter};
+use prediction_engine::risk::{RiskConfig, RiskManager};
+use prediction_engine::state::rewards::RewardsBook;
+use prediction_engine::strategy::StrategySet;
+
+use super::{Config, StrategySettings};
+
+/// Editors often save in several writes; wait for them to settle.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Handles to the running engine that a reload may update.
+pub struct LiveSettings {
+    pub log_filter: LogFilter,
+    pub risk: RiskManager,
+    pub strategies: StrategySet,
+    /// Handed to rebuilt market makers.
+    pub rewards: RewardsBook,
+    pub flags: FeatureFlags,
+    /// market_id → activity at discovery, for re-applying the universe filter.
+    pub activity: HashMap<String, MarketActivity>,
+}
+
+/// Re-read the config file whenever it changes and apply what can change
+/// live: log level, risk limits, strategy parameters, feature flags, and
+/// universe filters.
+/// Anything else is logged as needing a restart and left as it was. A file
+/// that fails to load or validate is rejected whole.
+pub async fn run_config_watcher(path: PathBuf, mut running: Config, live: LiveSettings) -> anyhow::Result<()> {
+    let (tx, mut rx) = mpsc::channel(1);
+    let file_name = path.file_name().map(ToOwned::to_owned);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let Ok(event) = res else { return };
+        if matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+            && event.paths.iter().any(|p| p.file_name() == file_name.as_deref())
+        {
+            let _ = tx.try_send(());
+        }
+    })?;
+    // Watch the directory: editors that save by rename replace the file's inode.
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    info!(path = %path.display(), "watching config file for changes");
+
+    while rx.recv().await.is_some() {
+        tokio::time::sleep(DEBOUNCE).await;
+        while rx.try_recv().is_ok() {}
+
+        match Config::load(Some(&path), &running.overrides) {
+            Ok(next) => running = apply(running, next, &live),
+            Err(e) => warn!(error = %format!("{e:#}"), "config reload rejected — keeping the running config"),
+        }
+    }
+    Ok(())
+}
+
+/// Apply the live-safe differences between `running` and `next`; returns the
+/// config now in effect.
+fn apply(mut running: Config, next: Config, live: &LiveSettings) -> Config {
+    let restart = restart_required(&running, &next);
+    if !restart.is_empty() {
+        warn!(settings = ?restart, "changed settings need a restart to take effect — ignoring them");
+    }
+
+    if next.log_level != running.log_level {
+        match live.log_filter.set(&next.log_level) {
+            Ok(previous) => {
+                info!(previous, current = %next.log_level, "log filter reloaded");
+                running.log_level = next.log_level;
+            }
+            Err(e) => warn!(error = %format!("{e:#}"), "invalid log filter in config — keeping the current one"),
+        }
+    }
+
+    if format!("{:?}", next.risk) != format!("{:?}", running.risk) {
+        live.risk.set_config(RiskConfig {
+            max_drawdown: next.risk.max_drawdown,
+            max_daily_notional: next.risk.max_daily_notional,
+        });
+        info!(
+            max_drawdown = next.risk.max_drawdown,
+            max_daily_notional = next.risk.max_daily_notional,
+            "risk limits reloaded"
+        );
+        running.risk = next.risk;
+    }
+
+    if format!("{:?}", next.strategies) != format!("{:?}", running.strategies) {
+        if same_kinds(&running.strategies, &next.strategies) {
+            match next.strategies.iter().map(|s| s.build(&live.rewards)).collect::<anyhow::Result<_>>() {
+                Ok(built) => {
+                    live.strategies.replace(built);
+                    running.strategies = next.strategies;
+                }
+                Err(e) => warn!(error = %format!("{e:#}"), "reloaded strategies are invalid; keeping the running set"),
+            }
+        } else {
+            warn!("strategies were added, removed, or reordered — restart required; keeping the running set");
+        }
+    }
+
+    if next.flags != running.flags {
+        live.flags.set_configured(next.flags.clone());
+        running.flags = next.flags;
+    }
+
+    if format!("{:?}", next.polymarket) != format!("{:?}", running.polymarket) {
+        let filter = UniverseFilter {
+            min_volume_24h: next.polymarket.min_volume_24h,
+            min_liquidity: next.polymarket.min_liquidity,
+        };
+        if live.activity.is_empty() {
+            warn!("universe was imported or is all pinned markets, so there is nothing to re-filter — restart to apply");
+        } else {
+            let excluded: HashSet<String> = live
+                .activity
+                .iter()
+                .filter(|(_, activity)| !filter.admits(activity))
+                .map(|(market_id, _)| market_id.clone())
+                .collect();
+            info!(
+                min_volume_24h = filter.min_volume_24h,
+                min_liquidity = filter.min_liquidity,
+                excluded = excluded.len(),
+                "universe filter reloaded (markets not already subscribed need a restart)"
+            );
+            live.strategies.set_excluded(excluded);
+        }
+        running.polymarket = next.polymarket;
+    }
+
+    running
+}
+
+/// Names of changed settings that only take effect at startup.
+fn restart_required(running: &Config, next: &Config) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    macro_rules! compare {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if format!("{:?}", running.$field) != format!("{:?}", next.$field) {
+                    changed.push(stringify!($field));
+                }
+            )*
+        };
+    }
+    compare!(
+        log_format, log_file, storage, archive, clickhouse, influx, upload, journal_path,
+        instance_lock_path, universe_import, universe_export, universe, retention, metrics, health_stale_after, notify,
+        watchdog, channels, hot_path, ws_keepalive, rate_limits, market_gauges_top_k, audit, admin_addr, admin_token, grpc_addr, execution_mode, signal_filter, confirm_live, correlation_groups,
+        key_source, secret_refresh, wallets, polymarket_ws_connections, kalshi, betfair, pollers, amm,
+    );
+    changed
+}
+
+fn same_kinds(a: &[StrategySettings], b: &[StrategySettings]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| std::mem::discriminant(a) == std::mem::discriminant(b))
+}
@@ -0,0 +1,611 @@
+//! The trading pipeline as a library.
+//!
+//! [`EngineBuilder`] assembles adapters → router → market workers → strategy
+//! engine → execution bridge from the parts an embedding program supplies:
+//! market-data adapters, strategies, an executor and risk limits. Anything
+//! left out gets a paper-trading default, so the smallest embedding is one
+//! adapter and one strategy:
+//!
+//! ```ignore
+//! let builder = EngineBuilder::new(PipelineChannels::default());
+//! let tx = builder.event_sender();
+//! let engine = builder
+//!     .adapter("adapter.custom", move || my_feed(tx.clone()))
+//!     .markets(market_map, token_to_market)
+//!     .strategy(Box::new(MyStrategy::default()))
+//!     .risk(RiskConfig { max_drawdown: 0.05, ..RiskConfig::default() })
+//!     .build();
+//! prediction_engine::engine::run(engine).await
+//! ```
+//!
+//! Every pipeline task runs under [`supervise`], so a panicking adapter or
+//! strategy is restarted rather than taking the engine down. The
+//! `prediction-engine` binary is itself an embedding: it builds each
+//! configured venue's [`MarketDataAdapter`] and the strategies from config, and adds
+//! persistence, the admin API and alerting around the engine's handles.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::channel::{self, ChannelConfig, Overflow};
+use crate::clock::{SharedClock, SystemClock};
+use crate::execution::{self, lanes::{signal_lanes, SignalReceiver, SignalSender}, SignalFilter};
+use crate::execution::paper::PaperExecutor;
+use crate::execution::traits::ExecutionEngine;
+use crate::flags::FeatureFlags;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::market_data::adapters::traits::MarketDataAdapter;
+use crate::market_data::bus::EventBus;
+use crate::market_data::market_worker::{NotifyReceiver, NotifySender};
+use crate::market_data::router;
+use crate::market_data::types::EventBatch;
+use crate::metrics::channels::ChannelMonitor;
+use crate::notify::Notifier;
+use crate::persist::Recorder;
+use crate::risk::{RiskConfig, RiskManager};
+use crate::runtime::{BusyPoll, HotPathConfig, HotPathRuntime};
+use crate::state::market_cache::MarketCache;
+use crate::state::portfolio::Portfolio;
+use crate::state::position::PositionTracker;
+use crate::strategy::{self, audit::DecisionAudit, traits::Strategy, PauseSwitch, StrategySet};
+use crate::supervisor::{supervise, RestartPolicy};
+
+/// Starting cash of the default (paper) portfolio.
+const DEFAULT_STARTING_CASH: f64 = 1_000.0;
+/// Cache staleness after which the default health registry reports not ready.
+const DEFAULT_HEALTH_STALE: Duration = Duration::from_secs(60);
+/// How long [`RunningEngine::shutdown`] waits for the in-flight order to report.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Capacities (and, for market data, overflow policies) of the pipeline's
+/// own channels. The defaults match the binary's.
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineChannels {
+    /// Adapters → router.
+    pub market_events: ChannelConfig,
+    /// Router → each venue's market worker.
+    pub venue_lane: ChannelConfig,
+    /// Market workers → strategy engine: markets pending evaluation.
+    pub notifications: usize,
+    /// Strategy engine → execution bridge, per lane.
+    pub signals: usize,
+}
+
+impl Default for PipelineChannels {
+    fn default() -> Self {
+        Self {
+            market_events: ChannelConfig { capacity: 4_096, overflow: Overflow::Block },
+            venue_lane: ChannelConfig { capacity: 1_024, overflow: Overflow::Block },
+            notifications: 4_096,
+            signals: 64,
+        }
+    }
+}
+
+/// Produces a fresh run of an adapter each time the supervisor (re)starts it.
+type AdapterFactory = Box<dyn FnMut() -> BoxFuture<'static, anyhow::Result<()>> + Send>;
+
+/// Collects the engine's parts. See the [module docs](self).
+pub struct EngineBuilder {
+    channels: PipelineChannels,
+    events_tx: channel::Sender<EventBatch>,
+    events_rx: channel::Receiver<EventBatch>,
+    adapters: Vec<(&'static str, AdapterFactory)>,
+    market_data: Vec<Arc<dyn MarketDataAdapter>>,
+    market_map: Arc<MarketMap>,
+    token_to_market: Arc<TokenToMarket>,
+    strategies: Vec<Box<dyn Strategy>>,
+    strategy_set: Option<StrategySet>,
+    flags: FeatureFlags,
+    executor: Option<(Arc<dyn ExecutionEngine>, &'static str)>,
+    risk_config: RiskConfig,
+    risk: Option<RiskManager>,
+    portfolio: Option<Portfolio>,
+    cache: MarketCache,
+    clock: SharedClock,
+    health: Option<HealthRegistry>,
+    notifier: Notifier,
+    recorder: Recorder,
+    audit: DecisionAudit,
+    pause: PauseSwitch,
+    event_bus: EventBus,
+    priority_edge: Option<f64>,
+    signal_filter: SignalFilter,
+    hot_path: Option<HotPathConfig>,
+    busy_poll: BusyPoll,
+    restart: RestartPolicy,
+}
+
+impl EngineBuilder {
+    /// Start from paper-trading defaults. The market-event channel is created
+    /// here, so adapters can be given [`event_sender`](Self::event_sender)
+    /// before the rest is set.
+    pub fn new(channels: PipelineChannels) -> Self {
+        let (events_tx, events_rx) = channel::bounded("market_events", channels.market_events);
+        Self {
+            channels,
+            events_tx,
+            events_rx,
+            adapters: Vec::new(),
+            market_data: Vec::new(),
+            market_map: Arc::default(),
+            token_to_market: Arc::default(),
+            strategies: Vec::new(),
+            strategy_set: None,
+            flags: FeatureFlags::default(),
+            executor: None,
+            risk_config: RiskConfig::default(),
+            risk: None,
+            portfolio: None,
+            cache: MarketCache::new(),
+            clock: Arc::new(SystemClock),
+            health: None,
+            notifier: Notifier::disabled(),
+            recorder: Recorder::disabled(),
+            audit: DecisionAudit::disabled(),
+            pause: PauseSwitch::default(),
+            event_bus: EventBus::new(1),
+            priority_edge: None,
+            signal_filter: SignalFilter::default(),
+            hot_path: None,
+            busy_poll: BusyPoll::default(),
+            restart: RestartPolicy::default(),
+        }
+    }
+
+    /// Where adapters send market data: one [`EventBatch`] per venue message.
+    pub fn event_sender(&self) -> channel::Sender<EventBatch> {
+        self.events_tx.clone()
+    }
+
+    /// Run a market-data adapter, supervised as `name`. `run` is called for
+    /// every (re)start and should send to a clone of
+    /// [`event_sender`](Self::event_sender).
+    pub fn adapter<F, Fut>(mut self, name: &'static str, mut run: F) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.adapters.push((name, Box::new(move || Box::pin(run()))));
+        self
+    }
+
+    /// Run a [`MarketDataAdapter`], supervised as its
+    /// [`name`](MarketDataAdapter::name) and shut down with the engine.
+    pub fn market_data(mut self, adapter: Arc<dyn MarketDataAdapter>) -> Self {
+        self.market_data.push(Arc::clone(&adapter));
+        self.adapter(adapter.name(), move || {
+            let adapter = Arc::clone(&adapter);
+            async move { adapter.start().await }
+        })
+    }
+
+    /// The markets the adapters subscribe to, which strategies look outcomes up in.
+    pub fn markets(mut self, market_map: Arc<MarketMap>, token_to_market: Arc<TokenToMarket>) -> Self {
+        self.market_map = market_map;
+        self.token_to_market = token_to_market;
+        self
+    }
+
+    /// Add a strategy. Strategies run in the order they are added.
+    pub fn strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.strategies.push(strategy);
+        self
+    }
+
+    /// Use a prepared set (e.g. one the caller also reloads at runtime)
+    /// instead of the strategies added with [`strategy`](Self::strategy).
+    pub fn strategy_set(mut self, strategies: StrategySet) -> Self {
+        self.strategy_set = Some(strategies);
+        self
+    }
+
+    /// Flags gating strategies per market and execution per venue.
+    pub fn flags(mut self, flags: FeatureFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Execute signals with `executor`, labelled `name` in metrics. Paper by default.
+    pub fn executor(mut self, executor: Arc<dyn ExecutionEngine>, name: &'static str) -> Self {
+        self.executor = Some((executor, name));
+        self
+    }
+
+    /// Risk limits for the default risk manager.
+    pub fn risk(mut self, config: RiskConfig) -> Self {
+        self.risk_config = config;
+        self
+    }
+
+    /// Use a prepared risk manager (e.g. one restored from a journal)
+    /// instead of building one from [`risk`](Self::risk).
+    pub fn risk_manager(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Use a prepared portfolio. By default fills go to a fresh one with
+    /// 1,000 of paper cash.
+    pub fn portfolio(mut self, portfolio: Portfolio) -> Self {
+        self.portfolio = Some(portfolio);
+        self
+    }
+
+    /// Share a market cache with the caller's own readers.
+    pub fn cache(mut self, cache: MarketCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn health(mut self, health: HealthRegistry) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    pub fn notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Where the execution bridge records signals, orders and fills. Nowhere by default.
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    pub fn audit(mut self, audit: DecisionAudit) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    pub fn pause(mut self, pause: PauseSwitch) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// The bus routed events are published on, for the caller's observers.
+    pub fn event_bus(mut self, bus: EventBus) -> Self {
+        self.event_bus = bus;
+        self
+    }
+
+    /// Signals with at least this edge take the priority lane.
+    pub fn priority_edge(mut self, edge: Option<f64>) -> Self {
+        self.priority_edge = edge;
+        self
+    }
+
+    /// Which stale or repeated signals the execution bridge drops.
+    pub fn signal_filter(mut self, filter: SignalFilter) -> Self {
+        self.signal_filter = filter;
+        self
+    }
+
+    /// Run the pipeline on a runtime of its own.
+    pub fn hot_path(mut self, config: Option<HotPathConfig>) -> Self {
+        self.hot_path = config;
+        self
+    }
+
+    pub fn busy_poll(mut self, busy: BusyPoll) -> Self {
+        self.busy_poll = busy;
+        self
+    }
+
+    pub fn restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart = policy;
+        self
+    }
+
+    /// Fill in the defaults and create the remaining channels. Nothing runs
+    /// until [`Engine::start`].
+    pub fn build(self) -> Engine {
+        let health = self
+            .health
+            .unwrap_or_else(|| HealthRegistry::new(self.cache.clone(), DEFAULT_HEALTH_STALE).with_notifier(self.notifier.clone()));
+        let risk = self.risk.unwrap_or_else(|| RiskManager::new(self.risk_config).with_notifier(self.notifier.clone()));
+        let portfolio = self.portfolio.unwrap_or_else(|| {
+            Portfolio::new(PositionTracker::new(), self.cache.clone(), Arc::clone(&self.token_to_market), DEFAULT_STARTING_CASH)
+        });
+        let strategies = self
+            .strategy_set
+            .unwrap_or_else(|| StrategySet::new(self.strategies).with_flags(self.flags.clone()));
+        let (executor, executor_name) = self.executor.unwrap_or_else(|| (Arc::new(PaperExecutor::new()), "paper"));
+
+        let (notify_tx, notify_rx) = channel::coalescing("notifications", self.channels.notifications);
+        let (signal_tx, signal_rx) = signal_lanes(self.channels.signals, self.priority_edge);
+
+        health.register(execution::HEALTH_COMPONENT);
+        health.watch_channel("market_events", &self.events_tx);
+        health.watch_channel("notifications", &notify_tx);
+        health.watch_channel("signals", signal_tx.normal());
+
+        Engine {
+            events_tx: self.events_tx,
+            events_rx: self.events_rx,
+            venue_lane: self.channels.venue_lane,
+            adapters: self.adapters,
+            market_data: self.market_data,
+            market_map: self.market_map,
+            token_to_market: self.token_to_market,
+            strategies,
+            flags: self.flags,
+            executor,
+            executor_name,
+            risk,
+            portfolio,
+            cache: self.cache,
+            clock: self.clock,
+            health,
+            notifier: self.notifier,
+            recorder: self.recorder,
+            audit: self.audit,
+            pause: self.pause,
+            event_bus: self.event_bus,
+            notify_tx,
+            notify_rx,
+            signal_tx,
+            signal_rx,
+            signal_filter: self.signal_filter,
+            hot_path: self.hot_path,
+            busy_poll: self.busy_poll,
+            restart: self.restart,
+        }
+    }
+}
+
+/// A built engine, not yet running. Its handles are shared with the
+/// running pipeline, so anything wired up from them before
+/// [`start`](Self::start) (admin API, dashboards, watchdogs) sees live state.
+pub struct Engine {
+    events_tx: channel::Sender<EventBatch>,
+    events_rx: channel::Receiver<EventBatch>,
+    venue_lane: ChannelConfig,
+    adapters: Vec<(&'static str, AdapterFactory)>,
+    market_data: Vec<Arc<dyn MarketDataAdapter>>,
+    market_map: Arc<MarketMap>,
+    token_to_market: Arc<TokenToMarket>,
+    strategies: StrategySet,
+    flags: FeatureFlags,
+    executor: Arc<dyn ExecutionEngine>,
+    executor_name: &'static str,
+    risk: RiskManager,
+    portfolio: Portfolio,
+    cache: MarketCache,
+    clock: SharedClock,
+    health: HealthRegistry,
+    notifier: Notifier,
+    recorder: Recorder,
+    audit: DecisionAudit,
+    pause: PauseSwitch,
+    event_bus: EventBus,
+    notify_tx: NotifySender,
+    notify_rx: NotifyReceiver,
+    signal_tx: SignalSender,
+    signal_rx: SignalReceiver,
+    signal_filter: SignalFilter,
+    hot_path: Option<HotPathConfig>,
+    busy_poll: BusyPoll,
+    restart: RestartPolicy,
+}
+
+impl Engine {
+    pub fn cache(&self) -> &MarketCache {
+        &self.cache
+    }
+
+    pub fn market_map(&self) -> &Arc<MarketMap> {
+        &self.market_map
+    }
+
+    pub fn token_to_market(&self) -> &Arc<TokenToMarket> {
+        &self.token_to_market
+    }
+
+    pub fn portfolio(&self) -> &Portfolio {
+        &self.portfolio
+    }
+
+    pub fn risk(&self) -> &RiskManager {
+        &self.risk
+    }
+
+    pub fn strategies(&self) -> &StrategySet {
+        &self.strategies
+    }
+
+    pub fn flags(&self) -> &FeatureFlags {
+        &self.flags
+    }
+
+    pub fn pause(&self) -> &PauseSwitch {
+        &self.pause
+    }
+
+    pub fn health(&self) -> &HealthRegistry {
+        &self.health
+    }
+
+    pub fn audit(&self) -> &DecisionAudit {
+        &self.audit
+    }
+
+    pub fn event_bus(&self) -> &EventBus {
+        &self.event_bus
+    }
+
+    /// Where market data enters the pipeline, for tasks besides the
+    /// adapters that emit events (e.g. the feed watchdog).
+    pub fn event_sender(&self) -> channel::Sender<EventBatch> {
+        self.events_tx.clone()
+    }
+
+    /// The execution bridge's input, for operator orders and monitoring.
+    pub fn signals(&self) -> &SignalSender {
+        &self.signal_tx
+    }
+
+    /// Sample the pipeline's channels in `monitor`.
+    pub fn watch_channels(&self, monitor: &ChannelMonitor) {
+        monitor.watch("market_events", &self.events_tx);
+        monitor.watch("notifications", &self.notify_tx);
+        monitor.watch("signals", self.signal_tx.normal());
+        monitor.watch("priority_signals", self.signal_tx.priority());
+    }
+
+    /// Spawn the supervised pipeline: every adapter, the router, the
+    /// strategy engine and the execution bridge. With a hot-path runtime
+    /// configured they run there, as do the tasks they spawn.
+    pub fn start(self) -> anyhow::Result<RunningEngine> {
+        let hot_runtime = self.hot_path.as_ref().map(HotPathRuntime::start).transpose()?;
+        if self.busy_poll.is_enabled() && self.hot_path.is_none() {
+            warn!(spin = ?self.busy_poll.spin, "busy-polling on the shared runtime takes worker threads from background tasks; set HOT_PATH_THREADS");
+        }
+        let hot = hot_runtime.as_ref().map_or_else(tokio::runtime::Handle::current, |rt| rt.handle().clone());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (restart, busy_poll, health, notifier) = (self.restart, self.busy_poll, self.health, self.notifier);
+        // Receivers sit behind a mutex outside any one run, so a restarted
+        // component resumes on the same channel and its upstream never sees
+        // it close.
+        let events_rx = Arc::new(Mutex::new(self.events_rx));
+        let notify_rx = Arc::new(Mutex::new(self.notify_rx));
+        let signal_rx = Arc::new(Mutex::new(self.signal_rx));
+        drop(self.events_tx);
+
+        for (name, adapter) in self.adapters {
+            hot.spawn(supervise(name, restart, health.clone(), notifier.clone(), shutdown_rx.clone(), adapter));
+        }
+
+        hot.spawn(supervise("router", restart, health.clone(), notifier.clone(), shutdown_rx.clone(), {
+            let (cache, notify_tx, bus, lane) = (self.cache.clone(), self.notify_tx, self.event_bus, self.venue_lane);
+            move || {
+                let (rx, cache, notify_tx, bus) = (Arc::clone(&events_rx), cache.clone(), notify_tx.clone(), bus.clone());
+                async move {
+                    let mut rx = rx.lock_owned().await;
+                    router::run_router(&mut rx, cache, notify_tx, bus, lane).await
+                }
+            }
+        }));
+
+        hot.spawn(supervise("strategy_engine", restart, health.clone(), notifier.clone(), shutdown_rx.clone(), {
+            let (cache, strategies, market_map) = (self.cache, self.strategies, self.market_map);
+            let (token_to_market, positions, clock) = (self.token_to_market, self.portfolio.positions().clone(), Arc::clone(&self.clock));
+            let (audit, pause, signal_tx) = (self.audit, self.pause.clone(), self.signal_tx);
+            move || {
+                let (rx, cache, strategies, signal_tx) = (Arc::clone(&notify_rx), cache.clone(), strategies.clone(), signal_tx.clone());
+                let (market_map, token_to_market) = (Arc::clone(&market_map), Arc::clone(&token_to_market));
+                let (positions, clock, audit, pause) = (positions.clone(), Arc::clone(&clock), audit.clone(), pause.clone());
+                async move {
+                    let mut rx = rx.lock_owned().await;
+                    strategy::run_strategy_engine(
+                        &mut rx, cache, strategies, signal_tx,
+                        market_map, token_to_market,
+                        positions,
+                        clock,
+                        audit,
+                        pause,
+                        busy_poll,
+                    )
+                    .await;
+                    Ok(())
+                }
+            }
+        }));
+
+        let execution = hot.spawn(supervise("execution_bridge", restart, health.clone(), notifier.clone(), shutdown_rx.clone(), {
+            let (executor, executor_name) = (self.executor, self.executor_name);
+            let (portfolio, risk, flags, recorder) = (self.portfolio, self.risk, self.flags, self.recorder);
+            let (clock, filter) = (self.clock, self.signal_filter);
+            let (health, notifier, shutdown) = (health.clone(), notifier.clone(), shutdown_rx.clone());
+            move || {
+                let (rx, executor, portfolio, risk, flags) =
+                    (Arc::clone(&signal_rx), Arc::clone(&executor), portfolio.clone(), risk.clone(), flags.clone());
+                let (recorder, clock, health, notifier, shutdown) =
+                    (recorder.clone(), Arc::clone(&clock), health.clone(), notifier.clone(), shutdown.clone());
+                async move {
+                    let mut rx = rx.lock_owned().await;
+                    execution::run_execution_bridge(
+                        &mut rx,
+                        executor,
+                        executor_name,
+                        portfolio,
+                        risk,
+                        flags,
+                        recorder,
+                        clock,
+                        health,
+                        notifier,
+                        shutdown,
+                        busy_poll,
+                        filter,
+                    )
+                    .await;
+                    Ok(())
+                }
+            }
+        }));
+
+        info!("engine started");
+        Ok(RunningEngine {
+            pause: self.pause,
+            shutdown: shutdown_tx,
+            execution: Some(execution),
+            market_data: self.market_data,
+            _hot_runtime: hot_runtime,
+        })
+    }
+}
+
+/// A started engine. Dropping it stops the hot-path runtime, if there is one.
+pub struct RunningEngine {
+    pause: PauseSwitch,
+    shutdown: watch::Sender<bool>,
+    execution: Option<JoinHandle<()>>,
+    market_data: Vec<Arc<dyn MarketDataAdapter>>,
+    /// Held only to be dropped with the engine.
+    _hot_runtime: Option<HotPathRuntime>,
+}
+
+impl RunningEngine {
+    /// Stop trading: pause the strategies, tell the execution bridge to
+    /// finish the order it is executing and drop what is queued, and wait up
+    /// to 10 s for it. Then shut down the adapters added with
+    /// [`market_data`](EngineBuilder::market_data); those added as plain
+    /// [`adapter`](EngineBuilder::adapter)s keep streaming until the engine
+    /// is dropped.
+    pub async fn shutdown(&mut self) {
+        self.pause.set_paused(true);
+        let _ = self.shutdown.send(true);
+        if let Some(execution) = self.execution.take() {
+            match tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, execution).await {
+                Ok(_) => info!("in-flight execution drained"),
+                Err(_) => warn!(timeout_secs = SHUTDOWN_DRAIN_TIMEOUT.as_secs(), "execution still in flight at shutdown; abandoning it"),
+            }
+        }
+        for adapter in &self.market_data {
+            adapter.shutdown().await;
+        }
+    }
+}
+
+/// Start `engine`, run it until Ctrl-C, then shut it down.
+pub async fn run(engine: Engine) -> anyhow::Result<()> {
+    let mut running = engine.start()?;
+    tokio::signal::ctrl_c().await?;
+    info!("shutting down");
+    running.shutdown().await;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+use anyhow::Context;
+use polymarket_rs::PrivateKeySigner;
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::secrets::{self, SecretRef};
+
+/// Where the Polymarket trading key is read from.
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// An environment variable (or `.env` entry): `PRIVATE_KEY` for the
+    /// default wallet.
+    Env(String),
+    /// OS keyring entry (macOS Keychain, Windows Credential Manager, Secret Service).
+    Keyring { service: String, user: String },
+    /// age file encrypted with a passphrase, prompted for on the terminal.
+    Age(PathBuf),
+    /// GPG-encrypted file, decrypted by the `gpg` binary (its agent prompts for the passphrase).
+    Gpg(PathBuf),
+    /// Vault or AWS Secrets Manager. Re-fetched periodically so a rotated key
+    /// is picked up without a restart.
+    Secret(SecretRef),
+}
+
+impl KeySource {
+    /// Remote sources are worth re-fetching; local ones only change on restart.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, KeySource::Secret(_))
+    }
+}
+
+/// Read the private key. The returned buffer is wiped on drop, as are the
+/// decrypted plaintext and passphrase; callers should parse it into a signer
+/// and drop it straight away. The env source can't scrub the process
+/// environment, so prefer one of the others in production.
+pub async fn load_private_key(source: &KeySource) -> anyhow::Result<Zeroizing<String>> {
+    let key = match source {
+        KeySource::Secret(secret) => secrets::fetch(secret).await?,
+        local => {
+            let local = local.clone();
+            tokio::task::spawn_blocking(move || read_local_key(&local)).await??
+        }
+    };
+
+    let trimmed = key.trim();
+    anyhow::ensure!(!trimmed.is_empty(), "private key from {source:?} is empty");
+    info!(source = source_name(source), "trading key loaded");
+    Ok(Zeroizing::new(trimmed.to_string()))
+}
+
+/// Load the key and parse it into a signer; the raw key is wiped before returning.
+/// Prompting for a passphrase happens on a blocking thread.
+pub async fn load_signer(source: &KeySource) -> anyhow::Result<PrivateKeySigner> {
+    let key = load_private_key(source).await?;
+    PrivateKeySigner::from_str(&key).context("invalid private key")
+}
+
+/// Parse a key fetched outside [`load_private_key`] (the rotation check).
+pub fn parse_signer(key: &str) -> anyhow::Result<PrivateKeySigner> {
+    PrivateKeySigner::from_str(key.trim()).context("invalid private key")
+}
+
+/// The sources that block: terminal prompts, keyring IPC, the gpg binary.
+fn read_local_key(source: &KeySource) -> anyhow::Result<Zeroizing<String>> {
+    Ok(match source {
+        KeySource::Env(var) => Zeroizing::new(std::env::var(var).with_context(|| format!("{var} is not set"))?),
+        KeySource::Keyring { service, user } => Zeroizing::new(
+            keyring::Entry::new(service, user)
+                .and_then(|entry| entry.get_password())
+                .with_context(|| format!("failed to read keyring entry {service}/{user}"))?,
+        ),
+        KeySource::Age(path) => {
+            let encrypted = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+            let passphrase = rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+                .context("failed to read passphrase")?;
+            let decryptor = match age::Decryptor::new(age::armor::ArmoredReader::new(&encrypted[..]))
+                .with_context(|| format!("{} is not an age file", path.display()))?
+            {
+                age::Decryptor::Passphrase(decryptor) => decryptor,
+                _ => anyhow::bail!("{} is not passphrase-encrypted (use `age -p`)", path.display()),
+            };
+            let mut reader = decryptor
+                .decrypt(&age::secrecy::Secret::new(passphrase), None)
+                .with_context(|| format!("failed to decrypt {}", path.display()))?;
+            let mut plaintext = Zeroizing::new(String::new());
+            reader.read_to_string(&mut plaintext)?;
+            plaintext
+        }
+        KeySource::Gpg(path) => {
+            let output = Command::new("gpg")
+                .args(["--quiet", "--decrypt"])
+                .arg(path)
+                .stdin(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .output()
+                .context("failed to run gpg")?;
+            let plaintext = Zeroizing::new(output.stdout);
+            anyhow::ensure!(output.status.success(), "gpg could not decrypt {}", path.display());
+            Zeroizing::new(String::from_utf8(plaintext.to_vec()).context("decrypted key is not UTF-8")?)
+        }
+        KeySource::Secret(_) => unreachable!("remote secrets are fetched asynchronously"),
+    })
+}
+
+fn source_name(source: &KeySource) -> &'static str {
+    match source {
+        KeySource::Env(_) => "env",
+        KeySource::Keyring { .. } => "keyring",
+        KeySource::Age(_) => "age",
+        KeySource::Gpg(_) => "gpg",
+        KeySource::Secret(secret) => secret.store.name(),
+    }
+}
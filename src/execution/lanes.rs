@@ -0,0 +1,138 @@
+//! Strategy engine → execution bridge signal path, in two lanes.
+//!
+//! Signals are executed one at a time, so a burst of marginal signals can
+//! leave a rare large-edge one waiting behind them while the book it was
+//! priced from moves away. Signals whose edge reaches `priority_edge` go on a
+//! lane of their own that the bridge always drains first.
+//!
+//! A priority signal can overtake older normal-lane signals for the same
+//! strategy and market. Those were priced from an earlier book, so the
+//! receiver reports them [superseded](SignalReceiver::is_superseded) and the
+//! bridge drops them rather than trading on a stale view after a fresh one.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use metrics::counter;
+use tokio::sync::mpsc;
+
+use crate::state::ids::MarketId;
+use crate::strategy::traits::TradeSignal;
+
+/// Create both lanes with `capacity` each. With `priority_edge` unset every
+/// signal takes the normal lane.
+pub fn signal_lanes(capacity: usize, priority_edge: Option<f64>) -> (SignalSender, SignalReceiver) {
+    let (normal_tx, normal_rx) = mpsc::channel(capacity);
+    let (priority_tx, priority_rx) = mpsc::channel(capacity);
+    (
+        SignalSender { normal: normal_tx, priority: priority_tx, priority_edge },
+        SignalReceiver {
+            normal: normal_rx,
+            priority: priority_rx,
+            normal_open: true,
+            priority_open: true,
+            overtaken: HashMap::new(),
+        },
+    )
+}
+
+#[derive(Clone)]
+pub struct SignalSender {
+    normal: mpsc::Sender<TradeSignal>,
+    priority: mpsc::Sender<TradeSignal>,
+    priority_edge: Option<f64>,
+}
+
+impl SignalSender {
+    /// Queue `signal` on the lane its edge calls for, waiting for space.
+    pub async fn send(&self, signal: TradeSignal) -> Result<(), mpsc::error::SendError<TradeSignal>> {
+        if self.priority_edge.is_some_and(|threshold| signal.edge >= threshold) {
+            counter!("execution_priority_signals_total", "strategy" => signal.strategy_name).increment(1);
+            return self.priority.send(signal).await;
+        }
+        self.normal.send(signal).await
+    }
+
+    /// The normal lane, for senders that don't carry an edge (operator
+    /// orders) and for channel monitoring.
+    pub fn normal(&self) -> &mpsc::Sender<TradeSignal> {
+        &self.normal
+    }
+
+    pub fn priority(&self) -> &mpsc::Sender<TradeSignal> {
+        &self.priority
+    }
+}
+
+pub struct SignalReceiver {
+    normal: mpsc::Receiver<TradeSignal>,
+    priority: mpsc::Receiver<TradeSignal>,
+    normal_open: bool,
+    priority_open: bool,
+    /// (strategy, market) → when the newest priority signal taken for it was
+    /// generated, until the normal lane catches up past it.
+    overtaken: HashMap<(&'static str, MarketId), Instant>,
+}
+
+impl SignalReceiver {
+    /// Next signal, priority lane first; `None` once both lanes are closed and empty.
+    pub async fn recv(&mut self) -> Option<TradeSignal> {
+        loop {
+            let (signal, priority) = tokio::select! {
+                biased;
+                signal = self.priority.recv(), if self.priority_open => match signal {
+                    Some(signal) => (signal, true),
+                    None => {
+                        self.priority_open = false;
+                        continue;
+                    }
+                },
+                signal = self.normal.recv(), if self.normal_open => match signal {
+                    Some(signal) => (signal, false),
+                    None => {
+                        self.normal_open = false;
+                        continue;
+                    }
+                },
+                else => return None,
+            };
+            self.taken(&signal, priority);
+            return Some(signal);
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<TradeSignal> {
+        if let Ok(signal) = self.priority.try_recv() {
+            self.taken(&signal, true);
+            return Some(signal);
+        }
+        let signal = self.normal.try_recv().ok()?;
+        self.taken(&signal, false);
+        Some(signal)
+    }
+
+    /// Whether a priority signal generated after `signal`, for the same
+    /// strategy and market, has already been taken.
+    pub fn is_superseded(&self, signal: &TradeSignal) -> bool {
+        self.overtaken
+            .get(&(signal.strategy_name, signal.market_id))
+            .is_some_and(|newest| signal.generated_at < *newest)
+    }
+
+    fn taken(&mut self, signal: &TradeSignal, priority: bool) {
+        let key = (signal.strategy_name, signal.market_id);
+        if priority {
+            let newest = self.overtaken.entry(key).or_insert(signal.generated_at);
+            *newest = (*newest).max(signal.generated_at);
+        } else if self.overtaken.get(&key).is_some_and(|newest| signal.generated_at > *newest) {
+            // The normal lane is in generation order, so nothing behind this is older.
+            self.overtaken.remove(&key);
+        }
+    }
+
+    /// Stop accepting signals on both lanes; queued ones can still be received.
+    pub fn close(&mut self) {
+        self.priority.close();
+        self.normal.close();
+    }
+}
@@ -1,40 +1,65 @@
-use std::str::FromStr;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::ToPrimitive;
+use tokio::sync::watch;
 use tracing::{info, warn};
 use polymarket_rs::{
     AuthenticatedClient, OrderBuilder, PrivateKeySigner,
     SignatureType, TradingClient,
 };
-use polymarket_rs::types::{OrderArgs, CreateOrderOptions, OrderType};
+use polymarket_rs::types::{AssetType, BalanceAllowanceParams, OrderArgs, CreateOrderOptions, OrderType};
 
-use crate::market_data::types::Side as OurSide;
+use crate::market_data::types::{Side as OurSide, Venue};
+use crate::rate_limit::{self, EndpointClass};
+use crate::secrets;
+use crate::state::market::as_f64;
+use super::keys::{load_signer, parse_signer, KeySource};
 use super::traits::{ExecutionEngine, ExecutionIntent, ExecutionReport, LegFillStatus};
-use std::time::Instant;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 const CLOB_HOST: &str = "https://clob.polymarket.com";
 const POLYGON_CHAIN_ID: u64 = 137;
+/// Polymarket CTF Exchange, the spender that must be approved for USDC.
+const CTF_EXCHANGE: &str = "0x4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e";
+/// USDC has 6 decimals.
+const USDC_UNIT: f64 = 1_000_000.0;
 
 pub struct LiveExecutor {
-    client: TradingClient,
+    /// Swapped by [`run_key_rotation`] when the key changes; each order
+    /// uses whichever client is current when it's placed.
+    client: watch::Receiver<Arc<TradingClient>>,
     tick_size: Decimal,
+    /// Sign orders but never post them; see [`LiveExecutor::dry_run`].
+    dry_run: bool,
+    next_dry_run_id: AtomicU64,
 }
 
 impl LiveExecutor {
     pub fn new(client: TradingClient, tick_size: Decimal) -> Self {
-        Self { client, tick_size }
+        Self::rotating(watch::channel(Arc::new(client)).1, tick_size)
+    }
+
+    pub fn rotating(client: watch::Receiver<Arc<TradingClient>>, tick_size: Decimal) -> Self {
+        Self { client, tick_size, dry_run: false, next_dry_run_id: AtomicU64::new(1) }
+    }
+
+    /// Build and sign every order exactly as live trading would, then report
+    /// it filled at the intended price instead of posting it. Exercises the
+    /// key, credentials and order encoding without risking funds.
+    pub fn dry_run(client: watch::Receiver<Arc<TradingClient>>, tick_size: Decimal) -> Self {
+        Self { dry_run: true, ..Self::rotating(client, tick_size) }
     }
 }
 
-pub async fn load_trading_client() -> anyhow::Result<TradingClient> {
+/// Authenticate against the CLOB with the key from `source`.
+pub async fn load_trading_client(source: &KeySource) -> anyhow::Result<TradingClient> {
     dotenvy::dotenv().ok();
+    trading_client_for(load_signer(source).await?).await
+}
 
-    let private_key = std::env::var("PRIVATE_KEY")
-        .expect("PRIVATE_KEY environment variable not set");
-    let signer = PrivateKeySigner::from_str(&private_key)
-        .expect("invalid PRIVATE_KEY");
-
+/// Derive API credentials for `signer` and build a client around it.
+pub async fn trading_client_for(signer: PrivateKeySigner) -> anyhow::Result<TradingClient> {
     let auth_client = AuthenticatedClient::new(
         CLOB_HOST,
         signer.clone(),
@@ -42,6 +67,7 @@ pub async fn load_trading_client() -> anyhow::Result<TradingClient> {
         None,
         None,
     );
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::Trading).await;
     let api_creds = auth_client.create_or_derive_api_key().await?;
 
     let order_builder = OrderBuilder::new(
@@ -61,6 +87,86 @@ pub async fn load_trading_client() -> anyhow::Result<TradingClient> {
     Ok(trading_client)
 }
 
+/// USDC the CLOB sees for a wallet, in dollars.
+#[derive(Debug, Clone, Copy)]
+pub struct Collateral {
+    pub balance: f64,
+    /// What the exchange may spend on the wallet's behalf.
+    pub allowance: f64,
+}
+
+/// Authenticate as `signer` and read its USDC balance and exchange allowance.
+/// Doubles as a credentials check: fails if the key can't derive API keys.
+pub async fn fetch_collateral(signer: &PrivateKeySigner) -> anyhow::Result<Collateral> {
+    let mut client = AuthenticatedClient::new(CLOB_HOST, signer.clone(), POLYGON_CHAIN_ID, None, None);
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::Trading).await;
+    let creds = client.create_or_derive_api_key().await?;
+    client.set_api_creds(Some(creds));
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::Trading).await;
+    let body = client
+        .get_balance_allowance(BalanceAllowanceParams::new().asset_type(AssetType::Collateral))
+        .await?;
+
+    let amount = |v: &serde_json::Value| v.as_str().and_then(|s| s.parse::<f64>().ok()).map(|units| units / USDC_UNIT);
+    let balance = amount(&body["balance"]).ok_or_else(|| anyhow::anyhow!("balance missing from {body}"))?;
+    // Older responses carry one `allowance`; newer ones one per spender.
+    let allowance = match body.get("allowances").and_then(|a| a.as_object()) {
+        Some(per_spender) => per_spender
+            .iter()
+            .find(|(spender, _)| spender.eq_ignore_ascii_case(CTF_EXCHANGE))
+            .and_then(|(_, v)| amount(v))
+            .unwrap_or_else(|| per_spender.values().filter_map(amount).fold(0.0, f64::max)),
+        None => amount(&body["allowance"]).unwrap_or(0.0),
+    };
+    Ok(Collateral { balance, allowance })
+}
+
+/// Re-fetch a remote trading key every `interval` and, when it has rotated,
+/// build a new client and publish it to the executor. Fetch or auth failures
+/// keep the current client and retry on the next tick. Returns when the
+/// executor is gone or the source isn't remote.
+pub async fn run_key_rotation(source: KeySource, interval: Duration, tx: watch::Sender<Arc<TradingClient>>) {
+    let KeySource::Secret(secret) = source else { return };
+    let mut current = match secrets::fetch(&secret).await.and_then(|key| parse_signer(&key)) {
+        Ok(signer) => signer.address(),
+        Err(e) => {
+            warn!(error = %format!("{e:#}"), "trading key re-fetch failed; rotation checks disabled");
+            return;
+        }
+    };
+    info!(store = secret.store.name(), interval_secs = interval.as_secs(), "watching trading key for rotation");
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tx.closed() => return,
+        }
+        let signer = match secrets::fetch(&secret).await.and_then(|key| parse_signer(&key)) {
+            Ok(signer) => signer,
+            Err(e) => {
+                warn!(error = %format!("{e:#}"), "trading key re-fetch failed; keeping current key");
+                continue;
+            }
+        };
+        let address = signer.address();
+        if address == current {
+            continue;
+        }
+        match trading_client_for(signer).await {
+            Ok(client) => {
+                info!(old = %current, new = %address, "trading key rotated; new orders use the new wallet");
+                current = address;
+                if tx.send(Arc::new(client)).is_err() {
+                    return;
+                }
+            }
+            Err(e) => warn!(error = %format!("{e:#}"), "failed to authenticate rotated key; keeping current key"),
+        }
+    }
+}
+
 fn convert_side(side: &OurSide) -> polymarket_rs::Side {
     match side {
         OurSide::Buy => polymarket_rs::Side::Buy,
@@ -71,14 +177,14 @@ fn convert_side(side: &OurSide) -> polymarket_rs::Side {
 #[async_trait]
 impl ExecutionEngine for LiveExecutor {
     async fn execute(&self, intent: ExecutionIntent) -> ExecutionReport {
+        let client = self.client.borrow().clone();
         let mut leg_results = Vec::with_capacity(intent.legs.len());
 
         for (i, leg) in intent.legs.iter().enumerate() {
-            let price = Decimal::try_from(leg.price).unwrap_or_default();
-            let size = Decimal::try_from(leg.size).unwrap_or_default();
+            let (price, size) = (leg.price, leg.size);
 
             let order_args = OrderArgs {
-                token_id: leg.token_id.clone(),
+                token_id: leg.token_id.to_string(),
                 price,
                 size,
                 side: convert_side(&leg.side),
@@ -89,7 +195,7 @@ impl ExecutionEngine for LiveExecutor {
                 neg_risk: Some(intent.neg_risk),
             };
 
-            let signed_order = match self.client.create_order(&order_args, None, None, options) {
+            let signed_order = match client.create_order(&order_args, None, None, options) {
                 Ok(order) => order,
                 Err(e) => {
                     warn!(
@@ -109,7 +215,26 @@ impl ExecutionEngine for LiveExecutor {
                 }
             };
 
-            match self.client.post_order(signed_order, OrderType::Fok).await {
+            if self.dry_run {
+                let order_id = format!("dry-run-{}", self.next_dry_run_id.fetch_add(1, Ordering::Relaxed));
+                info!(
+                    order_id = %order_id,
+                    token_id = %leg.token_id,
+                    side = ?leg.side,
+                    price = %price,
+                    size = %size,
+                    "DRY RUN — order signed, not posted"
+                );
+                leg_results.push(LegFillStatus::Filled {
+                    order_id,
+                    avg_price: as_f64(price),
+                    filled_size: as_f64(size),
+                });
+                continue;
+            }
+
+            rate_limit::acquire(Venue::Polymarket, EndpointClass::Trading).await;
+            match client.post_order(signed_order, OrderType::Fok).await {
                 Ok(resp) if resp.success => {
                     info!(
                         order_id = %resp.order_id,
@@ -121,8 +246,8 @@ impl ExecutionEngine for LiveExecutor {
                     );
                     leg_results.push(LegFillStatus::Filled {
                         order_id: resp.order_id.to_string(),
-                        avg_price: price.to_f64().unwrap_or(leg.price),
-                        filled_size: size.to_f64().unwrap_or(leg.size),
+                        avg_price: as_f64(price),
+                        filled_size: as_f64(size),
                     });
                 }
                 Ok(resp) => {
@@ -1,87 +1,456 @@
 pub mod traits;
 pub mod paper;
 pub mod live;
+pub mod keys;
+pub mod lanes;
+pub mod operator;
+pub mod wallets;
 
-use tokio::sync::mpsc;
-use tracing::{info, warn};
-use std::time::Instant;
+use tokio::sync::watch;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::metrics::prometheus::{
-    record_fill, record_rejection, record_signal_to_fill_latency_us, record_e2e_latency_us,
+    record_fill, record_rejection, record_signal_outcome, record_signal_to_fill_latency_us, record_e2e_latency_us,
 };
-use crate::strategy::traits::TradeSignal;
-use traits::{ExecutionEngine, ExecutionIntent, OrderLeg, LegFillStatus};
+use crate::persist::records::{
+    FillRecord, IntentRecord, OutcomeRecord, ReportRecord, RiskDecision, SignalOutcome, SignalRecord,
+};
+use crate::persist::{PersistRecord, Recorder};
+use crate::risk::RiskManager;
+use crate::runtime::BusyPoll;
+use crate::state::ids::MarketId;
+use crate::state::market::as_f64;
+use crate::state::market_cache::MarketKey;
+use crate::clock::SharedClock;
+use crate::flags::{execution_flag, venue_flag, FeatureFlags};
+use crate::health::HealthRegistry;
+use crate::notify::{Notifier, NotifyEvent};
+use crate::state::portfolio::{OpenOrderLeg, Portfolio};
+use crate::strategy::traits::SignalLeg;
+use lanes::SignalReceiver;
+use traits::{ExecutionEngine, ExecutionIntent, ExecutionReport, LegFillStatus, SignalResult};
+
+pub const HEALTH_COMPONENT: &str = "executor";
+
+/// Which strategy signals the bridge drops before risk checks. Each filter is
+/// off when `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalFilter {
+    /// Signals older than this when the bridge picks them up are dropped —
+    /// the book they were computed from has almost certainly moved.
+    pub ttl: Option<Duration>,
+    /// A signal with the same legs as one executed this recently for the same
+    /// strategy and market is a repeat of the same opportunity, not a new one.
+    pub dedup_window: Option<Duration>,
+}
+
+impl Default for SignalFilter {
+    fn default() -> Self {
+        Self { ttl: Some(Duration::from_millis(500)), dedup_window: Some(Duration::from_secs(2)) }
+    }
+}
 
 /// Bridges the strategy engine to the execution layer.
 /// Converts TradeSignals into ExecutionIntents, dispatches them,
-/// and records latency + fill metrics to Prometheus.
+/// records latency + fill metrics to Prometheus, and applies fills to the
+/// portfolio so strategies see up-to-date inventory. Every signal, intent,
+/// report, and fill is handed to `recorder` for persistence, followed by an
+/// outcome record saying how the signal was disposed of.
+///
+/// Reports `executor` up to `health` while running and down on exit, and
+/// sends each filled or rejected leg to `notifier`.
+///
+/// Signals whose `venue.<venue>` or `execution.<mode>` flag doesn't admit
+/// their market are blocked, except operator flattens.
+///
+/// Stale and repeated signals are dropped as `filter` says. A signal carrying a
+/// [`traits::ReplyTo`] has its result sent there too. Such signals are orders
+/// their submitter tracks individually, so they skip the duplicate filter, and are dropped if withdrawn before execution. Manual
+/// orders and operator flattens skip both the duplicate filter and the
+/// expiry.
+///
+/// When `shutdown` flips to true the bridge finishes the signal it is
+/// executing, discards anything still queued, and returns.
+///
+/// With `busy` enabled the bridge spins on `signal_rx` before parking; see
+/// [`BusyPoll`].
+#[allow(clippy::too_many_arguments)]
 pub async fn run_execution_bridge(
-    mut signal_rx: mpsc::Receiver<TradeSignal>,
-    executor: Box<dyn ExecutionEngine>,
+    signal_rx: &mut SignalReceiver,
+    executor: Arc<dyn ExecutionEngine>,
     executor_name: &'static str,
+    portfolio: Portfolio,
+    risk: RiskManager,
+    flags: FeatureFlags,
+    recorder: Recorder,
+    clock: SharedClock,
+    health: HealthRegistry,
+    notifier: Notifier,
+    mut shutdown: watch::Receiver<bool>,
+    busy: BusyPoll,
+    filter: SignalFilter,
 ) {
     info!("execution bridge started (executor={})", executor_name);
+    health.set_up(HEALTH_COMPONENT);
+
+    // Seeded from wall-clock so ids stay unique across restarts.
+    let mut next_signal_id = clock.unix_ms() * 1_000;
+    // (strategy, market) → legs + time of the last executed signal.
+    let mut last_executed: HashMap<(&'static str, MarketId), (Vec<SignalLeg>, Instant)> = HashMap::new();
 
-    while let Some(signal) = signal_rx.recv().await {
+    loop {
+        // A pending shutdown skips the spin so the select below sees it first.
+        let spun = match shutdown.has_changed() {
+            Ok(true) => None,
+            _ => busy.spin("execution_bridge", || signal_rx.try_recv()),
+        };
+        let mut signal = match spun {
+            Some(signal) => signal,
+            None => tokio::select! {
+                biased;
+                Ok(()) = shutdown.changed() => {
+                    if !*shutdown.borrow() {
+                        continue;
+                    }
+                    signal_rx.close();
+                    let mut discarded = 0;
+                    while signal_rx.try_recv().is_some() {
+                        discarded += 1;
+                    }
+                    info!(discarded, "shutdown requested, execution bridge stopped taking signals");
+                    health.set_down(HEALTH_COMPONENT, "shut down");
+                    return;
+                }
+                signal = signal_rx.recv() => match signal {
+                    Some(signal) => signal,
+                    None => break,
+                },
+            },
+        };
+        let superseded = signal.reply.is_none() && signal_rx.is_superseded(&signal);
         let signal_generated_at = signal.generated_at;
         let ws_received_at = signal.ws_received_at;
+        let mut stages = signal.stages;
+        let reply = signal.reply.take();
         let strategy_name = signal.strategy_name;
+        let signal_id = next_signal_id;
+        next_signal_id += 1;
 
-        let intent = ExecutionIntent {
-            venue: signal.venue,
-            market_id: signal.market_id,
-            strategy_name,
-            legs: signal
-                .legs
-                .into_iter()
-                .map(|leg| OrderLeg {
-                    token_id: leg.token_id,
-                    side: leg.side,
-                    price: leg.price,
-                    size: leg.size,
-                })
-                .collect(),
-            edge: signal.edge,
-            neg_risk: false,
-            created_at: Instant::now(),
-        };
+        // Every log line for this signal, including the executor's, carries
+        // these fields; `correlation_id` matches `signal_id` in the database.
+        let span = info_span!(
+            "signal",
+            correlation_id = signal_id,
+            strategy = strategy_name,
+            venue = ?signal.venue,
+            market_id = %signal.market_id,
+        );
 
-        let report = executor.execute(intent).await;
+        async {
+            recorder.record(PersistRecord::Signal(SignalRecord {
+                signal_id,
+                ts_ms: clock.unix_ms(),
+                strategy: strategy_name,
+                venue: signal.venue.clone(),
+                market_id: signal.market_id,
+                edge: signal.edge,
+                liquidity: signal.liquidity,
+                legs: signal.legs.clone(),
+            }));
 
-        // ── Record metrics ───────────────────────────────────────────
-        let signal_to_fill_us = signal_generated_at.elapsed().as_micros();
-        record_signal_to_fill_latency_us(strategy_name, signal_to_fill_us);
+            let total_legs = signal.legs.len();
+            let edge = signal.edge;
+            let venue_label = format!("{:?}", signal.venue);
+            let record_outcome = |market_id: MarketId,
+                                  risk: RiskDecision,
+                                  outcome: SignalOutcome,
+                                  filled_legs: usize,
+                                  report: Option<&ExecutionReport>| {
+                let elapsed_us = clock.elapsed_since(signal_generated_at).as_micros();
+                record_signal_outcome(strategy_name, outcome.name());
+                record_signal_to_fill_latency_us(strategy_name, &venue_label, outcome.name(), elapsed_us);
+                if let Some(ws_at) = ws_received_at {
+                    record_e2e_latency_us(strategy_name, &venue_label, outcome.name(), clock.elapsed_since(ws_at).as_micros());
+                }
+                recorder.record(PersistRecord::Outcome(OutcomeRecord {
+                    signal_id,
+                    ts_ms: clock.unix_ms(),
+                    strategy: strategy_name,
+                    market_id,
+                    edge,
+                    risk,
+                    outcome,
+                    filled_legs,
+                    total_legs,
+                    elapsed_us: elapsed_us as u64,
+                }));
+                if let Some(reply) = &reply {
+                    // Err only means the submitter has gone away.
+                    let _ = reply.tx.send(SignalResult {
+                        reference: reply.reference,
+                        signal_id,
+                        risk,
+                        outcome,
+                        report: report.cloned(),
+                    });
+                }
+            };
 
-        if let Some(ws_at) = ws_received_at {
-            let e2e_us = ws_at.elapsed().as_micros();
-            record_e2e_latency_us(strategy_name, e2e_us);
-        }
+            if reply.as_ref().is_some_and(|reply| reply.is_canceled()) {
+                debug!(strategy = strategy_name, market_id = %signal.market_id, "signal withdrawn before execution");
+                record_outcome(signal.market_id, RiskDecision::NotChecked, SignalOutcome::Canceled, 0, None);
+                return;
+            }
+
+            if superseded {
+                debug!(strategy = strategy_name, market_id = %signal.market_id, "signal overtaken by a newer priority signal — dropped");
+                record_outcome(signal.market_id, RiskDecision::NotChecked, SignalOutcome::Superseded, 0, None);
+                return;
+            }
+
+            // Orders entered by hand are what the operator asked for, not a
+            // strategy's read of the book: never a repeat, never stale.
+            let by_hand = matches!(strategy_name, operator::MANUAL_STRATEGY | operator::OPERATOR_STRATEGY);
+            let dedup_key = (strategy_name, signal.market_id);
+            if reply.is_none()
+                && !by_hand
+                && let Some(window) = filter.dedup_window
+                && let Some((legs, at)) = last_executed.get(&dedup_key)
+                && clock.elapsed_since(*at) < window
+                && *legs == signal.legs
+            {
+                debug!(strategy = strategy_name, market_id = %signal.market_id, "duplicate signal dropped");
+                record_outcome(signal.market_id, RiskDecision::NotChecked, SignalOutcome::Deduped, 0, None);
+                return;
+            }
+
+            if !by_hand && filter.ttl.is_some_and(|ttl| clock.elapsed_since(signal_generated_at) > ttl) {
+                warn!(
+                    strategy = strategy_name,
+                    market_id = %signal.market_id,
+                    age_ms = clock.elapsed_since(signal_generated_at).as_millis() as u64,
+                    "signal expired before execution — dropped"
+                );
+                record_outcome(signal.market_id, RiskDecision::NotChecked, SignalOutcome::Expired, 0, None);
+                return;
+            }
+
+            // Operator flattens reduce exposure, so flags, the breaker and the notional cap don't apply.
+            let operator = strategy_name == operator::OPERATOR_STRATEGY;
+            if !operator && !flags.is_empty() {
+                let gate = [venue_flag(&signal.venue), execution_flag(executor_name)]
+                    .into_iter()
+                    .find(|flag| !flags.allows(flag, signal.market_id.as_str()));
+                if let Some(flag) = gate {
+                    debug!(strategy = strategy_name, market_id = %signal.market_id, %flag, "feature flag off — signal dropped");
+                    record_outcome(signal.market_id, RiskDecision::FlagOff, SignalOutcome::Blocked, 0, None);
+                    return;
+                }
+            }
+            if risk.is_halted() && !operator {
+                warn!(
+                    strategy = strategy_name,
+                    market_id = %signal.market_id,
+                    "risk breaker tripped — signal dropped"
+                );
+                record_outcome(signal.market_id, RiskDecision::Halted, SignalOutcome::Blocked, 0, None);
+                return;
+            }
+            if !operator && risk.any_feed_stale(&signal.venue, signal.legs.iter().map(|leg| leg.token_id)) {
+                warn!(
+                    strategy = strategy_name,
+                    market_id = %signal.market_id,
+                    "a leg's market data feed is stale — signal dropped"
+                );
+                record_outcome(signal.market_id, RiskDecision::FeedStale, SignalOutcome::Blocked, 0, None);
+                return;
+            }
 
-        if report.fully_filled() {
-            record_fill(strategy_name, executor_name);
-            info!(
-                strategy = strategy_name,
-                market_id = %report.market_id,
-                legs = report.leg_results.len(),
-                signal_to_fill_us = signal_to_fill_us,
-                e2e_us = ws_received_at.map(|t| t.elapsed().as_micros()),
-                "execution complete — all legs filled"
+            let notional: f64 = signal.legs.iter().map(|leg| as_f64(leg.price * leg.size)).sum();
+            if !operator && !risk.check_daily_notional(notional, clock.unix_ms()) {
+                warn!(
+                    strategy = strategy_name,
+                    market_id = %signal.market_id,
+                    notional,
+                    "daily notional limit reached — signal dropped"
+                );
+                record_outcome(signal.market_id, RiskDecision::DailyNotional, SignalOutcome::Blocked, 0, None);
+                return;
+            }
+
+            last_executed.insert(dedup_key, (signal.legs.clone(), clock.now()));
+            if let Some(stages) = stages.as_mut() {
+                stages.risk_checked = Some(clock.now());
+            }
+
+            let intent = ExecutionIntent::from_signal(signal, clock.now());
+
+            let venue = intent.venue.clone();
+            let liquidity = intent.liquidity;
+            let legs = intent.legs.clone();
+
+            // Nothing is sent until the intent is on disk, so recovery after
+            // a crash always knows about orders that may be live.
+            let journaled = recorder
+                .journal(vec![PersistRecord::Intent(IntentRecord {
+                    signal_id,
+                    ts_ms: clock.unix_ms(),
+                    strategy: strategy_name,
+                    venue: venue.clone(),
+                    market_id: intent.market_id,
+                    edge: intent.edge,
+                    neg_risk: intent.neg_risk,
+                    legs: legs.clone(),
+                })])
+                .await;
+            if let Err(e) = journaled {
+                error!(error = %format!("{e:#}"), "journal write failed — signal dropped");
+                risk.halt("journal write failed");
+                record_outcome(intent.market_id, RiskDecision::Halted, SignalOutcome::Blocked, 0, None);
+                return;
+            }
+
+            let order_ref = portfolio.register_open_order(
+                venue.clone(),
+                intent.market_id,
+                strategy_name,
+                legs.iter()
+                    .map(|leg| OpenOrderLeg {
+                        token_id: leg.token_id,
+                        side: leg.side.clone(),
+                        price: as_f64(leg.price),
+                        size: as_f64(leg.size),
+                    })
+                    .collect(),
             );
-        } else {
-            let rejected = report.leg_results.iter()
-                .filter(|r| matches!(r, LegFillStatus::Rejected { .. }))
+            if let Some(stages) = stages.as_mut() {
+                stages.posted = Some(clock.now());
+            }
+            let report = executor.execute(intent).await;
+            portfolio.complete_order(order_ref);
+            if let Some(stages) = stages.as_mut() {
+                stages.acked = Some(report.completed_at);
+            }
+
+            // The report and its fills are journaled together, and before
+            // positions move, so recovery replays exactly what the venue did.
+            let ts_ms = clock.unix_ms();
+            let mut records = vec![PersistRecord::Report(ReportRecord {
+                signal_id,
+                ts_ms,
+                strategy: strategy_name,
+                market_id: report.market_id,
+                fully_filled: report.fully_filled(),
+                leg_results: report.leg_results.clone(),
+            })];
+            for (leg, result) in legs.iter().zip(&report.leg_results) {
+                if let LegFillStatus::Filled { order_id, avg_price, filled_size } = result {
+                    records.push(PersistRecord::Fill(FillRecord {
+                        signal_id,
+                        ts_ms,
+                        strategy: strategy_name,
+                        venue: venue.clone(),
+                        market_id: report.market_id,
+                        token_id: leg.token_id,
+                        order_id: order_id.clone(),
+                        side: leg.side.clone(),
+                        price: *avg_price,
+                        size: *filled_size,
+                        liquidity,
+                    }));
+                }
+            }
+            // The orders have executed either way, so the fills are still
+            // applied below; only new trading stops.
+            if let Err(e) = recorder.journal(records).await {
+                error!(error = %format!("{e:#}"), "journal write failed — fills not journaled");
+                risk.halt("journal write failed");
+            }
+
+            // ── Update positions ─────────────────────────────────────────
+            for (leg, result) in legs.iter().zip(&report.leg_results) {
+                if let LegFillStatus::Rejected { reason } = result {
+                    notifier.notify(NotifyEvent::Rejection {
+                        strategy: strategy_name,
+                        market_id: report.market_id.to_string(),
+                        reason: reason.clone(),
+                    });
+                }
+                if let LegFillStatus::Filled { avg_price, filled_size, .. } = result {
+                    portfolio.apply_fill(
+                        MarketKey(venue.clone(), leg.token_id),
+                        strategy_name,
+                        liquidity,
+                        &leg.side,
+                        *avg_price,
+                        *filled_size,
+                    );
+                    risk.record_fill_notional(avg_price * filled_size, ts_ms);
+                    notifier.notify(NotifyEvent::Fill {
+                        strategy: strategy_name,
+                        venue: venue.clone(),
+                        market_id: report.market_id.to_string(),
+                        side: leg.side.clone(),
+                        price: *avg_price,
+                        size: *filled_size,
+                    });
+                    health.mark_fill();
+                }
+            }
+
+            if let Some(stages) = stages.as_mut() {
+                stages.filled = Some(clock.now());
+                stages.record_execution(strategy_name);
+            }
+
+            let filled_legs = report.leg_results.iter()
+                .filter(|r| matches!(r, LegFillStatus::Filled { .. }))
                 .count();
-            if rejected > 0 {
-                record_rejection(strategy_name, executor_name);
-            }
-            warn!(
-                strategy = strategy_name,
-                market_id = %report.market_id,
-                leg_results = ?report.leg_results,
-                "execution incomplete — partial or rejected fills"
-            );
+            let outcome = if report.fully_filled() {
+                SignalOutcome::Filled
+            } else if filled_legs > 0 {
+                SignalOutcome::PartiallyFilled
+            } else {
+                SignalOutcome::Rejected
+            };
+            record_outcome(report.market_id, RiskDecision::Approved, outcome, filled_legs, Some(&report));
+
+            // ── Record metrics ───────────────────────────────────────────
+            // Latency histograms were recorded with the outcome above.
+            let signal_to_fill_us = clock.elapsed_since(signal_generated_at).as_micros();
+
+            if report.fully_filled() {
+                record_fill(strategy_name, executor_name);
+                info!(
+                    strategy = strategy_name,
+                    market_id = %report.market_id,
+                    legs = report.leg_results.len(),
+                    signal_to_fill_us = signal_to_fill_us,
+                    e2e_us = ws_received_at.map(|t| clock.elapsed_since(t).as_micros()),
+                    "execution complete — all legs filled"
+                );
+            } else {
+                let rejected = report.leg_results.iter()
+                    .filter(|r| matches!(r, LegFillStatus::Rejected { .. }))
+                    .count();
+                if rejected > 0 {
+                    record_rejection(strategy_name, executor_name);
+                }
+                warn!(
+                    strategy = strategy_name,
+                    market_id = %report.market_id,
+                    leg_results = ?report.leg_results,
+                    "execution incomplete — partial or rejected fills"
+                );
+            }
         }
+        .instrument(span)
+        .await;
     }
 
     info!("signal channel closed, execution bridge shutting down");
+    health.set_down(HEALTH_COMPONENT, "signal channel closed");
 }
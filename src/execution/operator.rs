@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use polymarket_rs::TradingClient;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+use crate::execution::traits::ReplyTo;
+use crate::market_data::adapters::polymarket::TokenToMarket;
+use crate::market_data::types::{Side, Venue};
+use crate::rate_limit::{self, EndpointClass};
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
+use crate::state::market::to_decimal;
+use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::portfolio::Portfolio;
+use crate::strategy::traits::{SignalLeg, TradeSignal};
+
+/// Strategy name on signals an operator submits by hand. The execution
+/// bridge lets these through the risk halt and daily notional limit, since
+/// they only ever reduce exposure.
+pub const OPERATOR_STRATEGY: &str = "operator";
+/// Strategy name on manual orders, which get the same risk checks as any
+/// strategy's signals.
+pub const MANUAL_STRATEGY: &str = "manual";
+
+/// Hands-on actions for incident response: flatten a market, place an order
+/// by hand, cancel resting orders. Cheap to clone.
+#[derive(Clone)]
+pub struct OperatorActions {
+    portfolio: Portfolio,
+    cache: MarketCache,
+    token_to_market: Arc<TokenToMarket>,
+    /// Weak so a stopped engine isn't kept alive by the admin API.
+    signal_tx: mpsc::WeakSender<TradeSignal>,
+    /// Set only in live mode; paper and dry-run have nothing resting at the venue.
+    client: Option<watch::Receiver<Arc<TradingClient>>>,
+    /// Clients of the extra `[[wallets]]`, by name; live mode only, as `client`.
+    wallets: Vec<(String, watch::Receiver<Arc<TradingClient>>)>,
+}
+
+/// One order entered by hand.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManualOrder {
+    #[serde(default = "default_venue")]
+    pub venue: Venue,
+    pub market_id: String,
+    pub token_id: String,
+    pub side: Side,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub size: Decimal,
+}
+
+fn default_venue() -> Venue {
+    Venue::Polymarket
+}
+
+/// What a flatten request did.
+#[derive(Debug, Default, Serialize)]
+pub struct FlattenReport {
+    /// Closing orders submitted, one per token.
+    pub legs: Vec<SignalLeg>,
+    /// Held tokens left open because the book had no price to close at.
+    pub unpriced: Vec<String>,
+}
+
+impl OperatorActions {
+    pub fn new(
+        portfolio: Portfolio,
+        cache: MarketCache,
+        token_to_market: Arc<TokenToMarket>,
+        signal_tx: mpsc::WeakSender<TradeSignal>,
+        client: Option<watch::Receiver<Arc<TradingClient>>>,
+    ) -> Self {
+        Self { portfolio, cache, token_to_market, signal_tx, client, wallets: Vec::new() }
+    }
+
+    /// Also cancel on these wallets' accounts in [`OperatorActions::cancel_all`].
+    pub fn with_wallets(mut self, wallets: Vec<(String, watch::Receiver<Arc<TradingClient>>)>) -> Self {
+        self.wallets = wallets;
+        self
+    }
+
+    /// Reject orders that can't be right: price outside (0, 1), non-positive
+    /// size, or a token that isn't part of the named market.
+    pub fn check(&self, order: &ManualOrder) -> anyhow::Result<()> {
+        self.check_leg(&order.market_id, &order.token_id, order.price, order.size)
+    }
+
+    /// [`check`](Self::check) for one leg of an order or externally built signal.
+    pub fn check_leg(&self, market_id: &str, token_id: &str, price: Decimal, size: Decimal) -> anyhow::Result<()> {
+        anyhow::ensure!(price > Decimal::ZERO && price < Decimal::ONE, "price {price} is outside (0, 1)");
+        anyhow::ensure!(size > Decimal::ZERO, "size {size} must be positive");
+        match self.token_to_market.get(token_id) {
+            Some(owner) if owner == market_id => Ok(()),
+            Some(owner) => anyhow::bail!("token {token_id} belongs to market {owner}, not {market_id}"),
+            None => anyhow::bail!("token {token_id} is not tracked"),
+        }
+    }
+
+    /// The market `token_id` belongs to, if it's tracked.
+    pub fn market_of(&self, token_id: &str) -> Option<&str> {
+        self.token_to_market.get(token_id).map(String::as_str)
+    }
+
+    /// Queue a hand-entered order as a `manual` signal. It takes the same
+    /// path as strategy signals — risk breaker, daily notional, executor,
+    /// journal — so this returns once it's queued, not once it's filled.
+    pub async fn submit(&self, order: ManualOrder) -> anyhow::Result<()> {
+        self.send_order(order, MANUAL_STRATEGY, None).await
+    }
+
+    /// Queue an order routed in from another system (e.g. an OMS over FIX)
+    /// as a `strategy` signal, checked like a manual order. Its result goes
+    /// to `reply` once the execution bridge is done with it.
+    pub async fn route(&self, order: ManualOrder, strategy: &'static str, reply: ReplyTo) -> anyhow::Result<()> {
+        self.send_order(order, strategy, Some(reply)).await
+    }
+
+    async fn send_order(&self, order: ManualOrder, strategy: &'static str, reply: Option<ReplyTo>) -> anyhow::Result<()> {
+        self.check(&order)?;
+        info!(
+            strategy,
+            market_id = %order.market_id,
+            token_id = %order.token_id,
+            side = ?order.side,
+            price = %order.price,
+            size = %order.size,
+            "order submitted"
+        );
+        let signal = TradeSignal {
+            strategy_name: strategy,
+            venue: order.venue,
+            market_id: MarketId::intern(&order.market_id),
+            legs: vec![SignalLeg { token_id: TokenId::intern(&order.token_id), side: order.side, price: order.price, size: order.size }],
+            edge: 0.0,
+            liquidity: Liquidity::Taker,
+            generated_at: Instant::now(),
+            ws_received_at: None,
+            stages: None,
+            reply,
+        };
+        self.submit_signal(signal).await
+    }
+
+    /// Queue a signal built outside the engine (e.g. by the signal webhook)
+    /// on the normal signal path. Its legs should already have passed
+    /// [`check_leg`](Self::check_leg).
+    pub async fn submit_signal(&self, signal: TradeSignal) -> anyhow::Result<()> {
+        let signal_tx = self.signal_tx.upgrade().ok_or_else(|| anyhow::anyhow!("execution bridge is not running"))?;
+        signal_tx.send(signal).await.map_err(|_| anyhow::anyhow!("execution bridge is not running"))
+    }
+
+    /// Close every position in `market_id` at the touch: longs are sold at
+    /// the best bid, shorts bought at the best ask. Goes through the normal
+    /// execution path, so fills are journaled and applied like any other.
+    pub async fn flatten(&self, market_id: &str) -> anyhow::Result<FlattenReport> {
+        let mut report = FlattenReport::default();
+        let mut by_venue: HashMap<Venue, Vec<SignalLeg>> = HashMap::new();
+        for position in self.portfolio.snapshot().positions {
+            if position.market_id.as_deref() != Some(market_id) || position.size == 0.0 {
+                continue;
+            }
+            let token_id = TokenId::intern(&position.token_id);
+            let state = self.cache.get_market_state(&MarketKey(position.venue.clone(), token_id));
+            let (side, price) = if position.size > 0.0 {
+                (Side::Sell, state.and_then(|s| s.best_bid))
+            } else {
+                (Side::Buy, state.and_then(|s| s.best_ask))
+            };
+            let Some(price) = price else {
+                report.unpriced.push(position.token_id);
+                continue;
+            };
+            by_venue.entry(position.venue).or_default().push(SignalLeg {
+                token_id,
+                side,
+                price,
+                size: to_decimal(position.size.abs())?,
+            });
+        }
+
+        let signal_tx = self.signal_tx.upgrade().ok_or_else(|| anyhow::anyhow!("execution bridge is not running"))?;
+        for (venue, legs) in by_venue {
+            info!(%market_id, ?venue, legs = legs.len(), "operator flatten submitted");
+            report.legs.extend(legs.iter().cloned());
+            let signal = TradeSignal {
+                strategy_name: OPERATOR_STRATEGY,
+                venue,
+                market_id: MarketId::intern(market_id),
+                legs,
+                edge: 0.0,
+                liquidity: Liquidity::Taker,
+                generated_at: Instant::now(),
+                ws_received_at: None,
+                stages: None,
+                reply: None,
+            };
+            signal_tx.send(signal).await.map_err(|_| anyhow::anyhow!("execution bridge is not running"))?;
+        }
+        if !report.unpriced.is_empty() {
+            warn!(%market_id, tokens = ?report.unpriced, "flatten skipped tokens with no quote");
+        }
+        Ok(report)
+    }
+
+    /// Cancel every resting order on the trading account and each wallet's.
+    /// Returns how many the venue cancelled; always 0 outside live mode.
+    pub async fn cancel_all(&self) -> anyhow::Result<usize> {
+        let Some(client) = &self.client else {
+            info!("cancel-all requested; no venue orders outside live mode");
+            return Ok(0);
+        };
+        let accounts = std::iter::once(("default", client)).chain(self.wallets.iter().map(|(name, c)| (name.as_str(), c)));
+        let mut canceled = 0;
+        for (wallet, client) in accounts {
+            let client = client.borrow().clone();
+            rate_limit::acquire(Venue::Polymarket, EndpointClass::Trading).await;
+            let response = client.cancel_all().await.map_err(|e| anyhow::anyhow!("cancel-all failed on wallet {wallet}: {e}"))?;
+            info!(wallet, canceled = response.canceled.len(), "operator cancelled all resting orders");
+            canceled += response.canceled.len();
+        }
+        Ok(canceled)
+    }
+}
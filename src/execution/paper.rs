@@ -3,12 +3,19 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 use tracing::info;
 
+use crate::state::market::as_f64;
 use super::traits::{ExecutionEngine, ExecutionIntent, ExecutionReport, LegFillStatus};
 
 pub struct PaperExecutor {
     next_order_id: AtomicU64,
 }
 
+impl Default for PaperExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PaperExecutor {
     pub fn new() -> Self {
         Self {
@@ -30,16 +37,16 @@ impl ExecutionEngine for PaperExecutor {
                     order_id,
                     token_id = %leg.token_id,
                     side = ?leg.side,
-                    price = leg.price,
-                    size = leg.size,
+                    price = %leg.price,
+                    size = %leg.size,
                     market_id = %intent.market_id,
                     "PAPER FILL"
                 );
 
                 LegFillStatus::Filled {
                     order_id: order_id.to_string(),
-                    avg_price: leg.price,
-                    filled_size: leg.size,
+                    avg_price: as_f64(leg.price),
+                    filled_size: as_f64(leg.size),
                 }
             })
             .collect();
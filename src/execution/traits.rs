@@ -1,27 +1,68 @@
 use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Serialize;
 use crate::market_data::types::{Venue, Side};
+use crate::persist::records::{RiskDecision, SignalOutcome};
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
+use crate::strategy::traits::TradeSignal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::mpsc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct OrderLeg {
-    pub token_id: String,
+    pub token_id: TokenId,
     pub side: Side,
-    pub price: f64,
-    pub size: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub size: Decimal,
 }
 
 #[derive(Debug, Clone)]
 pub struct ExecutionIntent {
     pub venue: Venue,
-    pub market_id: String,
+    pub market_id: MarketId,
     pub strategy_name: &'static str,
     pub legs: Vec<OrderLeg>,
     pub edge: f64,
+    pub liquidity: Liquidity,
     pub neg_risk: bool,
     pub created_at: Instant,
+    /// Arrival of the market update behind the signal, for tick-to-trade latency.
+    pub ws_received_at: Option<Instant>,
 }
 
-#[derive(Debug, Clone)]
+impl ExecutionIntent {
+    /// One order leg per signal leg, at the signal's limit prices.
+    pub fn from_signal(signal: TradeSignal, created_at: Instant) -> Self {
+        Self {
+            venue: signal.venue,
+            market_id: signal.market_id,
+            strategy_name: signal.strategy_name,
+            legs: signal
+                .legs
+                .into_iter()
+                .map(|leg| OrderLeg {
+                    token_id: leg.token_id,
+                    side: leg.side,
+                    price: leg.price,
+                    size: leg.size,
+                })
+                .collect(),
+            edge: signal.edge,
+            liquidity: signal.liquidity,
+            neg_risk: false,
+            created_at,
+            ws_received_at: signal.ws_received_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
 pub enum LegFillStatus {
     Filled {
         order_id: String,
@@ -36,7 +77,7 @@ pub enum LegFillStatus {
 
 #[derive(Debug, Clone)]
 pub struct ExecutionReport {
-    pub market_id: String,
+    pub market_id: MarketId,
     pub strategy_name: &'static str,
     pub leg_results: Vec<LegFillStatus>,
     pub completed_at: Instant,
@@ -48,6 +89,35 @@ impl ExecutionReport {
     }
 }
 
+/// Where the execution bridge reports what became of a signal, for
+/// submitters that track their orders one by one (the FIX gateway).
+#[derive(Debug, Clone)]
+pub struct ReplyTo {
+    /// The submitter's own id for the signal, handed back on the result.
+    pub reference: u64,
+    pub tx: mpsc::UnboundedSender<SignalResult>,
+    /// Set to withdraw the signal. The bridge drops it unexecuted if it
+    /// hasn't reached the executor yet.
+    pub canceled: Arc<AtomicBool>,
+}
+
+impl ReplyTo {
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Acquire)
+    }
+}
+
+/// A signal's final disposition, sent to its [`ReplyTo`].
+#[derive(Debug, Clone)]
+pub struct SignalResult {
+    pub reference: u64,
+    pub signal_id: u64,
+    pub risk: RiskDecision,
+    pub outcome: SignalOutcome,
+    /// What the executor reported; `None` if the signal never reached it.
+    pub report: Option<ExecutionReport>,
+}
+
 #[async_trait]
 pub trait ExecutionEngine: Send + Sync {
     async fn execute(&self, intent: ExecutionIntent) -> ExecutionReport;
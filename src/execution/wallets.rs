@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::clock::SharedClock;
+use crate::risk::RiskManager;
+use crate::state::market::as_f64;
+use super::traits::{ExecutionEngine, ExecutionIntent, ExecutionReport, LegFillStatus};
+
+/// One signing wallet: its own executor (and so its own key, nonces and
+/// balance) and its own daily notional budget.
+struct Wallet {
+    name: String,
+    executor: Arc<dyn ExecutionEngine>,
+    risk: RiskManager,
+}
+
+/// Routes each intent to the wallet its strategy is assigned to, or to the
+/// default executor when it has none. A wallet's daily notional budget is
+/// checked before its orders go out and charged with what fills; the
+/// bridge's own checks still apply across all wallets on top.
+pub struct WalletRouter {
+    default: Arc<dyn ExecutionEngine>,
+    wallets: Vec<Wallet>,
+    /// strategy name → index into `wallets`.
+    by_strategy: HashMap<String, usize>,
+    clock: SharedClock,
+}
+
+impl WalletRouter {
+    pub fn new(default: Arc<dyn ExecutionEngine>, clock: SharedClock) -> Self {
+        Self { default, wallets: Vec::new(), by_strategy: HashMap::new(), clock }
+    }
+
+    /// Add a wallet that executes for `strategies`. A strategy already
+    /// assigned to an earlier wallet moves to this one.
+    pub fn wallet(
+        mut self,
+        name: impl Into<String>,
+        executor: Arc<dyn ExecutionEngine>,
+        risk: RiskManager,
+        strategies: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let index = self.wallets.len();
+        self.wallets.push(Wallet { name: name.into(), executor, risk });
+        self.by_strategy.extend(strategies.into_iter().map(|s| (s, index)));
+        self
+    }
+
+    fn reject(&self, intent: &ExecutionIntent, reason: String) -> ExecutionReport {
+        ExecutionReport {
+            market_id: intent.market_id,
+            strategy_name: intent.strategy_name,
+            leg_results: intent.legs.iter().map(|_| LegFillStatus::Rejected { reason: reason.clone() }).collect(),
+            completed_at: self.clock.now(),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionEngine for WalletRouter {
+    async fn execute(&self, intent: ExecutionIntent) -> ExecutionReport {
+        let Some(wallet) = self.by_strategy.get(intent.strategy_name).map(|&i| &self.wallets[i]) else {
+            return self.default.execute(intent).await;
+        };
+
+        let notional: f64 = intent.legs.iter().map(|leg| as_f64(leg.price * leg.size)).sum();
+        if wallet.risk.is_halted() {
+            return self.reject(&intent, format!("wallet {}: trading halted", wallet.name));
+        }
+        if !wallet.risk.check_daily_notional(notional, self.clock.unix_ms()) {
+            warn!(wallet = %wallet.name, strategy = intent.strategy_name, notional, "wallet daily notional limit reached");
+            return self.reject(&intent, format!("wallet {}: daily notional limit reached", wallet.name));
+        }
+
+        let report = wallet.executor.execute(intent).await;
+        let ts_ms = self.clock.unix_ms();
+        for result in &report.leg_results {
+            if let LegFillStatus::Filled { avg_price, filled_size, .. } = result {
+                wallet.risk.record_fill_notional(avg_price * filled_size, ts_ms);
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,224 @@
+//! FIX tag=value encoding: framing, body length and checksum, field access.
+//!
+//! Only what the gateway speaks is covered: FIX 4.4 with one value per tag
+//! and no binary (`RawData`) fields.
+
+use std::fmt::Display;
+
+pub const BEGIN_STRING: &str = "FIX.4.4";
+const SOH: u8 = 0x01;
+/// A peer that sends this much without completing a message is not speaking FIX.
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+pub mod tag {
+    pub const AVG_PX: u32 = 6;
+    pub const BEGIN_SEQ_NO: u32 = 7;
+    pub const BEGIN_STRING: u32 = 8;
+    pub const BODY_LENGTH: u32 = 9;
+    pub const CHECKSUM: u32 = 10;
+    pub const CL_ORD_ID: u32 = 11;
+    pub const CUM_QTY: u32 = 14;
+    pub const END_SEQ_NO: u32 = 16;
+    pub const EXEC_ID: u32 = 17;
+    pub const LAST_PX: u32 = 31;
+    pub const LAST_QTY: u32 = 32;
+    pub const MSG_SEQ_NUM: u32 = 34;
+    pub const MSG_TYPE: u32 = 35;
+    pub const NEW_SEQ_NO: u32 = 36;
+    pub const ORDER_ID: u32 = 37;
+    pub const ORDER_QTY: u32 = 38;
+    pub const ORD_STATUS: u32 = 39;
+    pub const ORD_TYPE: u32 = 40;
+    pub const ORIG_CL_ORD_ID: u32 = 41;
+    pub const POSS_DUP_FLAG: u32 = 43;
+    pub const PRICE: u32 = 44;
+    pub const REF_SEQ_NUM: u32 = 45;
+    pub const SENDER_COMP_ID: u32 = 49;
+    pub const SENDING_TIME: u32 = 52;
+    pub const SIDE: u32 = 54;
+    pub const SYMBOL: u32 = 55;
+    pub const TARGET_COMP_ID: u32 = 56;
+    pub const TEXT: u32 = 58;
+    pub const TIME_IN_FORCE: u32 = 59;
+    pub const TRANSACT_TIME: u32 = 60;
+    pub const ENCRYPT_METHOD: u32 = 98;
+    pub const EX_DESTINATION: u32 = 100;
+    pub const CXL_REJ_REASON: u32 = 102;
+    pub const ORD_REJ_REASON: u32 = 103;
+    pub const HEART_BT_INT: u32 = 108;
+    pub const TEST_REQ_ID: u32 = 112;
+    pub const GAP_FILL_FLAG: u32 = 123;
+    pub const RESET_SEQ_NUM_FLAG: u32 = 141;
+    pub const EXEC_TYPE: u32 = 150;
+    pub const LEAVES_QTY: u32 = 151;
+    pub const REF_TAG_ID: u32 = 371;
+    pub const REF_MSG_TYPE: u32 = 372;
+    pub const SESSION_REJECT_REASON: u32 = 373;
+    pub const BUSINESS_REJECT_REASON: u32 = 380;
+    pub const CXL_REJ_RESPONSE_TO: u32 = 434;
+    pub const PASSWORD: u32 = 554;
+}
+
+pub mod msg_type {
+    pub const HEARTBEAT: &str = "0";
+    pub const TEST_REQUEST: &str = "1";
+    pub const RESEND_REQUEST: &str = "2";
+    pub const REJECT: &str = "3";
+    pub const SEQUENCE_RESET: &str = "4";
+    pub const LOGOUT: &str = "5";
+    pub const EXECUTION_REPORT: &str = "8";
+    pub const ORDER_CANCEL_REJECT: &str = "9";
+    pub const LOGON: &str = "A";
+    pub const NEW_ORDER_SINGLE: &str = "D";
+    pub const ORDER_CANCEL_REQUEST: &str = "F";
+    pub const BUSINESS_MESSAGE_REJECT: &str = "j";
+}
+
+/// One message: its type plus every field between the type and the
+/// checksum, in wire order.
+#[derive(Debug, Clone)]
+pub struct Message {
+    msg_type: String,
+    fields: Vec<(u32, String)>,
+}
+
+impl Message {
+    pub fn new(msg_type: &str) -> Self {
+        Self { msg_type: msg_type.to_string(), fields: Vec::new() }
+    }
+
+    pub fn msg_type(&self) -> &str {
+        &self.msg_type
+    }
+
+    /// First value of `tag`.
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| v.as_str())
+    }
+
+    /// `tag` parsed, or `None` when it's absent or malformed.
+    pub fn parse<T: std::str::FromStr>(&self, tag: u32) -> Option<T> {
+        self.get(tag).and_then(|v| v.parse().ok())
+    }
+
+    /// Whether `tag` is present with the value `Y`.
+    pub fn flag(&self, tag: u32) -> bool {
+        self.get(tag) == Some("Y")
+    }
+
+    pub fn with(mut self, tag: u32, value: impl Display) -> Self {
+        self.push(tag, value);
+        self
+    }
+
+    pub fn push(&mut self, tag: u32, value: impl Display) {
+        self.fields.push((tag, value.to_string()));
+    }
+
+    /// Put the standard header fields in front of the body.
+    pub fn set_header(&mut self, sender: &str, target: &str, seq: u64, sending_time: &str) {
+        let header = [
+            (tag::SENDER_COMP_ID, sender.to_string()),
+            (tag::TARGET_COMP_ID, target.to_string()),
+            (tag::MSG_SEQ_NUM, seq.to_string()),
+            (tag::SENDING_TIME, sending_time.to_string()),
+        ];
+        self.fields.splice(0..0, header);
+    }
+
+    /// Wire form, with `BodyLength` and `CheckSum` filled in.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(128);
+        push_field(&mut body, tag::MSG_TYPE, &self.msg_type);
+        for (tag, value) in &self.fields {
+            push_field(&mut body, *tag, value);
+        }
+        let mut out = Vec::with_capacity(body.len() + 32);
+        push_field(&mut out, tag::BEGIN_STRING, BEGIN_STRING);
+        push_field(&mut out, tag::BODY_LENGTH, &body.len().to_string());
+        out.extend_from_slice(&body);
+        let checksum = checksum(&out);
+        push_field(&mut out, tag::CHECKSUM, &format!("{checksum:03}"));
+        out
+    }
+}
+
+fn push_field(out: &mut Vec<u8>, tag: u32, value: &str) {
+    out.extend_from_slice(tag.to_string().as_bytes());
+    out.push(b'=');
+    out.extend_from_slice(value.as_bytes());
+    out.push(SOH);
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Take the first complete message off the front of `buf`. `None` until
+/// one has fully arrived; `Some(Err)` for a garbled one, which has been
+/// consumed and should be ignored. Bytes before a `BeginString` are skipped.
+pub fn decode(buf: &mut Vec<u8>) -> Option<Result<Message, String>> {
+    let prefix = format!("8={BEGIN_STRING}\x01");
+    let Some(start) = find(buf, prefix.as_bytes()) else {
+        // Keep a tail that could still grow into the prefix.
+        let keep = buf.len().min(prefix.len() - 1);
+        buf.drain(..buf.len() - keep);
+        return None;
+    };
+    buf.drain(..start);
+
+    let after_prefix = prefix.len();
+    let length_end = after_prefix + buf[after_prefix..].iter().position(|b| *b == SOH)?;
+    let Some(body_len) = std::str::from_utf8(&buf[after_prefix..length_end])
+        .ok()
+        .and_then(|field| field.strip_prefix("9="))
+        .and_then(|len| len.parse::<usize>().ok())
+    else {
+        buf.drain(..after_prefix);
+        return Some(Err("missing or malformed BodyLength".into()));
+    };
+    let body_start = length_end + 1;
+    let trailer_start = body_start + body_len;
+    // `10=nnn<SOH>`
+    let end = trailer_start + 7;
+    if buf.len() < end {
+        return None;
+    }
+
+    let frame: Vec<u8> = buf.drain(..end).collect();
+    let trailer = &frame[trailer_start..];
+    if !trailer.starts_with(b"10=") || trailer[6] != SOH {
+        return Some(Err("BodyLength doesn't end at the CheckSum".into()));
+    }
+    let expected = checksum(&frame[..trailer_start]);
+    match std::str::from_utf8(&trailer[3..6]).ok().and_then(|c| c.parse::<u8>().ok()) {
+        Some(sum) if sum == expected => {}
+        _ => return Some(Err(format!("bad CheckSum, expected {expected:03}"))),
+    }
+
+    let body = match std::str::from_utf8(&frame[body_start..trailer_start]) {
+        Ok(body) => body,
+        Err(_) => return Some(Err("body is not UTF-8".into())),
+    };
+    let mut fields = Vec::new();
+    for field in body.split('\x01').filter(|f| !f.is_empty()) {
+        let Some((tag, value)) = field.split_once('=') else {
+            return Some(Err(format!("field '{field}' has no '='")));
+        };
+        let Ok(tag) = tag.parse::<u32>() else {
+            return Some(Err(format!("tag '{tag}' is not a number")));
+        };
+        fields.push((tag, value.to_string()));
+    }
+    match fields.first() {
+        Some((tag::MSG_TYPE, _)) => {
+            let msg_type = fields.remove(0).1;
+            Some(Ok(Message { msg_type, fields }))
+        }
+        _ => Some(Err("MsgType is not the first body field".into())),
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
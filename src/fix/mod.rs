@@ -0,0 +1,67 @@
+//! FIX 4.4 acceptor, so an external OMS can route orders through the
+//! engine's venue connectivity and risk checks.
+//!
+//! A NewOrderSingle becomes a `fix` signal on the same path as strategy
+//! signals: feature flags, risk breaker, daily notional limit, executor,
+//! journal. The client hears back with ExecutionReports: Pending New once
+//! the order is queued, then a trade, rejection, expiry or cancel once the
+//! execution bridge is done with it. An OrderCancelRequest withdraws an
+//! order that hasn't reached the executor yet; orders are fill-or-kill at
+//! the venue, so after that there is nothing left to cancel.
+//!
+//! Sessions don't survive a reconnect. There is no message store: every
+//! Logon must reset sequence numbers, a ResendRequest is answered with a
+//! gap fill, and orders still queued when the connection drops are
+//! withdrawn.
+
+mod message;
+mod session;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::execution::operator::OperatorActions;
+
+/// Strategy name on signals routed in over FIX.
+pub const FIX_STRATEGY: &str = "fix";
+/// Pause after a failed accept (e.g. out of file descriptors).
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct FixConfig {
+    pub addr: SocketAddr,
+    /// Our CompID: clients send it as TargetCompID.
+    pub comp_id: String,
+    /// SenderCompIDs allowed to log on.
+    pub clients: Vec<String>,
+    /// Required in the Logon's Password field when set.
+    pub password: Option<String>,
+}
+
+/// Accept FIX sessions until the listener fails. Orders go through `operator`.
+pub async fn run_fix_gateway(config: FixConfig, operator: OperatorActions) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(config.addr).await?;
+    info!(addr = %config.addr, comp_id = %config.comp_id, clients = ?config.clients, "FIX gateway listening");
+    // Seeded from wall-clock so OrderIDs stay unique across restarts.
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64) * 1_000;
+    let ids = Arc::new(AtomicU64::new(seed));
+    let config = Arc::new(config);
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!(error = %e, "FIX accept failed");
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        info!(%peer, "FIX connection accepted");
+        tokio::spawn(session::run(stream, peer, Arc::clone(&config), operator.clone(), Arc::clone(&ids)));
+    }
+}
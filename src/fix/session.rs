@@ -0,0 +1,667 @@
+//! One FIX session: logon, sequence numbers, heartbeats, and the order
+//! messages mapped onto [`OperatorActions::route`].
+
+use std::cmp::Ordering as SeqOrder;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use metrics::{counter, gauge};
+use rust_decimal::Decimal;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use super::message::{decode, msg_type, tag, Message, MAX_MESSAGE_LEN};
+use super::{FixConfig, FIX_STRATEGY};
+use crate::execution::operator::{ManualOrder, OperatorActions};
+use crate::execution::traits::{LegFillStatus, ReplyTo, SignalResult};
+use crate::market_data::types::{Side, Venue};
+use crate::persist::records::SignalOutcome;
+use crate::state::market::as_f64;
+
+/// A connection that hasn't logged on by now is dropped.
+const LOGON_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often heartbeat deadlines are checked.
+const TICK: Duration = Duration::from_secs(1);
+
+// SessionRejectReason (373)
+const REQUIRED_TAG_MISSING: u32 = 1;
+const VALUE_INCORRECT: u32 = 5;
+const INCORRECT_DATA_FORMAT: u32 = 6;
+const COMP_ID_PROBLEM: u32 = 9;
+// OrdRejReason (103)
+const UNKNOWN_SYMBOL: u32 = 1;
+const EXCEEDS_LIMIT: u32 = 3;
+const DUPLICATE_ORDER: u32 = 6;
+const UNSUPPORTED_CHARACTERISTIC: u32 = 11;
+const OTHER: u32 = 99;
+// CxlRejReason (102)
+const TOO_LATE_TO_CANCEL: u32 = 0;
+const UNKNOWN_ORDER: u32 = 1;
+const ALREADY_PENDING_CANCEL: u32 = 3;
+// BusinessRejectReason (380)
+const UNSUPPORTED_MESSAGE_TYPE: u32 = 3;
+
+// ExecType (150) / OrdStatus (39) values used here.
+const PENDING_NEW: &str = "A";
+const FILLED: &str = "2";
+const CANCELED: &str = "4";
+const PENDING_CANCEL: &str = "6";
+const REJECTED: &str = "8";
+const EXPIRED: &str = "C";
+const TRADE: &str = "F";
+
+/// Serve one connection until it logs out, fails, or goes silent.
+pub(super) async fn run(stream: TcpStream, peer: SocketAddr, config: Arc<FixConfig>, operator: OperatorActions, ids: Arc<AtomicU64>) {
+    let (mut reader, writer) = stream.into_split();
+    let (replies_tx, replies_rx) = mpsc::unbounded_channel();
+    let mut session = Session {
+        writer,
+        config,
+        operator,
+        ids,
+        replies_tx,
+        client: String::new(),
+        out_seq: 1,
+        in_seq: 1,
+        heartbeat: Duration::from_secs(30),
+        last_sent: Instant::now(),
+        last_received: Instant::now(),
+        test_request_sent: false,
+        resend_requested: false,
+        orders: HashMap::new(),
+        by_reference: HashMap::new(),
+    };
+    gauge!("fix_sessions").increment(1.0);
+    let result = session.serve(&mut reader, replies_rx).await;
+    gauge!("fix_sessions").decrement(1.0);
+    let withdrawn = session.withdraw_pending();
+    match result {
+        Ok(()) => info!(%peer, client = %session.client, withdrawn, "FIX session closed"),
+        Err(e) => warn!(%peer, client = %session.client, withdrawn, error = %format!("{e:#}"), "FIX session ended"),
+    }
+}
+
+struct Session {
+    writer: OwnedWriteHalf,
+    config: Arc<FixConfig>,
+    operator: OperatorActions,
+    /// Shared by every session, so OrderIDs and ExecIDs never repeat.
+    ids: Arc<AtomicU64>,
+    replies_tx: mpsc::UnboundedSender<SignalResult>,
+    /// The client's SenderCompID, once it has logged on.
+    client: String,
+    /// Next MsgSeqNum to send.
+    out_seq: u64,
+    /// Next MsgSeqNum expected from the client.
+    in_seq: u64,
+    heartbeat: Duration,
+    last_sent: Instant,
+    last_received: Instant,
+    test_request_sent: bool,
+    resend_requested: bool,
+    /// Every order of this session, by ClOrdID.
+    orders: HashMap<String, Order>,
+    /// ClOrdID of each order still at the execution bridge, by reference.
+    by_reference: HashMap<u64, String>,
+}
+
+struct Order {
+    order_id: String,
+    cl_ord_id: String,
+    symbol: String,
+    side: Side,
+    qty: Decimal,
+    price: Decimal,
+    /// Current OrdStatus.
+    status: &'static str,
+    cum_qty: f64,
+    avg_px: f64,
+    canceled: Arc<AtomicBool>,
+    /// ClOrdID of a cancel request still waiting on the bridge.
+    cancel: Option<String>,
+}
+
+/// A NewOrderSingle that can't be accepted, and how to say so.
+enum Rejection {
+    /// Malformed: a session-level Reject naming the offending tag.
+    Session { reason: u32, tag: u32 },
+    /// Well-formed but not something the engine will trade: a rejected
+    /// ExecutionReport.
+    Order { reason: u32, text: String },
+}
+
+struct NewOrder {
+    cl_ord_id: String,
+    symbol: String,
+    side: Side,
+    qty: Decimal,
+    price: Decimal,
+    venue: Venue,
+}
+
+impl Session {
+    async fn serve(&mut self, reader: &mut OwnedReadHalf, mut replies: mpsc::UnboundedReceiver<SignalResult>) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+
+        let logon = tokio::time::timeout(LOGON_TIMEOUT, async {
+            loop {
+                if let Some(decoded) = decode(&mut buf) {
+                    match decoded {
+                        Ok(msg) => return Ok(Some(msg)),
+                        Err(reason) => warn!(%reason, "garbled FIX message ignored"),
+                    }
+                    continue;
+                }
+                anyhow::ensure!(buf.len() <= MAX_MESSAGE_LEN, "no complete message in {} bytes", buf.len());
+                match reader.read(&mut chunk).await? {
+                    0 => return Ok(None),
+                    n => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("no Logon within {}s", LOGON_TIMEOUT.as_secs()))??;
+        let Some(logon) = logon else {
+            return Ok(());
+        };
+        counter!("fix_messages_total", "direction" => "in", "msg_type" => type_name(logon.msg_type())).increment(1);
+        self.client = logon.get(tag::SENDER_COMP_ID).unwrap_or_default().to_string();
+        if let Err(reason) = self.check_logon(&logon) {
+            self.logout(&reason).await?;
+            anyhow::bail!("Logon refused: {reason}");
+        }
+        self.heartbeat = Duration::from_secs(logon.parse(tag::HEART_BT_INT).unwrap_or(30));
+        self.in_seq = 2;
+        let reply = Message::new(msg_type::LOGON)
+            .with(tag::ENCRYPT_METHOD, 0)
+            .with(tag::HEART_BT_INT, self.heartbeat.as_secs())
+            .with(tag::RESET_SEQ_NUM_FLAG, "Y");
+        self.send(reply).await?;
+        info!(client = %self.client, heartbeat_secs = self.heartbeat.as_secs(), "FIX session logged on");
+
+        let mut tick = tokio::time::interval(TICK);
+        loop {
+            tokio::select! {
+                read = reader.read(&mut chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        anyhow::bail!("connection closed without a Logout");
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    while let Some(decoded) = decode(&mut buf) {
+                        let msg = match decoded {
+                            Ok(msg) => msg,
+                            Err(reason) => {
+                                warn!(client = %self.client, %reason, "garbled FIX message ignored");
+                                continue;
+                            }
+                        };
+                        counter!("fix_messages_total", "direction" => "in", "msg_type" => type_name(msg.msg_type())).increment(1);
+                        self.last_received = Instant::now();
+                        self.test_request_sent = false;
+                        if !self.on_message(msg).await? {
+                            return Ok(());
+                        }
+                    }
+                    if buf.len() > MAX_MESSAGE_LEN {
+                        self.logout("message too long").await?;
+                        anyhow::bail!("no complete message in {} bytes", buf.len());
+                    }
+                }
+                Some(result) = replies.recv() => self.on_result(result).await?,
+                _ = tick.tick() => self.check_heartbeat().await?,
+            }
+        }
+    }
+
+    /// Why `logon` can't open a session, if it can't.
+    fn check_logon(&self, logon: &Message) -> Result<(), String> {
+        if logon.msg_type() != msg_type::LOGON {
+            return Err("first message must be a Logon".into());
+        }
+        if logon.get(tag::TARGET_COMP_ID) != Some(self.config.comp_id.as_str()) {
+            return Err(format!("TargetCompID must be {}", self.config.comp_id));
+        }
+        if !self.config.clients.contains(&self.client) {
+            return Err(format!("SenderCompID '{}' is not allowed", self.client));
+        }
+        if let Some(password) = &self.config.password
+            && logon.get(tag::PASSWORD) != Some(password.as_str())
+        {
+            return Err("bad or missing Password".into());
+        }
+        if logon.get(tag::ENCRYPT_METHOD) != Some("0") {
+            return Err("EncryptMethod must be 0".into());
+        }
+        if logon.parse::<u64>(tag::HEART_BT_INT).is_none_or(|secs| secs == 0) {
+            return Err("HeartBtInt must be a positive number of seconds".into());
+        }
+        if !logon.flag(tag::RESET_SEQ_NUM_FLAG) || logon.parse::<u64>(tag::MSG_SEQ_NUM) != Some(1) {
+            return Err("sequence numbers aren't kept across logons: send ResetSeqNumFlag=Y with MsgSeqNum=1".into());
+        }
+        Ok(())
+    }
+
+    /// Handle one message from the logged-on client. `false` ends the session.
+    async fn on_message(&mut self, msg: Message) -> anyhow::Result<bool> {
+        if msg.get(tag::SENDER_COMP_ID) != Some(self.client.as_str())
+            || msg.get(tag::TARGET_COMP_ID) != Some(self.config.comp_id.as_str())
+        {
+            self.reject(&msg, COMP_ID_PROBLEM, None, "CompID problem").await?;
+            self.logout("CompID problem").await?;
+            return Ok(false);
+        }
+        let Some(seq) = msg.parse::<u64>(tag::MSG_SEQ_NUM) else {
+            self.logout("MsgSeqNum missing").await?;
+            return Ok(false);
+        };
+
+        // SequenceReset in reset mode applies whatever its own number.
+        let reset_mode = msg.msg_type() == msg_type::SEQUENCE_RESET && !msg.flag(tag::GAP_FILL_FLAG);
+        if !reset_mode {
+            match seq.cmp(&self.in_seq) {
+                SeqOrder::Less if msg.flag(tag::POSS_DUP_FLAG) => return Ok(true),
+                SeqOrder::Less => {
+                    self.logout(&format!("MsgSeqNum too low, expecting {} but received {seq}", self.in_seq)).await?;
+                    return Ok(false);
+                }
+                SeqOrder::Greater if msg.msg_type() != msg_type::LOGOUT => {
+                    // Nothing past a gap is processed; the client resends it.
+                    if !self.resend_requested {
+                        self.resend_requested = true;
+                        let request = Message::new(msg_type::RESEND_REQUEST)
+                            .with(tag::BEGIN_SEQ_NO, self.in_seq)
+                            .with(tag::END_SEQ_NO, 0);
+                        self.send(request).await?;
+                    }
+                    return Ok(true);
+                }
+                _ => {
+                    self.in_seq = seq + 1;
+                    self.resend_requested = false;
+                }
+            }
+        }
+
+        match msg.msg_type() {
+            msg_type::HEARTBEAT => {}
+            msg_type::TEST_REQUEST => {
+                let mut heartbeat = Message::new(msg_type::HEARTBEAT);
+                if let Some(id) = msg.get(tag::TEST_REQ_ID) {
+                    heartbeat.push(tag::TEST_REQ_ID, id);
+                }
+                self.send(heartbeat).await?;
+            }
+            msg_type::RESEND_REQUEST => {
+                // No message store: everything asked for is gap-filled,
+                // since order state is re-sent only as it changes.
+                let begin = msg.parse::<u64>(tag::BEGIN_SEQ_NO).unwrap_or(1);
+                if begin < self.out_seq {
+                    let fill = Message::new(msg_type::SEQUENCE_RESET)
+                        .with(tag::POSS_DUP_FLAG, "Y")
+                        .with(tag::GAP_FILL_FLAG, "Y")
+                        .with(tag::NEW_SEQ_NO, self.out_seq);
+                    self.write(fill, begin).await?;
+                }
+            }
+            msg_type::REJECT => {
+                warn!(client = %self.client, ref_seq = msg.get(tag::REF_SEQ_NUM), text = msg.get(tag::TEXT), "FIX client rejected a message");
+            }
+            msg_type::SEQUENCE_RESET => match msg.parse::<u64>(tag::NEW_SEQ_NO) {
+                Some(next) if next >= self.in_seq => self.in_seq = next,
+                _ => self.reject(&msg, VALUE_INCORRECT, Some(tag::NEW_SEQ_NO), "NewSeqNo may not go backwards").await?,
+            },
+            msg_type::LOGOUT => {
+                self.send(Message::new(msg_type::LOGOUT)).await?;
+                return Ok(false);
+            }
+            msg_type::LOGON => self.reject(&msg, VALUE_INCORRECT, None, "already logged on").await?,
+            msg_type::NEW_ORDER_SINGLE => self.on_new_order(&msg).await?,
+            msg_type::ORDER_CANCEL_REQUEST => self.on_cancel(&msg).await?,
+            other => {
+                let reject = Message::new(msg_type::BUSINESS_MESSAGE_REJECT)
+                    .with(tag::REF_SEQ_NUM, seq)
+                    .with(tag::REF_MSG_TYPE, other)
+                    .with(tag::BUSINESS_REJECT_REASON, UNSUPPORTED_MESSAGE_TYPE)
+                    .with(tag::TEXT, "unsupported message type");
+                self.send(reject).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn on_new_order(&mut self, msg: &Message) -> anyhow::Result<()> {
+        let order = match parse_new_order(msg) {
+            Ok(order) => order,
+            Err(Rejection::Session { reason, tag }) => return self.reject(msg, reason, Some(tag), "invalid NewOrderSingle").await,
+            Err(Rejection::Order { reason, text }) => return self.reject_order(msg, reason, &text).await,
+        };
+        if self.orders.contains_key(&order.cl_ord_id) {
+            return self.reject_order(msg, DUPLICATE_ORDER, "duplicate ClOrdID").await;
+        }
+        let Some(market_id) = self.operator.market_of(&order.symbol).map(str::to_string) else {
+            return self.reject_order(msg, UNKNOWN_SYMBOL, "token is not tracked").await;
+        };
+
+        let reference = self.ids.fetch_add(1, Ordering::Relaxed);
+        let canceled = Arc::new(AtomicBool::new(false));
+        let reply = ReplyTo { reference, tx: self.replies_tx.clone(), canceled: Arc::clone(&canceled) };
+        let manual = ManualOrder {
+            venue: order.venue,
+            market_id,
+            token_id: order.symbol.clone(),
+            side: order.side.clone(),
+            price: order.price,
+            size: order.qty,
+        };
+        if let Err(e) = self.operator.route(manual, FIX_STRATEGY, reply).await {
+            return self.reject_order(msg, OTHER, &format!("{e:#}")).await;
+        }
+
+        debug!(client = %self.client, cl_ord_id = %order.cl_ord_id, reference, "FIX order queued");
+        let order = Order {
+            order_id: reference.to_string(),
+            cl_ord_id: order.cl_ord_id,
+            symbol: order.symbol,
+            side: order.side,
+            qty: order.qty,
+            price: order.price,
+            status: PENDING_NEW,
+            cum_qty: 0.0,
+            avg_px: 0.0,
+            canceled,
+            cancel: None,
+        };
+        let report = self.execution_report(&order, PENDING_NEW, None);
+        self.by_reference.insert(reference, order.cl_ord_id.clone());
+        self.orders.insert(order.cl_ord_id.clone(), order);
+        self.send(report).await
+    }
+
+    async fn on_cancel(&mut self, msg: &Message) -> anyhow::Result<()> {
+        let (Some(cl_ord_id), Some(orig)) = (msg.get(tag::CL_ORD_ID), msg.get(tag::ORIG_CL_ORD_ID)) else {
+            let missing = if msg.get(tag::CL_ORD_ID).is_none() { tag::CL_ORD_ID } else { tag::ORIG_CL_ORD_ID };
+            return self.reject(msg, REQUIRED_TAG_MISSING, Some(missing), "invalid OrderCancelRequest").await;
+        };
+        let refusal = match self.orders.get(orig) {
+            None => Some(("NONE".to_string(), REJECTED, UNKNOWN_ORDER, "unknown order")),
+            Some(order) if order.cancel.is_some() => {
+                Some((order.order_id.clone(), PENDING_CANCEL, ALREADY_PENDING_CANCEL, "cancel already pending"))
+            }
+            Some(order) if order.status != PENDING_NEW => {
+                Some((order.order_id.clone(), order.status, TOO_LATE_TO_CANCEL, "order is already done"))
+            }
+            Some(_) => None,
+        };
+        if let Some((order_id, status, reason, text)) = refusal {
+            return self.cancel_reject(&order_id, cl_ord_id, orig, status, reason, text).await;
+        }
+
+        let Some(order) = self.orders.get_mut(orig) else {
+            return Ok(());
+        };
+        order.canceled.store(true, Ordering::Release);
+        order.cancel = Some(cl_ord_id.to_string());
+        order.status = PENDING_CANCEL;
+        let report = self.execution_report(&self.orders[orig], PENDING_CANCEL, Some(cl_ord_id));
+        self.send(report).await
+    }
+
+    /// Report what became of an order once the execution bridge is done with it.
+    async fn on_result(&mut self, result: SignalResult) -> anyhow::Result<()> {
+        let Some(cl_ord_id) = self.by_reference.remove(&result.reference) else {
+            return Ok(());
+        };
+        let Some(order) = self.orders.get_mut(&cl_ord_id) else {
+            return Ok(());
+        };
+        let leg = result.report.as_ref().and_then(|report| report.leg_results.first());
+        let (exec_type, text, reason) = match (result.outcome, leg) {
+            (SignalOutcome::Canceled, _) => (CANCELED, None, None),
+            (_, Some(LegFillStatus::Filled { avg_price, filled_size, .. })) => {
+                order.cum_qty = *filled_size;
+                order.avg_px = *avg_price;
+                (TRADE, None, None)
+            }
+            (_, Some(LegFillStatus::Rejected { reason })) => (REJECTED, Some(reason.clone()), Some(OTHER)),
+            (SignalOutcome::Expired, _) => (EXPIRED, Some("expired before execution".to_string()), None),
+            (SignalOutcome::Blocked, _) => {
+                (REJECTED, Some(format!("blocked by risk check: {}", result.risk.name())), Some(EXCEEDS_LIMIT))
+            }
+            (outcome, _) => (REJECTED, Some(outcome.name().to_string()), Some(OTHER)),
+        };
+        order.status = match exec_type {
+            // Fill-or-kill: whatever didn't fill is gone.
+            TRADE if order.cum_qty >= as_f64(order.qty) => FILLED,
+            TRADE => CANCELED,
+            other => other,
+        };
+        let pending_cancel = order.cancel.take();
+
+        // A won cancel is reported against the cancel request.
+        let answers_cancel = pending_cancel.as_deref().filter(|_| exec_type == CANCELED);
+        let order = &self.orders[&cl_ord_id];
+        let mut report = self.execution_report(order, exec_type, answers_cancel);
+        if exec_type == TRADE {
+            report.push(tag::LAST_PX, order.avg_px);
+            report.push(tag::LAST_QTY, order.cum_qty);
+        }
+        if let Some(reason) = reason {
+            report.push(tag::ORD_REJ_REASON, reason);
+        }
+        if let Some(text) = text {
+            report.push(tag::TEXT, text);
+        }
+        let (order_id, status) = (order.order_id.clone(), order.status);
+        self.send(report).await?;
+        match pending_cancel {
+            Some(cancel) if exec_type != CANCELED => {
+                self.cancel_reject(&order_id, &cancel, &cl_ord_id, status, TOO_LATE_TO_CANCEL, "order executed before the cancel arrived")
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Heartbeat when we've been quiet, TestRequest when the client has,
+    /// and give up when it doesn't answer.
+    async fn check_heartbeat(&mut self) -> anyhow::Result<()> {
+        let silent = self.last_received.elapsed();
+        if self.test_request_sent && silent > self.heartbeat * 2 {
+            self.logout("heartbeat timeout").await?;
+            anyhow::bail!("no message for {}s", silent.as_secs());
+        }
+        if !self.test_request_sent && silent > self.heartbeat + self.heartbeat / 5 {
+            self.test_request_sent = true;
+            let id = self.ids.fetch_add(1, Ordering::Relaxed);
+            self.send(Message::new(msg_type::TEST_REQUEST).with(tag::TEST_REQ_ID, id)).await?;
+        }
+        if self.last_sent.elapsed() >= self.heartbeat {
+            self.send(Message::new(msg_type::HEARTBEAT)).await?;
+        }
+        Ok(())
+    }
+
+    /// Withdraw every order still waiting on the bridge (cancel on
+    /// disconnect). Returns how many there were.
+    fn withdraw_pending(&mut self) -> usize {
+        for cl_ord_id in self.by_reference.values() {
+            if let Some(order) = self.orders.get(cl_ord_id) {
+                order.canceled.store(true, Ordering::Release);
+            }
+        }
+        self.by_reference.len()
+    }
+
+    /// ExecutionReport of `order`'s current state. One answering a cancel
+    /// request carries that request's ClOrdID, and the order's as OrigClOrdID.
+    fn execution_report(&self, order: &Order, exec_type: &str, cancel: Option<&str>) -> Message {
+        let done = !matches!(order.status, PENDING_NEW | PENDING_CANCEL);
+        let leaves = if done { Decimal::ZERO } else { order.qty };
+        let mut report = Message::new(msg_type::EXECUTION_REPORT).with(tag::ORDER_ID, &order.order_id);
+        match cancel {
+            Some(cancel) => {
+                report.push(tag::CL_ORD_ID, cancel);
+                report.push(tag::ORIG_CL_ORD_ID, &order.cl_ord_id);
+            }
+            None => report.push(tag::CL_ORD_ID, &order.cl_ord_id),
+        }
+        report
+            .with(tag::EXEC_ID, self.ids.fetch_add(1, Ordering::Relaxed))
+            .with(tag::EXEC_TYPE, exec_type)
+            .with(tag::ORD_STATUS, order.status)
+            .with(tag::SYMBOL, &order.symbol)
+            .with(tag::SIDE, side_code(&order.side))
+            .with(tag::ORDER_QTY, order.qty)
+            .with(tag::ORD_TYPE, "2")
+            .with(tag::PRICE, order.price)
+            .with(tag::LEAVES_QTY, leaves)
+            .with(tag::CUM_QTY, order.cum_qty)
+            .with(tag::AVG_PX, order.avg_px)
+            .with(tag::TRANSACT_TIME, utc_timestamp())
+    }
+
+    /// A rejected ExecutionReport for a NewOrderSingle that never became an order.
+    async fn reject_order(&mut self, msg: &Message, reason: u32, text: &str) -> anyhow::Result<()> {
+        info!(client = %self.client, cl_ord_id = msg.get(tag::CL_ORD_ID), text, "FIX order rejected");
+        let mut report = Message::new(msg_type::EXECUTION_REPORT)
+            .with(tag::ORDER_ID, "NONE")
+            .with(tag::CL_ORD_ID, msg.get(tag::CL_ORD_ID).unwrap_or_default())
+            .with(tag::EXEC_ID, self.ids.fetch_add(1, Ordering::Relaxed))
+            .with(tag::EXEC_TYPE, REJECTED)
+            .with(tag::ORD_STATUS, REJECTED)
+            .with(tag::SYMBOL, msg.get(tag::SYMBOL).unwrap_or_default())
+            .with(tag::SIDE, msg.get(tag::SIDE).unwrap_or_default());
+        for tag in [tag::ORDER_QTY, tag::ORD_TYPE, tag::PRICE] {
+            if let Some(value) = msg.get(tag) {
+                report.push(tag, value);
+            }
+        }
+        let report = report
+            .with(tag::LEAVES_QTY, 0)
+            .with(tag::CUM_QTY, 0)
+            .with(tag::AVG_PX, 0)
+            .with(tag::ORD_REJ_REASON, reason)
+            .with(tag::TEXT, text)
+            .with(tag::TRANSACT_TIME, utc_timestamp());
+        self.send(report).await
+    }
+
+    async fn cancel_reject(
+        &mut self,
+        order_id: &str,
+        cl_ord_id: &str,
+        orig: &str,
+        status: &str,
+        reason: u32,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let reject = Message::new(msg_type::ORDER_CANCEL_REJECT)
+            .with(tag::ORDER_ID, order_id)
+            .with(tag::CL_ORD_ID, cl_ord_id)
+            .with(tag::ORIG_CL_ORD_ID, orig)
+            .with(tag::ORD_STATUS, status)
+            .with(tag::CXL_REJ_RESPONSE_TO, 1)
+            .with(tag::CXL_REJ_REASON, reason)
+            .with(tag::TEXT, text);
+        self.send(reject).await
+    }
+
+    /// Session-level Reject of `msg`.
+    async fn reject(&mut self, msg: &Message, reason: u32, ref_tag: Option<u32>, text: &str) -> anyhow::Result<()> {
+        let mut reject = Message::new(msg_type::REJECT)
+            .with(tag::REF_SEQ_NUM, msg.get(tag::MSG_SEQ_NUM).unwrap_or("0"))
+            .with(tag::REF_MSG_TYPE, msg.msg_type());
+        if let Some(ref_tag) = ref_tag {
+            reject.push(tag::REF_TAG_ID, ref_tag);
+        }
+        let reject = reject.with(tag::SESSION_REJECT_REASON, reason).with(tag::TEXT, text);
+        self.send(reject).await
+    }
+
+    async fn logout(&mut self, text: &str) -> anyhow::Result<()> {
+        self.send(Message::new(msg_type::LOGOUT).with(tag::TEXT, text)).await
+    }
+
+    async fn send(&mut self, msg: Message) -> anyhow::Result<()> {
+        let seq = self.out_seq;
+        self.out_seq += 1;
+        self.write(msg, seq).await
+    }
+
+    /// Send `msg` as `seq` without moving the outgoing sequence (gap fills).
+    async fn write(&mut self, mut msg: Message, seq: u64) -> anyhow::Result<()> {
+        msg.set_header(&self.config.comp_id, &self.client, seq, &utc_timestamp());
+        counter!("fix_messages_total", "direction" => "out", "msg_type" => type_name(msg.msg_type())).increment(1);
+        self.writer.write_all(&msg.encode()).await?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+}
+
+fn parse_new_order(msg: &Message) -> Result<NewOrder, Rejection> {
+    let required = |tag: u32| msg.get(tag).ok_or(Rejection::Session { reason: REQUIRED_TAG_MISSING, tag });
+    let cl_ord_id = required(tag::CL_ORD_ID)?.to_string();
+    let symbol = required(tag::SYMBOL)?.to_string();
+    let side = match required(tag::SIDE)? {
+        "1" => Side::Buy,
+        "2" => Side::Sell,
+        _ => return Err(Rejection::Session { reason: VALUE_INCORRECT, tag: tag::SIDE }),
+    };
+    let qty = required(tag::ORDER_QTY)?
+        .parse::<Decimal>()
+        .map_err(|_| Rejection::Session { reason: INCORRECT_DATA_FORMAT, tag: tag::ORDER_QTY })?;
+    if required(tag::ORD_TYPE)? != "2" {
+        return Err(Rejection::Order { reason: UNSUPPORTED_CHARACTERISTIC, text: "only limit orders (OrdType=2) are accepted".into() });
+    }
+    let price = required(tag::PRICE)?
+        .parse::<Decimal>()
+        .map_err(|_| Rejection::Session { reason: INCORRECT_DATA_FORMAT, tag: tag::PRICE })?;
+    // Orders are fill-or-kill at the venue; nothing rests.
+    if !matches!(msg.get(tag::TIME_IN_FORCE), None | Some("3") | Some("4")) {
+        return Err(Rejection::Order { reason: UNSUPPORTED_CHARACTERISTIC, text: "TimeInForce must be IOC (3) or FOK (4)".into() });
+    }
+    let venue = match msg.get(tag::EX_DESTINATION) {
+        Some(venue) => venue.parse().map_err(|e| Rejection::Order { reason: OTHER, text: format!("{e:#}") })?,
+        None => Venue::Polymarket,
+    };
+    Ok(NewOrder { cl_ord_id, symbol, side, qty, price, venue })
+}
+
+fn side_code(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "1",
+        Side::Sell => "2",
+    }
+}
+
+fn utc_timestamp() -> String {
+    chrono::Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+/// Metric label for a MsgType.
+fn type_name(msg_type: &str) -> &'static str {
+    match msg_type {
+        msg_type::HEARTBEAT => "heartbeat",
+        msg_type::TEST_REQUEST => "test_request",
+        msg_type::RESEND_REQUEST => "resend_request",
+        msg_type::REJECT => "reject",
+        msg_type::SEQUENCE_RESET => "sequence_reset",
+        msg_type::LOGOUT => "logout",
+        msg_type::EXECUTION_REPORT => "execution_report",
+        msg_type::ORDER_CANCEL_REJECT => "order_cancel_reject",
+        msg_type::LOGON => "logon",
+        msg_type::NEW_ORDER_SINGLE => "new_order_single",
+        msg_type::ORDER_CANCEL_REQUEST => "order_cancel_request",
+        msg_type::BUSINESS_MESSAGE_REJECT => "business_message_reject",
+        _ => "other",
+    }
+}
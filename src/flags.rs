@@ -0,0 +1,146 @@
+//! Runtime feature flags for deploying components dark and ramping them up.
+//!
+//! A flag is a rollout fraction in [0, 1]: 0 is off, 1 is on, and anything
+//! in between admits that share of markets, picked by a stable hash of flag
+//! and market so the same markets stay in as the fraction grows. Flags that
+//! are not defined admit everything, so gating a component costs nothing
+//! until someone sets its flag.
+//!
+//! Flag names the engine consults:
+//! - `strategy.<name>` — whether a strategy is evaluated for a market.
+//! - `venue.<venue>` — whether signals for the venue may execute.
+//! - `execution.<mode>` — whether signals may execute in `live`, `dry_run`
+//!   or `paper` mode.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::market_data::types::Venue;
+
+/// Configured flags plus operator overrides. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct FeatureFlags {
+    inner: Arc<RwLock<Flags>>,
+}
+
+#[derive(Default)]
+struct Flags {
+    configured: BTreeMap<String, f64>,
+    /// Set through the admin API; win over `configured` and survive reloads.
+    overrides: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagStatus {
+    pub name: String,
+    /// Value in effect.
+    pub rollout: f64,
+    pub configured: Option<f64>,
+    pub overridden: bool,
+}
+
+impl FeatureFlags {
+    pub fn new(configured: BTreeMap<String, f64>) -> Self {
+        Self { inner: Arc::new(RwLock::new(Flags { configured, overrides: BTreeMap::new() })) }
+    }
+
+    /// Rollout in effect for `name`, if the flag is defined.
+    pub fn rollout(&self, name: &str) -> Option<f64> {
+        let flags = self.inner.read().unwrap();
+        flags.overrides.get(name).or_else(|| flags.configured.get(name)).copied()
+    }
+
+    /// Whether flag `name` admits `key` (a market id). Undefined flags admit everything.
+    pub fn allows(&self, name: &str, key: &str) -> bool {
+        match self.rollout(name) {
+            None => true,
+            Some(rollout) if rollout >= 1.0 => true,
+            Some(rollout) if rollout <= 0.0 => false,
+            Some(rollout) => bucket(name, key) < rollout,
+        }
+    }
+
+    /// No flags defined — callers can skip building flag names on the hot path.
+    pub fn is_empty(&self) -> bool {
+        let flags = self.inner.read().unwrap();
+        flags.configured.is_empty() && flags.overrides.is_empty()
+    }
+
+    /// Replace the configured flags (config reload). Overrides are kept.
+    pub fn set_configured(&self, configured: BTreeMap<String, f64>) {
+        info!(flags = ?configured, "feature flags reloaded");
+        self.inner.write().unwrap().configured = configured;
+    }
+
+    /// Override one flag, or drop the override with `None` so the configured
+    /// value (if any) applies again.
+    pub fn set_override(&self, name: &str, rollout: Option<f64>) {
+        let mut flags = self.inner.write().unwrap();
+        match rollout {
+            Some(rollout) => {
+                flags.overrides.insert(name.to_string(), rollout.clamp(0.0, 1.0));
+            }
+            None => {
+                flags.overrides.remove(name);
+            }
+        }
+        info!(flag = name, ?rollout, "feature flag overridden");
+    }
+
+    /// Every defined flag, by name.
+    pub fn statuses(&self) -> Vec<FlagStatus> {
+        let flags = self.inner.read().unwrap();
+        let mut names: Vec<&String> = flags.configured.keys().chain(flags.overrides.keys()).collect();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| FlagStatus {
+                name: name.clone(),
+                rollout: flags.overrides.get(name).or_else(|| flags.configured.get(name)).copied().unwrap_or(1.0),
+                configured: flags.configured.get(name).copied(),
+                overridden: flags.overrides.contains_key(name),
+            })
+            .collect()
+    }
+}
+
+/// `on` / `off` / `true` / `false`, a fraction like `0.25`, or a percentage like `25%`.
+pub fn parse_rollout(raw: &str) -> anyhow::Result<f64> {
+    let raw = raw.trim();
+    let rollout = match raw.to_ascii_lowercase().as_str() {
+        "on" | "true" => 1.0,
+        "off" | "false" => 0.0,
+        value => match value.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f64>().map(|p| p / 100.0),
+            None => value.parse::<f64>(),
+        }
+        .map_err(|_| anyhow::anyhow!("'{raw}' is not on, off, a fraction, or a percentage"))?,
+    };
+    anyhow::ensure!((0.0..=1.0).contains(&rollout), "rollout '{raw}' must be between 0 and 1 (0% and 100%)");
+    Ok(rollout)
+}
+
+pub fn strategy_flag(name: &str) -> String {
+    format!("strategy.{name}")
+}
+
+pub fn venue_flag(venue: &Venue) -> String {
+    format!("venue.{}", format!("{venue:?}").to_lowercase())
+}
+
+pub fn execution_flag(mode: &str) -> String {
+    format!("execution.{mode}")
+}
+
+/// Stable position of `key` in [0, 1) for flag `name`.
+fn bucket(name: &str, key: &str) -> f64 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(name.as_bytes());
+    hasher.update(b"/");
+    hasher.update(key.as_bytes());
+    f64::from(hasher.finalize()) / (f64::from(u32::MAX) + 1.0)
+}
@@ -0,0 +1,279 @@
+//! Engine types → protobuf messages, and the few requests that go the other way.
+
+use super::proto;
+use crate::execution::operator::{FlattenReport, ManualOrder};
+use crate::flags::FlagStatus;
+use crate::health::{HealthReport, Status};
+use crate::market_data::types::{Side, Venue};
+use crate::persist::records::{FillRecord, SignalRecord};
+use crate::state::equity::{DailyReturn, Drawdown, EquitySample};
+use crate::state::fees::{Liquidity, StrategyPnl};
+use crate::state::market::{as_f64, to_decimal};
+use crate::state::pnl::{LedgerEntry, PnlSummary};
+use crate::state::portfolio::{Balances, OpenOrder, OpenOrderLeg, PortfolioSnapshot, PositionSnapshot};
+use crate::state::position::CostBasisMethod;
+use crate::strategy::audit::{Decision, DecisionOutcome};
+use crate::strategy::traits::SignalLeg;
+
+fn venue(venue: &Venue) -> i32 {
+    match venue {
+        Venue::Polymarket => proto::Venue::Polymarket as i32,
+        Venue::Kalshi => proto::Venue::Kalshi as i32,
+        Venue::Betfair => proto::Venue::Betfair as i32,
+        Venue::Rest => proto::Venue::Rest as i32,
+        Venue::Amm => proto::Venue::Amm as i32,
+    }
+}
+
+fn side(side: &Side) -> i32 {
+    match side {
+        Side::Buy => proto::Side::Buy as i32,
+        Side::Sell => proto::Side::Sell as i32,
+    }
+}
+
+fn liquidity(liquidity: Liquidity) -> i32 {
+    match liquidity {
+        Liquidity::Maker => proto::Liquidity::Maker as i32,
+        Liquidity::Taker => proto::Liquidity::Taker as i32,
+    }
+}
+
+impl From<PositionSnapshot> for proto::Position {
+    fn from(p: PositionSnapshot) -> Self {
+        Self {
+            venue: venue(&p.venue),
+            token_id: p.token_id,
+            market_id: p.market_id,
+            size: p.size,
+            avg_price: p.avg_price,
+            mark_price: p.mark_price,
+            realized_pnl: p.realized_pnl,
+            unrealized_pnl: p.unrealized_pnl,
+            exposure: p.exposure,
+        }
+    }
+}
+
+impl From<OpenOrderLeg> for proto::OrderLeg {
+    fn from(leg: OpenOrderLeg) -> Self {
+        Self { token_id: leg.token_id.to_string(), side: side(&leg.side), price: leg.price, size: leg.size }
+    }
+}
+
+impl From<SignalLeg> for proto::OrderLeg {
+    fn from(leg: SignalLeg) -> Self {
+        Self { token_id: leg.token_id.to_string(), side: side(&leg.side), price: as_f64(leg.price), size: as_f64(leg.size) }
+    }
+}
+
+impl From<OpenOrder> for proto::OpenOrder {
+    fn from(o: OpenOrder) -> Self {
+        Self {
+            id: o.id,
+            venue: venue(&o.venue),
+            market_id: o.market_id.to_string(),
+            strategy: o.strategy,
+            legs: o.legs.into_iter().map(Into::into).collect(),
+            submitted_at_ms: o.submitted_at_ms,
+        }
+    }
+}
+
+impl From<Balances> for proto::Balances {
+    fn from(b: Balances) -> Self {
+        Self { starting_cash: b.starting_cash, cash: b.cash, equity: b.equity }
+    }
+}
+
+impl From<PnlSummary> for proto::PnlSummary {
+    fn from(p: PnlSummary) -> Self {
+        Self {
+            realized: p.realized,
+            unrealized: p.unrealized,
+            taker_fees: p.taker_fees,
+            maker_rebates: p.maker_rebates,
+            rewards_estimate: p.rewards_estimate,
+            total: p.total,
+        }
+    }
+}
+
+impl From<StrategyPnl> for proto::StrategyPnl {
+    fn from(p: StrategyPnl) -> Self {
+        Self {
+            volume: p.volume,
+            realized_gross: p.realized_gross,
+            taker_fees: p.taker_fees,
+            maker_rebates: p.maker_rebates,
+            rewards_estimate: p.rewards_estimate,
+            realized_net: p.realized_net,
+        }
+    }
+}
+
+impl From<PortfolioSnapshot> for proto::Portfolio {
+    fn from(s: PortfolioSnapshot) -> Self {
+        Self {
+            taken_at_ms: s.taken_at_ms,
+            positions: s.positions.into_iter().map(Into::into).collect(),
+            open_orders: s.open_orders.into_iter().map(Into::into).collect(),
+            balances: Some(s.balances.into()),
+            pnl: Some(s.pnl.into()),
+            pnl_by_strategy: s.pnl_by_strategy.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            exposure_by_venue: s.exposure_by_venue,
+            exposure_by_group: s.exposure_by_group,
+        }
+    }
+}
+
+impl From<LedgerEntry> for proto::LedgerEntry {
+    fn from(e: LedgerEntry) -> Self {
+        let method = match e.method {
+            CostBasisMethod::Fifo => "fifo",
+            CostBasisMethod::AverageCost => "average_cost",
+        };
+        Self {
+            venue: venue(&e.venue),
+            token_id: e.token_id,
+            method: method.to_string(),
+            size: e.close.size,
+            open_price: e.close.open_price,
+            close_price: e.close.close_price,
+            opened_at_ms: e.close.opened_at_ms,
+            closed_at_ms: e.close.closed_at_ms,
+            direction: e.close.direction,
+            realized_pnl: e.close.realized_pnl,
+        }
+    }
+}
+
+impl From<EquitySample> for proto::EquitySample {
+    fn from(s: EquitySample) -> Self {
+        Self { ts_ms: s.ts_ms, equity: s.equity, cash: s.cash }
+    }
+}
+
+impl From<Drawdown> for proto::Drawdown {
+    fn from(d: Drawdown) -> Self {
+        Self { peak: d.peak, trough: d.trough, absolute: d.absolute, fraction: d.fraction }
+    }
+}
+
+impl From<DailyReturn> for proto::DailyReturn {
+    fn from(r: DailyReturn) -> Self {
+        Self { day: r.day, close_equity: r.close_equity, return_pct: r.return_pct }
+    }
+}
+
+impl From<HealthReport> for proto::HealthReport {
+    fn from(r: HealthReport) -> Self {
+        Self {
+            ready: r.ready,
+            components: r
+                .components
+                .into_iter()
+                .map(|(name, c)| {
+                    let health = proto::ComponentHealth {
+                        up: c.status == Status::Up,
+                        detail: c.detail,
+                        since_ms: c.since_ms,
+                    };
+                    (name, health)
+                })
+                .collect(),
+            channels: r
+                .channels
+                .into_iter()
+                .map(|c| proto::ChannelHealth { name: c.name.to_string(), open: c.open })
+                .collect(),
+            cache_age_ms: r.cache_age_ms,
+            cache_stale_after_ms: r.cache_stale_after_ms,
+        }
+    }
+}
+
+impl From<Decision> for proto::Decision {
+    fn from(d: Decision) -> Self {
+        let outcome = match d.outcome {
+            DecisionOutcome::Signal { edge } => proto::decision::Outcome::SignalEdge(edge),
+            DecisionOutcome::Rejected { reason } => proto::decision::Outcome::RejectedReason(reason.to_string()),
+        };
+        Self {
+            ts_ms: d.ts_ms,
+            strategy: d.strategy.to_string(),
+            market_id: d.market_id,
+            token_id: d.token_id,
+            values: d.values.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            outcome: Some(outcome),
+        }
+    }
+}
+
+impl From<SignalRecord> for proto::Signal {
+    fn from(s: SignalRecord) -> Self {
+        Self {
+            signal_id: s.signal_id,
+            ts_ms: s.ts_ms,
+            strategy: s.strategy.to_string(),
+            venue: venue(&s.venue),
+            market_id: s.market_id.to_string(),
+            edge: s.edge,
+            liquidity: liquidity(s.liquidity),
+            legs: s.legs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<FillRecord> for proto::Fill {
+    fn from(f: FillRecord) -> Self {
+        Self {
+            signal_id: f.signal_id,
+            ts_ms: f.ts_ms,
+            strategy: f.strategy.to_string(),
+            venue: venue(&f.venue),
+            market_id: f.market_id.to_string(),
+            token_id: f.token_id.to_string(),
+            order_id: f.order_id,
+            side: side(&f.side),
+            price: f.price,
+            size: f.size,
+            liquidity: liquidity(f.liquidity),
+        }
+    }
+}
+
+impl From<FlagStatus> for proto::FlagStatus {
+    fn from(f: FlagStatus) -> Self {
+        Self { name: f.name, rollout: f.rollout, configured: f.configured, overridden: f.overridden }
+    }
+}
+
+impl From<FlattenReport> for proto::FlattenReport {
+    fn from(r: FlattenReport) -> Self {
+        Self { legs: r.legs.into_iter().map(Into::into).collect(), unpriced: r.unpriced }
+    }
+}
+
+impl TryFrom<proto::ManualOrder> for ManualOrder {
+    type Error = String;
+
+    fn try_from(o: proto::ManualOrder) -> Result<Self, String> {
+        let venue = match proto::Venue::try_from(o.venue) {
+            Ok(proto::Venue::Kalshi) => Venue::Kalshi,
+            Ok(proto::Venue::Betfair) => Venue::Betfair,
+            Ok(proto::Venue::Rest) => Venue::Rest,
+            Ok(proto::Venue::Amm) => Venue::Amm,
+            Ok(proto::Venue::Polymarket | proto::Venue::Unspecified) => Venue::Polymarket,
+            Err(_) => return Err(format!("unknown venue {}", o.venue)),
+        };
+        let side = match proto::Side::try_from(o.side) {
+            Ok(proto::Side::Buy) => Side::Buy,
+            Ok(proto::Side::Sell) => Side::Sell,
+            _ => return Err("side must be BUY or SELL".to_string()),
+        };
+        let price = to_decimal(o.price).map_err(|e| e.to_string())?;
+        let size = to_decimal(o.size).map_err(|e| e.to_string())?;
+        Ok(Self { venue, market_id: o.market_id, token_id: o.token_id, side, price, size })
+    }
+}
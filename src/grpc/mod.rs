@@ -0,0 +1,354 @@
+//! gRPC twin of the admin API (`proto/engine.proto`), plus server streams of
+//! signals, fills, and PnL for supervisors and UIs that want typed access
+//! instead of polling REST or scraping logs.
+//!
+//! Signals and fills come from a [`crate::persist::Recorder`] sink fanned out
+//! over a broadcast channel; a subscriber that falls behind skips the records
+//! it missed rather than slowing the engine down.
+//!
+//! Like the admin API, reads are open and calls that change something need
+//! `authorization: Bearer <ADMIN_TOKEN>`; clients attach it with [`bearer`].
+
+mod convert;
+
+pub mod proto {
+    tonic::include_proto!("prediction_engine.v1");
+}
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::time::Duration;
+
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::metadata::errors::InvalidMetadataValue;
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use crate::admin::AdminState;
+use crate::execution::operator::ManualOrder;
+use crate::persist::PersistRecord;
+use crate::webhook::constant_time_eq;
+use proto::engine_server::{Engine, EngineServer};
+
+/// Records buffered per stream subscriber before it starts skipping.
+pub const STREAM_BUFFER: usize = 1024;
+const DEFAULT_PNL_INTERVAL: Duration = Duration::from_secs(1);
+
+type RpcStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Create the broadcast side of the record fan-out. Pass the sender to
+/// [`run_record_fanout`] and [`run_grpc_server`].
+pub fn record_channel() -> broadcast::Sender<PersistRecord> {
+    broadcast::channel(STREAM_BUFFER).0
+}
+
+/// Forward signals and fills from a recorder sink to stream subscribers.
+/// Other record kinds aren't streamed and are dropped here.
+pub async fn run_record_fanout(mut rx: mpsc::Receiver<PersistRecord>, tx: broadcast::Sender<PersistRecord>) {
+    while let Some(record) = rx.recv().await {
+        if matches!(record, PersistRecord::Signal(_) | PersistRecord::Fill(_)) {
+            // Err only means nobody is subscribed right now.
+            let _ = tx.send(record);
+        }
+    }
+}
+
+/// Serve the gRPC API until the listener fails.
+///
+/// Calls that change something need `authorization: Bearer <token>` with
+/// the state's `token`: UNAUTHENTICATED for a missing or wrong token,
+/// PERMISSION_DENIED when no token is configured.
+pub async fn run_grpc_server(
+    addr: SocketAddr,
+    state: AdminState,
+    records: broadcast::Sender<PersistRecord>,
+) -> anyhow::Result<()> {
+    info!(%addr, "gRPC API listening");
+    let check = TokenCheck { token: state.token.clone() };
+    tonic::transport::Server::builder()
+        .add_service(EngineServer::with_interceptor(EngineService { state, records }, check))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+/// Send `token` as `authorization: Bearer <token>` on every call, e.g.
+/// `EngineClient::with_interceptor(channel, bearer(&token)?)`.
+#[allow(clippy::result_large_err)] // tonic's `Status`, as the interceptor returns
+pub fn bearer(token: &str) -> Result<impl Interceptor + Clone, InvalidMetadataValue> {
+    let value: MetadataValue<_> = format!("Bearer {token}").parse()?;
+    Ok(move |mut request: Request<()>| {
+        request.metadata_mut().insert("authorization", value.clone());
+        Ok(request)
+    })
+}
+
+/// What a caller may do, decided by [`TokenCheck`] from the token it presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    /// Presented the configured token.
+    Operator,
+    /// No token, or the wrong one.
+    ReadOnly,
+    /// The engine has no token, so nobody may change anything.
+    Disabled,
+}
+
+/// Tags each request with its caller's [`Access`]; the calls that change
+/// something check it with [`require_operator`].
+#[derive(Clone)]
+struct TokenCheck {
+    token: Option<String>,
+}
+
+impl Interceptor for TokenCheck {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let access = match &self.token {
+            None => Access::Disabled,
+            Some(token) => {
+                let presented = request
+                    .metadata()
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.strip_prefix("Bearer "));
+                if presented.is_some_and(|presented| constant_time_eq(presented.as_bytes(), token.as_bytes())) {
+                    Access::Operator
+                } else {
+                    Access::ReadOnly
+                }
+            }
+        };
+        request.extensions_mut().insert(access);
+        Ok(request)
+    }
+}
+
+/// Refuse `rpc` unless its caller presented the configured token.
+#[allow(clippy::result_large_err)] // tonic's `Status`, as the handlers return
+fn require_operator<T>(request: &Request<T>, rpc: &'static str) -> Result<(), Status> {
+    match request.extensions().get::<Access>() {
+        Some(Access::Operator) => Ok(()),
+        Some(Access::Disabled) => {
+            Err(Status::permission_denied("operator calls are disabled; set ADMIN_TOKEN to enable them"))
+        }
+        Some(Access::ReadOnly) | None => {
+            warn!(rpc, "gRPC request refused: missing or invalid token");
+            Err(Status::unauthenticated("missing or invalid token"))
+        }
+    }
+}
+
+struct EngineService {
+    state: AdminState,
+    records: broadcast::Sender<PersistRecord>,
+}
+
+impl EngineService {
+    fn strategies(&self) -> proto::Strategies {
+        proto::Strategies {
+            paused: self.state.pause.is_paused(),
+            strategies: self
+                .state
+                .strategies
+                .statuses()
+                .into_iter()
+                .map(|(name, paused)| proto::StrategyStatus { name: name.to_string(), paused })
+                .collect(),
+        }
+    }
+
+    fn flags(&self) -> proto::Flags {
+        proto::Flags { flags: self.state.flags.statuses().into_iter().map(Into::into).collect() }
+    }
+
+    /// Records of one kind from subscription on, skipping any the client lagged past.
+    fn subscribe<T: Send + 'static>(
+        &self,
+        stream: &'static str,
+        pick: fn(PersistRecord) -> Option<T>,
+    ) -> RpcStream<T> {
+        let records = BroadcastStream::new(self.records.subscribe()).filter_map(move |record| match record {
+            Ok(record) => pick(record).map(Ok),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                warn!(stream, skipped, "gRPC subscriber fell behind; records skipped");
+                None
+            }
+        });
+        Box::pin(records)
+    }
+}
+
+#[tonic::async_trait]
+impl Engine for EngineService {
+    async fn get_portfolio(&self, _: Request<proto::Empty>) -> Result<Response<proto::Portfolio>, Status> {
+        Ok(Response::new(self.state.portfolio.snapshot().into()))
+    }
+
+    async fn get_ledger(&self, _: Request<proto::Empty>) -> Result<Response<proto::Ledger>, Status> {
+        let entries = self.state.portfolio.positions().ledger().entries();
+        Ok(Response::new(proto::Ledger { entries: entries.into_iter().map(Into::into).collect() }))
+    }
+
+    async fn get_equity(&self, _: Request<proto::Empty>) -> Result<Response<proto::EquityReport>, Status> {
+        let equity = &self.state.equity;
+        Ok(Response::new(proto::EquityReport {
+            samples: equity.samples().into_iter().map(Into::into).collect(),
+            max_drawdown: Some(equity.max_drawdown().into()),
+            current_drawdown: Some(equity.current_drawdown().into()),
+            daily_returns: equity.daily_returns().into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_health(&self, _: Request<proto::Empty>) -> Result<Response<proto::HealthReport>, Status> {
+        Ok(Response::new(self.state.health.report().into()))
+    }
+
+    async fn list_audit_markets(&self, _: Request<proto::Empty>) -> Result<Response<proto::AuditMarkets>, Status> {
+        Ok(Response::new(proto::AuditMarkets { market_ids: self.state.audit.watched() }))
+    }
+
+    async fn get_audit(&self, request: Request<proto::MarketRequest>) -> Result<Response<proto::Decisions>, Status> {
+        let market_id = request.into_inner().market_id;
+        let decisions = self
+            .state
+            .audit
+            .decisions(&market_id)
+            .ok_or_else(|| Status::not_found(format!("market {market_id} is not audited")))?;
+        Ok(Response::new(proto::Decisions { decisions: decisions.into_iter().map(Into::into).collect() }))
+    }
+
+    async fn watch_audit(&self, request: Request<proto::MarketRequest>) -> Result<Response<proto::Empty>, Status> {
+        require_operator(&request, "WatchAudit")?;
+        let market_id = request.into_inner().market_id;
+        if !self.state.audit.watch(&market_id) {
+            return Err(Status::unavailable("decision audit is disabled"));
+        }
+        info!(%market_id, "decision audit enabled via gRPC");
+        Ok(Response::new(proto::Empty {}))
+    }
+
+    async fn unwatch_audit(&self, request: Request<proto::MarketRequest>) -> Result<Response<proto::Empty>, Status> {
+        require_operator(&request, "UnwatchAudit")?;
+        let market_id = request.into_inner().market_id;
+        self.state.audit.unwatch(&market_id);
+        info!(%market_id, "decision audit disabled via gRPC");
+        Ok(Response::new(proto::Empty {}))
+    }
+
+    async fn get_log_level(&self, _: Request<proto::Empty>) -> Result<Response<proto::LogLevel>, Status> {
+        Ok(Response::new(proto::LogLevel { filter: self.state.log_filter.current(), previous: None }))
+    }
+
+    async fn set_log_level(
+        &self,
+        request: Request<proto::SetLogLevelRequest>,
+    ) -> Result<Response<proto::LogLevel>, Status> {
+        require_operator(&request, "SetLogLevel")?;
+        let previous = self
+            .state
+            .log_filter
+            .set(&request.into_inner().filter)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let filter = self.state.log_filter.current();
+        info!(%previous, %filter, "log filter changed via gRPC");
+        Ok(Response::new(proto::LogLevel { filter, previous: Some(previous) }))
+    }
+
+    async fn list_strategies(&self, _: Request<proto::Empty>) -> Result<Response<proto::Strategies>, Status> {
+        Ok(Response::new(self.strategies()))
+    }
+
+    async fn set_paused(&self, request: Request<proto::SetPausedRequest>) -> Result<Response<proto::Strategies>, Status> {
+        require_operator(&request, "SetPaused")?;
+        let request = request.into_inner();
+        if request.strategy.is_empty() {
+            self.state.pause.set_paused(request.paused);
+        } else if !self.state.strategies.set_paused(&request.strategy, request.paused) {
+            return Err(Status::not_found(format!("no running strategy named {}", request.strategy)));
+        }
+        Ok(Response::new(self.strategies()))
+    }
+
+    async fn list_flags(&self, _: Request<proto::Empty>) -> Result<Response<proto::Flags>, Status> {
+        Ok(Response::new(self.flags()))
+    }
+
+    async fn set_flag(&self, request: Request<proto::SetFlagRequest>) -> Result<Response<proto::Flags>, Status> {
+        require_operator(&request, "SetFlag")?;
+        let request = request.into_inner();
+        if let Some(rollout) = request.rollout.filter(|r| !(0.0..=1.0).contains(r)) {
+            return Err(Status::invalid_argument(format!("rollout {rollout} must be between 0 and 1")));
+        }
+        self.state.flags.set_override(&request.name, request.rollout);
+        Ok(Response::new(self.flags()))
+    }
+
+    async fn flatten(&self, request: Request<proto::MarketRequest>) -> Result<Response<proto::FlattenReport>, Status> {
+        require_operator(&request, "Flatten")?;
+        let market_id = request.into_inner().market_id;
+        info!(%market_id, "flatten requested via gRPC");
+        let report = self.state.operator.flatten(&market_id).await.map_err(|e| Status::unavailable(e.to_string()))?;
+        Ok(Response::new(report.into()))
+    }
+
+    async fn submit_order(&self, request: Request<proto::ManualOrder>) -> Result<Response<proto::Empty>, Status> {
+        require_operator(&request, "SubmitOrder")?;
+        let order = ManualOrder::try_from(request.into_inner()).map_err(Status::invalid_argument)?;
+        self.state.operator.check(&order).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        self.state.operator.submit(order).await.map_err(|e| Status::unavailable(e.to_string()))?;
+        Ok(Response::new(proto::Empty {}))
+    }
+
+    async fn cancel_all(&self, request: Request<proto::Empty>) -> Result<Response<proto::CancelAllReport>, Status> {
+        require_operator(&request, "CancelAll")?;
+        info!("cancel-all requested via gRPC");
+        let canceled = self.state.operator.cancel_all().await.map_err(|e| Status::unavailable(format!("{e:#}")))?;
+        Ok(Response::new(proto::CancelAllReport { canceled: canceled as u64 }))
+    }
+
+    type StreamSignalsStream = RpcStream<proto::Signal>;
+
+    async fn stream_signals(&self, _: Request<proto::Empty>) -> Result<Response<Self::StreamSignalsStream>, Status> {
+        Ok(Response::new(self.subscribe("signals", |record| match record {
+            PersistRecord::Signal(signal) => Some(signal.into()),
+            _ => None,
+        })))
+    }
+
+    type StreamFillsStream = RpcStream<proto::Fill>;
+
+    async fn stream_fills(&self, _: Request<proto::Empty>) -> Result<Response<Self::StreamFillsStream>, Status> {
+        Ok(Response::new(self.subscribe("fills", |record| match record {
+            PersistRecord::Fill(fill) => Some(fill.into()),
+            _ => None,
+        })))
+    }
+
+    type StreamPnlStream = RpcStream<proto::PnlUpdate>;
+
+    async fn stream_pnl(
+        &self,
+        request: Request<proto::StreamPnlRequest>,
+    ) -> Result<Response<Self::StreamPnlStream>, Status> {
+        let interval = match request.into_inner().interval_ms {
+            0 => DEFAULT_PNL_INTERVAL,
+            ms => Duration::from_millis(ms),
+        };
+        let portfolio = self.state.portfolio.clone();
+        let updates = IntervalStream::new(tokio::time::interval(interval)).map(move |_| {
+            let snapshot = portfolio.snapshot();
+            Ok(proto::PnlUpdate {
+                ts_ms: snapshot.taken_at_ms,
+                pnl: Some(snapshot.pnl.into()),
+                balances: Some(snapshot.balances.into()),
+                pnl_by_strategy: snapshot.pnl_by_strategy.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            })
+        });
+        Ok(Response::new(Box::pin(updates)))
+    }
+}
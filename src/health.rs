@@ -0,0 +1,163 @@
+//! Component health for the admin `/healthz` and `/readyz` endpoints.
+//!
+//! Long-running tasks report their own status here (adapters on connect and
+//! disconnect, the execution bridge on start and exit); channel liveness is
+//! probed without holding a sender so the registry never keeps a channel open.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::channel::{OpenProbe, Watch};
+use crate::notify::{Notifier, NotifyEvent};
+use crate::state::market_cache::MarketCache;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: Status,
+    pub detail: Option<String>,
+    /// Unix ms of the last status change.
+    pub since_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelHealth {
+    pub name: &'static str,
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub ready: bool,
+    /// `adapter.<venue>` and `executor` entries.
+    pub components: BTreeMap<String, ComponentHealth>,
+    pub channels: Vec<ChannelHealth>,
+    /// Time since the market cache last took an update; `None` before the first one.
+    pub cache_age_ms: Option<u64>,
+    pub cache_stale_after_ms: u64,
+}
+
+/// Shared registry — cheap to clone.
+#[derive(Clone)]
+pub struct HealthRegistry {
+    components: Arc<DashMap<String, ComponentHealth>>,
+    channels: Arc<std::sync::Mutex<Vec<(&'static str, OpenProbe)>>>,
+    cache: MarketCache,
+    stale_after: Duration,
+    notifier: Notifier,
+    /// Unix ms of the most recent fill, 0 before the first.
+    last_fill_ms: Arc<AtomicU64>,
+}
+
+impl HealthRegistry {
+    pub fn new(cache: MarketCache, stale_after: Duration) -> Self {
+        Self {
+            components: Arc::new(DashMap::new()),
+            channels: Arc::new(std::sync::Mutex::new(Vec::new())),
+            cache,
+            stale_after,
+            notifier: Notifier::disabled(),
+            last_fill_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Send a [`NotifyEvent::Disconnect`] whenever a component goes from up to down.
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Register a component as down until it reports in, so a task that never
+    /// starts keeps the engine unready.
+    pub fn register(&self, component: &str) {
+        self.components
+            .entry(component.to_string())
+            .or_insert_with(|| ComponentHealth { status: Status::Down, detail: Some("not started".into()), since_ms: now_ms() });
+    }
+
+    pub fn set_up(&self, component: &str) {
+        self.set(component, Status::Up, None);
+    }
+
+    pub fn set_down(&self, component: &str, detail: impl Into<String>) {
+        self.set(component, Status::Down, Some(detail.into()));
+    }
+
+    fn set(&self, component: &str, status: Status, detail: Option<String>) {
+        let mut entry = self.components.entry(component.to_string()).or_insert_with(|| ComponentHealth {
+            status,
+            detail: None,
+            since_ms: now_ms(),
+        });
+        if entry.status != status {
+            entry.since_ms = now_ms();
+            if status == Status::Down {
+                self.notifier.notify(NotifyEvent::Disconnect {
+                    component: component.to_string(),
+                    detail: detail.clone().unwrap_or_default(),
+                });
+            }
+        }
+        entry.status = status;
+        entry.detail = detail;
+    }
+
+    pub fn mark_fill(&self) {
+        self.last_fill_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    /// Unix ms of the most recent fill this session.
+    pub fn last_fill_ms(&self) -> Option<u64> {
+        Some(self.last_fill_ms.load(Ordering::Relaxed)).filter(|&ms| ms > 0)
+    }
+
+    /// Watch a channel. It counts as dead once its receiver is dropped or
+    /// every strong sender is gone.
+    pub fn watch_channel(&self, name: &'static str, tx: &impl Watch) {
+        self.channels.lock().expect("health channel list poisoned").push((name, tx.open_probe()));
+    }
+
+    /// Current status of everything registered, plus the readiness verdict:
+    /// every component up, every channel open, and the cache fresh.
+    pub fn report(&self) -> HealthReport {
+        let components: BTreeMap<String, ComponentHealth> =
+            self.components.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+
+        let channels: Vec<ChannelHealth> = self
+            .channels
+            .lock()
+            .expect("health channel list poisoned")
+            .iter()
+            .map(|(name, probe)| ChannelHealth { name, open: probe() })
+            .collect();
+
+        let cache_age = self.cache.since_last_update();
+        let fresh = cache_age.is_some_and(|age| age <= self.stale_after);
+
+        let ready = fresh
+            && components.values().all(|c| c.status == Status::Up)
+            && channels.iter().all(|c| c.open);
+
+        HealthReport {
+            ready,
+            components,
+            channels,
+            cache_age_ms: cache_age.map(|d| d.as_millis() as u64),
+            cache_stale_after_ms: self.stale_after.as_millis() as u64,
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
@@ -0,0 +1,99 @@
+//! Keeps two engines trading the same wallet from running at once.
+//!
+//! Always takes an exclusive lock on a local file; with PostgreSQL
+//! persistence it also takes a session-level advisory lock keyed on each
+//! wallet it trades, which covers engines on different hosts sharing that
+//! database.
+//! Both are released by the OS / server when the process exits, however it
+//! exits, so a crashed engine never leaves a stale lock behind.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use anyhow::Context;
+use tokio_postgres::{Client, NoTls};
+use tracing::{error, info};
+
+/// Held for the life of the process; dropping it releases the locks.
+pub struct InstanceLock {
+    _file: File,
+    _postgres: Option<Client>,
+}
+
+/// Take the instance lock for `identities`: the default wallet's address (or
+/// `paper`), then every other configured wallet's. Fails, naming the current
+/// holder where it can, if another engine has any of them.
+pub async fn acquire(path: &Path, postgres_url: Option<&str>, identities: &[String]) -> anyhow::Result<InstanceLock> {
+    let identity = identities.join(",");
+    let file = lock_file(path, &identity)?;
+    let postgres = match postgres_url {
+        Some(url) => Some(advisory_lock(url, identities).await?),
+        None => None,
+    };
+    info!(path = %path.display(), shared = postgres.is_some(), %identity, "instance lock acquired");
+    Ok(InstanceLock { _file: file, _postgres: postgres })
+}
+
+fn lock_file(path: &Path, identity: &str) -> anyhow::Result<File> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .with_context(|| format!("opening instance lock {}", path.display()))?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            let mut holder = String::new();
+            let _ = file.read_to_string(&mut holder);
+            anyhow::bail!(
+                "another engine holds {} ({}); refusing to start a second instance",
+                path.display(),
+                holder.trim().replace('\n', ", "),
+            );
+        }
+        Err(TryLockError::Error(e)) => {
+            return Err(e).with_context(|| format!("locking {}", path.display()));
+        }
+    }
+
+    // Record who holds it, for the error the next instance prints.
+    file.set_len(0)?;
+    file.rewind()?;
+    writeln!(file, "pid={}\nwallets={identity}\nstarted={}", std::process::id(), chrono::Utc::now().to_rfc3339())?;
+    file.flush()?;
+    Ok(file)
+}
+
+async fn advisory_lock(url: &str, identities: &[String]) -> anyhow::Result<Client> {
+    let (client, connection) = tokio_postgres::connect(url, NoTls).await.context("connecting for the instance lock")?;
+    tokio::spawn(async move {
+        // The advisory lock goes with the session; nothing stops a second
+        // engine from starting once it is gone.
+        if let Err(e) = connection.await {
+            error!(error = %e, "instance lock connection lost; another engine on this wallet could now start");
+        }
+    });
+
+    // Locks already taken are released with the session if a later one fails.
+    for identity in identities {
+        let key = lock_key(identity);
+        let row = client.query_one("SELECT pg_try_advisory_lock($1)", &[&key]).await?;
+        anyhow::ensure!(
+            row.get::<_, bool>(0),
+            "another engine holds the PostgreSQL instance lock for wallet {identity}; refusing to start a second instance"
+        );
+    }
+    Ok(client)
+}
+
+/// Stable across processes and builds, unlike `DefaultHasher`.
+fn lock_key(identity: &str) -> i64 {
+    let hash = crc32fast::hash(format!("prediction-engine:{identity}").as_bytes());
+    i64::from(hash)
+}
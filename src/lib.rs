@@ -2,4 +2,29 @@ pub mod state;
 pub mod market_data;
 pub mod metrics;
 pub mod strategy;
-pub mod execution;
\ No newline at end of file
+pub mod execution;
+pub mod admin;
+pub mod persist;
+pub mod risk;pub mod backtest;
+pub mod anomaly;
+pub mod channel;
+pub mod clock;
+pub mod engine;
+pub mod fix;
+pub mod flags;
+pub mod health;
+pub mod instance_lock;
+pub mod logging;
+pub mod notify;
+pub mod publish;
+pub mod rate_limit;
+pub mod ring;
+pub mod runtime;
+pub mod secrets;
+pub mod supervisor;
+pub mod watchdog;
+pub mod webhook;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "grpc")]
+pub mod grpc;
@@ -0,0 +1,36 @@
+use std::sync::{Arc, Mutex};
+
+type ApplyFn = dyn Fn(&str) -> anyhow::Result<()> + Send + Sync;
+
+/// Handle for changing the tracing filter of a running process.
+///
+/// The binary owns the subscriber (and so its concrete reload handle); this
+/// wraps it so the admin API and signal handlers can swap the filter without
+/// knowing the subscriber's type. Directives use `RUST_LOG` syntax, e.g.
+/// `info,prediction_engine::execution=debug`.
+#[derive(Clone)]
+pub struct LogFilter {
+    current: Arc<Mutex<String>>,
+    apply: Arc<ApplyFn>,
+}
+
+impl LogFilter {
+    /// `initial` is the filter the subscriber was built with; `apply` parses
+    /// and installs new directives, failing without side effects if they're invalid.
+    pub fn new(initial: impl Into<String>, apply: impl Fn(&str) -> anyhow::Result<()> + Send + Sync + 'static) -> Self {
+        Self { current: Arc::new(Mutex::new(initial.into())), apply: Arc::new(apply) }
+    }
+
+    pub fn current(&self) -> String {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Replace the active filter. Returns the previous directives.
+    pub fn set(&self, directives: &str) -> anyhow::Result<String> {
+        let directives = directives.trim();
+        anyhow::ensure!(!directives.is_empty(), "empty log filter");
+        let mut current = self.current.lock().unwrap();
+        (self.apply)(directives)?;
+        Ok(std::mem::replace(&mut *current, directives.to_string()))
+    }
+}
@@ -1,48 +1,345 @@
 #![allow(warnings)]
 
+mod cli;
 mod config;
 
 pub use tracing_subscriber::filter::EnvFilter;
 pub use anyhow::Result;
-pub use tracing::{info, warn};
-use tokio::sync::mpsc;
+pub use tracing::{error, info, warn};
+use clap::Parser;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::Layer;
+use tokio::sync::{mpsc, watch};
+use std::collections::HashMap;
 use std::sync::Arc;
-use prediction_engine::market_data::router;
-use prediction_engine::market_data::market_worker::Notification;
+use std::time::Duration;
+use prediction_engine::clock::{SharedClock, SystemClock};
+use prediction_engine::engine::EngineBuilder;
+use prediction_engine::market_data::bus::EventBus;
+use prediction_engine::market_data::feed_watchdog::{run_feed_watchdog, FeedWatchdogConfig};
+use prediction_engine::metrics::{ExportTarget, ExporterConfig, HistogramConfig};
+use prediction_engine::metrics::channels::ChannelMonitor;
+use prediction_engine::metrics::{latency, tasks};
+use prediction_engine::metrics::market_quality::{run_market_quality_exporter, MarketQualityConfig};
 use prediction_engine::state::market_cache::MarketCache;
-use prediction_engine::market_data::adapters::polymarket;
-use prediction_engine::strategy;
+use prediction_engine::state::position::{CostBasisMethod, PositionTracker};
+use prediction_engine::state::portfolio::Portfolio;
+use prediction_engine::state::fees::FeeSchedule;
+use prediction_engine::state::rewards::RewardsBook;
+use prediction_engine::market_data::types::Venue;
+use prediction_engine::market_data::universe::Universe;
+use prediction_engine::admin::{self, AdminState};
+use prediction_engine::health::HealthRegistry;
+use prediction_engine::logging::LogFilter;
+use prediction_engine::strategy::audit::DecisionAudit;
+use prediction_engine::strategy::{PauseSwitch, StrategySet};
+#[cfg(feature = "tui")]
+use prediction_engine::tui::{run_tui, TuiState};
+use prediction_engine::notify::{self, Channel, EventKind, NotifyConfig, Notifier};
+use prediction_engine::runtime::HotPathRuntime;
+use prediction_engine::watchdog::{run_watchdog, WatchdogConfig};
+use prediction_engine::anomaly::{run_rate_anomaly_detector, RateAnomalyConfig};
+use prediction_engine::persist::snapshot::{run_snapshot_writer, write_snapshot_files};
+use prediction_engine::persist::equity::run_equity_sampler;
+use prediction_engine::persist::influx::{run_influx_exporter, InfluxConfig};
+use prediction_engine::persist::journal::{self, JournalStorage};
+use prediction_engine::persist::retention::{run_retention, RetentionPolicy};
+use prediction_engine::persist::upload::{run_uploader, UploadConfig};
+use prediction_engine::persist::sqlite::SqliteStorage;
+use prediction_engine::persist::postgres::PostgresStorage;
+use prediction_engine::persist::archive::{run_archiver, ArchiveConfig};
+use prediction_engine::persist::clickhouse::{run_clickhouse_sink, ClickHouseConfig};
+use prediction_engine::publish::run_publisher;
+use prediction_engine::persist::{run_storage_writer, PersistRecord, Recorder, Storage};
+use prediction_engine::state::equity::EquityCurve;
+use prediction_engine::risk::{RiskConfig, RiskManager};
+use prediction_engine::instance_lock;
+use prediction_engine::flags::FeatureFlags;
+use prediction_engine::channel;
+use prediction_engine::market_data::adapters::{amm, betfair, kalshi, polymarket, rest_poller};
+use prediction_engine::market_data::adapters::traits::MarketDataAdapter;
+use prediction_engine::market_data::types::EventBatch;
+use prediction_engine::secrets::{fetch_kalshi_credentials, KalshiCredentials};
 use prediction_engine::strategy::traits::TradeSignal;
-use prediction_engine::strategy::arbitrage::ArbitrageStrategy;
-use prediction_engine::execution;
+use prediction_engine::execution::keys::KeySource;
+use prediction_engine::execution::live::{run_key_rotation, trading_client_for, LiveExecutor};
+use prediction_engine::execution::operator::OperatorActions;
 use prediction_engine::execution::paper::PaperExecutor;
+use prediction_engine::execution::traits::ExecutionEngine;
+use prediction_engine::execution::wallets::WalletRouter;
+use polymarket_rs::{PrivateKeySigner, TradingClient};
+use rust_decimal::Decimal;
 
-const ADAPTER_CHANNEL_BUFFER: usize = 4_096;
-const NOTIFY_CHANNEL_BUFFER: usize = 512;
-const SIGNAL_CHANNEL_BUFFER: usize = 64;
+const PAPER_STARTING_CASH: f64 = 1_000.0;
+/// Polymarket's default price increment.
+const LIVE_TICK_SIZE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+const SNAPSHOT_DIR: &str = "data";
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60);
+const EQUITY_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+const RETENTION_INTERVAL: Duration = Duration::from_secs(600);
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(300);
+const REWARDS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+const CHANNEL_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+const TASK_METRICS_INTERVAL: Duration = Duration::from_secs(10);
+const LATENCY_SUMMARY_INTERVAL: Duration = Duration::from_secs(60);
+/// How long shutdown waits for each flush step (cancel-all, persistence writers).
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+/// One week of 10s samples.
+const EQUITY_MAX_SAMPLES: usize = 60_480;
 
-fn init_tracing() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+/// Install the global subscriber. The returned [`LogFilter`] swaps its
+/// `EnvFilter` at runtime; the guard must be held to keep the file writer flushing.
+/// `stdout` is false while the terminal dashboard owns the screen.
+fn init_tracing(config: &config::Config, stdout: bool) -> Result<(LogFilter, Option<WorkerGuard>)> {
+    let json = config.log_format == config::LogFormat::Json;
+
+    let (file_layer, guard) = match &config.log_file {
+        Some(settings) => {
+            let rotation = match settings.rotation {
+                config::LogRotation::Hourly => Rotation::HOURLY,
+                config::LogRotation::Daily => Rotation::DAILY,
+                config::LogRotation::Never => Rotation::NEVER,
+            };
+            let mut appender = RollingFileAppender::builder().rotation(rotation).filename_prefix(&settings.prefix);
+            if let Some(max_files) = settings.max_files {
+                appender = appender.max_log_files(max_files);
+            }
+            let (writer, guard) = tracing_appender::non_blocking(appender.build(&settings.dir)?);
+            let layer = fmt::layer().with_writer(writer).with_ansi(false);
+            let layer = if json { log_json(layer).boxed() } else { layer.boxed() };
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let stdout_layer = stdout.then(|| if json { log_json(fmt::layer()).boxed() } else { fmt::layer().boxed() });
+
+    let (filter, reload_handle) = reload::Layer::new(EnvFilter::try_new(&config.log_level)?);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout_layer)
+        .with(file_layer)
         .init();
+
+    let log_filter = LogFilter::new(config.log_level.clone(), move |directives| {
+        reload_handle.reload(EnvFilter::try_new(directives)?)?;
+        Ok(())
+    });
+    Ok((log_filter, guard))
+}
+
+/// Resolves on Ctrl-C, or SIGTERM on unix (what `docker stop` and systemd send).
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        let mut term = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(term) => term,
+            Err(e) => {
+                warn!(error = %e, "cannot listen for SIGTERM; only Ctrl-C shuts down gracefully");
+                let _ = tokio::signal::ctrl_c().await;
+                return "SIGINT";
+            }
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+            _ = term.recv() => "SIGTERM",
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+/// On SIGHUP, re-read `RUST_LOG` from `.env` and apply it (or the startup
+/// filter if `.env` no longer sets it), so `kill -HUP` after editing the file
+/// changes verbosity without a restart.
+#[cfg(unix)]
+async fn reload_log_filter_on_hangup(log_filter: LogFilter, startup: String) -> Result<()> {
+    let mut hangups = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+    while hangups.recv().await.is_some() {
+        let directives = dotenvy::dotenv_iter()
+            .ok()
+            .and_then(|vars| vars.flatten().find(|(key, _)| key == "RUST_LOG").map(|(_, value)| value))
+            .unwrap_or_else(|| startup.clone());
+        match log_filter.set(&directives) {
+            Ok(previous) => info!(%previous, filter = %directives, "log filter reloaded on SIGHUP"),
+            Err(e) => warn!(error = %e, filter = %directives, "SIGHUP log filter rejected"),
+        }
+    }
+    Ok(())
+}
+
+fn log_json<S, W>(layer: fmt::Layer<S, fmt::format::DefaultFields, fmt::format::Format, W>) -> fmt::Layer<S, fmt::format::JsonFields, fmt::format::Format<fmt::format::Json>, W>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    layer.json().flatten_event(true).with_current_span(true).with_span_list(false)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    init_tracing();
-    prediction_engine::metrics::init_metrics();
+    let mut cli = cli::Cli::parse();
+    let command = cli.command.take().unwrap_or_else(|| cli::Command::Run(cli::RunArgs::default()));
+    match command {
+        cli::Command::Paper(_) => cli.overrides.push("execution.mode=paper".to_string()),
+        cli::Command::DryRun(_) => cli.overrides.push("execution.mode=dry_run".to_string()),
+        // Per-fill logs would dominate what the benchmark measures; `--set` still wins.
+        cli::Command::Bench(_) => cli.overrides.insert(0, "logging.level=warn".to_string()),
+        _ => {}
+    }
+    if cli.i_understand_live_trading {
+        cli.overrides.push("execution.confirm_live=true".to_string());
+    }
+    // Config comes first so it can pick the log format; load errors go to stderr via main's Result.
+    let config = config::Config::load(cli.config.as_deref(), &cli.overrides)?;
+    if cli.print_config {
+        cli::print_config::print(&config);
+        return Ok(());
+    }
+    // The TUI and the console own the terminal.
+    #[cfg(feature = "tui")]
+    let stdout_logs = !matches!(
+        &command,
+        cli::Command::Run(args) | cli::Command::Paper(args) | cli::Command::DryRun(args) if args.tui
+    ) && !matches!(command, cli::Command::Console(_));
+    #[cfg(not(feature = "tui"))]
+    let stdout_logs = !matches!(command, cli::Command::Console(_));
+    let (log_filter, _log_guard) = init_tracing(&config, stdout_logs)?;
+
+    if cli.check_migrations {
+        return cli::migrations::check(&config).await;
+    }
+    if cli.check {
+        return cli::preflight::check(&config, config.admin_addr).await;
+    }
+
+    match command {
+        cli::Command::Run(args) | cli::Command::Paper(args) | cli::Command::DryRun(args) => {
+            run_engine(config, log_filter, args).await
+        }
+        cli::Command::Download(args) => cli::download::run(args, &config).await,
+        cli::Command::Backtest(args) => cli::backtest::run(args, &config).await,
+        cli::Command::DiscoverMarkets(args) => cli::discover::run(args, &config).await,
+        cli::Command::Reconcile(args) => cli::reconcile::run(args, &config).await,
+        cli::Command::ExportLedger(args) => cli::ledger::run(args, &config).await,
+        cli::Command::Console(args) => cli::console::run(args).await,
+        cli::Command::Bench(args) => cli::bench::run(args, &config).await,
+    }
+}
+
+async fn run_engine(config: config::Config, log_filter: LogFilter, args: cli::RunArgs) -> Result<()> {
+    // Before the first venue REST call, which would settle on the defaults.
+    prediction_engine::rate_limit::install(&config.rate_limits)?;
+    // Before anything binds or trades. The age key source prompts on the terminal here.
+    let signers = cli::preflight::load_signers(&config).await;
+    // Held until the process exits, so a second engine on any of these wallets can't start.
+    let mut identities = vec![match &signers.default {
+        Some(Ok(signer)) => signer.address().to_string(),
+        _ => "paper".to_string(),
+    }];
+    identities.extend(signers.wallets.iter().filter_map(|(_, signer)| signer.as_ref().ok()).map(|signer| signer.address().to_string()));
+    let postgres_url = match &config.storage {
+        Some(config::StorageConfig::Postgres(url)) => Some(url.as_str()),
+        _ => None,
+    };
+    let _instance_lock = instance_lock::acquire(&config.instance_lock_path, postgres_url, &identities).await?;
+    let preflight = cli::preflight::run(&config, config.admin_addr, &signers).await;
+    preflight.log();
+    if preflight.failures() > 0 {
+        anyhow::bail!("{} preflight check(s) failed (run with --check for a summary)", preflight.failures());
+    }
+
+    let target = match &config.metrics.push {
+        None => ExportTarget::Listen(config.metrics.listen),
+        Some(push) => {
+            let (endpoint, interval) = (push.url.clone(), push.interval);
+            let (username, password) = (push.username.clone(), push.password.clone());
+            match push.mode {
+                config::MetricsPushMode::PushGateway => ExportTarget::PushGateway { endpoint, interval, username, password },
+                config::MetricsPushMode::Import => ExportTarget::TextImport { endpoint, interval, username, password },
+            }
+        }
+    };
+    let exporter = ExporterConfig {
+        target,
+        prefix: config.metrics.prefix.clone(),
+        global_labels: config.metrics.labels.clone(),
+    };
+    prediction_engine::metrics::init_metrics(&exporter, &HistogramConfig {
+        latency_us_buckets: config.metrics.latency_us_buckets.clone(),
+        latency_ms_buckets: config.metrics.latency_ms_buckets.clone(),
+        edge_buckets: config.metrics.edge_buckets_bps.as_ref().map(|b| b.iter().map(|bps| bps / 10_000.0).collect()),
+        quantiles: config.metrics.quantiles.clone(),
+    })?;
 
     info!("prediction-engine starting");
 
-    let (tx, rx) = mpsc::channel(ADAPTER_CHANNEL_BUFFER);
+    let trading_client = match signers.default.transpose()? {
+        Some(signer) => Some(start_trading_client(signer, &config.key_source, config.secret_refresh).await?),
+        None => None,
+    };
+    // One per `[[wallets]]` entry, in order; empty in paper mode.
+    let mut wallet_clients = Vec::new();
+    for (wallet, (_, signer)) in config.wallets.iter().zip(signers.wallets) {
+        wallet_clients.push(start_trading_client(signer?, &wallet.key_source()?, config.secret_refresh).await?);
+    }
+
+    #[cfg(unix)]
+    tokio::spawn(reload_log_filter_on_hangup(log_filter.clone(), config.log_level.clone()));
 
+    let builder = EngineBuilder::new(config.channels.pipeline());
+
+    let clock: SharedClock = Arc::new(SystemClock);
     let cache = MarketCache::new();
+    let positions = PositionTracker::with_method(CostBasisMethod::Fifo);
+    let notifier = match &config.notify {
+        Some(settings) => {
+            let (notifier, notify_events_rx) = Notifier::new();
+            tokio::spawn(notify::run_notifier(notify_events_rx, notify_config(settings)?));
+            notifier
+        }
+        None => Notifier::disabled(),
+    };
+    let health = HealthRegistry::new(cache.clone(), config.health_stale_after).with_notifier(notifier.clone());
+    let channels = ChannelMonitor::new();
+
+    let imported = config.universe_import.as_deref().map(Universe::load).transpose()?;
+    let equivalences = imported.as_ref().map(Universe::equivalence_map).unwrap_or_default();
 
     // Initialize adapter — fetches markets and returns metadata + spawned handle
-    let pm = polymarket::init_polymarket_adapter(tx).await?;
+    let filter = polymarket::UniverseFilter {
+        min_volume_24h: config.polymarket.min_volume_24h,
+        min_liquidity: config.polymarket.min_liquidity,
+    };
+    let pm = polymarket::init_polymarket_adapter(
+        builder.event_sender(),
+        imported.as_ref().map(Universe::market_map),
+        filter,
+        &config.universe,
+        health.clone(),
+    )
+    .await?;
+
+    // Every venue's adapter behind one type, Polymarket first; they're
+    // given venue threads, subscription handles and the engine alike.
+    let mut adapters: Vec<Box<dyn MarketDataAdapter>> = vec![Box::new(pm.task.with_ws_connections(config.polymarket_ws_connections))];
+    adapters.extend(venue_adapters(&config, builder.event_sender(), &health).await?);
 
+    config.check_strategy_markets(&pm.market_map)?;
     let market_map = Arc::new(pm.market_map);
     let token_to_market = pm.token_to_market;
+    let market_activity = pm.activity;
+
+    if let Some(path) = &config.universe_export {
+        Universe::new(&market_map, &token_to_market, &equivalences).save(path)?;
+    }
 
     info!(
         markets = market_map.len(),
@@ -50,62 +347,515 @@ async fn main() -> Result<()> {
         "market metadata loaded"
     );
 
-    // MarketWorker → StrategyEngine notification channel
-    let (notify_tx, notify_rx) = mpsc::channel::<Notification>(NOTIFY_CHANNEL_BUFFER);
+    // Reward programs, refreshed in the background only when something quotes.
+    let rewards = RewardsBook::default();
+    if config.strategies.iter().any(|s| matches!(s, config::StrategySettings::MarketMaker(_))) {
+        tokio::spawn(polymarket::run_rewards_refresher(rewards.clone(), Arc::clone(&token_to_market), REWARDS_REFRESH_INTERVAL));
+    }
+
+    let portfolio = Portfolio::new(
+        positions.clone(),
+        cache.clone(),
+        Arc::clone(&token_to_market),
+        PAPER_STARTING_CASH,
+    )
+    .with_fee_schedules([(Venue::Polymarket, FeeSchedule::polymarket_default())].into())
+    .with_correlation_groups(config.correlation_groups.clone().into_iter().collect())
+    .with_rewards(rewards.clone());
+
+    let equity_curve = EquityCurve::new(EQUITY_MAX_SAMPLES);
+    let risk = RiskManager::new(RiskConfig {
+        max_drawdown: config.risk.max_drawdown,
+        max_daily_notional: config.risk.max_daily_notional,
+    })
+    .with_notifier(notifier.clone());
+    // Each extra wallet has its own daily budget; drawdown stays global.
+    let wallet_risk: Vec<RiskManager> = config
+        .wallets
+        .iter()
+        .map(|wallet| RiskManager::new(RiskConfig {
+            max_drawdown: config.risk.max_drawdown,
+            max_daily_notional: wallet.max_daily_notional.unwrap_or(config.risk.max_daily_notional),
+        }))
+        .collect();
+    let wallet_risk_by_strategy: HashMap<String, RiskManager> = config
+        .wallets
+        .iter()
+        .zip(&wallet_risk)
+        .flat_map(|(wallet, risk)| wallet.strategies.iter().map(|s| (s.clone(), risk.clone())))
+        .collect();
+    let pause = PauseSwitch::default();
+
+    // Rebuild positions, in-flight orders, and risk counters before anything trades.
+    journal::recover(&config.journal_path, &portfolio, &risk, &wallet_risk_by_strategy)?;
+    if let Some(max_age) = config.retention.journal_max_age {
+        journal::compact(&config.journal_path, max_age)?;
+    }
+
+    let storage: Option<Box<dyn Storage>> = match &config.storage {
+        Some(config::StorageConfig::Sqlite(path)) => Some(Box::new(SqliteStorage::open(path)?)),
+        Some(config::StorageConfig::Postgres(url)) => Some(Box::new(PostgresStorage::connect(url).await?)),
+        None => None,
+    };
+    // Record sinks (database, ClickHouse, ...) and raw-event subscribers (archiver, ClickHouse).
+    let mut record_sinks = Vec::new();
+    let event_bus = EventBus::new(config.channels.taps);
 
-    // StrategyEngine → ExecutionBridge signal channel
-    let (signal_tx, signal_rx) = mpsc::channel::<TradeSignal>(SIGNAL_CHANNEL_BUFFER);
+    // Writers awaited on shutdown so everything recorded reaches disk.
+    let mut writer_handles = Vec::new();
 
-    // min_edge = 0.025 (2.5%): Polymarket charges ~1% taker fee per leg (2 legs = 2%
-    // total). A 2.5% edge threshold ensures we're profitable net of fees, with a small
-    // buffer for slippage. Real edges from the CLOB are typically 0.002–0.004 (below
-    // fees), so signals should be rare and only fire on genuine dislocations.
-    let strategies: Vec<Box<dyn strategy::traits::Strategy>> = vec![
-        Box::new(ArbitrageStrategy::new(0.025, 5.0)),
-    ];
+    // Only the execution bridge writes the journal, appending what recovery
+    // needs before it acts; signals go to the database sinks.
+    let journal = JournalStorage::open(&config.journal_path)?;
+
+    if let Some(storage) = storage {
+        let (persist_tx, persist_rx) = mpsc::channel::<PersistRecord>(config.channels.records);
+        writer_handles.push(tokio::spawn(run_storage_writer(persist_rx, storage, config.retention.db_max_age)));
+        channels.watch("database", &persist_tx);
+        record_sinks.push(("database", persist_tx));
+    }
 
-    let router_handle = tokio::spawn(router::run_router(rx, cache.clone(), notify_tx));
-    let strategy_handle = tokio::spawn(strategy::run_strategy_engine(
-        notify_rx, cache.clone(), strategies, signal_tx,
-        Arc::clone(&market_map), Arc::clone(&token_to_market),
+    if let Some(settings) = &config.clickhouse {
+        let (ch_records_tx, ch_records_rx) = mpsc::channel::<PersistRecord>(config.channels.records);
+        tokio::spawn(run_clickhouse_sink(event_bus.subscribe("clickhouse_events"), ch_records_rx, ClickHouseConfig {
+            url: settings.url.clone(),
+            database: settings.database.clone(),
+            user: settings.user.clone(),
+            password: settings.password.clone(),
+        }));
+        channels.watch("clickhouse_records", &ch_records_tx);
+        record_sinks.push(("clickhouse_records", ch_records_tx));
+    }
+
+    if let Some(publish) = config.publish.clone() {
+        let (publish_tx, publish_rx) = mpsc::channel::<PersistRecord>(config.channels.records);
+        tokio::spawn(run_publisher(publish_rx, publish));
+        channels.watch("publish_records", &publish_tx);
+        record_sinks.push(("publish_records", publish_tx));
+    }
+
+    #[cfg(feature = "tui")]
+    let tui_handle = if args.tui {
+        let tui_events = event_bus.subscribe("tui_events");
+        let (tui_records_tx, tui_records_rx) = mpsc::channel::<PersistRecord>(config.channels.records);
+        channels.watch("tui_records", &tui_records_tx);
+        record_sinks.push(("tui_records", tui_records_tx));
+        let state = TuiState {
+            cache: cache.clone(),
+            market_map: Arc::clone(&market_map),
+            portfolio: portfolio.clone(),
+            risk: risk.clone(),
+            pause: pause.clone(),
+        };
+        Some(tokio::spawn(run_tui(state, tui_events, tui_records_rx)))
+    } else {
+        None
+    };
+    #[cfg(not(feature = "tui"))]
+    let tui_handle: Option<tokio::task::JoinHandle<Result<()>>> = None;
+    let tui_exit = async move {
+        match tui_handle {
+            Some(handle) => handle.await,
+            None => std::future::pending().await,
+        }
+    };
+
+    #[cfg(feature = "grpc")]
+    let grpc_records = config.grpc_addr.map(|_| {
+        let (grpc_tx, grpc_rx) = mpsc::channel::<PersistRecord>(config.channels.records);
+        channels.watch("grpc_records", &grpc_tx);
+        record_sinks.push(("grpc_records", grpc_tx));
+        let records = prediction_engine::grpc::record_channel();
+        tokio::spawn(prediction_engine::grpc::run_record_fanout(grpc_rx, records.clone()));
+        records
+    });
+    #[cfg(not(feature = "grpc"))]
+    if config.grpc_addr.is_some() {
+        warn!("GRPC_ADDR is set but this build lacks the grpc feature — gRPC API disabled");
+    }
+
+    let recorder = Recorder::new(record_sinks).with_journal(journal);
+
+    tokio::spawn(run_rate_anomaly_detector(
+        event_bus.subscribe("event_rate"),
+        RateAnomalyConfig {
+            burst_ratio: config.watchdog.event_rate_burst_ratio,
+            drop_ratio: config.watchdog.event_rate_drop_ratio,
+            ..RateAnomalyConfig::default()
+        },
+        notifier.clone(),
     ));
-    let exec_handle = tokio::spawn(execution::run_execution_bridge(
-        signal_rx,
-        Box::new(PaperExecutor::new()),
-        "paper",
+
+    if config.market_gauges_top_k > 0 {
+        tokio::spawn(run_market_quality_exporter(event_bus.subscribe("market_quality"), MarketQualityConfig {
+            max_markets: config.market_gauges_top_k,
+            ..MarketQualityConfig::default()
+        }));
+    }
+
+    let flags = FeatureFlags::new(config.flags.clone());
+    let strategies = StrategySet::new(
+        config.strategies.iter().map(|s| s.build(&rewards)).collect::<anyhow::Result<_>>()?,
+    )
+    .with_flags(flags.clone());
+    let live = config.execution_mode == config::ExecutionMode::Live;
+    let operator_client = trading_client.clone().filter(|_| live);
+    let operator_wallets: Vec<_> = match live {
+        true => config.wallets.iter().map(|w| w.name.clone()).zip(wallet_clients.iter().cloned()).collect(),
+        false => Vec::new(),
+    };
+    let executor_for = |client: Option<watch::Receiver<Arc<TradingClient>>>| -> (Arc<dyn ExecutionEngine>, &'static str) {
+        match (config.execution_mode, client) {
+            (config::ExecutionMode::Live, Some(client)) => (Arc::new(LiveExecutor::rotating(client, LIVE_TICK_SIZE)), "live"),
+            (config::ExecutionMode::DryRun, Some(client)) => (Arc::new(LiveExecutor::dry_run(client, LIVE_TICK_SIZE)), "dry_run"),
+            _ => (Arc::new(PaperExecutor::new()), "paper"),
+        }
+    };
+    let (mut executor, executor_name) = executor_for(trading_client);
+    if !config.wallets.is_empty() {
+        let mut router = WalletRouter::new(executor, Arc::clone(&clock));
+        for (i, (wallet, risk)) in config.wallets.iter().zip(&wallet_risk).enumerate() {
+            let (wallet_executor, _) = executor_for(wallet_clients.get(i).cloned());
+            router = router.wallet(wallet.name.clone(), wallet_executor, risk.clone(), wallet.strategies.clone());
+        }
+        executor = Arc::new(router);
+    }
+    let audit = DecisionAudit::new(config.audit.depth, config.audit.all, config.audit.markets.clone());
+
+    for adapter in &mut adapters {
+        adapter.set_keepalive(config.ws_keepalive);
+    }
+
+    // Venue readers listed in VENUE_THREADS get a thread (and runtime) each.
+    let mut venue_runtimes: Vec<(Venue, HotPathRuntime)> = Vec::new();
+    for thread in &config.venue_threads {
+        venue_runtimes.push((thread.venue.clone(), HotPathRuntime::start_venue(thread)?));
+    }
+    for (venue, runtime) in &venue_runtimes {
+        let reads = adapters
+            .iter_mut()
+            .find(|adapter| adapter.venue() == *venue)
+            .map(|adapter| adapter.set_reader_runtime(runtime.handle().clone()));
+        match reads {
+            Some(true) => {}
+            Some(false) => warn!(venue = venue.name(), "this adapter has no socket to read; its reader thread is idle"),
+            None => warn!(venue = venue.name(), "no adapter runs for this venue; its reader thread is idle"),
+        }
+    }
+
+    // Handles for changing each adapter's subscriptions from the admin API.
+    let subscriptions: Vec<_> = adapters.iter().filter_map(|adapter| adapter.subscription_control()).collect();
+
+    let builder = adapters.into_iter().fold(builder, |builder, adapter| builder.market_data(Arc::from(adapter)));
+    let engine = builder
+        .markets(Arc::clone(&market_map), Arc::clone(&token_to_market))
+        .strategy_set(strategies.clone())
+        .flags(flags.clone())
+        .executor(executor, executor_name)
+        .risk_manager(risk.clone())
+        .portfolio(portfolio.clone())
+        .cache(cache.clone())
+        .clock(Arc::clone(&clock))
+        .health(health.clone())
+        .notifier(notifier.clone())
+        .recorder(recorder.clone())
+        .audit(audit.clone())
+        .pause(pause.clone())
+        .event_bus(event_bus.clone())
+        .priority_edge(config.priority_edge)
+        .signal_filter(config.signal_filter)
+        .hot_path(config.hot_path.clone())
+        .busy_poll(config.busy_poll)
+        .build();
+    engine.watch_channels(&channels);
+
+    tokio::spawn(run_watchdog(
+        WatchdogConfig {
+            event_timeout: config.watchdog.event_timeout,
+            signal_full_after: config.watchdog.signal_full_after,
+            fill_timeout: config.watchdog.fill_timeout,
+            active_hours: config.watchdog.active_hours,
+            ..WatchdogConfig::default()
+        },
+        cache.clone(),
+        engine.signals().normal().downgrade(),
+        health.clone(),
+        notifier.clone(),
     ));
+    if config.watchdog.watches_feeds() {
+        tokio::spawn(run_feed_watchdog(
+            FeedWatchdogConfig {
+                stale_after: config.watchdog.feed_stale_after,
+                venue_stale_after: config.watchdog.feed_stale_venues.iter().cloned().collect(),
+                ..FeedWatchdogConfig::default()
+            },
+            event_bus.subscribe("feed_watchdog"),
+            engine.event_sender(),
+            risk.clone(),
+        ));
+    }
+    let operator = OperatorActions::new(
+        portfolio.clone(),
+        cache.clone(),
+        Arc::clone(&token_to_market),
+        engine.signals().normal().downgrade(),
+        operator_client,
+    )
+    .with_wallets(operator_wallets);
 
+    if let Some(settings) = &config.archive {
+        let mut archive_config = ArchiveConfig { root: settings.dir.clone(), ..ArchiveConfig::default() };
+        if let Some(layout) = &settings.layout {
+            archive_config.layout = layout.clone();
+        }
+        tokio::spawn(run_archiver(event_bus.subscribe("archive"), cache.clone(), archive_config));
+
+        let policy = RetentionPolicy {
+            max_age: config.retention.archive_max_age,
+            max_bytes: config.retention.archive_max_bytes,
+        };
+        if !policy.is_unbounded() {
+            tokio::spawn(run_retention(vec![(settings.dir.clone(), policy)], RETENTION_INTERVAL));
+        }
+
+        if let Some(upload) = &config.upload {
+            tokio::spawn(run_uploader(settings.dir.clone(), UploadConfig {
+                bucket: upload.bucket.clone(),
+                prefix: upload.prefix.clone(),
+                endpoint: upload.endpoint.clone(),
+                region: upload.region.clone(),
+                delete_local: upload.delete_local,
+                interval: UPLOAD_INTERVAL,
+            }));
+        }
+    }
+
+    tokio::spawn(channels.run(CHANNEL_SAMPLE_INTERVAL));
+    tokio::spawn(tasks::run_task_metrics(TASK_METRICS_INTERVAL));
+    tokio::spawn(latency::run_latency_summary(LATENCY_SUMMARY_INTERVAL));
+    // ── Supervised pipeline ──────────────────────────────────────
+    // Adapter → router → strategy engine → execution bridge, each restarted
+    // with backoff if it panics or exits; see `prediction_engine::engine`.
+    let mut running = engine.start()?;
+    tokio::spawn(notify::run_daily_summary(notifier.clone(), portfolio.clone(), risk.clone()));
+    let admin_state = AdminState {
+        portfolio: portfolio.clone(),
+        equity: equity_curve.clone(),
+        health: health.clone(),
+        audit: audit.clone(),
+        log_filter: log_filter.clone(),
+        pause: pause.clone(),
+        strategies: strategies.clone(),
+        flags: flags.clone(),
+        operator: operator.clone(),
+        subscriptions,
+        token: config.admin_token.clone(),
+    };
+    #[cfg(feature = "grpc")]
+    if let (Some(addr), Some(records)) = (config.grpc_addr, grpc_records) {
+        tokio::spawn(prediction_engine::grpc::run_grpc_server(addr, admin_state.clone(), records));
+    }
+    tokio::spawn(admin::run_admin_server(config.admin_addr, admin_state));
+    if let Some(fix) = config.fix.clone() {
+        tokio::spawn(prediction_engine::fix::run_fix_gateway(fix, operator.clone()));
+    }
+    if let Some(webhook) = config.webhook.clone() {
+        tokio::spawn(prediction_engine::webhook::run_webhook_server(webhook, operator.clone()));
+    }
+    tokio::spawn(run_equity_sampler(
+        portfolio.clone(),
+        equity_curve.clone(),
+        risk.clone(),
+        SNAPSHOT_DIR.into(),
+        EQUITY_SAMPLE_INTERVAL,
+    ));
+    tokio::spawn(run_snapshot_writer(
+        portfolio.clone(),
+        SNAPSHOT_DIR.into(),
+        SNAPSHOT_INTERVAL,
+    ));
+    if let Some(settings) = &config.influx {
+        tokio::spawn(run_influx_exporter(cache.clone(), portfolio.clone(), InfluxConfig {
+            url: settings.url.clone(),
+            org: settings.org.clone(),
+            bucket: settings.bucket.clone(),
+            token: settings.token.clone(),
+            interval: settings.interval,
+        }));
+    }
+
+    if let Some(path) = config.source.clone() {
+        tokio::spawn(config::watch::run_config_watcher(path, config.clone(), config::watch::LiveSettings {
+            log_filter: log_filter.clone(),
+            risk: risk.clone(),
+            strategies: strategies.clone(),
+            rewards: rewards.clone(),
+            flags: flags.clone(),
+            activity: market_activity,
+        }));
+    }
+
+    // Supervised tasks restart rather than exit, so only the dashboard or a
+    // signal ends the run.
     tokio::select! {
-        res = pm.handle => {
+        res = tui_exit => {
             match res {
-                Ok(Ok(())) => warn!("polymarket adapter exited"),
-                Ok(Err(err)) => warn!(error = %err, "polymarket adapter returned error"),
-                Err(err) => warn!(error = %err, "polymarket adapter task panicked"),
+                Ok(Ok(())) => info!("dashboard closed, shutting down"),
+                Ok(Err(err)) => warn!(error = %err, "dashboard failed"),
+                Err(err) => warn!(error = %err, "dashboard task panicked"),
             }
         }
-        res = router_handle => {
-            match res {
-                Ok(Ok(())) => warn!("router task exited"),
-                Ok(Err(err)) => warn!(error = %err, "router task returned error"),
-                Err(err) => warn!(error = %err, "router task panicked"),
-            }
+        signal = shutdown_signal() => {
+            info!(signal, "shutting down");
         }
-        res = strategy_handle => {
-            match res {
-                Ok(()) => warn!("strategy engine exited"),
-                Err(err) => warn!(error = %err, "strategy engine task panicked"),
-            }
+    }
+
+    // ── Graceful shutdown ────────────────────────────────────────
+    // Stop new signals, let the in-flight order report, cancel anything
+    // resting, then flush snapshots, persistence and metrics.
+    running.shutdown().await;
+
+    match tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, operator.cancel_all()).await {
+        Ok(Ok(0)) => {}
+        Ok(Ok(canceled)) => info!(canceled, "resting orders cancelled at shutdown"),
+        Ok(Err(e)) => error!(error = %format!("{e:#}"), "cancel-all at shutdown failed; check the venue for resting orders"),
+        Err(_) => error!("cancel-all at shutdown timed out; check the venue for resting orders"),
+    }
+
+    if let Err(e) = write_snapshot_files(&portfolio, std::path::Path::new(SNAPSHOT_DIR)).await {
+        warn!(error = %e, "final portfolio snapshot failed");
+    }
+
+    // Writers finish once every recorder clone is gone and their queues are empty.
+    drop(recorder);
+    for handle in writer_handles {
+        if tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, handle).await.is_err() {
+            warn!("persistence writer did not drain in time; the last records may be lost");
         }
-        res = exec_handle => {
-            match res {
-                Ok(()) => warn!("execution bridge exited"),
-                Err(err) => warn!(error = %err, "execution bridge task panicked"),
+    }
+    prediction_engine::metrics::prometheus::flush_metrics().await;
+    drop(running);
+    drop(venue_runtimes);
+    info!("shutdown complete");
+
+    Ok(())
+}
+
+/// Authenticate `signer` and hand out its client, re-fetching the key from
+/// `source` every `refresh` when it lives in a remote secret store.
+async fn start_trading_client(
+    signer: PrivateKeySigner,
+    source: &KeySource,
+    refresh: Option<Duration>,
+) -> Result<watch::Receiver<Arc<TradingClient>>> {
+    let (client_tx, client_rx) = watch::channel(Arc::new(trading_client_for(signer).await?));
+    if let (true, Some(interval)) = (source.is_remote(), refresh) {
+        tokio::spawn(run_key_rotation(source.clone(), interval, client_tx));
+    }
+    Ok(client_rx)
+}
+
+/// The adapters of every venue besides Polymarket that's configured to run,
+/// each sending over `tx`.
+async fn venue_adapters(
+    config: &config::Config,
+    tx: channel::Sender<EventBatch>,
+    health: &HealthRegistry,
+) -> Result<Vec<Box<dyn MarketDataAdapter>>> {
+    let mut adapters: Vec<Box<dyn MarketDataAdapter>> = Vec::new();
+
+    let kalshi_tickers = kalshi_tickers(&config.kalshi).await?;
+    if let Some(source) = config.kalshi.credentials.as_ref().filter(|_| !kalshi_tickers.is_empty()) {
+        let credentials = load_kalshi_credentials(source).await?;
+        let tickers = kalshi_tickers.len();
+        let task = kalshi::init_kalshi_adapter(tx.clone(), &credentials, kalshi_tickers, health.clone())?;
+        info!(tickers, "kalshi adapter configured");
+        adapters.push(Box::new(match &config.kalshi.ws_url {
+            Some(url) => task.with_ws_url(url),
+            None => task,
+        }));
+    }
+
+    if let Some(credentials) = config.betfair.credentials.as_ref().filter(|_| !config.betfair.markets.is_empty()) {
+        let task = betfair::init_betfair_adapter(
+            tx.clone(),
+            credentials.clone(),
+            config.betfair.markets.clone(),
+            health.clone(),
+        )?;
+        info!(markets = config.betfair.markets.len(), "betfair adapter configured");
+        adapters.push(Box::new(match &config.betfair.stream_url {
+            Some(url) => task.with_stream_url(url),
+            None => task,
+        }));
+    }
+
+    if !config.pollers.is_empty() {
+        let task = rest_poller::init_rest_poller(tx.clone(), config.pollers.clone(), health.clone())?;
+        info!(feeds = task.feeds(), "rest poller configured");
+        adapters.push(Box::new(task));
+    }
+
+    if let Some(rpc_url) = config.amm.rpc_url.as_ref().filter(|_| !config.amm.pools.is_empty()) {
+        let task = amm::init_amm_adapter(tx, rpc_url, config.amm.pools.clone(), health.clone())?;
+        info!(pools = task.pools(), "amm adapter configured");
+        adapters.push(Box::new(match config.amm.quote_size {
+            Some(size) => task.with_quote_size(size),
+            None => task,
+        }));
+    }
+
+    Ok(adapters)
+}
+
+/// The Kalshi tickers to stream: the configured ones, then whatever
+/// discovery keeps when it's on.
+async fn kalshi_tickers(settings: &config::KalshiSettings) -> Result<Vec<String>> {
+    let mut tickers = settings.markets.clone();
+    if let Some(selection) = &settings.discovery {
+        let rest_url = settings.rest_url.as_deref().unwrap_or(kalshi::KALSHI_REST_URL);
+        let discovered = kalshi::discover(rest_url, selection).await?;
+        let before = tickers.len();
+        for market in discovered {
+            if !tickers.contains(&market.info.market_id) {
+                tickers.push(market.info.market_id);
             }
         }
-        _ = tokio::signal::ctrl_c() => {
-            info!("received Ctrl-C, shutting down");
+        info!(pinned = before, discovered = tickers.len() - before, "kalshi markets discovered");
+        if tickers.is_empty() {
+            warn!("Kalshi discovery found no markets; the Kalshi adapter won't run");
+        }
+    }
+    Ok(tickers)
+}
+
+/// Read the Kalshi API key from a PEM file or a remote secret.
+async fn load_kalshi_credentials(source: &config::KalshiCredentialSource) -> Result<KalshiCredentials> {
+    match source {
+        config::KalshiCredentialSource::File { api_key_id, private_key } => {
+            let pem = std::fs::read_to_string(private_key)
+                .map_err(|e| anyhow::anyhow!("reading Kalshi private key {}: {e}", private_key.display()))?;
+            Ok(KalshiCredentials { api_key_id: api_key_id.clone(), private_key: pem.into() })
         }
+        config::KalshiCredentialSource::Secret { store, id } => fetch_kalshi_credentials(*store, id).await,
     }
+}
 
-    Ok(())
+fn notify_config(settings: &config::NotifySettings) -> Result<NotifyConfig> {
+    let mut channels = Vec::new();
+    if let Some((bot_token, chat_id)) = &settings.telegram {
+        channels.push(Channel::Telegram { bot_token: bot_token.clone(), chat_id: chat_id.clone() });
+    }
+    if let Some(url) = &settings.discord_webhook {
+        channels.push(Channel::Discord { webhook_url: url.clone() });
+    }
+    if let Some(url) = &settings.slack_webhook {
+        channels.push(Channel::Slack { webhook_url: url.clone() });
+    }
+    let enabled = settings
+        .events
+        .iter()
+        .map(|name| EventKind::from_name(name).ok_or_else(|| anyhow::anyhow!("unknown NOTIFY_EVENTS entry '{name}'")))
+        .collect::<Result<_>>()?;
+    Ok(NotifyConfig { channels, enabled, max_per_minute: settings.max_per_minute })
 }
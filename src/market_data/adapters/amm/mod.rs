@@ -0,0 +1,196 @@
+//! Prices from on-chain AMM pools of outcome tokens.
+//!
+//! Each [`AmmPool`] is a two-token constant-product pool (the Uniswap V2
+//! interface: `getReserves()` and a `Sync` event on every reserve change)
+//! holding one market's YES and NO tokens. The adapter reads the reserves
+//! over an Ethereum JSON-RPC WebSocket (Polygon, say) and turns every change
+//! into a synthetic top of book for both outcomes; see [`pricing`] for how.
+//! Pools can carry the market and token ids of the same market on a CLOB
+//! venue, so the two books sit side by side in the market cache, told apart
+//! by `Venue::Amm`.
+
+pub mod pricing;
+mod rpc;
+
+use rpc::{run_rpc_loop, HEALTH_COMPONENT};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::runtime::Handle;
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::keepalive::KeepaliveConfig;
+use crate::market_data::adapters::traits::{MarketDataAdapter, StopSignal};
+use crate::market_data::types::{EventBatch, Venue};
+use crate::metrics::tasks;
+
+/// Outcome tokens quoted when no size is configured.
+pub const DEFAULT_QUOTE_SIZE: f64 = 100.0;
+
+/// One pool and the market whose outcome tokens it holds.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AmmPool {
+    /// The pool contract, `0x…`.
+    pub address: String,
+    pub market_id: String,
+    pub yes_token_id: String,
+    pub no_token_id: String,
+    /// Which of the pool's reserves is YES: 0 (`token0`) or 1.
+    #[serde(default)]
+    pub yes_index: u8,
+    /// Decimals of the outcome tokens.
+    #[serde(default = "default_decimals")]
+    pub decimals: u32,
+    /// Swap fee in basis points.
+    #[serde(default = "default_fee_bps")]
+    pub fee_bps: u32,
+}
+
+fn default_decimals() -> u32 {
+    6
+}
+
+fn default_fee_bps() -> u32 {
+    30
+}
+
+impl AmmPool {
+    /// Check the parts the config's types don't.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let hex = self.address.strip_prefix("0x").unwrap_or_default();
+        anyhow::ensure!(
+            hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            "address '{}' isn't a 0x-prefixed 20-byte address", self.address,
+        );
+        anyhow::ensure!(self.yes_index <= 1, "yes_index must be 0 or 1 (got {})", self.yes_index);
+        anyhow::ensure!(self.decimals <= 30, "decimals must be at most 30 (got {})", self.decimals);
+        anyhow::ensure!(self.fee_bps < 10_000, "fee_bps must be below 10000 (got {})", self.fee_bps);
+        anyhow::ensure!(
+            !self.market_id.is_empty() && !self.yes_token_id.is_empty() && !self.no_token_id.is_empty(),
+            "market_id, yes_token_id and no_token_id must be set",
+        );
+        Ok(())
+    }
+
+    fn fee(&self) -> f64 {
+        f64::from(self.fee_bps) / 10_000.0
+    }
+}
+
+/// Everything one run of the AMM adapter loop needs. Each
+/// [`run`](Self::run) opens a fresh RPC connection and reads every pool's
+/// reserves afresh.
+#[derive(Clone)]
+pub struct AmmAdapterTask {
+    tx: channel::Sender<EventBatch>,
+    pools: Vec<AmmPool>,
+    rpc_url: String,
+    quote_size: f64,
+    health: HealthRegistry,
+    /// Runtime to read the socket on; the adapter's own when `None`.
+    reader: Option<Handle>,
+    keepalive: KeepaliveConfig,
+    stop: StopSignal,
+}
+
+impl AmmAdapterTask {
+    /// Read the WebSocket on `reader` (e.g. a venue thread of its own)
+    /// rather than alongside the parser.
+    pub fn with_reader_runtime(mut self, reader: Handle) -> Self {
+        self.reader = Some(reader);
+        self
+    }
+
+    /// Ping the node on `keepalive`'s schedule instead of the default one.
+    pub fn with_keepalive(mut self, keepalive: KeepaliveConfig) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Quote `size` outcome tokens a side instead of [`DEFAULT_QUOTE_SIZE`].
+    pub fn with_quote_size(mut self, size: f64) -> Self {
+        self.quote_size = size;
+        self
+    }
+
+    pub fn pools(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn run(&self) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + use<> {
+        let rpc = run_rpc_loop(
+            self.tx.clone(),
+            self.pools.clone(),
+            self.rpc_url.clone(),
+            self.quote_size,
+            self.health.clone(),
+            self.reader.clone(),
+            self.keepalive,
+        );
+        async move {
+            tasks::instrument("adapter.amm.rpc", rpc).await;
+            anyhow::bail!("AMM RPC WebSocket gave up reconnecting")
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataAdapter for AmmAdapterTask {
+    fn venue(&self) -> Venue {
+        Venue::Amm
+    }
+
+    fn name(&self) -> &'static str {
+        "adapter.amm"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        self.stop.run_until_stopped(self.run()).await
+    }
+
+    async fn shutdown(&self) {
+        self.stop.stop();
+    }
+
+    fn set_reader_runtime(&mut self, reader: Handle) -> bool {
+        self.reader = Some(reader);
+        true
+    }
+
+    fn set_keepalive(&mut self, keepalive: KeepaliveConfig) {
+        self.keepalive = keepalive;
+    }
+}
+
+/// Initialise the AMM adapter for `pools`, read over the JSON-RPC
+/// WebSocket at `rpc_url` (`wss://…`). Each pool is checked with
+/// [`AmmPool::validate`]. Market events are sent over `tx`; connection
+/// state is reported to `health` as `adapter.amm`.
+pub fn init_amm_adapter(
+    tx: channel::Sender<EventBatch>,
+    rpc_url: &str,
+    pools: Vec<AmmPool>,
+    health: HealthRegistry,
+) -> anyhow::Result<AmmAdapterTask> {
+    anyhow::ensure!(!pools.is_empty(), "no AMM pools to read");
+    for (i, pool) in pools.iter().enumerate() {
+        pool.validate().map_err(|e| anyhow::anyhow!("pool {}: {e}", pool.address))?;
+        anyhow::ensure!(
+            pools[..i].iter().all(|p| !p.address.eq_ignore_ascii_case(&pool.address)),
+            "pool {} is listed twice", pool.address,
+        );
+    }
+    health.register(HEALTH_COMPONENT);
+    Ok(AmmAdapterTask {
+        tx,
+        pools,
+        rpc_url: rpc_url.to_string(),
+        quote_size: DEFAULT_QUOTE_SIZE,
+        health,
+        reader: None,
+        keepalive: KeepaliveConfig::default(),
+        stop: StopSignal::default(),
+    })
+}
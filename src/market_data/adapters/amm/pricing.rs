@@ -0,0 +1,40 @@
+//! Quotes from a constant-product pool of outcome tokens.
+//!
+//! The pool holds `yes` YES and `no` NO tokens with `yes × no` constant
+//! across swaps. Collateral reaches it through complete sets: a dollar mints
+//! (or merging redeems) one YES and one NO. So buying YES for collateral is
+//! minting sets and swapping their NO into the pool for more YES, and
+//! selling YES is swapping part of it for NO and merging the pairs. That is
+//! the fixed-product market maker's arithmetic, so the quotes hold for those
+//! pools as well as for plain YES/NO pairs.
+//!
+//! The marginal YES price is `no / (yes + no)`. A synthetic top of book
+//! prices a whole quote size instead, so it shows the slippage a taker of
+//! that size would pay, with the pool's swap fee taken on what's swapped in.
+
+/// A two-sided quote for one outcome, as average prices over the size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub bid: f64,
+    pub ask: f64,
+}
+
+/// The quote for `size` YES tokens from a pool holding `yes` and `no`
+/// (token units), charging `fee` (e.g. 0.003) on swaps. Swap the reserves
+/// for the NO side's quote. `None` for an empty pool or a non-positive size.
+pub fn quote(yes: f64, no: f64, size: f64, fee: f64) -> Option<Quote> {
+    if !(yes > 0.0 && no > 0.0 && size > 0.0) {
+        return None;
+    }
+    let g = 1.0 - fee;
+    // Buying: mint c sets and swap their c NO in, for c × g net of the fee:
+    // size = c + yes × c·g / (no + c·g), solved for c.
+    let b = no + yes * g - size * g;
+    let cost = (-b + (b * b + 4.0 * g * size * no).sqrt()) / (2.0 * g);
+    // Selling: swap s YES in for NO, then merge the remaining pairs:
+    // size − s = no × s·g / (yes + s·g), and the proceeds are size − s.
+    let b = yes + no * g - size * g;
+    let swapped = (-b + (b * b + 4.0 * g * size * yes).sqrt()) / (2.0 * g);
+    let proceeds = size - swapped;
+    Some(Quote { bid: (proceeds / size).clamp(0.0, 1.0), ask: (cost / size).clamp(0.0, 1.0) })
+}
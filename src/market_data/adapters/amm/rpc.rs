@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::pool;
+use crate::market_data::types::{EventBatch, MarketEventKind, Venue};
+use crate::metrics::prometheus::record_adapter_event;
+use crate::ring;
+use crate::market_data::adapters::keepalive::{Keepalive, KeepaliveConfig};
+use crate::market_data::adapters::reconnect::{backoff_duration, MAX_RECONNECT_ATTEMPTS};
+use super::pricing::quote;
+use super::AmmPool;
+
+pub(super) const HEALTH_COMPONENT: &str = "adapter.amm";
+
+/// Raw frames the socket reader may run ahead of the parser.
+const FRAME_RING_CAPACITY: usize = 4_096;
+
+/// `keccak256("Sync(uint112,uint112)")`, topic 0 of every reserve change.
+const SYNC_TOPIC: &str = "0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1";
+
+/// Selector of `getReserves()`.
+const GET_RESERVES: &str = "0x0902f1ac";
+
+/// Id of the `eth_subscribe` request; `eth_call`s for pool `i` use
+/// `FIRST_CALL_ID + i`.
+const SUBSCRIBE_ID: u64 = 1;
+/// Id of the keepalive's `net_version` requests.
+const PING_ID: u64 = 2;
+const FIRST_CALL_ID: u64 = 100;
+
+/// Decimal places kept of a quoted price.
+const PRICE_DP: u32 = 4;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// ── Wire format ───────────────────────────────────────────────────────────────
+
+/// A JSON-RPC response or subscription notification.
+#[derive(Deserialize)]
+struct RpcMessage {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<RpcError>,
+    #[serde(default)]
+    params: Option<Notification>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct Notification {
+    result: Log,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Log {
+    address: String,
+    #[serde(default)]
+    topics: Vec<String>,
+    data: String,
+    /// Hex.
+    #[serde(default)]
+    block_number: Option<String>,
+    /// Undone by a reorg.
+    #[serde(default)]
+    removed: bool,
+}
+
+/// What's known of a pool on the current connection.
+#[derive(Default)]
+struct PoolState {
+    /// Reserves as `(token0, token1)`, raw.
+    reserves: Option<(u128, u128)>,
+    /// A `Sync` has arrived, so a `getReserves` answer may be older.
+    synced_by_log: bool,
+}
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+/// Run the JSON-RPC loop until it gives up reconnecting.
+///
+/// Each connection subscribes to the `Sync` logs of every pool and reads
+/// each pool's current reserves with `getReserves()`. A reply that arrives
+/// after the pool's first `Sync` is dropped, since the log is newer. Every
+/// reserve change is sent over `tx` as a `TopOfBook` for YES and for NO,
+/// quoted for `quote_size` tokens a side ([`quote`]); a pool emptied of
+/// either token sends `BookStale` instead. A log undone by a reorg has the
+/// reserves read again. Connection state is reported to `health` as
+/// [`HEALTH_COMPONENT`]. When the stream ends every token is sent a
+/// `Resync` before reconnecting.
+///
+/// As with the venue WebSockets, the socket is read on a task of its own
+/// (on `reader_runtime` when set) that hands frames to this loop over an
+/// SPSC [`ring`], and pinged on `keepalive`'s schedule, here with a
+/// `net_version` request, which a node that has stalled stops answering
+/// even while its socket stays open.
+pub(super) async fn run_rpc_loop(
+    tx: channel::Sender<EventBatch>,
+    pools: Vec<AmmPool>,
+    rpc_url: String,
+    quote_size: f64,
+    health: HealthRegistry,
+    reader_runtime: Option<Handle>,
+    keepalive: KeepaliveConfig,
+) {
+    let mut attempt: u32 = 0;
+    // Lowercased address → index into `pools`.
+    let by_address: HashMap<String, usize> =
+        pools.iter().enumerate().map(|(i, p)| (p.address.to_ascii_lowercase(), i)).collect();
+
+    loop {
+        attempt += 1;
+        info!(attempt, "connecting to AMM RPC WebSocket");
+
+        let open = {
+            let (pools, rpc_url) = (pools.clone(), rpc_url.clone());
+            async move { connect(&rpc_url, &pools).await }
+        };
+        let connected = match &reader_runtime {
+            Some(runtime) => runtime
+                .spawn(open)
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("reader runtime: {e}"))),
+            None => open.await,
+        };
+
+        let socket = match connected {
+            Ok(socket) => {
+                info!(pools = pools.len(), "AMM RPC WebSocket connected");
+                health.set_up(HEALTH_COMPONENT);
+                attempt = 0;
+                socket
+            }
+            Err(e) => {
+                if attempt >= MAX_RECONNECT_ATTEMPTS {
+                    error!(error = %e, attempts = attempt, "max AMM RPC reconnect attempts reached");
+                    health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+                    return;
+                }
+                let backoff = backoff_duration(attempt);
+                warn!(error = %e, attempt, backoff_ms = backoff.as_millis() as u64, "AMM RPC connection failed, retrying");
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+        };
+
+        // Dropping the set when this connection ends stops its reader.
+        let (mut sink, mut stream) = socket.split();
+        let (mut producer, mut consumer) = ring::ring(FRAME_RING_CAPACITY);
+        let mut reader = JoinSet::new();
+        let read = async move {
+            while let Some(message) = stream.next().await {
+                match message {
+                    Ok(Message::Text(text)) => {
+                        if producer.push((Instant::now(), text)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(Message::Close(frame)) => {
+                        info!(?frame, "AMM RPC WebSocket closed");
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(error = %e, "AMM RPC WebSocket stream error");
+                        break;
+                    }
+                }
+            }
+        };
+        match &reader_runtime {
+            Some(runtime) => reader.spawn_on(read, runtime),
+            None => reader.spawn(read),
+        };
+
+        let mut states: Vec<PoolState> = pools.iter().map(|_| PoolState::default()).collect();
+        let mut pings = Keepalive::new(Venue::Amm, keepalive);
+        loop {
+            let (received_at, text) = tokio::select! {
+                frame = consumer.pop() => match frame {
+                    Some(frame) => frame,
+                    None => break,
+                },
+                due = pings.ping_due() => {
+                    if let Err(e) = due {
+                        warn!(error = %e, "AMM RPC WebSocket unresponsive, reconnecting");
+                        break;
+                    }
+                    let ping = json!({ "jsonrpc": "2.0", "id": PING_ID, "method": "net_version", "params": [] });
+                    if let Err(e) = sink.send(Message::Text(ping.to_string())).await {
+                        warn!(error = %e, "AMM RPC ping failed");
+                    }
+                    continue;
+                }
+            };
+            let message = match serde_json::from_str::<RpcMessage>(&text) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!(error = %e, "unparseable AMM RPC message");
+                    continue;
+                }
+            };
+            if message.id == Some(PING_ID) {
+                pings.pong(received_at);
+                continue;
+            }
+            let Some(i) = reserves_update(&message, &by_address, &mut states) else {
+                if let Some(reread) = reorged_pool(&message, &by_address) {
+                    warn!(pool = %pools[reread].address, "AMM pool log removed by a reorg, rereading reserves");
+                    states[reread].synced_by_log = false;
+                    if let Err(e) = sink.send(Message::Text(get_reserves(reread, &pools[reread]).to_string())).await {
+                        warn!(error = %e, "AMM getReserves request failed");
+                    }
+                }
+                continue;
+            };
+            let (pool, (reserve0, reserve1)) = (&pools[i], states[i].reserves.unwrap_or_default());
+            let mut batch = pool::batch();
+            push_quotes(pool, reserve0, reserve1, quote_size, received_at, &mut batch);
+            if tx.send(batch).await.is_err() {
+                warn!("market event channel closed");
+            }
+        }
+
+        // Stream ended — mark every token for resync, then reconnect.
+        health.set_down(HEALTH_COMPONENT, "stream ended, reconnecting");
+        let tokens = pools.iter().flat_map(|p| {
+            [(p.market_id.as_str(), p.yes_token_id.as_str()), (p.market_id.as_str(), p.no_token_id.as_str())]
+        });
+        if tx.send(pool::notices(Venue::Amm, MarketEventKind::Resync, tokens)).await.is_err() {
+            warn!("market event channel closed");
+        }
+        if attempt >= MAX_RECONNECT_ATTEMPTS {
+            error!(attempts = attempt, "max AMM RPC reconnect attempts reached");
+            health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+            return;
+        }
+        let backoff = backoff_duration(attempt);
+        warn!(attempt, backoff_ms = backoff.as_millis() as u64, "AMM RPC stream ended, reconnecting");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Open the WebSocket, subscribe to every pool's `Sync` logs, and ask for
+/// each pool's current reserves.
+async fn connect(url: &str, pools: &[AmmPool]) -> anyhow::Result<Socket> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+    let addresses: Vec<&str> = pools.iter().map(|p| p.address.as_str()).collect();
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": SUBSCRIBE_ID,
+        "method": "eth_subscribe",
+        "params": ["logs", { "address": addresses, "topics": [[SYNC_TOPIC]] }],
+    });
+    socket.send(Message::Text(subscribe.to_string())).await?;
+    for (i, pool) in pools.iter().enumerate() {
+        socket.send(Message::Text(get_reserves(i, pool).to_string())).await?;
+    }
+    Ok(socket)
+}
+
+/// An `eth_call` of pool `i`'s `getReserves()`.
+fn get_reserves(i: usize, pool: &AmmPool) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": FIRST_CALL_ID + i as u64,
+        "method": "eth_call",
+        "params": [{ "to": pool.address, "data": GET_RESERVES }, "latest"],
+    })
+}
+
+// ── Event handlers ────────────────────────────────────────────────────────────
+
+/// Record the reserves `message` carries, a `Sync` log or a `getReserves`
+/// reply, returning the pool whose reserves changed. Errors and the
+/// subscription's acknowledgement are only logged.
+fn reserves_update(message: &RpcMessage, by_address: &HashMap<String, usize>, states: &mut [PoolState]) -> Option<usize> {
+    if let Some(error) = &message.error {
+        warn!(id = ?message.id, code = error.code, error = %error.message, "AMM RPC error");
+        return None;
+    }
+    if let Some(Notification { result: log }) = &message.params {
+        if log.removed || log.topics.first().is_none_or(|t| !t.eq_ignore_ascii_case(SYNC_TOPIC)) {
+            return None;
+        }
+        let i = *by_address.get(&log.address.to_ascii_lowercase())?;
+        let Some(reserves) = decode_reserves(&log.data) else {
+            warn!(pool = %log.address, "malformed Sync log");
+            return None;
+        };
+        debug!(pool = %log.address, block = ?log.block_number, "AMM reserves changed");
+        states[i].reserves = Some(reserves);
+        states[i].synced_by_log = true;
+        return Some(i);
+    }
+    match message.id? {
+        SUBSCRIBE_ID => {
+            info!(subscription = ?message.result, "AMM pool logs subscribed");
+            None
+        }
+        id => {
+            let i = usize::try_from(id.checked_sub(FIRST_CALL_ID)?).ok().filter(|i| *i < states.len())?;
+            if states[i].synced_by_log {
+                return None; // a Sync since the call is newer
+            }
+            let reserves = message.result.as_ref().and_then(Value::as_str).and_then(decode_reserves);
+            if reserves.is_none() {
+                warn!(id, "malformed getReserves reply");
+            }
+            states[i].reserves = reserves;
+            reserves.map(|_| i)
+        }
+    }
+}
+
+/// The pool of a `Sync` log a reorg removed, if that's what `message` is.
+fn reorged_pool(message: &RpcMessage, by_address: &HashMap<String, usize>) -> Option<usize> {
+    let log = &message.params.as_ref()?.result;
+    if !log.removed {
+        return None;
+    }
+    by_address.get(&log.address.to_ascii_lowercase()).copied()
+}
+
+/// A `TopOfBook` for each outcome of `pool`, or a `BookStale` for each
+/// when the pool is empty on either side.
+fn push_quotes(pool: &AmmPool, reserve0: u128, reserve1: u128, size: f64, received_at: Instant, batch: &mut EventBatch) {
+    let scale = 10f64.powi(pool.decimals as i32);
+    let (token0, token1) = (reserve0 as f64 / scale, reserve1 as f64 / scale);
+    let (yes, no) = if pool.yes_index == 0 { (token0, token1) } else { (token1, token0) };
+    for (token, own, other) in [(&pool.yes_token_id, yes, no), (&pool.no_token_id, no, yes)] {
+        let kind = match quote(own, other, size, pool.fee()) {
+            Some(q) => MarketEventKind::TopOfBook { bid_price: q.bid, bid_size: size, ask_price: q.ask, ask_size: size },
+            None => MarketEventKind::BookStale,
+        };
+        record_adapter_event(Venue::Amm.name(), kind.name());
+        let (bid, ask) = match &kind {
+            MarketEventKind::TopOfBook { bid_price, ask_price, .. } => (price(*bid_price), price(*ask_price)),
+            _ => (None, None),
+        };
+        let mut event = pool::event(Venue::Amm, kind, &pool.market_id, token, received_at);
+        event.ts_receive_ms = Some(SystemTime::now());
+        event.parsed_at = Some(Instant::now());
+        event.best_bid = bid;
+        event.best_ask = ask;
+        batch.push(event);
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// The first two 32-byte words of ABI-encoded `data`, as `uint112`s.
+fn decode_reserves(data: &str) -> Option<(u128, u128)> {
+    let hex = data.strip_prefix("0x")?;
+    let word = |i: usize| -> Option<u128> {
+        let word = hex.get(i * 64..(i + 1) * 64)?;
+        let (high, low) = word.split_at(32);
+        high.bytes().all(|b| b == b'0').then_some(())?;
+        u128::from_str_radix(low, 16).ok()
+    };
+    Some((word(0)?, word(1)?))
+}
+
+fn price(value: f64) -> Option<Decimal> {
+    Decimal::from_f64_retain(value).map(|d| d.round_dp(PRICE_DP))
+}
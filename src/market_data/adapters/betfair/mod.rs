@@ -0,0 +1,158 @@
+//! Betfair Exchange market data over the Exchange Stream API.
+//!
+//! Betfair markets aren't binary: a match-odds market has one selection
+//! (runner) per team plus the draw, each traded on its own. Each selection
+//! is mapped to a binary outcome of its own, "this selection wins", with the
+//! Betfair market id as the market id and [`selection_token`] as the token
+//! id. Back and lay offers in decimal odds become YES asks and bids in
+//! [0, 1]; see [`stream`](self::stream) for the conversion.
+
+mod stream;
+
+use stream::{run_stream_loop, HEALTH_COMPONENT};
+
+use std::path::PathBuf;
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::traits::{MarketDataAdapter, StopSignal};
+use crate::market_data::subscriptions::{SubscriptionControl, Subscriptions};
+use crate::market_data::types::{EventBatch, Venue};
+use crate::metrics::tasks;
+
+/// Betfair's production Exchange Stream API.
+pub const BETFAIR_STREAM_URL: &str = "tls://stream-api.betfair.com:443";
+
+/// What the stream authenticates with.
+#[derive(Debug, Clone)]
+pub struct BetfairCredentials {
+    /// The application key the session was created for.
+    pub app_key: String,
+    /// A file holding the session token (`ssoid`). It's read on every
+    /// connect, so whatever keeps the session logged in can rewrite it
+    /// without restarting the adapter.
+    pub session_token_file: PathBuf,
+}
+
+/// The token id of one selection of `market_id`. Asian handicap markets
+/// list a selection once per handicap, which is then part of the id.
+pub fn selection_token(market_id: &str, selection_id: u64, handicap: Option<f64>) -> String {
+    match handicap.filter(|hc| *hc != 0.0) {
+        Some(hc) => format!("{market_id}:{selection_id}:{hc}"),
+        None => format!("{market_id}:{selection_id}"),
+    }
+}
+
+/// Everything one run of the Betfair adapter loop needs. Each
+/// [`run`](Self::run) opens a fresh connection, and the ladders are rebuilt
+/// from the images Betfair sends on subscribe.
+#[derive(Clone)]
+pub struct BetfairAdapterTask {
+    tx: channel::Sender<EventBatch>,
+    credentials: BetfairCredentials,
+    /// Markets to stream, changed at runtime through [`subscriptions`](Self::subscriptions).
+    subscriptions: Subscriptions,
+    health: HealthRegistry,
+    /// Runtime to read the connection on; the adapter's own when `None`.
+    reader: Option<Handle>,
+    stop: StopSignal,
+    stream_url: String,
+}
+
+impl BetfairAdapterTask {
+    /// Read the connection on `reader` (e.g. a venue thread of its own)
+    /// rather than alongside the parser.
+    pub fn with_reader_runtime(mut self, reader: Handle) -> Self {
+        self.reader = Some(reader);
+        self
+    }
+
+    /// Stream from `url` instead of Betfair's production endpoint, e.g. the
+    /// integration environment. `tls://host:port`, or `tcp://host:port`
+    /// for a plaintext stub.
+    pub fn with_stream_url(mut self, url: &str) -> Self {
+        self.stream_url = url.to_string();
+        self
+    }
+
+    /// Add and remove markets while the adapter runs. Subscriptions are by
+    /// Betfair market id, which is both the market id and the token id in
+    /// the set; events carry each selection's own token id.
+    pub fn subscriptions(&self) -> SubscriptionControl {
+        self.subscriptions.control()
+    }
+
+    pub fn run(&self) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + use<> {
+        let stream = run_stream_loop(
+            self.tx.clone(),
+            self.credentials.clone(),
+            self.subscriptions.clone(),
+            self.stream_url.clone(),
+            self.health.clone(),
+            self.reader.clone(),
+        );
+        async move {
+            tasks::instrument("adapter.betfair.stream", stream).await;
+            anyhow::bail!("Betfair stream gave up reconnecting")
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataAdapter for BetfairAdapterTask {
+    fn venue(&self) -> Venue {
+        Venue::Betfair
+    }
+
+    fn name(&self) -> &'static str {
+        "adapter.betfair"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        self.stop.run_until_stopped(self.run()).await
+    }
+
+    async fn shutdown(&self) {
+        self.stop.stop();
+    }
+
+    fn subscription_control(&self) -> Option<SubscriptionControl> {
+        Some(self.subscriptions.control())
+    }
+
+    fn set_reader_runtime(&mut self, reader: Handle) -> bool {
+        self.reader = Some(reader);
+        true
+    }
+}
+
+/// Initialise the Betfair adapter for `market_ids` (e.g. `1.234567890`).
+///
+/// Reads the session token file once up front, so a missing file fails at
+/// startup rather than on the first connect. Market events are sent over
+/// `tx`, one binary outcome per selection; connection state is reported to
+/// `health` as `adapter.betfair`.
+pub fn init_betfair_adapter(
+    tx: channel::Sender<EventBatch>,
+    credentials: BetfairCredentials,
+    market_ids: Vec<String>,
+    health: HealthRegistry,
+) -> anyhow::Result<BetfairAdapterTask> {
+    anyhow::ensure!(!market_ids.is_empty(), "no Betfair markets to subscribe to");
+    std::fs::read_to_string(&credentials.session_token_file).map_err(|e| {
+        anyhow::anyhow!("reading Betfair session token {}: {e}", credentials.session_token_file.display())
+    })?;
+    health.register(HEALTH_COMPONENT);
+    let subscriptions = Subscriptions::new(Venue::Betfair, market_ids.into_iter().map(|id| (id.clone(), id)));
+    Ok(BetfairAdapterTask {
+        tx,
+        credentials,
+        subscriptions,
+        health,
+        reader: None,
+        stop: StopSignal::default(),
+        stream_url: BETFAIR_STREAM_URL.to_string(),
+    })
+}
@@ -0,0 +1,633 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::task::JoinSet;
+use tokio_native_tls::{native_tls, TlsConnector};
+use tracing::{debug, error, info, warn};
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::pool;
+use crate::market_data::subscriptions::{SubscriptionChange, Subscriptions};
+use crate::market_data::types::{BookLevel, EventBatch, MarketEvent, MarketEventKind, Venue};
+use crate::metrics::prometheus::record_adapter_event;
+use crate::ring;
+use crate::market_data::adapters::reconnect::{backoff_duration, MAX_RECONNECT_ATTEMPTS};
+use super::{selection_token, BetfairCredentials};
+
+pub(super) const HEALTH_COMPONENT: &str = "adapter.betfair";
+
+/// Raw lines the connection reader may run ahead of the parser.
+const FRAME_RING_CAPACITY: usize = 4_096;
+
+/// Price levels streamed per side; Betfair allows up to 10.
+const LADDER_LEVELS: u32 = 5;
+
+/// How often Betfair sends a heartbeat on an otherwise idle stream.
+const HEARTBEAT_MS: u64 = 5_000;
+
+/// Best offers, last traded price, and the market definition (status and
+/// selections). Traded volume isn't asked for: it doesn't say which side
+/// took, so it can't become a `Trade`.
+const FIELDS: [&str; 3] = ["EX_BEST_OFFERS", "EX_LTP", "EX_MARKET_DEF"];
+
+/// Decimal places kept of a price converted from odds.
+const PRICE_DP: u32 = 4;
+
+/// A byte stream to the Stream API, over TLS or not.
+trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+/// The write half of a connection, kept by the loop for subscription changes.
+type Writer = WriteHalf<Box<dyn Connection>>;
+
+// ── Wire format ───────────────────────────────────────────────────────────────
+
+/// One CRLF-terminated JSON message from the Stream API.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum StreamMessage {
+    Connection(ConnectionMsg),
+    Status(StatusMsg),
+    Mcm(MarketChangeMsg),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectionMsg {
+    #[serde(default)]
+    connection_id: Option<String>,
+}
+
+/// The answer to an `authentication` or `marketSubscription`, or notice of
+/// a connection the server is about to close.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusMsg {
+    #[serde(default)]
+    id: Option<u64>,
+    /// `SUCCESS` or `FAILURE`.
+    status_code: String,
+    #[serde(default)]
+    error_code: Option<String>,
+    #[serde(default)]
+    error_message: Option<String>,
+    #[serde(default)]
+    connection_closed: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketChangeMsg {
+    /// `SUB_IMAGE`, `RESUB_DELTA`, `HEARTBEAT`, or absent for a delta.
+    #[serde(default)]
+    ct: Option<String>,
+    /// Publish time, Unix milliseconds.
+    #[serde(default)]
+    pt: Option<u64>,
+    #[serde(default)]
+    mc: Vec<MarketChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketChange {
+    id: String,
+    /// A full image: replaces everything known about the market.
+    #[serde(default)]
+    img: bool,
+    #[serde(default)]
+    market_definition: Option<MarketDefinition>,
+    #[serde(default)]
+    rc: Vec<RunnerChange>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketDefinition {
+    /// `OPEN`, `SUSPENDED`, `CLOSED` or `INACTIVE`.
+    status: String,
+    #[serde(default)]
+    in_play: bool,
+    #[serde(default)]
+    runners: Vec<RunnerDefinition>,
+}
+
+#[derive(Deserialize)]
+struct RunnerDefinition {
+    id: u64,
+    #[serde(default)]
+    hc: Option<f64>,
+    /// `ACTIVE` while it can be traded; `REMOVED`, `WINNER`, `LOSER`, …
+    status: String,
+}
+
+/// Changes to one selection. Offers are `[level, odds, stake]`; a zero
+/// stake empties the level.
+#[derive(Deserialize)]
+struct RunnerChange {
+    id: u64,
+    #[serde(default)]
+    hc: Option<f64>,
+    /// Best available to back.
+    #[serde(default)]
+    batb: Option<Vec<(u32, f64, f64)>>,
+    /// Best available to lay.
+    #[serde(default)]
+    batl: Option<Vec<(u32, f64, f64)>>,
+    /// Last traded odds.
+    #[serde(default)]
+    ltp: Option<f64>,
+}
+
+// ── Local state ───────────────────────────────────────────────────────────────
+
+/// One selection's ladders, as `level → (odds, stake)`.
+#[derive(Default)]
+struct Runner {
+    back: BTreeMap<u32, (f64, f64)>,
+    lay: BTreeMap<u32, (f64, f64)>,
+    ltp: Option<f64>,
+    /// Removed, or settled as a winner or loser.
+    withdrawn: bool,
+}
+
+impl Runner {
+    /// The selection's YES book: lay offers are bids, back offers asks.
+    fn book(&self) -> (Vec<BookLevel>, Vec<BookLevel>) {
+        let mut bids: Vec<BookLevel> = self.lay.values().filter_map(|&(odds, stake)| level(odds, stake)).collect();
+        let mut asks: Vec<BookLevel> = self.back.values().filter_map(|&(odds, stake)| level(odds, stake)).collect();
+        bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+        asks.sort_by_key(|l| l.price);
+        (bids, asks)
+    }
+}
+
+#[derive(Default)]
+struct Market {
+    /// Anything but `OPEN`: no selection can be traded.
+    suspended: bool,
+    /// Selections by token id.
+    runners: HashMap<String, Runner>,
+}
+
+impl Market {
+    fn tradeable(&self, token: &str) -> bool {
+        !self.suspended && self.runners.get(token).is_some_and(|runner| !runner.withdrawn)
+    }
+}
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+/// Run the Betfair stream loop until it gives up reconnecting.
+///
+/// Each connection authenticates with `credentials` (reading the session
+/// token file afresh), subscribes to the best offers, last traded price and
+/// definition of every market, and converts what arrives into
+/// `MarketEvent`s sent over `tx`, one [`EventBatch`] per message.
+/// Connection state is reported to `health` as [`HEALTH_COMPONENT`]. When
+/// the stream ends every selection is sent a `Resync` before reconnecting.
+///
+/// Each selection is a binary outcome of its own, and offers are converted
+/// from decimal odds to YES prices:
+///
+/// - **available to back** at odds `o` for stake `s`: backing pays `s × o`
+///   for `s`, so it's an offer of `s × o` YES contracts at `1 / o`, an ask;
+/// - **available to lay** at odds `o` for stake `s`: laying takes `s` for a
+///   liability of `s × o`, so it's a bid for `s × o` contracts at `1 / o`.
+///
+/// Changed selections are emitted as a `BookSnapshot` of their ladder, which
+/// is only [`LADDER_LEVELS`] deep; a change to the last traded price alone
+/// is a `PriceChange`. While a market isn't `OPEN`, or once a selection is
+/// removed or settled, its selections are sent a `BookStale` and nothing
+/// more until they can be traded again, when their books are re-sent.
+///
+/// Subscription commands resend the market subscription, which Betfair
+/// replaces the old one with and answers with fresh images. Removed markets'
+/// selections are sent an `Unsubscribed`. As with the other venues, the
+/// connection is read on a task of its own (on `reader_runtime` when set)
+/// that hands lines to this loop over an SPSC [`ring`].
+pub(super) async fn run_stream_loop(
+    tx: channel::Sender<EventBatch>,
+    credentials: BetfairCredentials,
+    subscriptions: Subscriptions,
+    stream_url: String,
+    health: HealthRegistry,
+    reader_runtime: Option<Handle>,
+) {
+    let mut attempt: u32 = 0;
+    let mut markets: HashMap<String, Market> = HashMap::new();
+    let mut commands = subscriptions.commands().await;
+    // What the current connection is subscribed to: market id → market id.
+    let mut live: HashMap<String, String> = HashMap::new();
+
+    loop {
+        attempt += 1;
+        info!(attempt, "connecting to Betfair stream");
+
+        live.clear();
+        subscriptions.sync(&mut live);
+        while commands.try_recv().is_ok() {} // already in the set just synced
+
+        let open = {
+            let market_ids: Vec<String> = live.keys().cloned().collect();
+            let (credentials, stream_url) = (credentials.clone(), stream_url.clone());
+            async move { connect(&stream_url, &credentials, &market_ids).await }
+        };
+        let connected = match &reader_runtime {
+            Some(runtime) => runtime
+                .spawn(open)
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("reader runtime: {e}"))),
+            None => open.await,
+        };
+
+        let connection = match connected {
+            Ok(connection) => {
+                info!(markets = live.len(), "Betfair stream connected");
+                health.set_up(HEALTH_COMPONENT);
+                markets.clear(); // images start afresh on every subscribe
+                attempt = 0;
+                connection
+            }
+            Err(e) => {
+                if attempt >= MAX_RECONNECT_ATTEMPTS {
+                    error!(error = %e, attempts = attempt, "max Betfair stream reconnect attempts reached");
+                    health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+                    return;
+                }
+                let backoff = backoff_duration(attempt);
+                warn!(error = %e, attempt, backoff_ms = backoff.as_millis() as u64, "Betfair stream connection failed, retrying");
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+        };
+
+        // Dropping the set when this connection ends stops its reader.
+        let (read, mut writer) = tokio::io::split(connection);
+        let (mut producer, mut consumer) = ring::ring(FRAME_RING_CAPACITY);
+        let mut reader = JoinSet::new();
+        let read = async move {
+            let mut lines = BufReader::new(read).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        if producer.push((Instant::now(), line)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => {
+                        info!("Betfair stream closed");
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Betfair stream error");
+                        break;
+                    }
+                }
+            }
+        };
+        match &reader_runtime {
+            Some(runtime) => reader.spawn_on(read, runtime),
+            None => reader.spawn(read),
+        };
+
+        // Ids 1 and 2 went with the authentication and first subscription.
+        let mut next_id: u64 = 3;
+
+        loop {
+            let (received_at, line) = tokio::select! {
+                frame = consumer.pop() => match frame {
+                    Some(frame) => frame,
+                    None => break,
+                },
+                Some(_) = commands.recv() => {
+                    while commands.try_recv().is_ok() {}
+                    let change = subscriptions.sync(&mut live);
+                    apply_subscription_change(&mut writer, &change, &live, &mut next_id, &mut markets, &tx).await;
+                    continue;
+                }
+            };
+            let message = match serde_json::from_str::<StreamMessage>(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!(error = %e, "unparseable Betfair message");
+                    continue;
+                }
+            };
+            let mut batch = pool::batch();
+            let open = handle_message(&mut markets, &live, message, received_at, &mut batch);
+            if batch.is_empty() {
+                pool::recycle_batch(batch);
+            } else if tx.send(batch).await.is_err() {
+                warn!("market event channel closed");
+            }
+            if !open {
+                break;
+            }
+        }
+
+        // Stream ended — mark every selection for resync, then reconnect.
+        health.set_down(HEALTH_COMPONENT, "stream ended, reconnecting");
+        let selections = markets
+            .iter()
+            .filter(|(market_id, _)| live.contains_key(*market_id))
+            .flat_map(|(market_id, market)| market.runners.keys().map(move |token| (market_id.as_str(), token.as_str())));
+        if tx.send(pool::notices(Venue::Betfair, MarketEventKind::Resync, selections)).await.is_err() {
+            warn!("market event channel closed");
+        }
+        if attempt >= MAX_RECONNECT_ATTEMPTS {
+            error!(attempts = attempt, "max Betfair stream reconnect attempts reached");
+            health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+            return;
+        }
+        let backoff = backoff_duration(attempt);
+        warn!(attempt, backoff_ms = backoff.as_millis() as u64, "Betfair stream ended, reconnecting");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Connect to `url` (`tls://host:port` or `tcp://host:port`), authenticate,
+/// and subscribe to `market_ids`. With none there's nothing to subscribe
+/// to yet; an empty filter would match every market.
+async fn connect(url: &str, credentials: &BetfairCredentials, market_ids: &[String]) -> anyhow::Result<Box<dyn Connection>> {
+    let (scheme, address) = url
+        .split_once("://")
+        .with_context(|| format!("Betfair stream URL {url} needs a scheme, tls:// or tcp://"))?;
+    let tcp = TcpStream::connect(address).await.with_context(|| format!("connecting to {address}"))?;
+    tcp.set_nodelay(true)?;
+    let mut connection: Box<dyn Connection> = match scheme {
+        "tls" => {
+            let host = address.rsplit_once(':').map_or(address, |(host, _)| host);
+            let tls = TlsConnector::from(native_tls::TlsConnector::new()?);
+            Box::new(tls.connect(host, tcp).await?)
+        }
+        "tcp" => Box::new(tcp),
+        other => anyhow::bail!("unknown Betfair stream scheme '{other}' (expected tls or tcp)"),
+    };
+
+    let session = tokio::fs::read_to_string(&credentials.session_token_file)
+        .await
+        .with_context(|| format!("reading Betfair session token {}", credentials.session_token_file.display()))?;
+    let authentication = json!({
+        "op": "authentication",
+        "id": 1,
+        "appKey": credentials.app_key,
+        "session": session.trim(),
+    });
+    write_line(&mut connection, &authentication).await?;
+    if !market_ids.is_empty() {
+        let ids: Vec<&str> = market_ids.iter().map(String::as_str).collect();
+        write_line(&mut connection, &subscription(2, &ids)).await?;
+    }
+    Ok(connection)
+}
+
+/// A `marketSubscription` for `market_ids`.
+fn subscription(id: u64, market_ids: &[&str]) -> Value {
+    json!({
+        "op": "marketSubscription",
+        "id": id,
+        "heartbeatMs": HEARTBEAT_MS,
+        "marketFilter": { "marketIds": market_ids },
+        "marketDataFilter": { "fields": FIELDS, "ladderLevels": LADDER_LEVELS },
+    })
+}
+
+async fn write_line<W: AsyncWrite + Unpin + ?Sized>(writer: &mut W, message: &Value) -> std::io::Result<()> {
+    let mut line = message.to_string();
+    line.push_str("\r\n");
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await
+}
+
+/// Resubscribe to the markets in `live` after `change`, on the open
+/// connection. Removed markets are forgotten and an `Unsubscribed` sent
+/// for each of their selections. A failed write is only logged: the
+/// connection is gone, and the reconnect subscribes to the new set anyway.
+async fn apply_subscription_change(
+    writer: &mut Writer,
+    change: &SubscriptionChange,
+    live: &HashMap<String, String>,
+    next_id: &mut u64,
+    markets: &mut HashMap<String, Market>,
+    tx: &channel::Sender<EventBatch>,
+) {
+    if change.is_empty() {
+        return;
+    }
+    let market_ids: Vec<&str> = live.keys().map(String::as_str).collect();
+    if market_ids.is_empty() {
+        // The old subscription keeps streaming; its markets are ignored.
+        info!("no Betfair markets left to stream");
+    } else {
+        info!(markets = market_ids.len(), added = change.added.len(), removed = change.removed.len(), "resubscribing on the open Betfair stream");
+        let message = subscription(*next_id, &market_ids);
+        *next_id += 1;
+        if let Err(e) = write_line(writer, &message).await {
+            warn!(error = %e, "Betfair subscription change failed");
+        }
+    }
+    let mut removed: Vec<(String, String)> = Vec::new();
+    for s in &change.removed {
+        if let Some(market) = markets.remove(&s.market_id) {
+            removed.extend(market.runners.into_keys().map(|token| (s.market_id.clone(), token)));
+        }
+    }
+    if !removed.is_empty() {
+        let removed = removed.iter().map(|(market_id, token)| (market_id.as_str(), token.as_str()));
+        if tx.send(pool::notices(Venue::Betfair, MarketEventKind::Unsubscribed, removed)).await.is_err() {
+            warn!("market event channel closed");
+        }
+    }
+}
+
+// ── Event handlers ────────────────────────────────────────────────────────────
+
+/// Convert one message into events on `batch`, updating the markets on the
+/// way. Changes to markets not in `live` (unsubscribed, but still in the
+/// subscription Betfair is replacing) are ignored. Returns `false` once
+/// Betfair says it's closing the connection.
+fn handle_message(
+    markets: &mut HashMap<String, Market>,
+    live: &HashMap<String, String>,
+    message: StreamMessage,
+    received_at: Instant,
+    batch: &mut EventBatch,
+) -> bool {
+    match message {
+        StreamMessage::Connection(msg) => {
+            info!(connection_id = ?msg.connection_id, "Betfair stream opened");
+            true
+        }
+        StreamMessage::Status(msg) => {
+            if msg.status_code == "SUCCESS" {
+                debug!(id = ?msg.id, "Betfair request succeeded");
+            } else {
+                warn!(
+                    id = ?msg.id,
+                    code = ?msg.error_code,
+                    error = ?msg.error_message,
+                    "Betfair stream request failed (an expired session needs a new token in the session token file)"
+                );
+            }
+            !msg.connection_closed
+        }
+        StreamMessage::Mcm(msg) => {
+            if msg.ct.as_deref() == Some("HEARTBEAT") {
+                return true;
+            }
+            let exchange_ts = msg.pt.map(|ms| UNIX_EPOCH + Duration::from_millis(ms));
+            for change in msg.mc {
+                if live.contains_key(&change.id) {
+                    apply_market_change(markets, change, exchange_ts, received_at, batch);
+                }
+            }
+            true
+        }
+        StreamMessage::Other => true,
+    }
+}
+
+/// Apply one market's change and push events for the selections it moved.
+fn apply_market_change(
+    markets: &mut HashMap<String, Market>,
+    change: MarketChange,
+    exchange_ts: Option<SystemTime>,
+    received_at: Instant,
+    batch: &mut EventBatch,
+) {
+    let market_id = change.id;
+    let market = markets.entry(market_id.clone()).or_default();
+    if change.img {
+        for runner in market.runners.values_mut() {
+            runner.back.clear();
+            runner.lay.clear();
+            runner.ltp = None;
+        }
+    }
+
+    // Selections whose book to send, and those whose last traded price alone moved.
+    let mut books: Vec<String> = Vec::new();
+    let mut prices: Vec<String> = Vec::new();
+
+    if let Some(definition) = change.market_definition {
+        let before: HashMap<String, bool> =
+            market.runners.keys().map(|token| (token.clone(), market.tradeable(token))).collect();
+        market.suspended = definition.status != "OPEN";
+        for r in &definition.runners {
+            let token = selection_token(&market_id, r.id, r.hc);
+            market.runners.entry(token).or_default().withdrawn = r.status != "ACTIVE";
+        }
+        debug!(market = %market_id, status = %definition.status, in_play = definition.in_play, "Betfair market definition");
+        for token in market.runners.keys() {
+            let (was, is) = (before.get(token).copied().unwrap_or(false), market.tradeable(token));
+            if was && !is {
+                record_adapter_event("Betfair", "book_stale");
+                let mut event = pool::event(Venue::Betfair, MarketEventKind::BookStale, &market_id, token, received_at);
+                event.ts_exchange_ms = exchange_ts;
+                event.ts_receive_ms = Some(SystemTime::now());
+                event.parsed_at = Some(Instant::now());
+                batch.push(event);
+            } else if is && !was {
+                books.push(token.clone());
+            }
+        }
+    }
+
+    for rc in change.rc {
+        let token = selection_token(&market_id, rc.id, rc.hc);
+        let runner = market.runners.entry(token.clone()).or_default();
+        let mut book_changed = false;
+        for (ladder, levels) in [(&mut runner.back, rc.batb), (&mut runner.lay, rc.batl)] {
+            if let Some(levels) = levels {
+                for (level, odds, stake) in levels {
+                    if stake == 0.0 {
+                        ladder.remove(&level);
+                    } else {
+                        ladder.insert(level, (odds, stake));
+                    }
+                }
+                book_changed = true;
+            }
+        }
+        if rc.ltp.is_some() {
+            runner.ltp = rc.ltp;
+        }
+        if book_changed {
+            if !books.contains(&token) {
+                books.push(token);
+            }
+        } else if rc.ltp.is_some() {
+            prices.push(token);
+        }
+    }
+
+    for token in books {
+        if !market.tradeable(&token) {
+            continue;
+        }
+        let runner = &market.runners[&token];
+        let (bids, asks) = runner.book();
+        record_adapter_event("Betfair", "book_snapshot");
+        let (best_bid, best_ask) = (bids.first().map(|l| l.price), asks.first().map(|l| l.price));
+        let mut event = runner_event(&market_id, &token, runner, MarketEventKind::BookSnapshot { bids, asks }, exchange_ts, received_at);
+        event.best_bid = best_bid;
+        event.best_ask = best_ask;
+        batch.push(event);
+    }
+    for token in prices {
+        if !market.tradeable(&token) {
+            continue;
+        }
+        let runner = &market.runners[&token];
+        let (bids, asks) = runner.book();
+        record_adapter_event("Betfair", "price_change");
+        let mut event = runner_event(&market_id, &token, runner, MarketEventKind::PriceChange, exchange_ts, received_at);
+        event.best_bid = bids.first().map(|l| l.price);
+        event.best_ask = asks.first().map(|l| l.price);
+        batch.push(event);
+    }
+}
+
+/// A `kind` event for one selection carrying its last traded price.
+fn runner_event(
+    market_id: &str,
+    token: &str,
+    runner: &Runner,
+    kind: MarketEventKind,
+    exchange_ts: Option<SystemTime>,
+    received_at: Instant,
+) -> MarketEvent {
+    let mut event = pool::event(Venue::Betfair, kind, market_id, token, received_at);
+    event.ts_exchange_ms = exchange_ts;
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(Instant::now());
+    event.last_trade_price = runner.ltp.filter(|odds| *odds > 1.0).map(|odds| 1.0 / odds);
+    event
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// A YES level from an offer of `stake` at decimal `odds`: `stake × odds`
+/// contracts at `1 / odds`. Odds of 1 or less can't be offered.
+fn level(odds: f64, stake: f64) -> Option<BookLevel> {
+    if odds <= 1.0 {
+        return None;
+    }
+    Some(BookLevel {
+        price: Decimal::from_f64(1.0 / odds)?.round_dp(PRICE_DP),
+        size: Decimal::from_f64(stake * odds)?.round_dp(2),
+    })
+}
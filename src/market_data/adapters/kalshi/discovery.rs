@@ -0,0 +1,200 @@
+//! Finding Kalshi markets to stream.
+//!
+//! Kalshi groups markets into events (one question, e.g. a Fed decision)
+//! and events into series (the recurring question, e.g. every Fed decision).
+//! Discovery lists the open events over the public REST API, with their
+//! markets nested, optionally only those of some series. It keeps the
+//! active markets that pass a [`KalshiSelection`]. They come back as the
+//! same [`DiscoveredMarket`] / [`MarketInfo`] records Polymarket discovery
+//! produces, so the CLI and the universe tooling can handle both.
+//!
+//! A Kalshi market is one YES/NO book under a single ticker. The ticker is
+//! its market id and both of its token ids, as in the adapter's events.
+//! Volumes count contracts, not dollars.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::market_data::adapters::polymarket::{DiscoveredMarket, MarketInfo};
+use crate::market_data::types::Venue;
+use crate::rate_limit::{self, EndpointClass};
+use crate::state::ids::TokenId;
+
+/// Kalshi's production REST API.
+pub const KALSHI_REST_URL: &str = "https://api.elections.kalshi.com/trade-api/v2";
+
+/// Events per page; Kalshi's maximum.
+const PAGE_SIZE: u32 = 200;
+/// Stop paging after this many pages per series, in case a cursor never ends.
+const MAX_PAGES: usize = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Which open Kalshi markets discovery keeps. The default keeps every
+/// active market.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KalshiSelection {
+    /// Only list events in these series, e.g. `KXFED`; every open event
+    /// when empty.
+    pub series: Vec<String>,
+    /// Only keep events in these categories (case-insensitive).
+    pub categories: Vec<String>,
+    /// Minimum 24-hour volume, in contracts.
+    pub min_volume_24h: u64,
+    /// Skip markets closing later than this from now.
+    pub max_time_to_close: Option<Duration>,
+    /// Keep the N qualifying markets with the most 24h volume.
+    pub top_n: Option<usize>,
+}
+
+// ── Wire format ───────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct EventsPage {
+    #[serde(default)]
+    events: Vec<Event>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    title: String,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    markets: Vec<Market>,
+}
+
+#[derive(Deserialize)]
+struct Market {
+    ticker: String,
+    /// `active` while trading.
+    status: String,
+    /// The outcome this market's YES stands for, within its event.
+    #[serde(default)]
+    yes_sub_title: String,
+    #[serde(default)]
+    volume: u64,
+    #[serde(default)]
+    volume_24h: u64,
+    /// Value of the resting offers, in cents.
+    #[serde(default)]
+    liquidity: Option<i64>,
+    #[serde(default)]
+    close_time: Option<DateTime<Utc>>,
+}
+
+// ── Discovery ─────────────────────────────────────────────────────────────────
+
+/// List the open markets at `rest_url` that `selection` keeps, most 24h
+/// volume first.
+pub async fn discover(rest_url: &str, selection: &KalshiSelection) -> anyhow::Result<Vec<DiscoveredMarket>> {
+    let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+    let mut events = Vec::new();
+    if selection.series.is_empty() {
+        events = fetch_events(&client, rest_url, None).await?;
+    } else {
+        for series in &selection.series {
+            events.extend(fetch_events(&client, rest_url, Some(series)).await?);
+        }
+    }
+    let total: usize = events.iter().map(|e| e.markets.len()).sum();
+    info!(events = events.len(), markets = total, series = selection.series.len(), "fetched open events from Kalshi");
+
+    let close_by = selection.max_time_to_close.and_then(|d| chrono::Duration::from_std(d).ok()).map(|d| Utc::now() + d);
+    let mut seen = HashSet::new();
+    let mut ranked: Vec<(u64, DiscoveredMarket)> = Vec::new();
+    for event in &events {
+        let in_category = selection.categories.is_empty()
+            || event.category.as_deref().is_some_and(|c| selection.categories.iter().any(|want| want.eq_ignore_ascii_case(c)));
+        if !in_category {
+            continue;
+        }
+        for market in &event.markets {
+            let closes_in_time = close_by.is_none_or(|by| market.close_time.is_some_and(|t| t <= by));
+            if market.status != "active" || market.volume_24h < selection.min_volume_24h || !closes_in_time {
+                debug!(ticker = %market.ticker, status = %market.status, "Kalshi market skipped");
+                continue;
+            }
+            if !seen.insert(market.ticker.as_str()) {
+                continue; // listed under two series
+            }
+            ranked.push((market.volume_24h, discovered(event, market)));
+        }
+    }
+    ranked.sort_by_key(|(volume_24h, _)| std::cmp::Reverse(*volume_24h));
+    if let Some(n) = selection.top_n {
+        ranked.truncate(n);
+    }
+
+    info!(
+        count = ranked.len(),
+        min_volume_24h = selection.min_volume_24h,
+        categories = ?selection.categories,
+        closes_by = ?close_by,
+        top_n = ?selection.top_n,
+        "eligible Kalshi markets"
+    );
+    Ok(ranked.into_iter().map(|(_, m)| m).collect())
+}
+
+/// Every open event (of `series`, when set) with its markets, page by page.
+async fn fetch_events(client: &reqwest::Client, rest_url: &str, series: Option<&str>) -> anyhow::Result<Vec<Event>> {
+    let url = format!("{}/events", rest_url.trim_end_matches('/'));
+    let mut events = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let limit = PAGE_SIZE.to_string();
+        let mut query = vec![("status", "open"), ("with_nested_markets", "true"), ("limit", limit.as_str())];
+        if let Some(series) = series {
+            query.push(("series_ticker", series));
+        }
+        if let Some(cursor) = cursor.as_deref() {
+            query.push(("cursor", cursor));
+        }
+        rate_limit::acquire(Venue::Kalshi, EndpointClass::Discovery).await;
+        let page: EventsPage = client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("listing Kalshi events at {url}"))?
+            .json()
+            .await
+            .context("unexpected Kalshi events response")?;
+        events.extend(page.events);
+        match page.cursor.filter(|c| !c.is_empty()) {
+            Some(next) => cursor = Some(next),
+            None => return Ok(events),
+        }
+    }
+    anyhow::bail!("Kalshi events at {url} didn't end after {MAX_PAGES} pages")
+}
+
+/// `market` in the shared metadata: its ticker is the market id and both
+/// token ids, and the question is the event's, narrowed to the outcome.
+fn discovered(event: &Event, market: &Market) -> DiscoveredMarket {
+    let ticker = TokenId::intern(&market.ticker);
+    let question = match market.yes_sub_title.as_str() {
+        "" => event.title.clone(),
+        outcome => format!("{} — {outcome}", event.title),
+    };
+    DiscoveredMarket {
+        info: MarketInfo {
+            market_id: market.ticker.clone(),
+            question,
+            yes_token_id: ticker,
+            no_token_id: ticker,
+            neg_risk: false,
+        },
+        volume: market.volume as f64,
+        liquidity: market.liquidity.map(|cents| cents as f64 / 100.0),
+        end_date: market.close_time,
+    }
+}
@@ -0,0 +1,142 @@
+mod auth;
+mod discovery;
+mod ws;
+
+pub use auth::KalshiAuth;
+pub use discovery::{discover, KalshiSelection, KALSHI_REST_URL};
+
+use ws::{run_ws_loop, HEALTH_COMPONENT};
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::keepalive::KeepaliveConfig;
+use crate::market_data::adapters::traits::{MarketDataAdapter, StopSignal};
+use crate::market_data::subscriptions::{SubscriptionControl, Subscriptions};
+use crate::market_data::types::{EventBatch, Venue};
+use crate::metrics::tasks;
+use crate::secrets::KalshiCredentials;
+
+/// Kalshi's production market data WebSocket.
+pub const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
+
+/// Everything one run of the Kalshi adapter loop needs. Each
+/// [`run`](Self::run) opens a fresh signed WebSocket, and the books are
+/// rebuilt from the snapshots Kalshi sends on subscribe.
+#[derive(Clone)]
+pub struct KalshiAdapterTask {
+    tx: channel::Sender<EventBatch>,
+    auth: Arc<KalshiAuth>,
+    /// Tickers to stream, changed at runtime through [`subscriptions`](Self::subscriptions).
+    subscriptions: Subscriptions,
+    health: HealthRegistry,
+    /// Runtime to read the socket on; the adapter's own when `None`.
+    reader: Option<Handle>,
+    stop: StopSignal,
+    ws_url: String,
+    keepalive: KeepaliveConfig,
+}
+
+impl KalshiAdapterTask {
+    /// Read the WebSocket on `reader` (e.g. a venue thread of its own)
+    /// rather than alongside the parser.
+    pub fn with_reader_runtime(mut self, reader: Handle) -> Self {
+        self.reader = Some(reader);
+        self
+    }
+
+    /// Ping the WebSocket on `keepalive`'s schedule instead of the default one.
+    pub fn with_keepalive(mut self, keepalive: KeepaliveConfig) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Stream from `ws` instead of Kalshi's production endpoint, e.g. the demo
+    /// environment. The handshake is signed for this URL's path.
+    pub fn with_ws_url(mut self, ws: &str) -> Self {
+        self.ws_url = ws.to_string();
+        self
+    }
+
+    /// Add and remove tickers while the adapter runs. A ticker is both the
+    /// market id and the token id.
+    pub fn subscriptions(&self) -> SubscriptionControl {
+        self.subscriptions.control()
+    }
+
+    pub fn run(&self) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + use<> {
+        let ws = run_ws_loop(
+            self.tx.clone(),
+            Arc::clone(&self.auth),
+            self.subscriptions.clone(),
+            self.ws_url.clone(),
+            self.health.clone(),
+            self.reader.clone(),
+            self.keepalive,
+        );
+        async move {
+            tasks::instrument("adapter.kalshi.ws", ws).await;
+            anyhow::bail!("Kalshi WebSocket gave up reconnecting")
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataAdapter for KalshiAdapterTask {
+    fn venue(&self) -> Venue {
+        Venue::Kalshi
+    }
+
+    fn name(&self) -> &'static str {
+        "adapter.kalshi"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        self.stop.run_until_stopped(self.run()).await
+    }
+
+    async fn shutdown(&self) {
+        self.stop.stop();
+    }
+
+    fn subscription_control(&self) -> Option<SubscriptionControl> {
+        Some(self.subscriptions.control())
+    }
+
+    fn set_reader_runtime(&mut self, reader: Handle) -> bool {
+        self.reader = Some(reader);
+        true
+    }
+
+    fn set_keepalive(&mut self, keepalive: KeepaliveConfig) {
+        self.keepalive = keepalive;
+    }
+}
+
+/// Initialise the Kalshi adapter for `tickers`, listed by hand or found by
+/// [`discover`].
+///
+/// Parses the API key in `credentials` up front, so a bad key fails at
+/// startup rather than on the first connect. Market events are sent over
+/// `tx` with the ticker as both market id and token id; connection state is
+/// reported to `health` as `adapter.kalshi`.
+pub fn init_kalshi_adapter(
+    tx: channel::Sender<EventBatch>,
+    credentials: &KalshiCredentials,
+    tickers: Vec<String>,
+    health: HealthRegistry,
+) -> anyhow::Result<KalshiAdapterTask> {
+    anyhow::ensure!(!tickers.is_empty(), "no Kalshi tickers to subscribe to");
+    let auth = Arc::new(KalshiAuth::new(credentials)?);
+    health.register(HEALTH_COMPONENT);
+    let subscriptions = Subscriptions::new(Venue::Kalshi, tickers.into_iter().map(|ticker| (ticker.clone(), ticker)));
+    Ok(KalshiAdapterTask { tx, auth, subscriptions, health,
+        reader: None,
+        stop: StopSignal::default(),
+        ws_url: KALSHI_WS_URL.to_string(),
+        keepalive: KeepaliveConfig::default(),
+    })
+}
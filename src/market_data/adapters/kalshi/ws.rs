@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::book_builder::{BookBuilder, DeltaOutcome, Sequence};
+use crate::market_data::pool;
+use crate::market_data::subscriptions::{SubscriptionChange, Subscriptions};
+use crate::market_data::types::{BookLevel, EventBatch, MarketEvent, MarketEventKind, Side, Venue};
+use crate::metrics::prometheus::record_adapter_event;
+use crate::ring;
+use crate::market_data::adapters::keepalive::{Keepalive, KeepaliveConfig};
+use crate::market_data::adapters::reconnect::{backoff_duration, MAX_RECONNECT_ATTEMPTS};
+use super::auth::KalshiAuth;
+
+pub(super) const HEALTH_COMPONENT: &str = "adapter.kalshi";
+
+/// Raw frames the socket reader may run ahead of the parser.
+const FRAME_RING_CAPACITY: usize = 4_096;
+
+/// Channels subscribed for every ticker.
+const CHANNELS: [&str; 3] = ["orderbook_delta", "ticker", "trade"];
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// The write half of a connection, kept by the loop for subscription changes.
+type SocketSink = SplitSink<Socket, Message>;
+
+// ── Wire format ───────────────────────────────────────────────────────────────
+
+/// One message on the market channel. Prices are YES prices in cents.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsMessage {
+    OrderbookSnapshot { sid: u64, seq: u64, msg: SnapshotMsg },
+    OrderbookDelta { sid: u64, seq: u64, msg: DeltaMsg },
+    Ticker { msg: TickerMsg },
+    Trade { msg: TradeMsg },
+    Subscribed { msg: SubscribedMsg },
+    Error { msg: ErrorMsg },
+    #[serde(other)]
+    Other,
+}
+
+/// Resting bids on both outcomes as `[price, contracts]`. A side with no
+/// bids is left out.
+#[derive(Deserialize)]
+struct SnapshotMsg {
+    market_ticker: String,
+    #[serde(default)]
+    yes: Vec<(i64, i64)>,
+    #[serde(default)]
+    no: Vec<(i64, i64)>,
+}
+
+/// Contracts added (or, negative, removed) at one price on one side.
+#[derive(Deserialize)]
+struct DeltaMsg {
+    market_ticker: String,
+    price: i64,
+    delta: i64,
+    /// `"yes"` or `"no"`.
+    side: String,
+    /// RFC 3339.
+    #[serde(default)]
+    ts: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TickerMsg {
+    market_ticker: String,
+    /// Last traded price.
+    price: Option<i64>,
+    yes_bid: Option<i64>,
+    yes_ask: Option<i64>,
+    /// Unix seconds.
+    ts: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TradeMsg {
+    market_ticker: String,
+    yes_price: i64,
+    count: i64,
+    /// `"yes"` or `"no"`.
+    taker_side: String,
+    /// Unix seconds.
+    ts: u64,
+}
+
+#[derive(Deserialize)]
+struct SubscribedMsg {
+    channel: String,
+    sid: u64,
+}
+
+#[derive(Deserialize)]
+struct ErrorMsg {
+    code: i64,
+    msg: String,
+}
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+/// Run the Kalshi WebSocket loop until it gives up reconnecting.
+///
+/// Each connection is signed with `auth`, subscribes to the order book,
+/// ticker and trade channels for every ticker, and converts what arrives
+/// into `MarketEvent`s sent over `tx`, one [`EventBatch`] per frame.
+/// Connection state is reported to `health` as [`HEALTH_COMPONENT`]. When
+/// the stream ends every ticker is sent a `Resync` before reconnecting.
+///
+/// Subscription commands are applied to the open connection: added tickers
+/// get a `subscribe` of their own (Kalshi snapshots them as on connect), and
+/// removed ones are deleted from every subscription the connection holds,
+/// by the `sid`s Kalshi acknowledged, and sent an `Unsubscribed`.
+///
+/// Kalshi markets are single-ticker binaries, so the ticker is both market
+/// id and token id and every price is the YES price, normalized from cents:
+///
+/// - **`orderbook_snapshot`** / **`orderbook_delta`**: Kalshi books hold
+///   only bids, and a NO bid at `p` is a YES offer at `100 - p`, so NO bids
+///   become YES asks. The books are kept in a [`BookBuilder`], since deltas
+///   are increments, and emitted as a `BookSnapshot` or a `BookDelta`
+///   carrying the new total. A gap in a subscription's `seq` means a delta
+///   was missed: its tickers are emitted as `BookStale`, and the connection
+///   is dropped so they're resnapshotted on reconnect.
+/// - **`ticker`**: carries the last traded price. Its best bid and ask are
+///   used only for tickers whose book hasn't been snapshotted yet, since the
+///   book is more current.
+/// - **`trade`**: emitted as a `Trade`. A YES taker is a buy, a NO taker a
+///   sell of YES.
+///
+/// As with Polymarket, the socket is read on a task of its own (on
+/// `reader_runtime` when set) that hands frames to this loop over an SPSC
+/// [`ring`]. Kalshi has no ping message of its own, so the keepalive sends
+/// WebSocket ping frames, and the reader passes their pongs on with the
+/// text frames.
+pub(super) async fn run_ws_loop(
+    tx: channel::Sender<EventBatch>,
+    auth: Arc<KalshiAuth>,
+    subscriptions: Subscriptions,
+    ws_url: String,
+    health: HealthRegistry,
+    reader_runtime: Option<Handle>,
+    keepalive: KeepaliveConfig,
+) {
+    let mut attempt: u32 = 0;
+    let mut books = BookBuilder::new();
+    let mut commands = subscriptions.commands().await;
+    // What the current connection is subscribed to: ticker → ticker.
+    let mut live: HashMap<String, String> = HashMap::new();
+
+    loop {
+        attempt += 1;
+        info!(attempt, "connecting to Kalshi WebSocket");
+
+        live.clear();
+        subscriptions.sync(&mut live);
+        while commands.try_recv().is_ok() {} // already in the set just synced
+
+        let subscribe = {
+            let tickers: Vec<String> = live.keys().cloned().collect();
+            let (auth, ws_url) = (Arc::clone(&auth), ws_url.clone());
+            async move { connect(&auth, &ws_url, &tickers).await }
+        };
+        let connected = match &reader_runtime {
+            Some(runtime) => runtime
+                .spawn(subscribe)
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("reader runtime: {e}"))),
+            None => subscribe.await,
+        };
+
+        let socket = match connected {
+            Ok(socket) => {
+                info!(tickers = live.len(), "Kalshi WebSocket connected");
+                health.set_up(HEALTH_COMPONENT);
+                books.clear(); // snapshots and sequences start afresh on every subscribe
+                attempt = 0;
+                socket
+            }
+            Err(e) => {
+                if attempt >= MAX_RECONNECT_ATTEMPTS {
+                    error!(error = %e, attempts = attempt, "max Kalshi WS reconnect attempts reached");
+                    health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+                    return;
+                }
+                let backoff = backoff_duration(attempt);
+                warn!(error = %e, attempt, backoff_ms = backoff.as_millis() as u64, "Kalshi WS connection failed, retrying");
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+        };
+
+        // Dropping the set when this connection ends stops its reader.
+        let (mut sink, mut stream) = socket.split();
+        let (mut producer, mut consumer) = ring::ring(FRAME_RING_CAPACITY);
+        let mut reader = JoinSet::new();
+        let read = async move {
+            while let Some(message) = stream.next().await {
+                match message {
+                    Ok(message @ (Message::Text(_) | Message::Pong(_))) => {
+                        if producer.push((Instant::now(), message)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(Message::Close(frame)) => {
+                        info!(?frame, "Kalshi WebSocket closed");
+                        break;
+                    }
+                    Ok(_) => {} // Kalshi's pings are answered by the socket itself
+                    Err(e) => {
+                        warn!(error = %e, "Kalshi WebSocket stream error");
+                        break;
+                    }
+                }
+            }
+        };
+        match &reader_runtime {
+            Some(runtime) => reader.spawn_on(read, runtime),
+            None => reader.spawn(read),
+        };
+
+        // Subscriptions Kalshi has acknowledged, and the next command id.
+        let mut sids: Vec<u64> = Vec::new();
+        let mut next_id: u64 = 2;
+        let mut pings = Keepalive::new(Venue::Kalshi, keepalive);
+
+        loop {
+            let (received_at, message) = tokio::select! {
+                frame = consumer.pop() => match frame {
+                    Some(frame) => frame,
+                    None => break,
+                },
+                Some(_) = commands.recv() => {
+                    while commands.try_recv().is_ok() {}
+                    let change = subscriptions.sync(&mut live);
+                    apply_subscription_change(&mut sink, &change, &sids, &mut next_id, &mut books, &tx).await;
+                    continue;
+                }
+                due = pings.ping_due() => {
+                    if let Err(e) = due {
+                        warn!(error = %e, "Kalshi WebSocket unresponsive, reconnecting");
+                        break;
+                    }
+                    if let Err(e) = sink.send(Message::Ping(Vec::new())).await {
+                        warn!(error = %e, "Kalshi WS ping failed");
+                    }
+                    continue;
+                }
+            };
+            let Message::Text(text) = message else {
+                pings.pong(received_at);
+                continue;
+            };
+            let message = match serde_json::from_str::<WsMessage>(&text) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!(error = %e, "unparseable Kalshi message");
+                    continue;
+                }
+            };
+            let mut batch = pool::batch();
+            let in_sync = handle_message(&mut books, &mut sids, message, received_at, &mut batch);
+            if batch.is_empty() {
+                pool::recycle_batch(batch);
+            } else if tx.send(batch).await.is_err() {
+                warn!("market event channel closed");
+            }
+            if !in_sync {
+                break;
+            }
+        }
+
+        // Stream ended (or fell out of sequence) — mark every ticker for
+        // resync, then reconnect.
+        health.set_down(HEALTH_COMPONENT, "stream ended, reconnecting");
+        let subscribed = live.keys().map(|ticker| (ticker.as_str(), ticker.as_str()));
+        if tx.send(pool::notices(Venue::Kalshi, MarketEventKind::Resync, subscribed)).await.is_err() {
+            warn!("market event channel closed");
+        }
+        if attempt >= MAX_RECONNECT_ATTEMPTS {
+            error!(attempts = attempt, "max Kalshi WS reconnect attempts reached");
+            health.set_down(HEALTH_COMPONENT, "gave up reconnecting");
+            return;
+        }
+        let backoff = backoff_duration(attempt);
+        warn!(attempt, backoff_ms = backoff.as_millis() as u64, "Kalshi WS stream ended, reconnecting");
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Open a signed connection to `url` and subscribe to every channel for `tickers`.
+async fn connect(auth: &KalshiAuth, url: &str, tickers: &[String]) -> anyhow::Result<Socket> {
+    let mut request = url.into_client_request()?;
+    let path = request.uri().path().to_string();
+    for (name, value) in auth.headers("GET", &path, unix_ms())? {
+        request.headers_mut().insert(name, HeaderValue::from_str(&value)?);
+    }
+    let (mut socket, _) = tokio_tungstenite::connect_async(request).await?;
+    let subscribe = json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": { "channels": CHANNELS, "market_tickers": tickers },
+    });
+    socket.send(Message::Text(subscribe.to_string())).await?;
+    Ok(socket)
+}
+
+/// Subscribe and unsubscribe `change` on the open connection. Removed
+/// tickers' books are forgotten and an `Unsubscribed` sent for each. A
+/// failed write is only logged: the socket is gone, and the reconnect
+/// subscribes to the new set anyway.
+async fn apply_subscription_change(
+    sink: &mut SocketSink,
+    change: &SubscriptionChange,
+    sids: &[u64],
+    next_id: &mut u64,
+    books: &mut BookBuilder,
+    tx: &channel::Sender<EventBatch>,
+) {
+    let mut commands = Vec::new();
+    if !change.added.is_empty() {
+        let tickers: Vec<&str> = change.added.iter().map(|s| s.token_id.as_str()).collect();
+        info!(?tickers, "subscribing on the open Kalshi WebSocket");
+        commands.push(json!({
+            "cmd": "subscribe",
+            "params": { "channels": CHANNELS, "market_tickers": tickers },
+        }));
+    }
+    if !change.removed.is_empty() {
+        let tickers: Vec<&str> = change.removed.iter().map(|s| s.token_id.as_str()).collect();
+        info!(?tickers, "unsubscribing on the open Kalshi WebSocket");
+        // A subscription that never held one of the tickers answers with an
+        // error, which is only logged.
+        for &sid in sids {
+            commands.push(json!({
+                "cmd": "update_subscription",
+                "params": { "sids": [sid], "market_tickers": tickers, "action": "delete_markets" },
+            }));
+        }
+    }
+    for mut command in commands {
+        command["id"] = json!(*next_id);
+        *next_id += 1;
+        if let Err(e) = sink.send(Message::Text(command.to_string())).await {
+            warn!(error = %e, "Kalshi subscription change failed");
+            break;
+        }
+    }
+    if !change.removed.is_empty() {
+        for s in &change.removed {
+            books.remove(&s.token_id);
+        }
+        let removed = change.removed.iter().map(|s| (s.token_id.as_str(), s.token_id.as_str()));
+        if tx.send(pool::notices(Venue::Kalshi, MarketEventKind::Unsubscribed, removed)).await.is_err() {
+            warn!("market event channel closed");
+        }
+    }
+}
+
+// ── Event handlers ────────────────────────────────────────────────────────────
+
+/// Convert one message into events on `batch`, updating the ticker's book on
+/// the way. Acknowledged subscriptions are added to `sids`; errors are only
+/// logged. Returns `false` when the books have fallen out of sequence and
+/// need resubscribing.
+fn handle_message(
+    books: &mut BookBuilder,
+    sids: &mut Vec<u64>,
+    message: WsMessage,
+    received_at: Instant,
+    batch: &mut EventBatch,
+) -> bool {
+    match message {
+        WsMessage::OrderbookSnapshot { sid, seq, msg } => {
+            let resting = |&&(_, size): &&(i64, i64)| size > 0;
+            let mut bids: Vec<BookLevel> = msg.yes.iter().filter(resting).map(|&(price, size)| level(price, size)).collect();
+            let mut asks: Vec<BookLevel> = msg.no.iter().filter(resting).map(|&(price, size)| level(100 - price, size)).collect();
+            bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+            asks.sort_by_key(|l| l.price);
+            let stale = books.snapshot(&msg.market_ticker, &bids, &asks, Some(Sequence { stream: sid, seq }));
+            record_adapter_event("Kalshi", "book_snapshot");
+            debug!(ticker = %msg.market_ticker, yes_levels = msg.yes.len(), no_levels = msg.no.len(), "book snapshot received");
+            let kind = MarketEventKind::BookSnapshot { bids, asks };
+            batch.push(book_event(books, &msg.market_ticker, kind, None, received_at));
+            if stale.is_empty() {
+                return true;
+            }
+            warn!(sid, seq, stale = stale.len(), "Kalshi order book sequence gap, resubscribing");
+            push_stale(&stale, received_at, batch);
+            false
+        }
+        WsMessage::OrderbookDelta { sid, seq, msg } => {
+            let (side, price) = match msg.side.as_str() {
+                "no" => (Side::Sell, Decimal::new(100 - msg.price, 2)),
+                _ => (Side::Buy, Decimal::new(msg.price, 2)),
+            };
+            let resting = books.book(&msg.market_ticker).map(|book| book.size_at(&side, price)).unwrap_or_default();
+            let size = (resting + Decimal::from(msg.delta)).max(Decimal::ZERO);
+            match books.delta(&msg.market_ticker, &side, price, size, Some(Sequence { stream: sid, seq })) {
+                DeltaOutcome::Applied => {
+                    record_adapter_event("Kalshi", "price_change");
+                    let exchange_ts = msg.ts.as_deref().and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok()).map(SystemTime::from);
+                    let kind = MarketEventKind::BookDelta { side, price, size };
+                    batch.push(book_event(books, &msg.market_ticker, kind, exchange_ts, received_at));
+                    true
+                }
+                DeltaOutcome::Unsynced => {
+                    debug!(ticker = %msg.market_ticker, "delta before snapshot");
+                    true
+                }
+                DeltaOutcome::Gap(stale) => {
+                    warn!(sid, seq, stale = stale.len(), "Kalshi order book sequence gap, resubscribing");
+                    push_stale(&stale, received_at, batch);
+                    false
+                }
+            }
+        }
+        WsMessage::Ticker { msg } => {
+            record_adapter_event("Kalshi", "ticker");
+            let mut event = pool::event(Venue::Kalshi, MarketEventKind::PriceChange, &msg.market_ticker, &msg.market_ticker, received_at);
+            event.ts_exchange_ms = msg.ts.map(unix_secs);
+            event.ts_receive_ms = Some(SystemTime::now());
+            event.parsed_at = Some(Instant::now());
+            event.last_trade_price = msg.price.map(cents);
+            if !books.is_synced(&msg.market_ticker) {
+                event.best_bid = msg.yes_bid.map(|p| Decimal::new(p, 2));
+                event.best_ask = msg.yes_ask.map(|p| Decimal::new(p, 2));
+            }
+            batch.push(event);
+            true
+        }
+        WsMessage::Trade { msg } => {
+            let price = cents(msg.yes_price);
+            let side = if msg.taker_side == "no" { Side::Sell } else { Side::Buy };
+            record_adapter_event("Kalshi", "trade");
+            debug!(ticker = %msg.market_ticker, price, size = msg.count, ?side, "trade received");
+            let kind = MarketEventKind::Trade { price, size: msg.count as f64, side };
+            let mut event = pool::event(Venue::Kalshi, kind, &msg.market_ticker, &msg.market_ticker, received_at);
+            event.ts_exchange_ms = Some(unix_secs(msg.ts));
+            event.ts_receive_ms = Some(SystemTime::now());
+            event.parsed_at = Some(Instant::now());
+            event.last_trade_price = Some(price);
+            batch.push(event);
+            true
+        }
+        WsMessage::Subscribed { msg } => {
+            info!(channel = %msg.channel, sid = msg.sid, "Kalshi channel subscribed");
+            sids.push(msg.sid);
+            true
+        }
+        WsMessage::Error { msg } => {
+            warn!(code = msg.code, error = %msg.msg, "Kalshi WebSocket error");
+            true
+        }
+        WsMessage::Other => true,
+    }
+}
+
+/// A `kind` event for `ticker` carrying its book's best prices.
+fn book_event(
+    books: &BookBuilder,
+    ticker: &str,
+    kind: MarketEventKind,
+    exchange_ts: Option<SystemTime>,
+    received_at: Instant,
+) -> MarketEvent {
+    let mut event = pool::event(Venue::Kalshi, kind, ticker, ticker, received_at);
+    event.ts_exchange_ms = exchange_ts;
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(Instant::now());
+    if let Some(book) = books.book(ticker) {
+        event.best_bid = book.best_bid().map(|level| level.price);
+        event.best_ask = book.best_ask().map(|level| level.price);
+    }
+    event
+}
+
+/// A `BookStale` event for each of `tickers`. Kalshi's deltas don't carry
+/// the touch, so their best prices are left unknown until the ticker
+/// channel or a snapshot says.
+fn push_stale(tickers: &[String], received_at: Instant, batch: &mut EventBatch) {
+    for ticker in tickers {
+        record_adapter_event("Kalshi", "book_stale");
+        let mut event = pool::event(Venue::Kalshi, MarketEventKind::BookStale, ticker, ticker, received_at);
+        event.ts_receive_ms = Some(SystemTime::now());
+        event.parsed_at = Some(Instant::now());
+        batch.push(event);
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// A YES level from a price in cents and a contract count.
+fn level(price: i64, size: i64) -> BookLevel {
+    BookLevel { price: Decimal::new(price, 2), size: Decimal::from(size) }
+}
+
+fn cents(price: i64) -> f64 {
+    price as f64 / 100.0
+}
+
+fn unix_secs(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
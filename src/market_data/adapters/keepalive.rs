@@ -0,0 +1,82 @@
+//! Keepalive shared by the venue WebSocket loops.
+//!
+//! A connection whose far end has gone without a FIN (a half-open TCP
+//! connection) reads nothing and never errors, so a loop waiting on it would
+//! hang with its books frozen. Each loop therefore pings the venue every
+//! [`ping_interval`](KeepaliveConfig::ping_interval) and times the answer:
+//! the round trip is recorded as `ws_ping_rtt_ms{venue}`. A ping still
+//! unanswered when the next one is due counts as missed
+//! (`ws_pongs_missed_total{venue}`), and after
+//! [`max_missed_pongs`](KeepaliveConfig::max_missed_pongs) in a row the loop
+//! drops the connection and reconnects as it does when the stream ends.
+//!
+//! What a ping is depends on the venue: Polymarket's market channel answers
+//! a `PING` text frame with `PONG`, Kalshi answers WebSocket ping frames,
+//! and a JSON-RPC node answers a `net_version` request.
+
+use std::time::{Duration, Instant};
+
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::warn;
+
+use crate::market_data::types::Venue;
+use crate::metrics::prometheus::{record_ws_pong_missed, record_ws_ping_rtt};
+
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_MISSED_PONGS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeepaliveConfig {
+    pub ping_interval: Duration,
+    /// Unanswered pings in a row after which the connection is dropped.
+    pub max_missed_pongs: u32,
+}
+
+impl Default for KeepaliveConfig {
+    fn default() -> Self {
+        Self { ping_interval: DEFAULT_PING_INTERVAL, max_missed_pongs: DEFAULT_MAX_MISSED_PONGS }
+    }
+}
+
+/// One connection's pings. Created when the connection opens; the first
+/// ping is due one interval later.
+pub struct Keepalive {
+    venue: Venue,
+    max_missed_pongs: u32,
+    ticker: Interval,
+    /// When the outstanding ping went out.
+    sent_at: Option<Instant>,
+    missed: u32,
+}
+
+impl Keepalive {
+    pub fn new(venue: Venue, config: KeepaliveConfig) -> Self {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + config.ping_interval, config.ping_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { venue, max_missed_pongs: config.max_missed_pongs, ticker, sent_at: None, missed: 0 }
+    }
+
+    /// Wait until the next ping is due and count the last one missed if it
+    /// went unanswered. The caller sends the ping on `Ok`; an error means the
+    /// venue has stopped answering and the connection should be dropped.
+    /// Cancel-safe, so it can sit in a `select!` beside the socket.
+    pub async fn ping_due(&mut self) -> anyhow::Result<()> {
+        self.ticker.tick().await;
+        if self.sent_at.take().is_some() {
+            self.missed += 1;
+            record_ws_pong_missed(self.venue.name());
+            warn!(venue = self.venue.name(), missed = self.missed, "WebSocket ping went unanswered");
+        }
+        anyhow::ensure!(self.missed < self.max_missed_pongs, "{} pings in a row went unanswered", self.missed);
+        self.sent_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// A pong arrived at `received_at`. One with no ping outstanding (a late
+    /// answer, or one the venue sent unasked) is ignored.
+    pub fn pong(&mut self, received_at: Instant) {
+        let Some(sent_at) = self.sent_at.take() else { return };
+        self.missed = 0;
+        record_ws_ping_rtt(self.venue.name(), received_at.saturating_duration_since(sent_at).as_secs_f64() * 1_000.0);
+    }
+}
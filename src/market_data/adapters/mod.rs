@@ -1,4 +1,8 @@
+pub mod amm;
+pub mod betfair;
+pub mod kalshi;
+pub mod keepalive;
 pub mod polymarket;
-
-// Kalshi adapter is a work in progress — not yet wired into the engine.
-// pub mod kalshi;
+pub mod reconnect;
+pub mod rest_poller;
+pub mod traits;
@@ -1,8 +1,11 @@
 use polymarket_rs::{ClobClient, Side};
-use polymarket_rs::types::TokenId;
+use polymarket_rs::types::{OrderBookSummary, TokenId};
 use rust_decimal::Decimal;
 use tracing::warn;
 
+use crate::market_data::types::Venue;
+use crate::rate_limit::{self, EndpointClass};
+
 /// Fetch bid and ask prices for a single token from the CLOB REST API.
 ///
 /// ## Polymarket API naming (counterintuitive)
@@ -18,7 +21,7 @@ use tracing::warn;
 /// and `sell_price` = what you receive when selling = **best bid**.
 ///
 /// The caller is responsible for mapping correctly:
-/// ```
+/// ```ignore
 /// let (buy_price, sell_price) = fetch_prices(...).await?;
 /// let best_ask = buy_price;
 /// let best_bid = sell_price;
@@ -31,6 +34,8 @@ pub(super) async fn fetch_prices(
     market_id: &str,
 ) -> Option<(Decimal, Decimal)> {
     let tid = TokenId::from(token_id.to_owned());
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::MarketData).await;
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::MarketData).await;
 
     // Issue both requests in parallel — no need to wait for one before the other.
     let (buy_res, sell_res) = tokio::join!(
@@ -56,3 +61,18 @@ pub(super) async fn fetch_prices(
 
     Some((buy_price, sell_price))
 }
+
+/// Fetch a token's full order book from the CLOB REST API, to resync a book
+/// the WebSocket feed has fallen out of step with.
+///
+/// Returns `None` if the request fails (logged as a warning).
+pub(super) async fn fetch_book(clob_client: &ClobClient, token_id: &str) -> Option<OrderBookSummary> {
+    rate_limit::acquire(Venue::Polymarket, EndpointClass::MarketData).await;
+    match clob_client.get_order_book(&TokenId::from(token_id.to_owned())).await {
+        Ok(book) => Some(book),
+        Err(e) => {
+            warn!(token_id, error = %e, "CLOB order book fetch failed");
+            None
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Finding the markets to trade.
+//!
+//! Discovery asks the Gamma API for active markets, keeps the binary
+//! CLOB-tradable ones that clear the [`UniverseFilter`] thresholds and the
+//! [`UniverseSelection`] (tags, categories, end date, top N), and puts the
+//! pinned markets on top. [`lookup_tables`] turns the result into the
+//! [`MarketMap`] / [`TokenToMarket`] pair the adapter subscribes from and the
+//! engine routes with, so no market has to be listed by hand.
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use polymarket_rs::client::GammaClient;
+use polymarket_rs::request::GammaMarketParams;
+use polymarket_rs::types::GammaMarket;
+use tracing::{info, warn};
+
+use crate::market_data::types::Venue;
+use crate::rate_limit::{self, EndpointClass};
+use super::types::{try_parse_eligible, EligibleMarket};
+use super::{DiscoveredMarket, MarketInfo, MarketMap, TokenToMarket, UniverseFilter, UniverseSelection};
+
+const GAMMA_URL: &str = "https://gamma-api.polymarket.com";
+
+/// Markets fetched per Gamma request (per tag when tags are set), most 24h
+/// volume first.
+const PAGE_SIZE: u32 = 500;
+
+/// Fetch active markets from Gamma (per tag when tags are set) and keep the
+/// eligible ones: pinned markets first, then the best of the rest.
+pub(super) async fn discover_markets(filter: &UniverseFilter, selection: &UniverseSelection) -> anyhow::Result<Vec<EligibleMarket>> {
+    let gamma = GammaClient::new(GAMMA_URL);
+    let now = Utc::now();
+    let mut params = GammaMarketParams::new()
+        .with_active(true)
+        .with_closed(false)
+        .with_archived(false)
+        .with_order("volume24hr", false)
+        .with_limit(PAGE_SIZE);
+    // Gamma filters on the end date too, so the page isn't spent on markets
+    // outside the window; they're checked again below.
+    let (earliest, latest) = selection.end_window(now);
+    if let Some(earliest) = earliest {
+        params = params.with_end_date_min(earliest);
+    }
+    if let Some(latest) = latest {
+        params = params.with_end_date_max(latest);
+    }
+
+    let mut raw_markets: Vec<GammaMarket> = Vec::new();
+    if selection.tags.is_empty() {
+        rate_limit::acquire(Venue::Polymarket, EndpointClass::Discovery).await;
+        raw_markets = gamma.get_markets(Some(params)).await?;
+    } else {
+        let mut seen = HashSet::new();
+        for tag in &selection.tags {
+            rate_limit::acquire(Venue::Polymarket, EndpointClass::Discovery).await;
+            let markets = gamma.get_markets(Some(params.clone().with_tag_id(tag))).await?;
+            raw_markets.extend(markets.into_iter().filter(|m| seen.insert(m.id.clone())));
+        }
+    }
+    info!(total = raw_markets.len(), tags = selection.tags.len(), "fetched markets from Gamma API");
+
+    // Pinned markets skip the thresholds and the end date window; the ones
+    // outside the fetched page are looked up directly.
+    let no_thresholds = UniverseFilter { min_volume_24h: 0.0, min_liquidity: 0.0 };
+    let mut pinned: Vec<EligibleMarket> = Vec::new();
+    for key in &selection.markets {
+        let market = match raw_markets.iter().find(|m| m.slug == *key || m.condition_id.eq_ignore_ascii_case(key) || m.id == *key) {
+            Some(m) => Some(m.clone()),
+            None if key.starts_with("0x") => {
+                rate_limit::acquire(Venue::Polymarket, EndpointClass::Discovery).await;
+                gamma.get_market(key).await.ok()
+            }
+            None if key.chars().all(|c| c.is_ascii_digit()) => {
+                rate_limit::acquire(Venue::Polymarket, EndpointClass::Discovery).await;
+                gamma.get_market_by_id(key).await.ok()
+            }
+            None => None,
+        };
+        match market.as_ref().and_then(|m| try_parse_eligible(m, &no_thresholds)) {
+            Some(em) if !pinned.iter().any(|p| p.market_id == em.market_id) => {
+                pinned.push(EligibleMarket { pinned: true, ..em })
+            }
+            Some(_) => {}
+            None => warn!(market = %key, "configured market not found, inactive, or not binary — skipped"),
+        }
+    }
+
+    let mut ranked: Vec<EligibleMarket> = raw_markets
+        .iter()
+        .filter(|m| !selection.pins(m) && selection.in_categories(m) && selection.ends_in_window(m, now))
+        .filter_map(|m| try_parse_eligible(m, filter))
+        .collect();
+    ranked.sort_by(|a, b| b.activity.volume_24h.total_cmp(&a.activity.volume_24h));
+    if let Some(n) = selection.top_n {
+        ranked.truncate(n);
+    }
+
+    info!(
+        count = pinned.len() + ranked.len(),
+        pinned = pinned.len(),
+        min_volume_24h = filter.min_volume_24h,
+        min_liquidity = filter.min_liquidity,
+        categories = ?selection.categories,
+        ends_after = ?earliest,
+        ends_before = ?latest,
+        top_n = ?selection.top_n,
+        "eligible binary CLOB-tradable markets"
+    );
+    pinned.extend(ranked);
+    Ok(pinned)
+}
+
+/// Run discovery on its own, without subscribing to anything.
+/// Returns the eligible markets, highest volume first.
+pub async fn discover(filter: &UniverseFilter, selection: &UniverseSelection) -> anyhow::Result<Vec<DiscoveredMarket>> {
+    let mut markets: Vec<DiscoveredMarket> = discover_markets(filter, selection)
+        .await?
+        .into_iter()
+        .map(|em| DiscoveredMarket {
+            info: em.info(),
+            volume: em.volume,
+            liquidity: em.liquidity,
+            end_date: em.end_date,
+        })
+        .collect();
+    markets.sort_by(|a, b| b.volume.total_cmp(&a.volume));
+    Ok(markets)
+}
+
+/// The market map and its token → market reverse lookup for `markets`.
+pub fn lookup_tables<'a>(markets: impl IntoIterator<Item = &'a MarketInfo>) -> (MarketMap, TokenToMarket) {
+    let mut market_map = MarketMap::new();
+    let mut token_to_market = TokenToMarket::new();
+    for m in markets {
+        for token_id in [m.yes_token_id, m.no_token_id] {
+            token_to_market.insert(token_id.to_string(), m.market_id.clone());
+        }
+        market_map.insert(m.market_id.clone(), m.clone());
+    }
+    (market_map, token_to_market)
+}
@@ -1,111 +1,244 @@
 mod clob;
+mod discovery;
+mod rewards;
+mod shards;
 mod types;
 mod ws;
 
-pub use types::{MarketInfo, MarketMap, TokenToMarket};
+pub use discovery::{discover, lookup_tables};
+pub use rewards::{fetch_rewards, run_rewards_refresher};
+pub use types::{
+    DiscoveredMarket, MarketActivity, MarketInfo, MarketMap, TokenToMarket, UniverseFilter, UniverseSelection,
+};
 
 use clob::fetch_prices;
-use types::{EligibleMarket, try_parse_eligible};
-use ws::run_ws_loop;
+use discovery::discover_markets;
+use types::EligibleMarket;
+use ws::{run_ws_shards, HEALTH_COMPONENT};
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
-use tokio::sync::mpsc;
-use tokio::task::JoinHandle;
-use tracing::{info, warn, debug, error};
+use tracing::{info, warn, debug};
 use futures::StreamExt;
+use tokio::runtime::Handle;
+use async_trait::async_trait;
 
-use polymarket_rs::client::GammaClient;
-use polymarket_rs::request::GammaMarketParams;
 use polymarket_rs::ClobClient;
-use rust_decimal::prelude::ToPrimitive;
 
-use crate::market_data::types::{MarketEvent, MarketEventKind, Venue};
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::keepalive::KeepaliveConfig;
+use crate::market_data::adapters::traits::{MarketDataAdapter, StopSignal};
+use crate::market_data::pool;
+use crate::market_data::subscriptions::{SubscriptionControl, Subscriptions};
+use crate::market_data::types::{EventBatch, MarketEventKind, Venue};
 use crate::metrics::prometheus::{record_adapter_event, record_adapter_latency};
+use crate::metrics::tasks;
 
 // ── Public handle returned to main ───────────────────────────────────────────
 
 /// Returned by [`init_polymarket_adapter`].
-/// Holds the market metadata maps and the background task handle.
+/// Holds the market metadata maps and the adapter task to run.
 pub struct PolymarketAdapterHandle {
     /// All eligible markets indexed by market_id.
     pub market_map: MarketMap,
     /// Reverse lookup: token_id → market_id.
     pub token_to_market: Arc<TokenToMarket>,
-    /// Background task running the adapter loop.
-    pub handle: JoinHandle<anyhow::Result<()>>,
+    /// market_id → activity at discovery, for re-filtering on reload. Empty for
+    /// an imported universe; pinned markets are left out so a reload never drops them.
+    pub activity: HashMap<String, MarketActivity>,
+    /// The adapter loop, runnable again after it dies.
+    pub task: PolymarketAdapterTask,
+}
+
+/// Everything one run of the adapter loop needs. Each [`run`](Self::run)
+/// reseeds prices over REST and opens a fresh WebSocket, so a restarted
+/// adapter picks up where the market is, not where it left off.
+#[derive(Clone)]
+pub struct PolymarketAdapterTask {
+    tx: channel::Sender<EventBatch>,
+    clob: Arc<ClobClient>,
+    eligible: Arc<Vec<EligibleMarket>>,
+    /// Tokens to stream, changed at runtime through [`subscriptions`](Self::subscriptions).
+    subscriptions: Subscriptions,
+    health: HealthRegistry,
+    /// Runtime to read the socket on; the adapter's own when `None`.
+    reader: Option<Handle>,
+    stop: StopSignal,
+    /// Market WebSocket endpoint; Polymarket's when `None`.
+    ws_url: Option<String>,
+    keepalive: KeepaliveConfig,
+    /// WebSockets the subscribed markets are spread over.
+    connections: usize,
+}
+
+impl PolymarketAdapterTask {
+    /// Read the WebSocket on `reader` (e.g. a venue thread of its own)
+    /// rather than alongside the parser.
+    pub fn with_reader_runtime(mut self, reader: Handle) -> Self {
+        self.reader = Some(reader);
+        self
+    }
+
+    /// Ping the WebSocket on `keepalive`'s schedule instead of the default one.
+    pub fn with_keepalive(mut self, keepalive: KeepaliveConfig) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Spread the subscribed markets over `connections` WebSockets instead
+    /// of one, each reconnecting on its own.
+    pub fn with_ws_connections(mut self, connections: usize) -> Self {
+        self.connections = connections.max(1);
+        self
+    }
+
+    /// Seed prices from `clob` and stream from `ws` instead of Polymarket's
+    /// own endpoints, e.g. to run against a local mock venue.
+    pub fn with_endpoints(mut self, clob: &str, ws: &str) -> Self {
+        self.clob = Arc::new(ClobClient::new(clob));
+        self.ws_url = Some(ws.to_string());
+        self
+    }
+
+    /// Add and remove tokens while the adapter runs.
+    pub fn subscriptions(&self) -> SubscriptionControl {
+        self.subscriptions.control()
+    }
+
+    pub fn run(&self) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + use<> {
+        run_adapter_loop(
+            self.tx.clone(),
+            Arc::clone(&self.clob),
+            self.eligible.as_ref().clone(),
+            self.subscriptions.clone(),
+            self.health.clone(),
+            self.reader.clone(),
+            self.ws_url.clone(),
+            self.keepalive,
+            self.connections,
+        )
+    }
+}
+
+#[async_trait]
+impl MarketDataAdapter for PolymarketAdapterTask {
+    fn venue(&self) -> Venue {
+        Venue::Polymarket
+    }
+
+    fn name(&self) -> &'static str {
+        "adapter.polymarket"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        self.stop.run_until_stopped(self.run()).await
+    }
+
+    async fn shutdown(&self) {
+        self.stop.stop();
+    }
+
+    fn subscription_control(&self) -> Option<SubscriptionControl> {
+        Some(self.subscriptions.control())
+    }
+
+    fn set_reader_runtime(&mut self, reader: Handle) -> bool {
+        self.reader = Some(reader);
+        true
+    }
+
+    fn set_keepalive(&mut self, keepalive: KeepaliveConfig) {
+        self.keepalive = keepalive;
+    }
 }
 
 // ── Initialisation ────────────────────────────────────────────────────────────
 
 /// Initialise the Polymarket adapter.
 ///
-/// 1. Fetches all active markets from the Gamma API.
-/// 2. Filters down to eligible binary CLOB markets (volume + liquidity thresholds).
-/// 3. Builds `market_map` and `token_to_market` lookup tables.
-/// 4. Spawns a background task that:
+/// 1. Fetches active markets from the Gamma API ([`discover`]).
+/// 2. Filters down to eligible binary CLOB markets (volume + liquidity
+///    thresholds, plus the `selection`'s tags, categories and end date).
+/// 3. Builds `market_map` and `token_to_market` lookup tables ([`lookup_tables`]).
+/// 4. Returns a task that, each time it's run:
 ///    a. Fires an initial CLOB REST price fetch for every token (parallel, 10 at a time).
 ///    b. Connects to the WebSocket and streams live order book updates indefinitely.
 ///
 /// Market events are sent over `tx` and consumed downstream by the router.
+///
+/// When `universe` is given (e.g. imported from a previous session), step 1-2
+/// are skipped and exactly those markets are subscribed; otherwise `filter`
+/// sets the step 2 thresholds and `selection` narrows or pins markets.
+///
+/// WebSocket connection state is reported to `health` as `adapter.polymarket`.
 pub async fn init_polymarket_adapter(
-    tx: mpsc::Sender<MarketEvent>,
+    tx: channel::Sender<EventBatch>,
+    universe: Option<MarketMap>,
+    filter: UniverseFilter,
+    selection: &UniverseSelection,
+    health: HealthRegistry,
 ) -> anyhow::Result<PolymarketAdapterHandle> {
-    let gamma = GammaClient::new("https://gamma-api.polymarket.com");
+    health.register(HEALTH_COMPONENT);
+
     let clob = Arc::new(ClobClient::new("https://clob.polymarket.com"));
 
     // ── Step 1: Fetch and filter markets ─────────────────────────────────────
-    let params = GammaMarketParams::new()
-        .with_active(true)
-        .with_closed(false)
-        .with_archived(false)
-        .with_limit(500);
-
-    let raw_markets = gamma.get_markets(Some(params)).await?;
-    info!(total = raw_markets.len(), "fetched markets from Gamma API");
-
-    let eligible: Vec<EligibleMarket> = raw_markets
-        .iter()
-        .filter_map(try_parse_eligible)
-        .collect();
-
-    info!(count = eligible.len(), "eligible binary CLOB-tradable markets");
+    let imported = universe.is_some();
+    let eligible: Vec<EligibleMarket> = match universe {
+        Some(markets) => {
+            info!(count = markets.len(), "using imported market universe — skipping Gamma discovery");
+            markets
+                .into_values()
+                .map(|m| EligibleMarket {
+                    market_id: m.market_id,
+                    question: m.question,
+                    token_ids: vec![m.yes_token_id.to_string(), m.no_token_id.to_string()],
+                    volume: 0.0,
+                    last_trade_price: None,
+                    liquidity: None,
+                    end_date: None,
+                    neg_risk: m.neg_risk,
+                    activity: MarketActivity::default(),
+                    pinned: false,
+                })
+                .collect()
+        }
+        None => discover_markets(&filter, selection).await?,
+    };
 
     // ── Step 2: Build lookup tables ───────────────────────────────────────────
-    let mut token_ids: Vec<String> = Vec::with_capacity(eligible.len() * 2);
-    let mut market_map: MarketMap = HashMap::with_capacity(eligible.len());
-    let mut token_to_market: TokenToMarket = HashMap::with_capacity(eligible.len() * 2);
-
+    let infos: Vec<MarketInfo> = eligible.iter().map(EligibleMarket::info).collect();
+    let (market_map, token_to_market) = lookup_tables(&infos);
+    // Subscribed in discovery order, best markets first.
+    let mut tokens: Vec<(String, String)> = Vec::with_capacity(eligible.len() * 2);
     for em in &eligible {
         debug!(market_id = %em.market_id, volume = em.volume, "eligible market");
-
-        for tid in &em.token_ids {
-            token_ids.push(tid.clone());
-            token_to_market.insert(tid.clone(), em.market_id.clone());
-        }
-
-        market_map.insert(em.market_id.clone(), MarketInfo {
-            market_id: em.market_id.clone(),
-            question: em.question.clone(),
-            yes_token_id: em.token_ids[0].clone(),
-            no_token_id: em.token_ids[1].clone(),
-            neg_risk: em.neg_risk,
-        });
+        tokens.extend(em.token_ids.iter().map(|tid| (tid.clone(), em.market_id.clone())));
+    }
+    let mut activity: HashMap<String, MarketActivity> = HashMap::new();
+    if !imported {
+        activity.extend(eligible.iter().filter(|em| !em.pinned).map(|em| (em.market_id.clone(), em.activity)));
     }
 
     let token_to_market = Arc::new(token_to_market);
 
-    // ── Step 3: Spawn background adapter task ─────────────────────────────────
-    let handle = tokio::spawn(run_adapter_loop(
+    // ── Step 3: Package the adapter task for the caller to run ────────────────
+    let task = PolymarketAdapterTask {
         tx,
         clob,
-        Arc::clone(&token_to_market),
-        eligible,
-        token_ids,
-    ));
-
-    Ok(PolymarketAdapterHandle { market_map, token_to_market, handle })
+        eligible: Arc::new(eligible),
+        subscriptions: Subscriptions::new(Venue::Polymarket, tokens),
+        health,
+        reader: None,
+        stop: StopSignal::default(),
+        ws_url: None,
+        keepalive: KeepaliveConfig::default(),
+        connections: 1,
+    };
+
+    Ok(PolymarketAdapterHandle { market_map, token_to_market, activity, task })
 }
 
 // ── Background adapter loop ───────────────────────────────────────────────────
@@ -113,48 +246,57 @@ pub async fn init_polymarket_adapter(
 /// Orchestrates the initial price fetch and the live WebSocket stream.
 ///
 /// The WebSocket is started immediately so we don't miss any events during the
-/// (potentially slow) initial CLOB REST price fetch.
+/// (potentially slow) initial CLOB REST price fetch. Only returns, with an
+/// error, once the WebSocket has given up reconnecting.
+#[allow(clippy::too_many_arguments)]
 async fn run_adapter_loop(
-    tx: mpsc::Sender<MarketEvent>,
+    tx: channel::Sender<EventBatch>,
     clob: Arc<ClobClient>,
-    token_to_market: Arc<TokenToMarket>,
     eligible: Vec<EligibleMarket>,
-    token_ids: Vec<String>,
+    subscriptions: Subscriptions,
+    health: HealthRegistry,
+    reader: Option<Handle>,
+    ws_url: Option<String>,
+    keepalive: KeepaliveConfig,
+    connections: usize,
 ) -> anyhow::Result<()> {
-    // Start the WS loop immediately in its own task so we don't miss events
-    // while the initial CLOB REST fetch is in progress.
-    let ws_handle = tokio::spawn(run_ws_loop(
+    // Run the WS loop alongside the seed fetch so we don't miss events while
+    // the initial CLOB REST fetch is in progress. Both live in this task, so
+    // a panic in either ends the run as a whole and nothing is left behind.
+    let ws = tasks::instrument("adapter.polymarket.ws", run_ws_shards(
         tx.clone(),
-        token_ids,
-        Arc::clone(&token_to_market),
+        Arc::clone(&clob),
+        subscriptions,
+        health,
+        reader,
+        ws_url,
+        keepalive,
+        connections,
     ));
 
     // Initial CLOB REST price fetch — run up to 10 requests concurrently.
     // This seeds the market cache before the first WS event arrives.
-    let price_futures = eligible.into_iter().map(|em| {
-        let clob = Arc::clone(&clob);
-        let tx = tx.clone();
-        async move { fetch_and_emit_heartbeats(clob, tx, em).await }
-    });
-
-    futures::stream::iter(price_futures)
-        .buffer_unordered(10)
-        .for_each(|_| async {})
-        .await;
-
-    info!("initial CLOB price fetch complete");
-
-    if let Err(e) = ws_handle.await {
-        error!(error = %e, "WebSocket task panicked");
-    }
-
-    Ok(())
+    let seed = async {
+        let price_futures = eligible.into_iter().map(|em| {
+            let clob = Arc::clone(&clob);
+            let tx = tx.clone();
+            async move { fetch_and_emit_heartbeats(clob, tx, em).await }
+        });
+        futures::stream::iter(price_futures)
+            .buffer_unordered(10)
+            .for_each(|_| async {})
+            .await;
+        info!("initial CLOB price fetch complete");
+    };
+
+    tokio::join!(ws, seed);
+    anyhow::bail!("WebSocket gave up reconnecting")
 }
 
 /// Fetch CLOB prices for both tokens of a market and emit `Heartbeat` events.
 async fn fetch_and_emit_heartbeats(
     clob: Arc<ClobClient>,
-    tx: mpsc::Sender<MarketEvent>,
+    tx: channel::Sender<EventBatch>,
     em: EligibleMarket,
 ) {
     for token_id in &em.token_ids {
@@ -167,26 +309,19 @@ async fn fetch_and_emit_heartbeats(
 
         // CLOB API semantics:  side=BUY → best ask,  side=SELL → best bid
         let (best_bid, best_ask) = match prices {
-            Some((buy_price, sell_price)) => (sell_price.to_f64(), buy_price.to_f64()),
+            Some((buy_price, sell_price)) => (Some(sell_price), Some(buy_price)),
             None => (None, None),
         };
 
-        let event = MarketEvent {
-            venue: Venue::Polymarket,
-            kind: MarketEventKind::Heartbeat,
-            market_id: em.market_id.clone(),
-            token_id: token_id.clone(),
-            ts_exchange_ms: Some(SystemTime::now()),
-            ts_receive_ms: None,
-            received_at: Instant::now(),
-            volume24h: Some(em.volume),
-            last_trade_price: em.last_trade_price,
-            liquidity: em.liquidity,
-            best_bid,
-            best_ask,
-        };
+        let mut event = pool::event(Venue::Polymarket, MarketEventKind::Heartbeat, &em.market_id, token_id, Instant::now());
+        event.ts_exchange_ms = Some(SystemTime::now());
+        event.volume24h = Some(em.volume);
+        event.last_trade_price = em.last_trade_price;
+        event.liquidity = em.liquidity;
+        event.best_bid = best_bid;
+        event.best_ask = best_ask;
 
-        if tx.send(event).await.is_err() {
+        if tx.send(pool::single(event)).await.is_err() {
             warn!("channel closed during initial heartbeat");
             return;
         }
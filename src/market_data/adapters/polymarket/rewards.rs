@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use polymarket_rs::request::{PaginationParams, END_CURSOR};
+use polymarket_rs::ClobClient;
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+
+use super::TokenToMarket;
+use crate::market_data::types::Venue;
+use crate::rate_limit::{self, EndpointClass};
+use crate::state::rewards::{RewardParams, RewardsBook};
+
+/// The CLOB reports `max_spread` in cents.
+const CENTS: Decimal = Decimal::ONE_HUNDRED;
+
+/// Fetch the reward programs of the subscribed markets from the CLOB's
+/// `/sampling-markets` listing (every market currently paying rewards),
+/// keyed by our market_id. Markets not in the listing pay nothing.
+pub async fn fetch_rewards(clob: &ClobClient, token_to_market: &TokenToMarket) -> anyhow::Result<HashMap<String, RewardParams>> {
+    let mut params = HashMap::new();
+    let mut cursor = PaginationParams::initial();
+    loop {
+        rate_limit::acquire(Venue::Polymarket, EndpointClass::Discovery).await;
+        let page = clob.get_sampling_markets(Some(cursor)).await?;
+        for market in page.data {
+            let Some(market_id) = market.tokens.iter().find_map(|t| token_to_market.get(&t.token_id)) else {
+                continue;
+            };
+            let daily_rate = market.rewards.rates.iter().flatten().map(|r| r.rewards_daily_rate).sum();
+            params.insert(market_id.clone(), RewardParams {
+                max_spread: market.rewards.max_spread / CENTS,
+                min_size: market.rewards.min_size,
+                daily_rate,
+            });
+        }
+        match page.next_cursor {
+            Some(next) if next != END_CURSOR && !next.is_empty() => cursor = PaginationParams::with_cursor(next),
+            _ => break,
+        }
+    }
+    Ok(params)
+}
+
+/// Refresh `book` every `interval`. A failed fetch keeps the previous table.
+pub async fn run_rewards_refresher(book: RewardsBook, token_to_market: Arc<TokenToMarket>, interval: Duration) {
+    let clob = ClobClient::new("https://clob.polymarket.com");
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match fetch_rewards(&clob, &token_to_market).await {
+            Ok(params) => {
+                let markets = params.len();
+                book.replace(params);
+                info!(markets, "liquidity-rewards programs refreshed");
+            }
+            Err(e) => warn!(error = %e, "liquidity-rewards fetch failed; keeping the previous programs"),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Spreading the subscribed tokens over several market-channel connections.
+//!
+//! Polymarket caps how many tokens one connection may carry, so a universe
+//! of hundreds of markets needs more than one socket. Each market is placed
+//! on the connection carrying the fewest tokens when it's first subscribed,
+//! both outcomes together since one price change usually covers both, and
+//! stays there for as long as it's subscribed. Adding a market therefore
+//! never moves another, and a reconnect only resubscribes its own share.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use metrics::gauge;
+
+use crate::health::HealthRegistry;
+use crate::market_data::subscriptions::Subscribed;
+use super::ws::HEALTH_COMPONENT;
+
+/// Which connection carries each market. Cheap to clone; every clone sees
+/// the same placements.
+#[derive(Clone)]
+pub(super) struct ShardMap {
+    connections: usize,
+    /// Market id → connection.
+    placed: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl ShardMap {
+    pub(super) fn new(connections: usize) -> Self {
+        Self { connections: connections.max(1), placed: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    pub(super) fn connections(&self) -> usize {
+        self.connections
+    }
+
+    /// The part of `wanted` (the whole subscribed set) that `connection`
+    /// carries. Markets not placed yet are placed first, and markets no
+    /// longer wanted are forgotten.
+    pub(super) fn carried_by(&self, connection: usize, wanted: Vec<Subscribed>) -> Vec<Subscribed> {
+        let mut placed = self.placed.lock().expect("shard map poisoned");
+        let markets: HashSet<&str> = wanted.iter().map(|s| s.market_id.as_str()).collect();
+        placed.retain(|market_id, _| markets.contains(market_id.as_str()));
+
+        // Tokens on each connection, counting new markets as they're placed.
+        let mut load = vec![0usize; self.connections];
+        for s in &wanted {
+            if let Some(&connection) = placed.get(&s.market_id) {
+                load[connection] += 1;
+            }
+        }
+        let mut fresh: HashSet<&str> = HashSet::new();
+        for s in &wanted {
+            let connection = match placed.get(&s.market_id) {
+                Some(&connection) if fresh.contains(s.market_id.as_str()) => connection,
+                Some(_) => continue,
+                None => {
+                    // The first of the least loaded, so placement is deterministic.
+                    let connection = (0..self.connections).min_by_key(|&i| load[i]).unwrap_or(0);
+                    placed.insert(s.market_id.clone(), connection);
+                    fresh.insert(&s.market_id);
+                    connection
+                }
+            };
+            load[connection] += 1;
+        }
+        for (i, tokens) in load.iter().enumerate() {
+            gauge!("polymarket_ws_connection_tokens", "connection" => i.to_string()).set(*tokens as f64);
+        }
+        wanted.into_iter().filter(|s| placed[&s.market_id] == connection).collect()
+    }
+}
+
+/// The connections' state, reported to health as the one adapter component:
+/// up while every connection is.
+#[derive(Clone)]
+pub(super) struct ConnectionHealth {
+    health: HealthRegistry,
+    /// Why each connection is down; `None` while it's up.
+    down: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl ConnectionHealth {
+    pub(super) fn new(health: HealthRegistry, connections: usize) -> Self {
+        Self { health, down: Arc::new(Mutex::new(vec![Some("not started".to_string()); connections.max(1)])) }
+    }
+
+    pub(super) fn set_up(&self, connection: usize) {
+        self.set(connection, None);
+    }
+
+    pub(super) fn set_down(&self, connection: usize, detail: &str) {
+        self.set(connection, Some(detail.to_string()));
+    }
+
+    fn set(&self, connection: usize, detail: Option<String>) {
+        let mut down = self.down.lock().expect("connection health poisoned");
+        down[connection] = detail;
+        match down.iter().enumerate().find_map(|(i, detail)| detail.as_ref().map(|d| (i, d))) {
+            None => self.health.set_up(HEALTH_COMPONENT),
+            Some((_, detail)) if down.len() == 1 => self.health.set_down(HEALTH_COMPONENT, detail.clone()),
+            Some((i, detail)) => self.health.set_down(HEALTH_COMPONENT, format!("connection {i}: {detail}")),
+        }
+    }
+}
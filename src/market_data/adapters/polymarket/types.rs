@@ -1,17 +1,22 @@
 use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
 use polymarket_rs::types::GammaMarket;
+use serde::{Deserialize, Serialize};
+
+use crate::state::ids::TokenId;
 
 // ── Public types used by the strategy engine and main ────────────────────────
 
 /// Metadata for a binary (YES/NO) prediction market on Polymarket.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketInfo {
     pub market_id: String,
     pub question: String,
     /// Token ID for the YES outcome.
-    pub yes_token_id: String,
+    pub yes_token_id: TokenId,
     /// Token ID for the NO outcome.
-    pub no_token_id: String,
+    pub no_token_id: TokenId,
     pub neg_risk: bool,
 }
 
@@ -22,10 +27,24 @@ pub type MarketMap = HashMap<String, MarketInfo>;
 /// Used by the WS handler to map incoming token events back to their market.
 pub type TokenToMarket = HashMap<String, String>;
 
+/// A market found by standalone discovery, with the activity it was ranked
+/// on. Kalshi discovery returns these too.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredMarket {
+    #[serde(flatten)]
+    pub info: MarketInfo,
+    /// Lifetime cumulative volume: USD on Polymarket, contracts on Kalshi.
+    pub volume: f64,
+    pub liquidity: Option<f64>,
+    /// When the market is scheduled to end, where Gamma says.
+    pub end_date: Option<DateTime<Utc>>,
+}
+
 // ── Internal types used only during startup ───────────────────────────────────
 
 /// A market that has passed all eligibility filters during startup.
 /// Exists only during initialisation — not exposed outside the adapter.
+#[derive(Clone)]
 pub(super) struct EligibleMarket {
     pub market_id: String,
     pub question: String,
@@ -35,22 +54,116 @@ pub(super) struct EligibleMarket {
     pub volume: f64,
     pub last_trade_price: Option<f64>,
     pub liquidity: Option<f64>,
+    pub end_date: Option<DateTime<Utc>>,
     pub neg_risk: bool,
+    /// What the universe filter was applied to.
+    pub activity: MarketActivity,
+    /// Listed in [`UniverseSelection::markets`] rather than picked by activity.
+    pub pinned: bool,
+}
+
+impl EligibleMarket {
+    pub fn info(&self) -> MarketInfo {
+        MarketInfo {
+            market_id: self.market_id.clone(),
+            question: self.question.clone(),
+            yes_token_id: TokenId::intern(&self.token_ids[0]),
+            no_token_id: TokenId::intern(&self.token_ids[1]),
+            neg_risk: self.neg_risk,
+        }
+    }
 }
 
 // ── Market eligibility filter ─────────────────────────────────────────────────
 
+/// Activity thresholds a market must clear during discovery.
+#[derive(Debug, Clone, Copy)]
+pub struct UniverseFilter {
+    /// Minimum 24-hour volume (USD).
+    pub min_volume_24h: f64,
+    /// Minimum order book liquidity (USD).
+    pub min_liquidity: f64,
+}
+
+impl Default for UniverseFilter {
+    fn default() -> Self {
+        Self { min_volume_24h: 100_000.0, min_liquidity: 10_000.0 }
+    }
+}
+
+impl UniverseFilter {
+    pub fn admits(&self, activity: &MarketActivity) -> bool {
+        activity.volume_24h >= self.min_volume_24h && activity.liquidity >= self.min_liquidity
+    }
+}
+
+/// Which markets discovery picks, on top of the [`UniverseFilter`] thresholds.
+/// The default searches every active market and keeps all that qualify.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UniverseSelection {
+    /// Always subscribed while active and binary, whatever their activity:
+    /// market slugs, condition IDs (`0x…`), or Gamma market ids.
+    pub markets: Vec<String>,
+    /// Only search markets with one of these Gamma tag ids.
+    pub tags: Vec<String>,
+    /// Only keep markets in one of these categories (case-insensitive).
+    pub categories: Vec<String>,
+    /// Keep the N qualifying markets with the highest 24h volume. Pinned
+    /// `markets` come on top; `Some(0)` subscribes to the pinned ones only.
+    pub top_n: Option<usize>,
+    /// Skip markets ending sooner than this from now, e.g. to stay out of
+    /// the run-up to resolution.
+    pub min_time_to_end: Option<Duration>,
+    /// Skip markets ending later than this from now, and those with no end
+    /// date.
+    pub max_time_to_end: Option<Duration>,
+}
+
+impl UniverseSelection {
+    /// Whether `m` is one of the pinned markets.
+    pub(super) fn pins(&self, m: &GammaMarket) -> bool {
+        self.markets.iter().any(|key| key == &m.slug || key.eq_ignore_ascii_case(&m.condition_id) || key == &m.id)
+    }
+
+    pub(super) fn in_categories(&self, m: &GammaMarket) -> bool {
+        self.categories.is_empty()
+            || m.category.as_deref().is_some_and(|c| self.categories.iter().any(|want| want.eq_ignore_ascii_case(c)))
+    }
+
+    /// The window `m` must end in, from `now`: `(earliest, latest)`.
+    pub(super) fn end_window(&self, now: DateTime<Utc>) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let after = |d: Duration| chrono::Duration::from_std(d).ok().map(|d| now + d);
+        (self.min_time_to_end.and_then(after), self.max_time_to_end.and_then(after))
+    }
+
+    pub(super) fn ends_in_window(&self, m: &GammaMarket, now: DateTime<Utc>) -> bool {
+        let (earliest, latest) = self.end_window(now);
+        match m.end_date {
+            Some(end) => earliest.is_none_or(|t| end >= t) && latest.is_none_or(|t| end <= t),
+            None => latest.is_none(),
+        }
+    }
+}
+
+/// A market's activity as of discovery, kept so the universe can be
+/// re-filtered when the thresholds change at runtime.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarketActivity {
+    pub volume_24h: f64,
+    pub liquidity: f64,
+}
+
 /// Parse a raw Gamma API market and decide whether it's eligible for trading.
 ///
 /// A market is eligible when it:
 /// - is active, not closed, not archived
 /// - is binary (exactly 2 CLOB token IDs)
 /// - has non-zero outcome prices
-/// - has ≥ $100K 24-hour volume
-/// - has ≥ $10K liquidity
+/// - has at least `filter.min_volume_24h` 24-hour volume ($100K by default)
+/// - has at least `filter.min_liquidity` liquidity ($10K by default)
 ///
 /// Returns `None` if any condition fails.
-pub(super) fn try_parse_eligible(m: &GammaMarket) -> Option<EligibleMarket> {
+pub(super) fn try_parse_eligible(m: &GammaMarket, filter: &UniverseFilter) -> Option<EligibleMarket> {
     if !m.active || m.closed || m.archived {
         return None;
     }
@@ -83,10 +196,11 @@ pub(super) fn try_parse_eligible(m: &GammaMarket) -> Option<EligibleMarket> {
         return None; // not a binary market
     }
 
-    if m.volume24hr.unwrap_or(0.0) < 100_000.0 {
-        return None;
-    }
-    if m.liquidity_num.unwrap_or(0.0) < 10_000.0 {
+    let activity = MarketActivity {
+        volume_24h: m.volume24hr.unwrap_or(0.0),
+        liquidity: m.liquidity_num.unwrap_or(0.0),
+    };
+    if !filter.admits(&activity) {
         return None;
     }
 
@@ -104,6 +218,9 @@ pub(super) fn try_parse_eligible(m: &GammaMarket) -> Option<EligibleMarket> {
         volume,
         last_trade_price: m.last_trade_price,
         liquidity: m.liquidity.as_ref().and_then(|l| l.parse::<f64>().ok()),
+        end_date: m.end_date,
         neg_risk: false,
+        activity,
+        pinned: false,
     })
 }
@@ -1,91 +1,336 @@
+use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime};
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{info, warn, debug, error};
 use rust_decimal::prelude::ToPrimitive;
-use polymarket_rs::types::WsEvent;
-use polymarket_rs::websocket::MarketWsClient;
-use polymarket_rs::StreamExt;
+use polymarket_rs::types::{OrderBookSummary, PriceLevel, Side as BookSide, WsEvent};
+use polymarket_rs::websocket::{parse_text_into, MarketSubscriptionControl, MarketWsClient};
+use polymarket_rs::{ClobClient, StreamExt};
+use rust_decimal::Decimal;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
 
-use crate::market_data::types::{MarketEvent, MarketEventKind, Venue};
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::book_builder::{BookBuilder, DeltaOutcome};
+use crate::market_data::pool;
+use crate::market_data::subscriptions::{SubscriptionChange, Subscriptions};
+use crate::market_data::types::{BookLevel, EventBatch, MarketEventKind, Side, Venue};
 use crate::metrics::prometheus::record_adapter_event;
+use crate::ring;
+use crate::market_data::adapters::keepalive::{Keepalive, KeepaliveConfig};
+use crate::market_data::adapters::reconnect::{backoff_duration, MAX_RECONNECT_ATTEMPTS};
+use super::clob::fetch_book;
+use super::shards::{ConnectionHealth, ShardMap};
 use super::types::TokenToMarket;
 
-// ── Reconnect policy ──────────────────────────────────────────────────────────
-
-const MAX_RECONNECT_ATTEMPTS: u32 = 10;
-/// Initial reconnect wait: 500 ms, doubling on each failure up to MAX_BACKOFF_MS.
-const INITIAL_BACKOFF_MS: u64 = 500;
-const MAX_BACKOFF_MS: u64 = 30_000;
-
 /// How often to emit a summary log of WebSocket activity.
 const LOG_INTERVAL: Duration = Duration::from_secs(30);
 
+pub(super) const HEALTH_COMPONENT: &str = "adapter.polymarket";
+
+/// Raw frames the socket reader may run ahead of the parser.
+const FRAME_RING_CAPACITY: usize = 4_096;
+
+/// Wait between attempts to fetch a book over REST for a resync.
+const RESYNC_RETRY: Duration = Duration::from_secs(1);
+
 // ── Public entry point ────────────────────────────────────────────────────────
 
-/// Run the Polymarket WebSocket loop forever, reconnecting on failure.
+/// Stream `subscriptions` over `connections` WebSockets, each carrying its
+/// share of the markets (see [`shards`](super::shards)) and reconnecting on
+/// its own; every connection sends its events over the same `tx`. Returns
+/// once any of them gives up reconnecting, so the adapter restarts whole.
 ///
-/// Subscribes to order book updates for all `token_ids` and converts each
-/// incoming event into a `MarketEvent` sent over `tx`.
+/// Subscription commands are read here and passed on to every connection,
+/// which syncs its own share. Connection state is reported to `health` as
+/// one [`HEALTH_COMPONENT`], down while any connection is.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn run_ws_shards(
+    tx: channel::Sender<EventBatch>,
+    clob: Arc<ClobClient>,
+    subscriptions: Subscriptions,
+    health: HealthRegistry,
+    reader_runtime: Option<Handle>,
+    ws_url: Option<String>,
+    keepalive: KeepaliveConfig,
+    connections: usize,
+) {
+    let mut commands = subscriptions.commands().await;
+    let shards = ShardMap::new(connections);
+    let status = ConnectionHealth::new(health, shards.connections());
+    let mut notify = Vec::new();
+    let mut loops = Vec::new();
+    for index in 0..shards.connections() {
+        let (changed_tx, changed) = mpsc::unbounded_channel();
+        notify.push(changed_tx);
+        let connection = Connection { index, shards: shards.clone(), changed, health: status.clone() };
+        loops.push(Box::pin(run_ws_loop(
+            connection,
+            tx.clone(),
+            Arc::clone(&clob),
+            subscriptions.clone(),
+            reader_runtime.clone(),
+            ws_url.clone(),
+            keepalive,
+        )));
+    }
+    let forward = async {
+        while commands.recv().await.is_some() {
+            while commands.try_recv().is_ok() {}
+            for changed in &notify {
+                let _ = changed.send(());
+            }
+        }
+        // The control holding the sender lives as long as the adapter.
+        std::future::pending::<()>().await
+    };
+    tokio::select! {
+        _ = futures::future::select_all(loops) => {}
+        _ = forward => {}
+    }
+}
+
+/// One connection's share of the adapter.
+struct Connection {
+    index: usize,
+    shards: ShardMap,
+    /// Told when the subscriptions change.
+    changed: mpsc::UnboundedReceiver<()>,
+    health: ConnectionHealth,
+}
+
+/// Run one Polymarket WebSocket, reconnecting on failure until the
+/// retries run out (see [`reconnect`](crate::market_data::adapters::reconnect)).
+///
+/// Subscribes to order book updates for the tokens in `subscriptions` that
+/// the connection carries and converts each incoming event into a
+/// `MarketEvent` sent over `tx`. When the stream ends every token is sent a
+/// `Resync` before the same tokens are resubscribed. One of several
+/// connections with no tokens to carry stays closed until the
+/// subscriptions change.
 ///
-/// Two event types are handled:
+/// Subscription changes are applied to the open connection: added tokens
+/// are subscribed on it (Polymarket sends their books as it does on
+/// connect) and removed ones unsubscribed and sent an `Unsubscribed`.
+///
+/// Three event types are handled:
 ///
 /// - **`BookEvent`**: Full order book snapshot sent on (re)connection.
-///   `bids[0]` = highest bid, `asks[0]` = lowest ask — used directly as
-///   best_bid/best_ask and sent as a `Heartbeat` to seed the market cache.
+///   Emitted as a `BookSnapshot` carrying every level, which replaces the
+///   token's book in the market cache.
 ///
 /// - **`PriceChangeEvent`**: Incremental update to a price level.
 ///   Each entry carries its own `asset_id`, `best_bid`, and `best_ask`
 ///   reflecting the real top-of-book *after* this update. A single event
 ///   often covers both the YES and NO tokens of the same market, so we
-///   process each entry independently.
-pub(super) async fn run_ws_loop(
-    tx: mpsc::Sender<MarketEvent>,
-    token_ids: Vec<String>,
-    token_to_market: Arc<TokenToMarket>,
+///   process each entry independently, each as a `BookDelta`.
+///
+///   Polymarket doesn't number its messages, so the books are kept in a
+///   [`BookBuilder`] and each entry's best prices checked against the local
+///   book after the change. A mismatch means an update was missed: the
+///   token is emitted as `BookStale` and its book refetched from `clob`'s
+///   `/book` endpoint, in the background, to go out as a `BookSnapshot`.
+///   Until then its entries go out as `PriceChange`s carrying only the
+///   venue's best prices.
+///
+/// - **`LastTradePriceEvent`**: A match. Emitted as a `Trade` carrying the
+///   aggressor's side, and recorded as the token's last trade price.
+///
+/// Every event carries the exchange's own timestamp.
+///
+/// Everything decoded from one frame (a message can be an array of events,
+/// and a price change can cover several tokens) goes downstream as a single
+/// [`EventBatch`], so the frame lands in the cache as one unit.
+///
+/// The socket is read on a task of its own, which hands each frame's raw
+/// text and receive time to this loop over an SPSC [`ring`]. Reading the
+/// next frame then overlaps parsing the last, and a slow parse doesn't stall
+/// the socket. With `reader_runtime` set, the socket is opened and read on
+/// that runtime (a venue thread of its own) instead of this one.
+///
+/// The connection is pinged on `keepalive`'s schedule, and dropped as if
+/// the stream had ended once too many pings go unanswered (see
+/// [`keepalive`](crate::market_data::adapters::keepalive)).
+///
+/// `ws_url` overrides Polymarket's market channel endpoint.
+async fn run_ws_loop(
+    connection: Connection,
+    tx: channel::Sender<EventBatch>,
+    clob: Arc<ClobClient>,
+    subscriptions: Subscriptions,
+    reader_runtime: Option<Handle>,
+    ws_url: Option<String>,
+    keepalive: KeepaliveConfig,
 ) {
+    let Connection { index, shards, mut changed, health } = connection;
+    let carried = |wanted| shards.carried_by(index, wanted);
     let mut attempt: u32 = 0;
+    let mut messages = Vec::new();
+    let mut books = BookBuilder::new();
+    let mut stale = Vec::new();
+    // What the current connection is subscribed to: token id → market id.
+    let mut token_to_market = TokenToMarket::new();
 
     loop {
+        token_to_market.clear();
+        subscriptions.sync_where(&mut token_to_market, carried);
+        while changed.try_recv().is_ok() {} // already in the set just synced
+        // A lone connection opens regardless, as it always has.
+        if token_to_market.is_empty() && shards.connections() > 1 {
+            health.set_up(index);
+            if changed.recv().await.is_none() {
+                return;
+            }
+            continue;
+        }
+
         attempt += 1;
-        info!(attempt, "connecting to Polymarket WebSocket");
+        info!(connection = index, attempt, "connecting to Polymarket WebSocket");
 
-        let ws_client = MarketWsClient::new();
+        // The socket is opened where it will be read, so a venue thread
+        // also does the readiness polling for it.
+        let subscribe = {
+            let (token_ids, ws_url) = (token_to_market.keys().cloned().collect(), ws_url.clone());
+            async move {
+                let client = ws_url.map_or_else(MarketWsClient::new, MarketWsClient::with_url);
+                client.subscribe_frames_with_control(token_ids).await
+            }
+        };
+        let subscribed = match &reader_runtime {
+            Some(runtime) => runtime
+                .spawn(subscribe)
+                .await
+                .unwrap_or_else(|e| Err(polymarket_rs::Error::WebSocket(format!("reader runtime: {e}")))),
+            None => subscribe.await,
+        };
 
-        let mut stream = match ws_client.subscribe(token_ids.clone()).await {
+        let (frames, mut control) = match subscribed {
             Ok(s) => {
-                info!(tokens = token_ids.len(), "WebSocket connected");
+                info!(connection = index, tokens = token_to_market.len(), "WebSocket connected");
+                health.set_up(index);
+                books.clear(); // every book is resnapshotted on subscribe
                 attempt = 0; // reset on successful connection
                 s
             }
             Err(e) => {
                 if attempt >= MAX_RECONNECT_ATTEMPTS {
-                    error!(error = %e, attempts = attempt, "max WS reconnect attempts reached");
+                    error!(connection = index, error = %e, attempts = attempt, "max WS reconnect attempts reached");
+                    health.set_down(index, "gave up reconnecting");
                     return;
                 }
-                let backoff_ms = backoff_duration(attempt);
-                warn!(error = %e, attempt, backoff_ms, "WS connection failed, retrying");
-                tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                let backoff = backoff_duration(attempt);
+                warn!(connection = index, error = %e, attempt, backoff_ms = backoff.as_millis() as u64, "WS connection failed, retrying");
+                tokio::time::sleep(backoff).await;
                 continue;
             }
         };
 
+        // Dropping the set when this connection ends stops its reader.
+        let (mut producer, mut consumer) = ring::ring(FRAME_RING_CAPACITY);
+        let mut reader = JoinSet::new();
+        let read = async move {
+            let mut frames = frames;
+            while let Some(frame) = frames.next().await {
+                if producer.push((Instant::now(), frame)).await.is_err() {
+                    break;
+                }
+            }
+        };
+        match &reader_runtime {
+            Some(runtime) => reader.spawn_on(read, runtime),
+            None => reader.spawn(read),
+        };
+
         // Counters for the periodic activity log.
         let mut last_log = Instant::now();
         let mut events_since_log: u64 = 0;
         let mut unknown_since_log: u64 = 0;
 
-        while let Some(message) = stream.next().await {
-            match message {
-                Ok(WsEvent::Book(book)) => {
-                    handle_book_event(&tx, &token_to_market, book, &mut unknown_since_log).await;
+        // REST book fetches for out-of-sync tokens; dropped with the connection.
+        let mut resyncs: JoinSet<OrderBookSummary> = JoinSet::new();
+        let mut resyncing: HashSet<String> = HashSet::new();
+        let mut pings = Keepalive::new(Venue::Polymarket, keepalive);
+
+        loop {
+            let (received_at, frame) = tokio::select! {
+                frame = consumer.pop() => match frame {
+                    Some(frame) => frame,
+                    None => break,
+                },
+                Some(fetched) = resyncs.join_next(), if !resyncs.is_empty() => {
+                    let Ok(book) = fetched else { continue };
+                    resyncing.remove(&book.asset_id);
+                    let mut batch = pool::batch();
+                    handle_rest_book(&token_to_market, &mut books, book, &mut batch);
+                    if tx.send(batch).await.is_err() {
+                        warn!("market event channel closed");
+                    }
+                    continue;
                 }
-                Ok(WsEvent::PriceChange(pc)) => {
-                    handle_price_change(&tx, &token_to_market, pc, &mut events_since_log, &mut unknown_since_log).await;
+                Some(_) = changed.recv() => {
+                    while changed.try_recv().is_ok() {}
+                    let change = subscriptions.sync_where(&mut token_to_market, carried);
+                    apply_subscription_change(&mut control, &change, &mut books, &mut resyncing, &tx).await;
+                    continue;
                 }
-                Ok(_) => {} // LastTradePrice, TickSizeChange — not needed yet
-                Err(e) => {
-                    warn!(error = %e, "WebSocket stream error");
+                due = pings.ping_due() => {
+                    if let Err(e) = due {
+                        warn!(connection = index, error = %e, "Polymarket WebSocket unresponsive, reconnecting");
+                        break;
+                    }
+                    if let Err(e) = control.ping().await {
+                        warn!(error = %e, "WS ping failed");
+                    }
+                    continue;
+                }
+            };
+            if matches!(&frame, Ok(text) if text == "PONG") {
+                pings.pong(received_at);
+                continue;
+            }
+            match frame {
+                Ok(text) => parse_text_into(&text, &mut messages),
+                Err(e) => messages.push(Err(e)),
+            }
+            let mut batch = pool::batch();
+            for message in messages.drain(..) {
+                match message {
+                    Ok(WsEvent::Book(book)) => {
+                        handle_book_event(&token_to_market, &mut books, book, received_at, &mut batch, &mut unknown_since_log);
+                    }
+                    Ok(WsEvent::PriceChange(pc)) => {
+                        handle_price_change(
+                            &token_to_market,
+                            &mut books,
+                            pc,
+                            received_at,
+                            &mut batch,
+                            &mut events_since_log,
+                            &mut unknown_since_log,
+                            &mut stale,
+                        );
+                    }
+                    Ok(WsEvent::LastTradePrice(trade)) => {
+                        handle_last_trade(&token_to_market, trade, received_at, &mut batch, &mut unknown_since_log);
+                    }
+                    Ok(_) => {} // TickSizeChange — not needed yet
+                    Err(e) => {
+                        warn!(error = %e, "WebSocket stream error");
+                    }
+                }
+            }
+            if batch.is_empty() {
+                pool::recycle_batch(batch);
+            } else if tx.send(batch).await.is_err() {
+                warn!("market event channel closed");
+            }
+
+            for token_id in stale.drain(..) {
+                if resyncing.insert(token_id.clone()) {
+                    warn!(%token_id, "order book out of sync, fetching a fresh snapshot");
+                    resyncs.spawn(refetch_book(Arc::clone(&clob), token_id));
                 }
             }
 
@@ -101,14 +346,20 @@ pub(super) async fn run_ws_loop(
             }
         }
 
-        // Stream ended — reconnect.
+        // Stream ended — mark every token for resync, then reconnect.
+        health.set_down(index, "stream ended, reconnecting");
+        let subscribed = token_to_market.iter().map(|(token, market)| (market.as_str(), token.as_str()));
+        if tx.send(pool::notices(Venue::Polymarket, MarketEventKind::Resync, subscribed)).await.is_err() {
+            warn!("market event channel closed");
+        }
         if attempt >= MAX_RECONNECT_ATTEMPTS {
-            error!(attempts = attempt, "max WS reconnect attempts reached");
+            error!(connection = index, attempts = attempt, "max WS reconnect attempts reached");
+            health.set_down(index, "gave up reconnecting");
             return;
         }
-        let backoff_ms = backoff_duration(attempt);
-        warn!(attempt, backoff_ms, "WS stream ended, reconnecting");
-        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+        let backoff = backoff_duration(attempt);
+        warn!(connection = index, attempt, backoff_ms = backoff.as_millis() as u64, "WS stream ended, reconnecting");
+        tokio::time::sleep(backoff).await;
     }
 }
 
@@ -117,22 +368,26 @@ pub(super) async fn run_ws_loop(
 /// Handle a full order book snapshot (`BookEvent`).
 ///
 /// Sent by Polymarket on initial connection (and after reconnects) for each
-/// subscribed token. `bids` are sorted highest-first, `asks` lowest-first,
-/// so `bids[0]` and `asks[0]` give us the real top-of-book.
-async fn handle_book_event(
-    tx: &mpsc::Sender<MarketEvent>,
-    token_to_market: &Arc<TokenToMarket>,
+/// subscribed token. The levels are sorted best-first before they go out,
+/// so the first of each side is the real top-of-book.
+fn handle_book_event(
+    token_to_market: &TokenToMarket,
+    books: &mut BookBuilder,
     book: polymarket_rs::types::BookEvent,
+    received_at: Instant,
+    batch: &mut EventBatch,
     unknown_count: &mut u64,
 ) {
-    let Some(market_id) = token_to_market.get(&book.asset_id).cloned() else {
+    let Some(market_id) = token_to_market.get(&book.asset_id) else {
         *unknown_count += 1;
         debug!(asset_id = %book.asset_id, "book snapshot for unknown token");
         return;
     };
 
-    let best_bid = book.bids.first().and_then(|pl| pl.price.to_f64());
-    let best_ask = book.asks.first().and_then(|pl| pl.price.to_f64());
+    let (bids, asks) = sorted_levels(&book.bids, &book.asks);
+    let best_bid = bids.first().map(|l| l.price);
+    let best_ask = asks.first().map(|l| l.price);
+    books.snapshot(&book.asset_id, &bids, &asks, None);
 
     record_adapter_event("Polymarket", "book_snapshot");
 
@@ -146,24 +401,41 @@ async fn handle_book_event(
         "book snapshot received"
     );
 
-    let event = MarketEvent {
-        venue: Venue::Polymarket,
-        kind: MarketEventKind::Heartbeat,
-        market_id,
-        token_id: book.asset_id,
-        ts_exchange_ms: None,
-        ts_receive_ms: Some(SystemTime::now()),
-        received_at: Instant::now(),
-        volume24h: None,
-        last_trade_price: None,
-        liquidity: None,
-        best_bid,
-        best_ask,
+    let kind = MarketEventKind::BookSnapshot { bids, asks };
+    let mut event = pool::event(Venue::Polymarket, kind, market_id, &book.asset_id, received_at);
+    event.ts_exchange_ms = exchange_time(&book.timestamp);
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(Instant::now());
+    event.best_bid = best_bid;
+    event.best_ask = best_ask;
+    batch.push(event);
+}
+
+/// Handle a book fetched over REST to resync a token, as a `BookSnapshot`.
+fn handle_rest_book(
+    token_to_market: &TokenToMarket,
+    books: &mut BookBuilder,
+    book: OrderBookSummary,
+    batch: &mut EventBatch,
+) {
+    let Some(market_id) = token_to_market.get(&book.asset_id) else {
+        return;
     };
+    let (bids, asks) = sorted_levels(&book.bids, &book.asks);
+    books.snapshot(&book.asset_id, &bids, &asks, None);
 
-    if tx.send(event).await.is_err() {
-        warn!("channel closed during book snapshot");
-    }
+    record_adapter_event("Polymarket", "book_resync");
+    info!(asset_id = %book.asset_id, market_id, "order book resynced");
+
+    let (best_bid, best_ask) = (bids.first().map(|l| l.price), asks.first().map(|l| l.price));
+    let kind = MarketEventKind::BookSnapshot { bids, asks };
+    let mut event = pool::event(Venue::Polymarket, kind, market_id, &book.asset_id, Instant::now());
+    event.ts_exchange_ms = Some(UNIX_EPOCH + Duration::from_millis(book.timestamp));
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(Instant::now());
+    event.best_bid = best_bid;
+    event.best_ask = best_ask;
+    batch.push(event);
 }
 
 /// Handle an incremental order book update (`PriceChangeEvent`).
@@ -177,26 +449,50 @@ async fn handle_book_event(
 ///
 /// Each entry also carries `best_bid` and `best_ask` — the real top-of-book
 /// values **after** this level change — so we use those directly rather than
-/// trying to infer the spread from the changed price level.
-async fn handle_price_change(
-    tx: &mpsc::Sender<MarketEvent>,
-    token_to_market: &Arc<TokenToMarket>,
+/// trying to infer the spread from the changed price level, and check the
+/// local book against them. Tokens whose book fails the check are pushed
+/// onto `stale` for the caller to resync.
+#[allow(clippy::too_many_arguments)]
+fn handle_price_change(
+    token_to_market: &TokenToMarket,
+    books: &mut BookBuilder,
     pc_event: polymarket_rs::types::PriceChangeEvent,
+    received_at: Instant,
+    batch: &mut EventBatch,
     event_count: &mut u64,
     unknown_count: &mut u64,
+    stale: &mut Vec<String>,
 ) {
-    let received_at = Instant::now();
     let now = SystemTime::now();
+    let exchange_ts = pc_event.timestamp.as_deref().and_then(exchange_time);
 
     for pc in &pc_event.price_changes {
-        let Some(market_id) = token_to_market.get(&pc.asset_id).cloned() else {
+        let Some(market_id) = token_to_market.get(&pc.asset_id) else {
             *unknown_count += 1;
             debug!(asset_id = %pc.asset_id, "price change for unknown token");
             continue;
         };
 
-        let best_bid = pc.best_bid.and_then(|d| d.to_f64());
-        let best_ask = pc.best_ask.and_then(|d| d.to_f64());
+        let (best_bid, best_ask) = (pc.best_bid, pc.best_ask);
+
+        let side = match pc.side {
+            BookSide::Buy => Side::Buy,
+            BookSide::Sell => Side::Sell,
+        };
+        // A price at the bounds isn't checked: an empty side may be reported that way.
+        let checked_bid = best_bid.filter(|bid| *bid > Decimal::ZERO);
+        let checked_ask = best_ask.filter(|ask| *ask < Decimal::ONE);
+        let kind = match books.delta(&pc.asset_id, &side, pc.price, pc.size, None) {
+            DeltaOutcome::Applied if books.check_top(&pc.asset_id, checked_bid, checked_ask) => {
+                MarketEventKind::BookDelta { side, price: pc.price, size: pc.size }
+            }
+            DeltaOutcome::Applied => {
+                stale.push(pc.asset_id.clone());
+                record_adapter_event("Polymarket", "book_stale");
+                MarketEventKind::BookStale
+            }
+            _ => MarketEventKind::PriceChange,
+        };
 
         *event_count += 1;
         record_adapter_event("Polymarket", "price_change");
@@ -212,31 +508,109 @@ async fn handle_price_change(
             "price change received"
         );
 
-        let event = MarketEvent {
-            venue: Venue::Polymarket,
-            kind: MarketEventKind::PriceChange,
-            market_id,
-            token_id: pc.asset_id.clone(),
-            ts_exchange_ms: None,
-            ts_receive_ms: Some(now),
-            received_at,
-            volume24h: None,
-            last_trade_price: None,
-            liquidity: None,
-            best_bid,
-            best_ask,
-        };
+        let mut event = pool::event(Venue::Polymarket, kind, market_id, &pc.asset_id, received_at);
+        event.ts_exchange_ms = exchange_ts;
+        event.ts_receive_ms = Some(now);
+        event.parsed_at = Some(Instant::now());
+        event.best_bid = best_bid;
+        event.best_ask = best_ask;
+        batch.push(event);
+    }
+}
 
-        if tx.send(event).await.is_err() {
-            warn!("channel closed during price change");
-            return;
+/// Handle a match (`LastTradePriceEvent`). Trades leave the book to the
+/// `book`/`price_change` messages that accompany them, so the event carries
+/// no best prices and the cached book is untouched.
+fn handle_last_trade(
+    token_to_market: &TokenToMarket,
+    trade: polymarket_rs::types::LastTradePriceEvent,
+    received_at: Instant,
+    batch: &mut EventBatch,
+    unknown_count: &mut u64,
+) {
+    let Some(market_id) = token_to_market.get(&trade.asset_id) else {
+        *unknown_count += 1;
+        debug!(asset_id = %trade.asset_id, "trade for unknown token");
+        return;
+    };
+    let (Some(price), Some(size)) = (trade.price.to_f64(), trade.size.to_f64()) else {
+        return;
+    };
+    let side = match trade.side {
+        BookSide::Buy => Side::Buy,
+        BookSide::Sell => Side::Sell,
+    };
+
+    record_adapter_event("Polymarket", "trade");
+    debug!(asset_id = %trade.asset_id, market_id, price, size, ?side, "trade received");
+
+    let mut event = pool::event(Venue::Polymarket, MarketEventKind::Trade { price, size, side }, market_id, &trade.asset_id, received_at);
+    event.ts_exchange_ms = exchange_time(&trade.timestamp);
+    event.ts_receive_ms = Some(SystemTime::now());
+    event.parsed_at = Some(Instant::now());
+    event.last_trade_price = Some(price);
+    batch.push(event);
+}
+
+/// Subscribe and unsubscribe `change` on the open connection. Unsubscribed
+/// tokens' books are forgotten and an `Unsubscribed` sent for each. A failed
+/// write is only logged: the socket is gone, and the reconnect subscribes
+/// to the new set anyway.
+async fn apply_subscription_change(
+    control: &mut MarketSubscriptionControl,
+    change: &SubscriptionChange,
+    books: &mut BookBuilder,
+    resyncing: &mut HashSet<String>,
+    tx: &channel::Sender<EventBatch>,
+) {
+    if !change.added.is_empty() {
+        let token_ids: Vec<String> = change.added.iter().map(|s| s.token_id.clone()).collect();
+        info!(tokens = ?token_ids, "subscribing on the open WebSocket");
+        if let Err(e) = control.subscribe(token_ids).await {
+            warn!(error = %e, "WS subscribe failed");
+        }
+    }
+    if !change.removed.is_empty() {
+        let token_ids: Vec<String> = change.removed.iter().map(|s| s.token_id.clone()).collect();
+        info!(tokens = ?token_ids, "unsubscribing on the open WebSocket");
+        if let Err(e) = control.unsubscribe(token_ids).await {
+            warn!(error = %e, "WS unsubscribe failed");
+        }
+        for s in &change.removed {
+            books.remove(&s.token_id);
+            resyncing.remove(&s.token_id);
+        }
+        let removed = change.removed.iter().map(|s| (s.market_id.as_str(), s.token_id.as_str()));
+        if tx.send(pool::notices(Venue::Polymarket, MarketEventKind::Unsubscribed, removed)).await.is_err() {
+            warn!("market event channel closed");
         }
     }
 }
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
-/// Exponential backoff capped at `MAX_BACKOFF_MS`.
-fn backoff_duration(attempt: u32) -> u64 {
-    (INITIAL_BACKOFF_MS * 2u64.saturating_pow(attempt.saturating_sub(1))).min(MAX_BACKOFF_MS)
+/// Fetch `token_id`'s book over REST, retrying every [`RESYNC_RETRY`] until
+/// it comes back (or the connection that asked for it ends).
+async fn refetch_book(clob: Arc<ClobClient>, token_id: String) -> OrderBookSummary {
+    loop {
+        if let Some(book) = fetch_book(&clob, &token_id).await {
+            return book;
+        }
+        tokio::time::sleep(RESYNC_RETRY).await;
+    }
+}
+
+/// A book's levels as bids and asks, best first.
+fn sorted_levels(bids: &[PriceLevel], asks: &[PriceLevel]) -> (Vec<BookLevel>, Vec<BookLevel>) {
+    let level = |pl: &PriceLevel| BookLevel { price: pl.price, size: pl.size };
+    let mut bids: Vec<BookLevel> = bids.iter().map(level).collect();
+    let mut asks: Vec<BookLevel> = asks.iter().map(level).collect();
+    bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+    asks.sort_by_key(|l| l.price);
+    (bids, asks)
+}
+
+/// The CLOB stamps messages with unix milliseconds, as a string.
+fn exchange_time(timestamp: &str) -> Option<SystemTime> {
+    timestamp.parse::<u64>().ok().map(|ms| UNIX_EPOCH + Duration::from_millis(ms))
 }
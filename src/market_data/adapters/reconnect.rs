@@ -0,0 +1,28 @@
+//! Reconnect policy shared by the venue WebSocket loops.
+//!
+//! A dropped socket is retried with exponential backoff, jittered so that
+//! adapters (or engines) dropped by the same venue outage don't all come back
+//! in lockstep. Once the retries run out the adapter task returns an error
+//! and its supervisor restarts it from scratch.
+//!
+//! Whatever a venue sent while its socket was down is lost, so every token
+//! subscribed on it is marked with a `Resync` event ([`pool::notices`](crate::market_data::pool::notices))
+//! as soon as the stream ends: the market cache drops its books and best prices until the
+//! snapshots that follow the resubscription arrive.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+/// Initial reconnect wait: 500 ms, doubling on each failure up to MAX_BACKOFF_MS.
+const INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Wait before reconnect `attempt` (1-based): exponential backoff capped at
+/// `MAX_BACKOFF_MS`, of which a random part up to half is taken off.
+pub fn backoff_duration(attempt: u32) -> Duration {
+    let ceiling = (INITIAL_BACKOFF_MS * 2u64.saturating_pow(attempt.saturating_sub(1))).min(MAX_BACKOFF_MS);
+    let jitter = rand::thread_rng().gen_range(0..=ceiling / 2);
+    Duration::from_millis(ceiling - jitter)
+}
@@ -0,0 +1,169 @@
+//! Market data from venues that only have a REST API.
+//!
+//! Each [`PollFeed`] fetches one JSON endpoint on an interval and reads
+//! quotes out of the response with [`JsonPath`]s from the config: where the
+//! quotes are, and where each one's id, bid, ask and last price are. Every
+//! feed's events go out under `Venue::Rest`, with token and market ids
+//! prefixed by the feed's name (`predictit:31455`) so feeds can't collide.
+
+mod path;
+mod poll;
+
+pub use path::JsonPath;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::adapters::traits::{MarketDataAdapter, StopSignal};
+use crate::market_data::types::{EventBatch, Venue};
+use crate::metrics::tasks;
+
+/// Shortest interval a feed may poll at.
+const MIN_INTERVAL_MS: u64 = 100;
+
+/// One polled endpoint and how to read quotes out of its responses.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PollFeed {
+    /// Names the feed in logs, its health component and its ids.
+    pub name: String,
+    pub url: String,
+    /// Milliseconds between polls.
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// Sent with every request, e.g. an API key.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The quotes in a response, e.g. `$.contracts[*]`; the whole response
+    /// is one quote when unset.
+    #[serde(default)]
+    pub items: Option<JsonPath>,
+    /// Each quote's token id.
+    pub token_id: JsonPath,
+    /// Each quote's market id; its token id when unset.
+    #[serde(default)]
+    pub market_id: Option<JsonPath>,
+    #[serde(default)]
+    pub bid: Option<JsonPath>,
+    #[serde(default)]
+    pub ask: Option<JsonPath>,
+    #[serde(default)]
+    pub last: Option<JsonPath>,
+    /// Sizes at the bid and ask. With both (and both prices) a quote is a
+    /// `TopOfBook`, otherwise a `PriceChange`.
+    #[serde(default)]
+    pub bid_size: Option<JsonPath>,
+    #[serde(default)]
+    pub ask_size: Option<JsonPath>,
+    /// Multiplies every price read, e.g. `0.01` for prices in cents.
+    #[serde(default = "default_price_scale")]
+    pub price_scale: f64,
+}
+
+fn default_interval_ms() -> u64 {
+    5_000
+}
+
+fn default_price_scale() -> f64 {
+    1.0
+}
+
+impl PollFeed {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    /// Check the parts the config's types don't.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.name.trim().is_empty() && !self.name.contains(':'),
+            "feed name '{}' must be non-empty and have no ':'", self.name,
+        );
+        anyhow::ensure!(self.url.starts_with("http://") || self.url.starts_with("https://"), "url '{}' isn't http(s)", self.url);
+        anyhow::ensure!(
+            self.interval_ms >= MIN_INTERVAL_MS,
+            "interval_ms must be at least {MIN_INTERVAL_MS} (got {})", self.interval_ms,
+        );
+        anyhow::ensure!(
+            self.price_scale > 0.0 && self.price_scale.is_finite(),
+            "price_scale must be positive (got {})", self.price_scale,
+        );
+        anyhow::ensure!(
+            self.bid.is_some() || self.ask.is_some() || self.last.is_some(),
+            "map at least one of bid, ask and last",
+        );
+        Ok(())
+    }
+}
+
+/// Polls every configured feed. One adapter task for all of them, each
+/// feed on its own interval.
+#[derive(Clone)]
+pub struct RestPollerTask {
+    tx: channel::Sender<EventBatch>,
+    feeds: Vec<Arc<PollFeed>>,
+    client: reqwest::Client,
+    health: HealthRegistry,
+    stop: StopSignal,
+}
+
+impl RestPollerTask {
+    pub fn feeds(&self) -> usize {
+        self.feeds.len()
+    }
+
+    pub fn run(&self) -> impl std::future::Future<Output = anyhow::Result<()>> + Send + use<> {
+        let polls = self
+            .feeds
+            .iter()
+            .map(|feed| poll::run_poll_loop(self.tx.clone(), Arc::clone(feed), self.client.clone(), self.health.clone()))
+            .collect::<Vec<_>>();
+        async move {
+            tasks::instrument("adapter.rest_poller", futures::future::join_all(polls)).await;
+            anyhow::bail!("REST poller stopped")
+        }
+    }
+}
+
+#[async_trait]
+impl MarketDataAdapter for RestPollerTask {
+    fn venue(&self) -> Venue {
+        Venue::Rest
+    }
+
+    fn name(&self) -> &'static str {
+        "adapter.rest_poller"
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        self.stop.run_until_stopped(self.run()).await
+    }
+
+    async fn shutdown(&self) {
+        self.stop.stop();
+    }
+}
+
+/// Initialise the poller for `feeds`, each checked with
+/// [`PollFeed::validate`]. Market events are sent over `tx`; each feed's
+/// state is reported to `health` as `adapter.rest_poller.<name>`.
+pub fn init_rest_poller(
+    tx: channel::Sender<EventBatch>,
+    feeds: Vec<PollFeed>,
+    health: HealthRegistry,
+) -> anyhow::Result<RestPollerTask> {
+    anyhow::ensure!(!feeds.is_empty(), "no REST feeds to poll");
+    for (i, feed) in feeds.iter().enumerate() {
+        feed.validate().map_err(|e| anyhow::anyhow!("feed {}: {e}", feed.name))?;
+        anyhow::ensure!(feeds[..i].iter().all(|f| f.name != feed.name), "two feeds are named {}", feed.name);
+        health.register(&poll::health_component(feed));
+    }
+    let client = reqwest::Client::builder().timeout(poll::REQUEST_TIMEOUT).build()?;
+    Ok(RestPollerTask { tx, feeds: feeds.into_iter().map(Arc::new).collect(), client, health, stop: StopSignal::default() })
+}
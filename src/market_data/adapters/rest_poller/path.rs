@@ -0,0 +1,139 @@
+//! The small JSONPath subset feed mappings are written in.
+//!
+//! A path is a chain of steps: `.key` (or a bare `key` first), `['key']`
+//! for keys with dots or spaces, `[n]` for an array index, and `[*]` (or
+//! `.*`) for every element of an array or value of an object. A path
+//! starting with `$` is read from the whole response; any other is read
+//! from the quote it belongs to. So `$.markets[*]` lists the quotes and
+//! `bestBid` reads a field of each.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    All,
+}
+
+/// A parsed path. Deserializes from its text, so a bad path fails when the
+/// config loads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    /// Starts with `$`: read from the response, not the quote.
+    from_root: bool,
+    steps: Vec<Step>,
+    text: String,
+}
+
+impl JsonPath {
+    /// Every value the path reaches, in document order.
+    pub fn select<'a>(&self, root: &'a Value, item: &'a Value) -> Vec<&'a Value> {
+        let mut values = vec![if self.from_root { root } else { item }];
+        for step in &self.steps {
+            values = values
+                .into_iter()
+                .flat_map(|value| -> Vec<&'a Value> {
+                    match (step, value) {
+                        (Step::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+                        (Step::Index(i), Value::Array(items)) => items.get(*i).into_iter().collect(),
+                        (Step::All, Value::Array(items)) => items.iter().collect(),
+                        (Step::All, Value::Object(map)) => map.values().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+        values
+    }
+
+    /// The first value the path reaches, unless it's `null`.
+    pub fn first<'a>(&self, root: &'a Value, item: &'a Value) -> Option<&'a Value> {
+        self.select(root, item).into_iter().next().filter(|value| !value.is_null())
+    }
+
+    /// The first value as a number; numeric strings count.
+    pub fn number(&self, root: &Value, item: &Value) -> Option<f64> {
+        match self.first(root, item)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The first value as text: strings as they are, numbers and booleans
+    /// printed.
+    pub fn text(&self, root: &Value, item: &Value) -> Option<String> {
+        match self.first(root, item)? {
+            Value::String(s) => Some(s.clone()),
+            value @ (Value::Number(_) | Value::Bool(_)) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let text = s.trim();
+        let (from_root, mut rest) = match text.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let mut steps = Vec::new();
+        let key_end = |s: &str| s.find(['.', '[']).unwrap_or(s.len());
+        if !from_root && !rest.is_empty() && !rest.starts_with(['.', '[']) {
+            let end = key_end(rest);
+            steps.push(Step::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = key_end(after);
+                match &after[..end] {
+                    "" => anyhow::bail!("path '{text}' has an empty key"),
+                    "*" => steps.push(Step::All),
+                    key => steps.push(Step::Key(key.to_string())),
+                }
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(|| anyhow::anyhow!("path '{text}' has an unclosed '['"))?;
+                let inner = after[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                steps.push(match (inner, quoted) {
+                    (_, Some(key)) => Step::Key(key.to_string()),
+                    ("*", None) => Step::All,
+                    (index, None) => Step::Index(
+                        index.parse().map_err(|_| anyhow::anyhow!("path '{text}': '[{index}]' isn't an index, '*' or a quoted key"))?,
+                    ),
+                });
+                rest = &after[end + 1..];
+            } else {
+                anyhow::bail!("path '{text}': expected '.' or '[' at '{rest}'");
+            }
+        }
+        anyhow::ensure!(from_root || !steps.is_empty(), "path is empty");
+        Ok(Self { from_root, steps, text: text.to_string() })
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(de::Error::custom)
+    }
+}
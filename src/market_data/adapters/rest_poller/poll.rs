@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::Context;
+use serde_json::Value;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use crate::channel;
+use crate::health::HealthRegistry;
+use crate::market_data::pool;
+use crate::market_data::types::{EventBatch, MarketEventKind, Venue};
+use crate::metrics::prometheus::record_adapter_event;
+use crate::rate_limit::{self, EndpointClass};
+use crate::state::market::to_decimal;
+use super::PollFeed;
+
+pub(super) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Failed polls in a row after which a feed's prices are dropped.
+const STALE_AFTER_FAILURES: u32 = 3;
+
+pub(super) fn health_component(feed: &PollFeed) -> String {
+    format!("adapter.rest_poller.{}", feed.name)
+}
+
+/// One quote as read from a response, prices already scaled.
+#[derive(Debug, Clone, PartialEq)]
+struct Quote {
+    market_id: String,
+    bid: Option<f64>,
+    ask: Option<f64>,
+    last: Option<f64>,
+    bid_size: Option<f64>,
+    ask_size: Option<f64>,
+}
+
+/// Poll `feed` forever, sending the quotes that changed since the last poll
+/// over `tx`, one [`EventBatch`] per poll.
+///
+/// A quote with a bid, an ask and both sizes is a `TopOfBook`; anything
+/// less is a `PriceChange` carrying what the response had. A token missing
+/// from a response is sent an `Unsubscribed` and forgotten, until it shows
+/// up again. A failed poll marks the feed down in `health`; after
+/// [`STALE_AFTER_FAILURES`] in a row every token is sent a `Resync`, so the
+/// market cache stops serving prices the feed can no longer vouch for.
+pub(super) async fn run_poll_loop(
+    tx: channel::Sender<EventBatch>,
+    feed: Arc<PollFeed>,
+    client: reqwest::Client,
+    health: HealthRegistry,
+) {
+    let component = health_component(&feed);
+    let mut interval = tokio::time::interval(feed.interval());
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // Token id → the quote last sent for it.
+    let mut quotes: HashMap<String, Quote> = HashMap::new();
+    let mut failures: u32 = 0;
+    info!(feed = %feed.name, url = %feed.url, interval_ms = feed.interval_ms, "polling REST feed");
+
+    loop {
+        interval.tick().await;
+        let (received_at, body) = match fetch(&client, &feed).await {
+            Ok(response) => response,
+            Err(e) => {
+                failures += 1;
+                warn!(feed = %feed.name, error = %e, failures, "REST poll failed");
+                health.set_down(&component, format!("{e:#}"));
+                if failures == STALE_AFTER_FAILURES && !quotes.is_empty() {
+                    let stale = quotes.iter().map(|(token_id, quote)| (quote.market_id.as_str(), token_id.as_str()));
+                    if tx.send(pool::notices(Venue::Rest, MarketEventKind::Resync, stale)).await.is_err() {
+                        warn!("market event channel closed");
+                    }
+                    quotes.clear();
+                }
+                continue;
+            }
+        };
+        if failures > 0 {
+            info!(feed = %feed.name, failures, "REST poll recovered");
+            failures = 0;
+        }
+        health.set_up(&component);
+
+        let polled = read_quotes(&feed, &body);
+        let mut batch = pool::batch();
+        for (token_id, quote) in &polled {
+            if quotes.get(token_id) == Some(quote) {
+                continue;
+            }
+            let kind = match (quote.bid, quote.ask, quote.bid_size, quote.ask_size) {
+                (Some(bid_price), Some(ask_price), Some(bid_size), Some(ask_size)) => {
+                    MarketEventKind::TopOfBook { bid_price, bid_size, ask_price, ask_size }
+                }
+                _ => MarketEventKind::PriceChange,
+            };
+            record_adapter_event(Venue::Rest.name(), kind.name());
+            let mut event = pool::event(Venue::Rest, kind, &quote.market_id, token_id, received_at);
+            event.ts_receive_ms = Some(SystemTime::now());
+            event.parsed_at = Some(Instant::now());
+            event.best_bid = quote.bid.and_then(|p| to_decimal(p).ok());
+            event.best_ask = quote.ask.and_then(|p| to_decimal(p).ok());
+            event.last_trade_price = quote.last;
+            batch.push(event);
+        }
+        let gone: Vec<(String, String)> = quotes
+            .iter()
+            .filter(|(token_id, _)| !polled.contains_key(*token_id))
+            .map(|(token_id, quote)| (quote.market_id.clone(), token_id.clone()))
+            .collect();
+        for (market_id, token_id) in &gone {
+            debug!(feed = %feed.name, token = %token_id, "token left the REST feed");
+            record_adapter_event(Venue::Rest.name(), MarketEventKind::Unsubscribed.name());
+            let mut event = pool::event(Venue::Rest, MarketEventKind::Unsubscribed, market_id, token_id, received_at);
+            event.ts_receive_ms = Some(SystemTime::now());
+            event.parsed_at = Some(Instant::now());
+            batch.push(event);
+        }
+        quotes = polled;
+
+        if batch.is_empty() {
+            pool::recycle_batch(batch);
+        } else if tx.send(batch).await.is_err() {
+            warn!("market event channel closed");
+        }
+    }
+}
+
+/// GET the feed's URL, returning when the response arrived and its JSON.
+async fn fetch(client: &reqwest::Client, feed: &PollFeed) -> anyhow::Result<(Instant, Value)> {
+    rate_limit::acquire(Venue::Rest, EndpointClass::MarketData).await;
+    let mut request = client.get(&feed.url);
+    for (name, value) in &feed.headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("GET {}", feed.url))?;
+    let received_at = Instant::now();
+    let body = response.json().await.context("response isn't JSON")?;
+    Ok((received_at, body))
+}
+
+/// Every quote in `body` with a token id, by token id. Prices outside
+/// [0, 1] once scaled are left out, as a mapping mistake rather than a
+/// price.
+fn read_quotes(feed: &PollFeed, body: &Value) -> HashMap<String, Quote> {
+    let items = match &feed.items {
+        Some(items) => items.select(body, body),
+        None => vec![body],
+    };
+    let price = |path: &Option<super::JsonPath>, item: &Value| {
+        path.as_ref()
+            .and_then(|path| path.number(body, item))
+            .map(|p| p * feed.price_scale)
+            .filter(|p| (0.0..=1.0).contains(p))
+    };
+    let size = |path: &Option<super::JsonPath>, item: &Value| {
+        path.as_ref().and_then(|path| path.number(body, item)).filter(|s| *s >= 0.0 && s.is_finite())
+    };
+
+    let mut quotes = HashMap::new();
+    for item in items {
+        let Some(id) = feed.token_id.text(body, item) else {
+            debug!(feed = %feed.name, path = %feed.token_id, "quote without a token id skipped");
+            continue;
+        };
+        let market_id = match feed.market_id.as_ref().and_then(|path| path.text(body, item)) {
+            Some(market) => format!("{}:{market}", feed.name),
+            None => format!("{}:{id}", feed.name),
+        };
+        let quote = Quote {
+            market_id,
+            bid: price(&feed.bid, item),
+            ask: price(&feed.ask, item),
+            last: price(&feed.last, item),
+            bid_size: size(&feed.bid_size, item),
+            ask_size: size(&feed.ask_size, item),
+        };
+        quotes.insert(format!("{}:{id}", feed.name), quote);
+    }
+    quotes
+}
@@ -0,0 +1,99 @@
+//! The lifecycle every market-data adapter shares.
+//!
+//! Each venue's task implements [`MarketDataAdapter`], so the binary (or an
+//! embedding program) can put them behind one type and hand them to
+//! [`EngineBuilder::market_data`](crate::engine::EngineBuilder::market_data),
+//! which supervises each as [`name`](MarketDataAdapter::name) and shuts them
+//! down with the engine.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::runtime::Handle;
+use tokio::sync::watch;
+
+use crate::market_data::adapters::keepalive::KeepaliveConfig;
+use crate::market_data::subscriptions::SubscriptionControl;
+use crate::market_data::types::Venue;
+
+#[async_trait]
+pub trait MarketDataAdapter: Send + Sync + 'static {
+    /// The venue this adapter's events are tagged with.
+    fn venue(&self) -> Venue;
+
+    /// Supervised, and reported to health, as `task.<name>`.
+    fn name(&self) -> &'static str;
+
+    /// One run: connect, stream until the connection gives up, and return
+    /// the error. Called again for every restart. Returns `Ok(())` once
+    /// [`shutdown`](Self::shutdown) is called, straight away if it already
+    /// was.
+    async fn start(&self) -> anyhow::Result<()>;
+
+    /// Stop for good: the current run ends and later ones return at once.
+    async fn shutdown(&self);
+
+    /// The handle for changing what this adapter streams; `None` when its
+    /// markets are fixed by config.
+    fn subscription_control(&self) -> Option<SubscriptionControl> {
+        None
+    }
+
+    /// Start streaming `token_ids` of `market_id` on the open connection.
+    /// Returns how many weren't streamed already.
+    fn subscribe(&self, market_id: &str, token_ids: &[String]) -> anyhow::Result<usize> {
+        match self.subscription_control() {
+            Some(control) => Ok(control.subscribe(market_id, token_ids)),
+            None => anyhow::bail!("the {} adapter's markets can't be changed at runtime", self.venue().name()),
+        }
+    }
+
+    /// Stop streaming `token_ids`. Returns how many were streamed.
+    fn unsubscribe(&self, token_ids: &[String]) -> anyhow::Result<usize> {
+        match self.subscription_control() {
+            Some(control) => Ok(control.unsubscribe(token_ids)),
+            None => anyhow::bail!("the {} adapter's markets can't be changed at runtime", self.venue().name()),
+        }
+    }
+
+    /// Read the venue's socket on `reader` (a venue thread of its own) from
+    /// the next run on. Returns `false` for an adapter with no socket to read.
+    fn set_reader_runtime(&mut self, reader: Handle) -> bool {
+        let _ = reader;
+        false
+    }
+
+    /// Ping the venue's socket on `keepalive`'s schedule from the next run
+    /// on. A no-op for an adapter with no socket to ping.
+    fn set_keepalive(&mut self, keepalive: KeepaliveConfig) {
+        let _ = keepalive;
+    }
+}
+
+/// What an adapter's [`shutdown`](MarketDataAdapter::shutdown) sets and its
+/// runs watch. Cheap to clone; every clone stops the same adapter.
+#[derive(Clone)]
+pub struct StopSignal(Arc<watch::Sender<bool>>);
+
+impl Default for StopSignal {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl StopSignal {
+    pub fn stop(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Drive `run` until it ends or the signal is set, whichever is first.
+    /// Dropping `run` closes its connection and the reader tasks it owns.
+    pub async fn run_until_stopped(&self, run: impl Future<Output = anyhow::Result<()>>) -> anyhow::Result<()> {
+        let mut stopped = self.0.subscribe();
+        tokio::select! {
+            result = run => result,
+            _ = stopped.wait_for(|stop| *stop) => Ok(()),
+        }
+    }
+}
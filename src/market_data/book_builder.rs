@@ -0,0 +1,168 @@
+//! Per-token order books as an adapter rebuilds them from a venue's feed.
+//!
+//! A snapshot makes a token's book current and deltas keep it that way, but
+//! only for as long as none is missed. A missed delta shows up one of two
+//! ways, depending on the venue:
+//!
+//! - **a sequence gap**: the message's number isn't one more than the last on
+//!   its [`Sequence`] stream (Kalshi numbers each subscription's messages);
+//! - **a disagreeing touch**: the best prices the venue reports alongside a
+//!   delta aren't the local book's ([`BookBuilder::check_top`]; Polymarket
+//!   has no sequence numbers but reports the touch after every change).
+//!
+//! Either way the affected books are marked out of sync: further deltas for
+//! them are dropped until a fresh snapshot arrives, and the adapter emits a
+//! `BookStale` event for each so the market cache drops its copy rather than
+//! serving prices from a book that no longer matches the venue. Fetching that
+//! snapshot is up to the adapter, since only it knows how to ask its venue.
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::market_data::types::{BookLevel, Side};
+use crate::state::book::OrderBook;
+
+/// Where a message sits in a venue's numbering: `seq` counts up by one per
+/// message on `stream`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sequence {
+    pub stream: u64,
+    pub seq: u64,
+}
+
+/// What became of a delta.
+#[derive(Debug, PartialEq)]
+pub enum DeltaOutcome {
+    Applied,
+    /// The token's book is out of sync (or was never snapshotted); the delta
+    /// was dropped and the book still waits for a snapshot.
+    Unsynced,
+    /// The delta's sequence skipped a message. It wasn't applied, and these
+    /// tokens' books (the delta's own among them) are now out of sync.
+    Gap(Vec<String>),
+}
+
+struct TokenBook {
+    book: OrderBook,
+    synced: bool,
+    /// The stream the token's last update came in on.
+    stream: Option<u64>,
+}
+
+/// Order books of every token an adapter streams, with the sequence state
+/// that says whether each can still be trusted.
+#[derive(Default)]
+pub struct BookBuilder {
+    books: HashMap<String, TokenBook>,
+    /// Last `seq` seen per stream.
+    streams: HashMap<u64, u64>,
+}
+
+impl BookBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace `token`'s book with a snapshot, which brings it back in sync.
+    /// Returns the other tokens whose books the snapshot's sequence shows
+    /// to have missed a message, usually none.
+    pub fn snapshot(&mut self, token: &str, bids: &[BookLevel], asks: &[BookLevel], seq: Option<Sequence>) -> Vec<String> {
+        let stale = seq.map(|seq| self.advance(seq)).unwrap_or_default();
+        let entry = self.books.entry(token.to_string()).or_insert_with(|| TokenBook {
+            book: OrderBook::default(),
+            synced: false,
+            stream: None,
+        });
+        entry.book.reset(bids, asks);
+        entry.synced = true;
+        entry.stream = seq.map(|seq| seq.stream);
+        stale.into_iter().filter(|stale| stale != token).collect()
+    }
+
+    /// Set the total size at `price` on `side` of `token`'s book (`Buy` =
+    /// bids; zero removes the level), if the book is in sync and nothing
+    /// was missed before this delta.
+    pub fn delta(&mut self, token: &str, side: &Side, price: Decimal, size: Decimal, seq: Option<Sequence>) -> DeltaOutcome {
+        if let Some(seq) = seq {
+            let mut stale = self.advance(seq);
+            if !stale.is_empty() {
+                if self.invalidate(token) && !stale.iter().any(|stale| stale == token) {
+                    stale.push(token.to_string());
+                }
+                return DeltaOutcome::Gap(stale);
+            }
+        }
+        match self.books.get_mut(token) {
+            Some(entry) if entry.synced => {
+                entry.book.apply(side, price, size);
+                if let Some(seq) = seq {
+                    entry.stream = Some(seq.stream);
+                }
+                DeltaOutcome::Applied
+            }
+            _ => DeltaOutcome::Unsynced,
+        }
+    }
+
+    /// Compare `token`'s book with the best prices the venue reports for
+    /// it, where it reports them (`None` isn't checked). A mismatch means
+    /// a delta was missed: the book is marked out of sync and `false`
+    /// returned. Books already out of sync pass.
+    pub fn check_top(&mut self, token: &str, best_bid: Option<Decimal>, best_ask: Option<Decimal>) -> bool {
+        let Some(book) = self.book(token) else {
+            return true;
+        };
+        let bid_ok = best_bid.is_none_or(|bid| book.best_bid().map(|level| level.price) == Some(bid));
+        let ask_ok = best_ask.is_none_or(|ask| book.best_ask().map(|level| level.price) == Some(ask));
+        if bid_ok && ask_ok {
+            return true;
+        }
+        self.invalidate(token);
+        false
+    }
+
+    /// Mark `token`'s book out of sync until its next snapshot. Returns
+    /// whether it was in sync.
+    pub fn invalidate(&mut self, token: &str) -> bool {
+        self.books.get_mut(token).is_some_and(|entry| std::mem::replace(&mut entry.synced, false))
+    }
+
+    /// Forget `token`'s book, e.g. once it's unsubscribed.
+    pub fn remove(&mut self, token: &str) {
+        self.books.remove(token);
+    }
+
+    /// `token`'s book, while it's in sync.
+    pub fn book(&self, token: &str) -> Option<&OrderBook> {
+        self.books.get(token).filter(|entry| entry.synced).map(|entry| &entry.book)
+    }
+
+    pub fn is_synced(&self, token: &str) -> bool {
+        self.book(token).is_some()
+    }
+
+    /// Forget every book and sequence, e.g. for a new connection whose
+    /// snapshots and numbering start afresh.
+    pub fn clear(&mut self) {
+        self.books.clear();
+        self.streams.clear();
+    }
+
+    /// Record `seq` on its stream. On a gap, every in-sync book last updated
+    /// on that stream is marked out of sync and returned.
+    fn advance(&mut self, seq: Sequence) -> Vec<String> {
+        let expected = self.streams.insert(seq.stream, seq.seq).map(|last| last + 1);
+        if expected.is_none_or(|expected| expected == seq.seq) {
+            return Vec::new();
+        }
+        let mut stale = Vec::new();
+        for (token, entry) in &mut self.books {
+            if entry.synced && entry.stream == Some(seq.stream) {
+                entry.synced = false;
+                stale.push(token.clone());
+            }
+        }
+        stale
+    }
+}
@@ -0,0 +1,133 @@
+//! Fan-out of routed market events to observers.
+//!
+//! The router publishes each event once; every subscriber (archiver,
+//! ClickHouse sink, anomaly detector, ...) reads it from one shared
+//! `tokio::sync::broadcast` ring instead of getting its own copy. Publishing
+//! never waits: a subscriber that falls more than `capacity` events behind
+//! loses the oldest ones it hasn't read, so a slow observer can't hold up
+//! the hot path or the other observers.
+//!
+//! Per subscriber, `event_bus_lag` is how many events it has queued and
+//! `event_bus_lagged_total` how many it has missed.
+//!
+//! The copy subscribers share is recycled like the pooled events it is made
+//! from: the bus keeps every copy it sent and refills the oldest in place
+//! once the ring and every subscriber have let go of it. Fresh copies count
+//! in `event_pool_allocations_total`, refilled ones in `event_pool_reuses_total`.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use metrics::{counter, gauge};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+use crate::market_data::types::MarketEvent;
+
+/// Publishing side. Cheap to clone.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<Arc<MarketEvent>>,
+    /// Copies sent so far, oldest first, waiting to be refilled.
+    sent: Arc<Mutex<VecDeque<Arc<MarketEvent>>>>,
+    /// Slots in the ring. A copy older than this has left it, so holding on
+    /// to it only waits for a subscriber still reading it.
+    slots: usize,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx, sent: Arc::new(Mutex::new(VecDeque::new())), slots: capacity.next_power_of_two() }
+    }
+
+    /// A new subscriber, labelled `name` in the lag metrics. It sees events
+    /// published from now on.
+    pub fn subscribe(&self, name: &'static str) -> Subscriber {
+        Subscriber { name, rx: self.tx.subscribe() }
+    }
+
+    /// Offer `event` to every subscriber. Copies it only if anyone is
+    /// listening, into a copy nobody holds any more when there is one.
+    pub fn publish(&self, event: &MarketEvent) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+        let mut sent = self.sent.lock().expect("event bus poisoned");
+        let copy = match sent.pop_front() {
+            Some(mut oldest) if Arc::strong_count(&oldest) == 1 => {
+                refill(Arc::get_mut(&mut oldest).expect("no other holder"), event);
+                counter!("event_pool_reuses_total").increment(1);
+                oldest
+            }
+            oldest => {
+                // Still being read: keep waiting on it unless the ring has
+                // long since moved past it.
+                if let Some(oldest) = oldest.filter(|_| sent.len() < self.slots) {
+                    sent.push_front(oldest);
+                }
+                counter!("event_pool_allocations_total").increment(1);
+                Arc::new(event.clone())
+            }
+        };
+        sent.push_back(Arc::clone(&copy));
+        let _ = self.tx.send(copy);
+    }
+}
+
+/// Overwrite `copy` with `event`, keeping the capacity of its strings.
+fn refill(copy: &mut MarketEvent, event: &MarketEvent) {
+    let MarketEvent {
+        venue,
+        kind,
+        market_id,
+        token_id,
+        ts_exchange_ms,
+        ts_receive_ms,
+        received_at,
+        parsed_at,
+        volume24h,
+        last_trade_price,
+        liquidity,
+        best_bid,
+        best_ask,
+    } = event;
+    copy.venue.clone_from(venue);
+    copy.kind.clone_from(kind);
+    copy.market_id.clone_from(market_id);
+    copy.token_id.clone_from(token_id);
+    copy.ts_exchange_ms = *ts_exchange_ms;
+    copy.ts_receive_ms = *ts_receive_ms;
+    copy.received_at = *received_at;
+    copy.parsed_at = *parsed_at;
+    copy.volume24h = *volume24h;
+    copy.last_trade_price = *last_trade_price;
+    copy.liquidity = *liquidity;
+    copy.best_bid = *best_bid;
+    copy.best_ask = *best_ask;
+}
+
+pub struct Subscriber {
+    name: &'static str,
+    rx: broadcast::Receiver<Arc<MarketEvent>>,
+}
+
+impl Subscriber {
+    /// Next event, or `None` once the bus is gone. Events missed by falling
+    /// behind are counted and skipped.
+    pub async fn recv(&mut self) -> Option<Arc<MarketEvent>> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => {
+                    gauge!("event_bus_lag", "subscriber" => self.name).set(self.rx.len() as f64);
+                    return Some(event);
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    counter!("event_bus_lagged_total", "subscriber" => self.name).increment(missed);
+                    debug!(subscriber = self.name, missed, "event bus subscriber fell behind");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
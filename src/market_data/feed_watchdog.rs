@@ -0,0 +1,138 @@
+//! Per-token feed staleness.
+//!
+//! A socket can stay open while a venue stops sending for some of its
+//! tokens, and the market cache would go on serving their last prices as if
+//! they were current. The feed watchdog follows the routed events on the
+//! event bus, keeps the last time each token heard from its venue, and once
+//! a token has been quiet past its venue's deadline sends a `FeedStale`
+//! event for it through the pipeline. That drops the token's prices from the
+//! cache, so strategies stop quoting off them, and the risk manager refuses
+//! orders on the token until the venue sends for it again.
+//!
+//! Per venue, `feed_stale_tokens` is how many tokens are stale now and
+//! `feed_stale_total` how many have gone stale.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use metrics::{counter, gauge};
+use tracing::{info, warn};
+
+use crate::channel;
+use crate::market_data::bus::Subscriber;
+use crate::market_data::pool;
+use crate::market_data::types::{EventBatch, MarketEventKind, Venue};
+use crate::risk::RiskManager;
+use crate::state::ids::TokenId;
+
+#[derive(Debug, Clone)]
+pub struct FeedWatchdogConfig {
+    pub check_interval: Duration,
+    /// How long a token may go without an event; venues without a deadline
+    /// of their own use this one, and aren't watched when it's `None`.
+    pub stale_after: Option<Duration>,
+    /// Deadlines for particular venues, e.g. a longer one for a REST poller.
+    pub venue_stale_after: HashMap<Venue, Duration>,
+}
+
+impl Default for FeedWatchdogConfig {
+    fn default() -> Self {
+        Self { check_interval: Duration::from_secs(1), stale_after: None, venue_stale_after: HashMap::new() }
+    }
+}
+
+impl FeedWatchdogConfig {
+    fn deadline(&self, venue: &Venue) -> Option<Duration> {
+        self.venue_stale_after.get(venue).copied().or(self.stale_after)
+    }
+}
+
+/// One token's feed, as last heard.
+struct Feed {
+    market_id: String,
+    last_event: Instant,
+    stale: bool,
+}
+
+/// Watch `events` until the bus closes, sending a `FeedStale` over `tx` for
+/// each token that outlives its venue's deadline and marking it in `risk`.
+///
+/// Any event for a token counts as hearing from the feed except the notices
+/// that say it's broken (`BookStale`, `Resync`) and the watchdog's own. An
+/// `Unsubscribed` token is forgotten. A stale token that hears again is live
+/// again; its prices come back with whatever the venue sent.
+pub async fn run_feed_watchdog(
+    config: FeedWatchdogConfig,
+    mut events: Subscriber,
+    tx: channel::Sender<EventBatch>,
+    risk: RiskManager,
+) {
+    let mut feeds: HashMap<(Venue, TokenId), Feed> = HashMap::new();
+    // Stale tokens per venue ever watched, so a venue's gauge drops to zero
+    // rather than vanishing when its last token is unsubscribed.
+    let mut stale_counts: HashMap<Venue, usize> = HashMap::new();
+    let mut ticker = tokio::time::interval(config.check_interval);
+    info!(stale_after_secs = ?config.stale_after.map(|d| d.as_secs()), venues = config.venue_stale_after.len(), "feed watchdog started");
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else { return };
+                if config.deadline(&event.venue).is_none() {
+                    continue;
+                }
+                let key = (event.venue.clone(), TokenId::intern(&event.token_id));
+                match event.kind {
+                    MarketEventKind::FeedStale | MarketEventKind::BookStale | MarketEventKind::Resync => {}
+                    MarketEventKind::Unsubscribed => {
+                        if feeds.remove(&key).is_some_and(|feed| feed.stale) {
+                            risk.set_feed_stale(&key.0, key.1, false);
+                        }
+                    }
+                    _ => {
+                        let feed = feeds.entry(key.clone()).or_insert_with(|| Feed {
+                            market_id: event.market_id.clone(),
+                            last_event: event.received_at,
+                            stale: false,
+                        });
+                        feed.last_event = event.received_at;
+                        if feed.stale {
+                            feed.stale = false;
+                            risk.set_feed_stale(&key.0, key.1, false);
+                            info!(venue = key.0.name(), token = %event.token_id, "stale feed is live again");
+                        }
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                let mut went_stale: HashMap<Venue, Vec<(String, String)>> = HashMap::new();
+                stale_counts.values_mut().for_each(|count| *count = 0);
+                for ((venue, token_id), feed) in &mut feeds {
+                    let Some(deadline) = config.deadline(venue) else { continue };
+                    if !feed.stale && feed.last_event.elapsed() >= deadline {
+                        feed.stale = true;
+                        risk.set_feed_stale(venue, *token_id, true);
+                        went_stale.entry(venue.clone()).or_default().push((feed.market_id.clone(), token_id.to_string()));
+                    }
+                    *stale_counts.entry(venue.clone()).or_default() += usize::from(feed.stale);
+                }
+                for (venue, count) in &stale_counts {
+                    gauge!("feed_stale_tokens", "venue" => venue.name()).set(*count as f64);
+                }
+                for (venue, tokens) in went_stale {
+                    warn!(venue = venue.name(), tokens = tokens.len(), "market data feed stale; dropping its prices");
+                    counter!("feed_stale_total", "venue" => venue.name()).increment(tokens.len() as u64);
+                    let notices = pool::notices(
+                        venue,
+                        MarketEventKind::FeedStale,
+                        tokens.iter().map(|(market_id, token_id)| (market_id.as_str(), token_id.as_str())),
+                    );
+                    if tx.send(notices).await.is_err() {
+                        warn!("market event channel closed");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
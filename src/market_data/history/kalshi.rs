@@ -0,0 +1,86 @@
+use serde::Deserialize;
+use tracing::info;
+
+use super::{history_event, HistoryRange};
+use crate::market_data::types::{MarketEvent, MarketEventKind, Side, Venue};
+
+const KALSHI_API_URL: &str = "https://api.elections.kalshi.com/trade-api/v2";
+const TRADES_PAGE: usize = 1_000;
+
+#[derive(Deserialize)]
+struct TradesPage {
+    trades: Vec<KalshiTrade>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KalshiTrade {
+    /// YES price in cents.
+    yes_price: f64,
+    count: f64,
+    /// `"yes"` or `"no"`.
+    taker_side: String,
+    /// RFC 3339.
+    created_time: String,
+}
+
+/// Download every public trade for a Kalshi market ticker in `range`.
+///
+/// Kalshi markets are single-ticker binaries, so the ticker is used as both
+/// market id and token id, and prices are normalized from cents to [0, 1].
+/// A YES-taker trade is recorded as a buy, a NO-taker trade as a sell of YES.
+pub async fn download_market(
+    client: &reqwest::Client,
+    ticker: &str,
+    range: HistoryRange,
+) -> anyhow::Result<Vec<MarketEvent>> {
+    let mut events = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let mut query = vec![
+            ("ticker", ticker.to_string()),
+            ("min_ts", (range.start_ms / 1_000).to_string()),
+            ("max_ts", (range.end_ms / 1_000).to_string()),
+            ("limit", TRADES_PAGE.to_string()),
+        ];
+        if let Some(c) = &cursor {
+            query.push(("cursor", c.clone()));
+        }
+
+        let page: TradesPage = client
+            .get(format!("{KALSHI_API_URL}/markets/trades"))
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        for trade in &page.trades {
+            let ts_ms = chrono::DateTime::parse_from_rfc3339(&trade.created_time)?.timestamp_millis() as u64;
+            if !range.contains(ts_ms) {
+                continue;
+            }
+            let price = trade.yes_price / 100.0;
+            let side = if trade.taker_side == "no" { Side::Sell } else { Side::Buy };
+            events.push(history_event(
+                Venue::Kalshi,
+                MarketEventKind::Trade { price, size: trade.count, side },
+                ticker,
+                ticker,
+                ts_ms,
+                Some(price),
+            ));
+        }
+
+        cursor = page.cursor.filter(|c| !c.is_empty());
+        if cursor.is_none() || page.trades.is_empty() {
+            break;
+        }
+    }
+
+    info!(ticker, events = events.len(), "kalshi history downloaded");
+    Ok(events)
+}
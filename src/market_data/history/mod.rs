@@ -0,0 +1,68 @@
+//! Bulk download of historical prices and trades from venues' public
+//! history endpoints, normalized into `MarketEvent`s so they can be written
+//! with the same Parquet layout the live archiver uses.
+
+pub mod kalshi;
+pub mod polymarket;
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::market_data::types::{MarketEvent, MarketEventKind, Venue};
+
+/// Half-open time window `[start_ms, end_ms)`.
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl HistoryRange {
+    pub fn contains(&self, ts_ms: u64) -> bool {
+        (self.start_ms..self.end_ms).contains(&ts_ms)
+    }
+}
+
+/// Shared HTTP client settings for history downloads.
+pub fn http_client() -> anyhow::Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("prediction-engine-history")
+        .build()?)
+}
+
+/// Build a historical event. Exchange and receive timestamps are both set to
+/// the venue timestamp so the archive buckets it into the right hour.
+pub(crate) fn history_event(
+    venue: Venue,
+    kind: MarketEventKind,
+    market_id: &str,
+    token_id: &str,
+    ts_ms: u64,
+    last_trade_price: Option<f64>,
+) -> MarketEvent {
+    let ts = UNIX_EPOCH + Duration::from_millis(ts_ms);
+    MarketEvent {
+        venue,
+        kind,
+        market_id: market_id.to_string(),
+        token_id: token_id.to_string(),
+        ts_exchange_ms: Some(ts),
+        ts_receive_ms: Some(ts),
+        received_at: Instant::now(),
+        parsed_at: None,
+        volume24h: None,
+        last_trade_price,
+        liquidity: None,
+        best_bid: None,
+        best_ask: None,
+    }
+}
+
+/// Venue timestamp of an event in unix ms (0 if unset).
+pub fn event_ts_ms(event: &MarketEvent) -> u64 {
+    event
+        .ts_exchange_ms
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,162 @@
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use super::{history_event, HistoryRange};
+use crate::market_data::types::{MarketEvent, MarketEventKind, Side, Venue};
+
+const GAMMA_URL: &str = "https://gamma-api.polymarket.com";
+const CLOB_URL: &str = "https://clob.polymarket.com";
+const DATA_API_URL: &str = "https://data-api.polymarket.com";
+/// Page size for the data-api trades endpoint.
+const TRADES_PAGE: usize = 500;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GammaMarketRef {
+    condition_id: String,
+    /// JSON-encoded array of token ids, e.g. `"[\"123\", \"456\"]"`.
+    clob_token_ids: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PricesHistory {
+    history: Vec<PricePoint>,
+}
+
+#[derive(Deserialize)]
+struct PricePoint {
+    /// Unix seconds.
+    t: u64,
+    p: f64,
+}
+
+#[derive(Deserialize)]
+struct DataApiTrade {
+    asset: String,
+    side: String,
+    price: f64,
+    size: f64,
+    /// Unix seconds.
+    timestamp: u64,
+}
+
+/// Download price history (and optionally trades) for one Polymarket market,
+/// identified by its Gamma market id — the same id the live adapter uses.
+///
+/// Prices come from the CLOB `prices-history` endpoint at `fidelity_minutes`
+/// resolution and are emitted as `PriceChange` events carrying the price in
+/// `last_trade_price`. Trades come from the public data-api.
+pub async fn download_market(
+    client: &reqwest::Client,
+    market_id: &str,
+    range: HistoryRange,
+    fidelity_minutes: u32,
+    include_trades: bool,
+) -> anyhow::Result<Vec<MarketEvent>> {
+    let market: GammaMarketRef = client
+        .get(format!("{GAMMA_URL}/markets/{market_id}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let token_ids: Vec<String> = market
+        .clob_token_ids
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()?
+        .unwrap_or_default();
+    anyhow::ensure!(!token_ids.is_empty(), "market {market_id} has no CLOB tokens");
+
+    let mut events = Vec::new();
+    for token_id in &token_ids {
+        let history: PricesHistory = client
+            .get(format!("{CLOB_URL}/prices-history"))
+            .query(&[
+                ("market", token_id.as_str()),
+                ("startTs", &(range.start_ms / 1_000).to_string()),
+                ("endTs", &(range.end_ms / 1_000).to_string()),
+                ("fidelity", &fidelity_minutes.to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        debug!(market_id, token_id = %token_id, points = history.history.len(), "price history fetched");
+
+        events.extend(history.history.into_iter().map(|point| {
+            history_event(
+                Venue::Polymarket,
+                MarketEventKind::PriceChange,
+                market_id,
+                token_id,
+                point.t * 1_000,
+                Some(point.p),
+            )
+        }));
+    }
+
+    if include_trades {
+        events.extend(download_trades(client, market_id, &market.condition_id, range).await?);
+    }
+
+    info!(market_id, tokens = token_ids.len(), events = events.len(), "polymarket history downloaded");
+    Ok(events)
+}
+
+/// Page backwards through the data-api trade feed (newest first) until
+/// trades fall before the start of the range.
+async fn download_trades(
+    client: &reqwest::Client,
+    market_id: &str,
+    condition_id: &str,
+    range: HistoryRange,
+) -> anyhow::Result<Vec<MarketEvent>> {
+    let mut events = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let page: Vec<DataApiTrade> = client
+            .get(format!("{DATA_API_URL}/trades"))
+            .query(&[
+                ("market", condition_id),
+                ("limit", &TRADES_PAGE.to_string()),
+                ("offset", &offset.to_string()),
+                ("takerOnly", "true"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let page_len = page.len();
+        let mut reached_start = false;
+
+        for trade in page {
+            let ts_ms = trade.timestamp * 1_000;
+            if ts_ms < range.start_ms {
+                reached_start = true;
+                continue;
+            }
+            if !range.contains(ts_ms) {
+                continue;
+            }
+            let side = if trade.side.eq_ignore_ascii_case("sell") { Side::Sell } else { Side::Buy };
+            events.push(history_event(
+                Venue::Polymarket,
+                MarketEventKind::Trade { price: trade.price, size: trade.size, side },
+                market_id,
+                &trade.asset,
+                ts_ms,
+                Some(trade.price),
+            ));
+        }
+
+        if reached_start || page_len < TRADES_PAGE {
+            break;
+        }
+        offset += page_len;
+    }
+    Ok(events)
+}
@@ -1,42 +1,96 @@
 #![allow(warnings)]
 
 use std::time::Instant;
-use tokio::sync::mpsc;
 use tracing::{debug, warn};
-use crate::market_data::types::MarketEvent;
-use crate::state::market::MarketState;
+use crate::channel;
+use crate::market_data::pool;
+use crate::market_data::types::{EventBatch, MarketEvent, MarketEventKind};
+use crate::metrics::channels::record_channel_drop;
+use crate::metrics::stages::StageTimes;
+use crate::state::market::{BookUpdate, MarketUpdate};
+use crate::state::ids::TokenId;
 use crate::state::market_cache::{MarketCache, MarketKey, insert};
 
-/// Notification payload sent to the strategy engine.
-/// Carries the WS receive timestamp for end-to-end latency measurement.
-pub type Notification = (MarketKey, Instant);
+/// Market worker → strategy engine: "this key changed", with the pipeline
+/// stage timestamps of its latest update for latency measurement. Coalesced
+/// per key, so a busy strategy engine evaluates each market once against its
+/// newest state rather than once per update.
+pub type NotifySender = channel::CoalescingSender<MarketKey, StageTimes>;
+pub type NotifyReceiver = channel::CoalescingReceiver<MarketKey, StageTimes>;
 
+/// Merge one event into the cache and return the key it updated.
+/// Shared by the live worker and the backtester so both see identical state.
+pub fn apply_event(handle: &MarketCache, event: &MarketEvent) -> MarketKey {
+    let (key, state) = cache_entry(event);
+    insert(handle, key.clone(), state);
+    key
+}
+
+/// Merge every event of a batch into the cache in one pass, pushing the key
+/// each one updated onto `keys` in order.
+pub fn apply_batch(handle: &MarketCache, batch: &[MarketEvent], keys: &mut Vec<MarketKey>) {
+    handle.update_batch(batch.iter().map(|event| {
+        let (key, state) = cache_entry(event);
+        keys.push(key.clone());
+        (key, state)
+    }));
+}
+
+fn cache_entry(event: &MarketEvent) -> (MarketKey, MarketUpdate<'_>) {
+    let key = MarketKey(event.venue.clone(), TokenId::intern(&event.token_id));
+
+    let book = match &event.kind {
+        MarketEventKind::BookSnapshot { bids, asks } => Some(BookUpdate::Snapshot { bids, asks }),
+        MarketEventKind::BookDelta { side, price, size } => Some(BookUpdate::Delta { side, price: *price, size: *size }),
+        MarketEventKind::BookStale | MarketEventKind::Resync | MarketEventKind::Unsubscribed | MarketEventKind::FeedStale => {
+            Some(BookUpdate::Stale)
+        }
+        _ => None,
+    };
+    let update = MarketUpdate {
+        best_bid: event.best_bid,
+        best_ask: event.best_ask,
+        volume24h: event.volume24h,
+        book,
+    };
+
+    debug!(
+        token_id = %event.token_id,
+        ?update,
+        "updating cache"
+    );
+
+    (key, update)
+}
+
+/// Apply each batch to the cache, then notify the strategy engine of every
+/// key it touched at once, so no evaluation sees part of a batch.
 pub async fn run_market_worker(
-    mut rx: mpsc::Receiver<MarketEvent>,
+    mut rx: channel::Receiver<EventBatch>,
     handle: MarketCache,
-    notify_tx: mpsc::Sender<Notification>,
+    notify_tx: NotifySender,
 ) -> anyhow::Result<()> {
-    while let Some(event) = rx.recv().await {
-        let key = MarketKey(event.venue.clone(), event.token_id.clone());
-        let received_at = event.received_at;
-
-        let state = MarketState {
-            best_bid: event.best_bid,
-            best_ask: event.best_ask,
-            volume24h: event.volume24h,
-        };
-
-        debug!(
-            token_id = %event.token_id,
-            ?state,
-            "updating cache"
-        );
-
-        insert(&handle, key.clone(), state);
-
-        // Notify strategy engine — non-blocking so the data path
-        // never stalls on a slow strategy consumer.
-        let _ = notify_tx.try_send((key, received_at));
+    // Reused across batches so the steady state allocates nothing.
+    let mut keys: Vec<MarketKey> = Vec::new();
+    let mut updated: Vec<(MarketKey, StageTimes)> = Vec::new();
+
+    while let Some(batch) = rx.recv().await {
+        apply_batch(&handle, &batch, &mut keys);
+        let cached = Instant::now();
+        for (key, event) in keys.drain(..).zip(&batch) {
+            let mut stages = StageTimes::new(event.received_at);
+            stages.parsed = event.parsed_at;
+            stages.cached = Some(cached);
+            stages.record_market_data(event.venue.name());
+            updated.push((key, stages));
+        }
+        pool::recycle_batch(batch);
+
+        // Notify strategy engine — never waits, so the data path doesn't
+        // stall on a slow strategy consumer.
+        if notify_tx.send_batch(updated.drain(..)).is_err() {
+            record_channel_drop("notifications");
+        }
     }
 
     Ok(())
@@ -1,4 +1,11 @@
 pub mod adapters;
+pub mod book_builder;
+pub mod bus;
+pub mod feed_watchdog;
+pub mod history;
 pub mod market_worker;
+pub mod pool;
 pub mod router;
-pub mod types;
\ No newline at end of file
+pub mod subscriptions;
+pub mod types;
+pub mod universe;
\ No newline at end of file
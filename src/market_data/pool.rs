@@ -0,0 +1,125 @@
+//! Recycled `MarketEvent`s for the adapter → router → worker path.
+//!
+//! Every event owns its market and token id strings, so building one per WS
+//! message costs two heap allocations. Instead the market worker hands each
+//! event back once it is in the cache, and adapters refill a recycled event
+//! in place: `clear` + `push_str` keeps the strings' capacity, so in steady
+//! state the path allocates nothing per message. Events shed on overflow are
+//! just freed; the pool tops itself up with fresh allocations as needed.
+//!
+//! The batches that carry one venue message's events (see [`EventBatch`])
+//! are pooled the same way, emptied of their events on the way back.
+//!
+//! `event_pool_allocations_total` counts events built from scratch and
+//! `event_pool_reuses_total` recycled ones, including the copies the event
+//! bus makes for observers (see [`crate::market_data::bus`]). Once the pool
+//! has warmed up the first should stay flat.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Instant, SystemTime};
+
+use metrics::counter;
+
+use crate::market_data::types::{EventBatch, MarketEvent, MarketEventKind, Venue};
+use crate::metrics::prometheus::record_adapter_event;
+
+/// Most events kept for reuse. Enough to cover every market-data channel
+/// being full at once; beyond that a recycled event is freed.
+const MAX_POOLED: usize = 16_384;
+
+/// Most empty batches kept for reuse.
+const MAX_POOLED_BATCHES: usize = 4_096;
+
+static EVENTS: LazyLock<Mutex<Vec<MarketEvent>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static BATCHES: LazyLock<Mutex<Vec<EventBatch>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// An event for `token_id` in `market_id`, recycled if one is free. Every
+/// other field is reset to `None`; the caller fills in what it has.
+pub fn event(venue: Venue, kind: MarketEventKind, market_id: &str, token_id: &str, received_at: Instant) -> MarketEvent {
+    let recycled = EVENTS.lock().expect("event pool poisoned").pop();
+    let Some(mut event) = recycled else {
+        counter!("event_pool_allocations_total").increment(1);
+        return MarketEvent {
+            venue,
+            kind,
+            market_id: market_id.to_owned(),
+            token_id: token_id.to_owned(),
+            ts_exchange_ms: None,
+            ts_receive_ms: None,
+            received_at,
+            parsed_at: None,
+            volume24h: None,
+            last_trade_price: None,
+            liquidity: None,
+            best_bid: None,
+            best_ask: None,
+        };
+    };
+    counter!("event_pool_reuses_total").increment(1);
+
+    event.venue = venue;
+    event.kind = kind;
+    event.market_id.clear();
+    event.market_id.push_str(market_id);
+    event.token_id.clear();
+    event.token_id.push_str(token_id);
+    event.ts_exchange_ms = None;
+    event.ts_receive_ms = None;
+    event.received_at = received_at;
+    event.parsed_at = None;
+    event.volume24h = None;
+    event.last_trade_price = None;
+    event.liquidity = None;
+    event.best_bid = None;
+    event.best_ask = None;
+    event
+}
+
+/// Hand a consumed event back for reuse.
+pub fn recycle(event: MarketEvent) {
+    let mut free = EVENTS.lock().expect("event pool poisoned");
+    if free.len() < MAX_POOLED {
+        free.push(event);
+    }
+}
+
+/// An empty batch, recycled if one is free.
+pub fn batch() -> EventBatch {
+    BATCHES.lock().expect("batch pool poisoned").pop().unwrap_or_default()
+}
+
+/// A batch holding just `event`.
+pub fn single(event: MarketEvent) -> EventBatch {
+    let mut batch = batch();
+    batch.push(event);
+    batch
+}
+
+/// A `kind` event carrying nothing else (a `Resync` or `Unsubscribed`
+/// notice) for each `(market_id, token_id)` on `venue`, as one batch.
+pub fn notices<'a>(venue: Venue, kind: MarketEventKind, tokens: impl IntoIterator<Item = (&'a str, &'a str)>) -> EventBatch {
+    let mut notices = batch();
+    for (market_id, token_id) in tokens {
+        record_adapter_event(venue.name(), kind.name());
+        let mut event = self::event(venue.clone(), kind.clone(), market_id, token_id, Instant::now());
+        event.ts_receive_ms = Some(SystemTime::now());
+        event.parsed_at = Some(Instant::now());
+        notices.push(event);
+    }
+    notices
+}
+
+/// Hand a consumed batch back for reuse, along with every event in it.
+pub fn recycle_batch(mut batch: EventBatch) {
+    {
+        let mut free = EVENTS.lock().expect("event pool poisoned");
+        let room = MAX_POOLED.saturating_sub(free.len());
+        let keep = batch.len().min(room);
+        free.extend(batch.drain(..keep));
+    }
+    batch.clear();
+    let mut free = BATCHES.lock().expect("batch pool poisoned");
+    if free.len() < MAX_POOLED_BATCHES {
+        free.push(batch);
+    }
+}
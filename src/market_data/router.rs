@@ -1,32 +1,50 @@
 #![allow(warnings)]
 
-use tokio::sync::mpsc;
 use tracing::{info, warn};
 use std::collections::HashMap;
-use crate::market_data::types::{MarketEvent, Venue};
-use crate::market_data::market_worker::{run_market_worker, Notification};
+use crate::channel::{self, ChannelConfig};
+use crate::market_data::bus::EventBus;
+use crate::market_data::pool;
+use crate::market_data::types::{EventBatch, Venue};
+use crate::market_data::market_worker::{run_market_worker, NotifySender};
+use crate::metrics::channels::record_channel_drop;
+use crate::metrics::tasks;
 use crate::state::market_cache::MarketCache;
 
-/// Per-venue lane buffer size.
-const LANE_BUFFER: usize = 1_024;
-
+/// Routes each batch to its venue's market worker.
+///
+/// Every event in a batch is also published once on `bus` for observers (archiver,
+/// ClickHouse sink, ...); see [`EventBus`] for how slow subscribers are
+/// handled. Each venue lane is a `channel::bounded` built from `lane`.
 pub async fn run_router(
-    mut rx: mpsc::Receiver<MarketEvent>,
+    rx: &mut channel::Receiver<EventBatch>,
     handle: MarketCache,
-    notify_tx: mpsc::Sender<Notification>,
+    notify_tx: NotifySender,
+    bus: EventBus,
+    lane: ChannelConfig,
 ) -> anyhow::Result<()> {
-    let mut lanes: HashMap<Venue, mpsc::Sender<MarketEvent>> = HashMap::new();
+    let mut lanes: HashMap<Venue, channel::Sender<EventBatch>> = HashMap::new();
+
+    while let Some(batch) = rx.recv().await {
+        // A batch is one venue message, so its first event names the venue.
+        let Some(venue) = batch.first().map(|event| event.venue.clone()) else {
+            pool::recycle_batch(batch);
+            continue;
+        };
+        for event in &batch {
+            bus.publish(event);
+        }
 
-    while let Some(event) = rx.recv().await {
-        if !lanes.contains_key(&event.venue) {
-            let (lane_tx, lane_rx) = mpsc::channel(LANE_BUFFER);
-            info!(venue = ?event.venue, "spawning market worker");
-            tokio::spawn(run_market_worker(lane_rx, handle.clone(), notify_tx.clone()));
-            lanes.insert(event.venue.clone(), lane_tx);
+        if !lanes.contains_key(&venue) {
+            let (lane_tx, lane_rx) = channel::bounded("venue_lane", lane);
+            info!(?venue, "spawning market worker");
+            tokio::spawn(tasks::instrument("market_worker", run_market_worker(lane_rx, handle.clone(), notify_tx.clone())));
+            lanes.insert(venue.clone(), lane_tx);
         }
 
-        if let Some(lane) = lanes.get(&event.venue) {
-            if lane.send(event).await.is_err() {
+        if let Some(lane) = lanes.get(&venue) {
+            if lane.send(batch).await.is_err() {
+                record_channel_drop("venue_lane");
                 warn!("venue lane closed unexpectedly");
             }
         }
@@ -0,0 +1,176 @@
+//! Changing what an adapter streams while it runs.
+//!
+//! Each adapter owns a [`Subscriptions`]: the tokens it should be streaming
+//! and the receiving end of a command channel. Whoever changes them (the
+//! admin API, an embedding program's strategy layer) holds a cloneable
+//! [`SubscriptionControl`]. A command updates the token set straight away
+//! and is then sent to the adapter, which subscribes or unsubscribes on its
+//! open connection without reconnecting. A reconnect, or a restart of the
+//! adapter, subscribes to whatever the set holds at that point, so changes
+//! outlive the connection they were made on.
+//!
+//! Tokens added at runtime stream into the market cache and the event bus;
+//! strategies look markets up in the map the engine was built with, so they
+//! only trade markets that were in it.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::market_data::types::Venue;
+
+/// A change to an adapter's subscriptions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubscriptionCommand {
+    /// Start streaming `token_ids`, the outcome tokens of `market_id`. On
+    /// Kalshi a ticker is both.
+    Subscribe { market_id: String, token_ids: Vec<String> },
+    /// Stop streaming `token_ids`.
+    Unsubscribe { token_ids: Vec<String> },
+}
+
+/// One subscribed token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Subscribed {
+    pub token_id: String,
+    pub market_id: String,
+}
+
+/// Tokens to subscribe and unsubscribe on an open connection.
+#[derive(Debug, Default)]
+pub struct SubscriptionChange {
+    pub added: Vec<Subscribed>,
+    pub removed: Vec<Subscribed>,
+}
+
+impl SubscriptionChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Tokens an adapter should be streaming, in the order they were added.
+type TokenSet = Arc<Mutex<Vec<Subscribed>>>;
+
+/// Changes one adapter's subscriptions. Cheap to clone.
+#[derive(Clone)]
+pub struct SubscriptionControl {
+    venue: Venue,
+    tokens: TokenSet,
+    tx: mpsc::UnboundedSender<SubscriptionCommand>,
+}
+
+impl SubscriptionControl {
+    pub fn venue(&self) -> &Venue {
+        &self.venue
+    }
+
+    /// Add `token_ids` of `market_id`. Returns how many weren't subscribed
+    /// already; the command is only sent if there are any.
+    pub fn subscribe(&self, market_id: &str, token_ids: &[String]) -> usize {
+        let added: Vec<String> = {
+            let mut tokens = self.tokens.lock().expect("subscriptions poisoned");
+            let mut added = Vec::new();
+            for token_id in token_ids {
+                if !tokens.iter().any(|s| s.token_id == *token_id) && !added.contains(token_id) {
+                    added.push(token_id.clone());
+                }
+            }
+            tokens.extend(added.iter().map(|token_id| Subscribed { token_id: token_id.clone(), market_id: market_id.to_string() }));
+            added
+        };
+        let count = added.len();
+        if count > 0 {
+            // Unsent only when the adapter is gone for good; the set is updated either way.
+            let _ = self.tx.send(SubscriptionCommand::Subscribe { market_id: market_id.to_string(), token_ids: added });
+        }
+        count
+    }
+
+    /// Drop `token_ids`. Returns how many were subscribed; the command is
+    /// only sent if there are any.
+    pub fn unsubscribe(&self, token_ids: &[String]) -> usize {
+        let removed: Vec<String> = {
+            let mut tokens = self.tokens.lock().expect("subscriptions poisoned");
+            let before: Vec<String> = tokens.iter().map(|s| s.token_id.clone()).collect();
+            tokens.retain(|s| !token_ids.contains(&s.token_id));
+            before.into_iter().filter(|token_id| token_ids.contains(token_id)).collect()
+        };
+        let count = removed.len();
+        if count > 0 {
+            let _ = self.tx.send(SubscriptionCommand::Unsubscribe { token_ids: removed });
+        }
+        count
+    }
+
+    /// The tokens the adapter should be streaming.
+    pub fn tokens(&self) -> Vec<Subscribed> {
+        self.tokens.lock().expect("subscriptions poisoned").clone()
+    }
+}
+
+/// An adapter's end: the token set plus the command receiver, both shared
+/// by every run of the adapter. Cheap to clone.
+#[derive(Clone)]
+pub struct Subscriptions {
+    control: SubscriptionControl,
+    /// Locked by one run at a time, as the engine does for its channels.
+    commands: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<SubscriptionCommand>>>,
+}
+
+impl Subscriptions {
+    /// Start from `tokens`, as `(token_id, market_id)`.
+    pub fn new(venue: Venue, tokens: impl IntoIterator<Item = (String, String)>) -> Self {
+        let tokens = tokens.into_iter().map(|(token_id, market_id)| Subscribed { token_id, market_id }).collect();
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            control: SubscriptionControl { venue, tokens: Arc::new(Mutex::new(tokens)), tx },
+            commands: Arc::new(tokio::sync::Mutex::new(rx)),
+        }
+    }
+
+    /// A handle for changing these subscriptions.
+    pub fn control(&self) -> SubscriptionControl {
+        self.control.clone()
+    }
+
+    /// The tokens to subscribe to on a new connection.
+    pub fn tokens(&self) -> Vec<Subscribed> {
+        self.control.tokens()
+    }
+
+    /// Bring `live` (token id → market id, what a connection is subscribed
+    /// to) in line with the token set, returning what changed.
+    pub fn sync(&self, live: &mut HashMap<String, String>) -> SubscriptionChange {
+        self.sync_where(live, |wanted| wanted)
+    }
+
+    /// [`sync`](Self::sync), for a connection that carries only part of the
+    /// set: `carried` picks that part out of the whole of it.
+    pub fn sync_where(
+        &self,
+        live: &mut HashMap<String, String>,
+        carried: impl FnOnce(Vec<Subscribed>) -> Vec<Subscribed>,
+    ) -> SubscriptionChange {
+        let wanted = carried(self.tokens());
+        let wanted_ids: HashSet<&str> = wanted.iter().map(|s| s.token_id.as_str()).collect();
+        let removed: Vec<Subscribed> = live
+            .iter()
+            .filter(|(token_id, _)| !wanted_ids.contains(token_id.as_str()))
+            .map(|(token_id, market_id)| Subscribed { token_id: token_id.clone(), market_id: market_id.clone() })
+            .collect();
+        for s in &removed {
+            live.remove(&s.token_id);
+        }
+        let added: Vec<Subscribed> = wanted.into_iter().filter(|s| !live.contains_key(&s.token_id)).collect();
+        live.extend(added.iter().map(|s| (s.token_id.clone(), s.market_id.clone())));
+        SubscriptionChange { added, removed }
+    }
+
+    /// The command receiver, held for as long as the guard lives.
+    pub async fn commands(&self) -> tokio::sync::OwnedMutexGuard<mpsc::UnboundedReceiver<SubscriptionCommand>> {
+        Arc::clone(&self.commands).lock_owned().await
+    }
+}
@@ -1,27 +1,107 @@
 #![allow(dead_code)]
 
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::time::{Instant, SystemTime};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Venue {
     Polymarket,
-    Kalshi
+    Kalshi,
+    Betfair,
+    /// Quotes polled from a REST endpoint by `adapters::rest_poller`.
+    Rest,
+    /// Synthetic quotes from on-chain AMM pools, by `adapters::amm`.
+    Amm,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+impl Venue {
+    /// Same as the `Debug` form, without formatting into a new `String`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Venue::Polymarket => "Polymarket",
+            Venue::Kalshi => "Kalshi",
+            Venue::Betfair => "Betfair",
+            Venue::Rest => "Rest",
+            Venue::Amm => "Amm",
+        }
+    }
+}
+
+impl FromStr for Venue {
+    type Err = anyhow::Error;
+
+    /// Case-insensitive venue name, as written in config.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "polymarket" => Ok(Venue::Polymarket),
+            "kalshi" => Ok(Venue::Kalshi),
+            "betfair" => Ok(Venue::Betfair),
+            "rest" => Ok(Venue::Rest),
+            "amm" => Ok(Venue::Amm),
+            _ => anyhow::bail!("'{s}' is not a venue (expected polymarket, kalshi, betfair, rest or amm)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Side {
     Buy,
     Sell
 }
 
+/// Total size resting at one price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MarketEventKind {
     Trade{price: f64, size: f64, side: Side},
     TopOfBook{bid_price: f64, bid_size: f64, ask_price: f64, ask_size: f64},
+    /// Every level of the book, best first on each side. Replaces the token's book.
+    BookSnapshot{bids: Vec<BookLevel>, asks: Vec<BookLevel>},
+    /// The new total size at one price; zero removes the level. `Buy` is the bid side.
+    BookDelta{side: Side, price: Decimal, size: Decimal},
+    /// The adapter's copy of the book no longer matches the venue's (a delta
+    /// was missed) and a fresh snapshot is on its way. Drops the token's
+    /// book; the event's best prices, if any, are the venue's own.
+    BookStale,
+    /// The venue's feed dropped and is being resubscribed: whatever it sent
+    /// meanwhile was missed. Drops the token's book and best prices until
+    /// the next snapshot.
+    Resync,
+    /// The adapter stopped streaming the token. Drops its book and best prices.
+    Unsubscribed,
+    /// Nothing arrived for the token within the feed watchdog's deadline.
+    /// Drops its book and best prices until the venue sends again.
+    FeedStale,
     Heartbeat,
     PriceChange
 }
 
+impl MarketEventKind {
+    /// Stable snake_case name, for metrics labels and archived rows.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarketEventKind::Trade { .. } => "trade",
+            MarketEventKind::TopOfBook { .. } => "top_of_book",
+            MarketEventKind::BookSnapshot { .. } => "book_snapshot",
+            MarketEventKind::BookDelta { .. } => "book_delta",
+            MarketEventKind::BookStale => "book_stale",
+            MarketEventKind::Resync => "resync",
+            MarketEventKind::Unsubscribed => "unsubscribed",
+            MarketEventKind::FeedStale => "feed_stale",
+            MarketEventKind::Heartbeat => "heartbeat",
+            MarketEventKind::PriceChange => "price_change",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MarketEvent {
     pub venue: Venue,
@@ -32,9 +112,39 @@ pub struct MarketEvent {
     pub ts_receive_ms: Option<SystemTime>,
     /// Monotonic receive timestamp for latency measurement.
     pub received_at: Instant,
+    /// When the adapter finished converting the message into this event.
+    pub parsed_at: Option<Instant>,
     pub volume24h: Option<f64>,
     pub last_trade_price: Option<f64>,
     pub liquidity: Option<f64>,
-    pub best_bid: Option<f64>,
-    pub best_ask: Option<f64>,
-}
\ No newline at end of file
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+}
+impl MarketEvent {
+    /// Sizes resting at the best bid and ask, as far as this event shows
+    /// them: both sides for a top of book or snapshot, and the changed side
+    /// of a delta at the best price. `None` where the event doesn't say.
+    pub fn touch_sizes(&self) -> (Option<f64>, Option<f64>) {
+        match &self.kind {
+            MarketEventKind::TopOfBook { bid_size, ask_size, .. } => (Some(*bid_size), Some(*ask_size)),
+            MarketEventKind::BookSnapshot { bids, asks } => {
+                let best = |levels: &[BookLevel]| Some(levels.first().map_or(0.0, |l| l.size.to_f64().unwrap_or(0.0)));
+                (best(bids), best(asks))
+            }
+            MarketEventKind::BookDelta { side, price, size } => {
+                let at_touch = |best: Option<Decimal>| if best == Some(*price) { size.to_f64() } else { None };
+                match side {
+                    Side::Buy => (at_touch(self.best_bid), None),
+                    Side::Sell => (None, at_touch(self.best_ask)),
+                }
+            }
+            _ => (None, None),
+        }
+    }
+}
+
+/// The events decoded from one venue message, all from the same venue. The
+/// market worker writes a batch to the cache as one unit and only then tells
+/// the strategy engine, so a message that moves both tokens of a market is
+/// never seen half-applied.
+pub type EventBatch = Vec<MarketEvent>;
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tracing::info;
+
+use crate::market_data::adapters::polymarket::{MarketInfo, MarketMap, TokenToMarket};
+use crate::market_data::types::Venue;
+use crate::state::portfolio::unix_ms;
+
+/// A market on another venue that resolves on the same event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VenueMarketRef {
+    pub venue: Venue,
+    pub market_id: String,
+}
+
+/// Polymarket market_id → equivalent markets on other venues.
+pub type EquivalenceMap = HashMap<String, Vec<VenueMarketRef>>;
+
+/// The full trading universe of a session: discovered markets, the
+/// token reverse lookup, and cross-venue equivalences. Written as JSON with
+/// sorted keys so two exports of the same universe diff cleanly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Universe {
+    pub exported_at_ms: u64,
+    pub markets: BTreeMap<String, MarketInfo>,
+    pub token_to_market: BTreeMap<String, String>,
+    #[serde(default)]
+    pub equivalences: BTreeMap<String, Vec<VenueMarketRef>>,
+}
+
+impl Universe {
+    pub fn new(market_map: &MarketMap, token_to_market: &TokenToMarket, equivalences: &EquivalenceMap) -> Self {
+        Self {
+            exported_at_ms: unix_ms(),
+            markets: market_map.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            token_to_market: token_to_market.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            equivalences: equivalences.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        info!(
+            path = %path.display(),
+            markets = self.markets.len(),
+            equivalences = self.equivalences.len(),
+            "market universe exported"
+        );
+        Ok(())
+    }
+
+    /// Load and sanity-check a universe file: every market's tokens must map
+    /// back to it, otherwise the file was hand-edited inconsistently.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let universe: Universe = serde_json::from_slice(&std::fs::read(path)?)?;
+        for (market_id, info) in &universe.markets {
+            for token in [info.yes_token_id, info.no_token_id] {
+                anyhow::ensure!(
+                    universe.token_to_market.get(token.as_str()) == Some(market_id),
+                    "universe file {}: token {token} does not map back to market {market_id}",
+                    path.display()
+                );
+            }
+        }
+        info!(path = %path.display(), markets = universe.markets.len(), "market universe imported");
+        Ok(universe)
+    }
+
+    pub fn market_map(&self) -> MarketMap {
+        self.markets.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn equivalence_map(&self) -> EquivalenceMap {
+        self.equivalences.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
@@ -0,0 +1,59 @@
+//! Queue depth and backpressure for the engine's channels.
+//!
+//! [`ChannelMonitor`] samples each registered channel's depth and capacity
+//! through a weak sender, so monitoring never keeps a channel open. Send
+//! sites count their own failures with [`record_channel_drop`].
+
+use metrics::{counter, gauge};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::channel::{DepthProbe, Overflow, Watch};
+
+/// Registry of channels to sample. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct ChannelMonitor {
+    channels: Arc<Mutex<Vec<(&'static str, DepthProbe)>>>,
+}
+
+impl ChannelMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn watch(&self, name: &'static str, tx: &impl Watch) {
+        self.channels.lock().expect("channel monitor poisoned").push((name, tx.depth_probe()));
+    }
+
+    /// Publish `channel_depth` and `channel_capacity` every `interval`.
+    /// Channels whose senders are all gone are dropped from the registry.
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            self.channels.lock().expect("channel monitor poisoned").retain(|(name, probe)| match probe() {
+                Some((depth, capacity)) => {
+                    gauge!("channel_depth", "channel" => *name).set(depth as f64);
+                    gauge!("channel_capacity", "channel" => *name).set(capacity as f64);
+                    true
+                }
+                None => false,
+            });
+        }
+    }
+}
+
+/// A send to `channel` failed because it was full or closed.
+pub fn record_channel_drop(channel: &'static str) {
+    counter!("channel_send_failures_total", "channel" => channel).increment(1);
+}
+
+/// A full `channel` shed `count` messages under its overflow policy.
+pub fn record_channel_overflow(channel: &'static str, policy: Overflow, count: u64) {
+    counter!("channel_overflow_total", "channel" => channel, "policy" => policy.name()).increment(count);
+}
+
+/// A coalescing `channel` folded `count` sends into keys that were already pending.
+pub fn record_channel_coalesced(channel: &'static str, count: u64) {
+    counter!("channel_coalesced_total", "channel" => channel).increment(count);
+}
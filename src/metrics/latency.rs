@@ -0,0 +1,57 @@
+//! Minute-by-minute latency percentiles in the logs.
+//!
+//! The Prometheus histograms carry the full distribution; this keeps raw
+//! samples for the current window so an operator tailing logs sees p50/p90/p99
+//! per strategy, venue, and outcome without a dashboard.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::info;
+
+type Key = (&'static str, String, &'static str);
+
+static WINDOW: Lazy<Mutex<HashMap<Key, Vec<u64>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Add one signal-to-disposition sample to the current window.
+pub fn observe(strategy: &'static str, venue: &str, outcome: &'static str, latency_us: u64) {
+    WINDOW
+        .lock()
+        .expect("latency window poisoned")
+        .entry((strategy, venue.to_string(), outcome))
+        .or_default()
+        .push(latency_us);
+}
+
+/// Log and reset the window every `interval`. Quiet windows log nothing.
+pub async fn run_latency_summary(interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        let window = std::mem::take(&mut *WINDOW.lock().expect("latency window poisoned"));
+
+        for ((strategy, venue, outcome), mut samples) in window {
+            samples.sort_unstable();
+            info!(
+                strategy,
+                venue,
+                outcome,
+                count = samples.len(),
+                p50_us = percentile(&samples, 0.50),
+                p90_us = percentile(&samples, 0.90),
+                p99_us = percentile(&samples, 0.99),
+                max_us = samples.last().copied().unwrap_or(0),
+                "signal latency (last {}s)", interval.as_secs()
+            );
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `samples`.
+fn percentile(samples: &[u64], q: f64) -> u64 {
+    let rank = ((q * samples.len() as f64).ceil() as usize).clamp(1, samples.len());
+    samples[rank - 1]
+}
@@ -0,0 +1,117 @@
+//! Per-market spread, top-of-book depth, and staleness gauges.
+//!
+//! Runs as an event-bus subscriber. Labelling by market makes cardinality grow with
+//! the universe, so only the `max_markets` deepest books are exported each
+//! interval; markets that drop out stop updating and expire with the
+//! exporter's gauge idle timeout.
+
+use metrics::gauge;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::market_data::bus::Subscriber;
+use crate::market_data::types::Venue;
+use crate::state::market::as_f64;
+
+#[derive(Debug, Clone)]
+pub struct MarketQualityConfig {
+    pub interval: Duration,
+    /// Cardinality cap: markets exported per interval, deepest books first.
+    pub max_markets: usize,
+}
+
+impl Default for MarketQualityConfig {
+    fn default() -> Self {
+        Self { interval: Duration::from_secs(10), max_markets: 50 }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Quote {
+    venue: Venue,
+    market_id: String,
+    bid: Option<f64>,
+    ask: Option<f64>,
+    bid_size: Option<f64>,
+    ask_size: Option<f64>,
+    updated_at: Instant,
+}
+
+impl Quote {
+    /// Notional resting at the touch on both sides; unknown sizes count as 0.
+    fn depth_notional(&self) -> f64 {
+        self.bid.unwrap_or(0.0) * self.bid_size.unwrap_or(0.0) + self.ask.unwrap_or(0.0) * self.ask_size.unwrap_or(0.0)
+    }
+}
+
+/// Track the latest quote per token from `rx` and export gauges for the top
+/// `config.max_markets` every `config.interval`.
+pub async fn run_market_quality_exporter(mut rx: Subscriber, config: MarketQualityConfig) {
+    let mut quotes: HashMap<String, Quote> = HashMap::new();
+    let mut ticker = tokio::time::interval(config.interval);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(event) = event else { return };
+                let (best_bid, best_ask) = (event.best_bid.map(as_f64), event.best_ask.map(as_f64));
+                let quote = quotes.entry(event.token_id.clone()).or_insert_with(|| Quote {
+                    venue: event.venue.clone(),
+                    market_id: event.market_id.clone(),
+                    bid: None,
+                    ask: None,
+                    bid_size: None,
+                    ask_size: None,
+                    updated_at: event.received_at,
+                });
+                // Where the event doesn't give a size, it's only still valid if its price didn't move.
+                let (bid_size, ask_size) = event.touch_sizes();
+                if bid_size.is_some() || (best_bid.is_some() && best_bid != quote.bid) {
+                    quote.bid_size = bid_size;
+                }
+                if ask_size.is_some() || (best_ask.is_some() && best_ask != quote.ask) {
+                    quote.ask_size = ask_size;
+                }
+                quote.bid = best_bid.or(quote.bid);
+                quote.ask = best_ask.or(quote.ask);
+                quote.updated_at = event.received_at;
+            }
+            _ = ticker.tick() => export(&quotes, config.max_markets),
+        }
+    }
+}
+
+fn export(quotes: &HashMap<String, Quote>, max_markets: usize) {
+    let mut ranked: Vec<(&String, &Quote)> = quotes.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.1.depth_notional()
+            .total_cmp(&a.1.depth_notional())
+            .then_with(|| b.1.updated_at.cmp(&a.1.updated_at))
+    });
+
+    for (token_id, quote) in ranked.into_iter().take(max_markets) {
+        let labels = [
+            ("venue", format!("{:?}", quote.venue)),
+            ("market_id", quote.market_id.clone()),
+            ("token_id", token_id.clone()),
+        ];
+        if let (Some(bid), Some(ask)) = (quote.bid, quote.ask) {
+            let mid = (bid + ask) / 2.0;
+            if mid > 0.0 {
+                gauge!("market_spread_bps", &labels[..]).set((ask - bid) / mid * 10_000.0);
+            }
+        }
+        if let Some(size) = quote.bid_size {
+            gauge!("market_top_depth", &with_side(&labels, "bid")[..]).set(size);
+        }
+        if let Some(size) = quote.ask_size {
+            gauge!("market_top_depth", &with_side(&labels, "ask")[..]).set(size);
+        }
+        gauge!("market_staleness_seconds", &labels[..]).set(quote.updated_at.elapsed().as_secs_f64());
+    }
+}
+
+fn with_side(labels: &[(&'static str, String); 3], side: &'static str) -> [(&'static str, String); 4] {
+    let [venue, market, token] = labels.clone();
+    [venue, market, token, ("side", side.to_string())]
+}
@@ -1,5 +1,12 @@
+pub mod channels;
+pub mod latency;
+pub mod market_quality;
 pub mod prometheus;
+pub mod stages;
+pub mod tasks;
 
-pub fn init_metrics() {
-	prometheus::init_metrics_server();
-}
\ No newline at end of file
+pub use prometheus::{ExportTarget, ExporterConfig, HistogramConfig};
+
+pub fn init_metrics(exporter: &ExporterConfig, histograms: &HistogramConfig) -> anyhow::Result<()> {
+	prometheus::init_metrics_server(exporter, histograms)
+}
@@ -1,14 +1,272 @@
-use metrics::{counter, histogram};
-use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use metrics_util::layers::{Layer, PrefixLayer};
+use metrics_util::MetricKindMask;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use std::time::Duration;
 
-/// Start the Prometheus HTTP exporter on :9000.
+use super::latency;
+use crate::state::portfolio::PortfolioSnapshot;
+
+const GAUGE_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Set for the push targets so [`flush_metrics`] can send one last push.
+static PUSHER: OnceLock<Pusher> = OnceLock::new();
+
+/// How histograms are rendered. Each unit family is matched by metric name;
+/// a family with no buckets is rendered as a summary with `quantiles`.
+#[derive(Debug, Clone)]
+pub struct HistogramConfig {
+    /// Metrics ending in `_us`.
+    pub latency_us_buckets: Option<Vec<f64>>,
+    /// Metrics ending in `_ms`.
+    pub latency_ms_buckets: Option<Vec<f64>>,
+    /// `strategy_signal_edge`, as a fraction (0.01 = 100 bps).
+    pub edge_buckets: Option<Vec<f64>>,
+    pub quantiles: Vec<f64>,
+}
+
+impl Default for HistogramConfig {
+    /// Microsecond-to-second latency, 1 ms–5 s adapter latency, 5–1000 bps edge.
+    fn default() -> Self {
+        Self {
+            latency_us_buckets: Some(vec![
+                10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0, 5_000.0, 10_000.0, 25_000.0, 50_000.0,
+                100_000.0, 250_000.0, 1_000_000.0,
+            ]),
+            latency_ms_buckets: Some(vec![1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0, 5_000.0]),
+            edge_buckets: Some(vec![0.0005, 0.001, 0.0025, 0.005, 0.01, 0.02, 0.025, 0.03, 0.05, 0.1]),
+            quantiles: vec![0.5, 0.9, 0.99, 0.999],
+        }
+    }
+}
+
+/// How metrics leave the process.
+#[derive(Debug, Clone)]
+pub enum ExportTarget {
+    /// Serve `/metrics` for Prometheus to scrape.
+    Listen(SocketAddr),
+    /// Push to a Pushgateway job URL (`http://host:9091/metrics/job/<job>`)
+    /// every `interval`, for deployments Prometheus can't reach.
+    PushGateway { endpoint: String, interval: Duration, username: Option<String>, password: Option<String> },
+    /// POST the text exposition format every `interval` to an endpoint that
+    /// ingests it, e.g. VictoriaMetrics `/api/v1/import/prometheus`. This is
+    /// not the protobuf remote-write protocol.
+    TextImport { endpoint: String, interval: Duration, username: Option<String>, password: Option<String> },
+}
+
+/// Where and under what names metrics are exported.
+#[derive(Debug, Clone)]
+pub struct ExporterConfig {
+    pub target: ExportTarget,
+    /// Prepended to every metric name as `<prefix>_<name>`.
+    pub prefix: Option<String>,
+    /// Labels added to every series, e.g. `instance`, `environment`.
+    pub global_labels: Vec<(String, String)>,
+}
+
+impl Default for ExporterConfig {
+    fn default() -> Self {
+        Self {
+            target: ExportTarget::Listen(SocketAddr::from(([0, 0, 0, 0], 9000))),
+            prefix: None,
+            global_labels: Vec::new(),
+        }
+    }
+}
+
+/// Start the Prometheus exporter for `exporter.target`.
 /// After this call, any metrics recorded via the `metrics` crate
-/// macros (counter!, histogram!) are automatically exported at /metrics.
-pub fn init_metrics_server() {
-    PrometheusBuilder::new()
-        .with_http_listener(([0, 0, 0, 0], 9000))
-        .install()
-        .expect("failed to start Prometheus metrics server");
+/// macros (counter!, histogram!) are automatically exported — served at
+/// /metrics, or pushed on an interval.
+///
+/// Must be called from within a tokio runtime; the listener or pusher runs
+/// as a spawned task.
+pub fn init_metrics_server(exporter: &ExporterConfig, histograms: &HistogramConfig) -> anyhow::Result<()> {
+    // Bucket matchers see names after the prefix layer has renamed them.
+    let prefixed = |name: &str| match &exporter.prefix {
+        Some(prefix) => format!("{prefix}_{name}"),
+        None => name.to_string(),
+    };
+
+    let mut builder = PrometheusBuilder::new()
+        // Gauges not refreshed for a while are dropped, so per-market series
+        // that fall out of the exported set don't linger with stale values.
+        .idle_timeout(MetricKindMask::GAUGE, Some(GAUGE_IDLE_TIMEOUT))
+        .set_quantiles(&histograms.quantiles)?;
+    if let Some(buckets) = &histograms.latency_us_buckets {
+        builder = builder.set_buckets_for_metric(Matcher::Suffix("_us".to_string()), buckets)?;
+    }
+    if let Some(buckets) = &histograms.latency_ms_buckets {
+        builder = builder.set_buckets_for_metric(Matcher::Suffix("_ms".to_string()), buckets)?;
+    }
+    if let Some(buckets) = &histograms.edge_buckets {
+        builder = builder.set_buckets_for_metric(Matcher::Full(prefixed("strategy_signal_edge")), buckets)?;
+    }
+    for (key, value) in &exporter.global_labels {
+        builder = builder.add_global_label(key, value);
+    }
+
+    let (recorder, exporter_future) = match &exporter.target {
+        ExportTarget::Listen(addr) => {
+            let (recorder, future) = builder.with_http_listener(*addr).build()?;
+            (recorder, Some(future))
+        }
+        ExportTarget::PushGateway { endpoint, interval, username, password } => {
+            let (recorder, future) =
+                builder.with_push_gateway(endpoint, *interval, username.clone(), password.clone())?.build()?;
+            // The Pushgateway replaces a job's metrics on PUT, as the exporter's own pushes do.
+            let auth = username.clone().map(|user| (user, password.clone()));
+            let _ = PUSHER.set(Pusher::new(recorder.handle(), endpoint.clone(), reqwest::Method::PUT, auth));
+            (recorder, Some(future))
+        }
+        ExportTarget::TextImport { endpoint, interval, username, password } => {
+            let recorder = builder.build_recorder();
+            let auth = username.clone().map(|user| (user, password.clone()));
+            let pusher = Pusher::new(recorder.handle(), endpoint.clone(), reqwest::Method::POST, auth);
+            let _ = PUSHER.set(pusher.clone());
+            tokio::spawn(run_text_import(pusher, *interval));
+            (recorder, None)
+        }
+    };
+    let installed = match &exporter.prefix {
+        Some(prefix) => metrics::set_global_recorder(PrefixLayer::new(prefix.clone()).layer(recorder)).is_ok(),
+        None => metrics::set_global_recorder(recorder).is_ok(),
+    };
+    anyhow::ensure!(installed, "a metrics recorder is already installed");
+    if let Some(exporter_future) = exporter_future {
+        tokio::spawn(async move {
+            if exporter_future.await.is_err() {
+                tracing::error!("Prometheus exporter stopped");
+            }
+        });
+    }
+    describe_metrics();
+    Ok(())
+}
+
+/// Renders the registry and sends it to a push endpoint.
+#[derive(Clone)]
+struct Pusher {
+    handle: PrometheusHandle,
+    endpoint: String,
+    method: reqwest::Method,
+    auth: Option<(String, Option<String>)>,
+    client: reqwest::Client,
+}
+
+impl Pusher {
+    fn new(handle: PrometheusHandle, endpoint: String, method: reqwest::Method, auth: Option<(String, Option<String>)>) -> Self {
+        Self { handle, endpoint, method, auth, client: reqwest::Client::new() }
+    }
+
+    async fn push(&self) -> reqwest::Result<()> {
+        self.handle.run_upkeep();
+        let mut request = self
+            .client
+            .request(self.method.clone(), &self.endpoint)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+            .body(self.handle.render());
+        if let Some((user, password)) = &self.auth {
+            request = request.basic_auth(user, password.as_ref());
+        }
+        request.send().await?.error_for_status().map(drop)
+    }
+}
+
+/// POST the registry to the endpoint every `interval`. Failed pushes are
+/// logged and retried on the next tick; Prometheus counters are cumulative,
+/// so a missed push loses resolution, not data.
+async fn run_text_import(pusher: Pusher, interval: Duration) {
+    let mut tick = tokio::time::interval(interval);
+    tracing::info!(endpoint = %pusher.endpoint, interval_secs = interval.as_secs(), "pushing metrics");
+    loop {
+        tick.tick().await;
+        if let Err(e) = pusher.push().await {
+            tracing::warn!(error = %e, endpoint = %pusher.endpoint, "metrics push failed");
+        }
+    }
+}
+
+/// Push the registry once more so whatever was recorded since the last
+/// interval isn't lost on exit. A no-op when metrics are scraped.
+pub async fn flush_metrics() {
+    let Some(pusher) = PUSHER.get() else { return };
+    match pusher.push().await {
+        Ok(()) => tracing::info!(endpoint = %pusher.endpoint, "final metrics push sent"),
+        Err(e) => tracing::warn!(error = %e, endpoint = %pusher.endpoint, "final metrics push failed"),
+    }
+}
+
+/// HELP text and units for every metric this module records.
+fn describe_metrics() {
+    describe_counter!("adapter_events_total", "Market data events received per venue and type");
+    describe_histogram!("adapter_event_latency_ms", Unit::Milliseconds, "Exchange timestamp to local receive");
+    describe_histogram!("ws_ping_rtt_ms", Unit::Milliseconds, "WebSocket keepalive ping to its pong, per venue");
+    describe_counter!("ws_pongs_missed_total", "WebSocket keepalive pings that went unanswered, per venue");
+    describe_gauge!("polymarket_ws_connection_tokens", "Tokens carried by each Polymarket market WebSocket");
+    describe_counter!("rest_rate_limited_total", "Venue REST calls held back by the rate limiter, per endpoint class");
+    describe_histogram!("rest_rate_limit_wait_ms", Unit::Milliseconds, "How long a held-back venue REST call waited");
+    describe_counter!("strategy_signals_total", "Signals emitted by strategies");
+    describe_histogram!("strategy_signal_edge", "Edge claimed by each signal");
+    describe_counter!("execution_fills_total", "Orders that filled every leg");
+    describe_counter!("execution_priority_signals_total", "Signals sent on the priority lane");
+    describe_counter!("execution_rejections_total", "Order legs rejected by the executor");
+    describe_counter!("execution_signal_outcomes_total", "Signals by final disposition (filled, blocked, expired, ...)");
+    describe_histogram!("execution_signal_to_fill_us", Unit::Microseconds, "Signal generation to final disposition, by outcome");
+    describe_histogram!("execution_e2e_latency_us", Unit::Microseconds, "WS receive to final disposition, by outcome");
+    describe_histogram!("pipeline_parse_us", Unit::Microseconds, "Socket receive to MarketEvent built");
+    describe_histogram!("pipeline_cache_write_us", Unit::Microseconds, "MarketEvent built to cache written");
+    describe_histogram!("pipeline_notify_queue_us", Unit::Microseconds, "Cache written to picked up by the strategy engine");
+    describe_histogram!("pipeline_strategy_eval_us", Unit::Microseconds, "Strategy evaluation");
+    describe_histogram!("pipeline_risk_check_us", Unit::Microseconds, "Signal emitted to risk checks passed");
+    describe_histogram!("pipeline_order_post_us", Unit::Microseconds, "Risk checks passed to order handed to the executor");
+    describe_histogram!("pipeline_ack_us", Unit::Microseconds, "Order handed to the executor to report returned");
+    describe_histogram!("pipeline_fill_us", Unit::Microseconds, "Report returned to fills applied");
+    describe_counter!("persist_records_dropped_total", "Records dropped because a persistence sink was full or closed");
+    describe_counter!("watchdog_alerts_total", "Watchdog checks that started failing");
+    describe_gauge!("feed_stale_tokens", "Tokens the feed watchdog holds stale, per venue");
+    describe_counter!("feed_stale_total", "Tokens whose feed went quiet past the deadline, per venue");
+    describe_counter!("busy_poll_total", "Busy-poll waits that found a message (hit) or parked, per task");
+    describe_counter!("supervisor_restarts_total", "Times each supervised task died and was restarted");
+    describe_gauge!("fix_sessions", "Open FIX gateway connections");
+    describe_counter!("fix_messages_total", "FIX messages received and sent, by direction and type");
+    describe_counter!("webhook_requests_total", "Signal webhook requests, by result");
+    describe_counter!("publish_messages_total", "Signal feed messages published, by sink and type");
+    describe_counter!("publish_errors_total", "Signal feed write failures and messages skipped by slow WebSocket subscribers");
+    describe_gauge!("publish_subscribers", "Connected signal feed WebSocket subscribers");
+    describe_gauge!("watchdog_alert_active", "1 while a watchdog check is failing");
+    describe_gauge!("event_rate", "Market events per second over the last bucket, per venue");
+    describe_gauge!("event_rate_baseline", "Learned normal event rate (EWMA), per venue");
+    describe_gauge!("event_rate_anomaly", "1 while a venue's event rate is anomalous");
+    describe_counter!("event_rate_anomalies_total", "Sustained event-rate anomalies raised, by direction");
+    describe_gauge!("market_spread_bps", "Ask minus bid over mid, per exported market");
+    describe_gauge!("market_top_depth", "Size resting at the best bid/ask, per exported market");
+    describe_gauge!("market_staleness_seconds", Unit::Seconds, "Time since the market's last update");
+    describe_gauge!("channel_depth", "Messages queued in each mpsc channel");
+    describe_gauge!("channel_capacity", "Buffer size of each mpsc channel");
+    describe_counter!("channel_send_failures_total", "Sends that failed because a channel was full or closed");
+    describe_gauge!("event_bus_lag", "Events queued for each event-bus subscriber");
+    describe_counter!("event_bus_lagged_total", "Events an event-bus subscriber missed by falling behind");
+    describe_counter!("event_pool_allocations_total", "Market events allocated because the event pool was empty (debug)");
+    describe_counter!("event_pool_reuses_total", "Market events refilled from the event pool (debug)");
+    describe_counter!("tokio_task_polls_total", "Times each named task was polled");
+    describe_counter!("tokio_task_slow_polls_total", "Polls that exceeded the slow-poll threshold");
+    describe_counter!("tokio_task_scheduled_total", "Times each task was woken and waited to be polled");
+    describe_counter!("tokio_task_long_delays_total", "Wake-to-poll delays that exceeded the long-delay threshold");
+    describe_gauge!("tokio_task_poll_seconds_total", Unit::Seconds, "Cumulative time spent polling each task");
+    describe_gauge!("tokio_task_scheduled_seconds_total", Unit::Seconds, "Cumulative wake-to-poll delay per task");
+    describe_gauge!("tokio_task_instrumented", "Live instrumented futures per task name");
+    describe_gauge!("portfolio_equity", "Cash plus positions at mark");
+    describe_gauge!("portfolio_cash", "Cash balance");
+    describe_gauge!("portfolio_pnl", "Portfolio PnL by kind (realized, unrealized, total)");
+    describe_gauge!("portfolio_realized_pnl", "Realized PnL net of fees and rebates, per strategy");
+    describe_gauge!("portfolio_unrealized_pnl", "Unrealized PnL at liquidation marks, per venue");
+    describe_gauge!("portfolio_exposure", "Gross position exposure at mark, per venue");
+    describe_gauge!("position_size", "Signed position size per token");
+    describe_gauge!("position_unrealized_pnl", "Unrealized PnL per token");
 }
 
 // ── Adapter metrics ──────────────────────────────────────────────
@@ -23,6 +281,19 @@ pub fn record_adapter_latency(venue: &str, event_type: &str, latency_ms: f64) {
         .record(latency_ms);
 }
 
+pub fn record_ws_ping_rtt(venue: &str, rtt_ms: f64) {
+    histogram!("ws_ping_rtt_ms", "venue" => venue.to_string()).record(rtt_ms);
+}
+
+pub fn record_ws_pong_missed(venue: &str) {
+    counter!("ws_pongs_missed_total", "venue" => venue.to_string()).increment(1);
+}
+
+pub fn record_rest_rate_limited(venue: &str, class: &str, wait_ms: f64) {
+    counter!("rest_rate_limited_total", "venue" => venue.to_string(), "class" => class.to_string()).increment(1);
+    histogram!("rest_rate_limit_wait_ms", "venue" => venue.to_string(), "class" => class.to_string()).record(wait_ms);
+}
+
 // ── Strategy metrics ─────────────────────────────────────────────
 
 pub fn record_signal(strategy: &str, venue: &str) {
@@ -47,14 +318,82 @@ pub fn record_rejection(strategy: &str, executor: &str) {
         .increment(1);
 }
 
-/// Time from strategy signal generation to execution complete.
-pub fn record_signal_to_fill_latency_us(strategy: &str, latency_us: u128) {
-    histogram!("execution_signal_to_fill_us", "strategy" => strategy.to_string())
-        .record(latency_us as f64);
+pub fn record_signal_outcome(strategy: &str, outcome: &str) {
+    counter!("execution_signal_outcomes_total", "strategy" => strategy.to_string(), "outcome" => outcome.to_string())
+        .increment(1);
+}
+
+/// Time from strategy signal generation to the signal's final disposition
+/// (filled, rejected, expired, ...). Also feeds the per-minute log summary.
+pub fn record_signal_to_fill_latency_us(strategy: &'static str, venue: &str, outcome: &'static str, latency_us: u128) {
+    histogram!(
+        "execution_signal_to_fill_us",
+        "strategy" => strategy, "venue" => venue.to_string(), "outcome" => outcome
+    )
+    .record(latency_us as f64);
+    latency::observe(strategy, venue, outcome, latency_us as u64);
+}
+
+/// Time from WS price receive to the signal's final disposition (full pipeline).
+pub fn record_e2e_latency_us(strategy: &'static str, venue: &str, outcome: &'static str, latency_us: u128) {
+    histogram!(
+        "execution_e2e_latency_us",
+        "strategy" => strategy, "venue" => venue.to_string(), "outcome" => outcome
+    )
+    .record(latency_us as f64);
+}
+
+// ── Persistence metrics ──────────────────────────────────────────
+
+pub fn record_persist_drop() {
+    counter!("persist_records_dropped_total").increment(1);
+}
+
+// ── Portfolio metrics ────────────────────────────────────────────
+
+/// Publish a portfolio snapshot as gauges. Positions that close stop being
+/// refreshed and expire with the gauge idle timeout.
+pub fn record_portfolio(snapshot: &PortfolioSnapshot) {
+    gauge!("portfolio_equity").set(snapshot.balances.equity);
+    gauge!("portfolio_cash").set(snapshot.balances.cash);
+    gauge!("portfolio_pnl", "kind" => "realized").set(snapshot.pnl.realized);
+    gauge!("portfolio_pnl", "kind" => "unrealized").set(snapshot.pnl.unrealized);
+    gauge!("portfolio_pnl", "kind" => "total").set(snapshot.pnl.total);
+
+    for (strategy, pnl) in &snapshot.pnl_by_strategy {
+        gauge!("portfolio_realized_pnl", "strategy" => strategy.clone()).set(pnl.realized_net);
+    }
+    for (venue, exposure) in &snapshot.exposure_by_venue {
+        gauge!("portfolio_exposure", "venue" => venue.clone()).set(*exposure);
+    }
+
+    let mut unrealized_by_venue: HashMap<String, f64> = HashMap::new();
+    for position in &snapshot.positions {
+        let venue = format!("{:?}", position.venue);
+        *unrealized_by_venue.entry(venue.clone()).or_default() += position.unrealized_pnl;
+        let labels = [
+            ("venue", venue),
+            ("market_id", position.market_id.clone().unwrap_or_default()),
+            ("token_id", position.token_id.clone()),
+        ];
+        gauge!("position_size", &labels[..]).set(position.size);
+        gauge!("position_unrealized_pnl", &labels[..]).set(position.unrealized_pnl);
+    }
+    for (venue, unrealized) in unrealized_by_venue {
+        gauge!("portfolio_unrealized_pnl", "venue" => venue).set(unrealized);
+    }
+}
+
+// ── Watchdog metrics ─────────────────────────────────────────────
+
+pub fn record_watchdog_alert(check: &str, venue: &str, active: bool) {
+    if active {
+        counter!("watchdog_alerts_total", "check" => check.to_string(), "venue" => venue.to_string()).increment(1);
+    }
+    gauge!("watchdog_alert_active", "check" => check.to_string(), "venue" => venue.to_string())
+        .set(if active { 1.0 } else { 0.0 });
 }
 
-/// Time from WS price receive to execution complete (full pipeline).
-pub fn record_e2e_latency_us(strategy: &str, latency_us: u128) {
-    histogram!("execution_e2e_latency_us", "strategy" => strategy.to_string())
-        .record(latency_us as f64);
+pub fn record_watchdog_alert_active(check: &str, venue: &str) {
+    gauge!("watchdog_alert_active", "check" => check.to_string(), "venue" => venue.to_string()).set(1.0);
 }
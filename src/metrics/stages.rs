@@ -0,0 +1,76 @@
+use metrics::histogram;
+use std::time::Instant;
+
+/// Monotonic timestamps of one market update's trip through the pipeline.
+///
+/// Stages after `received` are stamped as the update moves downstream; a
+/// stage stays `None` until it is reached, and a gap is only recorded when
+/// both of its ends were stamped.
+#[derive(Debug, Clone, Copy)]
+pub struct StageTimes {
+    /// WS message yielded by the socket stream.
+    pub received: Instant,
+    /// Converted into a `MarketEvent`.
+    pub parsed: Option<Instant>,
+    /// Written to the market cache.
+    pub cached: Option<Instant>,
+    /// Picked up by the strategy engine (the gap from `cached` is notify-channel queueing).
+    pub dequeued: Option<Instant>,
+    /// Strategy evaluation returned a signal.
+    pub evaluated: Option<Instant>,
+    /// Passed the bridge's dedup, TTL, and risk checks.
+    pub risk_checked: Option<Instant>,
+    /// Handed to the executor.
+    pub posted: Option<Instant>,
+    /// Executor returned its report.
+    pub acked: Option<Instant>,
+    /// Fills applied to the portfolio.
+    pub filled: Option<Instant>,
+}
+
+impl StageTimes {
+    pub fn new(received: Instant) -> Self {
+        Self {
+            received,
+            parsed: None,
+            cached: None,
+            dequeued: None,
+            evaluated: None,
+            risk_checked: None,
+            posted: None,
+            acked: None,
+            filled: None,
+        }
+    }
+
+    /// Record the market-data stages (parse, cache write). Called for every event.
+    pub fn record_market_data(&self, venue: &'static str) {
+        if let Some(us) = gap_us(Some(self.received), self.parsed) {
+            histogram!("pipeline_parse_us", "venue" => venue).record(us);
+        }
+        if let Some(us) = gap_us(self.parsed.or(Some(self.received)), self.cached) {
+            histogram!("pipeline_cache_write_us", "venue" => venue).record(us);
+        }
+    }
+
+    /// Record the signal-path stages for one signal that reached the executor.
+    pub fn record_execution(&self, strategy: &str) {
+        let stages = [
+            ("pipeline_notify_queue_us", self.cached, self.dequeued),
+            ("pipeline_strategy_eval_us", self.dequeued, self.evaluated),
+            ("pipeline_risk_check_us", self.evaluated, self.risk_checked),
+            ("pipeline_order_post_us", self.risk_checked, self.posted),
+            ("pipeline_ack_us", self.posted, self.acked),
+            ("pipeline_fill_us", self.acked, self.filled),
+        ];
+        for (name, from, to) in stages {
+            if let Some(us) = gap_us(from, to) {
+                histogram!(name, "strategy" => strategy.to_string()).record(us);
+            }
+        }
+    }
+}
+
+fn gap_us(from: Option<Instant>, to: Option<Instant>) -> Option<f64> {
+    Some(to?.saturating_duration_since(from?).as_micros() as f64)
+}
@@ -0,0 +1,62 @@
+//! Tokio scheduler metrics per named task, via `tokio-metrics`.
+//!
+//! Wrapping a task's future with [`instrument`] registers a `TaskMonitor`
+//! under that name; [`run_task_metrics`] exports the cumulative totals so
+//! scheduling delay (time spent runnable but not running) can be told apart
+//! from time spent waiting on the exchange. Runtime-wide metrics such as
+//! budget-forced yields need a `--cfg tokio_unstable` build and are exported
+//! only there.
+
+use metrics::{counter, gauge};
+use once_cell::sync::Lazy;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio_metrics::{Instrumented, TaskMonitor};
+
+static MONITORS: Lazy<Mutex<Vec<(&'static str, TaskMonitor)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Instrument `future` under `task`. Futures sharing a name share a monitor.
+pub fn instrument<F: Future>(task: &'static str, future: F) -> Instrumented<F> {
+    let mut monitors = MONITORS.lock().expect("task monitors poisoned");
+    let monitor = match monitors.iter().find(|(name, _)| *name == task) {
+        Some((_, monitor)) => monitor.clone(),
+        None => {
+            let monitor = TaskMonitor::new();
+            monitors.push((task, monitor.clone()));
+            monitor
+        }
+    };
+    monitor.instrument(future)
+}
+
+/// Export task (and, under `tokio_unstable`, runtime) metrics every `interval`.
+pub async fn run_task_metrics(interval: Duration) {
+    #[cfg(tokio_unstable)]
+    let mut runtime = tokio_metrics::RuntimeMonitor::new(&tokio::runtime::Handle::current()).intervals();
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let monitors: Vec<(&'static str, TaskMonitor)> = MONITORS.lock().expect("task monitors poisoned").clone();
+        for (task, monitor) in monitors {
+            let m = monitor.cumulative();
+            counter!("tokio_task_polls_total", "task" => task).absolute(m.total_poll_count);
+            counter!("tokio_task_slow_polls_total", "task" => task).absolute(m.total_slow_poll_count);
+            counter!("tokio_task_scheduled_total", "task" => task).absolute(m.total_scheduled_count);
+            counter!("tokio_task_long_delays_total", "task" => task).absolute(m.total_long_delay_count);
+            gauge!("tokio_task_poll_seconds_total", "task" => task).set(m.total_poll_duration.as_secs_f64());
+            gauge!("tokio_task_scheduled_seconds_total", "task" => task).set(m.total_scheduled_duration.as_secs_f64());
+            gauge!("tokio_task_instrumented", "task" => task).set((m.instrumented_count - m.dropped_count) as f64);
+        }
+
+        #[cfg(tokio_unstable)]
+        if let Some(r) = runtime.next() {
+            gauge!("tokio_runtime_workers").set(r.workers_count as f64);
+            gauge!("tokio_runtime_global_queue_depth").set(r.global_queue_depth as f64);
+            counter!("tokio_runtime_budget_forced_yields_total").increment(r.budget_forced_yield_count);
+            gauge!("tokio_runtime_busy_ratio").set(r.busy_ratio());
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use serde_json::json;
+
+/// One delivery target for rendered messages.
+#[derive(Debug, Clone)]
+pub enum Channel {
+    Telegram { bot_token: String, chat_id: String },
+    Discord { webhook_url: String },
+    Slack { webhook_url: String },
+}
+
+impl Channel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Channel::Telegram { .. } => "telegram",
+            Channel::Discord { .. } => "discord",
+            Channel::Slack { .. } => "slack",
+        }
+    }
+
+    pub(super) async fn send(&self, client: &reqwest::Client, text: &str) -> anyhow::Result<()> {
+        let request = match self {
+            Channel::Telegram { bot_token, chat_id } => client
+                .post(format!("https://api.telegram.org/bot{bot_token}/sendMessage"))
+                .json(&json!({ "chat_id": chat_id, "text": text })),
+            Channel::Discord { webhook_url } => client.post(webhook_url).json(&json!({ "content": text })),
+            Channel::Slack { webhook_url } => client.post(webhook_url).json(&json!({ "text": text })),
+        };
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("{} returned {status}: {body}", self.name());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+//! Operator notifications over Telegram, Discord, and Slack.
+//!
+//! Components hold a cheap [`Notifier`] handle and fire [`NotifyEvent`]s at it
+//! without blocking; [`run_notifier`] filters them by kind, rate-limits, renders
+//! each to text, and posts it to every configured channel.
+
+mod channels;
+
+pub use channels::Channel;
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::market_data::types::{Side, Venue};
+use crate::metrics::channels::record_channel_drop;
+use crate::risk::RiskManager;
+use crate::state::portfolio::Portfolio;
+
+const NOTIFY_CHANNEL_BUFFER: usize = 256;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Fill,
+    Rejection,
+    RiskHalt,
+    Disconnect,
+    Watchdog,
+    DailySummary,
+    Restart,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 7] = [
+        EventKind::Fill,
+        EventKind::Rejection,
+        EventKind::RiskHalt,
+        EventKind::Disconnect,
+        EventKind::Watchdog,
+        EventKind::DailySummary,
+        EventKind::Restart,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EventKind::Fill => "fill",
+            EventKind::Rejection => "rejection",
+            EventKind::RiskHalt => "risk",
+            EventKind::Disconnect => "disconnect",
+            EventKind::Watchdog => "watchdog",
+            EventKind::DailySummary => "daily",
+            EventKind::Restart => "restart",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NotifyEvent {
+    Fill {
+        strategy: &'static str,
+        venue: Venue,
+        market_id: String,
+        side: Side,
+        price: f64,
+        size: f64,
+    },
+    Rejection {
+        strategy: &'static str,
+        market_id: String,
+        reason: String,
+    },
+    RiskHalt {
+        drawdown: f64,
+        limit: f64,
+    },
+    Disconnect {
+        component: String,
+        detail: String,
+    },
+    /// A watchdog check started (`firing`) or stopped failing.
+    Watchdog {
+        check: String,
+        firing: bool,
+        detail: String,
+    },
+    /// A supervised task died and is about to be restarted.
+    TaskRestart {
+        task: &'static str,
+        reason: String,
+        restarts: u64,
+        backoff: Duration,
+    },
+    DailySummary {
+        date: String,
+        equity: f64,
+        change: Option<f64>,
+        realized: f64,
+        unrealized: f64,
+        fees: f64,
+        notional: f64,
+        open_positions: usize,
+    },
+}
+
+impl NotifyEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            NotifyEvent::Fill { .. } => EventKind::Fill,
+            NotifyEvent::Rejection { .. } => EventKind::Rejection,
+            NotifyEvent::RiskHalt { .. } => EventKind::RiskHalt,
+            NotifyEvent::Disconnect { .. } => EventKind::Disconnect,
+            NotifyEvent::Watchdog { .. } => EventKind::Watchdog,
+            NotifyEvent::DailySummary { .. } => EventKind::DailySummary,
+            NotifyEvent::TaskRestart { .. } => EventKind::Restart,
+        }
+    }
+
+    /// Plain-text message, safe for every channel without markup escaping.
+    pub fn render(&self) -> String {
+        match self {
+            NotifyEvent::Fill { strategy, venue, market_id, side, price, size } => {
+                format!("✅ FILL [{strategy}] {side:?} {size} @ {price:.4} on {venue:?} market {market_id}")
+            }
+            NotifyEvent::Rejection { strategy, market_id, reason } => {
+                format!("❌ REJECTED [{strategy}] market {market_id}: {reason}")
+            }
+            NotifyEvent::RiskHalt { drawdown, limit } => format!(
+                "🛑 RISK BREAKER TRIPPED — drawdown {:.2}% ≥ limit {:.2}%. Trading halted until reset.",
+                drawdown * 100.0,
+                limit * 100.0
+            ),
+            NotifyEvent::Disconnect { component, detail } => format!("⚠️ {component} DOWN: {detail}"),
+            NotifyEvent::Watchdog { check, firing: true, detail } => format!("🐕 WATCHDOG {check}: {detail}"),
+            NotifyEvent::Watchdog { check, firing: false, detail } => format!("🐕 WATCHDOG {check} recovered: {detail}"),
+            NotifyEvent::TaskRestart { task, reason, restarts, backoff } => format!(
+                "🔁 TASK {task} {reason} — restart #{restarts} in {}s",
+                backoff.as_secs()
+            ),
+            NotifyEvent::DailySummary { date, equity, change, realized, unrealized, fees, notional, open_positions } => {
+                let change = change.map_or_else(|| "n/a".to_string(), |c| format!("{c:+.2}"));
+                format!(
+                    "📊 Daily summary {date}\n\
+                     Equity: {equity:.2} ({change})\n\
+                     PnL: realized {realized:+.2}, unrealized {unrealized:+.2}, fees {fees:.2}\n\
+                     Filled notional: {notional:.2}\n\
+                     Open positions: {open_positions}"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    pub channels: Vec<Channel>,
+    /// Event kinds to send; everything else is dropped.
+    pub enabled: HashSet<EventKind>,
+    /// Messages per rolling minute across all kinds. Excess is dropped and
+    /// counted in the next message that goes out.
+    pub max_per_minute: usize,
+}
+
+/// Non-blocking handle for emitting notifications. Cheap to clone.
+/// A disabled notifier drops everything, so callers never need to branch.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    tx: Option<mpsc::Sender<NotifyEvent>>,
+}
+
+impl Notifier {
+    /// Create a handle and the receiver to hand to [`run_notifier`].
+    pub fn new() -> (Self, mpsc::Receiver<NotifyEvent>) {
+        let (tx, rx) = mpsc::channel(NOTIFY_CHANNEL_BUFFER);
+        (Self { tx: Some(tx) }, rx)
+    }
+
+    pub fn disabled() -> Self {
+        Self { tx: None }
+    }
+
+    pub fn notify(&self, event: NotifyEvent) {
+        if let Some(tx) = &self.tx && tx.try_send(event).is_err() {
+            record_channel_drop("notify");
+            warn!("notifier queue full — notification dropped");
+        }
+    }
+}
+
+/// Deliver events from `rx` until every [`Notifier`] is dropped.
+pub async fn run_notifier(mut rx: mpsc::Receiver<NotifyEvent>, config: NotifyConfig) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let mut sent: VecDeque<Instant> = VecDeque::new();
+    let mut suppressed: u64 = 0;
+
+    info!(
+        channels = ?config.channels.iter().map(Channel::name).collect::<Vec<_>>(),
+        events = ?config.enabled.iter().map(|k| k.name()).collect::<Vec<_>>(),
+        "notifier started"
+    );
+
+    while let Some(event) = rx.recv().await {
+        if !config.enabled.contains(&event.kind()) {
+            continue;
+        }
+
+        let now = Instant::now();
+        while sent.front().is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW) {
+            sent.pop_front();
+        }
+        if sent.len() >= config.max_per_minute {
+            suppressed += 1;
+            continue;
+        }
+        sent.push_back(now);
+
+        let mut text = event.render();
+        if suppressed > 0 {
+            text.push_str(&format!("\n({suppressed} earlier notifications suppressed by rate limit)"));
+            suppressed = 0;
+        }
+
+        for channel in &config.channels {
+            if let Err(e) = channel.send(&client, &text).await {
+                warn!(channel = channel.name(), kind = event.kind().name(), error = %e, "notification failed");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Emit a [`NotifyEvent::DailySummary`] just after each UTC midnight.
+pub async fn run_daily_summary(notifier: Notifier, portfolio: Portfolio, risk: RiskManager) {
+    let mut last_equity: Option<f64> = None;
+
+    loop {
+        let now = Utc::now();
+        let next_midnight = (now.date_naive() + chrono::Days::new(1)).and_hms_opt(0, 0, 0).expect("midnight is valid");
+        let wait = (next_midnight.and_utc() - now).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        // Summarise the day that just ended.
+        let closed_day: DateTime<Utc> = next_midnight.and_utc() - chrono::Duration::milliseconds(1);
+        let snapshot = portfolio.snapshot();
+        let equity = snapshot.balances.equity;
+        notifier.notify(NotifyEvent::DailySummary {
+            date: closed_day.format("%Y-%m-%d").to_string(),
+            equity,
+            change: last_equity.map(|prev| equity - prev),
+            realized: snapshot.pnl.realized,
+            unrealized: snapshot.pnl.unrealized,
+            fees: snapshot.pnl.taker_fees - snapshot.pnl.maker_rebates,
+            notional: risk.daily_notional(closed_day.timestamp_millis() as u64),
+            open_positions: snapshot.positions.len(),
+        });
+        last_equity = Some(equity);
+    }
+}
@@ -0,0 +1,460 @@
+use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::SerializedFileReader;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::market_data::bus::Subscriber;
+use crate::market_data::types::{MarketEvent, MarketEventKind, Side};
+use crate::state::market::{as_f64, MarketState};
+use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::portfolio::unix_ms;
+
+const MS_PER_HOUR: u64 = 3_600_000;
+/// Rows buffered in memory before a record batch is written to the open file.
+const FLUSH_ROWS: usize = 8_192;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+/// Appended to a file's name while it is being written. The file is renamed
+/// to its final name once its footer is written, so anything without this
+/// suffix is complete.
+pub const IN_PROGRESS_SUFFIX: &str = ".inprogress";
+
+/// Whether `path` is an archive file still being written (or left behind by
+/// a crash before it was finished).
+pub fn is_in_progress(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(IN_PROGRESS_SUFFIX))
+}
+
+/// Directory under the archive root that unreadable leftovers are moved
+/// to. Retention prunes it like the rest of the root; it is never uploaded.
+pub const CORRUPT_DIR: &str = "corrupt";
+
+fn in_progress_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(IN_PROGRESS_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Deal with in-progress files a previous run left behind when it died
+/// before closing them. Ones whose footer reads back get their final name;
+/// the rest move to [`CORRUPT_DIR`] with a `.corrupt` suffix, out of reach
+/// of backtests. Call before any writer is open.
+pub fn recover_in_progress(root: &Path) -> anyhow::Result<()> {
+    let mut orphans = Vec::new();
+    if root.exists() {
+        in_progress_files(root, &root.join(CORRUPT_DIR), &mut orphans)?;
+    }
+    for orphan in orphans {
+        let name = orphan.to_string_lossy();
+        let path = PathBuf::from(&name[..name.len() - IN_PROGRESS_SUFFIX.len()]);
+        match footer_reads_back(&orphan) {
+            Ok(()) => {
+                let path = if path.exists() { path.with_extension(format!("{}.parquet", unix_ms())) } else { path };
+                std::fs::rename(&orphan, &path)?;
+                info!(path = %path.display(), "finalized archive file left open by an earlier run");
+            }
+            Err(e) => {
+                let rel = path.strip_prefix(root).unwrap_or(&path);
+                let mut quarantined = root.join(CORRUPT_DIR).join(rel).into_os_string();
+                quarantined.push(".corrupt");
+                let quarantined = PathBuf::from(quarantined);
+                if let Some(parent) = quarantined.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::rename(&orphan, &quarantined)?;
+                warn!(path = %quarantined.display(), error = %e, "unreadable archive file left by an earlier run quarantined");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn footer_reads_back(path: &Path) -> anyhow::Result<()> {
+    SerializedFileReader::new(File::open(path)?)?;
+    Ok(())
+}
+
+fn in_progress_files(dir: &Path, skip: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path != skip {
+                in_progress_files(&path, skip, out)?;
+            }
+        } else if is_in_progress(&path) {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Archiver settings.
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    pub root: PathBuf,
+    /// Relative path template for each hourly file. Placeholders:
+    /// `{stream}` (`events` / `snapshots`), `{date}` (YYYY-MM-DD), `{hour}` (00–23).
+    pub layout: String,
+    /// How often the full market cache is snapshotted.
+    pub snapshot_interval: Duration,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("data/archive"),
+            layout: "{stream}/date={date}/hour={hour}.parquet".to_string(),
+            snapshot_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ArchiveConfig {
+    fn path_for(&self, stream: &str, hour_start_ms: u64) -> PathBuf {
+        let (date, hour) = utc_date_hour(hour_start_ms);
+        let rel = self
+            .layout
+            .replace("{stream}", stream)
+            .replace("{date}", &date)
+            .replace("{hour}", &format!("{hour:02}"));
+        self.root.join(rel)
+    }
+}
+
+// ── Row buffers ──────────────────────────────────────────────────────────────
+
+/// Column buffers shared by both streams. Events and snapshots use the same
+/// schema so research code can load either with one reader.
+#[derive(Default)]
+struct Rows {
+    ts_ms: Vec<i64>,
+    venue: Vec<String>,
+    kind: Vec<String>,
+    market_id: Vec<Option<String>>,
+    token_id: Vec<String>,
+    best_bid: Vec<Option<f64>>,
+    best_ask: Vec<Option<f64>>,
+    volume24h: Vec<Option<f64>>,
+    last_trade_price: Vec<Option<f64>>,
+    liquidity: Vec<Option<f64>>,
+    /// Top-of-book sizes and trade size/side, when the event carries them.
+    bid_size: Vec<Option<f64>>,
+    ask_size: Vec<Option<f64>>,
+    trade_size: Vec<Option<f64>>,
+    trade_side: Vec<Option<String>>,
+}
+
+impl Rows {
+    fn len(&self) -> usize {
+        self.ts_ms.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ts_ms.is_empty()
+    }
+
+    /// Each run of consecutive rows stamped in the same hour, as
+    /// `(hour start, first row, row count)`.
+    fn hour_runs(&self) -> Vec<(u64, usize, usize)> {
+        let mut runs: Vec<(u64, usize, usize)> = Vec::new();
+        for (i, ts) in self.ts_ms.iter().enumerate() {
+            let ts = *ts as u64;
+            let hour = ts - ts % MS_PER_HOUR;
+            match runs.last_mut() {
+                Some((last, _, len)) if *last == hour => *len += 1,
+                _ => runs.push((hour, i, 1)),
+            }
+        }
+        runs
+    }
+
+    fn push_event(&mut self, event: &MarketEvent) {
+        let ts = event.ts_receive_ms.map(system_time_ms).unwrap_or_else(unix_ms);
+        self.ts_ms.push(ts as i64);
+        self.venue.push(format!("{:?}", event.venue));
+        self.kind.push(event.kind.name().to_string());
+        self.market_id.push(Some(event.market_id.clone()));
+        self.token_id.push(event.token_id.clone());
+        self.best_bid.push(event.best_bid.map(as_f64));
+        self.best_ask.push(event.best_ask.map(as_f64));
+        self.volume24h.push(event.volume24h);
+        self.last_trade_price.push(event.last_trade_price);
+        self.liquidity.push(event.liquidity);
+        match &event.kind {
+            MarketEventKind::Trade { size, side, .. } => {
+                self.bid_size.push(None);
+                self.ask_size.push(None);
+                self.trade_size.push(Some(*size));
+                self.trade_side.push(Some(side_name(side).to_string()));
+            }
+            // Only the touch is archived, not the depth behind it.
+            _ => {
+                let (bid_size, ask_size) = event.touch_sizes();
+                self.bid_size.push(bid_size);
+                self.ask_size.push(ask_size);
+                self.trade_size.push(None);
+                self.trade_side.push(None);
+            }
+        }
+    }
+
+    fn push_snapshot(&mut self, ts_ms: u64, key: MarketKey, state: &MarketState) {
+        self.ts_ms.push(ts_ms as i64);
+        self.venue.push(format!("{:?}", key.0));
+        self.kind.push("snapshot".to_string());
+        self.market_id.push(None);
+        self.token_id.push(key.1.to_string());
+        self.best_bid.push(state.best_bid.map(as_f64));
+        self.best_ask.push(state.best_ask.map(as_f64));
+        self.volume24h.push(state.volume24h);
+        self.last_trade_price.push(None);
+        self.liquidity.push(None);
+        self.bid_size.push(None);
+        self.ask_size.push(None);
+        self.trade_size.push(None);
+        self.trade_side.push(None);
+    }
+
+    fn take_batch(&mut self, schema: &SchemaRef) -> anyhow::Result<RecordBatch> {
+        let rows = std::mem::take(self);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(rows.ts_ms)),
+            Arc::new(StringArray::from(rows.venue)),
+            Arc::new(StringArray::from(rows.kind)),
+            Arc::new(StringArray::from(rows.market_id)),
+            Arc::new(StringArray::from(rows.token_id)),
+            Arc::new(Float64Array::from(rows.best_bid)),
+            Arc::new(Float64Array::from(rows.best_ask)),
+            Arc::new(Float64Array::from(rows.volume24h)),
+            Arc::new(Float64Array::from(rows.last_trade_price)),
+            Arc::new(Float64Array::from(rows.liquidity)),
+            Arc::new(Float64Array::from(rows.bid_size)),
+            Arc::new(Float64Array::from(rows.ask_size)),
+            Arc::new(Float64Array::from(rows.trade_size)),
+            Arc::new(StringArray::from(rows.trade_side)),
+        ];
+        Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+    }
+}
+
+fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("ts_ms", DataType::Int64, false),
+        Field::new("venue", DataType::Utf8, false),
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("market_id", DataType::Utf8, true),
+        Field::new("token_id", DataType::Utf8, false),
+        Field::new("best_bid", DataType::Float64, true),
+        Field::new("best_ask", DataType::Float64, true),
+        Field::new("volume24h", DataType::Float64, true),
+        Field::new("last_trade_price", DataType::Float64, true),
+        Field::new("liquidity", DataType::Float64, true),
+        Field::new("bid_size", DataType::Float64, true),
+        Field::new("ask_size", DataType::Float64, true),
+        Field::new("trade_size", DataType::Float64, true),
+        Field::new("trade_side", DataType::Utf8, true),
+    ]))
+}
+
+// ── Hourly file writer ───────────────────────────────────────────────────────
+
+/// One Parquet file per stream per hour. Rolls over when the hour changes.
+struct HourlyWriter {
+    stream: &'static str,
+    schema: SchemaRef,
+    rows: Rows,
+    /// Hour, final path, and the writer for its in-progress file.
+    current: Option<(u64, PathBuf, ArrowWriter<File>)>,
+}
+
+impl HourlyWriter {
+    fn new(stream: &'static str, schema: SchemaRef) -> Self {
+        Self { stream, schema, rows: Rows::default(), current: None }
+    }
+
+    /// Write buffered rows to the file of the hour each was stamped in,
+    /// rolling over between hours, then close the file if its hour is over
+    /// by `now_ms`.
+    fn flush(&mut self, config: &ArchiveConfig, now_ms: u64) -> anyhow::Result<()> {
+        if !self.rows.is_empty() {
+            let runs = self.rows.hour_runs();
+            let batch = self.rows.take_batch(&self.schema)?;
+            for (hour, offset, len) in runs {
+                self.write(config, hour, &batch.slice(offset, len))?;
+            }
+        }
+        let hour = now_ms - now_ms % MS_PER_HOUR;
+        if self.current.as_ref().is_some_and(|(h, _, _)| *h < hour) {
+            self.close()?;
+        }
+        Ok(())
+    }
+
+    /// Append `batch` to `hour`'s file, closing the open file first if it
+    /// belongs to another hour.
+    fn write(&mut self, config: &ArchiveConfig, hour: u64, batch: &RecordBatch) -> anyhow::Result<()> {
+        if self.current.as_ref().is_some_and(|(h, _, _)| *h != hour) {
+            self.close()?;
+        }
+        if self.current.is_none() {
+            let path = config.path_for(self.stream, hour);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // A restart inside the same hour must not clobber the earlier file.
+            let path = if path.exists() || in_progress_path(&path).exists() {
+                path.with_extension(format!("{}.parquet", unix_ms()))
+            } else {
+                path
+            };
+            let props = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let file = File::create(in_progress_path(&path))?;
+            let writer = ArrowWriter::try_new(file, Arc::clone(&self.schema), Some(props))?;
+            info!(path = %path.display(), stream = self.stream, "opened archive file");
+            self.current = Some((hour, path, writer));
+        }
+        if let Some((_, _, writer)) = self.current.as_mut() {
+            writer.write(batch)?;
+        }
+        Ok(())
+    }
+
+    /// Write the footer and give the file its final name.
+    fn close(&mut self) -> anyhow::Result<()> {
+        if let Some((_, path, writer)) = self.current.take() {
+            writer.close()?;
+            std::fs::rename(in_progress_path(&path), &path)?;
+        }
+        Ok(())
+    }
+}
+
+// ── Task ─────────────────────────────────────────────────────────────────────
+
+/// Archive every normalized `MarketEvent` received on `rx`, plus a periodic
+/// snapshot of the whole market cache, into hourly Parquet files.
+pub async fn run_archiver(
+    mut rx: Subscriber,
+    cache: MarketCache,
+    config: ArchiveConfig,
+) -> anyhow::Result<()> {
+    let schema = schema();
+    let mut events = HourlyWriter::new("events", Arc::clone(&schema));
+    let mut snapshots = HourlyWriter::new("snapshots", Arc::clone(&schema));
+
+    let mut flush_tick = tokio::time::interval(FLUSH_INTERVAL);
+    let mut snapshot_tick = tokio::time::interval(config.snapshot_interval);
+
+    if let Err(e) = tokio::task::block_in_place(|| recover_in_progress(&config.root)) {
+        warn!(error = %e, "failed to recover archive files left open by an earlier run");
+    }
+    info!(root = %config.root.display(), "market-data archiver started");
+
+    loop {
+        tokio::select! {
+            maybe_event = rx.recv() => {
+                let Some(event) = maybe_event else { break };
+                events.rows.push_event(&event);
+                if events.rows.len() >= FLUSH_ROWS
+                    && let Err(e) = tokio::task::block_in_place(|| events.flush(&config, unix_ms()))
+                {
+                    warn!(error = %e, "failed to write event archive");
+                }
+            }
+            _ = flush_tick.tick() => {
+                if let Err(e) = tokio::task::block_in_place(|| events.flush(&config, unix_ms())) {
+                    warn!(error = %e, "failed to write event archive");
+                }
+            }
+            _ = snapshot_tick.tick() => {
+                let now = unix_ms();
+                for (key, state) in cache.all() {
+                    snapshots.rows.push_snapshot(now, key, &state);
+                }
+                if let Err(e) = tokio::task::block_in_place(|| snapshots.flush(&config, unix_ms())) {
+                    warn!(error = %e, "failed to write snapshot archive");
+                }
+            }
+        }
+    }
+
+    // Channel closed — flush and finalize both files so footers are written.
+    tokio::task::block_in_place(|| -> anyhow::Result<()> {
+        events.flush(&config, unix_ms())?;
+        events.close()?;
+        snapshots.flush(&config, unix_ms())?;
+        snapshots.close()
+    })?;
+    info!("market-data archiver stopped");
+    Ok(())
+}
+
+// ── Backfill ─────────────────────────────────────────────────────────────────
+
+/// Write historical events (e.g. from the history downloader) into the
+/// `events` stream, one file per event hour rather than per wall-clock hour.
+/// Existing files for an hour are left alone; a suffixed sibling is created.
+pub fn write_history(config: &ArchiveConfig, mut events: Vec<MarketEvent>) -> anyhow::Result<usize> {
+    events.sort_by_key(|e| e.ts_receive_ms.map(system_time_ms).unwrap_or(0));
+    let mut writer = HourlyWriter::new("events", schema());
+    let mut current_hour = None;
+
+    for event in &events {
+        let ts = event.ts_receive_ms.map(system_time_ms).unwrap_or(0);
+        let hour = ts - ts % MS_PER_HOUR;
+        if let Some(prev) = current_hour.filter(|prev| *prev != hour) {
+            writer.flush(config, prev)?;
+        }
+        current_hour = Some(hour);
+        writer.rows.push_event(event);
+        if writer.rows.len() >= FLUSH_ROWS {
+            writer.flush(config, hour)?;
+        }
+    }
+    if let Some(hour) = current_hour {
+        writer.flush(config, hour)?;
+    }
+    writer.close()?;
+    Ok(events.len())
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+fn side_name(side: &Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
+
+fn system_time_ms(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// UTC calendar date and hour for a unix-ms timestamp (civil-from-days).
+fn utc_date_hour(ts_ms: u64) -> (String, u64) {
+    let secs = ts_ms / 1_000;
+    let days = (secs / 86_400) as i64;
+    let hour = (secs % 86_400) / 3_600;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (format!("{year:04}-{month:02}-{day:02}"), hour)
+}
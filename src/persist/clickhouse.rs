@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::market_data::bus::Subscriber;
+use crate::state::market::as_f64;
+use crate::state::portfolio::unix_ms;
+use super::records::PersistRecord;
+
+const FLUSH_ROWS: usize = 5_000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_RETRIES: u32 = 5;
+const INITIAL_RETRY_BACKOFF_MS: u64 = 250;
+
+#[derive(Debug, Clone)]
+pub struct ClickHouseConfig {
+    /// HTTP interface, e.g. `http://localhost:8123`.
+    pub url: String,
+    pub database: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TickRow<'a> {
+    ts_ms: u64,
+    venue: String,
+    kind: &'static str,
+    market_id: &'a str,
+    token_id: &'a str,
+    best_bid: Option<f64>,
+    best_ask: Option<f64>,
+    volume24h: Option<f64>,
+    last_trade_price: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct FillRow<'a> {
+    ts_ms: u64,
+    signal_id: u64,
+    strategy: &'a str,
+    venue: String,
+    market_id: &'a str,
+    token_id: &'a str,
+    order_id: &'a str,
+    side: String,
+    price: f64,
+    size: f64,
+}
+
+/// Pending `JSONEachRow` payload for one table.
+struct Batch {
+    table: &'static str,
+    body: Vec<u8>,
+    rows: usize,
+}
+
+impl Batch {
+    fn new(table: &'static str) -> Self {
+        Self { table, body: Vec::new(), rows: 0 }
+    }
+
+    fn push<T: Serialize>(&mut self, row: &T) {
+        if serde_json::to_writer(&mut self.body, row).is_ok() {
+            self.body.push(b'\n');
+            self.rows += 1;
+        }
+    }
+}
+
+struct ClickHouseClient {
+    http: reqwest::Client,
+    config: ClickHouseConfig,
+}
+
+impl ClickHouseClient {
+    async fn query(&self, sql: &str, body: Vec<u8>) -> anyhow::Result<()> {
+        let mut req = self
+            .http
+            .post(&self.config.url)
+            .query(&[("query", sql), ("database", self.config.database.as_str())])
+            .body(body);
+        if let Some(user) = &self.config.user {
+            req = req.header("X-ClickHouse-User", user);
+        }
+        if let Some(password) = &self.config.password {
+            req = req.header("X-ClickHouse-Key", password);
+        }
+
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("ClickHouse returned {status}: {text}");
+        }
+        Ok(())
+    }
+
+    async fn create_tables(&self) -> anyhow::Result<()> {
+        self.query(
+            "CREATE TABLE IF NOT EXISTS ticks (
+                ts_ms UInt64, venue LowCardinality(String), kind LowCardinality(String),
+                market_id String, token_id String,
+                best_bid Nullable(Float64), best_ask Nullable(Float64),
+                volume24h Nullable(Float64), last_trade_price Nullable(Float64)
+            ) ENGINE = MergeTree ORDER BY (token_id, ts_ms)",
+            Vec::new(),
+        )
+        .await?;
+        self.query(
+            "CREATE TABLE IF NOT EXISTS fills (
+                ts_ms UInt64, signal_id UInt64, strategy LowCardinality(String),
+                venue LowCardinality(String), market_id String, token_id String,
+                order_id String, side LowCardinality(String), price Float64, size Float64
+            ) ENGINE = MergeTree ORDER BY (strategy, ts_ms)",
+            Vec::new(),
+        )
+        .await
+    }
+
+    /// Insert a batch, retrying with exponential backoff. Gives up (and drops
+    /// the batch) after `MAX_RETRIES` so a dead server can't grow memory unbounded.
+    async fn insert(&self, batch: &mut Batch) {
+        if batch.rows == 0 {
+            return;
+        }
+        let sql = format!("INSERT INTO {} FORMAT JSONEachRow", batch.table);
+        let body = std::mem::take(&mut batch.body);
+        let rows = std::mem::take(&mut batch.rows);
+
+        let mut backoff_ms = INITIAL_RETRY_BACKOFF_MS;
+        for attempt in 1..=MAX_RETRIES {
+            match self.query(&sql, body.clone()).await {
+                Ok(()) => return,
+                Err(e) if attempt < MAX_RETRIES => {
+                    warn!(table = batch.table, attempt, backoff_ms, error = %e, "ClickHouse insert failed, retrying");
+                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                    backoff_ms *= 2;
+                }
+                Err(e) => {
+                    warn!(table = batch.table, rows, error = %e, "ClickHouse insert failed, batch dropped");
+                }
+            }
+        }
+    }
+}
+
+/// Stream normalized market events and fills into ClickHouse.
+///
+/// Rows are batched per table and flushed every `FLUSH_INTERVAL` or once a
+/// batch reaches `FLUSH_ROWS`, whichever comes first.
+pub async fn run_clickhouse_sink(
+    mut events_rx: Subscriber,
+    mut records_rx: mpsc::Receiver<PersistRecord>,
+    config: ClickHouseConfig,
+) -> anyhow::Result<()> {
+    let client = ClickHouseClient { http: reqwest::Client::new(), config };
+    client.create_tables().await?;
+    info!(url = %client.config.url, "ClickHouse sink started");
+
+    let mut ticks = Batch::new("ticks");
+    let mut fills = Batch::new("fills");
+    let mut flush_tick = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            maybe_event = events_rx.recv() => {
+                let Some(event) = maybe_event else { break };
+                ticks.push(&TickRow {
+                    ts_ms: event.ts_receive_ms
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_millis() as u64)
+                        .unwrap_or_else(unix_ms),
+                    venue: format!("{:?}", event.venue),
+                    kind: event.kind.name(),
+                    market_id: &event.market_id,
+                    token_id: &event.token_id,
+                    best_bid: event.best_bid.map(as_f64),
+                    best_ask: event.best_ask.map(as_f64),
+                    volume24h: event.volume24h,
+                    last_trade_price: event.last_trade_price,
+                });
+                if ticks.rows >= FLUSH_ROWS {
+                    client.insert(&mut ticks).await;
+                }
+            }
+            Some(record) = records_rx.recv() => {
+                if let PersistRecord::Fill(f) = record {
+                    fills.push(&FillRow {
+                        ts_ms: f.ts_ms,
+                        signal_id: f.signal_id,
+                        strategy: f.strategy,
+                        venue: format!("{:?}", f.venue),
+                        market_id: f.market_id.as_str(),
+                        token_id: f.token_id.as_str(),
+                        order_id: &f.order_id,
+                        side: format!("{:?}", f.side),
+                        price: f.price,
+                        size: f.size,
+                    });
+                    if fills.rows >= FLUSH_ROWS {
+                        client.insert(&mut fills).await;
+                    }
+                }
+            }
+            _ = flush_tick.tick() => {
+                client.insert(&mut ticks).await;
+                client.insert(&mut fills).await;
+            }
+        }
+    }
+
+    client.insert(&mut ticks).await;
+    client.insert(&mut fills).await;
+    info!("ClickHouse sink stopped");
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::metrics::prometheus::record_portfolio;
+use crate::risk::RiskManager;
+use crate::state::equity::{EquityCurve, EquitySample};
+use crate::state::portfolio::Portfolio;
+
+const EQUITY_FILE: &str = "equity.jsonl";
+
+/// Sample portfolio equity every `interval`: append it to the in-memory curve
+/// and `equity.jsonl`, publish the portfolio gauges, and feed the current
+/// drawdown to the risk manager.
+pub async fn run_equity_sampler(
+    portfolio: Portfolio,
+    curve: EquityCurve,
+    risk: RiskManager,
+    dir: PathBuf,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(&dir).await?;
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(EQUITY_FILE))
+        .await?;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let snapshot = portfolio.snapshot();
+        let sample = EquitySample {
+            ts_ms: snapshot.taken_at_ms,
+            equity: snapshot.balances.equity,
+            cash: snapshot.balances.cash,
+        };
+        curve.push(sample);
+        record_portfolio(&snapshot);
+        risk.on_drawdown(curve.current_drawdown().fraction);
+
+        let mut line = serde_json::to_vec(&sample)?;
+        line.push(b'\n');
+        if let Err(e) = file.write_all(&line).await {
+            warn!(error = %e, "failed to append equity sample");
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt::Write as _;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::state::market::as_f64;
+use crate::state::market_cache::MarketCache;
+use crate::state::portfolio::Portfolio;
+
+#[derive(Debug, Clone)]
+pub struct InfluxConfig {
+    /// Server base URL, e.g. `http://localhost:8086`.
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    /// API token (InfluxDB 2.x). Omit for unauthenticated servers.
+    pub token: Option<String>,
+    pub interval: Duration,
+}
+
+/// Escape a tag key/value or measurement for line protocol.
+fn escape_tag(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if matches!(c, ',' | ' ' | '=') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Append one line: `measurement,tags fields ts`. Skipped when no field has a value.
+fn push_line(body: &mut String, measurement: &str, tags: &[(&str, &str)], fields: &[(&str, Option<f64>)], ts_ms: u64) {
+    let fields: Vec<String> = fields
+        .iter()
+        .filter_map(|(k, v)| v.filter(|v| v.is_finite()).map(|v| format!("{k}={v}")))
+        .collect();
+    if fields.is_empty() {
+        return;
+    }
+    body.push_str(measurement);
+    for (k, v) in tags {
+        let _ = write!(body, ",{k}={}", escape_tag(v));
+    }
+    let _ = writeln!(body, " {} {ts_ms}", fields.join(","));
+}
+
+/// Render the current market, position, and PnL state as line protocol.
+fn render(cache: &MarketCache, portfolio: &Portfolio) -> String {
+    let snapshot = portfolio.snapshot();
+    let ts = snapshot.taken_at_ms;
+    let mut body = String::new();
+
+    for (key, state) in cache.all() {
+        let venue = format!("{:?}", key.0);
+        let (best_bid, best_ask) = (state.best_bid.map(as_f64), state.best_ask.map(as_f64));
+        let (mid, spread) = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => (Some((bid + ask) / 2.0), Some(ask - bid)),
+            _ => (None, None),
+        };
+        push_line(
+            &mut body,
+            "market",
+            &[("venue", &venue), ("token_id", key.1.as_str())],
+            &[
+                ("mid", mid),
+                ("spread", spread),
+                ("best_bid", best_bid),
+                ("best_ask", best_ask),
+                ("volume24h", state.volume24h),
+            ],
+            ts,
+        );
+    }
+
+    for position in &snapshot.positions {
+        let venue = format!("{:?}", position.venue);
+        push_line(
+            &mut body,
+            "position",
+            &[("venue", &venue), ("token_id", &position.token_id)],
+            &[
+                ("size", Some(position.size)),
+                ("avg_price", Some(position.avg_price)),
+                ("mark_price", position.mark_price),
+                ("realized_pnl", Some(position.realized_pnl)),
+                ("unrealized_pnl", Some(position.unrealized_pnl)),
+                ("exposure", Some(position.exposure)),
+            ],
+            ts,
+        );
+    }
+
+    push_line(
+        &mut body,
+        "pnl",
+        &[],
+        &[
+            ("realized", Some(snapshot.pnl.realized)),
+            ("unrealized", Some(snapshot.pnl.unrealized)),
+            ("taker_fees", Some(snapshot.pnl.taker_fees)),
+            ("maker_rebates", Some(snapshot.pnl.maker_rebates)),
+            ("total", Some(snapshot.pnl.total)),
+            ("cash", Some(snapshot.balances.cash)),
+            ("equity", Some(snapshot.balances.equity)),
+        ],
+        ts,
+    );
+
+    for (strategy, line) in &snapshot.pnl_by_strategy {
+        push_line(
+            &mut body,
+            "strategy_pnl",
+            &[("strategy", strategy)],
+            &[
+                ("volume", Some(line.volume)),
+                ("realized_gross", Some(line.realized_gross)),
+                ("realized_net", Some(line.realized_net)),
+            ],
+            ts,
+        );
+    }
+
+    body
+}
+
+/// Sample market mids/spreads, positions, and PnL every `config.interval`
+/// and write them to InfluxDB's v2 write API. A failed write is logged and
+/// the samples dropped — the next tick carries fresh state anyway.
+pub async fn run_influx_exporter(cache: MarketCache, portfolio: Portfolio, config: InfluxConfig) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let write_url = format!("{}/api/v2/write", config.url.trim_end_matches('/'));
+    let mut ticker = tokio::time::interval(config.interval);
+
+    info!(url = %config.url, bucket = %config.bucket, interval_s = config.interval.as_secs(), "InfluxDB exporter started");
+
+    loop {
+        ticker.tick().await;
+
+        let body = render(&cache, &portfolio);
+        if body.is_empty() {
+            continue;
+        }
+        let lines = body.lines().count();
+
+        let mut request = client
+            .post(&write_url)
+            .query(&[("org", config.org.as_str()), ("bucket", config.bucket.as_str()), ("precision", "ms")])
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(body);
+        if let Some(token) = &config.token {
+            request = request.header("Authorization", format!("Token {token}"));
+        }
+
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => debug!(lines, "InfluxDB write ok"),
+            Err(e) => warn!(error = %e, lines, "InfluxDB write failed"),
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::records::PersistRecord;
+use crate::market_data::types::{Side, Venue};
+use crate::risk::RiskManager;
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
+use crate::state::market::as_f64;
+use crate::state::market_cache::MarketKey;
+use crate::state::portfolio::{unix_ms, OpenOrderLeg, Portfolio};
+
+/// Frames larger than this are treated as corruption rather than allocated.
+const MAX_FRAME_LEN: u32 = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalLeg {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+}
+
+/// One journal entry. Owned so it can be read back at startup.
+/// Only what recovery needs is kept — the database has the full records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// Written by earlier versions; read back so their journals still
+    /// replay, and dropped by compaction.
+    Signal {
+        signal_id: u64,
+        ts_ms: u64,
+        strategy: String,
+        market_id: MarketId,
+        edge: f64,
+    },
+    Intent {
+        signal_id: u64,
+        ts_ms: u64,
+        strategy: String,
+        venue: Venue,
+        market_id: MarketId,
+        legs: Vec<JournalLeg>,
+    },
+    Report {
+        signal_id: u64,
+        ts_ms: u64,
+        fully_filled: bool,
+    },
+    Fill {
+        signal_id: u64,
+        ts_ms: u64,
+        strategy: String,
+        venue: Venue,
+        token_id: TokenId,
+        side: Side,
+        price: f64,
+        size: f64,
+        liquidity: Liquidity,
+    },
+}
+
+impl JournalEntry {
+    /// The journal only keeps records that recovery replays; `None` for the rest.
+    pub fn from_record(record: &PersistRecord) -> Option<Self> {
+        let entry = match record {
+            PersistRecord::Intent(r) => JournalEntry::Intent {
+                signal_id: r.signal_id,
+                ts_ms: r.ts_ms,
+                strategy: r.strategy.to_string(),
+                venue: r.venue.clone(),
+                market_id: r.market_id,
+                legs: r
+                    .legs
+                    .iter()
+                    .map(|leg| JournalLeg {
+                        token_id: leg.token_id,
+                        side: leg.side.clone(),
+                        price: as_f64(leg.price),
+                        size: as_f64(leg.size),
+                    })
+                    .collect(),
+            },
+            PersistRecord::Report(r) => JournalEntry::Report {
+                signal_id: r.signal_id,
+                ts_ms: r.ts_ms,
+                fully_filled: r.fully_filled,
+            },
+            PersistRecord::Fill(r) => JournalEntry::Fill {
+                signal_id: r.signal_id,
+                ts_ms: r.ts_ms,
+                strategy: r.strategy.to_string(),
+                venue: r.venue.clone(),
+                token_id: r.token_id,
+                side: r.side.clone(),
+                price: r.price,
+                size: r.size,
+                liquidity: r.liquidity,
+            },
+            PersistRecord::Signal(_) | PersistRecord::Outcome(_) => return None,
+        };
+        Some(entry)
+    }
+}
+
+// ── Writer ──────────────────────────────────────────────────────────────
+
+/// Append-only journal of pipeline records.
+///
+/// Each frame is `[len: u32 LE][crc32: u32 LE][JSON payload]`. A batch is
+/// fsynced before [`append`](Self::append) returns, so anything acknowledged
+/// survives a crash; a torn final frame is detected by its length or CRC and
+/// dropped on the next startup.
+///
+/// The execution bridge appends intents, reports and fills itself through
+/// [`Recorder::journal`](super::Recorder::journal) and waits for them. It
+/// is the only writer; signals, which recovery doesn't need, are left to
+/// the database.
+#[derive(Clone)]
+pub struct JournalStorage {
+    path: PathBuf,
+    file: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl JournalStorage {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open journal {}", path.display()))?;
+        info!(path = %path.display(), "journal opened");
+        Ok(Self { path: path.to_path_buf(), file: Arc::new(Mutex::new(BufWriter::new(file))) })
+    }
+
+    /// Write the journaled part of `records` and fsync it.
+    pub async fn append<'a>(&self, records: impl IntoIterator<Item = &'a PersistRecord>) -> anyhow::Result<()> {
+        let entries: Vec<JournalEntry> = records.into_iter().filter_map(JournalEntry::from_record).collect();
+        let file = Arc::clone(&self.file);
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = file.lock().unwrap();
+            for entry in &entries {
+                write_frame(&mut *file, entry)?;
+            }
+            file.flush()?;
+            file.get_ref()
+                .sync_data()
+                .with_context(|| format!("failed to sync journal {}", path.display()))
+        })
+        .await?
+    }
+}
+
+// ── Compaction ──────────────────────────────────────────────────────────
+
+impl JournalEntry {
+    fn ts_ms(&self) -> u64 {
+        match self {
+            JournalEntry::Signal { ts_ms, .. }
+            | JournalEntry::Intent { ts_ms, .. }
+            | JournalEntry::Report { ts_ms, .. }
+            | JournalEntry::Fill { ts_ms, .. } => *ts_ms,
+        }
+    }
+}
+
+fn write_frame(out: &mut impl Write, entry: &JournalEntry) -> anyhow::Result<()> {
+    let payload = serde_json::to_vec(entry)?;
+    out.write_all(&(payload.len() as u32).to_le_bytes())?;
+    out.write_all(&crc32fast::hash(&payload).to_le_bytes())?;
+    out.write_all(&payload)?;
+    Ok(())
+}
+
+/// Drop entries older than `max_age` that recovery doesn't need. Fills are
+/// always kept (they are the position and cash history), as are intents
+/// that never got a report. Must run before the journal writer is started;
+/// the file is rewritten to a temp path and renamed over the original.
+pub fn compact(path: &Path, max_age: std::time::Duration) -> anyhow::Result<usize> {
+    let (entries, _) = read_journal(path)?;
+    let cutoff = unix_ms().saturating_sub(max_age.as_millis() as u64);
+
+    let reported: std::collections::HashSet<u64> = entries
+        .iter()
+        .filter_map(|e| match e {
+            JournalEntry::Report { signal_id, .. } => Some(*signal_id),
+            _ => None,
+        })
+        .collect();
+
+    let before = entries.len();
+    let kept: Vec<JournalEntry> = entries
+        .into_iter()
+        .filter(|e| match e {
+            JournalEntry::Fill { .. } => true,
+            JournalEntry::Intent { signal_id, .. } if !reported.contains(signal_id) => true,
+            e => e.ts_ms() >= cutoff,
+        })
+        .collect();
+
+    let removed = before - kept.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+
+    let tmp = path.with_extension("compact.tmp");
+    {
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        for entry in &kept {
+            write_frame(&mut out, entry)?;
+        }
+        out.flush()?;
+        out.get_ref().sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+
+    info!(path = %path.display(), removed, kept = kept.len(), "journal compacted");
+    Ok(removed)
+}
+
+// ── Recovery ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default)]
+pub struct RecoveryStats {
+    pub entries: usize,
+    pub fills: usize,
+    /// Intents with no report — the process died while they were in flight.
+    pub in_flight: usize,
+    /// Bytes discarded from a torn or corrupt tail.
+    pub truncated_bytes: u64,
+}
+
+/// Read every intact frame from the journal. Stops at the first torn or
+/// corrupt frame and truncates the file there so new frames append cleanly.
+pub fn read_journal(path: &Path) -> anyhow::Result<(Vec<JournalEntry>, u64)> {
+    let Some((entries, offset, total_len)) = scan(path)? else { return Ok((Vec::new(), 0)) };
+
+    let truncated = total_len - offset;
+    if truncated > 0 {
+        warn!(
+            path = %path.display(),
+            valid_bytes = offset,
+            discarded_bytes = truncated,
+            "journal tail is torn or corrupt — truncating"
+        );
+        OpenOptions::new().write(true).open(path)?.set_len(offset)?;
+    }
+    Ok((entries, truncated))
+}
+
+/// [`read_journal`] without repairing the file, so it's safe to run against
+/// the journal of a live engine (a half-written tail is just skipped).
+pub fn scan_journal(path: &Path) -> anyhow::Result<Vec<JournalEntry>> {
+    Ok(scan(path)?.map(|(entries, _, _)| entries).unwrap_or_default())
+}
+
+/// Intact entries, the byte offset where they end, and the file length.
+/// `None` when the journal doesn't exist.
+fn scan(path: &Path) -> anyhow::Result<Option<(Vec<JournalEntry>, u64, u64)>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to open journal {}", path.display())),
+    };
+    let total_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut entries = Vec::new();
+    let mut offset: u64 = 0;
+    let mut header = [0u8; 8];
+
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        let len = u32::from_le_bytes(header[0..4].try_into().unwrap());
+        let crc = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if len > MAX_FRAME_LEN {
+            break;
+        }
+
+        let mut payload = vec![0u8; len as usize];
+        if reader.read_exact(&mut payload).is_err() || crc32fast::hash(&payload) != crc {
+            break;
+        }
+        let Ok(entry) = serde_json::from_slice::<JournalEntry>(&payload) else { break };
+
+        entries.push(entry);
+        offset += 8 + len as u64;
+    }
+    Ok(Some((entries, offset, total_len)))
+}
+
+/// Rebuild state from the journal before the pipeline starts: replay fills
+/// into the portfolio (positions, cash, fees) and the risk manager's daily
+/// notional counter (and, for strategies in `wallet_risk`, their wallet's),
+/// and re-register intents that never got a report as open orders so their
+/// possible exposure stays visible.
+pub fn recover(
+    path: &Path,
+    portfolio: &Portfolio,
+    risk: &RiskManager,
+    wallet_risk: &HashMap<String, RiskManager>,
+) -> anyhow::Result<RecoveryStats> {
+    let (entries, truncated_bytes) = read_journal(path)?;
+    let mut stats = RecoveryStats { entries: entries.len(), truncated_bytes, ..Default::default() };
+
+    let reported: std::collections::HashSet<u64> = entries
+        .iter()
+        .filter_map(|e| match e {
+            JournalEntry::Report { signal_id, .. } => Some(*signal_id),
+            _ => None,
+        })
+        .collect();
+
+    for entry in entries {
+        match entry {
+            JournalEntry::Fill { ts_ms, strategy, venue, token_id, side, price, size, liquidity, .. } => {
+                portfolio.apply_fill_at(MarketKey(venue, token_id), &strategy, liquidity, &side, price, size, ts_ms);
+                risk.record_fill_notional(price * size, ts_ms);
+                if let Some(wallet) = wallet_risk.get(&strategy) {
+                    wallet.record_fill_notional(price * size, ts_ms);
+                }
+                stats.fills += 1;
+            }
+            JournalEntry::Intent { signal_id, strategy, venue, market_id, legs, .. }
+                if !reported.contains(&signal_id) =>
+            {
+                warn!(
+                    signal_id,
+                    strategy = %strategy,
+                    market_id = %market_id,
+                    "intent has no report in journal — treating as in flight"
+                );
+                portfolio.register_open_order(
+                    venue,
+                    market_id,
+                    &strategy,
+                    legs.into_iter()
+                        .map(|leg| OpenOrderLeg {
+                            token_id: leg.token_id,
+                            side: leg.side,
+                            price: leg.price,
+                            size: leg.size,
+                        })
+                        .collect(),
+                );
+                stats.in_flight += 1;
+            }
+            _ => {}
+        }
+    }
+
+    info!(
+        entries = stats.entries,
+        fills = stats.fills,
+        in_flight = stats.in_flight,
+        truncated_bytes = stats.truncated_bytes,
+        "journal recovery complete"
+    );
+    Ok(stats)
+}
@@ -0,0 +1,129 @@
+//! Versioned schema migrations for the SQL backends, embedded in the binary
+//! and applied at startup. Applied versions are tracked in `schema_migrations`.
+//!
+//! To change the schema, add a new numbered file for each backend and append
+//! it to the lists below — never edit a migration that has shipped.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tokio_postgres::Client;
+use tracing::info;
+
+use crate::state::portfolio::unix_ms;
+
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+pub const SQLITE: &[Migration] = &[
+    Migration { version: 1, name: "initial", sql: include_str!("sqlite/0001_initial.sql") },
+    Migration { version: 2, name: "signal_outcomes", sql: include_str!("sqlite/0002_signal_outcomes.sql") },
+];
+
+pub const POSTGRES: &[Migration] = &[
+    Migration { version: 1, name: "initial", sql: include_str!("postgres/0001_initial.sql") },
+    Migration { version: 2, name: "signal_outcomes", sql: include_str!("postgres/0002_signal_outcomes.sql") },
+];
+
+const CREATE_MIGRATIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version        BIGINT PRIMARY KEY,
+    name           TEXT NOT NULL,
+    applied_at_ms  BIGINT NOT NULL
+)";
+
+/// Migrations in `all` not yet in `applied`. Errors if the database has a
+/// version this build doesn't know — it was migrated by a newer binary.
+fn pending<'a>(all: &'a [Migration], applied: &[i64]) -> anyhow::Result<Vec<&'a Migration>> {
+    let latest = all.last().map_or(0, |m| m.version);
+    if let Some(unknown) = applied.iter().find(|v| **v > latest) {
+        anyhow::bail!("database schema is at version {unknown}, newer than this build supports ({latest})");
+    }
+    Ok(all.iter().filter(|m| !applied.contains(&m.version)).collect())
+}
+
+// ── SQLite ──────────────────────────────────────────────────────────────
+
+fn sqlite_applied(conn: &Connection) -> anyhow::Result<Vec<i64>> {
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if exists.is_none() {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare("SELECT version FROM schema_migrations ORDER BY version")?;
+    let versions = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<i64>, _>>()?;
+    Ok(versions)
+}
+
+/// Pending migrations, without changing the database.
+pub fn sqlite_pending(conn: &Connection) -> anyhow::Result<Vec<&'static Migration>> {
+    pending(SQLITE, &sqlite_applied(conn)?)
+}
+
+/// Apply every pending migration, each in its own transaction.
+pub fn sqlite_migrate(conn: &mut Connection) -> anyhow::Result<usize> {
+    let todo = sqlite_pending(conn)?;
+    conn.execute_batch(CREATE_MIGRATIONS_TABLE)?;
+    for migration in &todo {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at_ms) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, unix_ms() as i64],
+        )?;
+        tx.commit()?;
+        info!(backend = "sqlite", version = migration.version, name = migration.name, "migration applied");
+    }
+    Ok(todo.len())
+}
+
+// ── PostgreSQL ──────────────────────────────────────────────────────────
+
+async fn postgres_applied(client: &Client) -> anyhow::Result<Vec<i64>> {
+    let exists = client
+        .query_opt("SELECT 1 FROM information_schema.tables WHERE table_name = 'schema_migrations'", &[])
+        .await?;
+    if exists.is_none() {
+        return Ok(Vec::new());
+    }
+    let rows = client.query("SELECT version FROM schema_migrations ORDER BY version", &[]).await?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+pub async fn postgres_pending(client: &Client) -> anyhow::Result<Vec<&'static Migration>> {
+    pending(POSTGRES, &postgres_applied(client).await?)
+}
+
+/// Apply every pending migration, each in its own transaction. An advisory
+/// lock serializes instances that share the database and start together.
+pub async fn postgres_migrate(client: &mut Client) -> anyhow::Result<usize> {
+    const LOCK_KEY: i64 = 0x5045_4d49_4752; // "PEMIGR"
+
+    client.execute("SELECT pg_advisory_lock($1)", &[&LOCK_KEY]).await?;
+    let result = async {
+        client.batch_execute(CREATE_MIGRATIONS_TABLE).await?;
+        let todo = postgres_pending(client).await?;
+        for migration in &todo {
+            let tx = client.transaction().await?;
+            tx.batch_execute(migration.sql).await?;
+            tx.execute(
+                "INSERT INTO schema_migrations (version, name, applied_at_ms) VALUES ($1, $2, $3)",
+                &[&migration.version, &migration.name, &(unix_ms() as i64)],
+            )
+            .await?;
+            tx.commit().await?;
+            info!(backend = "postgres", version = migration.version, name = migration.name, "migration applied");
+        }
+        Ok::<_, anyhow::Error>(todo.len())
+    }
+    .await;
+    client.execute("SELECT pg_advisory_unlock($1)", &[&LOCK_KEY]).await?;
+    result
+}
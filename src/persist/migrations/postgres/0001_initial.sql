@@ -0,0 +1,56 @@
+CREATE TABLE IF NOT EXISTS signals (
+    id          BIGSERIAL PRIMARY KEY,
+    signal_id   BIGINT NOT NULL,
+    ts_ms       BIGINT NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    edge        DOUBLE PRECISION NOT NULL,
+    liquidity   TEXT NOT NULL,
+    legs        TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signals_ts ON signals (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_signals_strategy_market ON signals (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS intents (
+    id          BIGSERIAL PRIMARY KEY,
+    signal_id   BIGINT NOT NULL,
+    ts_ms       BIGINT NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    edge        DOUBLE PRECISION NOT NULL,
+    neg_risk    BOOLEAN NOT NULL,
+    legs        TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_intents_ts ON intents (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_intents_strategy_market ON intents (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS reports (
+    id            BIGSERIAL PRIMARY KEY,
+    signal_id     BIGINT NOT NULL,
+    ts_ms         BIGINT NOT NULL,
+    strategy      TEXT NOT NULL,
+    market_id     TEXT NOT NULL,
+    fully_filled  BOOLEAN NOT NULL,
+    leg_results   TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_reports_ts ON reports (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_reports_strategy_market ON reports (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS fills (
+    id          BIGSERIAL PRIMARY KEY,
+    signal_id   BIGINT NOT NULL,
+    ts_ms       BIGINT NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    token_id    TEXT NOT NULL,
+    order_id    TEXT NOT NULL,
+    side        TEXT NOT NULL,
+    price       DOUBLE PRECISION NOT NULL,
+    size        DOUBLE PRECISION NOT NULL,
+    liquidity   TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_fills_ts ON fills (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_fills_strategy_market ON fills (strategy, market_id);
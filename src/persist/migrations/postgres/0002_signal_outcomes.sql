@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS signal_outcomes (
+    id           BIGSERIAL PRIMARY KEY,
+    signal_id    BIGINT NOT NULL,
+    ts_ms        BIGINT NOT NULL,
+    strategy     TEXT NOT NULL,
+    market_id    TEXT NOT NULL,
+    edge         DOUBLE PRECISION NOT NULL,
+    risk         TEXT NOT NULL,
+    outcome      TEXT NOT NULL,
+    filled_legs  INTEGER NOT NULL,
+    total_legs   INTEGER NOT NULL,
+    elapsed_us   BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signal_outcomes_signal ON signal_outcomes (signal_id);
+CREATE INDEX IF NOT EXISTS idx_signal_outcomes_strategy_outcome ON signal_outcomes (strategy, outcome);
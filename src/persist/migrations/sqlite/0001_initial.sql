@@ -0,0 +1,56 @@
+CREATE TABLE IF NOT EXISTS signals (
+    id          INTEGER PRIMARY KEY,
+    signal_id   INTEGER NOT NULL,
+    ts_ms       INTEGER NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    edge        REAL NOT NULL,
+    liquidity   TEXT NOT NULL,
+    legs        TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signals_ts ON signals (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_signals_strategy_market ON signals (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS intents (
+    id          INTEGER PRIMARY KEY,
+    signal_id   INTEGER NOT NULL,
+    ts_ms       INTEGER NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    edge        REAL NOT NULL,
+    neg_risk    INTEGER NOT NULL,
+    legs        TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_intents_ts ON intents (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_intents_strategy_market ON intents (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS reports (
+    id            INTEGER PRIMARY KEY,
+    signal_id     INTEGER NOT NULL,
+    ts_ms         INTEGER NOT NULL,
+    strategy      TEXT NOT NULL,
+    market_id     TEXT NOT NULL,
+    fully_filled  INTEGER NOT NULL,
+    leg_results   TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_reports_ts ON reports (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_reports_strategy_market ON reports (strategy, market_id);
+
+CREATE TABLE IF NOT EXISTS fills (
+    id          INTEGER PRIMARY KEY,
+    signal_id   INTEGER NOT NULL,
+    ts_ms       INTEGER NOT NULL,
+    strategy    TEXT NOT NULL,
+    venue       TEXT NOT NULL,
+    market_id   TEXT NOT NULL,
+    token_id    TEXT NOT NULL,
+    order_id    TEXT NOT NULL,
+    side        TEXT NOT NULL,
+    price       REAL NOT NULL,
+    size        REAL NOT NULL,
+    liquidity   TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_fills_ts ON fills (ts_ms);
+CREATE INDEX IF NOT EXISTS idx_fills_strategy_market ON fills (strategy, market_id);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS signal_outcomes (
+    id           INTEGER PRIMARY KEY,
+    signal_id    INTEGER NOT NULL,
+    ts_ms        INTEGER NOT NULL,
+    strategy     TEXT NOT NULL,
+    market_id    TEXT NOT NULL,
+    edge         REAL NOT NULL,
+    risk         TEXT NOT NULL,
+    outcome      TEXT NOT NULL,
+    filled_legs  INTEGER NOT NULL,
+    total_legs   INTEGER NOT NULL,
+    elapsed_us   INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signal_outcomes_signal ON signal_outcomes (signal_id);
+CREATE INDEX IF NOT EXISTS idx_signal_outcomes_strategy_outcome ON signal_outcomes (strategy, outcome);
@@ -0,0 +1,95 @@
+pub mod archive;
+pub mod clickhouse;
+pub mod equity;
+pub mod influx;
+pub mod journal;
+pub mod migrations;
+pub mod postgres;
+pub mod records;
+pub mod retention;
+pub mod snapshot;
+pub mod sqlite;
+pub mod storage;
+pub mod upload;
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::metrics::channels::record_channel_drop;
+use crate::metrics::prometheus::record_persist_drop;
+
+pub use records::PersistRecord;
+pub use storage::{run_storage_writer, Storage};
+
+use journal::JournalStorage;
+
+/// Handle used by the pipeline to hand records to the persistence sinks
+/// (database writer, ClickHouse, ...). Every sink gets a copy of every record.
+///
+/// Sends are non-blocking: if a sink falls behind, its copy is dropped
+/// (and logged) rather than stalling execution. With no sinks it is a no-op.
+/// Records recovery depends on go through [`journal`](Self::journal)
+/// instead, which waits until they are on disk.
+#[derive(Clone, Default)]
+pub struct Recorder {
+    sinks: Vec<(&'static str, mpsc::Sender<PersistRecord>)>,
+    journal: Option<JournalStorage>,
+}
+
+impl std::fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Recorder")
+            .field("sinks", &self.sinks.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("journal", &self.journal.is_some())
+            .finish()
+    }
+}
+
+fn record_drop(sink: &'static str) {
+    record_persist_drop();
+    record_channel_drop(sink);
+    warn!(sink, "persistence channel full or closed, record dropped");
+}
+
+impl Recorder {
+    /// `sinks` are keyed by name for the per-channel drop counter.
+    pub fn new(sinks: Vec<(&'static str, mpsc::Sender<PersistRecord>)>) -> Self {
+        Self { sinks, journal: None }
+    }
+
+    /// Append records passed to [`journal`](Self::journal) to `journal`.
+    pub fn with_journal(mut self, journal: JournalStorage) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, record: PersistRecord) {
+        let Some((last, rest)) = self.sinks.split_last() else { return };
+        for (name, tx) in rest {
+            if tx.try_send(record.clone()).is_err() {
+                record_drop(name);
+            }
+        }
+        if last.1.try_send(record).is_err() {
+            record_drop(last.0);
+        }
+    }
+
+    /// Append `records` to the journal and wait for the fsync, then hand
+    /// them to the sinks as [`record`](Self::record) does. The sinks get
+    /// them even if the append fails. Without a journal this is `record`.
+    pub async fn journal(&self, records: Vec<PersistRecord>) -> anyhow::Result<()> {
+        let appended = match &self.journal {
+            Some(journal) => journal.append(&records).await,
+            None => Ok(()),
+        };
+        for record in records {
+            self.record(record);
+        }
+        appended
+    }
+}
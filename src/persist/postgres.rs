@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio_postgres::{Client, NoTls, Transaction};
+use tracing::{info, warn};
+
+use super::migrations;
+use super::records::PersistRecord;
+use super::sqlite::TABLES;
+use super::storage::Storage;
+
+/// Shared PostgreSQL backend, for multi-instance deployments writing to one database.
+/// Uses the same table layout as the SQLite backend.
+pub struct PostgresStorage {
+    client: Mutex<Client>,
+}
+
+impl PostgresStorage {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let mut client = connect_client(url).await?;
+        migrations::postgres_migrate(&mut client).await?;
+        info!("PostgreSQL persistence connected");
+        Ok(Self { client: Mutex::new(client) })
+    }
+}
+
+async fn connect_client(url: &str) -> anyhow::Result<Client> {
+    let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            warn!(error = %e, "PostgreSQL connection closed");
+        }
+    });
+    Ok(client)
+}
+
+/// Migrations the database at `url` still needs, without applying them.
+pub async fn pending_migrations(url: &str) -> anyhow::Result<Vec<&'static migrations::Migration>> {
+    let client = connect_client(url).await?;
+    migrations::postgres_pending(&client).await
+}
+
+#[async_trait]
+impl Storage for PostgresStorage {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn write_batch(&self, batch: Vec<PersistRecord>) -> anyhow::Result<()> {
+        let mut client = self.client.lock().await;
+        let tx = client.transaction().await?;
+        for record in &batch {
+            insert(&tx, record).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn prune(&self, before_ms: u64) -> anyhow::Result<u64> {
+        let client = self.client.lock().await;
+        let mut removed = 0;
+        for table in TABLES {
+            removed += client
+                .execute(&format!("DELETE FROM {table} WHERE ts_ms < $1"), &[&(before_ms as i64)])
+                .await?;
+        }
+        Ok(removed)
+    }
+}
+
+async fn insert(tx: &Transaction<'_>, record: &PersistRecord) -> anyhow::Result<()> {
+    match record {
+        PersistRecord::Signal(r) => {
+            tx.execute(
+                "INSERT INTO signals (signal_id, ts_ms, strategy, venue, market_id, edge, liquidity, legs)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &(r.signal_id as i64),
+                    &(r.ts_ms as i64),
+                    &r.strategy,
+                    &format!("{:?}", r.venue),
+                    &r.market_id.as_str(),
+                    &r.edge,
+                    &format!("{:?}", r.liquidity),
+                    &serde_json::to_string(&r.legs)?,
+                ],
+            )
+            .await?;
+        }
+        PersistRecord::Intent(r) => {
+            tx.execute(
+                "INSERT INTO intents (signal_id, ts_ms, strategy, venue, market_id, edge, neg_risk, legs)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &(r.signal_id as i64),
+                    &(r.ts_ms as i64),
+                    &r.strategy,
+                    &format!("{:?}", r.venue),
+                    &r.market_id.as_str(),
+                    &r.edge,
+                    &r.neg_risk,
+                    &serde_json::to_string(&r.legs)?,
+                ],
+            )
+            .await?;
+        }
+        PersistRecord::Report(r) => {
+            tx.execute(
+                "INSERT INTO reports (signal_id, ts_ms, strategy, market_id, fully_filled, leg_results)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &(r.signal_id as i64),
+                    &(r.ts_ms as i64),
+                    &r.strategy,
+                    &r.market_id.as_str(),
+                    &r.fully_filled,
+                    &serde_json::to_string(&r.leg_results)?,
+                ],
+            )
+            .await?;
+        }
+        PersistRecord::Fill(r) => {
+            tx.execute(
+                "INSERT INTO fills (signal_id, ts_ms, strategy, venue, market_id, token_id, order_id, side, price, size, liquidity)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+                &[
+                    &(r.signal_id as i64),
+                    &(r.ts_ms as i64),
+                    &r.strategy,
+                    &format!("{:?}", r.venue),
+                    &r.market_id.as_str(),
+                    &r.token_id.as_str(),
+                    &r.order_id,
+                    &format!("{:?}", r.side),
+                    &r.price,
+                    &r.size,
+                    &format!("{:?}", r.liquidity),
+                ],
+            )
+            .await?;
+        }
+        PersistRecord::Outcome(r) => {
+            tx.execute(
+                "INSERT INTO signal_outcomes (signal_id, ts_ms, strategy, market_id, edge, risk, outcome, filled_legs, total_legs, elapsed_us)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                &[
+                    &(r.signal_id as i64),
+                    &(r.ts_ms as i64),
+                    &r.strategy,
+                    &r.market_id.as_str(),
+                    &r.edge,
+                    &r.risk.name(),
+                    &r.outcome.name(),
+                    &(r.filled_legs as i32),
+                    &(r.total_legs as i32),
+                    &(r.elapsed_us as i64),
+                ],
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use serde::Serialize;
+
+use crate::execution::traits::{LegFillStatus, OrderLeg};
+use crate::market_data::types::{Side, Venue};
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
+use crate::strategy::traits::SignalLeg;
+
+/// A signal as received by the execution bridge.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalRecord {
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: MarketId,
+    pub edge: f64,
+    pub liquidity: Liquidity,
+    pub legs: Vec<SignalLeg>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntentRecord {
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: MarketId,
+    pub edge: f64,
+    pub neg_risk: bool,
+    pub legs: Vec<OrderLeg>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRecord {
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub market_id: MarketId,
+    pub fully_filled: bool,
+    pub leg_results: Vec<LegFillStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FillRecord {
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: MarketId,
+    pub token_id: TokenId,
+    pub order_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+    pub liquidity: Liquidity,
+}
+
+/// Result of the pre-trade risk check for a signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskDecision {
+    Approved,
+    /// Drawdown breaker tripped.
+    Halted,
+    /// Would exceed the daily notional cap.
+    DailyNotional,
+    /// A `venue.*` or `execution.*` feature flag keeps this market dark.
+    FlagOff,
+    /// A leg's token is on a feed the feed watchdog has marked stale.
+    FeedStale,
+    /// Dropped before reaching the risk check (expired / deduped).
+    NotChecked,
+}
+
+/// Where a signal ended up — one per signal, so the funnel from detection
+/// to fill can be counted without joining every table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalOutcome {
+    /// Every leg filled.
+    Filled,
+    /// Some legs filled, others did not.
+    PartiallyFilled,
+    /// Sent to the executor, nothing filled.
+    Rejected,
+    /// Blocked by the risk check.
+    Blocked,
+    /// Older than the signal TTL by the time the bridge saw it.
+    Expired,
+    /// Identical to a signal executed moments earlier.
+    Deduped,
+    /// Overtaken by a newer priority signal for the same strategy and market.
+    Superseded,
+    /// Withdrawn by its submitter before it reached the executor.
+    Canceled,
+}
+
+impl RiskDecision {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RiskDecision::Approved => "approved",
+            RiskDecision::Halted => "halted",
+            RiskDecision::DailyNotional => "daily_notional",
+            RiskDecision::FlagOff => "flag_off",
+            RiskDecision::FeedStale => "feed_stale",
+            RiskDecision::NotChecked => "not_checked",
+        }
+    }
+}
+
+impl SignalOutcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignalOutcome::Filled => "filled",
+            SignalOutcome::PartiallyFilled => "partially_filled",
+            SignalOutcome::Rejected => "rejected",
+            SignalOutcome::Blocked => "blocked",
+            SignalOutcome::Expired => "expired",
+            SignalOutcome::Deduped => "deduped",
+            SignalOutcome::Superseded => "superseded",
+            SignalOutcome::Canceled => "canceled",
+        }
+    }
+}
+
+/// Final disposition of a signal, written once the bridge is done with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutcomeRecord {
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub market_id: MarketId,
+    pub edge: f64,
+    pub risk: RiskDecision,
+    pub outcome: SignalOutcome,
+    pub filled_legs: usize,
+    pub total_legs: usize,
+    /// Signal generation → outcome.
+    pub elapsed_us: u64,
+}
+
+/// Everything the persistence layer knows how to store.
+#[derive(Debug, Clone)]
+pub enum PersistRecord {
+    Signal(SignalRecord),
+    Intent(IntentRecord),
+    Report(ReportRecord),
+    Fill(FillRecord),
+    Outcome(OutcomeRecord),
+}
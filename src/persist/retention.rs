@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn};
+
+use super::archive::is_in_progress;
+
+/// The archiver's open file is at most an hour old (plus the time until the
+/// flush that rolls it over), so an in-progress file untouched for longer
+/// than this was left behind by a crash and is pruned like any other.
+const ABANDONED_AFTER: Duration = Duration::from_secs(75 * 60);
+
+/// Limits for one directory of recorded data. `None` = unbounded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionPolicy {
+    pub max_age: Option<Duration>,
+    pub max_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_unbounded(&self) -> bool {
+        self.max_age.is_none() && self.max_bytes.is_none()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PruneStats {
+    pub files_removed: usize,
+    pub bytes_removed: u64,
+    pub bytes_kept: u64,
+}
+
+struct FileEntry {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+}
+
+fn collect_files(dir: &Path, now: SystemTime, out: &mut Vec<FileEntry>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            collect_files(&entry.path(), now, out)?;
+        } else if meta.is_file() {
+            let modified = meta.modified()?;
+            let abandoned = now.duration_since(modified).is_ok_and(|age| age > ABANDONED_AFTER);
+            if !is_in_progress(&entry.path()) || abandoned {
+                out.push(FileEntry { path: entry.path(), len: meta.len(), modified });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove directories left empty by pruning (never `root` itself).
+fn remove_empty_dirs(dir: &Path, root: &Path) -> std::io::Result<bool> {
+    let mut empty = true;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if !remove_empty_dirs(&entry.path(), root)? {
+                empty = false;
+            }
+        } else {
+            empty = false;
+        }
+    }
+    if empty && dir != root {
+        std::fs::remove_dir(dir)?;
+    }
+    Ok(empty)
+}
+
+/// Apply `policy` to every file under `root`: delete files older than
+/// `max_age`, then delete oldest-first until the total is under `max_bytes`.
+/// Files the archiver is still writing are neither counted nor deleted;
+/// in-progress files abandoned by a crash are.
+pub fn prune_dir(root: &Path, policy: RetentionPolicy, now: SystemTime) -> std::io::Result<PruneStats> {
+    let mut files = Vec::new();
+    if root.exists() {
+        collect_files(root, now, &mut files)?;
+    }
+    files.sort_by_key(|f| f.modified);
+
+    let age = |f: &FileEntry| now.duration_since(f.modified).unwrap_or_default();
+    let mut total: u64 = files.iter().map(|f| f.len).sum();
+    let mut stats = PruneStats::default();
+
+    for file in &files {
+        let expired = policy.max_age.is_some_and(|max| age(file) > max);
+        let over_size = policy.max_bytes.is_some_and(|max| total > max);
+        if !expired && !over_size {
+            continue;
+        }
+        match std::fs::remove_file(&file.path) {
+            Ok(()) => {
+                debug!(path = %file.path.display(), bytes = file.len, "pruned");
+                total -= file.len;
+                stats.files_removed += 1;
+                stats.bytes_removed += file.len;
+            }
+            Err(e) => warn!(path = %file.path.display(), error = %e, "failed to prune file"),
+        }
+    }
+    stats.bytes_kept = total;
+
+    if stats.files_removed > 0 {
+        remove_empty_dirs(root, root)?;
+    }
+    Ok(stats)
+}
+
+/// Periodically prune each `(directory, policy)` target.
+pub async fn run_retention(targets: Vec<(PathBuf, RetentionPolicy)>, interval: Duration) -> anyhow::Result<()> {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        for (root, policy) in &targets {
+            let (dir, policy) = (root.clone(), *policy);
+            let result = tokio::task::spawn_blocking(move || prune_dir(&dir, policy, SystemTime::now())).await?;
+            match result {
+                Ok(stats) if stats.files_removed > 0 => info!(
+                    dir = %root.display(),
+                    files = stats.files_removed,
+                    bytes_removed = stats.bytes_removed,
+                    bytes_kept = stats.bytes_kept,
+                    "retention pruned files"
+                ),
+                Ok(_) => {}
+                Err(e) => warn!(dir = %root.display(), error = %e, "retention pass failed"),
+            }
+        }
+    }
+}
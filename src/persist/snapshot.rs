@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+use crate::state::portfolio::Portfolio;
+
+const LATEST_FILE: &str = "portfolio-latest.json";
+const HISTORY_FILE: &str = "portfolio-snapshots.jsonl";
+const LEDGER_FILE: &str = "ledger.csv";
+
+/// Periodically write portfolio snapshots to `dir`.
+///
+/// - `portfolio-latest.json` is replaced atomically (write to temp, then rename)
+///   so readers never see a half-written file.
+/// - `portfolio-snapshots.jsonl` gets one line appended per snapshot.
+/// - `ledger.csv` is rewritten with every closed lot (per-lot cost basis detail).
+///
+/// Write failures are logged and retried on the next tick rather than
+/// stopping the task — losing one snapshot is better than losing all of them.
+pub async fn run_snapshot_writer(
+    portfolio: Portfolio,
+    dir: PathBuf,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(&dir).await?;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+        if let Err(e) = write_snapshot_files(&portfolio, &dir).await {
+            warn!(error = %e, dir = %dir.display(), "failed to write portfolio snapshot");
+        }
+    }
+}
+
+/// Write one snapshot and the ledger now, e.g. on shutdown so the files
+/// reflect the final state rather than the last tick.
+pub async fn write_snapshot_files(portfolio: &Portfolio, dir: &Path) -> anyhow::Result<()> {
+    let snapshot = portfolio.snapshot();
+    let json = serde_json::to_string(&snapshot)?;
+    write_snapshot(dir, &json).await?;
+    debug!(positions = snapshot.positions.len(), "portfolio snapshot written");
+
+    let ledger = portfolio.positions().ledger().clone();
+    let ledger_path = dir.join(LEDGER_FILE);
+    match tokio::task::spawn_blocking(move || ledger.export_csv(&ledger_path)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!(error = %e, "failed to export ledger"),
+        Err(e) => warn!(error = %e, "ledger export task panicked"),
+    }
+    Ok(())
+}
+
+async fn write_snapshot(dir: &Path, json: &str) -> std::io::Result<()> {
+    let tmp = dir.join(format!("{LATEST_FILE}.tmp"));
+    tokio::fs::write(&tmp, json).await?;
+    tokio::fs::rename(&tmp, dir.join(LATEST_FILE)).await?;
+
+    let mut history = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(HISTORY_FILE))
+        .await?;
+    history.write_all(json.as_bytes()).await?;
+    history.write_all(b"\n").await?;
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+use super::migrations;
+use super::records::PersistRecord;
+use super::storage::Storage;
+
+/// Every table, all keyed by `ts_ms` for retention.
+pub(crate) const TABLES: [&str; 5] = ["signals", "intents", "reports", "fills", "signal_outcomes"];
+
+/// Open (or create) the database and apply pending migrations.
+pub fn open(path: &Path) -> anyhow::Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    migrations::sqlite_migrate(&mut conn)?;
+    Ok(conn)
+}
+
+/// Migrations the database at `path` still needs, without applying them.
+pub fn pending_migrations(path: &Path) -> anyhow::Result<Vec<&'static migrations::Migration>> {
+    let conn = Connection::open(path)?;
+    migrations::sqlite_pending(&conn)
+}
+
+/// Single-file SQLite backend. Writes run on the blocking pool since
+/// rusqlite is synchronous.
+pub struct SqliteStorage {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = open(path)?;
+        info!(path = %path.display(), "SQLite persistence opened");
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn write_batch(&self, batch: Vec<PersistRecord>) -> anyhow::Result<()> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || write_batch(&mut conn.lock().unwrap(), &batch)).await?
+    }
+
+    async fn prune(&self, before_ms: u64) -> anyhow::Result<u64> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || -> anyhow::Result<u64> {
+            let conn = conn.lock().unwrap();
+            let mut removed = 0;
+            for table in TABLES {
+                removed += conn.execute(&format!("DELETE FROM {table} WHERE ts_ms < ?1"), params![before_ms as i64])?;
+            }
+            Ok(removed as u64)
+        })
+        .await?
+    }
+}
+
+fn write_batch(conn: &mut Connection, batch: &[PersistRecord]) -> anyhow::Result<()> {
+    let tx = conn.transaction()?;
+    for record in batch {
+        insert(&tx, record)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn insert(conn: &Connection, record: &PersistRecord) -> anyhow::Result<()> {
+    match record {
+        PersistRecord::Signal(r) => {
+            conn.prepare_cached(
+                "INSERT INTO signals (signal_id, ts_ms, strategy, venue, market_id, edge, liquidity, legs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?
+            .execute(params![
+                r.signal_id as i64,
+                r.ts_ms as i64,
+                r.strategy,
+                format!("{:?}", r.venue),
+                r.market_id.as_str(),
+                r.edge,
+                format!("{:?}", r.liquidity),
+                serde_json::to_string(&r.legs)?,
+            ])?;
+        }
+        PersistRecord::Intent(r) => {
+            conn.prepare_cached(
+                "INSERT INTO intents (signal_id, ts_ms, strategy, venue, market_id, edge, neg_risk, legs)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?
+            .execute(params![
+                r.signal_id as i64,
+                r.ts_ms as i64,
+                r.strategy,
+                format!("{:?}", r.venue),
+                r.market_id.as_str(),
+                r.edge,
+                r.neg_risk,
+                serde_json::to_string(&r.legs)?,
+            ])?;
+        }
+        PersistRecord::Report(r) => {
+            conn.prepare_cached(
+                "INSERT INTO reports (signal_id, ts_ms, strategy, market_id, fully_filled, leg_results)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?
+            .execute(params![
+                r.signal_id as i64,
+                r.ts_ms as i64,
+                r.strategy,
+                r.market_id.as_str(),
+                r.fully_filled,
+                serde_json::to_string(&r.leg_results)?,
+            ])?;
+        }
+        PersistRecord::Fill(r) => {
+            conn.prepare_cached(
+                "INSERT INTO fills (signal_id, ts_ms, strategy, venue, market_id, token_id, order_id, side, price, size, liquidity)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?
+            .execute(params![
+                r.signal_id as i64,
+                r.ts_ms as i64,
+                r.strategy,
+                format!("{:?}", r.venue),
+                r.market_id.as_str(),
+                r.token_id.as_str(),
+                r.order_id,
+                format!("{:?}", r.side),
+                r.price,
+                r.size,
+                format!("{:?}", r.liquidity),
+            ])?;
+        }
+        PersistRecord::Outcome(r) => {
+            conn.prepare_cached(
+                "INSERT INTO signal_outcomes (signal_id, ts_ms, strategy, market_id, edge, risk, outcome, filled_legs, total_legs, elapsed_us)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?
+            .execute(params![
+                r.signal_id as i64,
+                r.ts_ms as i64,
+                r.strategy,
+                r.market_id.as_str(),
+                r.edge,
+                r.risk.name(),
+                r.outcome.name(),
+                r.filled_legs as i64,
+                r.total_legs as i64,
+                r.elapsed_us as i64,
+            ])?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::state::portfolio::unix_ms;
+
+use super::records::PersistRecord;
+
+/// Max records written per `write_batch` call.
+const BATCH_SIZE: usize = 256;
+/// How often rows past the retention age are deleted.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3_600);
+
+/// A persistence backend for pipeline records.
+///
+/// Implementations must write the batch atomically (one transaction) so a
+/// crash never leaves a report without its fills.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn write_batch(&self, batch: Vec<PersistRecord>) -> anyhow::Result<()>;
+
+    /// Delete records with `ts_ms < before_ms`, returning how many were removed.
+    /// Backends without row-level retention keep everything.
+    async fn prune(&self, _before_ms: u64) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+}
+
+/// Drain `rx` into `storage` in batches: block for the first record,
+/// then take whatever else is already queued (up to `BATCH_SIZE`).
+///
+/// With `retention` set, rows older than that are pruned every `PRUNE_INTERVAL`.
+pub async fn run_storage_writer(
+    mut rx: mpsc::Receiver<PersistRecord>,
+    storage: Box<dyn Storage>,
+    retention: Option<Duration>,
+) -> anyhow::Result<()> {
+    info!(backend = storage.name(), "persistence writer started");
+    let mut prune_tick = tokio::time::interval(PRUNE_INTERVAL);
+
+    loop {
+        let first = tokio::select! {
+            record = rx.recv() => match record {
+                Some(record) => record,
+                None => break,
+            },
+            _ = prune_tick.tick(), if retention.is_some() => {
+                let before_ms = retention.map_or(0, |age| unix_ms().saturating_sub(age.as_millis() as u64));
+                match storage.prune(before_ms).await {
+                    Ok(0) => {}
+                    Ok(rows) => info!(backend = storage.name(), rows, "pruned rows past retention"),
+                    Err(e) => warn!(backend = storage.name(), error = %e, "failed to prune rows"),
+                }
+                continue;
+            }
+        };
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        batch.push(first);
+        while batch.len() < BATCH_SIZE {
+            match rx.try_recv() {
+                Ok(record) => batch.push(record),
+                Err(_) => break,
+            }
+        }
+
+        let len = batch.len();
+        if let Err(e) = storage.write_batch(batch).await {
+            warn!(backend = storage.name(), error = %e, records = len, "failed to persist batch");
+        }
+    }
+
+    info!("persistence channel closed, writer shutting down");
+    Ok(())
+}
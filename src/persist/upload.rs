@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use tracing::{debug, info, warn};
+
+use super::archive::{is_in_progress, CORRUPT_DIR};
+
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    pub bucket: String,
+    /// Key prefix; the file's path relative to the archive root is appended.
+    pub prefix: String,
+    /// Custom endpoint for S3-compatible stores (MinIO, R2, ...). `None` = AWS.
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    /// Delete the local copy once the remote object's size is verified.
+    pub delete_local: bool,
+    pub interval: Duration,
+}
+
+fn build_store(config: &UploadConfig) -> anyhow::Result<Arc<dyn ObjectStore>> {
+    // Credentials come from the standard AWS_* environment variables.
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(&config.bucket);
+    if let Some(endpoint) = &config.endpoint {
+        builder = builder.with_endpoint(endpoint).with_allow_http(endpoint.starts_with("http://"));
+    }
+    if let Some(region) = &config.region {
+        builder = builder.with_region(region);
+    }
+    Ok(Arc::new(builder.build()?))
+}
+
+/// Files the archiver has closed; the one it is writing is still in progress,
+/// and quarantined ones under `skip` aren't worth keeping remotely.
+fn closed_files(dir: &Path, skip: &Path, out: &mut Vec<(PathBuf, u64)>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            if entry.path() != skip {
+                closed_files(&entry.path(), skip, out)?;
+            }
+        } else if meta.is_file() && !is_in_progress(&entry.path()) {
+            out.push((entry.path(), meta.len()));
+        }
+    }
+    Ok(())
+}
+
+fn object_key(config: &UploadConfig, root: &Path, path: &Path) -> Option<ObjectPath> {
+    let rel = path.strip_prefix(root).ok()?.to_string_lossy().replace('\\', "/");
+    let prefix = config.prefix.trim_matches('/');
+    let key = if prefix.is_empty() { rel } else { format!("{prefix}/{rel}") };
+    Some(ObjectPath::from(key))
+}
+
+/// Upload one file unless an object of the same size already exists.
+/// Returns once the remote size has been verified.
+async fn upload_file(store: &dyn ObjectStore, key: &ObjectPath, path: &Path, len: u64) -> anyhow::Result<bool> {
+    if let Ok(meta) = store.head(key).await && meta.size as u64 == len {
+        return Ok(false);
+    }
+
+    let bytes = tokio::fs::read(path).await?;
+    store.put(key, PutPayload::from(bytes)).await?;
+
+    let meta = store.head(key).await?;
+    anyhow::ensure!(
+        meta.size as u64 == len,
+        "size mismatch after upload: local {len}, remote {}",
+        meta.size
+    );
+    Ok(true)
+}
+
+/// Periodically push closed files under `root` to object storage.
+/// Uploaded files are remembered for the life of the process; after a restart
+/// they are skipped by a remote size check instead of re-uploaded.
+pub async fn run_uploader(root: PathBuf, config: UploadConfig) -> anyhow::Result<()> {
+    let store = build_store(&config)?;
+    let mut uploaded: HashSet<PathBuf> = HashSet::new();
+    let mut ticker = tokio::time::interval(config.interval);
+
+    info!(
+        root = %root.display(),
+        bucket = %config.bucket,
+        prefix = %config.prefix,
+        delete_local = config.delete_local,
+        "archive uploader started"
+    );
+
+    loop {
+        ticker.tick().await;
+
+        let scan_root = root.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            if scan_root.exists() {
+                closed_files(&scan_root, &scan_root.join(CORRUPT_DIR), &mut files)?;
+            }
+            Ok::<_, std::io::Error>(files)
+        })
+        .await?;
+        let files = match files {
+            Ok(files) => files,
+            Err(e) => {
+                warn!(root = %root.display(), error = %e, "failed to scan archive for upload");
+                continue;
+            }
+        };
+
+        for (path, len) in files {
+            if uploaded.contains(&path) {
+                continue;
+            }
+            let Some(key) = object_key(&config, &root, &path) else { continue };
+
+            match upload_file(store.as_ref(), &key, &path, len).await {
+                Ok(sent) => {
+                    if sent {
+                        info!(path = %path.display(), key = %key, bytes = len, "archive file uploaded");
+                    } else {
+                        debug!(path = %path.display(), key = %key, "already uploaded");
+                    }
+                    if config.delete_local {
+                        if let Err(e) = tokio::fs::remove_file(&path).await {
+                            warn!(path = %path.display(), error = %e, "failed to delete uploaded file");
+                        }
+                    } else {
+                        uploaded.insert(path);
+                    }
+                }
+                Err(e) => warn!(path = %path.display(), key = %key, error = %e, "archive upload failed — will retry"),
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Outbound signal feed: every signal the execution bridge sees, and what
+//! became of it, published for services that follow the engine (copy
+//! trading, analytics) without running strategies themselves.
+//!
+//! The feed is a [`crate::persist::Recorder`] sink, so a slow or absent
+//! consumer costs dropped messages, never execution latency. Messages are
+//! JSON in the versioned [`schema`]. One target per engine:
+//!
+//! - `redis://` — `XADD` to a stream, capped at roughly `maxlen` entries.
+//! - `nats://` — core NATS `PUB` to a subject.
+//! - `ws://` — a WebSocket server on that address; subscribers connect to
+//!   `/<topic>` and get every message from then on.
+//!
+//! Redis and NATS are reconnected with backoff. A message whose write fails
+//! is sent again on the new connection, so consumers may see it twice;
+//! `(type, signal_id)` is unique.
+
+mod nats;
+mod redis;
+pub mod schema;
+mod ws;
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use metrics::counter;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::persist::PersistRecord;
+use schema::FeedMessage;
+
+pub const DEFAULT_TOPIC: &str = "prediction_engine.signals";
+pub const DEFAULT_REDIS_MAXLEN: u64 = 100_000;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Longest line a Redis or NATS server is expected to send us.
+const MAX_LINE: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct PublishConfig {
+    pub target: PublishTarget,
+    /// Redis stream key, NATS subject, or WebSocket path.
+    pub topic: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PublishTarget {
+    Redis {
+        /// `host:port`.
+        addr: String,
+        username: Option<String>,
+        password: Option<String>,
+        db: Option<u32>,
+        /// Approximate stream length cap (`XADD MAXLEN ~`).
+        maxlen: u64,
+    },
+    Nats {
+        addr: String,
+        username: Option<String>,
+        password: Option<String>,
+        /// Sent as `auth_token` when the URL has a user but no password.
+        token: Option<String>,
+    },
+    WebSocket {
+        addr: SocketAddr,
+    },
+}
+
+impl PublishTarget {
+    /// Parse `redis://[[user]:password@]host[:port][/db]`,
+    /// `nats://[user:password@|token@]host[:port]` or `ws://host:port`.
+    pub fn parse(url: &str, maxlen: u64) -> anyhow::Result<Self> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow::anyhow!("not a URL: {e}"))?;
+        let host = parsed.host_str().filter(|h| !h.is_empty()).ok_or_else(|| anyhow::anyhow!("URL has no host"))?;
+        let username = Some(parsed.username().to_string()).filter(|u| !u.is_empty());
+        let password = parsed.password().map(str::to_string);
+        match parsed.scheme() {
+            "redis" => {
+                let db = match parsed.path().trim_matches('/') {
+                    "" => None,
+                    db => Some(db.parse().map_err(|_| anyhow::anyhow!("Redis database '{db}' is not a number"))?),
+                };
+                let addr = format!("{host}:{}", parsed.port().unwrap_or(6379));
+                Ok(PublishTarget::Redis { addr, username, password, db, maxlen })
+            }
+            "nats" => {
+                let addr = format!("{host}:{}", parsed.port().unwrap_or(4222));
+                let (username, password, token) = match password {
+                    Some(password) => (username, Some(password), None),
+                    None => (None, None, username),
+                };
+                Ok(PublishTarget::Nats { addr, username, password, token })
+            }
+            "ws" => {
+                let port = parsed.port().ok_or_else(|| anyhow::anyhow!("ws:// needs a port to listen on"))?;
+                let addr = format!("{host}:{port}").parse().map_err(|_| anyhow::anyhow!("'{host}:{port}' is not a listen address"))?;
+                Ok(PublishTarget::WebSocket { addr })
+            }
+            "rediss" | "tls" | "wss" => anyhow::bail!("TLS is not supported; use {}", url.split("://").next().unwrap_or_default()),
+            scheme => anyhow::bail!("unknown scheme '{scheme}'; expected redis, nats or ws"),
+        }
+    }
+
+    /// The target with credentials left out, for logs.
+    pub fn describe(&self) -> String {
+        match self {
+            PublishTarget::Redis { addr, db, .. } => format!("redis://{addr}/{}", db.unwrap_or(0)),
+            PublishTarget::Nats { addr, .. } => format!("nats://{addr}"),
+            PublishTarget::WebSocket { addr } => format!("ws://{addr}"),
+        }
+    }
+}
+
+/// Publish signals and outcomes from a recorder sink until it closes.
+/// Other record kinds aren't published and are dropped here.
+pub async fn run_publisher(rx: mpsc::Receiver<PersistRecord>, config: PublishConfig) {
+    info!(target = %config.target.describe(), topic = %config.topic, "signal publisher starting");
+    match config.target {
+        PublishTarget::WebSocket { addr } => ws::run(rx, addr, config.topic).await,
+        target @ PublishTarget::Redis { .. } => {
+            run_connected(rx, "redis", || redis::Connection::connect(&target, &config.topic)).await
+        }
+        target @ PublishTarget::Nats { .. } => {
+            run_connected(rx, "nats", || nats::Connection::connect(&target, &config.topic)).await
+        }
+    }
+}
+
+/// Connect to a Redis or NATS target and hang up, to check it's usable.
+/// WebSocket targets are listeners and aren't probed here.
+pub async fn probe(target: &PublishTarget) -> anyhow::Result<String> {
+    match target {
+        PublishTarget::Redis { .. } => redis::Connection::connect(target, "").await.map(drop)?,
+        PublishTarget::Nats { .. } => nats::Connection::connect(target, "").await.map(drop)?,
+        PublishTarget::WebSocket { .. } => {}
+    }
+    Ok(target.describe())
+}
+
+/// A connection to a broker the feed is written to.
+trait Connection {
+    /// Write one encoded message.
+    async fn publish(&mut self, message: &FeedMessage, payload: &[u8]) -> anyhow::Result<()>;
+
+    /// Look after the connection between messages: answer server pings and
+    /// notice a hang-up. Returns only when the connection is unusable. Must
+    /// be cancel-safe; it loses the race whenever a message is ready.
+    async fn idle(&mut self) -> anyhow::Error;
+}
+
+async fn run_connected<C, F, Fut>(mut rx: mpsc::Receiver<PersistRecord>, sink: &'static str, connect: F)
+where
+    C: Connection,
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<C>>,
+{
+    let mut pending: Option<(FeedMessage, Vec<u8>)> = None;
+    loop {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut conn = loop {
+            match connect().await {
+                Ok(conn) => break conn,
+                Err(e) => {
+                    warn!(sink, error = %e, backoff_ms = backoff.as_millis() as u64, "signal publisher connect failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        };
+        info!(sink, "signal publisher connected");
+
+        loop {
+            let (message, payload) = match pending.take() {
+                Some(retry) => retry,
+                None => {
+                    let record = tokio::select! {
+                        record = rx.recv() => record,
+                        e = conn.idle() => {
+                            warn!(sink, error = %e, "signal publisher connection lost");
+                            break;
+                        }
+                    };
+                    let Some(record) = record else { return };
+                    let Some(message) = FeedMessage::from_record(&record) else { continue };
+                    let payload = match serde_json::to_vec(&message) {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            warn!(sink, error = %e, "signal publisher couldn't encode a message, dropped");
+                            continue;
+                        }
+                    };
+                    (message, payload)
+                }
+            };
+            if let Err(e) = conn.publish(&message, &payload).await {
+                counter!("publish_errors_total", "sink" => sink).increment(1);
+                warn!(sink, error = %e, "signal publish failed, reconnecting");
+                pending = Some((message, payload));
+                break;
+            }
+            counter!("publish_messages_total", "sink" => sink, "type" => message.kind()).increment(1);
+        }
+    }
+}
+
+/// A TCP connection to a line-oriented server (Redis, NATS), with what has
+/// been read but not yet consumed.
+struct Wire {
+    stream: TcpStream,
+    buf: Vec<u8>,
+}
+
+impl Wire {
+    async fn connect(addr: &str) -> anyhow::Result<Self> {
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr))
+            .await
+            .map_err(|_| anyhow::anyhow!("connecting to {addr} timed out"))??;
+        let _ = stream.set_nodelay(true);
+        Ok(Self { stream, buf: Vec::new() })
+    }
+
+    /// Read whatever has arrived into the buffer. Cancel-safe.
+    async fn fill(&mut self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.buf.len() < MAX_LINE, "server sent over {MAX_LINE} bytes we couldn't parse");
+        let read = self.stream.read_buf(&mut self.buf).await?;
+        anyhow::ensure!(read > 0, "server closed the connection");
+        Ok(())
+    }
+
+    /// The next buffered line, without its CRLF, if a whole one has arrived.
+    fn take_line(&mut self) -> Option<String> {
+        let end = self.buf.windows(2).position(|w| w == b"\r\n")?;
+        let line = String::from_utf8_lossy(&self.buf[..end]).into_owned();
+        self.buf.drain(..end + 2);
+        Some(line)
+    }
+
+    async fn read_line(&mut self) -> anyhow::Result<String> {
+        loop {
+            if let Some(line) = self.take_line() {
+                return Ok(line);
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Exactly `len` bytes followed by CRLF.
+    async fn read_exact_line(&mut self, len: usize) -> anyhow::Result<Vec<u8>> {
+        while self.buf.len() < len + 2 {
+            self.fill().await?;
+        }
+        let data = self.buf[..len].to_vec();
+        anyhow::ensure!(&self.buf[len..len + 2] == b"\r\n", "payload isn't followed by CRLF");
+        self.buf.drain(..len + 2);
+        Ok(data)
+    }
+}
@@ -0,0 +1,108 @@
+//! Core NATS target: `CONNECT` with the URL's credentials, then one `PUB`
+//! per message. The server's keepalive `PING`s are answered while idle.
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use super::schema::FeedMessage;
+use super::{PublishTarget, Wire};
+
+pub struct Connection {
+    wire: Wire,
+    subject: String,
+    /// Control replies (`PONG`) not yet written; they go out ahead of the
+    /// next message if the socket couldn't take them straight away.
+    outbox: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct Connect<'a> {
+    verbose: bool,
+    pedantic: bool,
+    name: &'static str,
+    lang: &'static str,
+    version: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pass: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_token: Option<&'a str>,
+}
+
+impl Connection {
+    pub async fn connect(target: &PublishTarget, subject: &str) -> anyhow::Result<Self> {
+        let PublishTarget::Nats { addr, username, password, token } = target else {
+            anyhow::bail!("not a NATS target");
+        };
+        let mut wire = Wire::connect(addr).await?;
+        let info = wire.read_line().await?;
+        let info = info.strip_prefix("INFO ").ok_or_else(|| anyhow::anyhow!("expected INFO from NATS, got '{info}'"))?;
+        let info: serde_json::Value = serde_json::from_str(info)?;
+        anyhow::ensure!(info["tls_required"] != true, "NATS server requires TLS, which isn't supported");
+
+        let connect = Connect {
+            verbose: false,
+            pedantic: false,
+            name: "prediction-engine",
+            lang: "rust",
+            version: env!("CARGO_PKG_VERSION"),
+            user: username.as_deref(),
+            pass: password.as_deref(),
+            auth_token: token.as_deref(),
+        };
+        let mut hello = b"CONNECT ".to_vec();
+        serde_json::to_writer(&mut hello, &connect)?;
+        hello.extend_from_slice(b"\r\nPING\r\n");
+        wire.stream.write_all(&hello).await?;
+        // The server answers our PING once CONNECT is accepted, or errors out.
+        loop {
+            let line = wire.read_line().await?;
+            match line.as_str() {
+                "PONG" => break,
+                "PING" => wire.stream.write_all(b"PONG\r\n").await?,
+                _ if line.starts_with("-ERR") => anyhow::bail!("NATS: {line}"),
+                _ => {}
+            }
+        }
+        Ok(Self { wire, subject: subject.to_string(), outbox: Vec::new() })
+    }
+}
+
+impl super::Connection for Connection {
+    async fn publish(&mut self, _message: &FeedMessage, payload: &[u8]) -> anyhow::Result<()> {
+        let mut out = std::mem::take(&mut self.outbox);
+        out.extend_from_slice(format!("PUB {} {}\r\n", self.subject, payload.len()).as_bytes());
+        out.extend_from_slice(payload);
+        out.extend_from_slice(b"\r\n");
+        self.wire.stream.write_all(&out).await?;
+        Ok(())
+    }
+
+    async fn idle(&mut self) -> anyhow::Error {
+        loop {
+            if let Err(e) = self.wire.fill().await {
+                return e;
+            }
+            while let Some(line) = self.wire.take_line() {
+                match line.as_str() {
+                    "PING" => self.outbox.extend_from_slice(b"PONG\r\n"),
+                    _ if line.starts_with("-ERR") => return anyhow::anyhow!("NATS: {line}"),
+                    // +OK, PONG, INFO updates.
+                    _ => {}
+                }
+            }
+            // A non-blocking write, so nothing is left half-sent if a
+            // message wins the race while we're here.
+            while !self.outbox.is_empty() {
+                match self.wire.stream.try_write(&self.outbox) {
+                    Ok(written) => {
+                        self.outbox.drain(..written);
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                    Err(e) => return e.into(),
+                }
+            }
+        }
+    }
+}
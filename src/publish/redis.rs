@@ -0,0 +1,84 @@
+//! Redis stream target, over RESP2: `AUTH`, `SELECT`, then one `XADD` per
+//! message with the message type and its JSON as the entry's fields.
+
+use tokio::io::AsyncWriteExt;
+
+use super::schema::FeedMessage;
+use super::{PublishTarget, Wire};
+
+pub struct Connection {
+    wire: Wire,
+    stream: String,
+    maxlen: String,
+}
+
+impl Connection {
+    pub async fn connect(target: &PublishTarget, stream: &str) -> anyhow::Result<Self> {
+        let PublishTarget::Redis { addr, username, password, db, maxlen } = target else {
+            anyhow::bail!("not a Redis target");
+        };
+        let mut wire = Wire::connect(addr).await?;
+        match (username, password) {
+            (Some(username), Some(password)) => command(&mut wire, &[b"AUTH", username.as_bytes(), password.as_bytes()]).await?,
+            (None, Some(password)) => command(&mut wire, &[b"AUTH", password.as_bytes()]).await?,
+            _ => {}
+        }
+        if let Some(db) = db {
+            command(&mut wire, &[b"SELECT", db.to_string().as_bytes()]).await?;
+        }
+        command(&mut wire, &[b"PING"]).await?;
+        Ok(Self { wire, stream: stream.to_string(), maxlen: maxlen.to_string() })
+    }
+}
+
+/// Send one command and wait for its reply; an error reply is an `Err`.
+async fn command(wire: &mut Wire, args: &[&[u8]]) -> anyhow::Result<()> {
+    let mut out = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg);
+        out.extend_from_slice(b"\r\n");
+    }
+    wire.stream.write_all(&out).await?;
+
+    let reply = wire.read_line().await?;
+    match reply.split_at_checked(1) {
+        Some(("+" | ":", _)) => Ok(()),
+        Some(("-", error)) => anyhow::bail!("Redis: {error}"),
+        Some(("$", len)) => {
+            // -1 is a nil reply, with no payload to read.
+            if let Ok(len) = len.parse::<usize>() {
+                wire.read_exact_line(len).await?;
+            }
+            Ok(())
+        }
+        _ => anyhow::bail!("unexpected Redis reply '{reply}'"),
+    }
+}
+
+impl super::Connection for Connection {
+    async fn publish(&mut self, message: &FeedMessage, payload: &[u8]) -> anyhow::Result<()> {
+        let args: [&[u8]; 10] = [
+            b"XADD",
+            self.stream.as_bytes(),
+            b"MAXLEN",
+            b"~",
+            self.maxlen.as_bytes(),
+            b"*",
+            b"type",
+            message.kind().as_bytes(),
+            b"data",
+            payload,
+        ];
+        command(&mut self.wire, &args).await
+    }
+
+    async fn idle(&mut self) -> anyhow::Error {
+        // Outside pub/sub Redis only speaks when spoken to, so any read
+        // completing here is a hang-up or a protocol error.
+        match self.wire.fill().await {
+            Ok(()) => anyhow::anyhow!("unsolicited data from Redis"),
+            Err(e) => e,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Wire schema of the published feed, kept apart from the persistence
+//! records so internal changes don't reach consumers. Any change to a
+//! field's name, type or meaning bumps [`SCHEMA_VERSION`]; adding a field
+//! doesn't.
+
+use serde::Serialize;
+
+use crate::market_data::types::{Side, Venue};
+use crate::persist::records::{OutcomeRecord, RiskDecision, SignalOutcome, SignalRecord};
+use crate::persist::PersistRecord;
+use crate::state::fees::Liquidity;
+use crate::state::market::as_f64;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One published message. Serialized as a JSON object with `schema` and
+/// `type` (`signal` or `outcome`) next to the fields of its kind.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedMessage {
+    Signal(FeedSignal),
+    Outcome(FeedOutcome),
+}
+
+/// A signal as the execution bridge received it.
+#[derive(Debug, Serialize)]
+pub struct FeedSignal {
+    pub schema: u32,
+    /// Engine-assigned; the matching outcome carries the same id.
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub venue: Venue,
+    pub market_id: String,
+    pub edge: f64,
+    pub liquidity: Liquidity,
+    pub legs: Vec<FeedLeg>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedLeg {
+    pub token_id: String,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+}
+
+/// What became of a signal, published once the bridge is done with it.
+#[derive(Debug, Serialize)]
+pub struct FeedOutcome {
+    pub schema: u32,
+    pub signal_id: u64,
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub market_id: String,
+    pub risk: RiskDecision,
+    pub outcome: SignalOutcome,
+    pub filled_legs: usize,
+    pub total_legs: usize,
+    /// Signal generation → outcome.
+    pub elapsed_us: u64,
+}
+
+impl FeedMessage {
+    /// The feed message for `record`, if it's a kind the feed carries.
+    pub fn from_record(record: &PersistRecord) -> Option<Self> {
+        match record {
+            PersistRecord::Signal(signal) => Some(FeedMessage::Signal(FeedSignal::from(signal))),
+            PersistRecord::Outcome(outcome) => Some(FeedMessage::Outcome(FeedOutcome::from(outcome))),
+            _ => None,
+        }
+    }
+
+    /// `signal` or `outcome`, as in the JSON `type` field.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FeedMessage::Signal(_) => "signal",
+            FeedMessage::Outcome(_) => "outcome",
+        }
+    }
+}
+
+impl From<&SignalRecord> for FeedSignal {
+    fn from(record: &SignalRecord) -> Self {
+        Self {
+            schema: SCHEMA_VERSION,
+            signal_id: record.signal_id,
+            ts_ms: record.ts_ms,
+            strategy: record.strategy,
+            venue: record.venue.clone(),
+            market_id: record.market_id.to_string(),
+            edge: record.edge,
+            liquidity: record.liquidity,
+            legs: record
+                .legs
+                .iter()
+                .map(|leg| FeedLeg {
+                    token_id: leg.token_id.to_string(),
+                    side: leg.side.clone(),
+                    price: as_f64(leg.price),
+                    size: as_f64(leg.size),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&OutcomeRecord> for FeedOutcome {
+    fn from(record: &OutcomeRecord) -> Self {
+        Self {
+            schema: SCHEMA_VERSION,
+            signal_id: record.signal_id,
+            ts_ms: record.ts_ms,
+            strategy: record.strategy,
+            market_id: record.market_id.to_string(),
+            risk: record.risk,
+            outcome: record.outcome,
+            filled_legs: record.filled_legs,
+            total_legs: record.total_legs,
+            elapsed_us: record.elapsed_us,
+        }
+    }
+}
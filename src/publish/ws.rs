@@ -0,0 +1,81 @@
+//! WebSocket target: a listener that pushes every message to whoever is
+//! connected to `/<topic>`, one JSON text frame each. A subscriber that
+//! falls behind skips what it missed rather than holding the others up.
+
+use std::net::SocketAddr;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use metrics::{counter, gauge};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
+
+use super::schema::FeedMessage;
+use crate::persist::PersistRecord;
+
+/// Messages buffered per subscriber before it starts skipping.
+const SUBSCRIBER_BUFFER: usize = 1024;
+
+pub async fn run(mut rx: mpsc::Receiver<PersistRecord>, addr: SocketAddr, topic: String) {
+    let (tx, _) = broadcast::channel::<String>(SUBSCRIBER_BUFFER);
+    let app = Router::new().route(&format!("/{}", topic.trim_start_matches('/')), get(subscribe)).with_state(tx.clone());
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!(%addr, error = %e, "signal publisher couldn't listen, feed disabled");
+            return;
+        }
+    };
+    info!(%addr, %topic, "signal feed WebSocket listening");
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!(error = %e, "signal feed WebSocket server stopped");
+        }
+    });
+
+    while let Some(record) = rx.recv().await {
+        let Some(message) = FeedMessage::from_record(&record) else { continue };
+        match serde_json::to_string(&message) {
+            // Err only means nobody is subscribed right now.
+            Ok(json) => {
+                let _ = tx.send(json);
+                counter!("publish_messages_total", "sink" => "ws", "type" => message.kind()).increment(1);
+            }
+            Err(e) => warn!(error = %e, "signal publisher couldn't encode a message, dropped"),
+        }
+    }
+}
+
+async fn subscribe(State(tx): State<broadcast::Sender<String>>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| forward(socket, tx.subscribe()))
+}
+
+async fn forward(mut socket: WebSocket, mut feed: broadcast::Receiver<String>) {
+    gauge!("publish_subscribers").increment(1.0);
+    loop {
+        tokio::select! {
+            message = feed.recv() => match message {
+                Ok(json) => {
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    counter!("publish_errors_total", "sink" => "ws").increment(skipped);
+                    warn!(skipped, "signal feed subscriber fell behind, messages skipped");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            // Reading is what answers the client's pings; anything it sends is ignored.
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!("signal feed subscriber disconnected");
+    gauge!("publish_subscribers").decrement(1.0);
+}
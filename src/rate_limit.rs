@@ -0,0 +1,125 @@
+//! Per-venue REST rate limits.
+//!
+//! Discovery pages through whole catalogues, a burst of out-of-sync books
+//! refetches each over REST, and every order is a request of its own. Left
+//! alone they run into the venue's limits, which answer with 429s just when
+//! the engine needs an answer. So every REST call an adapter or executor
+//! makes to a venue first waits on a token bucket for that venue and the
+//! call's [`EndpointClass`], which spreads a burst out here instead of
+//! having it refused there.
+//!
+//! The buckets are process-wide: the adapter, each wallet's executor and the
+//! operator's cancel-all share one budget per venue and class. [`install`]
+//! sets the limits once at startup; until then, and in programs that never
+//! call it, [`DEFAULT_LIMITS`] apply. A venue and class with no limit isn't
+//! throttled.
+//!
+//! `rest_rate_limited_total{venue, class}` counts calls that had to wait,
+//! and `rest_rate_limit_wait_ms{venue, class}` how long they waited.
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use governor::{DefaultDirectRateLimiter, Quota};
+
+use crate::market_data::types::Venue;
+use crate::metrics::prometheus::record_rest_rate_limited;
+
+/// What a REST call is for. Each has a bucket of its own, since venues
+/// budget them separately and a discovery sweep shouldn't hold up an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// Market listings and reference data: Gamma, Kalshi events, rewards.
+    Discovery,
+    /// Prices and books: the startup seed and book resyncs.
+    MarketData,
+    /// Orders, cancels and account calls.
+    Trading,
+}
+
+impl EndpointClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Discovery => "discovery",
+            Self::MarketData => "market_data",
+            Self::Trading => "trading",
+        }
+    }
+}
+
+impl FromStr for EndpointClass {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "discovery" => Ok(Self::Discovery),
+            "market_data" => Ok(Self::MarketData),
+            "trading" => Ok(Self::Trading),
+            other => anyhow::bail!("unknown endpoint class '{other}' (expected discovery, market_data or trading)"),
+        }
+    }
+}
+
+/// Requests per second (and burst) for a venue and class; 0 is no limit.
+pub type RateLimit = (Venue, EndpointClass, u32);
+
+/// Below the venues' published limits: Polymarket's Gamma and CLOB read
+/// endpoints and sustained order rate, and Kalshi's basic tier.
+pub const DEFAULT_LIMITS: [RateLimit; 6] = [
+    (Venue::Polymarket, EndpointClass::Discovery, 30),
+    (Venue::Polymarket, EndpointClass::MarketData, 50),
+    (Venue::Polymarket, EndpointClass::Trading, 40),
+    (Venue::Kalshi, EndpointClass::Discovery, 20),
+    (Venue::Kalshi, EndpointClass::MarketData, 20),
+    (Venue::Kalshi, EndpointClass::Trading, 10),
+];
+
+static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+/// A token bucket per venue and class.
+pub struct RateLimiter {
+    buckets: HashMap<(Venue, EndpointClass), DefaultDirectRateLimiter>,
+}
+
+impl RateLimiter {
+    /// [`DEFAULT_LIMITS`], with `limits` replacing the defaults they name.
+    pub fn new(limits: &[RateLimit]) -> Self {
+        let mut per_second: HashMap<(Venue, EndpointClass), u32> = HashMap::new();
+        for (venue, class, limit) in DEFAULT_LIMITS.iter().chain(limits) {
+            per_second.insert((venue.clone(), *class), *limit);
+        }
+        let buckets = per_second
+            .into_iter()
+            .filter_map(|(key, limit)| Some((key, DefaultDirectRateLimiter::direct(Quota::per_second(NonZeroU32::new(limit)?)))))
+            .collect();
+        Self { buckets }
+    }
+
+    /// Wait until a call of `class` to `venue` is within its limit.
+    pub async fn acquire(&self, venue: &Venue, class: EndpointClass) {
+        let Some(bucket) = self.buckets.get(&(venue.clone(), class)) else { return };
+        if bucket.check().is_ok() {
+            return;
+        }
+        let start = Instant::now();
+        bucket.until_ready().await;
+        record_rest_rate_limited(venue.name(), class.name(), start.elapsed().as_secs_f64() * 1_000.0);
+    }
+}
+
+/// Set the process-wide limits, [`DEFAULT_LIMITS`] overridden by `limits`.
+/// Fails if they're already set, by an earlier call or an earlier
+/// [`acquire`] under the defaults.
+pub fn install(limits: &[RateLimit]) -> anyhow::Result<()> {
+    LIMITER
+        .set(RateLimiter::new(limits))
+        .map_err(|_| anyhow::anyhow!("REST rate limits are already set"))
+}
+
+/// Wait until a call of `class` to `venue` is within the process-wide limit.
+pub async fn acquire(venue: Venue, class: EndpointClass) {
+    LIMITER.get_or_init(|| RateLimiter::new(&[])).acquire(&venue, class).await;
+}
@@ -0,0 +1,194 @@
+//! Lock-free single-producer, single-consumer ring buffer.
+//!
+//! For a hop with exactly one task on each side (a socket reader handing raw
+//! frames to its parser) an mpsc channel's multi-producer bookkeeping is pure
+//! overhead. Here each side owns one index and publishes it with a single
+//! atomic store; the other side only loads it. Wakeups go through a `Notify`,
+//! but only when the other side has said it is parked, so a busy hop costs
+//! an index store and a flag load per message on each side.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use tokio::sync::Notify;
+
+/// Create a ring holding at least `capacity` items (rounded up to a power of two).
+pub fn ring<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "ring needs a capacity of at least 1");
+    let capacity = capacity.next_power_of_two();
+    let shared = Arc::new(Shared {
+        slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+        mask: capacity - 1,
+        head: Padded(AtomicUsize::new(0)),
+        tail: Padded(AtomicUsize::new(0)),
+        consumer_parked: AtomicBool::new(false),
+        producer_parked: AtomicBool::new(false),
+        items: Notify::new(),
+        space: Notify::new(),
+        producer_gone: AtomicBool::new(false),
+        consumer_gone: AtomicBool::new(false),
+    });
+    (Producer { shared: Arc::clone(&shared), tail: 0 }, Consumer { shared, head: 0 })
+}
+
+/// Keeps the two indices on separate cache lines so the producer's stores
+/// don't invalidate the line the consumer is writing, and vice versa.
+#[repr(align(64))]
+struct Padded<T>(T);
+
+struct Shared<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    mask: usize,
+    /// Next slot to read. Written only by the consumer.
+    head: Padded<AtomicUsize>,
+    /// Next slot to write. Written only by the producer.
+    tail: Padded<AtomicUsize>,
+    consumer_parked: AtomicBool,
+    producer_parked: AtomicBool,
+    items: Notify,
+    space: Notify,
+    producer_gone: AtomicBool,
+    consumer_gone: AtomicBool,
+}
+
+// SAFETY: a slot is only touched by the producer while it is outside
+// `head..tail` and only by the consumer while it is inside, and the index
+// stores that move a slot between the two are Release/Acquire (or SeqCst).
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.0.get_mut(), *self.tail.0.get_mut());
+        // The indices wrap, so count from `head` rather than ranging to `tail`.
+        for i in 0..tail.wrapping_sub(head) {
+            // SAFETY: slots in `head..tail` were written and never read.
+            unsafe { self.slots[head.wrapping_add(i) & self.mask].get_mut().assume_init_drop() };
+        }
+    }
+}
+
+// ── Producer ──────────────────────────────────────────────────────────────────
+
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    /// Local copy of `shared.tail`; only this side changes it.
+    tail: usize,
+}
+
+impl<T> Producer<T> {
+    /// Queue `value` unless the ring is full, in which case it is handed back.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let shared = &*self.shared;
+        if self.tail.wrapping_sub(shared.head.0.load(Ordering::SeqCst)) > shared.mask {
+            return Err(value);
+        }
+        // SAFETY: the slot is outside `head..tail`, so the consumer won't read it.
+        unsafe { (*shared.slots[self.tail & shared.mask].get()).write(value) };
+        self.tail = self.tail.wrapping_add(1);
+        shared.tail.0.store(self.tail, Ordering::SeqCst);
+        if shared.consumer_parked.load(Ordering::SeqCst) && shared.consumer_parked.swap(false, Ordering::SeqCst) {
+            shared.items.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Queue `value`, waiting while the ring is full. Fails, handing the
+    /// value back, once the consumer is gone.
+    pub async fn push(&mut self, mut value: T) -> Result<(), T> {
+        loop {
+            if self.shared.consumer_gone.load(Ordering::Acquire) {
+                return Err(value);
+            }
+            match self.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(v) => value = v,
+            }
+            // A handle of its own, so the wait doesn't borrow `self`.
+            let shared = Arc::clone(&self.shared);
+            let space = shared.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+            shared.producer_parked.store(true, Ordering::SeqCst);
+            // The consumer may have made room before seeing the flag.
+            match self.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(v) => value = v,
+            }
+            if shared.consumer_gone.load(Ordering::SeqCst) {
+                continue;
+            }
+            space.await;
+        }
+    }
+}
+
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        self.shared.producer_gone.store(true, Ordering::SeqCst);
+        self.shared.items.notify_one();
+    }
+}
+
+// ── Consumer ──────────────────────────────────────────────────────────────────
+
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    /// Local copy of `shared.head`; only this side changes it.
+    head: usize,
+}
+
+impl<T> Consumer<T> {
+    /// The oldest queued item, if any, without waiting.
+    pub fn try_pop(&mut self) -> Option<T> {
+        let shared = &*self.shared;
+        if self.head == shared.tail.0.load(Ordering::SeqCst) {
+            return None;
+        }
+        // SAFETY: the slot is inside `head..tail`, so the producer wrote it
+        // and won't touch it again until `head` moves past it.
+        let value = unsafe { (*shared.slots[self.head & shared.mask].get()).assume_init_read() };
+        self.head = self.head.wrapping_add(1);
+        shared.head.0.store(self.head, Ordering::SeqCst);
+        if shared.producer_parked.load(Ordering::SeqCst) && shared.producer_parked.swap(false, Ordering::SeqCst) {
+            shared.space.notify_one();
+        }
+        Some(value)
+    }
+
+    /// The oldest queued item, waiting for one if the ring is empty.
+    /// `None` once the producer is gone and the ring is drained.
+    pub async fn pop(&mut self) -> Option<T> {
+        loop {
+            if let Some(value) = self.try_pop() {
+                return Some(value);
+            }
+            if self.shared.producer_gone.load(Ordering::SeqCst) {
+                // Anything pushed before the producer went is visible now.
+                return self.try_pop();
+            }
+            let shared = Arc::clone(&self.shared);
+            let items = shared.items.notified();
+            tokio::pin!(items);
+            items.as_mut().enable();
+            shared.consumer_parked.store(true, Ordering::SeqCst);
+            // The producer may have pushed before seeing the flag.
+            if let Some(value) = self.try_pop() {
+                return Some(value);
+            }
+            if shared.producer_gone.load(Ordering::SeqCst) {
+                continue;
+            }
+            items.await;
+        }
+    }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        self.shared.consumer_gone.store(true, Ordering::SeqCst);
+        self.shared.space.notify_one();
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{error, warn};
+
+use crate::market_data::types::Venue;
+use crate::notify::{Notifier, NotifyEvent};
+use crate::state::ids::TokenId;
+
+const MS_PER_DAY: u64 = 86_400_000;
+
+#[derive(Debug, Clone)]
+pub struct RiskConfig {
+    /// Halt trading once equity falls this fraction below its peak (0.10 = 10%).
+    pub max_drawdown: f64,
+    /// Max filled notional per UTC day, across all strategies.
+    pub max_daily_notional: f64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self { max_drawdown: 0.10, max_daily_notional: 10_000.0 }
+    }
+}
+
+/// Filled notional for one UTC day.
+#[derive(Debug, Clone, Copy, Default)]
+struct DailyNotional {
+    day: u64,
+    notional: f64,
+}
+
+/// Pre-trade gate shared by the execution bridge and the monitors that feed it.
+/// Once tripped it stays tripped until an operator calls [`RiskManager::reset`].
+/// Limits can be changed at runtime with [`RiskManager::set_config`].
+#[derive(Debug, Clone)]
+pub struct RiskManager {
+    config: Arc<Mutex<RiskConfig>>,
+    halted: Arc<AtomicBool>,
+    daily: Arc<Mutex<DailyNotional>>,
+    /// Tokens whose feed has gone quiet past the feed watchdog's deadline.
+    stale_feeds: Arc<Mutex<HashSet<(Venue, TokenId)>>>,
+    notifier: Notifier,
+}
+
+impl RiskManager {
+    pub fn new(config: RiskConfig) -> Self {
+        Self {
+            config: Arc::new(Mutex::new(config)),
+            halted: Arc::new(AtomicBool::new(false)),
+            daily: Arc::new(Mutex::new(DailyNotional::default())),
+            stale_feeds: Arc::default(),
+            notifier: Notifier::disabled(),
+        }
+    }
+
+    /// Send a [`NotifyEvent::RiskHalt`] when the breaker trips.
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    pub fn config(&self) -> RiskConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Swap the limits. Takes effect on the next check; a tripped breaker stays tripped.
+    pub fn set_config(&self, config: RiskConfig) {
+        *self.config.lock().unwrap() = config;
+    }
+
+    /// Feed the current drawdown fraction; trips the breaker past the limit.
+    pub fn on_drawdown(&self, fraction: f64) {
+        let limit = self.config.lock().unwrap().max_drawdown;
+        if fraction >= limit && !self.halted.swap(true, Ordering::SeqCst) {
+            error!(drawdown = fraction, limit, "drawdown limit breached — trading halted");
+            self.notifier.notify(NotifyEvent::RiskHalt { drawdown: fraction, limit });
+        }
+    }
+
+    /// Operator kill switch: trip the breaker regardless of drawdown.
+    pub fn halt(&self, reason: &str) {
+        if !self.halted.swap(true, Ordering::SeqCst) {
+            error!(reason, "kill switch tripped — trading halted");
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    pub fn reset(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+    }
+
+    /// Mark `token_id`'s feed on `venue` stale, or live again. Orders on a
+    /// stale feed are refused until it is.
+    pub fn set_feed_stale(&self, venue: &Venue, token_id: TokenId, stale: bool) {
+        let mut stale_feeds = self.stale_feeds.lock().unwrap();
+        if stale {
+            stale_feeds.insert((venue.clone(), token_id));
+        } else {
+            stale_feeds.remove(&(venue.clone(), token_id));
+        }
+    }
+
+    /// Whether any of `token_ids` on `venue` is on a stale feed.
+    pub fn any_feed_stale(&self, venue: &Venue, token_ids: impl IntoIterator<Item = TokenId>) -> bool {
+        let stale_feeds = self.stale_feeds.lock().unwrap();
+        !stale_feeds.is_empty() && token_ids.into_iter().any(|token_id| stale_feeds.contains(&(venue.clone(), token_id)))
+    }
+
+    /// Whether an order of `notional` fits in today's remaining budget.
+    pub fn check_daily_notional(&self, notional: f64, now_ms: u64) -> bool {
+        let limit = self.config.lock().unwrap().max_daily_notional;
+        let daily = self.daily.lock().unwrap();
+        let used = if daily.day == now_ms / MS_PER_DAY { daily.notional } else { 0.0 };
+        if used + notional > limit {
+            warn!(used, requested = notional, limit, "daily notional limit would be exceeded");
+            return false;
+        }
+        true
+    }
+
+    /// Count a fill against the daily budget of the day it happened.
+    /// Fills from earlier days (e.g. during journal replay) are ignored.
+    pub fn record_fill_notional(&self, notional: f64, ts_ms: u64) {
+        let day = ts_ms / MS_PER_DAY;
+        let mut daily = self.daily.lock().unwrap();
+        if day > daily.day {
+            *daily = DailyNotional { day, notional: 0.0 };
+        }
+        if day == daily.day {
+            daily.notional += notional;
+        }
+    }
+
+    pub fn daily_notional(&self, now_ms: u64) -> f64 {
+        let daily = self.daily.lock().unwrap();
+        if daily.day == now_ms / MS_PER_DAY { daily.notional } else { 0.0 }
+    }
+}
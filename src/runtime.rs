@@ -0,0 +1,161 @@
+//! Dedicated tokio runtime for the latency-critical pipeline.
+//!
+//! By default the adapter, router, strategy engine and execution bridge
+//! share worker threads with metrics export, persistence, archiving and the
+//! admin API, so a burst of background work can delay the next tick. A
+//! hot-path runtime gives the pipeline worker threads of its own, optionally
+//! pinned to CPU cores the operator keeps free of other work. Tasks spawned
+//! from a pipeline task (market workers, WS loops) stay on that runtime.
+//!
+//! [`VenueThread`] does the same for one venue's socket reader: a thread of
+//! its own, so a bursty venue's feed can't hold up another venue's reader.
+//!
+//! [`BusyPoll`] goes a step further for dedicated cores: the strategy engine
+//! and execution bridge spin on their queues for a while before parking, so a
+//! message that arrives soon after the last one skips the wakeup.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use metrics::counter;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tracing::{info, warn};
+
+use crate::market_data::types::Venue;
+
+#[derive(Debug, Clone)]
+pub struct HotPathConfig {
+    pub threads: usize,
+    /// CPU cores to pin threads to, assigned round-robin. Empty = unpinned.
+    /// Linux only; elsewhere pinning is skipped with a warning.
+    pub cores: Vec<usize>,
+}
+
+/// A venue whose WebSocket reader runs on a thread of its own.
+#[derive(Debug, Clone)]
+pub struct VenueThread {
+    pub venue: Venue,
+    /// CPU core to pin the thread to (Linux only). Unpinned when `None`.
+    pub core: Option<usize>,
+}
+
+/// Owns a dedicated runtime: the hot-path one or a venue reader's. Dropping
+/// it shuts the runtime down without waiting, which unlike dropping a
+/// `Runtime` is allowed inside another runtime.
+pub struct HotPathRuntime(Option<Runtime>);
+
+impl HotPathRuntime {
+    pub fn start(config: &HotPathConfig) -> anyhow::Result<Self> {
+        anyhow::ensure!(config.threads > 0, "the hot-path runtime needs at least one thread");
+        let runtime = build_runtime("hot-path".to_string(), config.threads, config.cores.clone())?;
+        info!(threads = config.threads, cores = ?config.cores, "hot-path runtime started");
+        Ok(Self(Some(runtime)))
+    }
+
+    /// A one-thread runtime for `config.venue`'s socket reader.
+    pub fn start_venue(config: &VenueThread) -> anyhow::Result<Self> {
+        let name = format!("reader-{}", config.venue.name().to_ascii_lowercase());
+        let runtime = build_runtime(name, 1, config.core.into_iter().collect())?;
+        info!(venue = config.venue.name(), core = ?config.core, "venue reader thread started");
+        Ok(Self(Some(runtime)))
+    }
+
+    pub fn handle(&self) -> &Handle {
+        self.0.as_ref().expect("runtime present until drop").handle()
+    }
+}
+
+impl Drop for HotPathRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// A multi-thread runtime with `threads` workers named `name`, pinned to
+/// `cores` round-robin when any are given.
+fn build_runtime(name: String, threads: usize, cores: Vec<usize>) -> std::io::Result<Runtime> {
+    let cores = Arc::new(cores);
+    let next = Arc::new(AtomicUsize::new(0));
+    let thread = name.clone();
+    Builder::new_multi_thread()
+        .worker_threads(threads)
+        .thread_name(name)
+        .enable_all()
+        // Runs for the runtime's blocking-pool threads too, so they share the cores.
+        .on_thread_start(move || {
+            if cores.is_empty() {
+                return;
+            }
+            let core = cores[next.fetch_add(1, Ordering::Relaxed) % cores.len()];
+            if let Err(e) = pin_current_thread(core) {
+                warn!(thread = %thread, core, error = %e, "could not pin runtime thread");
+            }
+        })
+        .build()
+}
+
+/// Spin-then-park receive. With a non-zero `spin`, a consumer polls its
+/// queue with `try_recv` for up to `spin` before falling back to awaiting
+/// `recv`. Each wait burns at most `spin` of CPU, and only while the queue
+/// is empty; a consumer that keeps finding work never parks.
+///
+/// Spinning holds the worker thread, so it only pays off on a hot-path
+/// runtime whose threads have cores to themselves.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusyPoll {
+    /// Longest spin per wait. Zero disables busy-polling.
+    pub spin: Duration,
+}
+
+impl BusyPoll {
+    pub fn is_enabled(&self) -> bool {
+        !self.spin.is_zero()
+    }
+
+    /// Call `try_recv` until it yields or the spin budget runs out.
+    /// `None` means the caller should park on its async `recv`.
+    /// Outcomes are counted in `busy_poll_total` under `task`.
+    pub fn spin<T>(&self, task: &'static str, mut try_recv: impl FnMut() -> Option<T>) -> Option<T> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let deadline = Instant::now() + self.spin;
+        loop {
+            if let Some(value) = try_recv() {
+                counter!("busy_poll_total", "task" => task, "outcome" => "hit").increment(1);
+                return Some(value);
+            }
+            if Instant::now() >= deadline {
+                counter!("busy_poll_total", "task" => task, "outcome" => "park").increment(1);
+                return None;
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) -> std::io::Result<()> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("core {core} is out of range")));
+    }
+    // SAFETY: `cpu_set_t` is plain data, `core` is within it, and pid 0
+    // targets the calling thread.
+    let rc = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_core: usize) -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "thread pinning is only supported on Linux"))
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use serde_json::Value;
+use tracing::info;
+use zeroize::Zeroizing;
+
+/// Re-fetch interval for remote secrets when not configured.
+pub const DEFAULT_REFRESH: Duration = Duration::from_secs(300);
+
+/// A remote secret store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretStore {
+    /// HashiCorp Vault over its HTTP API. The server and token come from the
+    /// standard `VAULT_ADDR`, `VAULT_TOKEN` and (optional) `VAULT_NAMESPACE`.
+    Vault,
+    /// AWS Secrets Manager, with region and credentials from the usual AWS
+    /// chain (env, profile, instance or task role).
+    AwsSecretsManager,
+}
+
+impl SecretStore {
+    pub fn name(self) -> &'static str {
+        match self {
+            SecretStore::Vault => "vault",
+            SecretStore::AwsSecretsManager => "aws",
+        }
+    }
+}
+
+/// One value in a remote store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretRef {
+    pub store: SecretStore,
+    /// Vault API path after `/v1/` (e.g. `secret/data/prediction-engine`), or
+    /// the Secrets Manager secret id or ARN.
+    pub id: String,
+    /// Key within a JSON secret. `None` takes a Secrets Manager string
+    /// secret whole; Vault secrets are always key/value and need one.
+    pub field: Option<String>,
+}
+
+/// Fetch one secret value. Nothing is cached: every call goes to the store,
+/// so rotated values are picked up on the next fetch.
+pub async fn fetch(secret: &SecretRef) -> anyhow::Result<Zeroizing<String>> {
+    let fields = fetch_fields(secret.store, &secret.id).await?;
+    let value = match (&secret.field, fields) {
+        (Some(field), Payload::Json(map)) => match map.get(field) {
+            Some(Value::String(s)) => Zeroizing::new(s.clone()),
+            Some(_) => anyhow::bail!("field '{field}' of {} secret {} is not a string", secret.store.name(), secret.id),
+            None => anyhow::bail!("{} secret {} has no field '{field}'", secret.store.name(), secret.id),
+        },
+        (None, Payload::Text(text)) => text,
+        (Some(field), Payload::Text(_)) => {
+            anyhow::bail!("{} secret {} is not JSON, so has no field '{field}'", secret.store.name(), secret.id)
+        }
+        (None, Payload::Json(_)) => {
+            anyhow::bail!("{} secret {} is key/value; name the field to read", secret.store.name(), secret.id)
+        }
+    };
+    anyhow::ensure!(!value.trim().is_empty(), "{} secret {} is empty", secret.store.name(), secret.id);
+    Ok(value)
+}
+
+/// Kalshi API credentials: the key id and its RSA private key (PEM).
+pub struct KalshiCredentials {
+    pub api_key_id: String,
+    pub private_key: Zeroizing<String>,
+}
+
+/// Read Kalshi credentials from one JSON secret with `api_key_id` and
+/// `private_key` fields.
+pub async fn fetch_kalshi_credentials(store: SecretStore, id: &str) -> anyhow::Result<KalshiCredentials> {
+    let field = |name: &str| SecretRef { store, id: id.to_string(), field: Some(name.to_string()) };
+    let api_key_id = fetch(&field("api_key_id")).await?.trim().to_string();
+    let private_key = fetch(&field("private_key")).await?;
+    info!(store = store.name(), secret = id, "kalshi credentials loaded");
+    Ok(KalshiCredentials { api_key_id, private_key })
+}
+
+enum Payload {
+    Json(serde_json::Map<String, Value>),
+    Text(Zeroizing<String>),
+}
+
+async fn fetch_fields(store: SecretStore, id: &str) -> anyhow::Result<Payload> {
+    match store {
+        SecretStore::Vault => fetch_vault(id).await,
+        SecretStore::AwsSecretsManager => fetch_aws(id).await,
+    }
+}
+
+/// KV v2 nests the values under `data.data`; KV v1 puts them in `data`.
+async fn fetch_vault(path: &str) -> anyhow::Result<Payload> {
+    let addr = std::env::var("VAULT_ADDR").context("VAULT_ADDR is not set")?;
+    let token = Zeroizing::new(std::env::var("VAULT_TOKEN").context("VAULT_TOKEN is not set")?);
+    let url = format!("{}/v1/{}", addr.trim_end_matches('/'), path.trim_start_matches('/'));
+
+    let mut request = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .get(&url)
+        .header("X-Vault-Token", token.as_str());
+    if let Ok(namespace) = std::env::var("VAULT_NAMESPACE") {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response = request.send().await.with_context(|| format!("vault request to {url} failed"))?;
+    let status = response.status();
+    anyhow::ensure!(status.is_success(), "vault returned {status} for {path}");
+
+    let mut body: Value = response.json().await.context("vault returned invalid JSON")?;
+    let data = body.get_mut("data").map(Value::take).unwrap_or(Value::Null);
+    let data = match data {
+        Value::Object(mut map) if matches!(map.get("data"), Some(Value::Object(_))) => {
+            map.remove("data").unwrap_or(Value::Null)
+        }
+        other => other,
+    };
+    match data {
+        Value::Object(map) => Ok(Payload::Json(map)),
+        _ => anyhow::bail!("vault response for {path} has no data"),
+    }
+}
+
+async fn fetch_aws(secret_id: &str) -> anyhow::Result<Payload> {
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let output = aws_sdk_secretsmanager::Client::new(&config)
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .with_context(|| format!("failed to read secret {secret_id} from AWS Secrets Manager"))?;
+    let text = Zeroizing::new(
+        output
+            .secret_string()
+            .with_context(|| format!("secret {secret_id} has no string value"))?
+            .to_string(),
+    );
+    // JSON key/value secrets are the console's default; anything else is a plain string.
+    match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(map)) => Ok(Payload::Json(map)),
+        _ => Ok(Payload::Text(text)),
+    }
+}
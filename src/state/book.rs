@@ -0,0 +1,77 @@
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+
+use crate::market_data::types::{BookLevel, Side};
+
+/// Aggregate size per price on both sides of one token's book (L2), built
+/// from a venue's snapshots and deltas. Prices and sizes are exact venue
+/// ticks and lots.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderBook {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl OrderBook {
+    /// Replace every level with a snapshot's. Empty levels are dropped.
+    pub fn reset(&mut self, bids: &[BookLevel], asks: &[BookLevel]) {
+        let resting = |level: &&BookLevel| level.size > Decimal::ZERO;
+        self.bids.clear();
+        self.bids.extend(bids.iter().filter(resting).map(|l| (l.price, l.size)));
+        self.asks.clear();
+        self.asks.extend(asks.iter().filter(resting).map(|l| (l.price, l.size)));
+    }
+
+    /// Set the total size at `price` on `side` (`Buy` = bids); zero removes it.
+    pub fn apply(&mut self, side: &Side, price: Decimal, size: Decimal) {
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        if size > Decimal::ZERO {
+            levels.insert(price, size);
+        } else {
+            levels.remove(&price);
+        }
+    }
+
+    /// Total size resting at `price` on `side`; zero when there's no level.
+    pub fn size_at(&self, side: &Side, price: Decimal) -> Decimal {
+        let levels = match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        };
+        levels.get(&price).copied().unwrap_or_default()
+    }
+
+    pub fn best_bid(&self) -> Option<BookLevel> {
+        self.bids.last_key_value().map(|(&price, &size)| BookLevel { price, size })
+    }
+
+    pub fn best_ask(&self) -> Option<BookLevel> {
+        self.asks.first_key_value().map(|(&price, &size)| BookLevel { price, size })
+    }
+
+    /// Bid levels, highest first.
+    pub fn bids(&self) -> impl Iterator<Item = BookLevel> + '_ {
+        self.bids.iter().rev().map(|(&price, &size)| BookLevel { price, size })
+    }
+
+    /// Ask levels, lowest first.
+    pub fn asks(&self) -> impl Iterator<Item = BookLevel> + '_ {
+        self.asks.iter().map(|(&price, &size)| BookLevel { price, size })
+    }
+
+    /// Size a taker on `side` could fill at `limit` or better: asks up to
+    /// `limit` for a buy, bids down to it for a sell.
+    pub fn depth_to(&self, side: &Side, limit: Decimal) -> Decimal {
+        match side {
+            Side::Buy => self.asks.range(..=limit).map(|(_, size)| size).sum(),
+            Side::Sell => self.bids.range(limit..).map(|(_, size)| size).sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+}
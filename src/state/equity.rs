@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+const MS_PER_DAY: u64 = 86_400_000;
+
+/// One point on the equity curve.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EquitySample {
+    pub ts_ms: u64,
+    /// cash + marked positions.
+    pub equity: f64,
+    pub cash: f64,
+}
+
+/// Peak-to-trough decline of the equity curve.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Drawdown {
+    pub peak: f64,
+    pub trough: f64,
+    pub absolute: f64,
+    /// `absolute / peak`, 0.0 when the peak is non-positive.
+    pub fraction: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DailyReturn {
+    /// Days since the unix epoch (UTC).
+    pub day: u64,
+    pub close_equity: f64,
+    pub return_pct: f64,
+}
+
+/// Bounded, periodically sampled equity curve. Shared across clones.
+#[derive(Debug, Clone)]
+pub struct EquityCurve {
+    samples: Arc<Mutex<VecDeque<EquitySample>>>,
+    max_samples: usize,
+}
+
+impl EquityCurve {
+    pub fn new(max_samples: usize) -> Self {
+        Self {
+            samples: Arc::new(Mutex::new(VecDeque::with_capacity(max_samples.min(4_096)))),
+            max_samples,
+        }
+    }
+
+    pub fn push(&self, sample: EquitySample) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.max_samples {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> Vec<EquitySample> {
+        self.samples.lock().unwrap().iter().copied().collect()
+    }
+
+    /// Largest peak-to-trough decline over the retained window.
+    pub fn max_drawdown(&self) -> Drawdown {
+        let samples = self.samples.lock().unwrap();
+        let mut peak = f64::MIN;
+        let mut worst = Drawdown::default();
+        for s in samples.iter() {
+            peak = peak.max(s.equity);
+            let absolute = peak - s.equity;
+            if absolute > worst.absolute {
+                worst = Drawdown {
+                    peak,
+                    trough: s.equity,
+                    absolute,
+                    fraction: if peak > 0.0 { absolute / peak } else { 0.0 },
+                };
+            }
+        }
+        worst
+    }
+
+    /// Decline from the running peak to the latest sample.
+    pub fn current_drawdown(&self) -> Drawdown {
+        let samples = self.samples.lock().unwrap();
+        let Some(last) = samples.back() else { return Drawdown::default() };
+        let peak = samples.iter().map(|s| s.equity).fold(f64::MIN, f64::max);
+        let absolute = peak - last.equity;
+        Drawdown {
+            peak,
+            trough: last.equity,
+            absolute,
+            fraction: if peak > 0.0 { absolute / peak } else { 0.0 },
+        }
+    }
+
+    /// Close-to-close returns per UTC day. The first day has no prior close
+    /// and is measured against its own first sample.
+    pub fn daily_returns(&self) -> Vec<DailyReturn> {
+        let samples = self.samples.lock().unwrap();
+        let mut out: Vec<DailyReturn> = Vec::new();
+        let mut prev_close = samples.front().map(|s| s.equity);
+
+        let mut i = 0;
+        while i < samples.len() {
+            let day = samples[i].ts_ms / MS_PER_DAY;
+            let mut close = samples[i].equity;
+            while i < samples.len() && samples[i].ts_ms / MS_PER_DAY == day {
+                close = samples[i].equity;
+                i += 1;
+            }
+            let base = prev_close.unwrap_or(close);
+            out.push(DailyReturn {
+                day,
+                close_equity: close,
+                return_pct: if base != 0.0 { (close - base) / base * 100.0 } else { 0.0 },
+            });
+            prev_close = Some(close);
+        }
+        out
+    }
+}
@@ -0,0 +1,88 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Whether a fill took liquidity from the book or rested and was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+/// Per-venue fee model, in basis points of fill notional.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub taker_fee_bps: f64,
+    pub maker_rebate_bps: f64,
+    /// Estimated liquidity-rewards payout per unit of maker notional.
+    /// Polymarket pays rewards out-of-band, so this is an estimate, not a cash flow.
+    pub rewards_bps: f64,
+}
+
+impl FeeSchedule {
+    /// ~1% taker fee per leg, no maker rebate, no rewards estimate.
+    pub fn polymarket_default() -> Self {
+        Self { taker_fee_bps: 100.0, maker_rebate_bps: 0.0, rewards_bps: 0.0 }
+    }
+
+    pub fn charges(&self, liquidity: Liquidity, notional: f64) -> FillCharges {
+        match liquidity {
+            Liquidity::Taker => FillCharges {
+                taker_fee: notional * self.taker_fee_bps / 10_000.0,
+                ..Default::default()
+            },
+            Liquidity::Maker => FillCharges {
+                maker_rebate: notional * self.maker_rebate_bps / 10_000.0,
+                rewards_estimate: notional * self.rewards_bps / 10_000.0,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Fee / rebate / rewards lines accrued by one fill.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FillCharges {
+    pub taker_fee: f64,
+    pub maker_rebate: f64,
+    pub rewards_estimate: f64,
+}
+
+/// Gross-vs-net PnL lines for a single strategy.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StrategyPnl {
+    pub volume: f64,
+    pub realized_gross: f64,
+    pub taker_fees: f64,
+    pub maker_rebates: f64,
+    pub rewards_estimate: f64,
+    /// realized_gross − taker_fees + maker_rebates (rewards excluded — not yet paid).
+    pub realized_net: f64,
+}
+
+/// Accrues fees and realized PnL per strategy. Shared across clones.
+#[derive(Debug, Clone, Default)]
+pub struct FeeAccruals {
+    by_strategy: Arc<DashMap<String, StrategyPnl>>,
+}
+
+impl FeeAccruals {
+    pub fn record(&self, strategy: &str, notional: f64, realized: f64, charges: FillCharges) {
+        let mut line = self.by_strategy.entry(strategy.to_string()).or_default();
+        line.volume += notional;
+        line.realized_gross += realized;
+        line.taker_fees += charges.taker_fee;
+        line.maker_rebates += charges.maker_rebate;
+        line.rewards_estimate += charges.rewards_estimate;
+        line.realized_net = line.realized_gross - line.taker_fees + line.maker_rebates;
+    }
+
+    pub fn by_strategy(&self) -> HashMap<String, StrategyPnl> {
+        self.by_strategy
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+//! Interned token and market identifiers.
+//!
+//! Polymarket token ids are 70+ digit strings. On the hot path they are
+//! hashed on every cache read and copied into every key, signal, and order
+//! leg, so they are interned once into `Copy` numeric ids instead. Each id
+//! resolves back to its string for logging, persistence, and venue calls.
+//!
+//! Interned strings live for the rest of the process; the set of tokens and
+//! markets an engine sees is bounded by its universe. Input from outside
+//! (admin API, config) should go through [`TokenId::lookup`] /
+//! [`MarketId::lookup`] so unknown ids don't grow the table.
+
+use std::fmt;
+use std::sync::RwLock;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+struct Interner {
+    ids: DashMap<&'static str, u32>,
+    names: RwLock<Vec<&'static str>>,
+}
+
+impl Interner {
+    fn new() -> Self {
+        Self { ids: DashMap::new(), names: RwLock::new(Vec::new()) }
+    }
+
+    fn intern(&self, s: &str) -> u32 {
+        if let Some(id) = self.ids.get(s) {
+            return *id;
+        }
+        // Slow path, once per distinct string: re-check under the write lock
+        // so two threads interning the same string agree on its id.
+        let mut names = self.names.write().unwrap();
+        if let Some(id) = self.ids.get(s) {
+            return *id;
+        }
+        let name: &'static str = Box::leak(s.to_owned().into_boxed_str());
+        let id = u32::try_from(names.len()).expect("more than u32::MAX interned ids");
+        names.push(name);
+        self.ids.insert(name, id);
+        id
+    }
+
+    fn lookup(&self, s: &str) -> Option<u32> {
+        self.ids.get(s).map(|id| *id)
+    }
+
+    fn resolve(&self, id: u32) -> &'static str {
+        self.names.read().unwrap()[id as usize]
+    }
+}
+
+macro_rules! interned_id {
+    ($(#[$doc:meta])* $name:ident, $table:ident) => {
+        static $table: std::sync::LazyLock<Interner> = std::sync::LazyLock::new(Interner::new);
+
+        $(#[$doc])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name(u32);
+
+        impl $name {
+            /// The id for `s`, interning it on first sight.
+            pub fn intern(s: &str) -> Self {
+                Self($table.intern(s))
+            }
+
+            /// The id for `s` if it has been interned, without adding it.
+            pub fn lookup(s: &str) -> Option<Self> {
+                $table.lookup(s).map(Self)
+            }
+
+            pub fn as_str(&self) -> &'static str {
+                $table.resolve(self.0)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(s: &str) -> Self {
+                Self::intern(s)
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(s: &String) -> Self {
+                Self::intern(s)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        /// Prints the string, not the number: ids differ from run to run.
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(self.as_str(), f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                Ok(Self::intern(&s))
+            }
+        }
+    };
+}
+
+interned_id!(
+    /// Interned venue token (outcome) id.
+    TokenId,
+    TOKENS
+);
+
+interned_id!(
+    /// Interned venue market id.
+    MarketId,
+    MARKETS
+);
@@ -1,24 +1,90 @@
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::market_data::types::{BookLevel, Side};
+use crate::state::book::OrderBook;
+
 /// Lightweight snapshot of the latest market data.
 /// Stores only the pricing/volume fields — no redundant full-event clone.
-#[derive(Clone, Debug)]
+/// Prices are exact venue ticks; convert to f64 only for analytics.
+///
+/// For venues that stream depth, `book` holds every level once the first
+/// snapshot arrives, and from then on the best prices are the book's.
+#[derive(Clone, Debug, Default)]
 pub struct MarketState {
-    pub best_bid: Option<f64>,
-    pub best_ask: Option<f64>,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    pub volume24h: Option<f64>,
+    pub book: Option<OrderBook>,
+}
+
+/// A partial change to one token's state, built from one market event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MarketUpdate<'a> {
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
     pub volume24h: Option<f64>,
+    pub book: Option<BookUpdate<'a>>,
+}
+
+/// The depth carried by a `BookSnapshot` or `BookDelta` event, or a
+/// `BookStale`, `Resync`, `Unsubscribed` or `FeedStale` event's word that
+/// the book can't be trusted.
+#[derive(Clone, Copy, Debug)]
+pub enum BookUpdate<'a> {
+    Snapshot { bids: &'a [BookLevel], asks: &'a [BookLevel] },
+    Delta { side: &'a Side, price: Decimal, size: Decimal },
+    Stale,
 }
 
 impl MarketState {
     /// Merge a partial update into this state.
     /// Only overwrites fields that are `Some` in `update`; leaves others unchanged.
-    pub fn merge(&mut self, update: &MarketState) {
-        if update.best_bid.is_some() {
-            self.best_bid = update.best_bid;
+    /// A snapshot replaces the book and a delta amends it (a delta before
+    /// any snapshot is dropped); while there is a book, the best prices are
+    /// derived from it and the update's own are ignored. A stale book is
+    /// dropped along with its best prices, which become the update's own
+    /// even where those are `None`.
+    pub fn merge(&mut self, update: &MarketUpdate) {
+        match (update.book, &mut self.book) {
+            (Some(BookUpdate::Snapshot { bids, asks }), book) => book.get_or_insert_with(OrderBook::default).reset(bids, asks),
+            (Some(BookUpdate::Delta { side, price, size }), Some(book)) => book.apply(side, price, size),
+            (Some(BookUpdate::Stale), book) => {
+                *book = None;
+                self.best_bid = update.best_bid;
+                self.best_ask = update.best_ask;
+            }
+            _ => {}
         }
-        if update.best_ask.is_some() {
-            self.best_ask = update.best_ask;
+        match &self.book {
+            Some(book) => {
+                self.best_bid = book.best_bid().map(|level| level.price);
+                self.best_ask = book.best_ask().map(|level| level.price);
+            }
+            None => {
+                if update.best_bid.is_some() {
+                    self.best_bid = update.best_bid;
+                }
+                if update.best_ask.is_some() {
+                    self.best_ask = update.best_ask;
+                }
+            }
         }
         if update.volume24h.is_some() {
             self.volume24h = update.volume24h;
         }
     }
 }
+
+/// `Decimal` → `f64` for analytics (positions, PnL, metrics, archives),
+/// where exactness no longer matters. Total: every `Decimal` is in range.
+pub fn as_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// `f64` → `Decimal` for prices and sizes entering from outside (config,
+/// operator input, positions). Uses the shortest decimal form, so `0.52`
+/// becomes exactly `0.52`; fails on NaN and infinities.
+pub fn to_decimal(value: f64) -> anyhow::Result<Decimal> {
+    Decimal::from_f64(value).ok_or_else(|| anyhow::anyhow!("{value} is not a representable price or size"))
+}
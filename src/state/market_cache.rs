@@ -1,61 +1,153 @@
-use crate::state::market::MarketState;
+use crate::state::market::{MarketState, MarketUpdate};
 use crate::market_data::types::Venue;
+use crate::state::ids::TokenId;
 use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
 pub struct MarketKey(
     pub Venue,
-    pub String, // token_id
+    pub TokenId,
 );
 
 /// Thread-safe market cache backed by DashMap.
 /// Eliminates write-lock contention: concurrent writers on different keys
 /// never block each other, and readers are never blocked by writers.
+///
+/// Each state sits behind its own `Arc`, so [`get_shared`](Self::get_shared)
+/// hands out the current state without copying it or holding a map lock.
+/// A write updates the state in place unless a reader still holds it, in
+/// which case the write copies it first and the reader keeps the old one.
 #[derive(Clone, Debug)]
 pub struct MarketCache {
-    cache: Arc<DashMap<MarketKey, MarketState>>,
+    cache: Arc<DashMap<MarketKey, Arc<MarketState>>>,
+    origin: Instant,
+    /// Micros since `origin` of the last write, plus one (0 = never written).
+    last_update_us: Arc<AtomicU64>,
+    last_update_by_venue: Arc<DashMap<Venue, Instant>>,
 }
 
 /// Shared handle to the cache — just a cheap Arc clone.
 pub type MarketCacheHandle = MarketCache;
 
+impl Default for MarketCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MarketCache {
     pub fn new() -> Self {
         MarketCache {
             cache: Arc::new(DashMap::new()),
+            origin: Instant::now(),
+            last_update_us: Arc::new(AtomicU64::new(0)),
+            last_update_by_venue: Arc::new(DashMap::new()),
         }
     }
 
     pub fn update_market_state(&self, key: MarketKey, state: MarketState) {
-        self.cache.insert(key, state);
+        self.touch(&key.0);
+        self.cache.insert(key, Arc::new(state));
     }
 
     /// Merge a partial update into an existing entry, or insert if none exists.
     /// Only overwrites fields that are `Some` in the incoming state.
-    pub fn update_partial(&self, key: MarketKey, update: MarketState) {
-        self.cache
-            .entry(key)
-            .and_modify(|existing| existing.merge(&update))
-            .or_insert(update);
+    pub fn update_partial(&self, key: MarketKey, update: MarketUpdate) {
+        self.touch(&key.0);
+        merge_entry(&self.cache, key, update);
+    }
+
+    /// Merge several partial updates, as [`update_partial`](Self::update_partial)
+    /// does for one. The last-update clocks are stamped once per venue rather
+    /// than once per entry, after every entry is in place.
+    pub fn update_batch<'a>(&self, updates: impl IntoIterator<Item = (MarketKey, MarketUpdate<'a>)>) {
+        let mut touched: Option<Venue> = None;
+        for (key, update) in updates {
+            match touched {
+                Some(ref venue) if *venue == key.0 => {}
+                Some(ref venue) => {
+                    self.touch(venue);
+                    touched = Some(key.0.clone());
+                }
+                None => touched = Some(key.0.clone()),
+            }
+            merge_entry(&self.cache, key, update);
+        }
+        if let Some(venue) = touched {
+            self.touch(&venue);
+        }
+    }
+
+    /// Wall time since the last write, or `None` if nothing has been written yet.
+    pub fn since_last_update(&self) -> Option<Duration> {
+        match self.last_update_us.load(Ordering::Relaxed) {
+            0 => None,
+            us => Some(self.origin.elapsed().saturating_sub(Duration::from_micros(us - 1))),
+        }
+    }
+
+    /// Wall time since each venue's last write. Venues never written are absent.
+    pub fn since_last_update_by_venue(&self) -> Vec<(Venue, Duration)> {
+        self.last_update_by_venue
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().elapsed()))
+            .collect()
+    }
+
+    fn touch(&self, venue: &Venue) {
+        let now = Instant::now();
+        let us = now.duration_since(self.origin).as_micros() as u64 + 1;
+        self.last_update_us.store(us, Ordering::Relaxed);
+        match self.last_update_by_venue.get_mut(venue) {
+            Some(mut at) => *at = now,
+            None => {
+                self.last_update_by_venue.insert(venue.clone(), now);
+            }
+        }
     }
 
     pub fn get_market_state(&self, key: &MarketKey) -> Option<MarketState> {
-        self.cache.get(key).map(|entry| entry.value().clone())
+        self.cache.get(key).map(|entry| MarketState::clone(entry.value()))
+    }
+
+    /// The current state for `key`, shared rather than copied. Later writes
+    /// don't change it, so a reader sees one consistent state however long
+    /// it holds on. Used on the strategy path, where every notification
+    /// reads one or more states.
+    pub fn get_shared(&self, key: &MarketKey) -> Option<Arc<MarketState>> {
+        self.cache.get(key).map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// Copy of every entry in the cache.
+    pub fn all(&self) -> Vec<(MarketKey, MarketState)> {
+        self.cache
+            .iter()
+            .map(|entry| (entry.key().clone(), MarketState::clone(entry.value())))
+            .collect()
     }
 
     pub fn get_markets_by_venue(&self, venue: &Venue) -> Vec<(MarketKey, MarketState)> {
         self.cache
             .iter()
             .filter(|entry| &entry.key().0 == venue)
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .map(|entry| (entry.key().clone(), MarketState::clone(entry.value())))
             .collect()
     }
 }
 
+/// Merge `update` into the entry for `key`, copying the state first only if
+/// a reader still holds it.
+fn merge_entry(cache: &DashMap<MarketKey, Arc<MarketState>>, key: MarketKey, update: MarketUpdate) {
+    let mut entry = cache.entry(key).or_default();
+    Arc::make_mut(entry.value_mut()).merge(&update);
+}
+
 /// Merge a partial market state update into the cache.
 /// Only overwrites fields present in the incoming event; preserves existing values otherwise.
 /// No async lock required — DashMap handles synchronization internally.
-pub fn insert(handle: &MarketCacheHandle, key: MarketKey, state: MarketState) {
-    handle.update_partial(key, state);
+pub fn insert(handle: &MarketCacheHandle, key: MarketKey, update: MarketUpdate) {
+    handle.update_partial(key, update);
 }
@@ -1,2 +1,10 @@
+pub mod book;
 pub mod market;
 pub mod market_cache;
+pub mod ids;
+pub mod equity;
+pub mod fees;
+pub mod pnl;
+pub mod portfolio;
+pub mod rewards;
+pub mod position;
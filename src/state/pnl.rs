@@ -0,0 +1,97 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use crate::market_data::types::Venue;
+use crate::state::market::{as_f64, MarketState};
+use crate::state::position::{CostBasisMethod, LotClose, Position};
+
+/// Aggregate PnL across all positions.
+/// `realized`/`unrealized` are gross of fees; `total` is net.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PnlSummary {
+    pub realized: f64,
+    pub unrealized: f64,
+    pub taker_fees: f64,
+    pub maker_rebates: f64,
+    /// Estimated liquidity rewards — reported separately, not included in `total`.
+    pub rewards_estimate: f64,
+    pub total: f64,
+}
+
+/// Price at which a position could be exited right now.
+///
+/// Longs are marked at the best bid and shorts at the best ask (liquidation
+/// value, not mid). Falls back to the mid, then to `None` if the book is empty.
+pub fn mark_price(position: &Position, state: Option<&MarketState>) -> Option<f64> {
+    let state = state?;
+    let exit = if position.size > 0.0 { state.best_bid } else { state.best_ask };
+    exit.or(match (state.best_bid, state.best_ask) {
+        (Some(b), Some(a)) => Some((b + a) / Decimal::TWO),
+        _ => None,
+    })
+    .map(as_f64)
+}
+
+/// Unrealized PnL of a position at `mark`. Unpriced positions contribute zero.
+pub fn unrealized_pnl(position: &Position, mark: Option<f64>) -> f64 {
+    match mark {
+        Some(m) => (m - position.avg_price) * position.size,
+        None => 0.0,
+    }
+}
+
+// ── Ledger ───────────────────────────────────────────────────────
+
+/// One closed (or partially closed) lot, with enough context to audit it.
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerEntry {
+    pub venue: Venue,
+    pub token_id: String,
+    pub method: CostBasisMethod,
+    #[serde(flatten)]
+    pub close: LotClose,
+}
+
+/// Append-only record of every lot closure. Shared across clones.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    entries: Arc<Mutex<Vec<LedgerEntry>>>,
+}
+
+impl Ledger {
+    pub fn record(&self, entry: LedgerEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub fn entries(&self) -> Vec<LedgerEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Write the ledger as CSV, one row per lot closure.
+    pub fn export_csv(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            out,
+            "venue,token_id,method,direction,size,open_price,close_price,opened_at_ms,closed_at_ms,realized_pnl"
+        )?;
+        for e in self.entries.lock().unwrap().iter() {
+            writeln!(
+                out,
+                "{:?},{},{:?},{},{},{},{},{},{},{}",
+                e.venue,
+                e.token_id,
+                e.method,
+                if e.close.direction > 0.0 { "long" } else { "short" },
+                e.close.size,
+                e.close.open_price,
+                e.close.close_price,
+                e.close.opened_at_ms,
+                e.close.closed_at_ms,
+                e.close.realized_pnl,
+            )?;
+        }
+        out.flush()
+    }
+}
@@ -0,0 +1,282 @@
+use crate::market_data::adapters::polymarket::TokenToMarket;
+use crate::market_data::types::{Side, Venue};
+use crate::state::fees::{FeeAccruals, FeeSchedule, Liquidity, StrategyPnl};
+use crate::state::ids::{MarketId, TokenId};
+use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::pnl::{mark_price, unrealized_pnl, PnlSummary};
+use crate::state::position::PositionTracker;
+use crate::state::rewards::RewardsBook;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One leg of an order that has been handed to an executor but not yet reported.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenOrderLeg {
+    pub token_id: TokenId,
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenOrder {
+    pub id: u64,
+    pub venue: Venue,
+    pub market_id: MarketId,
+    pub strategy: String,
+    pub legs: Vec<OpenOrderLeg>,
+    pub submitted_at_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionSnapshot {
+    pub venue: Venue,
+    pub token_id: String,
+    pub market_id: Option<String>,
+    pub size: f64,
+    pub avg_price: f64,
+    pub mark_price: Option<f64>,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    /// |size| × mark (falls back to avg price when unpriced).
+    pub exposure: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Balances {
+    pub starting_cash: f64,
+    pub cash: f64,
+    /// cash + signed position value at mark (avg price when unpriced).
+    pub equity: f64,
+}
+
+/// Point-in-time view of the whole portfolio, assembled on demand.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioSnapshot {
+    pub taken_at_ms: u64,
+    pub positions: Vec<PositionSnapshot>,
+    pub open_orders: Vec<OpenOrder>,
+    pub balances: Balances,
+    pub pnl: PnlSummary,
+    /// Gross vs net realized PnL, fees, and rebates per strategy.
+    pub pnl_by_strategy: HashMap<String, StrategyPnl>,
+    pub exposure_by_venue: HashMap<String, f64>,
+    /// Exposure summed per correlation group (defaults to one group per market).
+    pub exposure_by_group: HashMap<String, f64>,
+}
+
+/// Owns cash, positions, and in-flight orders.
+/// Cheap to clone — every field is shared behind an `Arc`.
+#[derive(Clone)]
+pub struct Portfolio {
+    positions: PositionTracker,
+    cache: MarketCache,
+    token_to_market: Arc<TokenToMarket>,
+    /// market_id → correlation group. Markets not listed form their own group.
+    correlation_groups: Arc<HashMap<String, String>>,
+    starting_cash: f64,
+    cash: Arc<Mutex<f64>>,
+    fee_schedules: Arc<HashMap<Venue, FeeSchedule>>,
+    accruals: FeeAccruals,
+    /// Liquidity rewards strategies estimate they earned while quoting.
+    rewards: RewardsBook,
+    open_orders: Arc<DashMap<u64, OpenOrder>>,
+    next_order_ref: Arc<AtomicU64>,
+}
+
+impl Portfolio {
+    pub fn new(
+        positions: PositionTracker,
+        cache: MarketCache,
+        token_to_market: Arc<TokenToMarket>,
+        starting_cash: f64,
+    ) -> Self {
+        Self {
+            positions,
+            cache,
+            token_to_market,
+            correlation_groups: Arc::new(HashMap::new()),
+            starting_cash,
+            cash: Arc::new(Mutex::new(starting_cash)),
+            fee_schedules: Arc::new(HashMap::new()),
+            accruals: FeeAccruals::default(),
+            rewards: RewardsBook::default(),
+            open_orders: Arc::new(DashMap::new()),
+            next_order_ref: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Sum exposure per group in `groups` (market id → group) rather than per market.
+    pub fn with_correlation_groups(mut self, groups: HashMap<String, String>) -> Self {
+        self.correlation_groups = Arc::new(groups);
+        self
+    }
+
+    /// Fee models per venue. Venues without a schedule trade fee-free.
+    pub fn with_fee_schedules(mut self, schedules: HashMap<Venue, FeeSchedule>) -> Self {
+        self.fee_schedules = Arc::new(schedules);
+        self
+    }
+
+    /// Report the quoting-time rewards accrued in `rewards` alongside the
+    /// per-fill estimates from the fee schedules.
+    pub fn with_rewards(mut self, rewards: RewardsBook) -> Self {
+        self.rewards = rewards;
+        self
+    }
+
+    pub fn positions(&self) -> &PositionTracker {
+        &self.positions
+    }
+
+    /// Apply a fill to positions and cash, accruing fees and rebates
+    /// against the strategy that generated it.
+    pub fn apply_fill(
+        &self,
+        key: MarketKey,
+        strategy: &str,
+        liquidity: Liquidity,
+        side: &Side,
+        price: f64,
+        size: f64,
+    ) {
+        self.apply_fill_at(key, strategy, liquidity, side, price, size, unix_ms());
+    }
+
+    /// [`Self::apply_fill`] with an explicit fill time, for journal replay.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_fill_at(
+        &self,
+        key: MarketKey,
+        strategy: &str,
+        liquidity: Liquidity,
+        side: &Side,
+        price: f64,
+        size: f64,
+        ts_ms: u64,
+    ) {
+        let notional = price * size;
+        let charges = self
+            .fee_schedules
+            .get(&key.0)
+            .map(|schedule| schedule.charges(liquidity, notional))
+            .unwrap_or_default();
+
+        {
+            let mut cash = self.cash.lock().unwrap();
+            match side {
+                Side::Buy => *cash -= notional,
+                Side::Sell => *cash += notional,
+            }
+            *cash += charges.maker_rebate - charges.taker_fee;
+        }
+
+        let realized = self.positions.apply_fill_at(key, side, price, size, ts_ms);
+        self.accruals.record(strategy, notional, realized, charges);
+    }
+
+    /// Record an order as in flight. Returns a reference to pass to [`Self::complete_order`].
+    pub fn register_open_order(
+        &self,
+        venue: Venue,
+        market_id: MarketId,
+        strategy: &str,
+        legs: Vec<OpenOrderLeg>,
+    ) -> u64 {
+        let id = self.next_order_ref.fetch_add(1, Ordering::Relaxed);
+        self.open_orders.insert(id, OpenOrder {
+            id,
+            venue,
+            market_id,
+            strategy: strategy.to_string(),
+            legs,
+            submitted_at_ms: unix_ms(),
+        });
+        id
+    }
+
+    pub fn complete_order(&self, id: u64) {
+        self.open_orders.remove(&id);
+    }
+
+    pub fn cash(&self) -> f64 {
+        *self.cash.lock().unwrap()
+    }
+
+    pub fn snapshot(&self) -> PortfolioSnapshot {
+        let mut positions = Vec::new();
+        let mut pnl = PnlSummary::default();
+        let mut exposure_by_venue: HashMap<String, f64> = HashMap::new();
+        let mut exposure_by_group: HashMap<String, f64> = HashMap::new();
+        let mut position_value = 0.0;
+
+        for (key, position) in self.positions.all() {
+            let state = self.cache.get_market_state(&key);
+            let mark = mark_price(&position, state.as_ref());
+            let unrealized = unrealized_pnl(&position, mark);
+            let exposure = position.size.abs() * mark.unwrap_or(position.avg_price);
+            position_value += position.size * mark.unwrap_or(position.avg_price);
+            let market_id = self.token_to_market.get(key.1.as_str()).cloned();
+
+            pnl.realized += position.realized_pnl;
+            pnl.unrealized += unrealized;
+
+            *exposure_by_venue.entry(format!("{:?}", key.0)).or_default() += exposure;
+            let group = market_id
+                .as_ref()
+                .map(|m| self.correlation_groups.get(m).cloned().unwrap_or_else(|| m.clone()))
+                .unwrap_or_else(|| key.1.to_string());
+            *exposure_by_group.entry(group).or_default() += exposure;
+
+            positions.push(PositionSnapshot {
+                venue: key.0,
+                token_id: key.1.to_string(),
+                market_id,
+                size: position.size,
+                avg_price: position.avg_price,
+                mark_price: mark,
+                realized_pnl: position.realized_pnl,
+                unrealized_pnl: unrealized,
+                exposure,
+            });
+        }
+        let mut pnl_by_strategy = self.accruals.by_strategy();
+        for (strategy, accrued) in self.rewards.accrued() {
+            pnl_by_strategy.entry(strategy).or_default().rewards_estimate += accrued;
+        }
+        for line in pnl_by_strategy.values() {
+            pnl.taker_fees += line.taker_fees;
+            pnl.maker_rebates += line.maker_rebates;
+            pnl.rewards_estimate += line.rewards_estimate;
+        }
+        pnl.total = pnl.realized + pnl.unrealized - pnl.taker_fees + pnl.maker_rebates;
+
+        let cash = self.cash();
+
+        PortfolioSnapshot {
+            taken_at_ms: unix_ms(),
+            positions,
+            open_orders: self.open_orders.iter().map(|e| e.value().clone()).collect(),
+            balances: Balances {
+                starting_cash: self.starting_cash,
+                cash,
+                equity: cash + position_value,
+            },
+            pnl,
+            pnl_by_strategy,
+            exposure_by_venue,
+            exposure_by_group,
+        }
+    }
+}
+
+pub(crate) fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
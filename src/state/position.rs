@@ -0,0 +1,220 @@
+use crate::market_data::types::{Side, Venue};
+use crate::state::ids::TokenId;
+use crate::state::market_cache::MarketKey;
+use crate::state::pnl::{Ledger, LedgerEntry};
+use crate::state::portfolio::unix_ms;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// How the cost basis of a partial exit is chosen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasisMethod {
+    /// Close the oldest open lots first.
+    #[default]
+    Fifo,
+    /// Close against the average price of all open lots.
+    AverageCost,
+}
+
+/// A block of size opened by a single fill. Size is always positive;
+/// direction is given by the sign of the owning position.
+#[derive(Clone, Debug, Serialize)]
+pub struct Lot {
+    pub size: f64,
+    pub price: f64,
+    pub opened_at_ms: u64,
+}
+
+/// The portion of a lot closed by an exit fill.
+#[derive(Clone, Debug, Serialize)]
+pub struct LotClose {
+    pub size: f64,
+    pub open_price: f64,
+    pub close_price: f64,
+    pub opened_at_ms: u64,
+    pub closed_at_ms: u64,
+    /// +1.0 if the closed lot was long, -1.0 if short.
+    pub direction: f64,
+    pub realized_pnl: f64,
+}
+
+/// Net holding in a single outcome token.
+#[derive(Clone, Debug, Default)]
+pub struct Position {
+    /// Signed net size: positive = long, negative = short.
+    pub size: f64,
+    /// Average entry price of the currently open size.
+    pub avg_price: f64,
+    /// PnL locked in by reducing or flipping the position.
+    pub realized_pnl: f64,
+    /// Open lots, oldest first.
+    pub lots: VecDeque<Lot>,
+}
+
+impl Position {
+    /// Apply a fill. Adding to the position opens a new lot; reducing it closes
+    /// lots according to `method`; crossing through zero closes everything and
+    /// opens a fresh lot in the other direction for the remainder.
+    ///
+    /// Returns the lot closures this fill produced (empty when only adding).
+    pub fn apply_fill(
+        &mut self,
+        side: &Side,
+        price: f64,
+        size: f64,
+        method: CostBasisMethod,
+        now_ms: u64,
+    ) -> Vec<LotClose> {
+        let signed = match side {
+            Side::Buy => size,
+            Side::Sell => -size,
+        };
+
+        let mut closes = Vec::new();
+        let mut remaining = size;
+
+        if self.size != 0.0 && self.size.signum() != signed.signum() {
+            let direction = self.size.signum();
+
+            if method == CostBasisMethod::AverageCost {
+                self.merge_lots();
+            }
+
+            while remaining > 0.0 {
+                let Some(lot) = self.lots.front_mut() else { break };
+                let closed = remaining.min(lot.size);
+                let realized = closed * (price - lot.price) * direction;
+
+                closes.push(LotClose {
+                    size: closed,
+                    open_price: lot.price,
+                    close_price: price,
+                    opened_at_ms: lot.opened_at_ms,
+                    closed_at_ms: now_ms,
+                    direction,
+                    realized_pnl: realized,
+                });
+
+                self.realized_pnl += realized;
+                self.size -= closed * direction;
+                lot.size -= closed;
+                remaining -= closed;
+
+                if lot.size <= f64::EPSILON {
+                    self.lots.pop_front();
+                }
+            }
+
+            if self.lots.is_empty() {
+                self.size = 0.0;
+            }
+        }
+
+        if remaining > 0.0 {
+            self.lots.push_back(Lot { size: remaining, price, opened_at_ms: now_ms });
+            self.size += remaining * signed.signum();
+        }
+
+        self.avg_price = self.lots_avg_price();
+        closes
+    }
+
+    /// Collapse all open lots into one at their weighted average price,
+    /// keeping the oldest open timestamp.
+    fn merge_lots(&mut self) {
+        if self.lots.len() <= 1 {
+            return;
+        }
+        let total: f64 = self.lots.iter().map(|l| l.size).sum();
+        let opened_at_ms = self.lots.iter().map(|l| l.opened_at_ms).min().unwrap_or(0);
+        let price = self.lots_avg_price();
+        self.lots.clear();
+        self.lots.push_back(Lot { size: total, price, opened_at_ms });
+    }
+
+    fn lots_avg_price(&self) -> f64 {
+        let total: f64 = self.lots.iter().map(|l| l.size).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.lots.iter().map(|l| l.size * l.price).sum::<f64>() / total
+    }
+}
+
+/// Thread-safe position book, keyed the same way as the market cache.
+/// Written by the execution bridge on fills, read by strategies via `EvalContext`.
+#[derive(Clone, Debug, Default)]
+pub struct PositionTracker {
+    positions: Arc<DashMap<MarketKey, Position>>,
+    method: CostBasisMethod,
+    ledger: Ledger,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_method(method: CostBasisMethod) -> Self {
+        Self { method, ..Self::default() }
+    }
+
+    /// Apply a fill and return the PnL it realized.
+    pub fn apply_fill(&self, key: MarketKey, side: &Side, price: f64, size: f64) -> f64 {
+        self.apply_fill_at(key, side, price, size, unix_ms())
+    }
+
+    /// [`Self::apply_fill`] with an explicit fill time.
+    pub fn apply_fill_at(&self, key: MarketKey, side: &Side, price: f64, size: f64, ts_ms: u64) -> f64 {
+        let closes = self
+            .positions
+            .entry(key.clone())
+            .or_default()
+            .apply_fill(side, price, size, self.method, ts_ms);
+
+        let mut realized = 0.0;
+        for close in closes {
+            realized += close.realized_pnl;
+            self.ledger.record(LedgerEntry {
+                venue: key.0.clone(),
+                token_id: key.1.to_string(),
+                method: self.method,
+                close,
+            });
+        }
+        realized
+    }
+
+    pub fn get(&self, key: &MarketKey) -> Option<Position> {
+        self.positions.get(key).map(|entry| entry.value().clone())
+    }
+
+    /// Signed net size held in a single token (0.0 if flat / never traded).
+    pub fn inventory(&self, key: &MarketKey) -> f64 {
+        self.positions.get(key).map(|entry| entry.size).unwrap_or(0.0)
+    }
+
+    /// Net YES-equivalent exposure of a binary market.
+    /// Holding NO is economically equivalent to being short YES, so
+    /// `yes_size - no_size` is the inventory the market maker must manage.
+    pub fn binary_inventory(&self, venue: &Venue, yes_token_id: TokenId, no_token_id: TokenId) -> f64 {
+        let yes = self.inventory(&MarketKey(venue.clone(), yes_token_id));
+        let no = self.inventory(&MarketKey(venue.clone(), no_token_id));
+        yes - no
+    }
+
+    pub fn all(&self) -> Vec<(MarketKey, Position)> {
+        self.positions
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Closed-lot history, for audit and export.
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+}
@@ -0,0 +1,91 @@
+use dashmap::DashMap;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// One-sided quotes score a third as much while the mid is inside this band,
+/// and nothing outside it.
+const SINGLE_SIDED_MIN_MID: Decimal = Decimal::from_parts(10, 0, 0, false, 2);
+const SINGLE_SIDED_MAX_MID: Decimal = Decimal::from_parts(90, 0, 0, false, 2);
+const SINGLE_SIDED_DIVISOR: Decimal = Decimal::from_parts(3, 0, 0, false, 0);
+
+/// A market's liquidity-rewards program, as the CLOB reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RewardParams {
+    /// Orders further than this from the mid earn nothing (price units, 0.03 = 3¢).
+    #[serde(with = "rust_decimal::serde::float")]
+    pub max_spread: Decimal,
+    /// Orders smaller than this earn nothing.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub min_size: Decimal,
+    /// USDC paid per day, split across every qualifying maker.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub daily_rate: Decimal,
+}
+
+impl RewardParams {
+    /// Score of one order `distance` from the mid: `((v − s) / v)²`, zero at
+    /// or beyond the max spread `v`.
+    pub fn order_score(&self, distance: Decimal) -> Decimal {
+        if self.max_spread <= Decimal::ZERO || distance >= self.max_spread {
+            return Decimal::ZERO;
+        }
+        let x = (self.max_spread - distance.max(Decimal::ZERO)) / self.max_spread;
+        x * x
+    }
+
+    /// Combined score of a bid and an ask, each `None` when not quoted or too
+    /// small to qualify. Two-sided quoting scores the weaker side; one side
+    /// alone scores a third of itself, and only while the mid is away from
+    /// the edges.
+    pub fn quote_score(&self, mid: Decimal, bid: Option<Decimal>, ask: Option<Decimal>) -> Decimal {
+        let bid_score = bid.map_or(Decimal::ZERO, |b| self.order_score(mid - b));
+        let ask_score = ask.map_or(Decimal::ZERO, |a| self.order_score(a - mid));
+        let two_sided = bid_score.min(ask_score);
+        if mid < SINGLE_SIDED_MIN_MID || mid > SINGLE_SIDED_MAX_MID {
+            return two_sided;
+        }
+        two_sided.max(bid_score.max(ask_score) / SINGLE_SIDED_DIVISOR)
+    }
+}
+
+/// Per-market reward programs and the rewards strategies estimate they have
+/// earned. The program table is refreshed from the venue in the background;
+/// markets without an entry pay nothing. Shared across clones.
+#[derive(Debug, Clone, Default)]
+pub struct RewardsBook {
+    /// market_id → program.
+    params: Arc<RwLock<HashMap<String, RewardParams>>>,
+    /// strategy → estimated rewards accrued, in USDC.
+    accrued: Arc<DashMap<String, f64>>,
+}
+
+impl RewardsBook {
+    pub fn get(&self, market_id: &str) -> Option<RewardParams> {
+        self.params.read().unwrap().get(market_id).copied()
+    }
+
+    /// Swap in a freshly fetched program table.
+    pub fn replace(&self, params: HashMap<String, RewardParams>) {
+        *self.params.write().unwrap() = params;
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn accrue(&self, strategy: &str, amount: f64) {
+        if amount > 0.0 {
+            *self.accrued.entry(strategy.to_string()).or_default() += amount;
+        }
+    }
+
+    pub fn accrued(&self) -> HashMap<String, f64> {
+        self.accrued.iter().map(|e| (e.key().clone(), *e.value())).collect()
+    }
+}
@@ -1,7 +1,10 @@
 use crate::market_data::types::Side;
+use crate::state::ids::MarketId;
+use crate::state::market::{as_f64, to_decimal};
 use crate::state::market_cache::MarketKey;
+use crate::state::fees::Liquidity;
 use super::traits::{Strategy, TradeSignal, SignalLeg, EvalContext};
-use std::time::Instant;
+use rust_decimal::Decimal;
 use tracing::info;
 
 /// Detects cross-outcome arbitrage on binary prediction markets.
@@ -9,13 +12,14 @@ use tracing::info;
 /// Sell arb: YES_bid + NO_bid > 1.0 — sell both outcomes for guaranteed profit.
 /// Buy arb:  YES_ask + NO_ask < 1.0 — buy both outcomes for guaranteed profit.
 pub struct ArbitrageStrategy {
-    min_edge: f64,
-    default_size: f64,
+    min_edge: Decimal,
+    default_size: Decimal,
 }
 
 impl ArbitrageStrategy {
-    pub fn new(min_edge: f64, default_size: f64) -> Self {
-        Self { min_edge, default_size }
+    /// Fails if either value is not a finite number.
+    pub fn new(min_edge: f64, default_size: f64) -> anyhow::Result<Self> {
+        Ok(Self { min_edge: to_decimal(min_edge)?, default_size: to_decimal(default_size)? })
     }
 }
 
@@ -25,97 +29,112 @@ impl Strategy for ArbitrageStrategy {
     }
 
     fn evaluate(&self, ctx: &EvalContext) -> Option<TradeSignal> {
-        let token_id = &ctx.updated_key.1;
+        let token_id = ctx.updated_key.1;
         let venue = &ctx.updated_key.0;
 
         // Look up which market this token belongs to
-        let market_id = ctx.token_to_market.get(token_id)?;
+        let market_id = ctx.token_to_market.get(token_id.as_str())?;
         let info = ctx.market_map.get(market_id)?;
 
         // Read both YES and NO token states from cache
-        let yes_key = MarketKey(venue.clone(), info.yes_token_id.clone());
-        let no_key = MarketKey(venue.clone(), info.no_token_id.clone());
+        let yes_key = MarketKey(venue.clone(), info.yes_token_id);
+        let no_key = MarketKey(venue.clone(), info.no_token_id);
 
-        let yes_state = ctx.cache.get_market_state(&yes_key)?;
-        let no_state = ctx.cache.get_market_state(&no_key)?;
+        let mut trace = ctx.audit.trace(self.name(), market_id, token_id.as_str(), ctx.clock.unix_ms());
+        trace.value("min_edge", self.min_edge);
 
-        let yes_bid = yes_state.best_bid?;
-        let no_bid = no_state.best_bid?;
-        let yes_ask = yes_state.best_ask?;
-        let no_ask = no_state.best_ask?;
+        let (Some(yes_state), Some(no_state)) = (ctx.cache.get_shared(&yes_key), ctx.cache.get_shared(&no_key))
+        else {
+            return trace.reject("missing book for one outcome");
+        };
+
+        let (Some(yes_bid), Some(no_bid), Some(yes_ask), Some(no_ask)) =
+            (yes_state.best_bid, no_state.best_bid, yes_state.best_ask, no_state.best_ask)
+        else {
+            return trace.reject("missing bid or ask on one outcome");
+        };
+        trace.value("yes_bid", yes_bid).value("no_bid", no_bid).value("yes_ask", yes_ask).value("no_ask", no_ask);
 
         // Sell arb: sell YES + sell NO when combined bids exceed 1.0
-        let sell_edge = yes_bid + no_bid - 1.0;
+        let sell_edge = yes_bid + no_bid - Decimal::ONE;
+        // Buy arb: buy YES + buy NO when combined asks are below 1.0
+        let buy_edge = Decimal::ONE - (yes_ask + no_ask);
+        trace.value("sell_edge", sell_edge).value("buy_edge", buy_edge);
+
         if sell_edge >= self.min_edge {
             info!(
                 market_id = %market_id,
-                yes_bid, no_bid, yes_ask, no_ask,
-                edge = sell_edge,
+                %yes_bid, %no_bid, %yes_ask, %no_ask,
+                edge = %sell_edge,
                 arb_type = "sell",
                 yes_token = %info.yes_token_id,
                 no_token = %info.no_token_id,
                 "arb detected"
             );
-            return Some(TradeSignal {
+            return trace.signal(TradeSignal {
                 strategy_name: self.name(),
                 venue: venue.clone(),
-                market_id: market_id.clone(),
+                market_id: MarketId::intern(market_id),
                 legs: vec![
                     SignalLeg {
-                        token_id: info.yes_token_id.clone(),
+                        token_id: info.yes_token_id,
                         side: Side::Sell,
                         price: yes_bid,
                         size: self.default_size,
                     },
                     SignalLeg {
-                        token_id: info.no_token_id.clone(),
+                        token_id: info.no_token_id,
                         side: Side::Sell,
                         price: no_bid,
                         size: self.default_size,
                     },
                 ],
-                edge: sell_edge,
-                generated_at: Instant::now(),
+                edge: as_f64(sell_edge),
+                liquidity: Liquidity::Taker,
+                generated_at: ctx.clock.now(),
                 ws_received_at: ctx.ws_received_at,
+                stages: None,
+                reply: None,
             });
         }
 
-        // Buy arb: buy YES + buy NO when combined asks are below 1.0
-        let buy_edge = 1.0 - (yes_ask + no_ask);
         if buy_edge >= self.min_edge {
             info!(
                 market_id = %market_id,
-                yes_bid, no_bid, yes_ask, no_ask,
-                edge = buy_edge,
+                %yes_bid, %no_bid, %yes_ask, %no_ask,
+                edge = %buy_edge,
                 arb_type = "buy",
                 yes_token = %info.yes_token_id,
                 no_token = %info.no_token_id,
                 "arb detected"
             );
-            return Some(TradeSignal {
+            return trace.signal(TradeSignal {
                 strategy_name: self.name(),
                 venue: venue.clone(),
-                market_id: market_id.clone(),
+                market_id: MarketId::intern(market_id),
                 legs: vec![
                     SignalLeg {
-                        token_id: info.yes_token_id.clone(),
+                        token_id: info.yes_token_id,
                         side: Side::Buy,
                         price: yes_ask,
                         size: self.default_size,
                     },
                     SignalLeg {
-                        token_id: info.no_token_id.clone(),
+                        token_id: info.no_token_id,
                         side: Side::Buy,
                         price: no_ask,
                         size: self.default_size,
                     },
                 ],
-                edge: buy_edge,
-                generated_at: Instant::now(),
+                edge: as_f64(buy_edge),
+                liquidity: Liquidity::Taker,
+                generated_at: ctx.clock.now(),
                 ws_received_at: ctx.ws_received_at,
+                stages: None,
+                reply: None,
             });
         }
 
-        None
+        trace.reject("edge below threshold")
     }
 }
@@ -0,0 +1,165 @@
+use dashmap::DashMap;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use tracing::debug;
+
+use super::traits::TradeSignal;
+
+/// Ring buffer depth per market when not configured.
+pub const DEFAULT_AUDIT_DEPTH: usize = 200;
+
+/// How one strategy evaluation ended.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum DecisionOutcome {
+    Signal { edge: f64 },
+    Rejected { reason: &'static str },
+}
+
+/// The intermediate values one strategy computed for one market update.
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub ts_ms: u64,
+    pub strategy: &'static str,
+    pub market_id: String,
+    pub token_id: String,
+    /// Named inputs and derived values (bids, asks, edges, thresholds, ...).
+    pub values: BTreeMap<&'static str, f64>,
+    #[serde(flatten)]
+    pub outcome: DecisionOutcome,
+}
+
+/// Per-market decision log answering "why didn't it trade?".
+///
+/// Only markets that are being watched are recorded; everything else costs a
+/// map lookup per evaluation. Markets are watched from config at startup or
+/// toggled at runtime through the admin API. Each watched market keeps its
+/// last `depth` decisions across all strategies, and every decision is also
+/// logged at debug level.
+#[derive(Clone, Default)]
+pub struct DecisionAudit {
+    inner: Option<Arc<AuditInner>>,
+}
+
+struct AuditInner {
+    depth: usize,
+    /// Record every market, not just the watched ones.
+    all: bool,
+    /// Watched market_id → recent decisions, newest last.
+    decisions: DashMap<String, VecDeque<Decision>>,
+}
+
+impl DecisionAudit {
+    /// `markets` are watched from the start; `all` watches every market.
+    pub fn new(depth: usize, all: bool, markets: impl IntoIterator<Item = String>) -> Self {
+        let decisions = DashMap::new();
+        for market_id in markets {
+            decisions.insert(market_id, VecDeque::new());
+        }
+        Self { inner: Some(Arc::new(AuditInner { depth: depth.max(1), all, decisions })) }
+    }
+
+    /// An audit that never records and can't be enabled (backtests).
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn is_watched(&self, market_id: &str) -> bool {
+        self.inner.as_ref().is_some_and(|inner| inner.all || inner.decisions.contains_key(market_id))
+    }
+
+    /// Start recording `market_id`. Returns false if auditing is disabled.
+    pub fn watch(&self, market_id: &str) -> bool {
+        let Some(inner) = &self.inner else { return false };
+        inner.decisions.entry(market_id.to_string()).or_default();
+        true
+    }
+
+    /// Stop recording `market_id` and drop its history.
+    pub fn unwatch(&self, market_id: &str) {
+        if let Some(inner) = &self.inner {
+            inner.decisions.remove(market_id);
+        }
+    }
+
+    /// Markets with recorded history or an explicit watch.
+    pub fn watched(&self) -> Vec<String> {
+        let Some(inner) = &self.inner else { return Vec::new() };
+        let mut markets: Vec<String> = inner.decisions.iter().map(|e| e.key().clone()).collect();
+        markets.sort();
+        markets
+    }
+
+    /// Recent decisions for `market_id`, oldest first. `None` if not watched.
+    pub fn decisions(&self, market_id: &str) -> Option<Vec<Decision>> {
+        let inner = self.inner.as_ref()?;
+        inner.decisions.get(market_id).map(|d| d.iter().cloned().collect())
+    }
+
+    /// Begin tracing one evaluation. A no-op trace when `market_id` isn't watched.
+    pub fn trace(&self, strategy: &'static str, market_id: &str, token_id: &str, ts_ms: u64) -> Trace<'_> {
+        let decision = self.is_watched(market_id).then(|| Decision {
+            ts_ms,
+            strategy,
+            market_id: market_id.to_string(),
+            token_id: token_id.to_string(),
+            values: BTreeMap::new(),
+            outcome: DecisionOutcome::Rejected { reason: "" },
+        });
+        Trace { audit: self, decision }
+    }
+
+    fn record(&self, decision: Decision) {
+        let Some(inner) = &self.inner else { return };
+        debug!(
+            strategy = decision.strategy,
+            market_id = %decision.market_id,
+            token_id = %decision.token_id,
+            values = ?decision.values,
+            outcome = ?decision.outcome,
+            "strategy decision"
+        );
+        let mut history = inner.decisions.entry(decision.market_id.clone()).or_default();
+        if history.len() >= inner.depth {
+            history.pop_front();
+        }
+        history.push_back(decision);
+    }
+}
+
+/// An in-progress decision. Strategies add values as they compute them and
+/// finish with [`Trace::reject`] or [`Trace::signal`].
+pub struct Trace<'a> {
+    audit: &'a DecisionAudit,
+    decision: Option<Decision>,
+}
+
+impl Trace<'_> {
+    /// Record a named value; prices and edges may be passed as `Decimal`.
+    pub fn value(&mut self, name: &'static str, value: impl ToPrimitive) -> &mut Self {
+        if let Some(decision) = &mut self.decision {
+            decision.values.insert(name, value.to_f64().unwrap_or(f64::NAN));
+        }
+        self
+    }
+
+    /// Record the rejection; always returns `None` so it can end `evaluate`.
+    pub fn reject(self, reason: &'static str) -> Option<TradeSignal> {
+        if let Some(mut decision) = self.decision {
+            decision.outcome = DecisionOutcome::Rejected { reason };
+            self.audit.record(decision);
+        }
+        None
+    }
+
+    /// Record the emitted signal and pass it through.
+    pub fn signal(self, signal: TradeSignal) -> Option<TradeSignal> {
+        if let Some(mut decision) = self.decision {
+            decision.outcome = DecisionOutcome::Signal { edge: signal.edge };
+            self.audit.record(decision);
+        }
+        Some(signal)
+    }
+}
@@ -0,0 +1,319 @@
+use crate::market_data::types::Side;
+use crate::state::ids::MarketId;
+use crate::state::market::{as_f64, to_decimal};
+use crate::state::market_cache::MarketKey;
+use crate::state::fees::Liquidity;
+use crate::state::rewards::{RewardParams, RewardsBook};
+use super::traits::{Strategy, TradeSignal, SignalLeg, EvalContext};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::debug;
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// What the market maker optimizes its quotes for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteMode {
+    /// Capture the spread: quote `half_spread` around the reservation price.
+    #[default]
+    Spread,
+    /// Earn liquidity rewards: in markets with a rewards program, tighten
+    /// quotes into the scoring band and size them up to the minimum that
+    /// qualifies, within `max_inventory`. Markets without one quote as `Spread`.
+    Rewards,
+}
+
+/// Tuning knobs for [`MarketMakerStrategy`].
+#[derive(Debug, Clone)]
+pub struct MarketMakerConfig {
+    /// Half-spread quoted around the reservation price when flat.
+    pub half_spread: f64,
+    pub quote_size: f64,
+    /// Inventory (in YES-equivalent shares) at which skew and widening saturate
+    /// and the side that would add more risk stops being quoted.
+    pub max_inventory: f64,
+    /// How far the reservation price shifts at full inventory, as a multiple of `half_spread`.
+    pub skew_factor: f64,
+    /// How much the half-spread grows at full inventory (0.5 = 50% wider).
+    pub widen_factor: f64,
+    pub tick_size: f64,
+    pub mode: QuoteMode,
+    /// In `Rewards` mode, the half-spread as a fraction of the market's max
+    /// reward spread. Tighter scores more per order but is picked off more.
+    pub reward_spread_fraction: f64,
+    /// Share of a market's daily reward pool assumed earned at a perfect
+    /// score. Only feeds the accrued-rewards estimate.
+    pub reward_share: f64,
+}
+
+impl Default for MarketMakerConfig {
+    fn default() -> Self {
+        Self {
+            half_spread: 0.01,
+            quote_size: 5.0,
+            max_inventory: 50.0,
+            skew_factor: 1.0,
+            widen_factor: 0.5,
+            tick_size: 0.01,
+            mode: QuoteMode::Spread,
+            reward_spread_fraction: 0.5,
+            reward_share: 0.1,
+        }
+    }
+}
+
+/// [`MarketMakerConfig`] as exact decimals, so quotes land on ticks.
+#[derive(Debug, Clone, Copy)]
+struct Params {
+    half_spread: Decimal,
+    quote_size: Decimal,
+    max_inventory: Decimal,
+    skew_factor: Decimal,
+    widen_factor: Decimal,
+    tick_size: Decimal,
+    mode: QuoteMode,
+    reward_spread_fraction: Decimal,
+    reward_share: f64,
+}
+
+/// Two-sided quote for the YES token of a market.
+/// A side is `None` when inventory limits forbid quoting it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+}
+
+/// Quotes the YES token of binary markets around the mid, skewed by inventory.
+///
+/// Inventory is read from the position tracker as net YES exposure
+/// (`yes_size - no_size`). When long, the reservation price shifts down so the
+/// bid becomes less aggressive and the ask more aggressive; when short, the
+/// opposite. The spread also widens as |inventory| grows, and at `max_inventory`
+/// the side that would add to the position is pulled entirely.
+///
+/// In markets with a liquidity-rewards program the last quote is assumed to
+/// rest until the next one, and its reward score over that time is accrued
+/// into `rewards` as an estimate, whatever the [`QuoteMode`].
+pub struct MarketMakerStrategy {
+    params: Params,
+    /// Last quote emitted per market — only re-quote when it actually changes.
+    last_quotes: Mutex<HashMap<String, Resting>>,
+    rewards: RewardsBook,
+}
+
+/// A quote assumed resting on the book, and since when its rewards were last accrued.
+#[derive(Debug, Clone, Copy)]
+struct Resting {
+    quote: Quote,
+    size: Decimal,
+    accrued_to: Instant,
+}
+
+impl MarketMakerStrategy {
+    /// Fails if a knob is not a finite number or the tick size isn't positive.
+    pub fn new(config: MarketMakerConfig) -> anyhow::Result<Self> {
+        let params = Params {
+            half_spread: to_decimal(config.half_spread)?,
+            quote_size: to_decimal(config.quote_size)?,
+            max_inventory: to_decimal(config.max_inventory)?,
+            skew_factor: to_decimal(config.skew_factor)?,
+            widen_factor: to_decimal(config.widen_factor)?,
+            tick_size: to_decimal(config.tick_size)?,
+            mode: config.mode,
+            reward_spread_fraction: to_decimal(config.reward_spread_fraction)?,
+            reward_share: config.reward_share,
+        };
+        anyhow::ensure!(params.tick_size > Decimal::ZERO, "market maker tick_size must be positive");
+        Ok(Self { params, last_quotes: Mutex::new(HashMap::new()), rewards: RewardsBook::default() })
+    }
+
+    /// Read reward programs from, and accrue estimated rewards into, `rewards`.
+    pub fn with_rewards(mut self, rewards: RewardsBook) -> Self {
+        self.rewards = rewards;
+        self
+    }
+
+    /// Compute the skewed quote for a given top-of-book and inventory.
+    pub fn compute_quote(&self, best_bid: Decimal, best_ask: Decimal, inventory: Decimal) -> Quote {
+        self.quote_within(best_bid, best_ask, inventory, None)
+    }
+
+    /// Like [`compute_quote`](Self::compute_quote), aiming to earn `program`'s
+    /// rewards: the half-spread is capped at `reward_spread_fraction` of its
+    /// max spread. Inventory skew and limits apply as usual.
+    pub fn compute_reward_quote(&self, best_bid: Decimal, best_ask: Decimal, inventory: Decimal, program: &RewardParams) -> Quote {
+        let cap = (program.max_spread * self.params.reward_spread_fraction).max(self.params.tick_size);
+        self.quote_within(best_bid, best_ask, inventory, Some(cap))
+    }
+
+    /// Size to quote at: in `Rewards` mode, raised to the program's minimum
+    /// when that still fits within `max_inventory`.
+    fn quote_size(&self, program: Option<&RewardParams>) -> Decimal {
+        let p = &self.params;
+        match program {
+            Some(r) if p.mode == QuoteMode::Rewards && r.min_size <= p.max_inventory => p.quote_size.max(r.min_size),
+            _ => p.quote_size,
+        }
+    }
+
+    /// Estimated rewards `resting` earned from its last accrual until `now`,
+    /// scored against the current `mid`. Orders below the program's minimum
+    /// size score nothing.
+    fn accrued_reward(&self, resting: &Resting, program: &RewardParams, mid: Decimal, now: Instant) -> f64 {
+        if resting.size < program.min_size {
+            return 0.0;
+        }
+        let score = program.quote_score(mid, resting.quote.bid, resting.quote.ask);
+        let days = now.saturating_duration_since(resting.accrued_to).as_secs_f64() / SECS_PER_DAY;
+        as_f64(program.daily_rate * score) * self.params.reward_share * days
+    }
+
+    fn quote_within(&self, best_bid: Decimal, best_ask: Decimal, inventory: Decimal, max_half: Option<Decimal>) -> Quote {
+        let p = &self.params;
+        let mid = (best_bid + best_ask) / Decimal::TWO;
+
+        // Normalised inventory in [-1, 1].
+        let q = if p.max_inventory > Decimal::ZERO {
+            (inventory / p.max_inventory).clamp(Decimal::NEGATIVE_ONE, Decimal::ONE)
+        } else {
+            Decimal::ZERO
+        };
+
+        let reservation = mid - p.skew_factor * p.half_spread * q;
+        let mut half = p.half_spread * (Decimal::ONE + p.widen_factor * q.abs());
+        if let Some(max_half) = max_half {
+            half = half.min(max_half);
+        }
+
+        let tick = p.tick_size;
+        let floor_tick = |x: Decimal| (x / tick).floor() * tick;
+        let ceil_tick = |x: Decimal| (x / tick).ceil() * tick;
+
+        // Stay passive: never cross the opposite side of the book.
+        let bid = floor_tick(reservation - half).min(best_ask - tick).max(tick);
+        let ask = ceil_tick(reservation + half).max(best_bid + tick).min(Decimal::ONE - tick);
+
+        let at_limit = inventory.abs() >= p.max_inventory;
+        Quote {
+            bid: (!(at_limit && inventory > Decimal::ZERO)).then_some(bid),
+            ask: (!(at_limit && inventory < Decimal::ZERO)).then_some(ask),
+        }
+    }
+}
+
+impl Strategy for MarketMakerStrategy {
+    fn name(&self) -> &'static str {
+        "market_maker"
+    }
+
+    fn evaluate(&self, ctx: &EvalContext) -> Option<TradeSignal> {
+        let token_id = ctx.updated_key.1;
+        let venue = &ctx.updated_key.0;
+
+        let market_id = ctx.token_to_market.get(token_id.as_str())?;
+        let info = ctx.market_map.get(market_id)?;
+
+        let mut trace = ctx.audit.trace(self.name(), market_id, token_id.as_str(), ctx.clock.unix_ms());
+
+        let yes_key = MarketKey(venue.clone(), info.yes_token_id);
+        let Some(yes_state) = ctx.cache.get_shared(&yes_key) else {
+            return trace.reject("no YES book");
+        };
+        let (Some(best_bid), Some(best_ask)) = (yes_state.best_bid, yes_state.best_ask) else {
+            return trace.reject("missing YES bid or ask");
+        };
+        trace.value("best_bid", best_bid).value("best_ask", best_ask);
+        if best_ask <= best_bid {
+            return trace.reject("crossed or locked book");
+        }
+
+        let inventory = ctx.positions.binary_inventory(venue, info.yes_token_id, info.no_token_id);
+        trace.value("inventory", inventory).value("max_inventory", self.params.max_inventory);
+        let Ok(inventory) = to_decimal(inventory) else {
+            return trace.reject("inventory is not a number");
+        };
+        let program = self.rewards.get(market_id);
+        let quote = match &program {
+            Some(r) if self.params.mode == QuoteMode::Rewards => {
+                trace.value("reward_max_spread", r.max_spread).value("reward_min_size", r.min_size);
+                self.compute_reward_quote(best_bid, best_ask, inventory, r)
+            }
+            _ => self.compute_quote(best_bid, best_ask, inventory),
+        };
+        let size = self.quote_size(program.as_ref());
+        if let Some(bid) = quote.bid {
+            trace.value("quote_bid", bid);
+        }
+        if let Some(ask) = quote.ask {
+            trace.value("quote_ask", ask);
+        }
+
+        {
+            let now = ctx.clock.now();
+            let mut last = self.last_quotes.lock().unwrap();
+            if let (Some(resting), Some(r)) = (last.get_mut(market_id), &program) {
+                let mid = (best_bid + best_ask) / Decimal::TWO;
+                let earned = self.accrued_reward(resting, r, mid, now);
+                self.rewards.accrue(self.name(), earned);
+                resting.accrued_to = now;
+            }
+            if last.get(market_id).is_some_and(|r| r.quote == quote && r.size == size) {
+                return trace.reject("quote unchanged");
+            }
+            last.insert(market_id.clone(), Resting { quote, size, accrued_to: now });
+        }
+
+        debug!(
+            market_id = %market_id,
+            %best_bid, %best_ask, %inventory,
+            bid = ?quote.bid,
+            ask = ?quote.ask,
+            "market maker requote"
+        );
+
+        let mut legs = Vec::with_capacity(2);
+        if let Some(bid) = quote.bid {
+            legs.push(SignalLeg {
+                token_id: info.yes_token_id,
+                side: Side::Buy,
+                price: bid,
+                size,
+            });
+        }
+        if let Some(ask) = quote.ask {
+            legs.push(SignalLeg {
+                token_id: info.yes_token_id,
+                side: Side::Sell,
+                price: ask,
+                size,
+            });
+        }
+        if legs.is_empty() {
+            return trace.reject("inventory limit on both sides");
+        }
+
+        // Edge for a quote pair is the half-spread we'd capture per round trip.
+        let edge = match (quote.bid, quote.ask) {
+            (Some(b), Some(a)) => (a - b) / Decimal::TWO,
+            _ => Decimal::ZERO,
+        };
+
+        trace.signal(TradeSignal {
+            strategy_name: self.name(),
+            venue: venue.clone(),
+            market_id: MarketId::intern(market_id),
+            legs,
+            edge: as_f64(edge),
+            liquidity: Liquidity::Maker,
+            generated_at: ctx.clock.now(),
+            ws_received_at: ctx.ws_received_at,
+            stages: None,
+            reply: None,
+        })
+    }
+}
@@ -1,33 +1,196 @@
 pub mod traits;
+pub mod audit;
 pub mod arbitrage;
+pub mod market_maker;
 pub mod simple;
+pub mod scoped;
 
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use tracing::{info, warn, debug};
 use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
-use crate::market_data::market_worker::Notification;
+use crate::clock::SharedClock;
+use crate::execution::lanes::SignalSender;
+use crate::flags::{strategy_flag, FeatureFlags};
+use crate::market_data::market_worker::NotifyReceiver;
+use crate::metrics::channels::record_channel_drop;
 use crate::metrics::prometheus::{record_signal, record_signal_edge};
+use crate::runtime::BusyPoll;
 use crate::state::market_cache::MarketCache;
+use crate::state::position::PositionTracker;
+use audit::DecisionAudit;
 use traits::{Strategy, TradeSignal, EvalContext};
 
-/// Receives Notification (MarketKey + ws_received_at) on every cache update,
-/// reads the latest state, and runs all registered strategies.
+/// Run every strategy against one cache update, in registration order.
+/// Shared by the live engine and the backtester.
+pub fn evaluate_all(strategies: &[Box<dyn Strategy>], ctx: &EvalContext) -> Vec<TradeSignal> {
+    strategies.iter().filter_map(|strategy| strategy.evaluate(ctx)).collect()
+}
+
+/// Operator switch that stops strategy evaluation while market data keeps
+/// flowing into the cache. Cheap to clone.
+#[derive(Clone, Debug, Default)]
+pub struct PauseSwitch(Arc<AtomicBool>);
+
+impl PauseSwitch {
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        if self.0.swap(paused, Ordering::Relaxed) != paused {
+            info!(paused, "strategy evaluation {}", if paused { "paused" } else { "resumed" });
+        }
+    }
+
+    /// Flip the switch; returns the new state.
+    pub fn toggle(&self) -> bool {
+        let paused = !self.is_paused();
+        self.set_paused(paused);
+        paused
+    }
+}
+
+type Strategies = Arc<[Box<dyn Strategy>]>;
+
+/// The strategies the engine runs and the markets they may evaluate.
+/// Both can be swapped at runtime (config reload) without restarting the
+/// engine. Cheap to clone.
+#[derive(Clone)]
+pub struct StrategySet {
+    strategies: Arc<RwLock<Strategies>>,
+    /// Subscribed markets that no longer pass the universe filter.
+    excluded: Arc<RwLock<HashSet<String>>>,
+    /// Strategies the operator has paused, by name. Survives `replace`.
+    paused: Arc<RwLock<HashSet<String>>>,
+    /// `strategy.<name>` flags decide which markets each strategy sees.
+    flags: FeatureFlags,
+}
+
+impl StrategySet {
+    pub fn new(strategies: Vec<Box<dyn Strategy>>) -> Self {
+        Self {
+            strategies: Arc::new(RwLock::new(strategies.into())),
+            excluded: Arc::default(),
+            paused: Arc::default(),
+            flags: FeatureFlags::default(),
+        }
+    }
+
+    pub fn with_flags(mut self, flags: FeatureFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn current(&self) -> Arc<[Box<dyn Strategy>]> {
+        Arc::clone(&self.strategies.read().unwrap())
+    }
+
+    /// Swap in a new set. Per-strategy state (e.g. the market maker's last
+    /// quotes) starts fresh.
+    pub fn replace(&self, strategies: Vec<Box<dyn Strategy>>) {
+        let names: Vec<&'static str> = strategies.iter().map(|s| s.name()).collect();
+        *self.strategies.write().unwrap() = strategies.into();
+        info!(?names, "strategy set replaced");
+    }
+
+    /// Names of the current strategies with whether each is paused.
+    pub fn statuses(&self) -> Vec<(&'static str, bool)> {
+        let paused = self.paused.read().unwrap();
+        self.current().iter().map(|s| (s.name(), paused.contains(s.name()))).collect()
+    }
+
+    /// Pause or resume one strategy. Returns false if none of the current
+    /// strategies has that name.
+    pub fn set_paused(&self, name: &str, paused: bool) -> bool {
+        if !self.current().iter().any(|s| s.name() == name) {
+            return false;
+        }
+        let changed = if paused {
+            self.paused.write().unwrap().insert(name.to_string())
+        } else {
+            self.paused.write().unwrap().remove(name)
+        };
+        if changed {
+            info!(strategy = name, paused, "strategy {}", if paused { "paused" } else { "resumed" });
+        }
+        true
+    }
+
+    /// Run every strategy that isn't paused and whose `strategy.<name>`
+    /// flag admits the updated market.
+    pub fn evaluate(&self, ctx: &EvalContext) -> Vec<TradeSignal> {
+        let strategies = self.current();
+        let paused = self.paused.read().unwrap();
+        if paused.is_empty() && self.flags.is_empty() {
+            return evaluate_all(&strategies, ctx);
+        }
+        let token_id = ctx.updated_key.1.as_str();
+        let market_id = ctx.token_to_market.get(token_id).map_or(token_id, String::as_str);
+        strategies
+            .iter()
+            .filter(|strategy| !paused.contains(strategy.name()))
+            .filter(|strategy| self.flags.allows(&strategy_flag(strategy.name()), market_id))
+            .filter_map(|strategy| strategy.evaluate(ctx))
+            .collect()
+    }
+
+    pub fn is_excluded(&self, market_id: &str) -> bool {
+        self.excluded.read().unwrap().contains(market_id)
+    }
+
+    /// Stop evaluating these markets (replacing any previous exclusion).
+    pub fn set_excluded(&self, markets: HashSet<String>) {
+        info!(count = markets.len(), "market exclusions updated");
+        *self.excluded.write().unwrap() = markets;
+    }
+}
+
+/// Receives a notification (MarketKey + stage timestamps) for each updated
+/// market, coalesced while the engine is busy, reads the latest state, and
+/// runs the current strategies unless the market is excluded. Evaluations of markets watched by `audit` are recorded there.
+/// Notifications are drained but not evaluated while `pause` is set;
+/// strategies paused individually in `strategies` are skipped.
+///
+/// With `busy` enabled the engine spins on `notify_rx` before parking; see
+/// [`BusyPoll`].
+#[allow(clippy::too_many_arguments)]
 pub async fn run_strategy_engine(
-    mut notify_rx: mpsc::Receiver<Notification>,
+    notify_rx: &mut NotifyReceiver,
     cache: MarketCache,
-    strategies: Vec<Box<dyn Strategy>>,
-    signal_tx: mpsc::Sender<TradeSignal>,
+    strategies: StrategySet,
+    signal_tx: SignalSender,
     market_map: Arc<MarketMap>,
     token_to_market: Arc<TokenToMarket>,
+    positions: PositionTracker,
+    clock: SharedClock,
+    audit: DecisionAudit,
+    pause: PauseSwitch,
+    busy: BusyPoll,
 ) {
     info!(
-        strategy_count = strategies.len(),
+        strategy_count = strategies.current().len(),
         "strategy engine started"
     );
 
-    while let Some((key, ws_received_at)) = notify_rx.recv().await {
-        let Some(state) = cache.get_market_state(&key) else {
+    loop {
+        let next = match busy.spin("strategy_engine", || notify_rx.try_recv()) {
+            Some(next) => next,
+            None => match notify_rx.recv().await {
+                Some(next) => next,
+                None => break,
+            },
+        };
+        let (key, mut stages) = next;
+        stages.dequeued = Some(clock.now());
+        if pause.is_paused() {
+            continue;
+        }
+        if token_to_market.get(key.1.as_str()).is_some_and(|market_id| strategies.is_excluded(market_id)) {
+            continue;
+        }
+        let Some(state) = cache.get_shared(&key) else {
             debug!(?key, "cache miss for notified key");
             continue;
         };
@@ -38,26 +201,32 @@ pub async fn run_strategy_engine(
             cache: &cache,
             market_map: &market_map,
             token_to_market: &token_to_market,
-            ws_received_at: Some(ws_received_at),
+            positions: &positions,
+            ws_received_at: Some(stages.received),
+            clock: clock.as_ref(),
+            audit: &audit,
         };
 
-        for strategy in &strategies {
-            if let Some(signal) = strategy.evaluate(&ctx) {
-                record_signal(signal.strategy_name, &format!("{:?}", signal.venue));
-                record_signal_edge(signal.strategy_name, signal.edge);
-
-                info!(
-                    strategy = signal.strategy_name,
-                    market_id = %signal.market_id,
-                    edge = %signal.edge,
-                    legs = signal.legs.len(),
-                    "trade signal generated"
-                );
-
-                if signal_tx.send(signal).await.is_err() {
-                    warn!("signal channel closed, stopping strategy engine");
-                    return;
-                }
+        let signals = strategies.evaluate(&ctx);
+        stages.evaluated = Some(clock.now());
+
+        for mut signal in signals {
+            signal.stages = Some(stages);
+            record_signal(signal.strategy_name, &format!("{:?}", signal.venue));
+            record_signal_edge(signal.strategy_name, signal.edge);
+
+            info!(
+                strategy = signal.strategy_name,
+                market_id = %signal.market_id,
+                edge = %signal.edge,
+                legs = signal.legs.len(),
+                "trade signal generated"
+            );
+
+            if signal_tx.send(signal).await.is_err() {
+                record_channel_drop("signals");
+                warn!("signal channel closed, stopping strategy engine");
+                return;
             }
         }
     }
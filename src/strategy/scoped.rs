@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use super::traits::{EvalContext, Strategy, TradeSignal};
+
+/// Restricts a strategy to a fixed set of markets; updates for any other
+/// market are skipped before the inner strategy sees them.
+pub struct MarketScoped {
+    inner: Box<dyn Strategy>,
+    markets: HashSet<String>,
+}
+
+impl MarketScoped {
+    pub fn new(inner: Box<dyn Strategy>, markets: impl IntoIterator<Item = String>) -> Self {
+        Self { inner, markets: markets.into_iter().collect() }
+    }
+}
+
+impl Strategy for MarketScoped {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn evaluate(&self, ctx: &EvalContext) -> Option<TradeSignal> {
+        let market_id = ctx.token_to_market.get(ctx.updated_key.1.as_str())?;
+        if !self.markets.contains(market_id) {
+            return None;
+        }
+        self.inner.evaluate(ctx)
+    }
+}
@@ -1,16 +1,28 @@
 use crate::market_data::types::{Venue, Side};
 use crate::market_data::adapters::polymarket::{MarketMap, TokenToMarket};
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
 use crate::state::market::MarketState;
 use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::position::PositionTracker;
+use rust_decimal::Decimal;
+use serde::Serialize;
 use std::time::Instant;
+use crate::clock::Clock;
+use crate::metrics::stages::StageTimes;
+use crate::execution::traits::ReplyTo;
+use super::audit::DecisionAudit;
 
 /// A single leg of a multi-leg trade signal.
-#[derive(Debug, Clone)]
+/// Price and size are exact; they serialize as plain numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SignalLeg {
-    pub token_id: String,
+    pub token_id: TokenId,
     pub side: Side,
-    pub price: f64,
-    pub size: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub size: Decimal,
 }
 
 /// Output of a strategy evaluation — a signal, not an order.
@@ -19,13 +31,24 @@ pub struct SignalLeg {
 pub struct TradeSignal {
     pub strategy_name: &'static str,
     pub venue: Venue,
-    pub market_id: String,
+    pub market_id: MarketId,
     pub legs: Vec<SignalLeg>,
+    /// Computed and thresholded in `Decimal` by the strategy; carried as
+    /// f64 for reporting.
     pub edge: f64,
+    /// Whether the legs cross the spread (taker) or rest on the book (maker).
+    /// Drives fee / rebate accrual on fill.
+    pub liquidity: Liquidity,
     pub generated_at: Instant,
     /// Monotonic timestamp of when the triggering WS event was received.
     /// Used to measure end-to-end pipeline latency.
     pub ws_received_at: Option<Instant>,
+    /// Pipeline stage timestamps, stamped by the strategy engine after
+    /// evaluation. Strategies leave this `None`.
+    pub stages: Option<StageTimes>,
+    /// Set by submitters that want to hear what became of the signal.
+    /// Strategies leave this `None`.
+    pub reply: Option<ReplyTo>,
 }
 
 /// Context provided to strategies on each cache update.
@@ -34,11 +57,18 @@ pub struct TradeSignal {
 pub struct EvalContext<'a> {
     pub updated_key: &'a MarketKey,
     pub updated_state: &'a MarketState,
+    /// Read other states with [`MarketCache::get_shared`], which doesn't copy them.
     pub cache: &'a MarketCache,
     pub market_map: &'a MarketMap,
     pub token_to_market: &'a TokenToMarket,
+    /// Current inventory, for strategies that manage their own risk (e.g. market making).
+    pub positions: &'a PositionTracker,
     /// When the triggering WS event was received (monotonic).
     pub ws_received_at: Option<Instant>,
+    /// Time source — strategies must not call `Instant::now()` directly.
+    pub clock: &'a dyn Clock,
+    /// Per-market decision log; strategies trace the values they compute into it.
+    pub audit: &'a DecisionAudit,
 }
 
 /// Trait that all strategies implement.
@@ -0,0 +1,105 @@
+//! Restart-on-failure for the engine's long-running tasks.
+//!
+//! A supervised component is a factory producing a fresh run of the task.
+//! Anything that must outlive one run — most importantly the receiving end
+//! of its input channel — is owned by the factory, not the run, so upstream
+//! senders never see the channel close while the component restarts.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use metrics::counter;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::health::HealthRegistry;
+use crate::metrics::tasks;
+use crate::notify::{Notifier, NotifyEvent};
+
+/// Backoff between restarts of one component.
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// A run that lasted at least this long resets the backoff.
+    pub reset_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            reset_after: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Run `component` until `shutdown` is set, restarting it whenever it
+/// panics, returns an error, or returns at all — these tasks are meant to
+/// live as long as the process.
+///
+/// Each run is reported as `task.<name>` to `health` (down while waiting to
+/// restart), counted in `supervisor_restarts_total`, and sent to `notifier`.
+/// Returns once a run ends with `shutdown` set, or straight away if it is
+/// set while waiting to restart.
+pub async fn supervise<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    health: HealthRegistry,
+    notifier: Notifier,
+    mut shutdown: watch::Receiver<bool>,
+    mut component: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let health_name = format!("task.{name}");
+    health.register(&health_name);
+    let mut backoff = policy.initial_backoff;
+    let mut restarts: u64 = 0;
+
+    loop {
+        health.set_up(&health_name);
+        let started = Instant::now();
+        let result = tokio::spawn(tasks::instrument(name, component())).await;
+        if *shutdown.borrow() {
+            return;
+        }
+
+        let reason = match result {
+            Ok(Ok(())) => "exited unexpectedly".to_string(),
+            Ok(Err(e)) => format!("failed: {e:#}"),
+            Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+            Err(e) => format!("was cancelled: {e}"),
+        };
+        if started.elapsed() >= policy.reset_after {
+            backoff = policy.initial_backoff;
+        }
+        restarts += 1;
+        error!(task = name, %reason, restarts, backoff_ms = backoff.as_millis() as u64, "supervised task stopped; restarting");
+        counter!("supervisor_restarts_total", "task" => name).increment(1);
+        health.set_down(&health_name, format!("{reason}; restarting in {}s", backoff.as_secs()));
+        notifier.notify(NotifyEvent::TaskRestart { task: name, reason, restarts, backoff });
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = shutdown.wait_for(|stop| *stop) => return,
+        }
+        backoff = (backoff * 2).min(policy.max_backoff);
+        info!(task = name, restarts, "restarting supervised task");
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => {
+                warn!("panic payload is not a string");
+                "unknown panic".to_string()
+            }
+        },
+    }
+}
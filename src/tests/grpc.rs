@@ -0,0 +1,116 @@
+//! The gRPC API's token check: reads are open, and calls that change
+//! something need the configured bearer token, as on the admin API.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tonic::transport::Channel;
+use tonic::{Code, Request};
+
+use prediction_engine::admin::AdminState;
+use prediction_engine::execution::operator::OperatorActions;
+use prediction_engine::flags::FeatureFlags;
+use prediction_engine::grpc::proto::engine_client::EngineClient;
+use prediction_engine::grpc::proto::{Empty, MarketRequest, SetFlagRequest, SetLogLevelRequest, SetPausedRequest};
+use prediction_engine::grpc::{bearer, record_channel, run_grpc_server};
+use prediction_engine::health::HealthRegistry;
+use prediction_engine::logging::LogFilter;
+use prediction_engine::state::equity::EquityCurve;
+use prediction_engine::state::market_cache::MarketCache;
+use prediction_engine::state::portfolio::Portfolio;
+use prediction_engine::state::position::PositionTracker;
+use prediction_engine::strategy::audit::DecisionAudit;
+use prediction_engine::strategy::{PauseSwitch, StrategySet};
+
+const TOKEN: &str = "s3cret";
+const WAIT: Duration = Duration::from_secs(10);
+
+/// Serve the gRPC API on a free local port, in paper mode with no positions.
+async fn serve(token: Option<&str>) -> Channel {
+    let cache = MarketCache::new();
+    let token_to_market = Arc::new(HashMap::new());
+    let portfolio = Portfolio::new(PositionTracker::new(), cache.clone(), Arc::clone(&token_to_market), 1_000.0);
+    let (signal_tx, _signal_rx) = mpsc::channel(1);
+    let state = AdminState {
+        portfolio: portfolio.clone(),
+        equity: EquityCurve::new(16),
+        health: HealthRegistry::new(cache.clone(), Duration::from_secs(60)),
+        audit: DecisionAudit::disabled(),
+        log_filter: LogFilter::new("info", |_| Ok(())),
+        pause: PauseSwitch::default(),
+        strategies: StrategySet::new(Vec::new()),
+        flags: FeatureFlags::new(BTreeMap::new()),
+        operator: OperatorActions::new(portfolio, cache, token_to_market, signal_tx.downgrade(), None),
+        subscriptions: Vec::new(),
+        token: token.map(str::to_string),
+    };
+
+    let addr: SocketAddr = {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        listener.local_addr().expect("local addr")
+    };
+    tokio::spawn(run_grpc_server(addr, state, record_channel()));
+
+    let connect = async {
+        loop {
+            match Channel::from_shared(format!("http://{addr}")).expect("uri").connect().await {
+                Ok(channel) => return channel,
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    };
+    tokio::time::timeout(WAIT, connect).await.expect("gRPC server never came up")
+}
+
+#[tokio::test]
+async fn operator_calls_need_the_token() {
+    let channel = serve(Some(TOKEN)).await;
+
+    let mut anonymous = EngineClient::new(channel.clone());
+    assert!(anonymous.get_health(Request::new(Empty {})).await.is_ok(), "reads are open");
+    let refused = anonymous.cancel_all(Request::new(Empty {})).await.unwrap_err();
+    assert_eq!(refused.code(), Code::Unauthenticated);
+
+    let mut wrong = EngineClient::with_interceptor(channel.clone(), bearer("guess").unwrap());
+    let refused = wrong.cancel_all(Request::new(Empty {})).await.unwrap_err();
+    assert_eq!(refused.code(), Code::Unauthenticated);
+
+    let mut operator = EngineClient::with_interceptor(channel, bearer(TOKEN).unwrap());
+    let report = operator.cancel_all(Request::new(Empty {})).await.expect("operator cancel-all").into_inner();
+    assert_eq!(report.canceled, 0);
+}
+
+#[tokio::test]
+async fn controls_need_the_token() {
+    let channel = serve(Some(TOKEN)).await;
+    let pause = || Request::new(SetPausedRequest { strategy: String::new(), paused: true });
+
+    let mut anonymous = EngineClient::new(channel.clone());
+    let refusals = [
+        anonymous.set_paused(pause()).await.unwrap_err(),
+        anonymous.set_flag(Request::new(SetFlagRequest { name: "quoting".to_string(), rollout: Some(0.0) })).await.unwrap_err(),
+        anonymous.set_log_level(Request::new(SetLogLevelRequest { filter: "trace".to_string() })).await.unwrap_err(),
+        anonymous.watch_audit(Request::new(MarketRequest { market_id: "m".to_string() })).await.unwrap_err(),
+        anonymous.unwatch_audit(Request::new(MarketRequest { market_id: "m".to_string() })).await.unwrap_err(),
+    ];
+    assert!(refusals.iter().all(|status| status.code() == Code::Unauthenticated), "{refusals:?}");
+    let strategies = anonymous.list_strategies(Request::new(Empty {})).await.expect("reads are open").into_inner();
+    assert!(!strategies.paused);
+
+    let mut operator = EngineClient::with_interceptor(channel, bearer(TOKEN).unwrap());
+    let strategies = operator.set_paused(pause()).await.expect("operator pause").into_inner();
+    assert!(strategies.paused);
+}
+
+#[tokio::test]
+async fn operator_calls_are_refused_without_a_configured_token() {
+    let channel = serve(None).await;
+
+    let mut client = EngineClient::with_interceptor(channel, bearer(TOKEN).unwrap());
+    assert!(client.get_health(Request::new(Empty {})).await.is_ok(), "reads are open");
+    let refused = client.cancel_all(Request::new(Empty {})).await.unwrap_err();
+    assert_eq!(refused.code(), Code::PermissionDenied);
+}
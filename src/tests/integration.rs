@@ -0,0 +1,382 @@
+//! End-to-end tests: the whole pipeline — Polymarket adapter, router,
+//! strategy engine, execution bridge and the live executor — run against
+//! [`MockVenue`] on a local port. Books are set from the test and order
+//! answers are scripted, so each run sees the same signals and fills.
+
+mod mock_venue;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use polymarket_rs::types::ApiCreds;
+use polymarket_rs::{OrderBuilder, SignatureType, TradingClient};
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+use prediction_engine::engine::{EngineBuilder, PipelineChannels, RunningEngine};
+use prediction_engine::execution::keys::parse_signer;
+use prediction_engine::execution::live::LiveExecutor;
+use prediction_engine::health::HealthRegistry;
+use prediction_engine::market_data::adapters::keepalive::KeepaliveConfig;
+use prediction_engine::market_data::adapters::polymarket::{
+    init_polymarket_adapter, MarketInfo, MarketMap, UniverseFilter, UniverseSelection,
+};
+use prediction_engine::market_data::bus::{EventBus, Subscriber};
+use prediction_engine::market_data::feed_watchdog::{run_feed_watchdog, FeedWatchdogConfig};
+use prediction_engine::market_data::subscriptions::SubscriptionControl;
+use prediction_engine::market_data::types::{MarketEvent, MarketEventKind, Side, Venue};
+use prediction_engine::persist::records::{OutcomeRecord, SignalOutcome};
+use prediction_engine::persist::{PersistRecord, Recorder};
+use prediction_engine::state::ids::TokenId;
+use prediction_engine::state::market_cache::{MarketCache, MarketKey};
+use prediction_engine::state::portfolio::Portfolio;
+use prediction_engine::strategy::arbitrage::ArbitrageStrategy;
+
+use mock_venue::{MockVenue, OrderReply};
+
+const MARKET: &str = "mock-market";
+const YES: &str = "1001";
+const NO: &str = "1002";
+/// A throwaway key: orders are signed for real but only the mock sees them.
+const TEST_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const WAIT: Duration = Duration::from_secs(10);
+
+struct Harness {
+    venue: MockVenue,
+    portfolio: Portfolio,
+    cache: MarketCache,
+    records: mpsc::Receiver<PersistRecord>,
+    /// Every routed market event.
+    events: Subscriber,
+    /// The Polymarket adapter's subscriptions.
+    subscriptions: SubscriptionControl,
+    /// Held so the pipeline keeps running.
+    engine: RunningEngine,
+}
+
+impl Harness {
+    /// Start the venue with fair books on both outcomes (no arbitrage), then
+    /// an engine running the arbitrage strategy against it in live mode.
+    async fn start(replies: impl IntoIterator<Item = OrderReply>) -> Self {
+        Self::start_with(replies, None, KeepaliveConfig::default(), 1).await
+    }
+
+    /// [`start`](Self::start), with the feed watchdog marking tokens stale
+    /// after `feed_stale_after` without an event, and the adapter pinging the
+    /// venue on `keepalive`'s schedule over `connections` WebSockets.
+    async fn start_with(
+        replies: impl IntoIterator<Item = OrderReply>,
+        feed_stale_after: Option<Duration>,
+        keepalive: KeepaliveConfig,
+        connections: usize,
+    ) -> Self {
+        let venue = MockVenue::start(replies).await;
+        venue.set_book(YES, 0.48, 0.52);
+        venue.set_book(NO, 0.48, 0.52);
+
+        let market_map: MarketMap = HashMap::from([(MARKET.to_string(), MarketInfo {
+            market_id: MARKET.to_string(),
+            question: "Mock market?".to_string(),
+            yes_token_id: TokenId::intern(YES),
+            no_token_id: TokenId::intern(NO),
+            neg_risk: false,
+        })]);
+
+        let builder = EngineBuilder::new(PipelineChannels::default());
+        let cache = MarketCache::new();
+        let health = HealthRegistry::new(cache.clone(), Duration::from_secs(60));
+        let pm = init_polymarket_adapter(
+            builder.event_sender(),
+            Some(market_map.clone()),
+            UniverseFilter { min_volume_24h: 0.0, min_liquidity: 0.0 },
+            &UniverseSelection::default(),
+            health.clone(),
+        )
+        .await
+        .expect("adapter init");
+        let adapter = pm
+            .task
+            .with_endpoints(&venue.clob_url(), &venue.ws_url())
+            .with_keepalive(keepalive)
+            .with_ws_connections(connections);
+        let subscriptions = adapter.subscriptions();
+
+        let signer = parse_signer(TEST_KEY).expect("test key");
+        let client = TradingClient::new(
+            venue.clob_url(),
+            signer.clone(),
+            137,
+            ApiCreds::new("key".to_string(), "c2VjcmV0".to_string(), "passphrase".to_string()),
+            OrderBuilder::new(signer, Some(SignatureType::Eoa), None),
+        );
+        let (records_tx, records) = mpsc::channel(1_024);
+
+        let engine = builder
+            .market_data(Arc::new(adapter))
+            .markets(Arc::new(market_map), pm.token_to_market)
+            .strategy(Box::new(ArbitrageStrategy::new(0.025, 5.0).expect("strategy")))
+            .executor(Arc::new(LiveExecutor::new(client, Decimal::new(1, 2))), "live")
+            .cache(cache)
+            .event_bus(EventBus::new(1_024))
+            .health(health)
+            .recorder(Recorder::new(vec![("test", records_tx)]))
+            .build();
+        let portfolio = engine.portfolio().clone();
+        let events = engine.event_bus().subscribe("test");
+        if let Some(stale_after) = feed_stale_after {
+            tokio::spawn(run_feed_watchdog(
+                FeedWatchdogConfig { stale_after: Some(stale_after), ..FeedWatchdogConfig::default() },
+                engine.event_bus().subscribe("feed_watchdog"),
+                engine.event_sender(),
+                engine.risk().clone(),
+            ));
+        }
+        let cache = engine.cache().clone();
+        let engine = engine.start().expect("engine start");
+        Self { venue, portfolio, cache, records, events, subscriptions, engine }
+    }
+
+    /// The outcome record of the next signal the bridge disposes of.
+    async fn next_outcome(&mut self) -> OutcomeRecord {
+        let outcome = async {
+            loop {
+                match self.records.recv().await {
+                    Some(PersistRecord::Outcome(outcome)) => return outcome,
+                    Some(_) => continue,
+                    None => panic!("recorder closed"),
+                }
+            }
+        };
+        tokio::time::timeout(WAIT, outcome).await.expect("no signal outcome in time")
+    }
+
+    /// The next routed trade event: (price, size, side, exchange time in ms).
+    async fn next_trade(&mut self) -> (f64, f64, Side, u64) {
+        let trade = async {
+            loop {
+                let event = self.events.recv().await.expect("event bus closed");
+                if let MarketEventKind::Trade { price, size, side } = &event.kind {
+                    let ts = event.ts_exchange_ms.expect("exchange timestamp");
+                    return (*price, *size, side.clone(), ts.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64);
+                }
+            }
+        };
+        tokio::time::timeout(WAIT, trade).await.expect("no trade event in time")
+    }
+
+    /// The next routed event that is `wanted`.
+    async fn next_event_where(&mut self, wanted: impl Fn(&MarketEvent) -> bool) -> Arc<MarketEvent> {
+        let event = async {
+            loop {
+                let event = self.events.recv().await.expect("event bus closed");
+                if wanted(&event) {
+                    return event;
+                }
+            }
+        };
+        tokio::time::timeout(WAIT, event).await.expect("no matching event in time")
+    }
+
+    /// The next routed event for `token_id` whose kind is `wanted`.
+    async fn next_token_event(&mut self, token_id: &str, wanted: impl Fn(&MarketEventKind) -> bool) -> Arc<MarketEvent> {
+        self.next_event_where(|event| event.token_id == token_id && wanted(&event.kind)).await
+    }
+
+    fn inventory(&self, token_id: &str) -> f64 {
+        self.portfolio.positions().inventory(&MarketKey(Venue::Polymarket, TokenId::intern(token_id)))
+    }
+
+    /// Drop the YES ask so buying both outcomes costs 0.92: an 8¢ edge.
+    fn open_buy_arbitrage(&self) {
+        self.venue.set_book(YES, 0.38, 0.40);
+    }
+}
+
+async fn wait_until(what: &str, mut done: impl FnMut() -> bool) {
+    let poll = async {
+        while !done() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(WAIT, poll).await.unwrap_or_else(|_| panic!("timed out waiting for {what}"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn arbitrage_fills_both_legs() {
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    h.open_buy_arbitrage();
+    let outcome = h.next_outcome().await;
+
+    assert_eq!(outcome.outcome, SignalOutcome::Filled);
+    assert_eq!((outcome.filled_legs, outcome.total_legs), (2, 2));
+    let orders = h.venue.orders();
+    assert_eq!(orders.iter().map(|o| o.token_id.as_str()).collect::<Vec<_>>(), [YES, NO]);
+    assert!(orders.iter().all(|o| o.side == "BUY"));
+    assert_eq!(h.inventory(YES), 5.0);
+    assert_eq!(h.inventory(NO), 5.0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejected_second_leg_leaves_first_leg_open() {
+    let mut h = Harness::start([OrderReply::Fill, OrderReply::Reject("not enough liquidity")]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    h.open_buy_arbitrage();
+    let outcome = h.next_outcome().await;
+
+    assert_eq!(outcome.outcome, SignalOutcome::PartiallyFilled);
+    assert_eq!((outcome.filled_legs, outcome.total_legs), (1, 2));
+    assert_eq!(h.venue.orders().len(), 2);
+    assert_eq!(h.inventory(YES), 5.0);
+    assert_eq!(h.inventory(NO), 0.0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn resubscribes_and_trades_after_disconnect() {
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    h.venue.disconnect();
+    // One batch for both tokens, in no particular order.
+    let mut pending = vec![YES, NO];
+    while !pending.is_empty() {
+        let resync = h.next_event_where(|event| event.kind == MarketEventKind::Resync).await;
+        pending.retain(|token_id| *token_id != resync.token_id);
+    }
+    wait_until("resubscription", || h.venue.subscriptions().len() == 2).await;
+    let subscriptions = h.venue.subscriptions();
+    assert_eq!(subscriptions[0], subscriptions[1]);
+
+    h.open_buy_arbitrage();
+    let outcome = h.next_outcome().await;
+    assert_eq!(outcome.outcome, SignalOutcome::Filled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn trades_carry_exchange_time() {
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    h.venue.trade(YES, 0.47, 12.0, "SELL", 1_700_000_000_123);
+    let (price, size, side, ts_ms) = h.next_trade().await;
+
+    assert_eq!((price, size, side, ts_ms), (0.47, 12.0, Side::Sell, 1_700_000_000_123));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn out_of_sync_book_is_marked_stale_and_refetched() {
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    // The venue's YES book moves without a word, then the next change
+    // reports a touch the adapter's book can't have.
+    h.venue.miss_update(YES, 0.38, 0.40);
+    h.venue.price_change(YES, "BUY", 0.30, 25.0);
+
+    let stale = h.next_token_event(YES, |kind| *kind == MarketEventKind::BookStale).await;
+    assert_eq!((stale.best_bid, stale.best_ask), (Some(Decimal::new(38, 2)), Some(Decimal::new(40, 2))));
+
+    let resynced = h.next_token_event(YES, |kind| matches!(kind, MarketEventKind::BookSnapshot { .. })).await;
+    assert_eq!((resynced.best_bid, resynced.best_ask), (Some(Decimal::new(38, 2)), Some(Decimal::new(40, 2))));
+    assert_eq!(h.venue.book_requests(), [YES]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quiet_feed_is_marked_stale_until_it_sends_again() {
+    let h = Harness::start_with([], Some(Duration::from_millis(300)), KeepaliveConfig::default(), 1).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    let yes = MarketKey(Venue::Polymarket, TokenId::intern(YES));
+    let no = MarketKey(Venue::Polymarket, TokenId::intern(NO));
+    let bid = |key: &MarketKey| h.cache.get_market_state(key).and_then(|state| state.best_bid);
+    wait_until("prices", || bid(&yes).is_some() && bid(&no).is_some()).await;
+    // Nothing more is sent, so both tokens go stale and lose their prices.
+    wait_until("prices dropped", || bid(&yes).is_none() && bid(&no).is_none()).await;
+
+    h.venue.set_book(YES, 0.47, 0.53);
+    wait_until("prices back", || bid(&yes) == Some(Decimal::new(47, 2))).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unanswered_pings_force_a_reconnect() {
+    let keepalive = KeepaliveConfig { ping_interval: Duration::from_millis(100), max_missed_pongs: 2 };
+    let h = Harness::start_with([], None, keepalive, 1).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    wait_until("pings", || h.venue.pings() >= 4).await;
+    assert_eq!(h.venue.subscriptions().len(), 1, "answered pings keep the connection");
+
+    h.venue.ignore_pings();
+    wait_until("resubscription", || h.venue.subscriptions().len() == 2).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn subscriptions_change_on_the_open_connection() {
+    const OTHER_MARKET: &str = "mock-market-2";
+    const OTHER_YES: &str = "2001";
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+    h.venue.set_book(OTHER_YES, 0.20, 0.25);
+
+    assert_eq!(h.subscriptions.subscribe(OTHER_MARKET, &[OTHER_YES.to_string()]), 1);
+    let snapshot = h.next_token_event(OTHER_YES, |kind| matches!(kind, MarketEventKind::BookSnapshot { .. })).await;
+    assert_eq!(snapshot.market_id, OTHER_MARKET);
+    assert_eq!((snapshot.best_bid, snapshot.best_ask), (Some(Decimal::new(20, 2)), Some(Decimal::new(25, 2))));
+
+    assert_eq!(h.subscriptions.unsubscribe(&[OTHER_YES.to_string()]), 1);
+    let unsubscribed = h.next_token_event(OTHER_YES, |kind| *kind == MarketEventKind::Unsubscribed).await;
+    assert_eq!(unsubscribed.market_id, OTHER_MARKET);
+
+    wait_until("unsubscribe", || h.venue.operations().len() == 2).await;
+    assert_eq!(h.venue.subscriptions().len(), 1, "the connection stayed up");
+    assert_eq!(h.venue.operations(), [
+        ("subscribe".to_string(), vec![OTHER_YES.to_string()]),
+        ("unsubscribe".to_string(), vec![OTHER_YES.to_string()]),
+    ]);
+    assert_eq!(h.subscriptions.tokens().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn markets_are_spread_over_connections() {
+    const OTHER_MARKET: &str = "mock-market-2";
+    const OTHER_YES: &str = "2001";
+    let mut h = Harness::start_with([], None, KeepaliveConfig::default(), 2).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+    h.venue.set_book(OTHER_YES, 0.20, 0.25);
+
+    // The idle second connection opens for the new market.
+    assert_eq!(h.subscriptions.subscribe(OTHER_MARKET, &[OTHER_YES.to_string()]), 1);
+    let snapshot = h.next_token_event(OTHER_YES, |kind| matches!(kind, MarketEventKind::BookSnapshot { .. })).await;
+    assert_eq!(snapshot.market_id, OTHER_MARKET);
+    let mut subscriptions = h.venue.subscriptions();
+    subscriptions.iter_mut().for_each(|tokens| tokens.sort());
+    assert_eq!(subscriptions, [vec![YES.to_string(), NO.to_string()], vec![OTHER_YES.to_string()]]);
+    assert!(h.venue.operations().is_empty(), "the first connection wasn't touched");
+
+    // Each connection resubscribes its own share.
+    h.venue.disconnect();
+    wait_until("resubscription", || h.venue.subscriptions().len() == 4).await;
+    let mut resubscribed = h.venue.subscriptions().split_off(2);
+    resubscribed.iter_mut().for_each(|tokens| tokens.sort());
+    resubscribed.sort();
+    assert_eq!(resubscribed, subscriptions);
+
+    h.open_buy_arbitrage();
+    let outcome = h.next_outcome().await;
+    assert_eq!(outcome.outcome, SignalOutcome::Filled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shutdown_stops_the_adapter() {
+    let mut h = Harness::start([]).await;
+    wait_until("subscription", || h.venue.subscriptions().len() == 1).await;
+
+    h.engine.shutdown().await;
+    h.venue.disconnect();
+    // Past the first reconnect backoff, which a running adapter would have taken.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(h.venue.subscriptions().len(), 1, "no reconnect after shutdown");
+}
@@ -0,0 +1,319 @@
+//! In-process stand-in for the Polymarket CLOB.
+//!
+//! Serves the endpoints the engine talks to: the market WebSocket
+//! (`/ws/market`), the `/price` endpoint the adapter seeds the cache from,
+//! `/book`, which it resyncs an out-of-sync book from, and `/order`, which
+//! answers from a script of fills and rejects. Tests set
+//! books with [`MockVenue::set_book`], which both changes what `/price`
+//! returns and pushes a snapshot to every subscriber, so the seed fetch and
+//! the stream agree whichever lands first. Subscribe and unsubscribe
+//! operations sent on an open socket are recorded, and newly subscribed
+//! tokens sent their books as on connect. A `PING` is answered with `PONG`
+//! until [`MockVenue::ignore_pings`].
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use tokio::sync::broadcast;
+
+/// How `/order` answers the next order.
+#[derive(Debug, Clone)]
+pub enum OrderReply {
+    Fill,
+    /// `success: false` with this message, as the CLOB answers an unfillable FOK order.
+    Reject(&'static str),
+}
+
+/// An order as the venue received it.
+#[derive(Debug, Clone)]
+pub struct PostedOrder {
+    pub token_id: String,
+    /// `BUY` or `SELL`.
+    pub side: String,
+}
+
+#[derive(Debug, Clone)]
+enum Feed {
+    Frame(String),
+    /// Close every open market socket.
+    Disconnect,
+}
+
+/// Best bid and ask of one token.
+#[derive(Debug, Clone, Copy)]
+struct Book {
+    bid: f64,
+    ask: f64,
+}
+
+struct Venue {
+    books: Mutex<HashMap<String, Book>>,
+    /// Answers for the next orders, in order; fills once it runs out.
+    replies: Mutex<VecDeque<OrderReply>>,
+    orders: Mutex<Vec<PostedOrder>>,
+    /// Token ids of each subscription, one entry per connection.
+    subscriptions: Mutex<Vec<Vec<String>>>,
+    /// Each `operation` sent on an open socket, with its token ids.
+    operations: Mutex<Vec<(String, Vec<String>)>>,
+    /// Token ids of each `/book` request, in order.
+    book_requests: Mutex<Vec<String>>,
+    /// `PING`s received on every socket.
+    pings: AtomicUsize,
+    answer_pings: AtomicBool,
+    feed: broadcast::Sender<Feed>,
+    next_order_id: Mutex<u64>,
+}
+
+pub struct MockVenue {
+    addr: SocketAddr,
+    venue: Arc<Venue>,
+}
+
+impl MockVenue {
+    /// Listen on a free local port. `replies` scripts the first orders' answers.
+    pub async fn start(replies: impl IntoIterator<Item = OrderReply>) -> Self {
+        let venue = Arc::new(Venue {
+            books: Mutex::new(HashMap::new()),
+            replies: Mutex::new(replies.into_iter().collect()),
+            orders: Mutex::new(Vec::new()),
+            subscriptions: Mutex::new(Vec::new()),
+            operations: Mutex::new(Vec::new()),
+            book_requests: Mutex::new(Vec::new()),
+            pings: AtomicUsize::new(0),
+            answer_pings: AtomicBool::new(true),
+            feed: broadcast::channel(64).0,
+            next_order_id: Mutex::new(1),
+        });
+        let app = Router::new()
+            .route("/price", get(price))
+            .route("/book", get(book))
+            .route("/order", post(order))
+            .route("/ws/market", get(market_ws))
+            .with_state(Arc::clone(&venue));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind mock venue");
+        let addr = listener.local_addr().expect("mock venue address");
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { addr, venue }
+    }
+
+    pub fn clob_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}/ws/market", self.addr)
+    }
+
+    /// Set a token's top of book and push it to every subscriber.
+    pub fn set_book(&self, token_id: &str, bid: f64, ask: f64) {
+        let book = Book { bid, ask };
+        self.venue.books.lock().unwrap().insert(token_id.to_string(), book);
+        let _ = self.venue.feed.send(Feed::Frame(json!([book_event(token_id, book)]).to_string()));
+    }
+
+    /// Change a token's top of book without telling subscribers, as if the
+    /// update had been lost on the way.
+    pub fn miss_update(&self, token_id: &str, bid: f64, ask: f64) {
+        self.venue.books.lock().unwrap().insert(token_id.to_string(), Book { bid, ask });
+    }
+
+    /// Push a change to one level of a token's book, reporting the token's
+    /// current top of book as the best prices after it.
+    pub fn price_change(&self, token_id: &str, side: &str, price: f64, size: f64) {
+        let book = self.venue.books.lock().unwrap().get(token_id).copied().unwrap_or(Book { bid: 0.0, ask: 1.0 });
+        let event = json!({
+            "event_type": "price_change",
+            "market": "mock",
+            "timestamp": unix_ms().to_string(),
+            "price_changes": [{
+                "asset_id": token_id,
+                "side": side,
+                "price": price.to_string(),
+                "size": size.to_string(),
+                "best_bid": book.bid.to_string(),
+                "best_ask": book.ask.to_string(),
+            }],
+        });
+        let _ = self.venue.feed.send(Feed::Frame(event.to_string()));
+    }
+
+    /// Report a match on `token_id` to every subscriber, stamped `timestamp_ms`.
+    pub fn trade(&self, token_id: &str, price: f64, size: f64, side: &str, timestamp_ms: u64) {
+        let event = json!({
+            "event_type": "last_trade_price",
+            "market": "mock",
+            "asset_id": token_id,
+            "price": price.to_string(),
+            "size": size.to_string(),
+            "fee_rate_bps": "0",
+            "side": side,
+            "timestamp": timestamp_ms.to_string(),
+            "transaction_hash": "0x0",
+        });
+        let _ = self.venue.feed.send(Feed::Frame(event.to_string()));
+    }
+
+    /// Drop every market socket, as a venue restart would.
+    pub fn disconnect(&self) {
+        let _ = self.venue.feed.send(Feed::Disconnect);
+    }
+
+    /// Leave every later `PING` unanswered, as a half-open connection would.
+    pub fn ignore_pings(&self) {
+        self.venue.answer_pings.store(false, Ordering::Relaxed);
+    }
+
+    pub fn pings(&self) -> usize {
+        self.venue.pings.load(Ordering::Relaxed)
+    }
+
+    pub fn orders(&self) -> Vec<PostedOrder> {
+        self.venue.orders.lock().unwrap().clone()
+    }
+
+    pub fn subscriptions(&self) -> Vec<Vec<String>> {
+        self.venue.subscriptions.lock().unwrap().clone()
+    }
+
+    /// `(operation, token ids)` of each subscription change, in order.
+    pub fn operations(&self) -> Vec<(String, Vec<String>)> {
+        self.venue.operations.lock().unwrap().clone()
+    }
+
+    pub fn book_requests(&self) -> Vec<String> {
+        self.venue.book_requests.lock().unwrap().clone()
+    }
+}
+
+fn book_event(token_id: &str, book: Book) -> Value {
+    json!({
+        "event_type": "book",
+        "market": "mock",
+        "asset_id": token_id,
+        "timestamp": unix_ms().to_string(),
+        "hash": "",
+        "bids": [{ "price": book.bid.to_string(), "size": "100" }],
+        "asks": [{ "price": book.ask.to_string(), "size": "100" }],
+    })
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// `side=BUY` is the price to buy (the ask), `side=SELL` the price to sell (the bid).
+async fn price(State(venue): State<Arc<Venue>>, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    let book = query.get("token_id").and_then(|id| venue.books.lock().unwrap().get(id).copied());
+    let book = book.unwrap_or(Book { bid: 0.0, ask: 1.0 });
+    let price = if query.get("side").map(String::as_str) == Some("BUY") { book.ask } else { book.bid };
+    Json(json!({ "price": price.to_string() }))
+}
+
+async fn book(State(venue): State<Arc<Venue>>, Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+    let token_id = query.get("token_id").cloned().unwrap_or_default();
+    venue.book_requests.lock().unwrap().push(token_id.clone());
+    let book = venue.books.lock().unwrap().get(&token_id).copied().unwrap_or(Book { bid: 0.0, ask: 1.0 });
+    Json(book_event(&token_id, book))
+}
+
+async fn order(State(venue): State<Arc<Venue>>, Json(body): Json<Value>) -> Json<Value> {
+    let field = |name: &str| body["order"][name].as_str().unwrap_or_default().to_string();
+    venue.orders.lock().unwrap().push(PostedOrder { token_id: field("tokenId"), side: field("side") });
+    let reply = venue.replies.lock().unwrap().pop_front().unwrap_or(OrderReply::Fill);
+    Json(match reply {
+        OrderReply::Fill => {
+            let mut next = venue.next_order_id.lock().unwrap();
+            let id = *next;
+            *next += 1;
+            json!({ "errorMsg": "", "orderID": format!("mock-{id}"), "status": "matched", "success": true })
+        }
+        OrderReply::Reject(reason) => json!({ "errorMsg": reason, "orderID": "", "status": "unmatched", "success": false }),
+    })
+}
+
+async fn market_ws(State(venue): State<Arc<Venue>>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| stream_market(venue, socket))
+}
+
+/// Wait for the subscription, send a snapshot of each subscribed book, then
+/// forward feed frames until told to disconnect or the client goes away.
+async fn stream_market(venue: Arc<Venue>, mut socket: WebSocket) {
+    // Subscribe to the feed before reading the books, so no update falls between.
+    let mut feed = venue.feed.subscribe();
+    let tokens: Vec<String> = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => {
+                let request: Value = serde_json::from_str(&text).unwrap_or_default();
+                break asset_ids(&request);
+            }
+            Some(Ok(_)) => continue,
+            _ => return,
+        }
+    };
+    venue.subscriptions.lock().unwrap().push(tokens.clone());
+
+    if send_books(&venue, &mut socket, &tokens).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            message = feed.recv() => match message {
+                Ok(Feed::Frame(text)) => {
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(Feed::Disconnect) | Err(_) => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) if text == "PING" => {
+                    venue.pings.fetch_add(1, Ordering::Relaxed);
+                    if venue.answer_pings.load(Ordering::Relaxed) && socket.send(Message::Text("PONG".into())).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Text(text))) => {
+                    let request: Value = serde_json::from_str(&text).unwrap_or_default();
+                    let Some(operation) = request["operation"].as_str() else { continue };
+                    let tokens = asset_ids(&request);
+                    venue.operations.lock().unwrap().push((operation.to_string(), tokens.clone()));
+                    if operation == "subscribe" && send_books(&venue, &mut socket, &tokens).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(_)) => {}
+                _ => return,
+            },
+        }
+    }
+}
+
+fn asset_ids(request: &Value) -> Vec<String> {
+    let ids = request["assets_ids"].as_array().cloned().unwrap_or_default();
+    ids.iter().filter_map(|id| id.as_str().map(str::to_string)).collect()
+}
+
+/// Send a snapshot of each of `tokens`' books that's set, in one frame.
+async fn send_books(venue: &Venue, socket: &mut WebSocket, tokens: &[String]) -> Result<(), axum::Error> {
+    let snapshot: Vec<Value> = {
+        let books = venue.books.lock().unwrap();
+        tokens.iter().filter_map(|id| books.get(id).map(|book| book_event(id, *book))).collect()
+    };
+    if snapshot.is_empty() {
+        return Ok(());
+    }
+    socket.send(Message::Text(json!(snapshot).to_string())).await
+}
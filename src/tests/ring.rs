@@ -0,0 +1,147 @@
+//! The SPSC ring in `prediction_engine::ring`: capacity, ordering across index
+//! wraparound, what each side sees once the other is gone, and parking and
+//! waking under contention.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use prediction_engine::ring::ring;
+
+const WAIT: Duration = Duration::from_secs(10);
+
+/// Counts its drops, to check that queued items are dropped exactly once.
+struct Tracked(Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn capacity_rounds_up_to_a_power_of_two() {
+    let (mut tx, mut rx) = ring(3);
+    for i in 0..4 {
+        assert!(tx.try_push(i).is_ok(), "push {i} should fit");
+    }
+    assert_eq!(tx.try_push(4), Err(4));
+
+    assert_eq!(rx.try_pop(), Some(0));
+    assert!(tx.try_push(4).is_ok());
+    assert_eq!(tx.try_push(5), Err(5));
+}
+
+#[test]
+fn empty_ring_pops_nothing() {
+    let (mut tx, mut rx) = ring::<u32>(4);
+    assert_eq!(rx.try_pop(), None);
+    tx.try_push(1).unwrap();
+    assert_eq!(rx.try_pop(), Some(1));
+    assert_eq!(rx.try_pop(), None);
+}
+
+#[test]
+fn order_survives_wraparound() {
+    let (mut tx, mut rx) = ring(4);
+    let (mut next_in, mut next_out) = (0, 0);
+    // Uneven batches keep the indices crossing the end of the slot array at
+    // different offsets.
+    for round in 0..1_000 {
+        for _ in 0..(round % 4 + 1) {
+            tx.try_push(next_in).unwrap();
+            next_in += 1;
+        }
+        while let Some(value) = rx.try_pop() {
+            assert_eq!(value, next_out);
+            next_out += 1;
+        }
+    }
+    assert_eq!(next_out, next_in);
+}
+
+#[test]
+fn unread_items_are_dropped_with_the_ring() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (mut tx, mut rx) = ring(4);
+    // Wrap once so the unread items straddle the end of the slot array.
+    for _ in 0..3 {
+        assert!(tx.try_push(Tracked(Arc::clone(&drops))).is_ok());
+    }
+    for _ in 0..3 {
+        drop(rx.try_pop());
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+
+    for _ in 0..3 {
+        assert!(tx.try_push(Tracked(Arc::clone(&drops))).is_ok());
+    }
+    drop(tx);
+    assert_eq!(drops.load(Ordering::SeqCst), 3, "items still queued must not be dropped early");
+    drop(rx);
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+}
+
+#[tokio::test]
+async fn pop_drains_then_ends_once_the_producer_is_gone() {
+    let (mut tx, mut rx) = ring(4);
+    tx.try_push(1).unwrap();
+    tx.try_push(2).unwrap();
+    drop(tx);
+
+    assert_eq!(rx.pop().await, Some(1));
+    assert_eq!(rx.pop().await, Some(2));
+    assert_eq!(rx.pop().await, None);
+}
+
+#[tokio::test]
+async fn push_hands_the_value_back_once_the_consumer_is_gone() {
+    let (mut tx, rx) = ring(1);
+    tx.try_push(1).unwrap();
+    drop(rx);
+    assert_eq!(tx.push(2).await, Err(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parked_consumer_wakes_on_push() {
+    let (mut tx, mut rx) = ring(4);
+    let consumer = tokio::spawn(async move { rx.pop().await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    tx.try_push(7).unwrap();
+    assert_eq!(tokio::time::timeout(WAIT, consumer).await.expect("consumer never woke").unwrap(), Some(7));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn parked_producer_wakes_when_room_is_made() {
+    let (mut tx, mut rx) = ring(1);
+    tx.try_push(1).unwrap();
+    let producer = tokio::spawn(async move { tx.push(2).await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(rx.try_pop(), Some(1));
+    tokio::time::timeout(WAIT, producer).await.expect("producer never woke").unwrap().unwrap();
+    assert_eq!(rx.try_pop(), Some(2));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn both_sides_parking_lose_nothing() {
+    const ITEMS: u64 = 200_000;
+    // Two slots keep both sides parking and waking each other constantly.
+    let (mut tx, mut rx) = ring(2);
+    let producer = tokio::spawn(async move {
+        for i in 0..ITEMS {
+            tx.push(i).await.unwrap();
+        }
+    });
+    let consumer = tokio::spawn(async move {
+        let mut expected = 0;
+        while let Some(value) = rx.pop().await {
+            assert_eq!(value, expected);
+            expected += 1;
+        }
+        expected
+    });
+
+    tokio::time::timeout(WAIT, producer).await.expect("producer stalled").unwrap();
+    let received = tokio::time::timeout(WAIT, consumer).await.expect("consumer stalled").unwrap();
+    assert_eq!(received, ITEMS);
+}
@@ -0,0 +1,341 @@
+//! Terminal dashboard for operators running the engine interactively.
+//! Built only with the `tui` feature.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Stdout};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+use tokio::sync::mpsc;
+use tracing::info;
+
+use crate::market_data::adapters::polymarket::MarketMap;
+use crate::market_data::bus::Subscriber;
+use crate::market_data::types::Venue;
+use crate::persist::records::SignalOutcome;
+use crate::persist::PersistRecord;
+use crate::risk::RiskManager;
+use crate::state::market::as_f64;
+use crate::state::market_cache::{MarketCache, MarketKey};
+use crate::state::portfolio::{Portfolio, PortfolioSnapshot};
+use crate::strategy::PauseSwitch;
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_SIGNALS: usize = 200;
+/// Markets not updated for this long are drawn dimmed.
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// Shared engine state the dashboard reads and the controls it drives.
+#[derive(Clone)]
+pub struct TuiState {
+    pub cache: MarketCache,
+    pub market_map: Arc<MarketMap>,
+    pub portfolio: Portfolio,
+    pub risk: RiskManager,
+    pub pause: PauseSwitch,
+}
+
+/// A signal and, once the bridge has disposed of it, its outcome.
+struct SignalRow {
+    signal_id: u64,
+    ts_ms: u64,
+    strategy: &'static str,
+    market_id: String,
+    edge: f64,
+    outcome: Option<SignalOutcome>,
+}
+
+/// Run the dashboard until the operator quits.
+///
+/// `events` is an event-bus subscription, used to track when each token last
+/// updated; `records` is a recorder sink, used for the signal feed.
+///
+/// Keys: `p` pause/resume strategies, `k` trip the kill switch, `q` quit.
+pub async fn run_tui(
+    state: TuiState,
+    mut events: Subscriber,
+    mut records: mpsc::Receiver<PersistRecord>,
+) -> anyhow::Result<()> {
+    let mut terminal = enter_terminal()?;
+    let mut last_update: HashMap<String, Instant> = HashMap::new();
+    let mut signals: VecDeque<SignalRow> = VecDeque::with_capacity(MAX_SIGNALS);
+    let mut tick = tokio::time::interval(REDRAW_INTERVAL);
+
+    let result = loop {
+        tokio::select! {
+            Some(event) = events.recv() => {
+                last_update.insert(event.token_id.clone(), event.received_at);
+            }
+            Some(record) = records.recv() => match record {
+                PersistRecord::Signal(s) => {
+                    if signals.len() == MAX_SIGNALS {
+                        signals.pop_back();
+                    }
+                    signals.push_front(SignalRow {
+                        signal_id: s.signal_id,
+                        ts_ms: s.ts_ms,
+                        strategy: s.strategy,
+                        market_id: s.market_id.to_string(),
+                        edge: s.edge,
+                        outcome: None,
+                    });
+                }
+                PersistRecord::Outcome(o) => {
+                    if let Some(row) = signals.iter_mut().find(|row| row.signal_id == o.signal_id) {
+                        row.outcome = Some(o.outcome);
+                    }
+                }
+                _ => {}
+            },
+            _ = tick.tick() => {
+                match handle_keys(&state) {
+                    Ok(true) => break Ok(()),
+                    Ok(false) => {}
+                    Err(e) => break Err(e),
+                }
+                let snapshot = state.portfolio.snapshot();
+                if let Err(e) = terminal.draw(|frame| draw(frame, &state, &snapshot, &last_update, &signals)) {
+                    break Err(e.into());
+                }
+            }
+        }
+    };
+
+    leave_terminal(&mut terminal)?;
+    info!("dashboard closed");
+    result
+}
+
+// ── Terminal setup ──────────────────────────────────────────────
+
+fn enter_terminal() -> anyhow::Result<Terminal<CrosstermBackend<Stdout>>> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    Ok(Terminal::new(CrosstermBackend::new(io::stdout()))?)
+}
+
+fn leave_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> anyhow::Result<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Apply pending key presses. Returns true when the operator asked to quit.
+fn handle_keys(state: &TuiState) -> anyhow::Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+            // Raw mode swallows SIGINT, so Ctrl-C arrives as a key.
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+            KeyCode::Char('p') => {
+                state.pause.toggle();
+            }
+            KeyCode::Char('k') => state.risk.halt("operator (dashboard)"),
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+// ── Rendering ───────────────────────────────────────────────────
+
+fn draw(
+    frame: &mut Frame,
+    state: &TuiState,
+    snapshot: &PortfolioSnapshot,
+    last_update: &HashMap<String, Instant>,
+    signals: &VecDeque<SignalRow>,
+) {
+    let [header, markets, bottom] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(8), Constraint::Percentage(40)]).areas(frame.area());
+    let [signals_area, book_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+    let [orders_area, positions_area] =
+        Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(book_area);
+
+    draw_header(frame, header, state, snapshot);
+    draw_markets(frame, markets, state, last_update);
+    draw_signals(frame, signals_area, signals);
+    draw_orders(frame, orders_area, snapshot);
+    draw_positions(frame, positions_area, snapshot);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, state: &TuiState, snapshot: &PortfolioSnapshot) {
+    let flag = |on: bool, label: &'static str| {
+        if on { label.bold().fg(Color::Red) } else { label.dim() }
+    };
+    let line = Line::from(vec![
+        format!(
+            "equity {:.2}  cash {:.2}  realized {:+.2}  unrealized {:+.2}  total {:+.2}   ",
+            snapshot.balances.equity,
+            snapshot.balances.cash,
+            snapshot.pnl.realized,
+            snapshot.pnl.unrealized,
+            snapshot.pnl.total,
+        )
+        .into(),
+        flag(state.pause.is_paused(), "PAUSED"),
+        "  ".into(),
+        flag(state.risk.is_halted(), "HALTED"),
+        "   [p] pause/resume  [k] kill switch  [q] quit".dim(),
+    ]);
+    frame.render_widget(Paragraph::new(line).block(Block::bordered().title(" prediction-engine ")), area);
+}
+
+fn draw_markets(frame: &mut Frame, area: Rect, state: &TuiState, last_update: &HashMap<String, Instant>) {
+    struct MarketRow<'a> {
+        question: &'a str,
+        bid: Option<f64>,
+        ask: Option<f64>,
+        age: Option<Duration>,
+    }
+
+    let mut rows: Vec<MarketRow> = state
+        .market_map
+        .values()
+        .map(|info| {
+            let yes = state.cache.get_market_state(&MarketKey(Venue::Polymarket, info.yes_token_id));
+            let age = [info.yes_token_id, info.no_token_id]
+                .iter()
+                .filter_map(|token| last_update.get(token.as_str()).map(Instant::elapsed))
+                .min();
+            MarketRow {
+                question: &info.question,
+                bid: yes.as_ref().and_then(|s| s.best_bid).map(as_f64),
+                ask: yes.as_ref().and_then(|s| s.best_ask).map(as_f64),
+                age,
+            }
+        })
+        .collect();
+    // Most recently updated first; never-updated markets last.
+    rows.sort_by_key(|row| row.age.unwrap_or(Duration::MAX));
+
+    let price = |p: Option<f64>| p.map(|p| format!("{p:.3}")).unwrap_or_else(|| "—".into());
+    let table_rows = rows.iter().take(area.height.saturating_sub(3) as usize).map(|row| {
+        let spread = match (row.bid, row.ask) {
+            (Some(bid), Some(ask)) => format!("{:.3}", ask - bid),
+            _ => "—".into(),
+        };
+        let age = row.age.map(|a| format!("{:.1}s", a.as_secs_f64())).unwrap_or_else(|| "—".into());
+        let style = match row.age {
+            Some(age) if age < STALE_AFTER => Style::default(),
+            _ => Style::default().add_modifier(Modifier::DIM),
+        };
+        Row::new(vec![
+            Cell::from(row.question.to_string()),
+            Cell::from(price(row.bid)),
+            Cell::from(price(row.ask)),
+            Cell::from(spread),
+            Cell::from(age),
+        ])
+        .style(style)
+    });
+
+    let table = Table::new(table_rows, [
+        Constraint::Fill(1),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(8),
+    ])
+    .header(Row::new(["market (YES)", "bid", "ask", "spread", "age"]).bold())
+    .block(Block::bordered().title(format!(" markets ({}) ", rows.len())));
+    frame.render_widget(table, area);
+}
+
+fn draw_signals(frame: &mut Frame, area: Rect, signals: &VecDeque<SignalRow>) {
+    let rows = signals.iter().take(area.height.saturating_sub(3) as usize).map(|s| {
+        let time = chrono::DateTime::from_timestamp_millis(s.ts_ms as i64)
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        let outcome = s.outcome.map(|o| o.name()).unwrap_or("pending");
+        let style = match s.outcome {
+            Some(SignalOutcome::Filled) => Style::default().fg(Color::Green),
+            Some(SignalOutcome::Rejected | SignalOutcome::Blocked) => Style::default().fg(Color::Red),
+            _ => Style::default(),
+        };
+        Row::new(vec![
+            Cell::from(time),
+            Cell::from(s.strategy),
+            Cell::from(s.market_id.clone()),
+            Cell::from(format!("{:.4}", s.edge)),
+            Cell::from(outcome),
+        ])
+        .style(style)
+    });
+    let table = Table::new(rows, [
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Fill(1),
+        Constraint::Length(7),
+        Constraint::Length(15),
+    ])
+    .header(Row::new(["time", "strategy", "market", "edge", "outcome"]).bold())
+    .block(Block::bordered().title(" signals "));
+    frame.render_widget(table, area);
+}
+
+fn draw_orders(frame: &mut Frame, area: Rect, snapshot: &PortfolioSnapshot) {
+    let rows = snapshot.open_orders.iter().map(|order| {
+        let legs = order
+            .legs
+            .iter()
+            .map(|leg| format!("{:?} {}@{:.3}", leg.side, leg.size, leg.price))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Row::new(vec![
+            Cell::from(order.id.to_string()),
+            Cell::from(order.strategy.clone()),
+            Cell::from(order.market_id.as_str()),
+            Cell::from(legs),
+        ])
+    });
+    let table = Table::new(rows, [
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Fill(1),
+        Constraint::Fill(2),
+    ])
+    .header(Row::new(["id", "strategy", "market", "legs"]).bold())
+    .block(Block::bordered().title(format!(" open orders ({}) ", snapshot.open_orders.len())));
+    frame.render_widget(table, area);
+}
+
+fn draw_positions(frame: &mut Frame, area: Rect, snapshot: &PortfolioSnapshot) {
+    let rows = snapshot.positions.iter().map(|p| {
+        let pnl_style = if p.unrealized_pnl < 0.0 { Style::default().fg(Color::Red) } else { Style::default() };
+        Row::new(vec![
+            Cell::from(p.market_id.clone().unwrap_or_else(|| p.token_id.clone())),
+            Cell::from(format!("{:+.1}", p.size)),
+            Cell::from(format!("{:.3}", p.avg_price)),
+            Cell::from(p.mark_price.map(|m| format!("{m:.3}")).unwrap_or_else(|| "—".into())),
+            Cell::from(format!("{:+.2}", p.realized_pnl)),
+            Cell::from(format!("{:+.2}", p.unrealized_pnl)).style(pnl_style),
+        ])
+    });
+    let table = Table::new(rows, [
+        Constraint::Fill(1),
+        Constraint::Length(8),
+        Constraint::Length(7),
+        Constraint::Length(7),
+        Constraint::Length(9),
+        Constraint::Length(9),
+    ])
+    .header(Row::new(["position", "size", "avg", "mark", "realized", "unreal."]).bold())
+    .block(Block::bordered().title(format!(" positions ({}) ", snapshot.positions.len())));
+    frame.render_widget(table, area);
+}
@@ -0,0 +1,141 @@
+//! Catches the "silently dead" failure mode: every task still running, but
+//! nothing flowing. Each check raises an alert once when it starts failing and
+//! clears it once it recovers, via the notifier and `watchdog_alert_active`.
+
+use chrono::{Timelike, Utc};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::health::HealthRegistry;
+use crate::metrics::prometheus::{record_watchdog_alert, record_watchdog_alert_active};
+use crate::notify::{Notifier, NotifyEvent};
+use crate::state::market_cache::MarketCache;
+use crate::strategy::traits::TradeSignal;
+
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+    pub check_interval: Duration,
+    /// Alert when a venue has sent no market events for this long.
+    pub event_timeout: Duration,
+    /// Alert when the signal channel has been full for this long.
+    pub signal_full_after: Duration,
+    /// Alert when there has been no fill for this long inside the active window.
+    /// Disabled when `None`.
+    pub fill_timeout: Option<Duration>,
+    /// UTC hours `[start, end)` in which fills are expected; wraps past
+    /// midnight when `start > end`. All day when `None`.
+    pub active_hours: Option<(u32, u32)>,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(5),
+            event_timeout: Duration::from_secs(60),
+            signal_full_after: Duration::from_secs(30),
+            fill_timeout: None,
+            active_hours: None,
+        }
+    }
+}
+
+/// Alert state: which (check, venue) pairs are currently firing.
+struct Alerts {
+    firing: HashSet<(&'static str, String)>,
+    notifier: Notifier,
+}
+
+impl Alerts {
+    /// Re-assert firing gauges so the exporter's idle timeout doesn't drop them.
+    fn refresh(&self) {
+        for (check, venue) in &self.firing {
+            record_watchdog_alert_active(check, venue);
+        }
+    }
+
+    fn set(&mut self, check: &'static str, venue: &str, failing: bool, detail: String) {
+        let key = (check, venue.to_string());
+        let changed = if failing { self.firing.insert(key) } else { self.firing.remove(&key) };
+        if !changed {
+            return;
+        }
+
+        let label = if venue.is_empty() { check.to_string() } else { format!("{check}.{venue}") };
+        if failing {
+            warn!(check, venue, %detail, "watchdog alert");
+        } else {
+            info!(check, venue, %detail, "watchdog alert cleared");
+        }
+        record_watchdog_alert(check, venue, failing);
+        self.notifier.notify(NotifyEvent::Watchdog { check: label, firing: failing, detail });
+    }
+}
+
+/// Run the checks every `config.check_interval` until the process exits.
+///
+/// `signals` is only observed, never used to send: the watchdog must not keep
+/// the channel open on its own.
+pub async fn run_watchdog(
+    config: WatchdogConfig,
+    cache: MarketCache,
+    signals: mpsc::WeakSender<TradeSignal>,
+    health: HealthRegistry,
+    notifier: Notifier,
+) {
+    let mut alerts = Alerts { firing: HashSet::new(), notifier };
+    let mut ticker = tokio::time::interval(config.check_interval);
+    let mut full_since: Option<Instant> = None;
+    // Start of the current active window, or of the session if it began inside one.
+    let mut window_since: Option<u64> = None;
+
+    loop {
+        ticker.tick().await;
+        alerts.refresh();
+
+        // ── Market data per venue ────────────────────────────────────
+        for (venue, age) in cache.since_last_update_by_venue() {
+            alerts.set(
+                "no_market_data",
+                &format!("{venue:?}").to_lowercase(),
+                age >= config.event_timeout,
+                format!("no market events for {}s", age.as_secs()),
+            );
+        }
+
+        // ── Signal channel backpressure ──────────────────────────────
+        let full = signals.upgrade().is_some_and(|tx| tx.capacity() == 0);
+        full_since = if full { full_since.or(Some(Instant::now())) } else { None };
+        let full_for = full_since.map(|t| t.elapsed()).unwrap_or_default();
+        alerts.set(
+            "signal_channel_full",
+            "",
+            full_for >= config.signal_full_after,
+            format!("signal channel full for {}s", full_for.as_secs()),
+        );
+
+        // ── Fills in the active window ───────────────────────────────
+        let Some(fill_timeout) = config.fill_timeout else { continue };
+        let now = Utc::now();
+        let now_ms = now.timestamp_millis() as u64;
+        let active = config.active_hours.is_none_or(|(start, end)| in_hours(now.hour(), start, end));
+        if !active {
+            window_since = None;
+            alerts.set("no_fills", "", false, "outside the active window".to_string());
+            continue;
+        }
+        let since = *window_since.get_or_insert(now_ms);
+        let quiet_ms = now_ms.saturating_sub(health.last_fill_ms().unwrap_or(0).max(since));
+        alerts.set(
+            "no_fills",
+            "",
+            quiet_ms >= fill_timeout.as_millis() as u64,
+            format!("no fills for {}m in the active window", quiet_ms / 60_000),
+        );
+    }
+}
+
+fn in_hours(hour: u32, start: u32, end: u32) -> bool {
+    if start <= end { (start..end).contains(&hour) } else { hour >= start || hour < end }
+}
@@ -0,0 +1,210 @@
+//! HTTP endpoint for trade signals generated outside the engine: a research
+//! notebook, a TradingView-style alert, another team's model.
+//!
+//! `POST /signals` takes one signal as JSON:
+//!
+//! ```json
+//! {
+//!   "source": "notebook",
+//!   "venue": "Polymarket",
+//!   "market_id": "0xabc…",
+//!   "legs": [{ "token_id": "123…", "side": "Buy", "price": 0.42, "size": 25 }],
+//!   "edge": 0.03,
+//!   "liquidity": "taker"
+//! }
+//! ```
+//!
+//! Only `market_id` and `legs` are required. `source` must be one of the
+//! configured sources and names the signal's strategy, `webhook.<source>`;
+//! without it the strategy is `webhook`. `venue` defaults to Polymarket,
+//! `edge` to 0 and `liquidity` to `taker`.
+//!
+//! The shared token goes in an `Authorization: Bearer` header or, for
+//! senders that can't set headers, a `token` field in the body. The body is
+//! read as JSON whatever its content type, since alerting services often
+//! post text/plain.
+//!
+//! Accepted signals join the strategies' signals on the normal lane, so the
+//! execution bridge dedups, expires, risk-checks and journals them like any
+//! other. The response comes once the signal is queued: 202, or 400 for a
+//! malformed or invalid signal, 401 for a missing or wrong token, 503 when
+//! the execution bridge is down.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use metrics::counter;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::execution::operator::OperatorActions;
+use crate::market_data::types::{Side, Venue};
+use crate::state::fees::Liquidity;
+use crate::state::ids::{MarketId, TokenId};
+use crate::strategy::traits::{SignalLeg, TradeSignal};
+
+/// Strategy name on webhook signals that don't name a source.
+pub const WEBHOOK_STRATEGY: &str = "webhook";
+/// More legs than any strategy here emits; larger signals are rejected.
+const MAX_LEGS: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub addr: SocketAddr,
+    /// Shared secret every request must present.
+    pub token: String,
+    /// Names allowed in a signal's `source` field.
+    pub sources: Vec<String>,
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    token: Arc<str>,
+    /// Source name → strategy name, interned once at startup.
+    sources: Arc<HashMap<String, &'static str>>,
+    operator: OperatorActions,
+}
+
+/// One externally generated signal, as posted.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExternalSignal {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default = "default_venue")]
+    venue: Venue,
+    market_id: String,
+    legs: Vec<ExternalLeg>,
+    #[serde(default)]
+    edge: f64,
+    #[serde(default = "default_liquidity")]
+    liquidity: Liquidity,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExternalLeg {
+    token_id: String,
+    side: Side,
+    #[serde(with = "rust_decimal::serde::float")]
+    price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    size: Decimal,
+}
+
+fn default_venue() -> Venue {
+    Venue::Polymarket
+}
+
+fn default_liquidity() -> Liquidity {
+    Liquidity::Taker
+}
+
+/// Serve the signal webhook until the listener fails. Signals go through `operator`.
+pub async fn run_webhook_server(config: WebhookConfig, operator: OperatorActions) -> anyhow::Result<()> {
+    let sources = config
+        .sources
+        .iter()
+        .map(|source| {
+            let strategy: &'static str = Box::leak(format!("{WEBHOOK_STRATEGY}.{source}").into_boxed_str());
+            (source.clone(), strategy)
+        })
+        .collect();
+    let state = WebhookState { token: config.token.into(), sources: Arc::new(sources), operator };
+    let app = Router::new().route("/signals", post(post_signal)).with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    info!(addr = %config.addr, sources = ?config.sources, "signal webhook listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn post_signal(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let parsed = serde_json::from_slice::<ExternalSignal>(&body);
+    // Authenticate before saying anything about the body.
+    let presented = bearer.or_else(|| parsed.as_ref().ok().and_then(|signal| signal.token.as_deref()));
+    if !presented.is_some_and(|token| constant_time_eq(token.as_bytes(), state.token.as_bytes())) {
+        counter!("webhook_requests_total", "result" => "unauthorized").increment(1);
+        return Err((StatusCode::UNAUTHORIZED, "missing or invalid token".to_string()));
+    }
+    let signal = parsed
+        .map_err(|e| e.to_string())
+        .and_then(|signal| to_trade_signal(&state, signal).map_err(|e| e.to_string()))
+        .map_err(|e| {
+            counter!("webhook_requests_total", "result" => "invalid").increment(1);
+            warn!(error = %e, "webhook signal rejected");
+            (StatusCode::BAD_REQUEST, e)
+        })?;
+
+    info!(
+        strategy = signal.strategy_name,
+        venue = ?signal.venue,
+        market_id = %signal.market_id,
+        legs = signal.legs.len(),
+        edge = signal.edge,
+        "webhook signal received"
+    );
+    if let Err(e) = state.operator.submit_signal(signal).await {
+        counter!("webhook_requests_total", "result" => "unavailable").increment(1);
+        return Err((StatusCode::SERVICE_UNAVAILABLE, e.to_string()));
+    }
+    counter!("webhook_requests_total", "result" => "accepted").increment(1);
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Validate a posted signal and build the `TradeSignal` it stands for.
+fn to_trade_signal(state: &WebhookState, signal: ExternalSignal) -> anyhow::Result<TradeSignal> {
+    let strategy_name = match &signal.source {
+        None => WEBHOOK_STRATEGY,
+        Some(source) => {
+            *state.sources.get(source).ok_or_else(|| anyhow::anyhow!("source '{source}' is not configured"))?
+        }
+    };
+    anyhow::ensure!(!signal.legs.is_empty(), "signal has no legs");
+    anyhow::ensure!(signal.legs.len() <= MAX_LEGS, "signal has {} legs, at most {MAX_LEGS} allowed", signal.legs.len());
+    anyhow::ensure!(signal.edge.is_finite(), "edge must be a finite number");
+    for leg in &signal.legs {
+        state.operator.check_leg(&signal.market_id, &leg.token_id, leg.price, leg.size)?;
+    }
+    // Every id is tracked by now, so interning doesn't grow the tables.
+    let legs = signal
+        .legs
+        .into_iter()
+        .map(|leg| SignalLeg { token_id: TokenId::intern(&leg.token_id), side: leg.side, price: leg.price, size: leg.size })
+        .collect();
+    Ok(TradeSignal {
+        strategy_name,
+        venue: signal.venue,
+        market_id: MarketId::intern(&signal.market_id),
+        legs,
+        edge: signal.edge,
+        liquidity: signal.liquidity,
+        generated_at: Instant::now(),
+        ws_received_at: None,
+        stages: None,
+        reply: None,
+    })
+}
+
+/// Byte comparison that takes the same time wherever the inputs differ.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}